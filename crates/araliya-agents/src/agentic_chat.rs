@@ -20,8 +20,9 @@ use tokio::sync::oneshot;
 use araliya_core::bus::message::BusResult;
 use araliya_core::config::AgenticChatConfig;
 
+use super::chat::core::ChatCore;
 use super::core::agentic::{AgenticLoop, LocalTool};
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 #[cfg(feature = "idocstore")]
 use super::docs::DocsRagTool;
@@ -101,6 +102,15 @@ impl Agent for AgenticChatPlugin {
         "agentic-chat"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new(
+            "Plans tool calls in an instruction pass, then answers with their results.",
+        )
+        .with_sessions()
+        .with_tools()
+        .with_streaming()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -110,8 +120,18 @@ impl Agent for AgenticChatPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
+        if let Some(reply) = ChatCore::reject_empty_input(
+            &state,
+            self.id(),
+            &channel_id,
+            &content,
+            session_id.as_deref(),
+        ) {
+            let _ = reply_tx.send(reply);
+            return;
+        }
         let loop_ = self.build_loop(&state);
-        tokio::spawn(async move {
+        request::spawn(async move {
             let result = loop_.run(channel_id, content, session_id, state).await;
             let _ = reply_tx.send(result);
         });
@@ -125,8 +145,18 @@ impl Agent for AgenticChatPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
+        if let Some(reply) = ChatCore::reject_empty_input(
+            &state,
+            self.id(),
+            &channel_id,
+            &content,
+            session_id.as_deref(),
+        ) {
+            let _ = reply_tx.send(AgenticLoop::wrap_as_stream(reply));
+            return;
+        }
         let loop_ = self.build_loop(&state);
-        tokio::spawn(async move {
+        request::spawn(async move {
             let result = loop_
                 .run_stream(channel_id, content, session_id, state)
                 .await;
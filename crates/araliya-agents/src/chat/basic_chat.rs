@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use tokio::sync::oneshot;
 
-use super::super::{Agent, AgentsState};
+use super::super::{Agent, AgentCapabilities, AgentsState};
 use super::core::ChatCore;
 use araliya_core::bus::message::BusResult;
 
@@ -17,6 +17,10 @@ impl Agent for BasicChatPlugin {
         "basic_chat"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("One-shot LLM chat without history.")
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -26,9 +30,17 @@ impl Agent for BasicChatPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
+        if let Some(reply) =
+            ChatCore::reject_empty_input(&state, self.id(), &channel_id, &content, None)
+                .or_else(|| ChatCore::moderate_input(&state, &channel_id, &content, None))
+        {
+            let _ = reply_tx.send(reply);
+            return;
+        }
         // Spawn so the supervisor loop is not blocked on the LLM round-trip.
+        let agent_id = self.id().to_string();
         tokio::spawn(async move {
-            let result = ChatCore::basic_complete(&state, &channel_id, &content).await;
+            let result = ChatCore::basic_complete(&state, &agent_id, &channel_id, &content).await;
             let _ = reply_tx.send(result);
         });
     }
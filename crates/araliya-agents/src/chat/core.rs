@@ -4,10 +4,22 @@
 //! `BasicChatPlugin`, `SessionChatPlugin`, and future chat variants
 //! can share common behaviour without duplicating code.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use tracing::{debug, warn};
+
 use crate::AgentsState;
-use araliya_core::bus::message::BusResult;
+use crate::moderation::ModerationResult;
+use araliya_core::bus::message::{BusPayload, BusResult};
+use araliya_memory::MemorySystem;
+
+/// System prompt of the `[agents] auto_title` call.
+const TITLE_SYSTEM: &str = "Write a title of at most six words for the conversation below. \
+Reply with the title only: no quotes, no trailing punctuation.";
+
+/// Longest session title kept, in characters.
+const MAX_TITLE_CHARS: usize = 60;
 
 /// Reusable core for chat-family plugins.
 ///
@@ -17,14 +29,159 @@ use araliya_core::bus::message::BusResult;
 pub struct ChatCore;
 
 impl ChatCore {
+    /// Answer empty or whitespace-only input with the configured
+    /// `empty_input_reply` instead of spending an LLM call.  Returns `None`
+    /// when the input should be processed — it has content, or `agent_id`
+    /// opted out with `allow_empty_input`.
+    pub fn reject_empty_input(
+        state: &AgentsState,
+        agent_id: &str,
+        channel_id: &str,
+        content: &str,
+        session_id: Option<&str>,
+    ) -> Option<BusResult> {
+        if !content.trim().is_empty() || state.allow_empty_input.contains(agent_id) {
+            return None;
+        }
+        Some(Ok(BusPayload::CommsMessage {
+            channel_id: channel_id.to_string(),
+            content: state.empty_input_reply.clone(),
+            session_id: session_id.map(str::to_string),
+            usage: None,
+            timing: None,
+            thinking: None,
+        }))
+    }
+
+    /// Refuse input blocked by `[agents.moderation]` before it reaches the
+    /// LLM.  Returns `None` when moderation is off or the input passes.
+    pub fn moderate_input(
+        state: &AgentsState,
+        channel_id: &str,
+        content: &str,
+        session_id: Option<&str>,
+    ) -> Option<BusResult> {
+        let moderation = state.moderation.as_ref()?;
+        let ModerationResult::Blocked { reason } = moderation.moderator.check(content) else {
+            return None;
+        };
+        warn!(%channel_id, %reason, "chat input blocked by moderation");
+        Some(Ok(BusPayload::CommsMessage {
+            channel_id: channel_id.to_string(),
+            content: moderation.refusal.clone(),
+            session_id: session_id.map(str::to_string),
+            usage: None,
+            timing: None,
+            thinking: None,
+        }))
+    }
+
+    /// Replace a model reply blocked by `[agents.moderation]` with the
+    /// refusal.  Usage and timing are kept; the model's thinking is dropped
+    /// along with the reply.
+    pub fn moderate_output(state: &AgentsState, result: BusResult) -> BusResult {
+        let Some(moderation) = &state.moderation else {
+            return result;
+        };
+        match result {
+            Ok(BusPayload::CommsMessage {
+                channel_id,
+                content,
+                session_id,
+                usage,
+                timing,
+                thinking,
+            }) => {
+                let (content, thinking) = match moderation.moderator.check(&content) {
+                    ModerationResult::Allowed => (content, thinking),
+                    ModerationResult::Blocked { reason } => {
+                        warn!(%channel_id, %reason, "chat reply blocked by moderation");
+                        (moderation.refusal.clone(), None)
+                    }
+                };
+                Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content,
+                    session_id,
+                    usage,
+                    timing,
+                    thinking,
+                })
+            }
+            other => other,
+        }
+    }
+
+    /// Title a session from its first exchange (`[agents] auto_title`): ask
+    /// the instruction LLM in the background and store the answer on the
+    /// session's entry in `index_path`.  A failed call leaves it untitled.
+    pub fn spawn_session_title(
+        state: &Arc<AgentsState>,
+        channel_id: &str,
+        index_path: PathBuf,
+        session_id: String,
+        user: &str,
+        reply: &str,
+    ) {
+        let state = state.clone();
+        let channel_id = channel_id.to_string();
+        let exchange = format!("User: {user}\nAssistant: {reply}");
+        tokio::spawn(async move {
+            let title = match state
+                .complete_via_instruct_llm(&channel_id, &exchange, Some(TITLE_SYSTEM))
+                .await
+            {
+                Ok(BusPayload::CommsMessage { content, .. }) => clean_title(&content),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!(%session_id, "session title: llm call failed: {}", e.message);
+                    None
+                }
+            };
+            let Some(title) = title else {
+                return;
+            };
+            match MemorySystem::set_session_title_in(&index_path, &session_id, &title) {
+                Ok(()) => debug!(%session_id, %title, "session titled"),
+                Err(e) => warn!(%session_id, "session title: {e}"),
+            }
+        });
+    }
+
     /// Simple one-shot completion: forward content to the LLM and return the
-    /// result.  This is the primitive both `basic_chat` and `session_chat`
-    /// build on top of.
+    /// result, in `agent_id`'s configured response format and passed through
+    /// [`AgentsState::replace_empty_reply`] and
+    /// [`moderate_output`](Self::moderate_output).  This is the primitive both
+    /// `basic_chat` and `session_chat` build on top of.
     pub async fn basic_complete(
         state: &Arc<AgentsState>,
+        agent_id: &str,
         channel_id: &str,
         content: &str,
     ) -> BusResult {
-        state.complete_via_llm(channel_id, content).await
+        let result = state
+            .complete_via_llm_formatted(
+                channel_id,
+                content,
+                None,
+                state.response_format(agent_id),
+                None,
+            )
+            .await;
+        let result = state.replace_empty_reply(agent_id, result);
+        Self::moderate_output(state, result)
     }
 }
+
+/// The first line of a model's title answer, without wrapping quotes or
+/// markup and cut to [`MAX_TITLE_CHARS`]; `None` when nothing is left.
+fn clean_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line
+        .trim_matches(['#', '*', '"', '\'', '`', ' '])
+        .trim_end_matches('.')
+        .trim_end();
+    let title: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    let title = title.trim_end().to_string();
+    (!title.is_empty()).then_some(title)
+}
@@ -3,20 +3,23 @@
 //! Creates a new memory session on first message (ephemeral — one per bot run).
 //! Records user messages and assistant replies in the transcript, and injects
 //! recent conversation history into the LLM prompt for multi-turn context.
+//! With `[agents] auto_title`, the first turn also titles the session.
 
 use std::sync::Arc;
 
 use tokio::sync::{Mutex, oneshot};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
-use super::super::{Agent, AgentsState};
+use super::super::{Agent, AgentCapabilities, AgentsState};
 use super::core::ChatCore;
+use crate::context_budget::{estimate_tokens, history_line, last_turns};
 use crate::core::prompt::PromptBuilder;
 use araliya_core::bus::message::{BusPayload, BusResult};
 
 use araliya_memory::handle::SessionHandle;
 
-/// How many recent transcript entries to inject as conversation context.
+/// How many recent transcript entries to inject as conversation context,
+/// before the `context_turns` cap and trimming to the model's context window.
 const CONTEXT_WINDOW: usize = 20;
 
 pub(crate) struct SessionChatPlugin {
@@ -37,6 +40,11 @@ impl Agent for SessionChatPlugin {
         "chat"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("LLM chat that remembers the conversation per session.")
+            .with_sessions()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -46,6 +54,18 @@ impl Agent for SessionChatPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
+        if let Some(reply) = ChatCore::reject_empty_input(
+            &state,
+            self.id(),
+            &channel_id,
+            &content,
+            session_id.as_deref(),
+        )
+        .or_else(|| ChatCore::moderate_input(&state, &channel_id, &content, session_id.as_deref()))
+        {
+            let _ = reply_tx.send(reply);
+            return;
+        }
         let session = self.session.clone();
         tokio::spawn(async move {
             let result = handle_with_memory(
@@ -100,41 +120,33 @@ async fn handle_with_memory(
                 Err(e) => {
                     warn!("session_chat: failed to create session: {e}");
                     // Fall back to stateless completion.
-                    return ChatCore::basic_complete(state, channel_id, content).await;
+                    return ChatCore::basic_complete(state, "chat", channel_id, content).await;
                 }
             }
         }
         guard.clone().unwrap()
     };
 
+    if let Some(refusal) = state.daily_budget_refusal(&handle, channel_id).await {
+        return refusal;
+    }
+
     // Record user message.
     if let Err(e) = handle.transcript_append("user", content).await {
         warn!("session_chat: transcript_append(user) failed: {e}");
     }
 
-    // Build conversation context from recent transcript.
-    let context = match handle.transcript_read_last(CONTEXT_WINDOW).await {
-        Ok(entries) => {
-            let mut ctx = String::new();
-            for entry in &entries {
-                // Skip the just-appended user message (it's already the prompt).
-                if std::ptr::eq(&entries[entries.len() - 1], entry) {
-                    continue;
-                }
-                ctx.push_str(&format!("{}: {}\n", entry.role, entry.content));
-            }
-            ctx
-        }
-        Err(e) => {
-            warn!("session_chat: transcript_read_last failed: {e}");
-            String::new()
-        }
-    };
-
-    // Build system preamble (identity layers) and user message separately.
+    // Build system preamble (identity layers and the session's working
+    // memory) and user message separately.
     let chat_skills = state.agent_skills.get("chat").cloned().unwrap_or_default();
     let agents_dir = std::path::Path::new(&state.agents_dir);
-    let system = crate::core::prompt::preamble(&state.agents_dir, &chat_skills).build();
+    let memory = handle.working_memory_read().await.unwrap_or_else(|e| {
+        warn!("session_chat: working_memory_read failed: {e}");
+        String::new()
+    });
+    let system = crate::core::prompt::preamble(&state.agents_dir, &chat_skills)
+        .with_working_memory(&memory)
+        .build();
 
     let body = {
         let agent_path = agents_dir.join("chat").join("context.md");
@@ -142,32 +154,95 @@ async fn handle_with_memory(
             "Conversation history:\n{{history}}\nUser: {{user_input}}\nAI:".to_string()
         })
     };
+
+    // Build conversation context from recent transcript: at most
+    // `context_turns` turns, and as much as fits the context window of the
+    // provider the prompt resolves to, beside the rest of the prompt.
+    let history = handle.transcript_read_last(CONTEXT_WINDOW).await;
+    // No reply recorded yet (earlier turns may have failed): if this one
+    // succeeds it is the session's first turn.
+    let first_turn =
+        matches!(&history, Ok(entries) if entries.iter().all(|e| e.role != "assistant"));
+    let mut provider = None;
+    let context = match history {
+        Ok(entries) => {
+            // Skip the just-appended user message (it's already the prompt).
+            let earlier = &entries[..entries.len().saturating_sub(1)];
+            let earlier = match state.context_turns("chat") {
+                Some(turns) => last_turns(earlier, turns),
+                None => earlier,
+            };
+            let fixed =
+                estimate_tokens(&system) + estimate_tokens(&body) + estimate_tokens(content);
+            let (kept, resolved) = state.fit_history(earlier, fixed).await;
+            provider = resolved;
+            if kept.len() < earlier.len() {
+                debug!(
+                    dropped = earlier.len() - kept.len(),
+                    "session_chat: history trimmed to the context window"
+                );
+            }
+            kept.iter().map(history_line).collect()
+        }
+        Err(e) => {
+            warn!("session_chat: transcript_read_last failed: {e}");
+            String::new()
+        }
+    };
+
     let prompt = PromptBuilder::new(agents_dir.join("_shared"))
         .append(body)
         .var("history", &context)
         .var("user_input", content)
         .build();
 
-    // Get LLM completion with identity in system role.
+    // Get LLM completion with identity in system role; an empty or blocked
+    // reply is replaced before it reaches the transcript.
     let result = state
-        .complete_via_llm_with_system(channel_id, &prompt, Some(&system))
+        .complete_via_llm_formatted(
+            channel_id,
+            &prompt,
+            Some(&system),
+            state.response_format("chat"),
+            provider.as_deref(),
+        )
         .await;
+    let result = state.replace_empty_reply("chat", result);
+    let result = ChatCore::moderate_output(state, result);
 
     // Record assistant reply in transcript + accumulate token spend.
     if let Ok(BusPayload::CommsMessage {
         content: ref reply,
         ref usage,
+        ref timing,
         ..
     }) = result
     {
-        if let Err(e) = handle.transcript_append("assistant", reply).await {
+        let meta = state.reply_meta(usage.as_ref(), timing.as_ref());
+        if let Err(e) = handle
+            .transcript_append_with_meta("assistant", reply, meta)
+            .await
+        {
             warn!("session_chat: transcript_append(assistant) failed: {e}");
         }
         if let Some(u) = usage
-            && let Err(e) = handle.accumulate_spend(u, &state.llm_rates).await
+            && let Err(e) = state.record_spend(&handle, u).await
         {
             warn!("session_chat: accumulate_spend failed: {e}");
         }
+        if state.auto_title && first_turn {
+            match state.open_agent_store("chat") {
+                Ok(store) => ChatCore::spawn_session_title(
+                    state,
+                    channel_id,
+                    store.agent_sessions_index(),
+                    handle.session_id.clone(),
+                    content,
+                    reply,
+                ),
+                Err(e) => warn!("session_chat: cannot title session: {e}"),
+            }
+        }
     }
 
     match result {
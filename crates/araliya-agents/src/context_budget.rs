@@ -0,0 +1,170 @@
+//! Fitting conversation history into the active model's context window
+//! (`[llm.providers.<name>] context_window`).
+//!
+//! Chat agents read the last few transcript entries as history.  With a
+//! [`ContextBudget`] set, [`ContextBudget::fit_history`] keeps only as much
+//! of that history, newest first, as fits beside the rest of the prompt and
+//! the room reserved for the reply.  Token counts are estimated at four
+//! bytes per token, the same rule `[[llm.auto_route]]` uses.
+//!
+//! [`last_turns`] is the simpler cap behind `[agents.<id>] context_turns`:
+//! a fixed number of recent turns, whatever their size.
+
+use araliya_memory::store::TranscriptEntry;
+
+/// Tokens held back for the reply when the provider sets no `max_tokens`.
+pub const DEFAULT_REPLY_RESERVE: usize = 1_024;
+
+/// Token room for one request to the active model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextBudget {
+    /// Tokens per request, prompt and reply together.
+    pub context_window: usize,
+    /// Tokens kept free for the reply.
+    pub reply_reserve: usize,
+}
+
+impl ContextBudget {
+    /// Budget for a model with `context_window` tokens whose replies are
+    /// capped at `max_tokens` (0 = uncapped, reserve
+    /// [`DEFAULT_REPLY_RESERVE`]).  The reserve never takes more than half
+    /// the window.
+    pub fn new(context_window: usize, max_tokens: usize) -> Self {
+        let reserve = if max_tokens > 0 {
+            max_tokens
+        } else {
+            DEFAULT_REPLY_RESERVE
+        };
+        Self {
+            context_window,
+            reply_reserve: reserve.min(context_window / 2),
+        }
+    }
+
+    /// Tokens left for history once `fixed_tokens` of prompt (system
+    /// prompt, template, user input) and the reply reserve are set aside.
+    pub fn history_tokens(&self, fixed_tokens: usize) -> usize {
+        self.context_window
+            .saturating_sub(self.reply_reserve)
+            .saturating_sub(fixed_tokens)
+    }
+
+    /// The newest suffix of `entries` that fits beside `fixed_tokens` of
+    /// other prompt text.  Older entries are dropped whole.
+    pub fn fit_history<'a>(
+        &self,
+        entries: &'a [TranscriptEntry],
+        fixed_tokens: usize,
+    ) -> &'a [TranscriptEntry] {
+        let mut room = self.history_tokens(fixed_tokens);
+        let mut start = entries.len();
+        for entry in entries.iter().rev() {
+            let cost = estimate_tokens(&history_line(entry));
+            if cost > room {
+                break;
+            }
+            room -= cost;
+            start -= 1;
+        }
+        &entries[start..]
+    }
+}
+
+/// The suffix of `entries` holding the last `turns` turns.  A turn starts
+/// at a `user` entry and runs up to the next one, so each user message
+/// keeps its reply (and any `context` entry) with it.
+pub fn last_turns(entries: &[TranscriptEntry], turns: usize) -> &[TranscriptEntry] {
+    if turns == 0 {
+        return &[];
+    }
+    let mut seen = 0;
+    for (i, entry) in entries.iter().enumerate().rev() {
+        if entry.role == "user" {
+            seen += 1;
+            if seen == turns {
+                return &entries[i..];
+            }
+        }
+    }
+    entries
+}
+
+/// Estimated tokens for `text`: one per four bytes, rounded up.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// `entry` as it appears in a prompt's history block.
+pub fn history_line(entry: &TranscriptEntry) -> String {
+    format!("{}: {}\n", entry.role, entry.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: &str, content: &str) -> TranscriptEntry {
+        TranscriptEntry {
+            role: role.into(),
+            timestamp: "2026-10-16T00:00:00Z".into(),
+            content: content.into(),
+            tokens: None,
+            meta: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn reply_reserve_follows_max_tokens() {
+        assert_eq!(ContextBudget::new(8_192, 500).reply_reserve, 500);
+        assert_eq!(
+            ContextBudget::new(8_192, 0).reply_reserve,
+            DEFAULT_REPLY_RESERVE
+        );
+        assert_eq!(ContextBudget::new(1_000, 0).reply_reserve, 500);
+    }
+
+    #[test]
+    fn history_is_trimmed_oldest_first_at_the_window() {
+        // "user: " + 34 bytes + "\n" = 41 bytes = 11 tokens per line.
+        let line = "x".repeat(34);
+        let entries: Vec<_> = (0..6).map(|_| entry("user", &line)).collect();
+        assert_eq!(estimate_tokens(&history_line(&entries[0])), 11);
+
+        // 100 window - 20 reserve - 47 fixed = 33 tokens: exactly three lines.
+        let budget = ContextBudget::new(100, 20);
+        assert_eq!(budget.history_tokens(47), 33);
+        assert_eq!(budget.fit_history(&entries, 47).len(), 3);
+        // One more token of fixed prompt and the third line no longer fits.
+        assert_eq!(budget.fit_history(&entries, 48).len(), 2);
+        // A roomier window keeps everything.
+        assert_eq!(
+            ContextBudget::new(8_192, 0).fit_history(&entries, 47).len(),
+            6
+        );
+
+        let mixed = [entry("user", "old"), entry("assistant", &line)];
+        let kept = ContextBudget::new(100, 20).fit_history(&mixed, 69);
+        assert_eq!(kept.len(), 0);
+        let kept = ContextBudget::new(100, 20).fit_history(&mixed, 66);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].role, "assistant");
+    }
+
+    #[test]
+    fn last_turns_keeps_whole_recent_turns() {
+        let entries = [
+            entry("user", "one"),
+            entry("assistant", "1"),
+            entry("user", "two"),
+            entry("context", "retrieved"),
+            entry("assistant", "2"),
+            entry("user", "three"),
+            entry("assistant", "3"),
+        ];
+        let kept = last_turns(&entries, 2);
+        let contents: Vec<_> = kept.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, ["two", "retrieved", "2", "three", "3"]);
+        assert_eq!(last_turns(&entries, 10).len(), entries.len());
+        assert!(last_turns(&entries, 0).is_empty());
+    }
+}
@@ -3,7 +3,8 @@
 //! [`AgenticLoop`] is the composable building block for multi-pass agent plugins.
 //! Plugins create an `AgenticLoop`, register any in-process [`LocalTool`]s, and call
 //! [`AgenticLoop::run`] (buffered) or [`AgenticLoop::run_stream`] (SSE-streaming)
-//! from within `tokio::spawn`.
+//! from within `tokio::spawn`.  [`AgenticLoop::debug_prompt`] assembles the
+//! same prompts without calling the LLM (`agents/{id}/debug_prompt`).
 //!
 //! ## Local tools vs bus tools
 //!
@@ -25,13 +26,19 @@ use araliya_core::error::AppError;
 use araliya_core::obs::{ObsEvent, ObsLevel, ObservabilityHandle};
 use araliya_llm::StreamChunk;
 use araliya_memory::handle::SessionHandle;
+use araliya_memory::store::TranscriptEntry;
 
 use super::super::AgentsState;
 use super::prompt::{PromptBuilder, preamble};
+use crate::context_budget::{estimate_tokens, history_line, last_turns};
 
 /// How many recent transcript entries to inject as conversation context.
 const CONTEXT_WINDOW: usize = 20;
 
+/// Response-pass template used when the agent has no context prompt file.
+const FALLBACK_CONTEXT_TEMPLATE: &str =
+    "Context:\n{{context}}\n\nConversation history:\n{{history}}\nUser: {{user_input}}\nAI:";
+
 // ── LocalTool ─────────────────────────────────────────────────────────────────
 
 /// An in-process tool that runs synchronously inside `tokio::task::spawn_blocking`.
@@ -90,6 +97,8 @@ struct PreparedTurn {
     system: String,
     /// Fully-rendered response-pass prompt (context + history + user input).
     response_prompt: String,
+    /// Provider the history was sized for; the response pass runs there.
+    provider: Option<String>,
     debug_n: usize,
 }
 
@@ -114,6 +123,9 @@ enum TurnOutcome {
 /// 3. Tool execution (local tools first, then bus dispatch).
 /// 4. Response pass (main LLM with context + history).
 /// 5. Transcript and spend persistence.
+///
+/// With `[agents] emit_steps` on, the instruction pass, each tool call and
+/// the response pass are announced on `comms/agent_step` as they start.
 pub(crate) struct AgenticLoop {
     agent_id: String,
     use_instruction_llm: bool,
@@ -184,12 +196,15 @@ impl AgenticLoop {
 
         // ── Response pass (buffered) ────────────────────────────────
         let result = state
-            .complete_via_llm_with_system(
+            .complete_via_llm_formatted(
                 &turn.channel_id,
                 &turn.response_prompt,
                 Some(&turn.system),
+                state.response_format(&self.agent_id),
+                turn.provider.as_deref(),
             )
             .await;
+        let result = state.replace_empty_reply(&self.agent_id, result);
 
         // ── Persist reply + spend ───────────────────────────────────
         if let Ok(BusPayload::CommsMessage {
@@ -199,14 +214,19 @@ impl AgenticLoop {
             ..
         }) = result
         {
-            if let Err(e) = turn.handle.transcript_append("assistant", reply).await {
+            let meta = state.reply_meta(usage.as_ref(), timing.as_ref());
+            if let Err(e) = turn
+                .handle
+                .transcript_append_with_meta("assistant", reply, meta)
+                .await
+            {
                 warn!(
                     "{}: transcript_append(assistant) failed: {e}",
                     self.agent_id
                 );
             }
             if let Some(u) = usage {
-                if let Err(e) = turn.handle.accumulate_spend(u, &state.llm_rates).await {
+                if let Err(e) = state.record_spend(&turn.handle, u).await {
                     warn!("{}: accumulate_spend failed: {e}", self.agent_id);
                 }
                 if let Some(obs) = &state.obs {
@@ -281,11 +301,16 @@ impl AgenticLoop {
         };
 
         // ── Streaming response pass ─────────────────────────────────
-        let llm_rx = match state
-            .stream_via_llm_with_system(&turn.channel_id, &turn.response_prompt, Some(&turn.system))
+        let (llm_rx, request_id) = match state
+            .stream_via_llm_with_system(
+                &turn.channel_id,
+                &turn.response_prompt,
+                Some(&turn.system),
+                turn.provider.as_deref(),
+            )
             .await
         {
-            Ok(rx) => rx,
+            Ok(stream) => stream,
             Err(e) => return Err(e),
         };
 
@@ -293,7 +318,6 @@ impl AgenticLoop {
         let (fwd_tx, fwd_rx) = mpsc::channel::<StreamChunk>(64);
 
         let agent_id = self.agent_id.clone();
-        let llm_rates = state.llm_rates.clone();
         let obs = state.obs.clone();
         let debug_logging = self.debug_logging;
         let debug_n = turn.debug_n;
@@ -305,7 +329,7 @@ impl AgenticLoop {
                 fwd_tx,
                 handle,
                 agent_id,
-                llm_rates,
+                state,
                 obs,
                 debug_logging,
                 debug_n,
@@ -315,6 +339,7 @@ impl AgenticLoop {
 
         Ok(BusPayload::LlmStreamResult {
             rx: StreamReceiver(fwd_rx),
+            request_id,
         })
     }
 
@@ -343,6 +368,10 @@ impl AgenticLoop {
             }
         };
 
+        if let Some(refusal) = state.daily_budget_refusal(&handle, &channel_id).await {
+            return TurnOutcome::EarlyReply(refusal);
+        }
+
         if let Some(obs) = &state.obs {
             obs.emit(
                 ObsEvent::now(
@@ -389,19 +418,13 @@ impl AgenticLoop {
         };
 
         // ── 2. History ────────────────────────────────────────────────
-        let history = self.read_history(&handle).await;
+        let (history, provider) = self
+            .read_history(&handle, state, estimate_tokens(&content))
+            .await;
 
         // ── 3. Instruction pass ───────────────────────────────────────
-        let tool_manifest = self.build_tool_manifest(&self.allowed_tools);
-        let memory_manifest = self.build_memory_manifest();
-
-        let agents_path = std::path::Path::new(&self.agents_dir);
-        let instruct_prompt = PromptBuilder::new(agents_path.join("_shared"))
-            .agent_layer(agents_path, &self.agent_id, &self.instruct_prompt_file)
-            .var("tools", &tool_manifest)
-            .var("memory", &memory_manifest)
-            .var("user_input", &content)
-            .build();
+        state.emit_step(&channel_id, &handle.session_id, "planning");
+        let instruct_prompt = self.instruct_prompt(&content);
 
         if self.debug_logging {
             if let Err(e) = handle
@@ -502,6 +525,11 @@ impl AgenticLoop {
         for call in tool_calls {
             let tool_name = call.tool.clone();
             let action_name = call.action.clone();
+            state.emit_step(
+                &channel_id,
+                &handle.session_id,
+                format!("calling tool {tool_name}/{action_name}"),
+            );
 
             if let Some(obs) = &state.obs {
                 obs.emit(
@@ -567,6 +595,7 @@ impl AgenticLoop {
             let params_json = call.params.to_string();
             match state
                 .execute_tool(
+                    &self.agent_id,
                     &tool_name,
                     &action_name,
                     params_json,
@@ -618,6 +647,14 @@ impl AgenticLoop {
         }
         let context = context_parts.join("\n\n---\n\n");
 
+        // Retrieved context joins the transcript only for agents that opt in
+        // (`persist_context`); RAG agents otherwise keep it out to stay small.
+        if !context.is_empty() && state.persist_context.contains(&self.agent_id) {
+            if let Err(e) = handle.transcript_append("context", &context).await {
+                warn!("{}: transcript_append(context) failed: {e}", self.agent_id);
+            }
+        }
+
         if self.debug_logging {
             let outputs_json = serde_json::to_string(&debug_tool_outputs).unwrap_or_default();
             if let Err(e) = handle
@@ -638,20 +675,11 @@ impl AgenticLoop {
         }
 
         // ── Build response-pass prompt ────────────────────────────────
-        let system = preamble(&self.agents_dir, &self.allowed_tools).build();
-
-        let context_ref = if context.is_empty() {
-            "(no context retrieved)"
-        } else {
-            &context
-        };
-
-        let response_prompt = PromptBuilder::new(agents_path.join("_shared"))
-            .agent_layer(agents_path, &self.agent_id, &self.context_prompt_file)
-            .var("context", context_ref)
-            .var("history", &history)
-            .var("user_input", &content)
-            .build();
+        let memory = handle.working_memory_read().await.unwrap_or_else(|e| {
+            warn!("{}: working_memory_read failed: {e}", self.agent_id);
+            String::new()
+        });
+        let (system, response_prompt) = self.response_prompt(&memory, &context, &history, &content);
 
         if self.debug_logging {
             if let Err(e) = handle
@@ -665,20 +693,143 @@ impl AgenticLoop {
             }
         }
 
+        state.emit_step(&channel_id, &handle.session_id, "writing the reply");
         TurnOutcome::Ready(PreparedTurn {
             handle,
             channel_id,
             system,
             response_prompt,
+            provider,
             debug_n,
         })
     }
 
+    /// Assemble the prompts a turn on `content` would send, without any LLM
+    /// call, as a `JsonResponse` of `instruct_prompt`, `context`, `system`
+    /// and `prompt` (`agents/{id}/debug_prompt`).
+    ///
+    /// The instruction pass is not run, so every memory tool is queried with
+    /// the raw input in its place; action and bus tools are left out.  With a
+    /// `session_id`, its transcript is read as history (the session is not
+    /// marked used); nothing is written anywhere.
+    pub(crate) async fn debug_prompt(
+        &self,
+        content: String,
+        session_id: Option<String>,
+        state: Arc<AgentsState>,
+    ) -> BusResult {
+        let instruct_prompt = self.instruct_prompt(&content);
+
+        let mut context_parts = Vec::new();
+        for tool in &self.memory_tools {
+            let tool = Arc::clone(tool);
+            let params = serde_json::json!({ "query": &content });
+            match tokio::task::spawn_blocking(move || tool.call(&params)).await {
+                Ok(Ok(output)) => context_parts.push(output),
+                Ok(Err(e)) => warn!("{}: debug_prompt: local tool error: {e}", self.agent_id),
+                Err(e) => warn!("{}: debug_prompt: local tool panic: {e}", self.agent_id),
+            }
+        }
+        let context = context_parts.join("\n\n---\n\n");
+
+        let (history, memory) = match session_id.as_deref() {
+            None => (String::new(), String::new()),
+            Some(sid) => {
+                let store = state
+                    .open_agent_store(&self.agent_id)
+                    .map_err(|e| BusError::new(-32000, format!("session error: {e}")))?;
+                let handle = state
+                    .memory
+                    .peek_session_in(
+                        &store.agent_sessions_dir(),
+                        &store.agent_sessions_index(),
+                        sid,
+                    )
+                    .map_err(|e| BusError::new(-32000, format!("session error: {e}")))?;
+                let history = match handle.transcript_read_last(CONTEXT_WINDOW).await {
+                    Ok(entries) => {
+                        self.history_from(&entries, &state, estimate_tokens(&content))
+                            .await
+                            .0
+                    }
+                    Err(e) => {
+                        warn!("{}: transcript_read_last failed: {e}", self.agent_id);
+                        String::new()
+                    }
+                };
+                (
+                    history,
+                    handle.working_memory_read().await.unwrap_or_default(),
+                )
+            }
+        };
+
+        let (system, prompt) = self.response_prompt(&memory, &context, &history, &content);
+        Ok(BusPayload::JsonResponse {
+            data: serde_json::json!({
+                "agent_id": &self.agent_id,
+                "instruct_prompt": instruct_prompt,
+                "context": context,
+                "system": system,
+                "prompt": prompt,
+            })
+            .to_string(),
+        })
+    }
+
     // ── Private helpers ───────────────────────────────────────────────────────
 
+    /// The instruction-pass prompt for `content`.
+    fn instruct_prompt(&self, content: &str) -> String {
+        let tool_manifest = self.build_tool_manifest(&self.allowed_tools);
+        let memory_manifest = self.build_memory_manifest();
+        let agents_path = std::path::Path::new(&self.agents_dir);
+        PromptBuilder::new(agents_path.join("_shared"))
+            .agent_layer(agents_path, &self.agent_id, &self.instruct_prompt_file)
+            .var("tools", &tool_manifest)
+            .var("memory", &memory_manifest)
+            .var("user_input", content)
+            .build()
+    }
+
+    /// System preamble (with the session's working `memory`) and prompt of
+    /// the response pass.  Without the agent's context prompt file,
+    /// [`FALLBACK_CONTEXT_TEMPLATE`] is used.
+    fn response_prompt(
+        &self,
+        memory: &str,
+        context: &str,
+        history: &str,
+        content: &str,
+    ) -> (String, String) {
+        let system = preamble(&self.agents_dir, &self.allowed_tools)
+            .with_working_memory(memory)
+            .build();
+        let context = if context.is_empty() {
+            "(no context retrieved)"
+        } else {
+            context
+        };
+        let agents_path = std::path::Path::new(&self.agents_dir);
+        let mut builder = PromptBuilder::new(agents_path.join("_shared")).agent_layer(
+            agents_path,
+            &self.agent_id,
+            &self.context_prompt_file,
+        );
+        if builder.is_empty() {
+            builder = builder.append(FALLBACK_CONTEXT_TEMPLATE);
+        }
+        let prompt = builder
+            .var("context", context)
+            .var("history", history)
+            .var("user_input", content)
+            .build();
+        (system, prompt)
+    }
+
     /// Wrap a buffered `BusResult` into a synthetic `LlmStreamResult` so
     /// streaming callers always get a uniform return type.
-    fn wrap_as_stream(result: BusResult) -> BusResult {
+    pub(crate) fn wrap_as_stream(result: BusResult) -> BusResult {
         match result {
             Ok(BusPayload::CommsMessage { content, .. }) => {
                 let (tx, rx) = mpsc::channel::<StreamChunk>(2);
@@ -688,11 +839,14 @@ impl AgenticLoop {
                         .send(StreamChunk::Done {
                             usage: None,
                             timing: None,
+                            error: None,
+                            stopped: false,
                         })
                         .await;
                 });
                 Ok(BusPayload::LlmStreamResult {
                     rx: StreamReceiver(rx),
+                    request_id: None,
                 })
             }
             Err(e) => Err(e),
@@ -735,22 +889,44 @@ impl AgenticLoop {
         )
     }
 
-    async fn read_history(&self, handle: &SessionHandle) -> String {
+    /// The last [`CONTEXT_WINDOW`] transcript entries, minus the
+    /// just-recorded user input, as [`Self::history_from`] renders them.
+    async fn read_history(
+        &self,
+        handle: &SessionHandle,
+        state: &AgentsState,
+        fixed_tokens: usize,
+    ) -> (String, Option<String>) {
         match handle.transcript_read_last(CONTEXT_WINDOW).await {
             Ok(entries) => {
-                let mut h = String::new();
-                for entry in entries.iter().rev().skip(1).rev() {
-                    h.push_str(&format!("{}: {}\n", entry.role, entry.content));
-                }
-                h
+                let earlier = &entries[..entries.len().saturating_sub(1)];
+                self.history_from(earlier, state, fixed_tokens).await
             }
             Err(e) => {
                 warn!("{}: transcript_read_last failed: {e}", self.agent_id);
-                String::new()
+                (String::new(), None)
             }
         }
     }
 
+    /// `entries` as prompt history, capped at `[agents.<id>] context_turns`
+    /// turns and trimmed beside `fixed_tokens` of other prompt text to the
+    /// provider the prompt resolves to, which is returned (see
+    /// [`AgentsState::fit_history`]).
+    async fn history_from(
+        &self,
+        entries: &[TranscriptEntry],
+        state: &AgentsState,
+        fixed_tokens: usize,
+    ) -> (String, Option<String>) {
+        let entries = match state.context_turns(&self.agent_id) {
+            Some(turns) => last_turns(entries, turns),
+            None => entries,
+        };
+        let (kept, provider) = state.fit_history(entries, fixed_tokens).await;
+        (kept.iter().map(history_line).collect(), provider)
+    }
+
     fn build_memory_manifest(&self) -> String {
         if self.memory_tools.is_empty() {
             return "No memory available.".to_string();
@@ -787,6 +963,15 @@ impl AgenticLoop {
                      \x20 Description: Fetches recent news email summaries."
                         .to_string(),
                 ),
+                "memory" => lines.push(
+                    "- tool: \"memory\", action: \"search\", params: {\"query\": \"<words>\"}\n\
+                     \x20 Description: Searches your past conversations.\n\
+                     - tool: \"memory\", action: \"list_sessions\", params: {}\n\
+                     \x20 Description: Lists your past conversations, most recent first.\n\
+                     - tool: \"memory\", action: \"get_session\", params: {\"session_id\": \"<id>\"}\n\
+                     \x20 Description: Reads one past conversation."
+                        .to_string(),
+                ),
                 other => lines.push(format!(
                     "- tool: \"{other}\", action: \"<action>\", params: {{}}"
                 )),
@@ -805,7 +990,8 @@ impl AgenticLoop {
 
 /// Forward chunks from the LLM stream to the browser while buffering the full
 /// response text.  On [`StreamChunk::Done`], persist the transcript and
-/// accumulate spend.
+/// accumulate spend.  A stream that failed part-way still persists what
+/// arrived, with the error in the entry's `meta`.
 ///
 /// If the browser disconnects (`fwd_tx` send fails), the task continues
 /// draining the LLM stream to ensure the full response is still persisted.
@@ -815,7 +1001,7 @@ async fn tee_and_persist(
     fwd_tx: mpsc::Sender<StreamChunk>,
     handle: SessionHandle,
     agent_id: String,
-    llm_rates: araliya_llm::ModelRates,
+    state: Arc<AgentsState>,
     obs: Option<ObservabilityHandle>,
     debug_logging: bool,
     debug_n: usize,
@@ -827,16 +1013,32 @@ async fn tee_and_persist(
         match &chunk {
             StreamChunk::Content(delta) => content_buf.push_str(delta),
             StreamChunk::Thinking(_) => { /* forward only */ }
-            StreamChunk::Done { usage, timing } => {
-                // Persist transcript.
+            StreamChunk::Done {
+                usage,
+                timing,
+                error,
+                stopped,
+            } => {
+                // Persist transcript, partial if the stream failed or was
+                // stopped.
                 if !content_buf.is_empty() {
-                    if let Err(e) = handle.transcript_append("assistant", &content_buf).await {
+                    let mut meta = state.reply_meta(usage.as_ref(), timing.as_ref());
+                    if let Some(error) = error {
+                        meta["error"] = error.clone().into();
+                    }
+                    if *stopped {
+                        meta["stopped"] = true.into();
+                    }
+                    if let Err(e) = handle
+                        .transcript_append_with_meta("assistant", &content_buf, meta)
+                        .await
+                    {
                         warn!("{agent_id}: transcript_append(assistant) failed: {e}");
                     }
                 }
                 // Accumulate spend and emit observability event.
                 if let Some(u) = usage {
-                    if let Err(e) = handle.accumulate_spend(u, &llm_rates).await {
+                    if let Err(e) = state.record_spend(&handle, u).await {
                         warn!("{agent_id}: accumulate_spend failed: {e}");
                     }
                     if let Some(ref obs) = obs {
@@ -980,6 +1182,278 @@ fn parse_instruction_response(text: &str) -> InstructionResponse {
 mod tests {
     use super::*;
 
+    // ── persist_context transcript policy ─────────────────────────────
+
+    struct FakeSearch;
+
+    impl LocalTool for FakeSearch {
+        fn name(&self) -> &str {
+            "fake_search"
+        }
+
+        fn description(&self) -> &str {
+            "returns a fixed passage"
+        }
+
+        fn call(&self, _params: &serde_json::Value) -> Result<String, String> {
+            Ok("RETRIEVED PASSAGE".to_string())
+        }
+    }
+
+    /// Run one turn of a loop with a fake retrieval tool and return the
+    /// transcript roles and contents it left behind.
+    async fn transcript_after_turn(persist_context: bool) -> Vec<(String, String)> {
+        use araliya_core::bus::handle::SupervisorBus;
+        use araliya_core::bus::message::BusMessage;
+        use araliya_core::config::AgentsConfig;
+        use std::collections::HashSet;
+
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        // Instruction pass asks for the tool; the response pass answers.
+        tokio::spawn(async move {
+            let mut calls = 0;
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = rx.recv().await
+            {
+                if let BusPayload::LlmRequest { channel_id, .. } = payload {
+                    calls += 1;
+                    let content = if calls == 1 {
+                        r#"{"tools":[{"tool":"fake_search","action":"search","params":{}}],"reply":null}"#
+                    } else {
+                        "final answer"
+                    };
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: content.to_string(),
+                        session_id: None,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory = Arc::new(
+            araliya_memory::MemorySystem::new(dir.path(), araliya_memory::MemoryConfig::default())
+                .unwrap(),
+        );
+        let mut cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        if persist_context {
+            cfg.persist_context.insert("echo".to_string());
+        }
+        let agents = crate::AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let state = agents.state.clone();
+
+        let loop_ = AgenticLoop::new(
+            "echo",
+            false,
+            "instruct.md",
+            "context.md",
+            vec![],
+            vec![Arc::new(FakeSearch)],
+            vec![],
+            "/nonexistent/agents",
+            false,
+        );
+        let reply = loop_
+            .run(
+                "pty0".to_string(),
+                "what is it?".to_string(),
+                None,
+                state.clone(),
+            )
+            .await;
+        let session_id = match reply {
+            Ok(BusPayload::CommsMessage {
+                content,
+                session_id,
+                ..
+            }) => {
+                assert_eq!(content, "final answer");
+                session_id.unwrap()
+            }
+            other => panic!("unexpected reply: {other:?}"),
+        };
+
+        let handle = state.open_agent_session("echo", Some(&session_id)).unwrap();
+        handle
+            .transcript_read_last(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.role, e.content))
+            .collect()
+    }
+
+    // ── Response-pass prompt ──────────────────────────────────────────
+
+    fn prompt_loop(agents_dir: &str) -> AgenticLoop {
+        AgenticLoop::new(
+            "echo",
+            false,
+            "instruct.md",
+            "context.md",
+            vec![],
+            vec![],
+            vec![],
+            agents_dir,
+            false,
+        )
+    }
+
+    #[test]
+    fn response_prompt_falls_back_to_a_minimal_template_without_a_context_file() {
+        let (_, prompt) =
+            prompt_loop("/nonexistent/agents").response_prompt("", "", "User: hi\n", "and now?");
+        assert_eq!(
+            prompt,
+            "Context:\n(no context retrieved)\n\nConversation history:\nUser: hi\n\n\
+             User: and now?\nAI:"
+        );
+    }
+
+    #[test]
+    fn response_prompt_uses_the_agent_context_file_when_present() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("echo")).unwrap();
+        std::fs::write(
+            dir.path().join("echo").join("context.md"),
+            "Known: {{context}}\nAsked: {{user_input}}",
+        )
+        .unwrap();
+        let (_, prompt) =
+            prompt_loop(dir.path().to_str().unwrap()).response_prompt("", "RETRIEVED", "", "hello");
+        assert_eq!(prompt, "Known: RETRIEVED\nAsked: hello");
+    }
+
+    #[tokio::test]
+    async fn emit_steps_announces_each_phase_before_the_reply() {
+        use araliya_core::bus::handle::SupervisorBus;
+        use araliya_core::bus::message::BusMessage;
+        use araliya_core::config::AgentsConfig;
+        use std::collections::HashSet;
+
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        // Everything the loop puts on the bus, in order: steps and LLM calls.
+        let (log_tx, mut log_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            let mut calls = 0;
+            while let Some(message) = rx.recv().await {
+                match message {
+                    BusMessage::Notification {
+                        method,
+                        payload:
+                            BusPayload::AgentStep {
+                                channel_id, step, ..
+                            },
+                    } => {
+                        let _ = log_tx.send(format!("{method} {channel_id}: {step}"));
+                    }
+                    BusMessage::Request {
+                        payload: BusPayload::LlmRequest { channel_id, .. },
+                        reply_tx,
+                        ..
+                    } => {
+                        calls += 1;
+                        let _ = log_tx.send(format!("llm #{calls}"));
+                        let content = if calls == 1 {
+                            r#"{"tools":[{"tool":"fake_search","action":"search","params":{}}],"reply":null}"#
+                        } else {
+                            "final answer"
+                        };
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id,
+                            content: content.to_string(),
+                            session_id: None,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory = Arc::new(
+            araliya_memory::MemorySystem::new(dir.path(), araliya_memory::MemoryConfig::default())
+                .unwrap(),
+        );
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            emit_steps: true,
+            ..AgentsConfig::default()
+        };
+        let agents = crate::AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let loop_ = AgenticLoop::new(
+            "echo",
+            false,
+            "instruct.md",
+            "context.md",
+            vec![],
+            vec![Arc::new(FakeSearch)],
+            vec![],
+            "/nonexistent/agents",
+            false,
+        );
+        let reply = loop_
+            .run(
+                "pty0".to_string(),
+                "what is it?".to_string(),
+                None,
+                agents.state.clone(),
+            )
+            .await;
+        assert!(matches!(
+            reply,
+            Ok(BusPayload::CommsMessage { ref content, .. }) if content == "final answer"
+        ));
+
+        let mut log = Vec::new();
+        while let Ok(entry) = log_rx.try_recv() {
+            log.push(entry);
+        }
+        assert_eq!(
+            log,
+            [
+                "comms/agent_step pty0: planning",
+                "llm #1",
+                "comms/agent_step pty0: calling tool fake_search/search",
+                "comms/agent_step pty0: writing the reply",
+                "llm #2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn context_is_left_out_of_transcript_by_default() {
+        let entries = transcript_after_turn(false).await;
+        let roles: Vec<&str> = entries.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant"]);
+        assert!(!entries.iter().any(|(_, c)| c.contains("RETRIEVED PASSAGE")));
+    }
+
+    #[tokio::test]
+    async fn persist_context_writes_context_to_transcript() {
+        let entries = transcript_after_turn(true).await;
+        let roles: Vec<&str> = entries.iter().map(|(r, _)| r.as_str()).collect();
+        assert_eq!(roles, ["user", "context", "assistant"]);
+        assert_eq!(entries[1].1, "RETRIEVED PASSAGE");
+    }
+
     // ── parse_instruction_response: new object format ─────────────────
 
     #[test]
@@ -152,6 +152,12 @@ impl PromptBuilder {
         self
     }
 
+    /// `true` while no layer or fragment has been added (or every file was
+    /// missing or blank).
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
     /// Directly append a text fragment (e.g. an already-loaded template body).
     pub fn append(mut self, text: impl Into<String>) -> Self {
         let s = text.into();
@@ -162,6 +168,15 @@ impl PromptBuilder {
         self
     }
 
+    /// Append the session's working memory (seeded from `[agents.<id>]
+    /// seed_memory`) under a heading; blank memory adds nothing.
+    pub fn with_working_memory(self, memory: &str) -> Self {
+        if memory.trim().is_empty() {
+            return self;
+        }
+        self.append(format!("## Working memory\n\n{}", memory.trim()))
+    }
+
     /// Register `{{key}}` → `value` substitution pairs applied at build time.
     #[allow(dead_code)]
     pub fn with_vars<'a, I>(mut self, vars: I) -> Self
@@ -212,6 +227,16 @@ pub fn subagent_preamble(agents_dir: impl AsRef<Path>, tools: &[String]) -> Prom
 mod tests {
     use super::*;
 
+    #[test]
+    fn working_memory_is_appended_only_when_present() {
+        let prompt = PromptBuilder::new("/nonexistent")
+            .append("You are a bot.")
+            .with_working_memory("  - tz: UTC\n")
+            .with_working_memory(" \n")
+            .build();
+        assert_eq!(prompt, "You are a bot.\n\n## Working memory\n\n- tz: UTC");
+    }
+
     fn agents_dir() -> std::path::PathBuf {
         std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/agents")
     }
@@ -0,0 +1,99 @@
+//! Agent chain depth guard (`[agents] max_depth`).
+//!
+//! Every request to the agents subsystem runs on an [`AgentChain`]: a new
+//! one, or the chain a handoff carries on the bus (see [`request`]).  Every
+//! handoff ([`AgentsState::dispatch_to_agent`]) and every tool call
+//! ([`AgentsState::execute_tool`]) made while serving it is a hop on that
+//! chain, and hops are never given back: an agentic loop that runs tool after
+//! tool, or A handing off to B which hands back to A, keeps growing it.  The
+//! hop that would take it past `max_depth` fails with [`ERR_MAX_DEPTH`] and
+//! the chain is logged, instead of a loop running until the budget is gone.
+//! A hop made outside any request counts alone.
+//!
+//! [`request`]: crate::request
+//! [`AgentsState::dispatch_to_agent`]: crate::AgentsState::dispatch_to_agent
+//! [`AgentsState::execute_tool`]: crate::AgentsState::execute_tool
+
+use std::sync::PoisonError;
+
+use araliya_core::bus::message::{AgentChain, BusError};
+use tracing::{debug, warn};
+
+use crate::request;
+
+/// Error code for a hop past `[agents] max_depth`.
+pub const ERR_MAX_DEPTH: i32 = -32006;
+
+/// The `[agents] max_depth` limit applied to hops.
+#[derive(Debug)]
+pub struct AgentChains {
+    max_depth: usize,
+}
+
+impl AgentChains {
+    pub fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+
+    /// Add `hop` to the current request's chain, or fail with
+    /// [`ERR_MAX_DEPTH`] when the chain is full.  Returns the chain with
+    /// `hop` on it, for a handoff to carry on.
+    pub fn enter(&self, channel_id: &str, hop: &str) -> Result<AgentChain, BusError> {
+        let Some(request) = request::current() else {
+            debug!(%channel_id, %hop, "hop outside any agent request");
+            let mut chain = AgentChain::start();
+            chain.hops.push(hop.to_string());
+            return Ok(chain);
+        };
+        let mut chain = request.chain.lock().unwrap_or_else(PoisonError::into_inner);
+        if chain.hops.len() >= self.max_depth {
+            warn!(
+                %channel_id,
+                chain_id = %chain.id,
+                max_depth = self.max_depth,
+                chain = %format!("{} -> {hop}", chain.hops.join(" -> ")),
+                "max agent depth exceeded"
+            );
+            return Err(BusError::new(
+                ERR_MAX_DEPTH,
+                format!("max agent depth exceeded (max_depth = {})", self.max_depth),
+            ));
+        }
+        chain.hops.push(hop.to_string());
+        Ok(chain.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sequential_hops_count_against_the_chain() {
+        let chains = AgentChains::new(2);
+        let task = request::scope(AgentChain::start(), None, || {
+            request::spawn(async move {
+                let first = chains.enter("pty0", "web/search").unwrap();
+                let second = chains.enter("pty0", "web/fetch").unwrap();
+                assert_eq!(first.id, second.id);
+                assert_eq!(second.hops, ["web/search", "web/fetch"]);
+                chains.enter("pty0", "web/search").unwrap_err().code
+            })
+        });
+        assert_eq!(task.await.unwrap(), ERR_MAX_DEPTH);
+    }
+
+    #[tokio::test]
+    async fn separate_requests_have_separate_chains() {
+        let chains = Arc::new(AgentChains::new(1));
+        for _ in 0..2 {
+            let chains = chains.clone();
+            let task = request::scope(AgentChain::start(), None, || {
+                request::spawn(async move { chains.enter("pty0", "a").is_ok() })
+            });
+            assert!(task.await.unwrap());
+        }
+    }
+}
@@ -20,7 +20,7 @@ use tokio::sync::oneshot;
 use tracing::warn;
 
 use super::core::agentic::{AgenticLoop, LocalTool};
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 use araliya_core::bus::message::{BusError, BusPayload, BusResult, ERR_METHOD_NOT_FOUND};
 use araliya_core::config::DocsKgConfig;
 use araliya_memory::stores::docstore::IDocStore;
@@ -233,6 +233,13 @@ impl Agent for DocsAgentPlugin {
         "docs"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Answers questions about the project documentation.")
+            .with_sessions()
+            .with_tools()
+            .with_streaming()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -242,7 +249,7 @@ impl Agent for DocsAgentPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             // Health ping — no LLM call needed.
             if action == "health" {
                 let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
@@ -272,6 +279,27 @@ impl Agent for DocsAgentPlugin {
         });
     }
 
+    fn debug_prompt(
+        &self,
+        content: String,
+        session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        state: Arc<AgentsState>,
+    ) {
+        request::spawn(async move {
+            let result = match prepare_docs_loop("handle", content, &state) {
+                Ok(setup) => {
+                    setup
+                        .loop_
+                        .debug_prompt(setup.query, session_id, state)
+                        .await
+                }
+                Err(result) => result,
+            };
+            let _ = reply_tx.send(result);
+        });
+    }
+
     fn handle_stream(
         &self,
         channel_id: String,
@@ -280,7 +308,7 @@ impl Agent for DocsAgentPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             let setup = match prepare_docs_loop("handle", content, &state) {
                 Ok(s) => s,
                 Err(result) => {
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use tokio::sync::oneshot;
 
 use super::docs::DocsAgentPlugin;
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState};
 use araliya_core::bus::message::BusResult;
 
 pub(crate) struct DocsAgentWrapper {
@@ -29,6 +29,10 @@ impl Agent for DocsAgentWrapper {
         "docs_agent"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        self.inner.capabilities()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -53,4 +57,15 @@ impl Agent for DocsAgentWrapper {
         self.inner
             .handle_stream(channel_id, content, session_id, reply_tx, state);
     }
+
+    fn debug_prompt(
+        &self,
+        content: String,
+        session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        state: Arc<AgentsState>,
+    ) {
+        self.inner
+            .debug_prompt(content, session_id, reply_tx, state);
+    }
 }
@@ -1,17 +1,27 @@
 //! `docs_import` — populate an agent's [IDocStore] from a configured docs directory tree.
 //!
-//! Called once during agent subsystem startup.  If the target docstore already
-//! contains documents, the import is skipped so existing data is never overwritten.
+//! Called once during agent subsystem startup.  The import is incremental: a
+//! checkpoint file ([`CHECKPOINT_FILENAME`]) in the agent identity dir records
+//! the SHA-256 and document id of every ingested source file, so a re-run only
+//! processes files that are new or modified and drops documents whose source
+//! was deleted.  Files with identical content share one document, which is
+//! only deleted once no source file references it any more.  The
+//! checkpoint is flushed every [`CHECKPOINT_EVERY`] files, so an interrupted
+//! import resumes where it stopped instead of starting from scratch.
 //!
 //! Allowed file extensions: `.md`, `.txt`.  
 //! Skipped sub-directories: `images`.  
 //! Files larger than [`MAX_FILE_BYTES`] are ignored with a warning.
 
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use std::{fs, io};
 
+use serde::{Deserialize, Serialize};
+
 use araliya_core::error::AppError;
 use araliya_memory::stores::docstore::{Document, IDocStore};
+use araliya_memory::stores::sqlite_core::sha256_hex;
 
 /// Maximum size of a single source file that will be imported.
 pub const MAX_FILE_BYTES: u64 = 2_000_000; // 2 MB
@@ -28,17 +38,104 @@ const SKIP_DIRS: &[&str] = &["images"];
 /// Placeholder content written to `index_name` when it is missing from `source_dir`.
 const INDEX_PLACEHOLDER: &str = "_TODO_";
 
-/// Populate `agent_identity_dir`'s docstore from `source_dir`.
+/// Checkpoint file name, relative to the agent identity dir.
+pub const CHECKPOINT_FILENAME: &str = "docs_import.json";
+
+/// Number of ingested files between checkpoint flushes.
+const CHECKPOINT_EVERY: usize = 25;
+
+/// Checkpoint record for one ingested source file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IngestedFile {
+    /// SHA-256 of the file content.
+    hash: String,
+    /// Document holding the content.  The docstore deduplicates by content
+    /// hash, so several files may point at the same document.
+    doc_id: String,
+}
+
+/// Ingested source files, keyed by relative path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ImportCheckpoint {
+    files: BTreeMap<String, IngestedFile>,
+}
+
+impl ImportCheckpoint {
+    /// Document recorded for `rel_path`, if no other file references it —
+    /// i.e. the document that may be deleted when `rel_path` changes or goes.
+    fn unshared_doc(&self, rel_path: &str) -> Option<String> {
+        let doc_id = &self.files.get(rel_path)?.doc_id;
+        let shared = self
+            .files
+            .iter()
+            .any(|(path, f)| path != rel_path && &f.doc_id == doc_id);
+        (!shared).then(|| doc_id.clone())
+    }
+
+    /// Whether any recorded file is stored under document `doc_id`.
+    fn references(&self, doc_id: &str) -> bool {
+        self.files.values().any(|f| f.doc_id == doc_id)
+    }
+
+    /// Document already holding content with `hash`, if any.
+    fn doc_with_hash(&self, hash: &str) -> Option<String> {
+        self.files
+            .values()
+            .find(|f| f.hash == hash)
+            .map(|f| f.doc_id.clone())
+    }
+
+    /// Load the checkpoint; a missing or unreadable file yields an empty one.
+    fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!("docs_import: ignoring corrupt checkpoint {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write atomically (temp file + rename) so a crash never leaves a torn file.
+    fn save(&self, path: &Path) -> Result<(), AppError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::Memory(format!("docs_import: serialize checkpoint: {e}")))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| AppError::Memory(format!("docs_import: write checkpoint {:?}: {e}", path)))
+    }
+}
+
+/// Outcome of one [`populate_docstore_from_source`] run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    /// New or modified files that were (re)ingested.
+    pub imported: usize,
+    /// Files whose content hash matched the checkpoint.
+    pub skipped: usize,
+    /// Documents removed because their source file is gone.
+    pub removed: usize,
+}
+
+/// Incrementally populate `agent_identity_dir`'s docstore from `source_dir`.
 ///
 /// # Behaviour
-/// 1. Opens (or creates) the [IDocStore] at `{agent_identity_dir}/docstore/`.
-/// 2. If the docstore already contains at least one document, returns immediately.
+/// 1. Opens (or creates) the [IDocStore] at `{agent_identity_dir}/docstore/`
+///    and loads the checkpoint at `{agent_identity_dir}/docs_import.json`.
+/// 2. If the store has rows but the index document's content is unreadable,
+///    the store is treated as corrupt: all rows and the checkpoint are cleared.
+///    A healthy store without a checkpoint (imported by an older version) seeds
+///    the checkpoint from the stored content hashes.
 /// 3. Recursively walks `source_dir`.  For each `.md` / `.txt` file:
 ///    - Skips files larger than [`MAX_FILE_BYTES`].
 ///    - Uses the relative path from `source_dir` as the document ID.
-///    - Adds the document to the store, then chunks and indexes it.
-/// 4. If no file matching `index_name` was found (or added), creates a placeholder
+///    - Skips the file if its hash matches the checkpoint; otherwise replaces
+///      the stored document, then chunks and indexes it.  A file whose
+///      content is already stored shares that document instead.
+/// 4. If no file matching `index_name` was found, creates a placeholder
 ///    document with content `"_TODO_"`.
+/// 5. Deletes documents whose source file no longer exists.
 ///
 /// All per-file errors are logged as warnings; only fatal errors (cannot open
 /// the docstore, cannot read the source directory) are propagated.
@@ -46,39 +143,48 @@ pub fn populate_docstore_from_source(
     agent_identity_dir: &Path,
     source_dir: &Path,
     index_name: &str,
-) -> Result<(), AppError> {
+) -> Result<ImportReport, AppError> {
     let docstore = IDocStore::open(agent_identity_dir)?;
+    let checkpoint_path = agent_identity_dir.join(CHECKPOINT_FILENAME);
+    let mut checkpoint = ImportCheckpoint::load(&checkpoint_path);
 
-    // Idempotency guard: skip if already populated AND the index document's
-    // content file is actually readable.  If the DB has rows but the content
-    // file is missing (e.g. stale data from a previous partial/broken import),
-    // treat the store as corrupt, wipe all rows, and re-import from scratch.
+    // Stale-data guard: if the DB has rows but the index document's content
+    // file is missing (e.g. a previous broken import), wipe all rows and the
+    // checkpoint and re-import from scratch.
     let existing = docstore.list_documents()?;
     if !existing.is_empty() {
-        let index_healthy = docstore.get_document(index_name).is_ok();
-        if index_healthy {
-            tracing::debug!(
-                "docstore already populated ({} docs) at {:?}, skipping import",
+        if docstore.get_document(index_name).is_ok() {
+            if checkpoint.files.is_empty() {
+                checkpoint.files = existing
+                    .iter()
+                    .map(|m| {
+                        (
+                            m.doc_id.clone(),
+                            IngestedFile {
+                                hash: m.content_hash.clone(),
+                                doc_id: m.doc_id.clone(),
+                            },
+                        )
+                    })
+                    .collect();
+            }
+        } else {
+            tracing::warn!(
+                "docstore has {} metadata row(s) but index '{}' content is missing — \
+                 stale data detected; clearing for fresh import",
                 existing.len(),
-                agent_identity_dir
+                index_name
             );
-            return Ok(());
-        }
-
-        tracing::warn!(
-            "docstore has {} metadata row(s) but index '{}' content is missing — \
-             stale data detected; clearing for fresh import",
-            existing.len(),
-            index_name
-        );
-        for meta in &existing {
-            if let Err(e) = docstore.delete_document(&meta.doc_id) {
-                tracing::warn!(
-                    "docs_import: failed to remove stale doc '{}': {}",
-                    meta.doc_id,
-                    e
-                );
+            for meta in &existing {
+                if let Err(e) = docstore.delete_document(&meta.doc_id) {
+                    tracing::warn!(
+                        "docs_import: failed to remove stale doc '{}': {}",
+                        meta.doc_id,
+                        e
+                    );
+                }
             }
+            checkpoint = ImportCheckpoint::default();
         }
     }
 
@@ -103,14 +209,51 @@ pub fn populate_docstore_from_source(
         entries.push((index_name.to_string(), INDEX_PLACEHOLDER.to_string()));
     }
 
-    let mut added = 0usize;
+    let mut report = ImportReport::default();
+    let mut seen: HashSet<String> = HashSet::with_capacity(entries.len());
     for (rel_path, content) in entries {
+        seen.insert(rel_path.clone());
+        let hash = sha256_hex(&content);
+        if checkpoint.files.get(&rel_path).map(|f| f.hash.as_str()) == Some(hash.as_str()) {
+            report.skipped += 1;
+            continue;
+        }
+
+        // Modified file: drop the old document unless another file with the
+        // same previous content still uses it.
+        if let Some(old_doc) = checkpoint.unshared_doc(&rel_path)
+            && let Err(e) = docstore.delete_document(&old_doc)
+        {
+            tracing::warn!(
+                "docs_import: failed to remove old version of '{}': {}",
+                rel_path,
+                e
+            );
+        }
+        checkpoint.files.remove(&rel_path);
+
+        // Same content as a file already ingested: share its document.
+        if let Some(doc_id) = checkpoint.doc_with_hash(&hash) {
+            checkpoint
+                .files
+                .insert(rel_path, IngestedFile { hash, doc_id });
+            report.imported += 1;
+            continue;
+        }
+
+        // The path is the preferred document ID, unless a shared document
+        // created for another file still holds it.
+        let id = if checkpoint.references(&rel_path) {
+            String::new()
+        } else {
+            rel_path.clone()
+        };
         let doc = Document {
-            id: rel_path.clone(),
+            id,
             title: rel_path.clone(),
             source: source_dir.to_string_lossy().to_string(),
             content,
-            content_hash: String::new(),
+            content_hash: hash.clone(),
             created_at: String::new(),
             metadata: Default::default(),
         };
@@ -135,22 +278,66 @@ pub fn populate_docstore_from_source(
                         rel_path,
                         e
                     );
+                    continue;
                 }
             }
             Err(e) => {
                 tracing::warn!("docs_import: failed to chunk '{}': {}", rel_path, e);
+                continue;
             }
         }
 
-        added += 1;
+        checkpoint
+            .files
+            .insert(rel_path, IngestedFile { hash, doc_id });
+        report.imported += 1;
+        if report.imported % CHECKPOINT_EVERY == 0 {
+            checkpoint.save(&checkpoint_path)?;
+        }
+    }
+
+    // Source files that disappeared since the last run.
+    let gone: Vec<String> = checkpoint
+        .files
+        .keys()
+        .filter(|k| !seen.contains(k.as_str()))
+        .cloned()
+        .collect();
+    for rel_path in gone {
+        if let Some(doc_id) = checkpoint.unshared_doc(&rel_path)
+            && let Err(e) = docstore.delete_document(&doc_id)
+        {
+            tracing::warn!("docs_import: failed to remove '{}': {}", rel_path, e);
+            continue;
+        }
+        checkpoint.files.remove(&rel_path);
+        report.removed += 1;
     }
 
+    checkpoint.save(&checkpoint_path)?;
+
     tracing::info!(
-        "docs import complete: {} document(s) added for agent at {:?}",
-        added,
+        "docs import complete: {} imported, {} unchanged, {} removed for agent at {:?}",
+        report.imported,
+        report.skipped,
+        report.removed,
         agent_identity_dir
     );
-    Ok(())
+    Ok(report)
+}
+
+/// Text of every chunk in the agent's docstore, split as the import indexed
+/// it.  Documents that cannot be read are skipped with a warning.
+pub fn chunk_texts(agent_identity_dir: &Path) -> Result<Vec<String>, AppError> {
+    let docstore = IDocStore::open(agent_identity_dir)?;
+    let mut texts = Vec::new();
+    for meta in docstore.list_documents()? {
+        match docstore.chunk_document(&meta.doc_id, CHUNK_SIZE) {
+            Ok(chunks) => texts.extend(chunks.into_iter().map(|c| c.text)),
+            Err(e) => tracing::warn!("docs_import: chunk {}: {}", meta.doc_id, e),
+        }
+    }
+    Ok(texts)
 }
 
 /// Populate an agent's [`IKGDocStore`] from `source_dir` and rebuild the KG.
@@ -161,7 +348,7 @@ pub fn populate_docstore_from_source(
 /// # Behaviour
 /// 1. Opens (or creates) the [`IKGDocStore`] at `{agent_identity_dir}/kgdocstore/`.
 /// 2. If the store already contains at least one document, returns immediately
-///    (same idempotency semantics as [`populate_docstore_from_source`]).
+///    (no checkpoint — the KG is rebuilt from the full corpus or not at all).
 /// 3. Walks `source_dir`, adding + chunking + indexing every `.md` / `.txt` file.
 /// 4. Calls [`IKGDocStore::rebuild_kg_with_config`] to extract entities/relations
 ///    and write `kgdocstore/kg/graph.json`.
@@ -465,4 +652,126 @@ mod tests {
 
         assert_eq!(count_before, count_after, "second import must be a no-op");
     }
+
+    #[test]
+    fn second_import_reprocesses_only_changed_file() {
+        let identity_tmp = make_identity_dir();
+        let identity_dir = identity_tmp.path().join(AGENTS_DIRNAME);
+        let source_tmp = make_source_dir();
+
+        let first = populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("first import");
+        assert_eq!(first.imported, 4);
+        assert!(identity_dir.join(CHECKPOINT_FILENAME).exists());
+
+        fs::write(source_tmp.path().join("guide.md"), "## Guide rewritten").expect("modify");
+
+        let second = populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("second import");
+        assert_eq!(
+            second,
+            ImportReport {
+                imported: 1,
+                skipped: 3,
+                removed: 0
+            }
+        );
+
+        let store = IDocStore::open(&identity_dir).expect("open");
+        assert_eq!(store.list_documents().expect("list").len(), 4);
+        let guide = store.get_document("guide.md").expect("get guide.md");
+        assert_eq!(guide.content.trim(), "## Guide rewritten");
+        assert!(
+            !store
+                .search_by_text("rewritten", 5)
+                .expect("search")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn import_removes_documents_for_deleted_files() {
+        let identity_tmp = make_identity_dir();
+        let identity_dir = identity_tmp.path().join(AGENTS_DIRNAME);
+        let source_tmp = make_source_dir();
+
+        populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("first import");
+        fs::remove_file(source_tmp.path().join("notes.txt")).expect("remove");
+
+        let report = populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("second import");
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.imported, 0);
+
+        let store = IDocStore::open(&identity_dir).expect("open");
+        let docs = store.list_documents().expect("list");
+        assert!(!docs.iter().any(|d| d.doc_id == "notes.txt"));
+    }
+
+    #[test]
+    fn identical_files_share_a_document_until_both_are_gone() {
+        let identity_tmp = make_identity_dir();
+        let identity_dir = identity_tmp.path().join(AGENTS_DIRNAME);
+        let source_tmp = TempDir::new().expect("source");
+        let src = source_tmp.path();
+        fs::write(src.join("index.md"), "# Index").expect("index.md");
+        fs::write(src.join("a.md"), "duplicated release notes").expect("a.md");
+        fs::write(src.join("b.md"), "duplicated release notes").expect("b.md");
+
+        let first =
+            populate_docstore_from_source(&identity_dir, src, "index.md").expect("first import");
+        assert_eq!(first.imported, 3);
+        let store = IDocStore::open(&identity_dir).expect("open");
+        assert_eq!(store.list_documents().expect("list").len(), 2);
+
+        // Dropping one copy must keep the content for the other.
+        fs::remove_file(src.join("a.md")).expect("remove a.md");
+        let second =
+            populate_docstore_from_source(&identity_dir, src, "index.md").expect("second import");
+        assert_eq!(second.removed, 1);
+        assert_eq!(store.list_documents().expect("list").len(), 2);
+        assert!(
+            !store
+                .search_by_text("duplicated", 5)
+                .expect("search")
+                .is_empty()
+        );
+
+        // Changing the last copy replaces the shared document.
+        fs::write(src.join("b.md"), "rewritten changelog").expect("modify b.md");
+        let third =
+            populate_docstore_from_source(&identity_dir, src, "index.md").expect("third import");
+        assert_eq!(third.imported, 1);
+        assert_eq!(store.list_documents().expect("list").len(), 2);
+        assert!(
+            store
+                .search_by_text("duplicated", 5)
+                .expect("search")
+                .is_empty()
+        );
+        assert!(
+            !store
+                .search_by_text("rewritten", 5)
+                .expect("search")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn missing_checkpoint_is_seeded_from_existing_store() {
+        let identity_tmp = make_identity_dir();
+        let identity_dir = identity_tmp.path().join(AGENTS_DIRNAME);
+        let source_tmp = make_source_dir();
+
+        populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("first import");
+        // Simulate a store written before checkpoints existed.
+        fs::remove_file(identity_dir.join(CHECKPOINT_FILENAME)).expect("remove checkpoint");
+
+        let report = populate_docstore_from_source(&identity_dir, source_tmp.path(), "index.md")
+            .expect("second import");
+        assert_eq!(report.imported, 0);
+        assert_eq!(report.skipped, 4);
+    }
 }
@@ -0,0 +1,326 @@
+//! Embedding cache for docs agents (`[agents.<id>] embedding_cache_entries`).
+//!
+//! After the docs import, [`reindex`] embeds every docstore chunk through
+//! `llm/embed`.  Most chunks have not changed since the last run, so
+//! [`EmbeddingCache`] keeps vectors keyed by the SHA-256 of the embedding
+//! model and the chunk text, and [`embed`] only sends text the cache does not
+//! already hold.  The cache lives in [`EMBEDDING_CACHE_FILENAME`] in the agent
+//! identity dir, next to the docs import checkpoint; past `max_entries` the
+//! least recently used vectors are dropped.
+//!
+//! The file is read and written on the blocking pool; only the `llm/embed`
+//! round trips run on the async runtime.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+
+use araliya_core::bus::handle::BusHandle;
+use araliya_core::bus::message::{BusError, BusPayload};
+use araliya_core::error::AppError;
+use araliya_memory::stores::sqlite_core::sha256_hex;
+
+/// Cache file name, relative to the agent identity dir.
+pub const EMBEDDING_CACHE_FILENAME: &str = "embedding_cache.json";
+
+/// Chunks sent to `llm/embed` per request during [`reindex`].
+const EMBED_BATCH: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEmbedding {
+    /// Value of [`CacheFile::tick`] when the entry was last read or written.
+    used: u64,
+    vector: Vec<f32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    tick: u64,
+    entries: HashMap<String, CachedEmbedding>,
+}
+
+/// Embeddings by model and text hash, capped at `max_entries`.
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    path: PathBuf,
+    max_entries: usize,
+    file: Mutex<CacheFile>,
+}
+
+impl EmbeddingCache {
+    /// Open the cache in `identity_dir`; a missing or unreadable file yields
+    /// an empty one.  Blocking: call it from the blocking pool.
+    pub fn open(identity_dir: &Path, max_entries: usize) -> Self {
+        let path = identity_dir.join(EMBEDDING_CACHE_FILENAME);
+        let file = match fs::read_to_string(&path) {
+            Ok(s) => serde_json::from_str(&s).unwrap_or_else(|e| {
+                tracing::warn!("embedding cache: ignoring corrupt {:?}: {}", path, e);
+                CacheFile::default()
+            }),
+            Err(_) => CacheFile::default(),
+        };
+        Self {
+            path,
+            max_entries,
+            file: Mutex::new(file),
+        }
+    }
+
+    fn key(model: &str, text: &str) -> String {
+        sha256_hex(&format!("{model}\u{0}{text}"))
+    }
+
+    /// The vector cached for `text` under `model`, if any.
+    pub fn get(&self, model: &str, text: &str) -> Option<Vec<f32>> {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.tick += 1;
+        let tick = file.tick;
+        let entry = file.entries.get_mut(&Self::key(model, text))?;
+        entry.used = tick;
+        Some(entry.vector.clone())
+    }
+
+    /// Cache `vector` for `text` under `model`, dropping the least recently
+    /// used entries past the cap.
+    pub fn insert(&self, model: &str, text: &str, vector: Vec<f32>) {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        file.tick += 1;
+        let used = file.tick;
+        file.entries
+            .insert(Self::key(model, text), CachedEmbedding { used, vector });
+        let excess = file.entries.len().saturating_sub(self.max_entries);
+        if excess > 0 {
+            let mut by_age: Vec<(u64, String)> = file
+                .entries
+                .iter()
+                .map(|(key, e)| (e.used, key.clone()))
+                .collect();
+            by_age.sort_unstable();
+            for (_, key) in by_age.into_iter().take(excess) {
+                file.entries.remove(&key);
+            }
+        }
+    }
+
+    /// Number of cached embeddings.
+    pub fn len(&self) -> usize {
+        self.file
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the cache to disk (via a temp file, so a crash mid-write keeps
+    /// the previous copy).  Blocking: call it from the blocking pool.
+    pub fn save(&self) -> Result<(), AppError> {
+        let json = {
+            let file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+            serde_json::to_string(&*file)
+                .map_err(|e| AppError::Memory(format!("embedding cache: serialize: {e}")))?
+        };
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json)
+            .and_then(|()| fs::rename(&tmp, &self.path))
+            .map_err(|e| AppError::Memory(format!("embedding cache: write {:?}: {e}", self.path)))
+    }
+}
+
+/// Embed `inputs` under `model`, one vector per input in order.  Text already
+/// in `cache` is served from it; only the rest is sent to `llm/embed`, and
+/// then cached.  Returns the vectors and the number of inputs that were sent.
+pub async fn embed(
+    bus: &BusHandle,
+    cache: &EmbeddingCache,
+    model: &str,
+    inputs: &[String],
+) -> Result<(Vec<Vec<f32>>, usize), BusError> {
+    #[derive(Deserialize)]
+    struct EmbedReply {
+        embeddings: Vec<Vec<f32>>,
+    }
+
+    let mut vectors: Vec<Option<Vec<f32>>> = inputs.iter().map(|t| cache.get(model, t)).collect();
+    let missing: Vec<usize> = (0..inputs.len())
+        .filter(|&i| vectors[i].is_none())
+        .collect();
+    if missing.is_empty() {
+        return Ok((vectors.into_iter().flatten().collect(), 0));
+    }
+
+    let input: Vec<&str> = missing.iter().map(|&i| inputs[i].as_str()).collect();
+    let payload = BusPayload::JsonRequest {
+        data: serde_json::json!({ "input": input }).to_string(),
+    };
+    let data = match bus.request("llm/embed", payload).await {
+        Ok(Ok(BusPayload::JsonResponse { data })) => data,
+        Ok(Ok(_)) => return Err(BusError::new(-32000, "unexpected llm/embed reply")),
+        Ok(Err(e)) => return Err(e),
+        Err(e) => return Err(BusError::new(-32000, e.to_string())),
+    };
+    let reply: EmbedReply = serde_json::from_str(&data)
+        .map_err(|e| BusError::new(-32000, format!("invalid llm/embed reply: {e}")))?;
+    if reply.embeddings.len() != missing.len() {
+        return Err(BusError::new(
+            -32000,
+            format!(
+                "llm/embed returned {} embeddings for {} inputs",
+                reply.embeddings.len(),
+                missing.len()
+            ),
+        ));
+    }
+    let sent = missing.len();
+    for (i, vector) in missing.into_iter().zip(reply.embeddings) {
+        cache.insert(model, &inputs[i], vector.clone());
+        vectors[i] = Some(vector);
+    }
+    Ok((vectors.into_iter().flatten().collect(), sent))
+}
+
+/// Embed the docs agent's chunks `texts` through its cache in
+/// `identity_dir`, then save the cache.  Run as a background task once the
+/// import is done: `llm/embed` is only served after the supervisor starts.
+/// Failures are logged; the agent keeps answering from BM25 either way.
+pub async fn reindex(
+    bus: BusHandle,
+    agent_id: String,
+    identity_dir: PathBuf,
+    model: String,
+    max_entries: usize,
+    texts: Vec<String>,
+) {
+    let cache = match tokio::task::spawn_blocking(move || {
+        Arc::new(EmbeddingCache::open(&identity_dir, max_entries))
+    })
+    .await
+    {
+        Ok(cache) => cache,
+        Err(e) => {
+            tracing::warn!(%agent_id, "embedding cache: open panicked: {e}");
+            return;
+        }
+    };
+
+    let mut sent = 0;
+    for batch in texts.chunks(EMBED_BATCH) {
+        match embed(&bus, &cache, &model, batch).await {
+            Ok((_, n)) => sent += n,
+            Err(e) => {
+                tracing::warn!(%agent_id, "docs embeddings: llm/embed failed: {}", e.message);
+                break;
+            }
+        }
+    }
+
+    let saved = cache.clone();
+    match tokio::task::spawn_blocking(move || saved.save()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!(%agent_id, "{e}"),
+        Err(e) => tracing::warn!(%agent_id, "embedding cache: save panicked: {e}"),
+    }
+    tracing::info!(
+        %agent_id,
+        "docs embeddings: {} chunks, {} sent to llm/embed, {} cached",
+        texts.len(),
+        sent,
+        cache.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::bus::handle::SupervisorBus;
+    use araliya_core::bus::message::BusMessage;
+
+    #[test]
+    fn keeps_recently_used_entries_and_survives_reopen() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = EmbeddingCache::open(dir.path(), 2);
+        cache.insert("m", "a", vec![1.0]);
+        cache.insert("m", "b", vec![2.0]);
+        // Reading "a" makes "b" the least recently used.
+        assert_eq!(cache.get("m", "a"), Some(vec![1.0]));
+        cache.insert("m", "c", vec![3.0]);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("m", "b"), None);
+        // Another model is another key.
+        assert_eq!(cache.get("other", "a"), None);
+        cache.save().unwrap();
+
+        let reopened = EmbeddingCache::open(dir.path(), 2);
+        assert_eq!(reopened.get("m", "a"), Some(vec![1.0]));
+        assert_eq!(reopened.get("m", "c"), Some(vec![3.0]));
+    }
+
+    /// Text embedded once is served from the cache; only new text reaches
+    /// `llm/embed`.
+    #[tokio::test]
+    async fn embedding_the_same_text_twice_hits_the_cache() {
+        let bus = SupervisorBus::new(16);
+        let mut rx = bus.rx;
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = EmbeddingCache::open(dir.path(), 100);
+
+        // Stand-in for `llm/embed`: a text's vector is its length, and each
+        // request's inputs are recorded.
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let seen = calls.clone();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload: BusPayload::JsonRequest { data },
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "llm/embed");
+                let input: Vec<String> = serde_json::from_value(
+                    serde_json::from_str::<serde_json::Value>(&data).unwrap()["input"].clone(),
+                )
+                .unwrap();
+                let embeddings: Vec<Vec<f32>> =
+                    input.iter().map(|t| vec![t.len() as f32]).collect();
+                seen.lock().unwrap().push(input);
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: serde_json::json!({ "model": "embed-small", "embeddings": embeddings })
+                        .to_string(),
+                }));
+            }
+        });
+
+        let text = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let first = embed(&bus.handle, &cache, "embed-small", &text(&["chunk one"]))
+            .await
+            .unwrap();
+        let second = embed(&bus.handle, &cache, "embed-small", &text(&["chunk one"]))
+            .await
+            .unwrap();
+        assert_eq!(first, (vec![vec![9.0]], 1));
+        assert_eq!(second, (vec![vec![9.0]], 0));
+        assert_eq!(calls.lock().unwrap().len(), 1);
+
+        // A changed chunk alongside an unchanged one: only the new text is sent.
+        let (mixed, sent) = embed(
+            &bus.handle,
+            &cache,
+            "embed-small",
+            &text(&["chunk one", "chunk 2!"]),
+        )
+        .await
+        .unwrap();
+        assert_eq!((mixed, sent), (vec![vec![9.0], vec![8.0]], 1));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [text(&["chunk one"]), text(&["chunk 2!"])]
+        );
+    }
+}
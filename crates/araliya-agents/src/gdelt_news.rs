@@ -13,7 +13,7 @@ use araliya_core::bus::message::{BusError, BusPayload, BusResult, ERR_METHOD_NOT
 use araliya_memory::stores::agent::TextItem;
 
 use super::core::prompt::PromptBuilder;
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 const NO_EVENTS_MSG: &str = "No GDELT events found.";
 
@@ -24,6 +24,10 @@ impl Agent for GdeltNewsAgent {
         "gdelt_news"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Summarises recent global events from GDELT.").with_tools()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -33,7 +37,7 @@ impl Agent for GdeltNewsAgent {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             if action == "health" {
                 let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                     channel_id,
@@ -60,6 +64,7 @@ impl Agent for GdeltNewsAgent {
             // ── 1. Fetch from tool ──────────────────────────────────────
             let result = state
                 .execute_tool(
+                    "gdelt_news",
                     "gdelt_bigquery",
                     "fetch",
                     state.gdelt_query_args_json.clone(),
@@ -82,13 +87,7 @@ impl Agent for GdeltNewsAgent {
                 Ok(BusPayload::ToolResponse {
                     ok: false, error, ..
                 }) => {
-                    let _ = reply_tx.send(Err(BusError::new(
-                        -32000,
-                        format!(
-                            "gdelt_bigquery tool error: {}",
-                            error.unwrap_or_else(|| "unknown".to_string())
-                        ),
-                    )));
+                    let _ = reply_tx.send(Err(BusError::tool_failed("gdelt_bigquery", error)));
                     return;
                 }
                 Ok(other) => {
@@ -215,14 +214,15 @@ impl Agent for GdeltNewsAgent {
             // ── 8. Cache the summary ────────────────────────────────────
             persist_summary(&state, &cache_key, &summary).await;
 
-            let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+            let reply = Ok(BusPayload::CommsMessage {
                 channel_id,
                 content: summary,
                 session_id,
                 usage,
                 timing: None,
                 thinking,
-            }));
+            });
+            let _ = reply_tx.send(state.replace_empty_reply("gdelt_news", reply));
         });
     }
 }
@@ -4,7 +4,8 @@ use tokio::sync::oneshot;
 
 use araliya_core::bus::message::{BusPayload, BusResult};
 
-use super::{Agent, AgentsState};
+use super::tool_output::truncate_tool_output;
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 pub(crate) struct GmailAgentPlugin;
 
@@ -13,6 +14,10 @@ impl Agent for GmailAgentPlugin {
         "gmail"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Reads recent Gmail messages.").with_tools()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -22,7 +27,7 @@ impl Agent for GmailAgentPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             if action != "read" {
                 let _ = reply_tx.send(Err(araliya_core::bus::message::BusError::new(
                     araliya_core::bus::message::ERR_METHOD_NOT_FOUND,
@@ -33,6 +38,7 @@ impl Agent for GmailAgentPlugin {
 
             let result = state
                 .execute_tool(
+                    "gmail",
                     "gmail",
                     "read_latest",
                     serde_json::json!({}).to_string(),
@@ -47,9 +53,13 @@ impl Agent for GmailAgentPlugin {
                     data_json: Some(data),
                     ..
                 }) => {
-                    let summary = serde_json::from_str::<serde_json::Value>(&data)
-                        .unwrap_or_else(|_| serde_json::json!({}));
-                    let content = format!(
+                    let shaped = truncate_tool_output(
+                        serde_json::from_str::<serde_json::Value>(&data)
+                            .unwrap_or_else(|_| serde_json::json!({})),
+                        &state.tool_output,
+                    );
+                    let summary = &shaped.value;
+                    let mut content = format!(
                         "From: {}\nSubject: {}\nDate: {}\nSnippet: {}",
                         summary.get("from").and_then(|v| v.as_str()).unwrap_or(""),
                         summary
@@ -62,6 +72,10 @@ impl Agent for GmailAgentPlugin {
                             .and_then(|v| v.as_str())
                             .unwrap_or(""),
                     );
+                    if let Some(note) = shaped.note() {
+                        content.push('\n');
+                        content.push_str(&note);
+                    }
                     let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                         channel_id,
                         content,
@@ -74,12 +88,8 @@ impl Agent for GmailAgentPlugin {
                 Ok(BusPayload::ToolResponse {
                     ok: false, error, ..
                 }) => {
-                    let _ = reply_tx.send(Err(araliya_core::bus::message::BusError::new(
-                        -32000,
-                        format!(
-                            "gmail tool error: {}",
-                            error.unwrap_or_else(|| "unknown".to_string())
-                        ),
+                    let _ = reply_tx.send(Err(araliya_core::bus::message::BusError::tool_failed(
+                        "gmail", error,
                     )));
                 }
                 Ok(other) => {
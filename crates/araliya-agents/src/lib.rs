@@ -13,7 +13,8 @@
 
 use std::collections::{HashMap, HashSet};
 // _TODO_: check if we should be using more fine-grained locks.
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::core::AgentRuntimeClass;
 
@@ -23,15 +24,25 @@ use araliya_core::bus::component::{ComponentInfo, ComponentStatusResponse};
 use araliya_core::bus::dispatch::BusHandler;
 use araliya_core::bus::handle::BusHandle;
 use araliya_core::bus::health::HealthReporter;
-use araliya_core::bus::message::{BusError, BusPayload, BusResult, ERR_METHOD_NOT_FOUND};
-use araliya_core::config::{AgenticChatConfig, AgentsConfig, DocsAgentConfig};
+use araliya_core::bus::maintenance::Maintenance;
+use araliya_core::bus::message::{
+    AgentChain, BusError, BusPayload, BusResult, ERR_METHOD_NOT_FOUND, StreamReceiver,
+};
+use araliya_core::config::{
+    AgenticChatConfig, AgentsConfig, DocsAgentConfig, LinkPolicy, ToolOutputLimits,
+};
 use araliya_core::error::AppError;
 use araliya_core::obs::ObservabilityHandle;
-use araliya_llm::ModelRates;
+use araliya_llm::{LlmUsage, ModelRates, ResponseFormat};
 
 use araliya_core::identity::{self, Identity};
+use araliya_memory::clock::{Clock, SystemClock};
 use araliya_memory::handle::SessionHandle;
-use araliya_memory::{AGENTS_DIRNAME, MemorySystem};
+use araliya_memory::store::TranscriptEntry;
+use araliya_memory::{AGENTS_DIRNAME, MemorySystem, SessionInfo, SessionSort, SessionSpend};
+use context_budget::ContextBudget;
+use depth::AgentChains;
+use moderation::Moderation;
 
 // CHECK: wat?
 pub(crate) mod core;
@@ -39,30 +50,46 @@ pub(crate) mod core;
 // Chat-family plugins (basic_chat, session_chat) and shared ChatCore.
 #[cfg(feature = "plugin-agentic-chat")]
 mod agentic_chat;
-#[cfg(any(feature = "plugin-basic-chat", feature = "plugin-chat"))]
+#[cfg(any(
+    feature = "plugin-basic-chat",
+    feature = "plugin-chat",
+    feature = "plugin-agentic-chat"
+))]
 mod chat;
+pub mod context_budget;
+pub mod depth;
 #[cfg(feature = "plugin-docs")]
 mod docs;
 #[cfg(feature = "plugin-docs-agent")]
 mod docs_agent;
 #[cfg(feature = "plugin-docs")]
 mod docs_import;
+#[cfg(feature = "plugin-docs")]
+pub mod embed_cache;
 #[cfg(feature = "plugin-gdelt-news-agent")]
 mod gdelt_news;
 #[cfg(feature = "plugin-gmail-agent")]
 mod gmail;
+pub mod links;
+pub mod moderation;
 #[cfg(feature = "plugin-news-agent")]
 mod news;
 #[cfg(feature = "plugin-news-aggregator")]
 mod news_aggregator;
 #[cfg(feature = "plugin-newsroom-agent")]
 mod newsroom;
+pub mod request;
 #[cfg(feature = "plugin-runtime-cmd")]
 mod runtime_cmd;
 #[cfg(feature = "isqlite")]
 pub mod sqlite_tool;
+#[cfg(feature = "plugin-summarize")]
+mod summarize;
+#[cfg(feature = "plugin-sysinfo")]
+mod sysinfo;
 #[cfg(feature = "plugin-test-rssnews")]
 mod test_rssnews;
+pub mod tool_output;
 #[cfg(feature = "plugin-uniweb")]
 mod uniweb;
 #[cfg(any(feature = "plugin-webbuilder", feature = "plugin-homebuilder"))]
@@ -70,6 +97,11 @@ mod webbuilder;
 
 // ── AgentsState ───────────────────────────────────────────────────────────────
 
+/// Reply sent instead of a chat turn once the session's
+/// `[agents] daily_output_token_budget` is used up.
+pub const DAILY_BUDGET_REPLY: &str = "This session has used its output allowance for today. \
+Please try again after midnight UTC.";
+
 /// Shared capability surface passed to agent plugins.
 ///
 /// The raw [`BusHandle`] is private — plugins call typed methods and cannot
@@ -85,6 +117,17 @@ pub struct AgentsState {
     pub agent_identities: HashMap<String, Identity>,
     /// Pricing rates for the active LLM model — used to compute per-session spend.
     pub llm_rates: ModelRates,
+    /// Name of the active LLM model, for the per-model spend breakdown.
+    pub llm_model: String,
+    /// Pricing rates of every configured model, by model name.  Spend is
+    /// counted at the rates of the model that ran the completion, falling
+    /// back to `llm_rates`.
+    pub model_rates: HashMap<String, ModelRates>,
+    /// Context window of the default LLM model at startup; chat history is
+    /// trimmed to the window of the provider each request resolves to (see
+    /// [`AgentsState::fit_history`]), and to this one when that cannot be
+    /// resolved.  `None` keeps the fixed entry-count window only.
+    pub context_budget: Option<ContextBudget>,
     /// Default args JSON forwarded by the `news` agent to `newsmail_aggregator/get`.
     pub news_query_args_json: String,
     /// Default args JSON forwarded by the `gdelt_news` agent to `gdelt_bigquery/fetch`.
@@ -110,6 +153,43 @@ pub struct AgentsState {
     /// Observability handle — when set, the agentic loop emits structured
     /// events (session_start, llm_call_complete, tool_call).
     pub obs: Option<ObservabilityHandle>,
+    /// Session cost (USD) that triggers a one-time `manage/spend/alert`.
+    pub spend_alert_usd: Option<f64>,
+    /// Output tokens a session may use per UTC day; `None` means no limit.
+    pub daily_output_token_budget: Option<u64>,
+    /// Time source for the daily budget.  [`SystemClock`] outside tests.
+    pub clock: Arc<dyn Clock>,
+    /// Reply chat agents give to empty input instead of calling the LLM.
+    pub empty_input_reply: String,
+    /// Agents that forward empty input to the LLM anyway.
+    pub allow_empty_input: HashSet<String>,
+    /// Reply chat agents send in place of an empty LLM completion.
+    pub empty_reply: String,
+    /// Per-agent overrides of `empty_reply`.
+    pub agent_empty_replies: HashMap<String, String>,
+    /// Title chat sessions after their first turn (`[agents] auto_title`).
+    pub auto_title: bool,
+    /// Agents that write retrieved/tool context to the transcript.
+    pub persist_context: HashSet<String>,
+    /// Per-agent reply format; agents not listed reply in plain text.
+    pub agent_response_formats: HashMap<String, ResponseFormat>,
+    /// Per-agent cap on history turns in the prompt; agents not listed
+    /// keep the fixed entry-count window.
+    pub agent_context_turns: HashMap<String, usize>,
+    /// Chat input/output moderation (`[agents.moderation]`); `None` skips it.
+    pub moderation: Option<Moderation>,
+    /// Bounds on tool results fed into prompts (`[agents.tool_output]`).
+    pub tool_output: ToolOutputLimits,
+    /// Send `comms/agent_step` notifications during agentic turns
+    /// (`[agents] emit_steps`).
+    pub emit_steps: bool,
+    /// What agent replies do with links (`[agents] link_policy`).
+    pub link_policy: LinkPolicy,
+    /// Handoff and tool-call chains, bounded by `[agents] max_depth`.
+    pub chains: AgentChains,
+    /// Model behind `llm/embed` (`[llm] embedding_model`); docs chunks are
+    /// only embedded when it is known.
+    pub embedding_model: Option<String>,
 }
 
 impl AgentsState {
@@ -128,6 +208,21 @@ impl AgentsState {
         debug_logging: bool,
         agents_dir: String,
         user_agents_dir: Option<String>,
+        spend_alert_usd: Option<f64>,
+        daily_output_token_budget: Option<u64>,
+        empty_input_reply: String,
+        allow_empty_input: HashSet<String>,
+        empty_reply: String,
+        agent_empty_replies: HashMap<String, String>,
+        auto_title: bool,
+        persist_context: HashSet<String>,
+        agent_response_formats: HashMap<String, ResponseFormat>,
+        agent_context_turns: HashMap<String, usize>,
+        moderation: Option<Moderation>,
+        tool_output: ToolOutputLimits,
+        emit_steps: bool,
+        link_policy: LinkPolicy,
+        max_depth: usize,
     ) -> Self {
         Self {
             bus,
@@ -135,6 +230,9 @@ impl AgentsState {
             agent_memory,
             agent_identities,
             llm_rates: ModelRates::default(),
+            llm_model: String::new(),
+            model_rates: HashMap::new(),
+            context_budget: None,
             news_query_args_json,
             gdelt_query_args_json,
             newsroom_query_args_json,
@@ -145,7 +243,225 @@ impl AgentsState {
             agents_dir,
             user_agents_dir,
             obs: None,
+            spend_alert_usd,
+            daily_output_token_budget,
+            clock: Arc::new(SystemClock),
+            empty_input_reply,
+            allow_empty_input,
+            empty_reply,
+            agent_empty_replies,
+            auto_title,
+            persist_context,
+            agent_response_formats,
+            agent_context_turns,
+            moderation,
+            tool_output,
+            emit_steps,
+            link_policy,
+            chains: AgentChains::new(max_depth),
+            embedding_model: None,
+        }
+    }
+
+    /// Reply format for `agent_id`: the one the current request asked for,
+    /// else `[agents.<id>] response_format`.
+    pub fn response_format(&self, agent_id: &str) -> ResponseFormat {
+        request::response_format()
+            .or_else(|| self.agent_response_formats.get(agent_id).copied())
+            .unwrap_or_default()
+    }
+
+    /// Reply sent in place of an empty completion for `agent_id`
+    /// (`[agents.<id>] empty_reply`, else `[agents] empty_reply`).
+    pub fn empty_reply(&self, agent_id: &str) -> &str {
+        self.agent_empty_replies
+            .get(agent_id)
+            .map_or(&self.empty_reply, String::as_str)
+    }
+
+    /// Replace an empty or whitespace-only model reply with `agent_id`'s
+    /// configured `empty_reply`, so the user is not left with a blank
+    /// message.  Usage and timing are kept.  Every agent that answers with a
+    /// completion passes its reply through here.
+    pub fn replace_empty_reply(&self, agent_id: &str, result: BusResult) -> BusResult {
+        match result {
+            Ok(BusPayload::CommsMessage {
+                channel_id,
+                content,
+                session_id,
+                usage,
+                timing,
+                thinking,
+            }) if content.trim().is_empty() => {
+                tracing::warn!(
+                    %agent_id,
+                    %channel_id,
+                    output_tokens = usage.as_ref().map_or(0, |u| u.output_tokens),
+                    "empty llm reply replaced with empty_reply"
+                );
+                Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content: self.empty_reply(agent_id).to_string(),
+                    session_id,
+                    usage,
+                    timing,
+                    thinking,
+                })
+            }
+            other => other,
+        }
+    }
+
+    /// Strip or rewrite the links in a reply leaving the agents subsystem per
+    /// `[agents] link_policy`: a reply's content and thinking, or a stream's
+    /// deltas as they arrive.  Applied once, to every agent's reply, as
+    /// [`AgentsSubsystem`] sends it back.
+    pub fn apply_link_policy(&self, result: BusResult) -> BusResult {
+        if self.link_policy == LinkPolicy::Allow {
+            return result;
+        }
+        match result {
+            Ok(BusPayload::CommsMessage {
+                channel_id,
+                content,
+                session_id,
+                usage,
+                timing,
+                thinking,
+            }) => Ok(BusPayload::CommsMessage {
+                channel_id,
+                content: links::apply(&self.link_policy, &content).into_owned(),
+                session_id,
+                usage,
+                timing,
+                thinking: thinking.map(|t| links::apply(&self.link_policy, &t).into_owned()),
+            }),
+            Ok(BusPayload::LlmStreamResult { rx, request_id }) => Ok(BusPayload::LlmStreamResult {
+                rx: StreamReceiver(links::filter_stream(self.link_policy.clone(), rx.0)),
+                request_id,
+            }),
+            other => other,
+        }
+    }
+
+    /// History turn cap configured for `agent_id` (`[agents.<id>] context_turns`).
+    pub fn context_turns(&self, agent_id: &str) -> Option<usize> {
+        self.agent_context_turns.get(agent_id).copied()
+    }
+
+    /// Tell the channel behind `channel_id` what the agent is doing now,
+    /// as a [`BusPayload::AgentStep`] on `comms/agent_step`.  A no-op unless
+    /// `emit_steps` is on; a full bus only loses the step.
+    pub fn emit_step(&self, channel_id: &str, session_id: &str, step: impl Into<String>) {
+        if !self.emit_steps {
+            return;
+        }
+        let step = BusPayload::AgentStep {
+            channel_id: channel_id.to_string(),
+            session_id: Some(session_id.to_string()),
+            step: step.into(),
+        };
+        if let Err(e) = self.bus.notify("comms/agent_step", step) {
+            tracing::debug!("agents: step notification dropped: {e}");
+        }
+    }
+
+    /// Transcript metadata for an LLM reply: the model that ran it
+    /// (`LlmUsage::model`, else the active model, as in
+    /// [`record_spend`](Self::record_spend)) and its usage and timing, when
+    /// the provider reported them (`null` when none of these is known).
+    pub fn reply_meta(
+        &self,
+        usage: Option<&LlmUsage>,
+        timing: Option<&araliya_core::types::llm::LlmTiming>,
+    ) -> serde_json::Value {
+        let mut meta = serde_json::Map::new();
+        let model = usage
+            .and_then(|u| u.model.as_deref())
+            .unwrap_or(&self.llm_model);
+        if !model.is_empty() {
+            meta.insert("model".to_string(), model.into());
+        }
+        if let Some(usage) = usage {
+            meta.insert("usage".to_string(), serde_json::json!(usage));
+        }
+        if let Some(timing) = timing {
+            meta.insert("timing".to_string(), serde_json::json!(timing));
+        }
+        if meta.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::Value::Object(meta)
+        }
+    }
+
+    /// Add `usage` to the session's spend, under the model that ran it
+    /// (`LlmUsage::model`, else the active model) and at that model's rates.
+    /// The first time the total reaches `spend_alert_usd`, a
+    /// [`BusPayload::SpendAlert`] is sent to `manage/spend/alert`.
+    pub async fn record_spend(
+        &self,
+        handle: &SessionHandle,
+        usage: &LlmUsage,
+    ) -> Result<SessionSpend, AppError> {
+        let model = usage.model.as_deref().unwrap_or(&self.llm_model);
+        let rates = self.model_rates.get(model).unwrap_or(&self.llm_rates);
+        let spend = handle
+            .accumulate_spend_at(usage, model, rates, self.clock.now_unix_secs())
+            .await?;
+        if let Some(threshold) = self.spend_alert_usd
+            && spend.total_cost_usd >= threshold
+            && handle.claim_spend_alert(threshold).await?
+        {
+            tracing::warn!(
+                session_id = %handle.session_id,
+                total_cost_usd = spend.total_cost_usd,
+                threshold,
+                "session spend alert"
+            );
+            let alert = BusPayload::SpendAlert {
+                session_id: handle.session_id.clone(),
+                total_cost_usd: spend.total_cost_usd,
+            };
+            if let Err(e) = self.bus.notify("manage/spend/alert", alert) {
+                tracing::warn!("agents: spend alert notification failed: {e}");
+            }
+        }
+        Ok(spend)
+    }
+
+    /// The reply to send instead of running a turn when `handle` has used
+    /// its `daily_output_token_budget` for the current UTC day, else `None`.
+    pub async fn daily_budget_refusal(
+        &self,
+        handle: &SessionHandle,
+        channel_id: &str,
+    ) -> Option<BusResult> {
+        let budget = self.daily_output_token_budget?;
+        let used = match handle.read_spend().await {
+            Ok(spend) => spend?.day_output_tokens_at(self.clock.now_unix_secs()),
+            Err(e) => {
+                tracing::warn!(session_id = %handle.session_id, "read_spend failed: {e}");
+                return None;
+            }
+        };
+        if used < budget {
+            return None;
         }
+        tracing::info!(
+            session_id = %handle.session_id,
+            used,
+            budget,
+            "daily output token budget reached"
+        );
+        Some(Ok(BusPayload::CommsMessage {
+            channel_id: channel_id.to_string(),
+            content: DAILY_BUDGET_REPLY.to_string(),
+            session_id: Some(handle.session_id.clone()),
+            usage: None,
+            timing: None,
+            thinking: None,
+        }))
     }
 
     /// Open (or create) the persistent [`AgentStore`] for `agent_id`.
@@ -166,6 +482,84 @@ impl AgentsState {
         araliya_memory::stores::agent::AgentStore::open(&identity.identity_dir)
     }
 
+    /// Load `session_id` from `agent_id`'s session store, or create a new
+    /// session there (with the agent's configured store types) when `None`.
+    pub fn open_agent_session(
+        &self,
+        agent_id: &str,
+        session_id: Option<&str>,
+    ) -> Result<SessionHandle, AppError> {
+        let store = self.open_agent_store(agent_id)?;
+        let sessions_root = store.agent_sessions_dir();
+        let index_path = store.agent_sessions_index();
+        match session_id {
+            Some(id) => {
+                self.memory
+                    .load_session_in(&sessions_root, &index_path, id, Some(agent_id))
+            }
+            None => {
+                let store_types = self
+                    .agent_memory
+                    .get(agent_id)
+                    .map(|v| v.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+                    .unwrap_or_else(|| vec!["basic_session"]);
+                self.memory.create_session_in(
+                    &sessions_root,
+                    &index_path,
+                    &store_types,
+                    Some(agent_id),
+                )
+            }
+        }
+    }
+
+    /// Create a `tmp`-only session for `agent_id`: its history lives in
+    /// memory and nothing is written under the session directory.
+    pub fn open_ephemeral_session(&self, agent_id: &str) -> Result<SessionHandle, AppError> {
+        let store = self.open_agent_store(agent_id)?;
+        self.memory.create_session_in(
+            &store.agent_sessions_dir(),
+            &store.agent_sessions_index(),
+            &["tmp"],
+            Some(agent_id),
+        )
+    }
+
+    /// Forget the ephemeral session `session_id` of `agent_id`: its index
+    /// entry and in-memory data are dropped.  Persistent sessions are refused.
+    pub fn close_ephemeral_session(
+        &self,
+        agent_id: &str,
+        session_id: &str,
+    ) -> Result<(), AppError> {
+        let store = self.open_agent_store(agent_id)?;
+        self.memory.discard_tmp_session_in(
+            &store.agent_sessions_dir(),
+            &store.agent_sessions_index(),
+            session_id,
+        )
+    }
+
+    /// Hand session `session_id` from agent `from` to agent `to`: it moves
+    /// into `to`'s session store with its id and transcript intact.
+    pub fn move_agent_session(
+        &self,
+        from: &str,
+        to: &str,
+        session_id: &str,
+    ) -> Result<(), AppError> {
+        let source = self.open_agent_store(from)?;
+        let target = self.open_agent_store(to)?;
+        self.memory.move_session_in(
+            &source.agent_sessions_dir(),
+            &source.agent_sessions_index(),
+            &target.agent_sessions_dir(),
+            &target.agent_sessions_index(),
+            session_id,
+            Some(to),
+        )
+    }
+
     /// Open (or create) a named SQLite database for `agent_id`.
     ///
     /// The database is stored at `{agent_identity_dir}/sqlite/{db_name}.db`.
@@ -205,7 +599,8 @@ impl AgentsState {
     ///
     /// Equivalent to an inbound comms message delivered to `agents/{agent_id}/{action}`.
     /// If the target agent is not registered the bus returns `ERR_METHOD_NOT_FOUND` — callers
-    /// should handle or discard that error as appropriate.
+    /// should handle or discard that error as appropriate.  Each handoff is a hop
+    /// counted against `[agents] max_depth` (see [`depth`]).
     pub async fn dispatch_to_agent(
         &self,
         agent_id: &str,
@@ -214,17 +609,22 @@ impl AgentsState {
         channel_id: &str,
         session_id: Option<String>,
     ) -> BusResult {
+        let chain = self.chains.enter(channel_id, agent_id)?;
         let result = self
             .bus
             .request(
                 &format!("agents/{agent_id}/{action}"),
-                BusPayload::CommsMessage {
-                    channel_id: channel_id.to_string(),
-                    content: content.to_string(),
-                    session_id,
-                    usage: None,
-                    timing: None,
-                    thinking: None,
+                BusPayload::AgentRequest {
+                    chain: Some(chain),
+                    response_format: None,
+                    payload: Box::new(BusPayload::CommsMessage {
+                        channel_id: channel_id.to_string(),
+                        content: content.to_string(),
+                        session_id,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    }),
                 },
             )
             .await;
@@ -249,6 +649,22 @@ impl AgentsState {
         channel_id: &str,
         content: &str,
         system: Option<&str>,
+    ) -> BusResult {
+        self.complete_via_llm_formatted(channel_id, content, system, ResponseFormat::Text, None)
+            .await
+    }
+
+    /// Like [`complete_via_llm_with_system`], asking for the reply in
+    /// `format`, on `provider` when set (see [`Self::fit_history`]).  With
+    /// [`ResponseFormat::Json`] the LLM subsystem returns an error when the
+    /// reply is not valid JSON.
+    pub async fn complete_via_llm_formatted(
+        &self,
+        channel_id: &str,
+        content: &str,
+        system: Option<&str>,
+        format: ResponseFormat,
+        provider: Option<&str>,
     ) -> BusResult {
         let result = self
             .bus
@@ -258,8 +674,9 @@ impl AgentsState {
                     channel_id: channel_id.to_string(),
                     content: content.to_string(),
                     system: system.map(|s| s.to_string()),
-                    provider_override: None,
+                    provider_override: provider.map(|p| p.to_string()),
                     model_override: None,
+                    response_format: format,
                 },
             )
             .await;
@@ -269,6 +686,56 @@ impl AgentsState {
         }
     }
 
+    /// The newest suffix of `entries` that fits the context window of the
+    /// provider the prompt will run on, beside `fixed_tokens` of other
+    /// prompt text, and that provider's name.
+    ///
+    /// The provider is asked of `llm/resolve` for the untrimmed prompt, so
+    /// `[[llm.auto_route]]` and `llm/set_default` are followed.  Callers pin
+    /// the completion to it: the trimmed prompt is smaller and could
+    /// otherwise be auto-routed to a model with a smaller window.  Without a
+    /// [`ContextBudget`] nothing is trimmed; if the LLM subsystem does not
+    /// answer, the startup budget is used and nothing is pinned.
+    pub async fn fit_history<'a>(
+        &self,
+        entries: &'a [TranscriptEntry],
+        fixed_tokens: usize,
+    ) -> (&'a [TranscriptEntry], Option<String>) {
+        let Some(fallback) = self.context_budget else {
+            return (entries, None);
+        };
+        let prompt_tokens = fixed_tokens
+            + entries
+                .iter()
+                .map(|e| context_budget::estimate_tokens(&context_budget::history_line(e)))
+                .sum::<usize>();
+        let request = BusPayload::JsonRequest {
+            data: serde_json::json!({ "prompt_tokens": prompt_tokens }).to_string(),
+        };
+        let resolved = match self.bus.request("llm/resolve", request).await {
+            Ok(Ok(BusPayload::JsonResponse { data })) => {
+                serde_json::from_str::<serde_json::Value>(&data)
+                    .ok()
+                    .and_then(|v| {
+                        let provider = v["provider"].as_str()?.to_string();
+                        let budget = ContextBudget::new(
+                            v["context_window"].as_u64()? as usize,
+                            v["max_tokens"].as_u64().unwrap_or(0) as usize,
+                        );
+                        Some((provider, budget))
+                    })
+            }
+            _ => None,
+        };
+        match resolved {
+            Some((provider, budget)) => (budget.fit_history(entries, fixed_tokens), Some(provider)),
+            None => {
+                tracing::debug!("llm/resolve unavailable; history sized to the startup budget");
+                (fallback.fit_history(entries, fixed_tokens), None)
+            }
+        }
+    }
+
     /// Forward content to the instruction LLM via `llm/instruct`.
     ///
     /// If no separate instruction LLM is configured, the LLM subsystem
@@ -289,6 +756,7 @@ impl AgentsState {
                     system: system.map(|s| s.to_string()),
                     provider_override: None,
                     model_override: None,
+                    response_format: ResponseFormat::Text,
                 },
             )
             .await;
@@ -300,15 +768,23 @@ impl AgentsState {
 
     /// Stream a completion from the main LLM with an explicit system prompt.
     ///
-    /// Returns a channel receiver that yields [`StreamChunk`] values.  The
-    /// caller should forward these as SSE events.
+    /// Returns a channel receiver that yields [`StreamChunk`] values, and
+    /// the stream's `request_id` for `llm/stream/stop`.  The caller should
+    /// forward these as SSE events.  `provider` pins the stream to one
+    /// provider, as in [`Self::complete_via_llm_formatted`].
     pub async fn stream_via_llm_with_system(
         &self,
         channel_id: &str,
         content: &str,
         system: Option<&str>,
-    ) -> Result<tokio::sync::mpsc::Receiver<araliya_llm::StreamChunk>, BusError> {
-        use araliya_core::bus::message::StreamReceiver;
+        provider: Option<&str>,
+    ) -> Result<
+        (
+            tokio::sync::mpsc::Receiver<araliya_llm::StreamChunk>,
+            Option<String>,
+        ),
+        BusError,
+    > {
         let result = self
             .bus
             .request(
@@ -317,30 +793,34 @@ impl AgentsState {
                     channel_id: channel_id.to_string(),
                     content: content.to_string(),
                     system: system.map(|s| s.to_string()),
-                    provider_override: None,
+                    provider_override: provider.map(|p| p.to_string()),
                     model_override: None,
+                    response_format: ResponseFormat::Text,
                 },
             )
             .await;
         match result {
             Ok(Ok(BusPayload::LlmStreamResult {
                 rx: StreamReceiver(rx),
-            })) => Ok(rx),
+                request_id,
+            })) => Ok((rx, request_id)),
             Ok(Ok(_)) => Err(BusError::new(-32000, "unexpected reply to llm/stream")),
             Ok(Err(e)) => Err(e),
             Err(e) => Err(BusError::new(-32000, e.to_string())),
         }
     }
 
-    /// Execute a tool through the tools subsystem.
+    /// Execute a tool through the tools subsystem on behalf of `agent_id`.
     pub async fn execute_tool(
         &self,
+        agent_id: &str,
         tool: &str,
         action: &str,
         args_json: String,
         channel_id: &str,
         session_id: Option<String>,
     ) -> BusResult {
+        self.chains.enter(channel_id, &format!("{tool}/{action}"))?;
         let result = self
             .bus
             .request(
@@ -351,6 +831,7 @@ impl AgentsState {
                     args_json,
                     channel_id: channel_id.to_string(),
                     session_id,
+                    agent_id: Some(agent_id.to_string()),
                 },
             )
             .await;
@@ -384,6 +865,35 @@ impl AgentsState {
             Err(e) => Err(BusError::new(-32000, e.to_string())),
         }
     }
+
+    /// Bot health and uptime, as served at `GET /api/health`
+    /// (`manage/http/get`).
+    pub async fn bot_status(&self) -> BusResult {
+        self.query("manage/http/get", BusPayload::Empty).await
+    }
+
+    /// Active LLM provider and model (`llm/detailed_status`).
+    pub async fn llm_status(&self) -> BusResult {
+        self.query("llm/detailed_status", BusPayload::Empty).await
+    }
+
+    /// Active sessions (`agents/sessions`).
+    pub async fn session_list(&self) -> BusResult {
+        self.query(
+            "agents/sessions",
+            BusPayload::JsonRequest {
+                data: "{}".to_string(),
+            },
+        )
+        .await
+    }
+
+    async fn query(&self, method: &str, payload: BusPayload) -> BusResult {
+        match self.bus.request(method, payload).await {
+            Ok(r) => r,
+            Err(e) => Err(BusError::new(-32000, e.to_string())),
+        }
+    }
 }
 
 // ── Agent trait ───────────────────────────────────────────────────────────────
@@ -437,6 +947,70 @@ pub trait Agent: Send + Sync {
             state,
         );
     }
+
+    /// Assemble the prompt this agent would send for `content` — system,
+    /// user prompt and retrieved context — and reply with it as a
+    /// `JsonResponse` instead of calling the LLM (`agents/{id}/debug_prompt`).
+    ///
+    /// Default: unsupported.
+    fn debug_prompt(
+        &self,
+        _content: String,
+        _session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        _state: Arc<AgentsState>,
+    ) {
+        let _ = reply_tx.send(Err(BusError::new(
+            ERR_METHOD_NOT_FOUND,
+            format!("agent '{}' does not support debug_prompt", self.id()),
+        )));
+    }
+
+    /// What this agent supports, shown as node metadata in the agents
+    /// subsystem's component tree.
+    ///
+    /// Default: no description and every capability off.
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::default()
+    }
+}
+
+/// Capability descriptor an [`Agent`] contributes to the component tree,
+/// so UIs can render agent cards with their features.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct AgentCapabilities {
+    /// Keeps per-session history across turns.
+    pub supports_sessions: bool,
+    /// Calls tools (bus tools or local ones) while answering.
+    pub supports_tools: bool,
+    /// Overrides [`Agent::handle_stream`] with real streaming.
+    pub supports_streaming: bool,
+    /// One-line summary of what the agent does.
+    pub description: String,
+}
+
+impl AgentCapabilities {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_sessions(mut self) -> Self {
+        self.supports_sessions = true;
+        self
+    }
+
+    pub fn with_tools(mut self) -> Self {
+        self.supports_tools = true;
+        self
+    }
+
+    pub fn with_streaming(mut self) -> Self {
+        self.supports_streaming = true;
+        self
+    }
 }
 
 // ── AgentRegistration ─────────────────────────────────────────────────────────
@@ -475,8 +1049,30 @@ impl AgentRegistration {
 
 // ── Built-in agents ───────────────────────────────────────────────────────────
 
+/// Reflects content back, optionally transformed per `[agents.echo]`.
+/// Verbatim and immediate by default, which makes it a handy fixture for
+/// routing, timeout and cancellation tests.
 #[cfg(feature = "plugin-echo")]
-struct EchoAgent;
+struct EchoAgent {
+    config: araliya_core::config::EchoAgentConfig,
+}
+
+#[cfg(feature = "plugin-echo")]
+impl EchoAgent {
+    fn new(config: araliya_core::config::EchoAgentConfig) -> Self {
+        Self { config }
+    }
+
+    fn transform(&self, content: &str) -> String {
+        use araliya_core::config::EchoCase;
+        let body = match self.config.case {
+            EchoCase::Verbatim => content.to_string(),
+            EchoCase::Upper => content.to_uppercase(),
+            EchoCase::Lower => content.to_lowercase(),
+        };
+        format!("{}{body}{}", self.config.prefix, self.config.suffix)
+    }
+}
 
 #[cfg(feature = "plugin-echo")]
 impl Agent for EchoAgent {
@@ -491,16 +1087,62 @@ impl Agent for EchoAgent {
         session_id: Option<String>,
         reply_tx: oneshot::Sender<BusResult>,
         _state: Arc<AgentsState>,
+    ) {
+        let reply = Ok(BusPayload::CommsMessage {
+            channel_id,
+            content: self.transform(&content),
+            session_id,
+            usage: None,
+            timing: None,
+            thinking: None,
+        });
+        if self.config.delay_ms == 0 {
+            let _ = reply_tx.send(reply);
+            return;
+        }
+        let delay = Duration::from_millis(self.config.delay_ms);
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = reply_tx.send(reply);
+        });
+    }
+
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Reflects each message back, optionally transformed.")
+    }
+}
+
+/// Stand-in registered when the build has no `plugin-*` agents and
+/// `[agents] noop_fallback` is on: answers every request with a fixed notice
+/// so channels stay up.
+struct NoopAgent;
+
+impl Agent for NoopAgent {
+    fn id(&self) -> &str {
+        NOOP_AGENT
+    }
+    fn handle(
+        &self,
+        _action: String,
+        channel_id: String,
+        _content: String,
+        session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        _state: Arc<AgentsState>,
     ) {
         let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
             channel_id,
-            content,
+            content: NOOP_AGENT_REPLY.to_string(),
             session_id,
             usage: None,
             timing: None,
             thinking: None,
         }));
     }
+
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Placeholder for builds without agents; replies with a notice.")
+    }
 }
 
 // ── AgentsSubsystem ───────────────────────────────────────────────────────────
@@ -520,8 +1162,146 @@ pub struct AgentsSubsystem {
     agents: HashMap<String, AgentRegistration>,
     default_agent: String,
     channel_map: HashMap<String, String>,
+    /// Channel type (`telegram`, `http`, …) → agent, consulted when
+    /// `channel_map` has no exact match.  See [`channel_type`].
+    channel_defaults: HashMap<String, String>,
     enabled_agents: HashSet<String>,
     reporter: Option<HealthReporter>,
+    /// While enabled, routed agent requests get the maintenance message.
+    maintenance: Maintenance,
+    /// Minimum interval between invocations of an agent on one channel.
+    cooldowns: HashMap<String, Duration>,
+    /// Last accepted invocation per `(agent_id, channel_id)`.
+    last_invocation: Mutex<HashMap<(String, String), Instant>>,
+    /// Record stateless agents' exchanges in the caller's session
+    /// (`[comms] auto_session`).  Off: a `session_id` is passed through only.
+    session_transcripts: bool,
+    /// Serve `agents/{id}/debug_prompt` (`[agents] debug_prompt`).
+    debug_prompt: bool,
+}
+
+/// Agent used when the configured default is not loaded and
+/// `[agents] strict_default` is off.
+const FALLBACK_DEFAULT_AGENT: &str = "echo";
+
+/// Agent registered by `[agents] noop_fallback` when no agents are compiled in.
+const NOOP_AGENT: &str = "noop";
+
+/// Reply of the [`NoopAgent`].
+const NOOP_AGENT_REPLY: &str = "No agents are available in this build.";
+
+/// Cargo feature that compiles in built-in agent `agent_id`, and whether this
+/// build has it.
+fn agent_feature(agent_id: &str) -> Option<(&'static str, bool)> {
+    Some(match agent_id {
+        "echo" => ("plugin-echo", cfg!(feature = "plugin-echo")),
+        "basic_chat" => ("plugin-basic-chat", cfg!(feature = "plugin-basic-chat")),
+        "chat" => ("plugin-chat", cfg!(feature = "plugin-chat")),
+        "agentic-chat" => ("plugin-agentic-chat", cfg!(feature = "plugin-agentic-chat")),
+        "docs" => ("plugin-docs", cfg!(feature = "plugin-docs")),
+        "docs_agent" => ("plugin-docs-agent", cfg!(feature = "plugin-docs-agent")),
+        "gmail" => ("plugin-gmail-agent", cfg!(feature = "plugin-gmail-agent")),
+        "news" => ("plugin-news-agent", cfg!(feature = "plugin-news-agent")),
+        "gdelt_news" => (
+            "plugin-gdelt-news-agent",
+            cfg!(feature = "plugin-gdelt-news-agent"),
+        ),
+        "newsroom" => (
+            "plugin-newsroom-agent",
+            cfg!(feature = "plugin-newsroom-agent"),
+        ),
+        "news_aggregator" => (
+            "plugin-news-aggregator",
+            cfg!(feature = "plugin-news-aggregator"),
+        ),
+        "summarize" => ("plugin-summarize", cfg!(feature = "plugin-summarize")),
+        "sysinfo" => ("plugin-sysinfo", cfg!(feature = "plugin-sysinfo")),
+        "test_rssnews" => ("plugin-test-rssnews", cfg!(feature = "plugin-test-rssnews")),
+        "runtime_cmd" => ("plugin-runtime-cmd", cfg!(feature = "plugin-runtime-cmd")),
+        "uniweb" => ("plugin-uniweb", cfg!(feature = "plugin-uniweb")),
+        "webbuilder" => ("plugin-webbuilder", cfg!(feature = "plugin-webbuilder")),
+        "homebuilder" => ("plugin-homebuilder", cfg!(feature = "plugin-homebuilder")),
+        _ => return None,
+    })
+}
+
+/// Why `agent_id` is not in the registered agents map, for operators.
+fn missing_agent_reason(agent_id: &str) -> String {
+    match agent_feature(agent_id) {
+        Some((feature, false)) => format!(
+            "default agent '{agent_id}' is not compiled in; rebuild with the `{feature}` feature"
+        ),
+        Some((_, true)) => format!(
+            "default agent '{agent_id}' is compiled in but not loaded; check that [agents.{agent_id}] is enabled"
+        ),
+        None => format!("default agent '{agent_id}' is not a known agent"),
+    }
+}
+
+/// Handle a build with no `plugin-*` agents before any default-agent checks.
+///
+/// An empty registry is refused with an actionable error unless
+/// `noop_fallback` is set, in which case [`NoopAgent`] is registered and
+/// becomes the default for every route.
+fn ensure_agents_registered(
+    default_agent: String,
+    enabled_agents: HashSet<String>,
+    agents: &mut HashMap<String, AgentRegistration>,
+    noop_fallback: bool,
+) -> Result<(String, HashSet<String>), AppError> {
+    if !agents.is_empty() {
+        return Ok((default_agent, enabled_agents));
+    }
+    if !noop_fallback {
+        return Err(AppError::Config(
+            "no agents compiled in; enable at least one plugin-* feature \
+             (or set [agents] noop_fallback = true)"
+                .to_string(),
+        ));
+    }
+
+    tracing::warn!(
+        "no agents compiled in — registering the '{NOOP_AGENT}' agent; enable a plugin-* feature for real replies"
+    );
+    agents.insert(
+        NOOP_AGENT.to_string(),
+        AgentRegistration::new(AgentRuntimeClass::RequestResponse, Box::new(NoopAgent)),
+    );
+    Ok((NOOP_AGENT.to_string(), HashSet::new()))
+}
+
+/// Validate the configured default agent against the registered agents.
+///
+/// When it is missing, `strict` refuses to start; otherwise the default
+/// becomes [`FALLBACK_DEFAULT_AGENT`] (added to a non-empty enabled set so
+/// routing accepts it) and a warning names the cause.
+fn check_default_agent(
+    default_agent: String,
+    mut enabled_agents: HashSet<String>,
+    agents: &HashMap<String, AgentRegistration>,
+    strict: bool,
+) -> Result<(String, HashSet<String>), AppError> {
+    if agents.contains_key(&default_agent) {
+        return Ok((default_agent, enabled_agents));
+    }
+
+    let reason = missing_agent_reason(&default_agent);
+    if strict {
+        return Err(AppError::Config(format!(
+            "{reason} ([agents] strict_default = true)"
+        )));
+    }
+    if !agents.contains_key(FALLBACK_DEFAULT_AGENT) {
+        return Err(AppError::Config(format!(
+            "{reason}, and fallback agent '{FALLBACK_DEFAULT_AGENT}' is not available either"
+        )));
+    }
+
+    tracing::warn!("{reason} — falling back to '{FALLBACK_DEFAULT_AGENT}' for unrouted messages");
+    if !enabled_agents.is_empty() {
+        enabled_agents.insert(FALLBACK_DEFAULT_AGENT.to_string());
+    }
+    Ok((FALLBACK_DEFAULT_AGENT.to_string(), enabled_agents))
 }
 
 impl AgentsSubsystem {
@@ -669,6 +1449,8 @@ impl AgentsSubsystem {
         // Runtime class mapping (v0.6 PR1):
         //   echo          → RequestResponse  (stateless echo)
         //   basic_chat    → RequestResponse  (single-turn LLM pass-through)
+        //   summarize     → RequestResponse  (stateless one-shot summary)
+        //   sysinfo       → RequestResponse  (bus status queries, no LLM)
         //   chat          → Session          (multi-turn with transcript)
         //   agentic-chat  → Agentic          (instruction → tools → response loop)
         //   docs          → Agentic          (RAG retrieval + multi-pass LLM)
@@ -688,7 +1470,28 @@ impl AgentsSubsystem {
 
         #[cfg(feature = "plugin-echo")]
         {
-            let agent: Box<dyn Agent> = Box::new(EchoAgent);
+            let agent: Box<dyn Agent> =
+                Box::new(EchoAgent::new(config.echo.clone().unwrap_or_default()));
+            agents.insert(
+                agent.id().to_string(),
+                AgentRegistration::new(AgentRuntimeClass::RequestResponse, agent),
+            );
+        }
+
+        #[cfg(feature = "plugin-summarize")]
+        {
+            let agent: Box<dyn Agent> = Box::new(summarize::SummarizeAgent::new(
+                &config.summarize.clone().unwrap_or_default(),
+            ));
+            agents.insert(
+                agent.id().to_string(),
+                AgentRegistration::new(AgentRuntimeClass::RequestResponse, agent),
+            );
+        }
+
+        #[cfg(feature = "plugin-sysinfo")]
+        {
+            let agent: Box<dyn Agent> = Box::new(sysinfo::SysinfoAgent);
             agents.insert(
                 agent.id().to_string(),
                 AgentRegistration::new(AgentRuntimeClass::RequestResponse, agent),
@@ -843,15 +1646,41 @@ impl AgentsSubsystem {
             );
         }
 
+        let moderation = config
+            .moderation
+            .as_ref()
+            .map(Moderation::from_config)
+            .transpose()?;
+
         // Per-agent skills from config — only tools declared here are visible
         // to each agent's instruction manifest.
         let agent_skills = config.agent_skills;
 
-        // Initialize cryptographic identities for all registered agents.
+        // A build without agents (or a default naming an agent that was not
+        // compiled in or not loaded) would otherwise only fail on the first
+        // unrouted request.
+        let (default_agent, enabled_agents) = ensure_agents_registered(
+            default_agent,
+            enabled_agents,
+            &mut agents,
+            config.noop_fallback,
+        )?;
+        let (default_agent, enabled_agents) = check_default_agent(
+            default_agent,
+            enabled_agents,
+            &agents,
+            config.strict_default,
+        )?;
+
+        // Initialize cryptographic identities for all registered agents, and
+        // create the stores each one declares before anything reads them.
         let mut agent_identities = HashMap::new();
         let agent_memory_root = memory.memory_root().join(AGENTS_DIRNAME);
         for agent_id in agents.keys() {
             let identity = identity::setup_named_identity(&agent_memory_root, agent_id)?;
+            if let Some(stores) = agent_memory.get(agent_id) {
+                provision_agent_stores(agent_id, &identity.identity_dir, stores)?;
+            }
             agent_identities.insert(agent_id.clone(), identity);
         }
 
@@ -904,12 +1733,37 @@ impl AgentsSubsystem {
                 config.debug_logging,
                 "config/agents".to_string(),
                 user_agents_dir,
+                config.spend_alert_usd,
+                config.daily_output_token_budget,
+                config.empty_input_reply,
+                config.allow_empty_input,
+                config.empty_reply,
+                config.agent_empty_replies,
+                config.auto_title,
+                config.persist_context,
+                config.agent_response_formats,
+                config.agent_context_turns,
+                moderation,
+                config.tool_output,
+                config.emit_steps,
+                config.link_policy.clone(),
+                config.max_depth,
             )),
             agents,
             default_agent,
             channel_map: config.channel_map,
+            channel_defaults: HashMap::new(),
             enabled_agents,
             reporter: None,
+            maintenance: Maintenance::default(),
+            cooldowns: config
+                .agent_cooldowns
+                .into_iter()
+                .map(|(id, ms)| (id, Duration::from_millis(ms)))
+                .collect(),
+            last_invocation: Mutex::new(HashMap::new()),
+            session_transcripts: false,
+            debug_prompt: config.debug_prompt,
         })
     }
 
@@ -918,6 +1772,11 @@ impl AgentsSubsystem {
     /// any requests.  Safe to call from an async context.
     ///
     /// If no agent has a `docsdir` configured this is a no-op.
+    ///
+    /// With an embedding model set (see [`Self::with_embedding_model`]) and
+    /// `embedding_cache_entries` above zero, the imported chunks are then
+    /// embedded in the background through the agent's embedding cache, so
+    /// only new or changed chunks reach `llm/embed`.
     #[cfg(feature = "plugin-docs")]
     pub async fn init_docs(&self) -> Result<(), AppError> {
         for (agent_id, docs_cfg) in &self.state.agent_docs {
@@ -942,30 +1801,37 @@ impl AgentsSubsystem {
                 .clone()
                 .unwrap_or_else(|| "index.md".to_string());
             let identity_dir = identity.identity_dir.clone();
+            let embedding = self
+                .state
+                .embedding_model
+                .clone()
+                .filter(|_| docs_cfg.embedding_cache_entries > 0);
+            let embed = embedding.is_some();
 
             #[cfg(feature = "ikgdocstore")]
             let use_kg = docs_cfg.use_kg;
             #[cfg(feature = "ikgdocstore")]
             let kg_cfg = docs_cfg.kg.clone();
 
-            tokio::task::spawn_blocking(move || -> Result<(), AppError> {
-                docs_import::populate_docstore_from_source(
-                    &identity_dir,
-                    &source_dir,
-                    &index_name,
-                )?;
+            let import_dir = identity_dir.clone();
+            let texts = tokio::task::spawn_blocking(move || -> Result<Vec<String>, AppError> {
+                docs_import::populate_docstore_from_source(&import_dir, &source_dir, &index_name)?;
 
                 #[cfg(feature = "ikgdocstore")]
                 if use_kg {
                     docs_import::populate_kgdocstore_from_source(
-                        &identity_dir,
+                        &import_dir,
                         &source_dir,
                         &index_name,
                         &kg_cfg,
                     )?;
                 }
 
-                Ok(())
+                if embed {
+                    docs_import::chunk_texts(&import_dir)
+                } else {
+                    Ok(Vec::new())
+                }
             })
             .await
             .map_err(|e| {
@@ -974,16 +1840,55 @@ impl AgentsSubsystem {
                     agent_id
                 ))
             })??;
+
+            if let Some(model) = embedding {
+                tokio::spawn(embed_cache::reindex(
+                    self.state.bus.clone(),
+                    agent_id.clone(),
+                    identity_dir,
+                    model,
+                    docs_cfg.embedding_cache_entries,
+                    texts,
+                ));
+            }
         }
         Ok(())
     }
 
-    /// Set the LLM pricing rates on the shared state.
+    /// Set the `llm/embed` model docs chunks are embedded with.  Without one
+    /// nothing is embedded or cached: a cached vector is only valid for the
+    /// model that made it.
+    pub fn with_embedding_model(mut self, model: impl Into<String>) -> Self {
+        let state =
+            Arc::get_mut(&mut self.state).expect("AgentsState Arc must be exclusive at build time");
+        state.embedding_model = Some(model.into());
+        self
+    }
+
+    /// Set the active LLM model and its pricing rates on the shared state.
     /// Call this after `new()` when rates are available from config.
-    pub fn with_llm_rates(mut self, rates: ModelRates) -> Self {
+    pub fn with_llm_rates(mut self, model: impl Into<String>, rates: ModelRates) -> Self {
+        let state =
+            Arc::get_mut(&mut self.state).expect("AgentsState Arc must be exclusive at build time");
+        state.llm_model = model.into();
+        state.llm_rates = rates;
+        self
+    }
+
+    /// Set the pricing rates of every configured model, so spend on a
+    /// completion another provider ran is priced at its own rates.
+    pub fn with_model_rates(mut self, rates: HashMap<String, ModelRates>) -> Self {
+        let state =
+            Arc::get_mut(&mut self.state).expect("AgentsState Arc must be exclusive at build time");
+        state.model_rates = rates;
+        self
+    }
+
+    /// Set the default model's context budget on the shared state.
+    pub fn with_context_budget(mut self, budget: ContextBudget) -> Self {
         Arc::get_mut(&mut self.state)
             .expect("AgentsState Arc must be exclusive at build time")
-            .llm_rates = rates;
+            .context_budget = Some(budget);
         self
     }
 
@@ -995,6 +1900,33 @@ impl AgentsSubsystem {
         self
     }
 
+    /// Replace the clock used for the daily output token budget.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        Arc::get_mut(&mut self.state)
+            .expect("AgentsState Arc must be exclusive at build time")
+            .clock = clock;
+        self
+    }
+
+    /// Set per-channel-type default agents (from `[comms.<channel>] default_agent`).
+    pub fn with_channel_defaults(mut self, defaults: HashMap<String, String>) -> Self {
+        self.channel_defaults = defaults;
+        self
+    }
+
+    /// Share the supervisor's maintenance switch (toggled by `manage/maintenance/*`).
+    pub fn with_maintenance(mut self, maintenance: Maintenance) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Record `RequestResponse` agents' exchanges in the caller's session
+    /// transcript (enabled alongside `[comms] auto_session`).
+    pub fn with_session_transcripts(mut self, enabled: bool) -> Self {
+        self.session_transcripts = enabled;
+        self
+    }
+
     /// Attach a health reporter and report initial healthy state.
     pub fn with_health_reporter(mut self, reporter: HealthReporter) -> Self {
         let enabled = self.effective_enabled_agent_ids();
@@ -1014,6 +1946,33 @@ impl AgentsSubsystem {
         self
     }
 
+    /// `reply_tx`, with an agent's reply to `payload` sent through
+    /// [`AgentsState::apply_link_policy`] on its way.
+    fn with_link_policy(
+        &self,
+        payload: &BusPayload,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) -> oneshot::Sender<BusResult> {
+        let is_agent_request = matches!(
+            payload,
+            BusPayload::CommsMessage { .. } | BusPayload::CommsStreamRequest { .. }
+        );
+        if !is_agent_request || self.state.link_policy == LinkPolicy::Allow {
+            return reply_tx;
+        }
+        let (tx, rx) = oneshot::channel();
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            if let Ok(result) = rx.await {
+                let _ = reply_tx.send(state.apply_link_policy(result));
+            }
+        });
+        tx
+    }
+
+    /// Pick the agent for a request.  Precedence: explicit agent in the
+    /// method → exact `channel_map` entry → channel-type default → global
+    /// default.
     fn resolve_agent<'a>(
         &'a self,
         method_agent_id: Option<&'a str>,
@@ -1036,6 +1995,12 @@ impl AgentsSubsystem {
             return Ok(mapped.as_str());
         }
 
+        if let Some(agent) = self.channel_defaults.get(channel_type(channel_id))
+            && (self.enabled_agents.is_empty() || self.enabled_agents.contains(agent))
+        {
+            return Ok(agent.as_str());
+        }
+
         // Use the default agent only if it is enabled, or if no agents have
         // been explicitly enabled (empty set = no restrictions, for backward
         // compat and minimal / test configurations).
@@ -1057,25 +2022,479 @@ impl AgentsSubsystem {
         agent_id: Option<&str>,
     ) -> Result<SessionHandle, AppError> {
         if let Some(agent) = agent_id {
-            let store = self.state.open_agent_store(agent)?;
-            let sessions_root = store.agent_sessions_dir();
-            let index_path = store.agent_sessions_index();
-            return self.state.memory.load_session_in(
-                &sessions_root,
-                &index_path,
-                session_id,
-                Some(agent),
-            );
+            return self.state.open_agent_session(agent, Some(session_id));
         }
 
         self.state.memory.load_session(session_id, None)
     }
 
-    /// Handle `agents/sessions` — return a JSON list of all global sessions.
-    fn handle_session_list(&self, reply_tx: oneshot::Sender<BusResult>) {
+    /// Answer a routed request with a fixed message (maintenance, cooldown)
+    /// without reaching the agent.  Streams get a single content chunk so
+    /// streaming clients render it like a reply.
+    fn reply_fixed(
+        &self,
+        method: &str,
+        payload: BusPayload,
+        message: String,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) {
+        let result = match payload {
+            BusPayload::CommsMessage {
+                channel_id,
+                session_id,
+                ..
+            } => Ok(BusPayload::CommsMessage {
+                channel_id,
+                content: message,
+                session_id,
+                usage: None,
+                timing: None,
+                thinking: None,
+            }),
+            BusPayload::CommsStreamRequest { .. } => {
+                let (tx, rx) = tokio::sync::mpsc::channel(2);
+                let _ = tx.try_send(araliya_llm::StreamChunk::Content(message));
+                let _ = tx.try_send(araliya_llm::StreamChunk::Done {
+                    usage: None,
+                    timing: None,
+                    error: None,
+                    stopped: false,
+                });
+                Ok(BusPayload::LlmStreamResult {
+                    rx: StreamReceiver(rx),
+                    request_id: None,
+                })
+            }
+            _ => Err(BusError::new(
+                ERR_METHOD_NOT_FOUND,
+                format!("unsupported payload for method: {method}"),
+            )),
+        };
+        let _ = reply_tx.send(result);
+    }
+
+    /// Remaining wait if `agent_id` was invoked on `channel_id` less than its
+    /// configured cooldown ago.  Otherwise records this invocation and returns
+    /// `None`; rejected calls do not restart the timer.
+    fn cooldown_wait(&self, agent_id: &str, channel_id: &str) -> Option<Duration> {
+        let cooldown = *self.cooldowns.get(agent_id)?;
+        let now = Instant::now();
+        let mut last = self
+            .last_invocation
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let key = (agent_id.to_string(), channel_id.to_string());
+        if let Some(prev) = last.get(&key) {
+            let elapsed = now.duration_since(*prev);
+            if elapsed < cooldown {
+                return Some(cooldown - elapsed);
+            }
+        }
+        last.insert(key, now);
+        None
+    }
+
+    /// Handle `agents/sessions/open` — create a session for the agent that
+    /// would serve `channel_id` and return `{"session_id", "agent_id"}`.
+    ///
+    /// Expects `JsonRequest { "channel_id": …, "agent_id"?: …, "ephemeral"?: … }`.
+    /// Used by comms `auto_session` to give every channel conversation a
+    /// session; `"ephemeral": true` creates a `tmp`-only session instead.
+    fn handle_session_open(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
+        let req = match payload {
+            BusPayload::JsonRequest { data } => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap_or_default()
+            }
+            _ => serde_json::Value::Null,
+        };
+        let Some(channel_id) = req.get("channel_id").and_then(|v| v.as_str()) else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "agents/sessions/open requires channel_id in JsonRequest payload",
+            )));
+            return;
+        };
+        let requested = req.get("agent_id").and_then(|v| v.as_str());
+
+        let agent_id = match self.resolve_agent(requested, channel_id) {
+            Ok(id) => id,
+            Err(e) => {
+                let _ = reply_tx.send(Err(e));
+                return;
+            }
+        };
+
+        let ephemeral = req
+            .get("ephemeral")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let opened = if ephemeral {
+            self.state.open_ephemeral_session(agent_id)
+        } else {
+            self.state.open_agent_session(agent_id, None)
+        };
+        let result = opened
+            .map(|handle| BusPayload::JsonResponse {
+                data: serde_json::json!({
+                    "session_id": handle.session_id,
+                    "agent_id": agent_id,
+                })
+                .to_string(),
+            })
+            .map_err(|e| BusError::new(-32000, format!("session create failed: {e}")));
+        let _ = reply_tx.send(result);
+    }
+
+    /// Handle `agents/sessions/close` — discard an ephemeral session opened
+    /// by `agents/sessions/open`, once the request it served is answered.
+    ///
+    /// Expects `JsonRequest { "channel_id": …, "agent_id"?: …, "session_id":
+    /// … }` and replies `{"ok": true}`; sessions with a persistent store are
+    /// refused.
+    fn handle_session_close(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
+        let req = match payload {
+            BusPayload::JsonRequest { data } => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap_or_default()
+            }
+            _ => serde_json::Value::Null,
+        };
+        let field = |key: &str| req.get(key).and_then(|v| v.as_str());
+        let (Some(channel_id), Some(session_id)) = (field("channel_id"), field("session_id"))
+        else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "agents/sessions/close requires channel_id and session_id in JsonRequest payload",
+            )));
+            return;
+        };
+        let agent_id = match self.resolve_agent(field("agent_id"), channel_id) {
+            Ok(id) => id,
+            Err(e) => {
+                let _ = reply_tx.send(Err(e));
+                return;
+            }
+        };
+        let result = self
+            .state
+            .close_ephemeral_session(agent_id, session_id)
+            .map(|()| BusPayload::JsonResponse {
+                data: serde_json::json!({ "ok": true }).to_string(),
+            })
+            .map_err(|e| BusError::new(-32000, format!("session close failed: {e}")));
+        let _ = reply_tx.send(result);
+    }
+
+    /// Handle `agents/sessions/switch` — hand a conversation's session from
+    /// the agent serving it to another, keeping its id and transcript, and
+    /// return `{"session_id", "agent_id"}`.
+    ///
+    /// Expects `JsonRequest { "channel_id": …, "session_id": …,
+    /// "from_agent"?: …, "to_agent": … }`; `from_agent` defaults to the agent
+    /// that would serve `channel_id`.  Used by the comms `/switch` command.
+    fn handle_session_switch(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
+        let req = match payload {
+            BusPayload::JsonRequest { data } => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap_or_default()
+            }
+            _ => serde_json::Value::Null,
+        };
+        let field = |key: &str| req.get(key).and_then(|v| v.as_str());
+        let (Some(channel_id), Some(session_id), Some(to_agent)) =
+            (field("channel_id"), field("session_id"), field("to_agent"))
+        else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "agents/sessions/switch requires channel_id, session_id and to_agent in JsonRequest payload",
+            )));
+            return;
+        };
+
+        let agents = self
+            .resolve_agent(field("from_agent"), channel_id)
+            .and_then(|from| Ok((from, self.resolve_agent(Some(to_agent), channel_id)?)));
+        let (from, to) = match agents {
+            Ok(pair) => pair,
+            Err(e) => {
+                let _ = reply_tx.send(Err(e));
+                return;
+            }
+        };
+
+        let moved = if from == to {
+            Ok(())
+        } else {
+            self.state.move_agent_session(from, to, session_id)
+        };
+        let result = moved
+            .map(|()| BusPayload::JsonResponse {
+                data: serde_json::json!({ "session_id": session_id, "agent_id": to }).to_string(),
+            })
+            .map_err(|e| BusError::new(-32000, format!("session switch failed: {e}")));
+        let _ = reply_tx.send(result);
+    }
+
+    /// Handle `agents/sessions/create` — create a session in the agent's own
+    /// session store (the default agent's when none is named), where its
+    /// later messages look it up, and return `{"session_id", "agent_id",
+    /// "store_types"}`.
+    ///
+    /// Expects `JsonRequest { "agent_id"?: …, "store_types"?: […] }`.  Store
+    /// types default to the agent's configured memory (or `basic_session`);
+    /// an unknown agent or store type is rejected with `-32602`.  Lets
+    /// clients set up a conversation before sending its first message.
+    fn handle_session_create(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
+        let req = match payload {
+            BusPayload::JsonRequest { data } => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap_or_default()
+            }
+            _ => serde_json::Value::Null,
+        };
+        let agent_id = req.get("agent_id").and_then(|v| v.as_str());
+        if let Some(agent) = agent_id.filter(|a| !self.agents.contains_key(*a)) {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32602,
+                format!("unknown agent '{agent}'"),
+            )));
+            return;
+        }
+        let agent_id = agent_id.unwrap_or(&self.default_agent);
+
+        let store_types: Vec<String> = match req.get("store_types") {
+            Some(serde_json::Value::Array(types)) => {
+                match types
+                    .iter()
+                    .map(|t| t.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                {
+                    Some(types) => types,
+                    None => {
+                        let _ = reply_tx.send(Err(BusError::new(
+                            -32602,
+                            "store_types must be an array of strings",
+                        )));
+                        return;
+                    }
+                }
+            }
+            Some(_) => {
+                let _ = reply_tx.send(Err(BusError::new(
+                    -32602,
+                    "store_types must be an array of strings",
+                )));
+                return;
+            }
+            None => self
+                .state
+                .agent_memory
+                .get(agent_id)
+                .cloned()
+                .unwrap_or_else(|| vec!["basic_session".to_string()]),
+        };
+        if store_types.is_empty() {
+            let _ = reply_tx.send(Err(BusError::new(-32602, "store_types must not be empty")));
+            return;
+        }
+        if let Some(unknown) = store_types
+            .iter()
+            .find(|t| !self.state.memory.has_store_type(t))
+        {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32602,
+                format!("unknown store type '{unknown}'"),
+            )));
+            return;
+        }
+
+        let types: Vec<&str> = store_types.iter().map(String::as_str).collect();
+        let result = self
+            .state
+            .open_agent_store(agent_id)
+            .and_then(|store| {
+                self.state.memory.create_session_in(
+                    &store.agent_sessions_dir(),
+                    &store.agent_sessions_index(),
+                    &types,
+                    Some(agent_id),
+                )
+            })
+            .map(|handle| BusPayload::JsonResponse {
+                data: serde_json::json!({
+                    "session_id": handle.session_id,
+                    "agent_id": agent_id,
+                    "store_types": store_types,
+                })
+                .to_string(),
+            })
+            .map_err(|e| BusError::new(-32000, format!("session create failed: {e}")));
+        let _ = reply_tx.send(result);
+    }
+
+    /// Route to a stateless agent while recording the exchange in the
+    /// caller's session transcript, so `RequestResponse` agents keep a
+    /// history without any per-agent session code.
+    ///
+    /// Falls back to plain dispatch when the session is not in the agent's
+    /// store.
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch_with_transcript(
+        &self,
+        reg: &AgentRegistration,
+        agent_id: &str,
+        action: String,
+        channel_id: String,
+        content: String,
+        session_id: String,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) {
+        let handle = match self.state.open_agent_session(agent_id, Some(&session_id)) {
+            Ok(h) => h,
+            Err(e) => {
+                tracing::debug!(%agent_id, %session_id, "no agent session to record into: {e}");
+                reg.agent.handle(
+                    action,
+                    channel_id,
+                    content,
+                    Some(session_id),
+                    reply_tx,
+                    self.state.clone(),
+                );
+                return;
+            }
+        };
+
+        let (inner_tx, inner_rx) = oneshot::channel();
+        let user_message = content.clone();
+        reg.agent.handle(
+            action,
+            channel_id,
+            content,
+            Some(session_id),
+            inner_tx,
+            self.state.clone(),
+        );
+
+        tokio::spawn(async move {
+            if let Err(e) = handle.transcript_append("user", &user_message).await {
+                tracing::warn!("agents: transcript_append(user) failed: {e}");
+            }
+            let result = inner_rx
+                .await
+                .unwrap_or_else(|_| Err(BusError::new(-32000, "agent dropped its reply")));
+            let result = match result {
+                Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content,
+                    usage,
+                    timing,
+                    thinking,
+                    ..
+                }) => {
+                    if let Err(e) = handle.transcript_append("assistant", &content).await {
+                        tracing::warn!("agents: transcript_append(assistant) failed: {e}");
+                    }
+                    Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content,
+                        session_id: Some(handle.session_id.clone()),
+                        usage,
+                        timing,
+                        thinking,
+                    })
+                }
+                other => other,
+            };
+            let _ = reply_tx.send(result);
+        });
+    }
+
+    /// Handle `agents/{id}/debug_prompt` — the prompts agent `id` would send
+    /// for a `CommsMessage`, without calling the LLM.  Needs `[agents]
+    /// debug_prompt`, since the reply carries retrieved context.
+    fn handle_debug_prompt(
+        &self,
+        agent_id: Option<String>,
+        payload: BusPayload,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) {
+        if !self.debug_prompt {
+            let _ = reply_tx.send(Err(BusError::new(
+                ERR_METHOD_NOT_FOUND,
+                "debug_prompt is disabled (set [agents] debug_prompt = true)",
+            )));
+            return;
+        }
+        let Some(agent_id) = agent_id else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "debug_prompt needs an agent: agents/{id}/debug_prompt",
+            )));
+            return;
+        };
+        let BusPayload::CommsMessage {
+            content,
+            session_id,
+            ..
+        } = payload
+        else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "agents/{id}/debug_prompt requires a CommsMessage payload",
+            )));
+            return;
+        };
+        match self.agents.get(&agent_id) {
+            Some(reg) => reg
+                .agent
+                .debug_prompt(content, session_id, reply_tx, self.state.clone()),
+            None => {
+                let _ = reply_tx.send(Err(BusError::new(
+                    ERR_METHOD_NOT_FOUND,
+                    format!("agent not loaded: {agent_id}"),
+                )));
+            }
+        }
+    }
+
+    /// Handle `agents/sessions` — return a JSON list of global sessions.
+    ///
+    /// Archived sessions are left out unless the payload is
+    /// `JsonRequest { "include_archived": true }`.  Sessions are listed most
+    /// recently updated first; `"sort": "created_at" | "session_id"` picks
+    /// another order.
+    fn handle_session_list(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
         let memory = self.state.memory.clone();
+        let request = match payload {
+            BusPayload::JsonRequest { data } => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap_or_default()
+            }
+            _ => serde_json::Value::Null,
+        };
+        let include_archived = request
+            .get("include_archived")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let sort = match request.get("sort").and_then(|v| v.as_str()) {
+            None => SessionSort::default(),
+            Some(key) => match SessionSort::parse(key) {
+                Some(sort) => sort,
+                None => {
+                    let _ = reply_tx.send(Err(BusError::new(
+                        -32602,
+                        format!(
+                            "unknown sort key '{key}' (expected updated_at, created_at or session_id)"
+                        ),
+                    )));
+                    return;
+                }
+            },
+        };
 
-        let sessions = match memory.list_sessions() {
+        let listed = if include_archived {
+            memory.list_sessions_including_archived()
+        } else {
+            memory.list_sessions()
+        };
+        let sessions = match listed {
             Ok(s) => s,
             Err(e) => {
                 let _ = reply_tx.send(Err(BusError::new(-32000, format!("memory error: {e}"))));
@@ -1083,17 +2502,41 @@ impl AgentsSubsystem {
             }
         };
 
-        let sessions_root = memory.sessions_root().to_path_buf();
+        // The last spend update is the freshest activity timestamp; order by
+        // what is reported.
+        let mut roots = vec![(memory.sessions_root().to_path_buf(), sessions)];
+        match self.agent_scoped_sessions(include_archived) {
+            Ok(scoped) => roots.extend(scoped),
+            Err(e) => {
+                let _ = reply_tx.send(Err(BusError::new(-32000, format!("memory error: {e}"))));
+                return;
+            }
+        }
+        let mut seen = HashSet::new();
+        let mut sessions = Vec::new();
+        for (sessions_root, listed) in roots {
+            for mut s in listed {
+                if !seen.insert(s.session_id.clone()) {
+                    continue;
+                }
+                if let Some(updated_at) = read_session_updated_at(&sessions_root, &s.session_id) {
+                    s.updated_at = Some(updated_at);
+                }
+                sessions.push(s);
+            }
+        }
+        sort.sort(&mut sessions);
+
         let body = serde_json::json!({
             "sessions": sessions.iter().map(|s| {
-                let updated_at = read_session_updated_at(&sessions_root, &s.session_id)
-                    .unwrap_or_else(|| s.created_at.clone());
                 serde_json::json!({
                     "session_id": s.session_id,
                     "created_at": s.created_at,
-                    "updated_at": updated_at,
+                    "updated_at": s.updated_at.as_deref().unwrap_or(&s.created_at),
                     "store_types": s.store_types,
                     "last_agent": s.last_agent,
+                    "archived": s.archived,
+                    "title": s.title,
                 })
             }).collect::<Vec<_>>()
         });
@@ -1103,6 +2546,27 @@ impl AgentsSubsystem {
         }));
     }
 
+    /// Sessions indexed in each agent's own store (chat sessions, sessions
+    /// from `agents/sessions/create`, …), with the directory they live in.
+    /// Diagnostic sessions are left out, and archived ones unless asked for.
+    fn agent_scoped_sessions(
+        &self,
+        include_archived: bool,
+    ) -> Result<Vec<(std::path::PathBuf, Vec<SessionInfo>)>, AppError> {
+        let mut agent_ids: Vec<&String> = self.state.agent_identities.keys().collect();
+        agent_ids.sort();
+        let mut scoped = Vec::new();
+        for agent_id in agent_ids {
+            let store = self.state.open_agent_store(agent_id)?;
+            let mut sessions = MemorySystem::list_sessions_in(&store.agent_sessions_index())?;
+            sessions.retain(|s| include_archived || !s.archived);
+            if !sessions.is_empty() {
+                scoped.push((store.agent_sessions_dir(), sessions));
+            }
+        }
+        Ok(scoped)
+    }
+
     /// Handle `agents/session` — return the primary session transcript for an agent.
     ///
     /// Reads `active_session_id` from the agent's KV store and returns
@@ -1155,6 +2619,7 @@ impl AgentsSubsystem {
                             "role": e.role,
                             "timestamp": e.timestamp,
                             "content": e.content,
+                            "meta": e.meta,
                         })
                     })
                     .collect::<Vec<_>>(),
@@ -1390,6 +2855,35 @@ impl AgentsSubsystem {
         }));
     }
 
+    /// Handle `agents/resolve` — name the loaded agent a `CommsMessage`
+    /// from its channel would reach, without running it (the startup
+    /// self-test's default-agent check).
+    fn handle_resolve(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
+        let BusPayload::CommsMessage { channel_id, .. } = payload else {
+            let _ = reply_tx.send(Err(BusError::new(
+                -32600,
+                "agents/resolve requires a CommsMessage payload",
+            )));
+            return;
+        };
+        let reply = self
+            .resolve_agent(None, &channel_id)
+            .and_then(|agent_id| {
+                if self.agents.contains_key(agent_id) {
+                    Ok(agent_id)
+                } else {
+                    Err(BusError::new(
+                        ERR_METHOD_NOT_FOUND,
+                        format!("agent not loaded: {agent_id}"),
+                    ))
+                }
+            })
+            .map(|agent_id| BusPayload::JsonResponse {
+                data: serde_json::json!({ "agent_id": agent_id }).to_string(),
+            });
+        let _ = reply_tx.send(reply);
+    }
+
     /// Handle `agents/list` — return metadata for all registered agents.
     ///
     /// Each entry now includes a `runtime_class` field (v0.6 PR1) so that
@@ -1463,6 +2957,7 @@ impl AgentsSubsystem {
                             "role": e.role,
                             "timestamp": e.timestamp,
                             "content": e.content,
+                            "meta": e.meta,
                         })
                     })
                     .collect::<Vec<_>>(),
@@ -1545,7 +3040,54 @@ impl AgentsSubsystem {
         });
     }
 
-    /// Handle `agents/sessions/files` — return files in the session directory.
+    /// Handle `agents/sessions/memory/clear` — empty the working memory,
+    /// leaving the transcript untouched.
+    fn handle_session_memory_clear(
+        &self,
+        payload: BusPayload,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) {
+        let (session_id, agent_id) = match payload {
+            BusPayload::SessionQuery {
+                session_id,
+                agent_id,
+            } => (session_id, agent_id),
+            _ => {
+                let _ = reply_tx.send(Err(BusError::new(-32600, "expected SessionQuery payload")));
+                return;
+            }
+        };
+
+        let handle = match self.load_scoped_session(&session_id, agent_id.as_deref()) {
+            Ok(h) => h,
+            Err(e) => {
+                let _ = reply_tx.send(Err(BusError::new(-32000, format!("{e}"))));
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            if let Err(e) = handle.working_memory_write("").await {
+                let _ = reply_tx.send(Err(BusError::new(
+                    -32000,
+                    format!("working memory clear failed: {e}"),
+                )));
+                return;
+            }
+
+            let body = serde_json::json!({
+                "session_id": session_id,
+                "agent_id": agent_id,
+                "cleared": true,
+            });
+
+            let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                data: body.to_string(),
+            }));
+        });
+    }
+
+    /// Handle `agents/sessions/files` — return files in the session directory.
     fn handle_session_files(&self, payload: BusPayload, reply_tx: oneshot::Sender<BusResult>) {
         let (session_id, agent_id) = match payload {
             BusPayload::SessionQuery {
@@ -1674,7 +3216,8 @@ impl BusHandler for AgentsSubsystem {
     /// the supervisor loop returns immediately after this call.
     ///
     /// Session queries (`agents/sessions`, `agents/sessions/detail`,
-    /// `agents/sessions/memory`, `agents/sessions/files`) are
+    /// `agents/sessions/memory`, `agents/sessions/memory/clear`,
+    /// `agents/sessions/files`) are
     /// intercepted before agent routing to return session metadata.
     fn handle_request(
         &self,
@@ -1682,6 +3225,29 @@ impl BusHandler for AgentsSubsystem {
         payload: BusPayload,
         reply_tx: oneshot::Sender<BusResult>,
     ) {
+        // ── Request scope: a handoff's chain or a new one, and any format ──
+        let (context, payload) = match payload {
+            BusPayload::AgentRequest {
+                chain,
+                response_format,
+                payload,
+            } => (Some((chain, response_format)), *payload),
+            payload => (None, payload),
+        };
+        if context.is_some() || !request::in_request() {
+            let (chain, response_format) = context.unwrap_or_default();
+            // A handoff's reply goes back to the agent that handed off; any
+            // other reply leaves the subsystem and gets the link policy.
+            let reply_tx = match chain {
+                None => self.with_link_policy(&payload, reply_tx),
+                Some(_) => reply_tx,
+            };
+            let chain = chain.unwrap_or_else(AgentChain::start);
+            return request::scope(chain, response_format, || {
+                self.handle_request(method, payload, reply_tx)
+            });
+        }
+
         // ── Subsystem-level health (must be intercepted before parse_method
         //    which would interpret "agents/health" as agent_id="health") ──────
         if method == "agents/health" {
@@ -1743,6 +3309,10 @@ impl BusHandler for AgentsSubsystem {
             self.handle_agent_kg_graph(payload, reply_tx);
             return;
         }
+        if method == "agents/resolve" {
+            self.handle_resolve(payload, reply_tx);
+            return;
+        }
 
         // ── Session queries (intercepted before agent routing) ──────
         if method == "agents/session" {
@@ -1778,7 +3348,7 @@ impl BusHandler for AgentsSubsystem {
             return;
         }
         if method == "agents/sessions" {
-            self.handle_session_list(reply_tx);
+            self.handle_session_list(payload, reply_tx);
             return;
         }
         if method == "agents/sessions/detail" {
@@ -1789,6 +3359,10 @@ impl BusHandler for AgentsSubsystem {
             self.handle_session_memory(payload, reply_tx);
             return;
         }
+        if method == "agents/sessions/memory/clear" {
+            self.handle_session_memory_clear(payload, reply_tx);
+            return;
+        }
         if method == "agents/sessions/files" {
             self.handle_session_files(payload, reply_tx);
             return;
@@ -1797,6 +3371,22 @@ impl BusHandler for AgentsSubsystem {
             self.handle_session_debug(payload, reply_tx);
             return;
         }
+        if method == "agents/sessions/open" {
+            self.handle_session_open(payload, reply_tx);
+            return;
+        }
+        if method == "agents/sessions/close" {
+            self.handle_session_close(payload, reply_tx);
+            return;
+        }
+        if method == "agents/sessions/create" {
+            self.handle_session_create(payload, reply_tx);
+            return;
+        }
+        if method == "agents/sessions/switch" {
+            self.handle_session_switch(payload, reply_tx);
+            return;
+        }
 
         // ── Agent routing ───────────────────────────────────────────
         let (method_agent_id, action) = match parse_method(method) {
@@ -1865,6 +3455,33 @@ impl BusHandler for AgentsSubsystem {
             }
         }
 
+        // ── Prompt inspection: no LLM call, so no cooldown or maintenance ──
+        if action == "debug_prompt" {
+            self.handle_debug_prompt(method_agent_id, payload, reply_tx);
+            return;
+        }
+
+        // ── Maintenance mode: answer without reaching any agent ─────
+        if self.maintenance.is_enabled() {
+            let message = self.maintenance.message().to_string();
+            self.reply_fixed(method, payload, message, reply_tx);
+            return;
+        }
+
+        // ── Per-agent cooldown per channel ──────────────────────────
+        if let BusPayload::CommsMessage { channel_id, .. }
+        | BusPayload::CommsStreamRequest { channel_id, .. } = &payload
+            && let Ok(agent_id) = self.resolve_agent(method_agent_id.as_deref(), channel_id)
+            && let Some(wait) = self.cooldown_wait(agent_id, channel_id)
+        {
+            let message = format!(
+                "Please wait {:.1}s before calling '{agent_id}' again.",
+                wait.as_secs_f64()
+            );
+            self.reply_fixed(method, payload, message, reply_tx);
+            return;
+        }
+
         match payload {
             BusPayload::CommsMessage {
                 channel_id,
@@ -1880,6 +3497,21 @@ impl BusHandler for AgentsSubsystem {
                     }
                 };
                 match self.agents.get(agent_id) {
+                    Some(reg)
+                        if self.session_transcripts
+                            && reg.runtime_class == AgentRuntimeClass::RequestResponse
+                            && session_id.is_some() =>
+                    {
+                        self.dispatch_with_transcript(
+                            reg,
+                            agent_id,
+                            action,
+                            channel_id,
+                            content,
+                            session_id.unwrap_or_default(),
+                            reply_tx,
+                        )
+                    }
                     Some(reg) => reg.agent.handle(
                         action,
                         channel_id,
@@ -1943,7 +3575,12 @@ impl BusHandler for AgentsSubsystem {
                 if id == self.default_agent {
                     node.name = format!("{name} (default)");
                 }
-                node
+                match self.agents.get(&id) {
+                    Some(reg) => node.with_metadata(
+                        serde_json::to_value(reg.agent.capabilities()).unwrap_or_default(),
+                    ),
+                    None => node,
+                }
             })
             .collect();
         children.sort_by(|a, b| a.id.cmp(&b.id));
@@ -2025,6 +3662,39 @@ fn detect_agent_store_types(
     out
 }
 
+/// Create the on-disk stores an agent declares in `memory = [...]` under its
+/// identity dir.  Idempotent — existing stores are opened as they are.
+/// Session stores are created with the agent's first session.
+#[cfg_attr(
+    not(any(feature = "idocstore", feature = "ikgdocstore")),
+    allow(unused_variables)
+)]
+fn provision_agent_stores(
+    agent_id: &str,
+    identity_dir: &std::path::Path,
+    stores: &[String],
+) -> Result<(), AppError> {
+    for store in stores {
+        match store.as_str() {
+            #[cfg(feature = "idocstore")]
+            "docstore" => {
+                araliya_memory::stores::docstore::IDocStore::open(identity_dir)?;
+            }
+            #[cfg(feature = "ikgdocstore")]
+            "kgdocstore" => {
+                araliya_memory::stores::kg_docstore::IKGDocStore::open(identity_dir)?;
+            }
+            "basic_session" | "tmp" => {}
+            other => tracing::warn!(
+                agent_id,
+                store = other,
+                "agent declares a memory store this build cannot provide"
+            ),
+        }
+    }
+    Ok(())
+}
+
 /// Derive a [`ComponentStatusResponse`] from an optional [`HealthReporter`].
 ///
 /// Returns `running` when the reporter has not yet written any state (or is
@@ -2064,6 +3734,15 @@ fn parse_method(method: &str) -> Result<(Option<String>, String), BusError> {
     }
 }
 
+/// Channel type of a comms channel id: the leading alphabetic prefix
+/// (`"telegram0:1234"` → `"telegram"`, `"axum0"` → `"axum"`).
+fn channel_type(channel_id: &str) -> &str {
+    let end = channel_id
+        .find(|c: char| !c.is_ascii_alphabetic() && c != '_')
+        .unwrap_or(channel_id.len());
+    &channel_id[..end]
+}
+
 #[cfg(test)]
 #[allow(clippy::collapsible_if)]
 mod tests {
@@ -2072,6 +3751,10 @@ mod tests {
     use araliya_core::bus::dispatch::BusHandler;
     use araliya_core::bus::handle::SupervisorBus;
     use araliya_core::bus::message::BusMessage;
+    use araliya_core::config::{
+        DEFAULT_AGENT_ERROR_MESSAGE, DEFAULT_AGENT_MAX_DEPTH, DEFAULT_EMPTY_INPUT_REPLY,
+        DEFAULT_EMPTY_REPLY,
+    };
     use tokio::sync::oneshot;
 
     fn echo_bus() -> (SupervisorBus, BusHandle) {
@@ -2102,6 +3785,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2110,6 +3795,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2152,6 +3859,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2160,6 +3869,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2199,6 +3930,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2207,6 +3940,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2243,6 +3998,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2251,6 +4008,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2294,6 +4073,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2302,6 +4083,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2367,6 +4170,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2375,6 +4180,28 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2400,89 +4227,16 @@ mod tests {
         }
     }
 
-    /// Verifies news fetches, calls LLM, and returns the LLM summary.
-    #[cfg(feature = "plugin-news-agent")]
     #[tokio::test]
-    async fn news_agent_summarises_via_llm() {
+    async fn basic_chat_answers_empty_input_without_llm() {
         let bus = SupervisorBus::new(16);
         let handle = bus.handle.clone();
         let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
-
-        // Handle two sequential bus requests: tool then LLM.
-        tokio::spawn(async move {
-            // First: tools/execute
-            if let Some(BusMessage::Request {
-                method,
-                payload,
-                reply_tx,
-                ..
-            }) = rx.recv().await
-            {
-                assert_eq!(method, "tools/execute");
-                if let BusPayload::ToolRequest { tool, action, .. } = payload {
-                    assert_eq!(tool, "newsmail_aggregator");
-                    assert_eq!(action, "get");
-                }
-                let _ = reply_tx.send(Ok(BusPayload::ToolResponse {
-                    tool: "newsmail_aggregator".to_string(),
-                    action: "get".to_string(),
-                    ok: true,
-                    data_json: Some("[{\"subject\":\"Test Headline\",\"from\":\"news@example.com\",\"date\":\"2026-02-21\"}]".to_string()),
-                    error: None,
-                }));
-            }
-            // Second: llm/complete
-            if let Some(BusMessage::Request {
-                method,
-                payload,
-                reply_tx,
-                ..
-            }) = rx.recv().await
-            {
-                assert_eq!(method, "llm/complete");
-                if let BusPayload::LlmRequest {
-                    content,
-                    channel_id,
-                    ..
-                } = payload
-                {
-                    assert!(
-                        content.contains("Test Headline"),
-                        "prompt should contain subject"
-                    );
-                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
-                        channel_id,
-                        content: "Summary: Test Headline from news@example.com.".to_string(),
-                        session_id: None,
-                        usage: None,
-
-                        timing: None,
-
-                        thinking: None,
-                    }));
-                }
-            }
-        });
-
         let cfg = AgentsConfig {
-            default_agent: "news".to_string(),
-            enabled: HashSet::from(["news".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            default_agent: "basic_chat".to_string(),
+            enabled: HashSet::from(["basic_chat".to_string()]),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -2491,12 +4245,10 @@ mod tests {
             "agents",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "".to_string(),
+                content: "  \n\t".to_string(),
                 session_id: None,
                 usage: None,
-
                 timing: None,
-
                 thinking: None,
             },
             tx,
@@ -2504,199 +4256,230 @@ mod tests {
 
         match rx_reply.await.unwrap() {
             Ok(BusPayload::CommsMessage { content, .. }) => {
-                assert!(
-                    content.contains("Summary:"),
-                    "response should be LLM summary, got: {content}"
-                );
+                assert_eq!(content, DEFAULT_EMPTY_INPUT_REPLY)
             }
             other => panic!("unexpected response: {other:?}"),
         }
+        assert!(rx.try_recv().is_err(), "empty input must not reach the LLM");
     }
 
-    /// Empty inbox skips LLM entirely and returns a fixed message.
-    #[cfg(feature = "plugin-news-agent")]
+    #[cfg(feature = "plugin-agentic-chat")]
     #[tokio::test]
-    async fn news_agent_empty_inbox_skips_llm() {
+    async fn agentic_chat_answers_empty_input_without_llm() {
         let bus = SupervisorBus::new(16);
         let handle = bus.handle.clone();
         let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
-
-        tokio::spawn(async move {
-            // Only one bus message: the tool request.
-            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
-                let _ = reply_tx.send(Ok(BusPayload::ToolResponse {
-                    tool: "newsmail_aggregator".to_string(),
-                    action: "get".to_string(),
-                    ok: true,
-                    data_json: Some("[]".to_string()),
-                    error: None,
-                }));
-            }
-            // No further messages — asserting LLM is not called.
-        });
-
         let cfg = AgentsConfig {
-            default_agent: "news".to_string(),
-            enabled: HashSet::from(["news".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            default_agent: "agentic-chat".to_string(),
+            enabled: HashSet::from(["agentic-chat".to_string()]),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request("agents", chat_message(" \n"), tx);
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, DEFAULT_EMPTY_INPUT_REPLY)
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        // Streamed requests get the same reply as a one-chunk stream.
         let (tx, rx_reply) = oneshot::channel();
         agents.handle_request(
             "agents",
-            BusPayload::CommsMessage {
+            BusPayload::CommsStreamRequest {
                 channel_id: "pty0".to_string(),
-                content: "".to_string(),
+                content: String::new(),
                 session_id: None,
-                usage: None,
-
-                timing: None,
-
-                thinking: None,
             },
             tx,
         );
+        let Ok(BusPayload::LlmStreamResult {
+            rx: StreamReceiver(mut chunks),
+            ..
+        }) = rx_reply.await.unwrap()
+        else {
+            panic!("expected a stream");
+        };
+        assert!(
+            matches!(chunks.recv().await, Some(araliya_llm::StreamChunk::Content(text)) if text == DEFAULT_EMPTY_INPUT_REPLY)
+        );
+        assert!(rx.try_recv().is_err(), "empty input must not reach the LLM");
+    }
+
+    fn moderated_basic_chat_config() -> AgentsConfig {
+        AgentsConfig {
+            default_agent: "basic_chat".to_string(),
+            enabled: HashSet::from(["basic_chat".to_string()]),
+            moderation: Some(araliya_core::config::ModerationConfig {
+                blocked_terms: vec!["forbidden".to_string()],
+                blocked_patterns: vec![r"\bsecret\s+plan\b".to_string()],
+                refusal: "Not here.".to_string(),
+            }),
+            ..AgentsConfig::default()
+        }
+    }
+
+    fn chat_message(content: &str) -> BusPayload {
+        BusPayload::CommsMessage {
+            channel_id: "pty0".to_string(),
+            content: content.to_string(),
+            session_id: None,
+            usage: None,
+            timing: None,
+            thinking: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn moderation_refuses_blocked_input_before_llm() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+        let agents = AgentsSubsystem::new(moderated_basic_chat_config(), handle, memory).unwrap();
+
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request("agents", chat_message("tell me the secret  plan"), tx);
 
         match rx_reply.await.unwrap() {
-            Ok(BusPayload::CommsMessage { content, .. }) => {
-                assert_eq!(content, "No new news emails.");
-            }
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "Not here."),
             other => panic!("unexpected response: {other:?}"),
         }
+        assert!(
+            rx.try_recv().is_err(),
+            "blocked input must not reach the LLM"
+        );
     }
 
-    /// Basic health check for docs agent.
-    #[cfg(feature = "plugin-docs")]
     #[tokio::test]
-    async fn docs_agent_health() {
-        let (_bus, handle) = echo_bus();
-        let (_dir, memory) = test_memory();
-        let cfg = AgentsConfig {
-            default_agent: "docs".to_string(),
-            enabled: HashSet::from(["docs".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
-        };
-        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-
-        let (tx, rx) = oneshot::channel();
-        agents.handle_request(
-            "agents/docs/health",
-            BusPayload::CommsMessage {
-                channel_id: "pty0".to_string(),
-                content: "".to_string(),
-                session_id: None,
-                usage: None,
-
-                timing: None,
-
-                thinking: None,
-            },
-            tx,
-        );
-        match rx.await.unwrap() {
-            Ok(BusPayload::CommsMessage { content, .. }) => {
-                assert!(content.contains("docs component"));
-            }
-            other => panic!("unexpected: {other:?}"),
-        }
-    }
-
-    /// Asking the docs agent reads the specified file and forwards prompt to LLM.
-    #[cfg(feature = "plugin-docs")]
-    #[tokio::test]
-    async fn docs_agent_reads_file_and_queries_llm() {
+    async fn moderation_replaces_blocked_output_after_llm() {
         let bus = SupervisorBus::new(16);
         let handle = bus.handle.clone();
         let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
 
-        // Prepare a temp docs directory with an index.md containing known content.
-        let docs_tmp = tempfile::TempDir::new().unwrap();
-        std::fs::write(docs_tmp.path().join("index.md"), "the quick brown fox").unwrap();
-        let docsdir = docs_tmp.path().to_str().unwrap().to_string();
-
-        // Fake LLM responder — handles two bus requests from the AgenticLoop:
-        //   1. Instruction pass: return a docs_search tool call
-        //   2. Response pass: reply with "brown"
-        //
-        // Note: we don't assert on prompt content because the prompt template
-        // files live at the workspace root (config/prompts/) but binary tests
-        // run from the package root (crates/araliya-bot/), so PromptBuilder
-        // silently skips missing template files.
         tokio::spawn(async move {
-            // ── Instruction pass ──────────────────────────────────────
-            if let Some(BusMessage::Request {
-                method,
-                payload,
-                reply_tx,
-                ..
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
             }) = rx.recv().await
             {
-                assert_eq!(method, "llm/complete");
-                if let BusPayload::LlmRequest { channel_id, .. } = payload {
-                    // Return a tool call that invokes docs_search.
+                if let BusPayload::LlmRequest {
+                    channel_id,
+                    content,
+                    ..
+                } = payload
+                {
+                    let reply = if content.contains("leak") {
+                        "Here is the Forbidden answer."
+                    } else {
+                        "All good."
+                    };
                     let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                         channel_id,
-                        content: r#"[{"tool":"docs_search","action":"search","params":{"query":"what color"}}]"#.to_string(),
+                        content: reply.to_string(),
                         session_id: None,
                         usage: None,
-
                         timing: None,
-
-                        thinking: None,
-
+                        thinking: Some("reasoning about it".to_string()),
                     }));
                 }
             }
+        });
 
-            // ── Response pass ─────────────────────────────────────────
-            if let Some(BusMessage::Request {
-                method,
-                payload,
-                reply_tx,
-                ..
+        let agents = AgentsSubsystem::new(moderated_basic_chat_config(), handle, memory).unwrap();
+        let ask = |content: &str| {
+            let (tx, rx_reply) = oneshot::channel();
+            agents.handle_request("agents", chat_message(content), tx);
+            rx_reply
+        };
+
+        match ask("please leak it").await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                content, thinking, ..
+            }) => {
+                assert_eq!(content, "Not here.");
+                assert!(thinking.is_none());
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        match ask("hello").await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "All good."),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn link_policy_applies_once_to_replies_leaving_the_subsystem() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            // Not idempotent: applied twice, the link would be wrapped twice.
+            link_policy: LinkPolicy::Rewrite {
+                template: "<{url}>".to_string(),
+            },
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request("agents", chat_message("see https://x.io now"), tx);
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, "see <https://x.io> now")
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        // A handoff's reply is left for the agent that handed off.
+        let (tx, rx_reply) = oneshot::channel();
+        let handoff = BusPayload::AgentRequest {
+            chain: Some(AgentChain::start()),
+            response_format: None,
+            payload: Box::new(chat_message("see https://x.io now")),
+        };
+        agents.handle_request("agents/echo", handoff, tx);
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, "see https://x.io now")
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn empty_llm_reply_is_replaced_and_logged() {
+        use araliya_core::logger::LogBuffer;
+        use araliya_core::obs::ObsLevel;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let logs = LogBuffer::new(16);
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(logs.layer()));
+
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+
+        // Fake provider: every completion comes back blank.
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
             }) = rx.recv().await
             {
-                assert_eq!(method, "llm/complete");
                 if let BusPayload::LlmRequest { channel_id, .. } = payload {
                     let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                         channel_id,
-                        content: "brown".to_string(),
+                        content: " \n".to_string(),
                         session_id: None,
                         usage: None,
-
                         timing: None,
-
                         thinking: None,
                     }));
                 }
@@ -2704,152 +4487,338 @@ mod tests {
         });
 
         let cfg = AgentsConfig {
-            default_agent: "docs".to_string(),
-            enabled: HashSet::from(["docs".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: HashMap::from([(
-                "docs".to_string(),
-                DocsAgentConfig {
-                    docsdir: Some(docsdir),
-                    index: Some("index.md".to_string()),
-                    use_kg: false,
-                    kg: araliya_core::config::DocsKgConfig::default(),
-                },
-            )]),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            default_agent: "basic_chat".to_string(),
+            enabled: HashSet::from(["basic_chat".to_string()]),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        // Populate the docs docstore before handling any queries.
-        agents.init_docs().await.unwrap();
 
-        let (tx, rx) = oneshot::channel();
+        let (tx, rx_reply) = oneshot::channel();
         agents.handle_request(
-            "agents/docs/ask",
+            "agents",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "what color".to_string(),
+                content: "hello".to_string(),
                 session_id: None,
                 usage: None,
-
                 timing: None,
-
                 thinking: None,
             },
             tx,
         );
 
-        match rx.await.unwrap() {
-            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "brown"),
-            other => panic!("unexpected: {other:?}"),
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, DEFAULT_EMPTY_REPLY)
+            }
+            other => panic!("unexpected response: {other:?}"),
         }
+        let warned = logs.recent(ObsLevel::Warn, 16);
+        assert!(
+            warned
+                .iter()
+                .any(|e| e.message.contains("empty llm reply")
+                    && e.fields["agent_id"] == "basic_chat"),
+            "{warned:?}"
+        );
     }
 
-    /// Asking the docs agent when the docstore is empty returns an error.
-    #[cfg(feature = "plugin-docs")]
     #[tokio::test]
-    async fn docs_agent_missing_file_errors() {
-        let (_bus, handle) = echo_bus();
+    async fn allow_empty_input_forwards_to_llm() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            if let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = rx.recv().await
+                && let BusPayload::LlmRequest { channel_id, .. } = payload
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content: "[fake] reached llm".to_string(),
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
         let cfg = AgentsConfig {
-            default_agent: "docs".to_string(),
-            enabled: HashSet::from(["docs".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            // No docsdir configured — docstore will remain empty.
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            default_agent: "basic_chat".to_string(),
+            enabled: HashSet::from(["basic_chat".to_string()]),
+            empty_input_reply: "Ask away.".to_string(),
+            allow_empty_input: HashSet::from(["basic_chat".to_string()]),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        // Do NOT call init_docs — docstore stays empty.
 
-        let (tx, rx) = oneshot::channel();
+        let (tx, rx_reply) = oneshot::channel();
         agents.handle_request(
-            "agents/docs/ask",
+            "agents",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "hi".to_string(),
+                content: String::new(),
                 session_id: None,
                 usage: None,
-
                 timing: None,
-
                 thinking: None,
             },
             tx,
         );
-        assert!(rx.await.unwrap().is_err());
+
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, "[fake] reached llm")
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
-    #[tokio::test]
-    async fn test_get_or_create_subagent() {
-        let (_bus, handle) = echo_bus();
+    #[cfg(all(feature = "idocstore", feature = "plugin-echo"))]
+    #[test]
+    fn declared_docstore_is_created_at_startup() {
         let (_dir, memory) = test_memory();
-        let cfg = AgentsConfig {
+        let cfg = || AgentsConfig {
             default_agent: "echo".to_string(),
             enabled: HashSet::from(["echo".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            agent_memory: HashMap::from([("echo".to_string(), vec!["docstore".to_string()])]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg(), echo_bus().1, memory.clone()).unwrap();
+        let docstore = agents.agent_identity_dirs()["echo"].join("docstore");
+        assert!(docstore.is_dir(), "{} was not created", docstore.display());
+
+        // A second startup finds the store in place.
+        AgentsSubsystem::new(cfg(), echo_bus().1, memory).unwrap();
+        assert!(docstore.is_dir());
+    }
+
+    /// The summarize agent forwards the pasted text untouched and states the
+    /// configured style and word budget in the system prompt.
+    #[cfg(feature = "plugin-summarize")]
+    #[tokio::test]
+    async fn summarize_agent_sends_styled_system_prompt() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            if let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "llm/complete");
+                if let BusPayload::LlmRequest {
+                    channel_id,
+                    content,
+                    system,
+                    ..
+                } = payload
+                {
+                    assert_eq!(content, "Long meeting notes.\nWe agreed to ship on Friday.");
+                    let system = system.expect("summarize must send a system prompt");
+                    assert!(system.contains("paragraphs"), "style missing: {system}");
+                    assert!(
+                        system.contains("at most 30 words"),
+                        "budget missing: {system}"
+                    );
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "The team will ship on Friday.".to_string(),
+                        session_id: None,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "summarize".to_string(),
+            enabled: HashSet::from(["summarize".to_string()]),
+            summarize: Some(araliya_core::config::SummarizeAgentConfig {
+                style: araliya_core::config::SummaryStyle::Prose,
+                max_words: 30,
+            }),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-        let subagent = agents
-            .state
-            .get_or_create_subagent("echo", "worker1")
-            .unwrap();
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "Long meeting notes.\nWe agreed to ship on Friday.".to_string(),
+                session_id: None,
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
 
-        assert!(subagent.identity_dir.exists());
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, "The team will ship on Friday.")
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
 
-        let dir_name = subagent.identity_dir.file_name().unwrap().to_str().unwrap();
-        assert!(dir_name.starts_with("worker1-"));
-        assert!(dir_name.ends_with(&subagent.public_id));
+    /// Answers `question` through the sysinfo agent against a bus whose
+    /// status methods reply with fixed data; fails on any LLM call.
+    #[cfg(feature = "plugin-sysinfo")]
+    async fn ask_sysinfo(question: &str) -> String {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
 
-        let parent_dir = subagent.identity_dir.parent().unwrap();
-        assert!(parent_dir.ends_with("subagents"));
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method, reply_tx, ..
+            }) = rx.recv().await
+            {
+                let data = match method.as_str() {
+                    "manage/http/get" => serde_json::json!({
+                        "status": "ok",
+                        "uptime_ms": 7_500_000,
+                        "subsystems": [{ "id": "llm", "healthy": true }],
+                    }),
+                    "llm/detailed_status" => serde_json::json!({
+                        "provider": "local",
+                        "model": "qwen-7b",
+                        "context_window": 8192,
+                    }),
+                    other => panic!("sysinfo must not call {other}"),
+                };
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: data.to_string(),
+                }));
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "sysinfo".to_string(),
+            enabled: HashSet::from(["sysinfo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: question.to_string(),
+                session_id: None,
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => content,
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
+    #[cfg(feature = "plugin-sysinfo")]
     #[tokio::test]
-    async fn component_info_shows_only_enabled_agents() {
-        let (_bus, handle) = echo_bus();
+    async fn sysinfo_agent_reports_uptime_from_management() {
+        assert_eq!(ask_sysinfo("what's your uptime?").await, "Uptime: 2h 5m");
+    }
+
+    #[cfg(feature = "plugin-sysinfo")]
+    #[tokio::test]
+    async fn sysinfo_agent_reports_model_from_llm_status() {
+        assert_eq!(
+            ask_sysinfo("which model are you using").await,
+            "Model: qwen-7b via local (8192-token context)"
+        );
+    }
+
+    /// Verifies news fetches, calls LLM, and returns the LLM summary.  The
+    /// request has empty content — news is triggered that way, so the chat
+    /// empty-input guard must not apply to it.
+    #[cfg(feature = "plugin-news-agent")]
+    #[tokio::test]
+    async fn news_agent_summarises_via_llm() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        // Handle two sequential bus requests: tool then LLM.
+        tokio::spawn(async move {
+            // First: tools/execute
+            if let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "tools/execute");
+                if let BusPayload::ToolRequest { tool, action, .. } = payload {
+                    assert_eq!(tool, "newsmail_aggregator");
+                    assert_eq!(action, "get");
+                }
+                let _ = reply_tx.send(Ok(BusPayload::ToolResponse {
+                    tool: "newsmail_aggregator".to_string(),
+                    action: "get".to_string(),
+                    ok: true,
+                    data_json: Some("[{\"subject\":\"Test Headline\",\"from\":\"news@example.com\",\"date\":\"2026-02-21\"}]".to_string()),
+                    error: None,
+                }));
+            }
+            // Second: llm/complete
+            if let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "llm/complete");
+                if let BusPayload::LlmRequest {
+                    content,
+                    channel_id,
+                    ..
+                } = payload
+                {
+                    assert!(
+                        content.contains("Test Headline"),
+                        "prompt should contain subject"
+                    );
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "Summary: Test Headline from news@example.com.".to_string(),
+                        session_id: None,
+                        usage: None,
+
+                        timing: None,
+
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
         let cfg = AgentsConfig {
-            default_agent: "echo".to_string(),
-            enabled: HashSet::from(["echo".to_string()]),
+            default_agent: "news".to_string(),
+            enabled: HashSet::from(["news".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
@@ -2857,6 +4826,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2865,21 +4836,84 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-        let info = agents.component_info();
-        let child_ids: Vec<String> = info.children.into_iter().map(|c| c.id).collect();
-        assert_eq!(child_ids, vec!["echo".to_string()]);
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "".to_string(),
+                session_id: None,
+                usage: None,
+
+                timing: None,
+
+                thinking: None,
+            },
+            tx,
+        );
+
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert!(
+                    content.contains("Summary:"),
+                    "response should be LLM summary, got: {content}"
+                );
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
+    /// Empty inbox skips LLM entirely and returns a fixed message.
+    #[cfg(feature = "plugin-news-agent")]
     #[tokio::test]
-    async fn detailed_status_reports_only_enabled_agents() {
-        let (_bus, handle) = echo_bus();
+    async fn news_agent_empty_inbox_skips_llm() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            // Only one bus message: the tool request.
+            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
+                let _ = reply_tx.send(Ok(BusPayload::ToolResponse {
+                    tool: "newsmail_aggregator".to_string(),
+                    action: "get".to_string(),
+                    ok: true,
+                    data_json: Some("[]".to_string()),
+                    error: None,
+                }));
+            }
+            // No further messages — asserting LLM is not called.
+        });
+
         let cfg = AgentsConfig {
-            default_agent: "echo".to_string(),
-            enabled: HashSet::from(["echo".to_string()]),
+            default_agent: "news".to_string(),
+            enabled: HashSet::from(["news".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
@@ -2887,6 +4921,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2895,97 +4931,73 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-        let (tx, rx) = oneshot::channel();
-        agents.handle_request("agents/detailed_status", BusPayload::Empty, tx);
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "".to_string(),
+                session_id: None,
+                usage: None,
 
-        let payload = rx.await.unwrap().unwrap();
-        let BusPayload::JsonResponse { data } = payload else {
-            panic!("unexpected payload");
-        };
-        let value: serde_json::Value = serde_json::from_str(&data).unwrap();
-        let enabled = value
-            .get("enabled_agents")
-            .and_then(|v| v.as_array())
-            .cloned()
-            .unwrap_or_default();
-        assert_eq!(enabled, vec![serde_json::Value::String("echo".to_string())]);
-    }
-
-    // ── AgenticLoop integration tests ─────────────────────────────────────────
-    //
-    // These tests verify the full 3-phase instruction loop:
-    //   1. Instruction pass (llm/instruct) → parse tool calls
-    //   2. Tool execution (skipped when instruction returns [])
-    //   3. Response pass (llm/complete) → reply with session_id
-    //
-    // The fake bus responder handles llm/instruct and llm/complete sequentially.
-    // Instruction pass returning "[]" exercises graceful degradation (no tools called).
-
-    /// Verifies agentic-chat completes the full 3-phase loop and returns a session_id.
-    ///
-    /// Fake LLM handles two requests:
-    ///   - llm/instruct → "[]"  (no tool calls, graceful)
-    ///   - llm/complete → "[fake] hello" (response)
-    #[cfg(feature = "plugin-agentic-chat")]
-    #[tokio::test]
-    async fn agentic_chat_returns_session_id() {
-        let bus = SupervisorBus::new(16);
-        let handle = bus.handle.clone();
-        let mut bus_rx = bus.rx;
-        let (_dir, memory) = test_memory();
-
-        tokio::spawn(async move {
-            // Request 1: llm/instruct → empty JSON array (no tools)
-            if let Some(araliya_core::bus::message::BusMessage::Request {
-                payload, reply_tx, ..
-            }) = bus_rx.recv().await
-                && let BusPayload::LlmRequest { channel_id, .. } = payload
-            {
-                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
-                    channel_id,
-                    content: "[]".to_string(),
-                    session_id: None,
-                    usage: None,
-
-                    timing: None,
-
-                    thinking: None,
-                }));
-            }
-            // Request 2: llm/complete → response
-            if let Some(araliya_core::bus::message::BusMessage::Request {
-                payload, reply_tx, ..
-            }) = bus_rx.recv().await
-                && let BusPayload::LlmRequest { channel_id, .. } = payload
-            {
-                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
-                    channel_id,
-                    content: "[fake] hello".to_string(),
-                    session_id: None,
-                    usage: None,
+                timing: None,
 
-                    timing: None,
+                thinking: None,
+            },
+            tx,
+        );
 
-                    thinking: None,
-                }));
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert_eq!(content, "No new news emails.");
             }
-        });
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
 
+    /// Basic health check for docs agent.
+    #[cfg(feature = "plugin-docs")]
+    #[tokio::test]
+    async fn docs_agent_health() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
         let cfg = AgentsConfig {
-            default_agent: "agentic-chat".to_string(),
-            enabled: HashSet::from(["agentic-chat".to_string()]),
+            default_agent: "docs".to_string(),
+            enabled: HashSet::from(["docs".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
             news_query: None,
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
-            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
-                use_instruction_llm: false,
-            }),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -2994,15 +5006,37 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-        let (tx, rx_reply) = oneshot::channel();
+        let (tx, rx) = oneshot::channel();
         agents.handle_request(
-            "agents",
+            "agents/docs/health",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "hello".to_string(),
+                content: "".to_string(),
                 session_id: None,
                 usage: None,
 
@@ -3012,50 +5046,75 @@ mod tests {
             },
             tx,
         );
-
-        match rx_reply.await.unwrap() {
-            Ok(BusPayload::CommsMessage {
-                content,
-                session_id,
-                ..
-            }) => {
-                assert_eq!(content, "[fake] hello");
-                assert!(
-                    session_id.is_some(),
-                    "agentic loop must return a session_id"
-                );
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert!(content.contains("docs component"));
             }
-            other => panic!("unexpected response: {other:?}"),
+            other => panic!("unexpected: {other:?}"),
         }
     }
 
-    /// Verifies that a second message with the returned session_id reuses the same session.
-    #[cfg(feature = "plugin-agentic-chat")]
+    /// Asking the docs agent reads the specified file and forwards prompt to LLM.
+    #[cfg(feature = "plugin-docs")]
     #[tokio::test]
-    async fn agentic_chat_second_turn_reuses_session() {
+    async fn docs_agent_reads_file_and_queries_llm() {
         let bus = SupervisorBus::new(16);
         let handle = bus.handle.clone();
-        let mut bus_rx = bus.rx;
+        let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
 
-        // Handle 4 sequential LLM requests (2 per turn: instruct + complete).
+        // Prepare a temp docs directory with an index.md containing known content.
+        let docs_tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(docs_tmp.path().join("index.md"), "the quick brown fox").unwrap();
+        let docsdir = docs_tmp.path().to_str().unwrap().to_string();
+
+        // Fake LLM responder — handles two bus requests from the AgenticLoop:
+        //   1. Instruction pass: return a docs_search tool call
+        //   2. Response pass: reply with "brown"
+        //
+        // Note: we don't assert on prompt content because the prompt template
+        // files live at the workspace root (config/prompts/) but binary tests
+        // run from the package root (crates/araliya-bot/), so PromptBuilder
+        // silently skips missing template files.
         tokio::spawn(async move {
-            for i in 0..4u32 {
-                if let Some(araliya_core::bus::message::BusMessage::Request {
-                    payload,
-                    reply_tx,
-                    ..
-                }) = bus_rx.recv().await
-                    && let BusPayload::LlmRequest { channel_id, .. } = payload
-                {
-                    let content = if i % 2 == 0 {
-                        "[]".to_string() // instruct pass → no tools
-                    } else {
-                        format!("[fake-turn-{}] response", i / 2 + 1)
-                    };
+            // ── Instruction pass ──────────────────────────────────────
+            if let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "llm/complete");
+                if let BusPayload::LlmRequest { channel_id, .. } = payload {
+                    // Return a tool call that invokes docs_search.
                     let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                         channel_id,
-                        content,
+                        content: r#"[{"tool":"docs_search","action":"search","params":{"query":"what color"}}]"#.to_string(),
+                        session_id: None,
+                        usage: None,
+
+                        timing: None,
+
+                        thinking: None,
+
+                    }));
+                }
+            }
+
+            // ── Response pass ─────────────────────────────────────────
+            if let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                assert_eq!(method, "llm/complete");
+                if let BusPayload::LlmRequest { channel_id, .. } = payload {
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "brown".to_string(),
                         session_id: None,
                         usage: None,
 
@@ -3068,17 +5127,26 @@ mod tests {
         });
 
         let cfg = AgentsConfig {
-            default_agent: "agentic-chat".to_string(),
-            enabled: HashSet::from(["agentic-chat".to_string()]),
+            default_agent: "docs".to_string(),
+            enabled: HashSet::from(["docs".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
             news_query: None,
             gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
-                use_instruction_llm: false,
-            }),
+            agent_docs: HashMap::from([(
+                "docs".to_string(),
+                DocsAgentConfig {
+                    docsdir: Some(docsdir),
+                    index: Some("index.md".to_string()),
+                    use_kg: false,
+                    kg: araliya_core::config::DocsKgConfig::default(),
+                    embedding_cache_entries: 0,
+                },
+            )]),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -3087,16 +5155,39 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        // Populate the docs docstore before handling any queries.
+        agents.init_docs().await.unwrap();
 
-        // Turn 1 — no session_id provided.
-        let (tx1, rx1) = oneshot::channel();
+        let (tx, rx) = oneshot::channel();
         agents.handle_request(
-            "agents",
+            "agents/docs/ask",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "first message".to_string(),
+                content: "what color".to_string(),
                 session_id: None,
                 usage: None,
 
@@ -3104,110 +5195,159 @@ mod tests {
 
                 thinking: None,
             },
-            tx1,
+            tx,
         );
-        let session_id = match rx1.await.unwrap() {
-            Ok(BusPayload::CommsMessage { session_id, .. }) => {
-                session_id.expect("first turn must return a session_id")
+
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "brown"),
+            other => panic!("unexpected: {other:?}"),
+        }
+    }
+
+    /// `debug_prompt` returns the docs agent's assembled prompt, retrieved
+    /// docs included, without a single bus request to the LLM.
+    #[cfg(feature = "plugin-docs")]
+    #[tokio::test]
+    async fn docs_debug_prompt_assembles_without_calling_the_llm() {
+        let bus = SupervisorBus::new(16);
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+
+        let docs_tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            docs_tmp.path().join("index.md"),
+            "The lighthouse lamp is painted brown.",
+        )
+        .unwrap();
+
+        // Every bus request, collected until the last handle is dropped.
+        let requests = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            while let Some(msg) = rx.recv().await {
+                if let BusMessage::Request { method, .. } = msg {
+                    seen.push(method);
+                }
             }
-            other => panic!("unexpected response: {other:?}"),
+            seen
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "docs".to_string(),
+            enabled: HashSet::from(["docs".to_string()]),
+            agent_docs: HashMap::from([(
+                "docs".to_string(),
+                DocsAgentConfig {
+                    docsdir: Some(docs_tmp.path().to_str().unwrap().to_string()),
+                    index: Some("index.md".to_string()),
+                    use_kg: false,
+                    kg: araliya_core::config::DocsKgConfig::default(),
+                    embedding_cache_entries: 0,
+                },
+            )]),
+            debug_prompt: true,
+            ..AgentsConfig::default()
         };
+        let agents = AgentsSubsystem::new(cfg, bus.handle.clone(), memory).unwrap();
+        agents.init_docs().await.unwrap();
 
-        // Turn 2 — pass the session_id back.
-        let (tx2, rx2) = oneshot::channel();
+        let (tx, reply) = oneshot::channel();
         agents.handle_request(
-            "agents",
+            "agents/docs/debug_prompt",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "second message".to_string(),
-                session_id: Some(session_id.clone()),
+                content: "what colour is the lighthouse lamp".to_string(),
+                session_id: None,
                 usage: None,
-
                 timing: None,
-
                 thinking: None,
             },
-            tx2,
+            tx,
         );
-        match rx2.await.unwrap() {
-            Ok(BusPayload::CommsMessage {
-                session_id: sid2, ..
-            }) => {
-                assert_eq!(
-                    sid2,
-                    Some(session_id),
-                    "session_id must be reused across turns"
-                );
-            }
-            other => panic!("unexpected response: {other:?}"),
-        }
+        let Ok(BusPayload::JsonResponse { data }) = reply.await.unwrap() else {
+            panic!("expected a JsonResponse");
+        };
+        let debug: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(debug["agent_id"], "docs");
+        let prompt = debug["prompt"].as_str().unwrap();
+        assert!(
+            prompt.contains("The lighthouse lamp is painted brown."),
+            "{prompt}"
+        );
+        assert!(
+            prompt.contains("what colour is the lighthouse lamp"),
+            "{prompt}"
+        );
+
+        // With the agents gone the bus closes, so anything they sent has
+        // been collected.
+        drop(agents);
+        drop(bus.handle);
+        let requests = tokio::time::timeout(std::time::Duration::from_secs(5), requests)
+            .await
+            .expect("the bus closes once the agents are dropped")
+            .unwrap();
+        assert!(requests.is_empty(), "{requests:?}");
     }
 
-    /// Verifies debug_logging writes turn KV data when enabled.
-    ///
-    /// Checks that the session returned by the loop has a session_id — the actual
-    /// KV key contents are verifiable via the debug API endpoint once it is wired up.
-    /// This test ensures the flag does not break the loop.
-    #[cfg(feature = "plugin-agentic-chat")]
+    /// Asking the docs agent when the docstore is empty returns an error.
+    #[cfg(feature = "plugin-docs")]
     #[tokio::test]
-    async fn agentic_chat_debug_logging_does_not_break_loop() {
-        let bus = SupervisorBus::new(16);
-        let handle = bus.handle.clone();
-        let mut bus_rx = bus.rx;
+    async fn docs_agent_missing_file_errors() {
+        let (_bus, handle) = echo_bus();
         let (_dir, memory) = test_memory();
-
-        tokio::spawn(async move {
-            for _ in 0..2u32 {
-                if let Some(araliya_core::bus::message::BusMessage::Request {
-                    payload,
-                    reply_tx,
-                    ..
-                }) = bus_rx.recv().await
-                    && let BusPayload::LlmRequest { channel_id, .. } = payload
-                {
-                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
-                        channel_id,
-                        content: "[]".to_string(),
-                        session_id: None,
-                        usage: None,
-
-                        timing: None,
-
-                        thinking: None,
-                    }));
-                }
-            }
-        });
-
         let cfg = AgentsConfig {
-            default_agent: "agentic-chat".to_string(),
-            enabled: HashSet::from(["agentic-chat".to_string()]),
+            default_agent: "docs".to_string(),
+            enabled: HashSet::from(["docs".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
             news_query: None,
             gdelt_query: None,
+            // No docsdir configured — docstore will remain empty.
             agent_docs: std::collections::HashMap::new(),
-            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
-                use_instruction_llm: false,
-            }),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
-            debug_logging: true,
+            debug_logging: false,
             uniweb_session_id: None,
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        // Do NOT call init_docs — docstore stays empty.
 
-        let (tx, rx_reply) = oneshot::channel();
+        let (tx, rx) = oneshot::channel();
         agents.handle_request(
-            "agents",
+            "agents/docs/ask",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "test debug".to_string(),
+                content: "hi".to_string(),
                 session_id: None,
                 usage: None,
 
@@ -3217,64 +5357,16 @@ mod tests {
             },
             tx,
         );
-
-        match rx_reply.await.unwrap() {
-            Ok(BusPayload::CommsMessage { session_id, .. }) => {
-                assert!(
-                    session_id.is_some(),
-                    "debug_logging=true must not break session creation"
-                );
-            }
-            other => panic!("unexpected response: {other:?}"),
-        }
+        assert!(rx.await.unwrap().is_err());
     }
 
-    // ── runtime_cmd tests ─────────────────────────────────────────────────────
-
-    /// When `runtime_cmd` is the default and only enabled agent, an unrouted
-    /// `CommsMessage` must reach the runtime_cmd plugin, trigger `runtimes/init`
-    /// then `runtimes/exec` on the bus, and return the formatted stdout.
-    #[cfg(feature = "plugin-runtime-cmd")]
     #[tokio::test]
-    async fn runtime_cmd_is_routed_as_default_and_execs() {
-        use araliya_core::config::RuntimeCmdAgentConfig;
-
-        let bus = SupervisorBus::new(16);
-        let handle = bus.handle.clone();
-        let mut rx = bus.rx;
+    async fn test_get_or_create_subagent() {
+        let (_bus, handle) = echo_bus();
         let (_dir, memory) = test_memory();
-
-        // Fake runtimes subsystem: respond to init then exec.
-        tokio::spawn(async move {
-            // First message: runtimes/init — just ack success.
-            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
-                let init_result = serde_json::json!({
-                    "success": true,
-                    "exit_code": null,
-                    "stdout": "",
-                    "stderr": "",
-                    "runtime_dir": "/tmp/test"
-                })
-                .to_string();
-                let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data: init_result }));
-            }
-            // Second message: runtimes/exec — return "2" as stdout.
-            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
-                let exec_result = serde_json::json!({
-                    "success": true,
-                    "exit_code": 0,
-                    "stdout": "2\n",
-                    "stderr": "",
-                    "duration_ms": 5
-                })
-                .to_string();
-                let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data: exec_result }));
-            }
-        });
-
         let cfg = AgentsConfig {
-            default_agent: "runtime_cmd".to_string(),
-            enabled: HashSet::from(["runtime_cmd".to_string()]),
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
@@ -3282,11 +5374,9 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
-            runtime_cmd: Some(RuntimeCmdAgentConfig {
-                runtime: "node".to_string(),
-                command: "node".to_string(),
-                setup_script: None,
-            }),
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
             debug_logging: false,
@@ -3294,45 +5384,48 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-        let (tx, rx_reply) = oneshot::channel();
-        agents.handle_request(
-            "agents",
-            BusPayload::CommsMessage {
-                channel_id: "pty0".to_string(),
-                content: "console.log(1+1)".to_string(),
-                session_id: None,
-                usage: None,
+        let subagent = agents
+            .state
+            .get_or_create_subagent("echo", "worker1")
+            .unwrap();
 
-                timing: None,
+        assert!(subagent.identity_dir.exists());
 
-                thinking: None,
-            },
-            tx,
-        );
+        let dir_name = subagent.identity_dir.file_name().unwrap().to_str().unwrap();
+        assert!(dir_name.starts_with("worker1-"));
+        assert!(dir_name.ends_with(&subagent.public_id));
 
-        match rx_reply.await.unwrap() {
-            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "2"),
-            other => panic!("unexpected response: {other:?}"),
-        }
+        let parent_dir = subagent.identity_dir.parent().unwrap();
+        assert!(parent_dir.ends_with("subagents"));
     }
 
-    // ── v0.6 PR1: Runtime class registration tests ────────────────────────────
-    //
-    // These tests verify that each built-in agent is registered with the
-    // expected runtime class.  They are the acceptance tests for the first-class
-    // runtime classification layer introduced in agents v0.6 PR1.
-    //
-    // Ground rules verified here:
-    //   - Existing agent behavior is not changed (routing tests above still pass)
-    //   - Runtime class mapping matches the PR1 spec table
-    //   - AgentRegistration wraps the agent without changing its ID
-
-    #[cfg(feature = "plugin-echo")]
     #[tokio::test]
-    async fn echo_agent_classified_as_request_response() {
+    async fn component_info_shows_only_enabled_agents() {
         let (_bus, handle) = echo_bus();
         let (_dir, memory) = test_memory();
         let cfg = AgentsConfig {
@@ -3345,6 +5438,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -3353,57 +5448,72 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("echo")
-            .expect("echo must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::RequestResponse);
-        assert_eq!(reg.agent.id(), "echo");
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let info = agents.component_info();
+        let child_ids: Vec<String> = info.children.into_iter().map(|c| c.id).collect();
+        assert_eq!(child_ids, vec!["echo".to_string()]);
     }
 
-    #[cfg(feature = "plugin-basic-chat")]
     #[tokio::test]
-    async fn basic_chat_agent_classified_as_request_response() {
+    async fn component_tree_json_includes_agent_capabilities() {
         let (_bus, handle) = echo_bus();
         let (_dir, memory) = test_memory();
         let cfg = AgentsConfig {
-            default_agent: "basic_chat".to_string(),
-            enabled: HashSet::from(["basic_chat".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            default_agent: "echo".to_string(),
+            ..AgentsConfig::default()
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("basic_chat")
-            .expect("basic_chat must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::RequestResponse);
-        assert_eq!(reg.agent.id(), "basic_chat");
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let tree: serde_json::Value = serde_json::to_value(agents.component_info()).unwrap();
+        let children = tree["children"].as_array().unwrap();
+        assert!(!children.is_empty());
+        for child in children {
+            let meta = &child["metadata"];
+            for field in ["supports_sessions", "supports_tools", "supports_streaming"] {
+                assert!(meta[field].is_boolean(), "{}: missing {field}", child["id"]);
+            }
+            assert!(
+                meta["description"].is_string(),
+                "{}: missing description",
+                child["id"]
+            );
+        }
+        let echo = children.iter().find(|c| c["id"] == "echo").unwrap();
+        assert_eq!(echo["metadata"]["supports_sessions"], false);
+        assert!(!echo["metadata"]["description"].as_str().unwrap().is_empty());
     }
 
-    #[cfg(feature = "plugin-chat")]
     #[tokio::test]
-    async fn session_chat_agent_classified_as_session() {
+    async fn detailed_status_reports_only_enabled_agents() {
         let (_bus, handle) = echo_bus();
         let (_dir, memory) = test_memory();
         let cfg = AgentsConfig {
-            default_agent: "chat".to_string(),
-            enabled: HashSet::from(["chat".to_string()]),
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
@@ -3411,6 +5521,8 @@ mod tests {
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -3419,21 +5531,107 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("chat")
-            .expect("chat must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::Session);
-        assert_eq!(reg.agent.id(), "chat");
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request("agents/detailed_status", BusPayload::Empty, tx);
+
+        let payload = rx.await.unwrap().unwrap();
+        let BusPayload::JsonResponse { data } = payload else {
+            panic!("unexpected payload");
+        };
+        let value: serde_json::Value = serde_json::from_str(&data).unwrap();
+        let enabled = value
+            .get("enabled_agents")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        assert_eq!(enabled, vec![serde_json::Value::String("echo".to_string())]);
     }
 
+    // ── AgenticLoop integration tests ─────────────────────────────────────────
+    //
+    // These tests verify the full 3-phase instruction loop:
+    //   1. Instruction pass (llm/instruct) → parse tool calls
+    //   2. Tool execution (skipped when instruction returns [])
+    //   3. Response pass (llm/complete) → reply with session_id
+    //
+    // The fake bus responder handles llm/instruct and llm/complete sequentially.
+    // Instruction pass returning "[]" exercises graceful degradation (no tools called).
+
+    /// Verifies agentic-chat completes the full 3-phase loop and returns a session_id.
+    ///
+    /// Fake LLM handles two requests:
+    ///   - llm/instruct → "[]"  (no tool calls, graceful)
+    ///   - llm/complete → "[fake] hello" (response)
     #[cfg(feature = "plugin-agentic-chat")]
     #[tokio::test]
-    async fn agentic_chat_agent_classified_as_agentic() {
-        let (_bus, handle) = echo_bus();
+    async fn agentic_chat_returns_session_id() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut bus_rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            // Request 1: llm/instruct → empty JSON array (no tools)
+            if let Some(araliya_core::bus::message::BusMessage::Request {
+                payload, reply_tx, ..
+            }) = bus_rx.recv().await
+                && let BusPayload::LlmRequest { channel_id, .. } = payload
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content: "[]".to_string(),
+                    session_id: None,
+                    usage: None,
+
+                    timing: None,
+
+                    thinking: None,
+                }));
+            }
+            // Request 2: llm/complete → response
+            if let Some(araliya_core::bus::message::BusMessage::Request {
+                payload, reply_tx, ..
+            }) = bus_rx.recv().await
+                && let BusPayload::LlmRequest { channel_id, .. } = payload
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content: "[fake] hello".to_string(),
+                    session_id: None,
+                    usage: None,
+
+                    timing: None,
+
+                    thinking: None,
+                }));
+            }
+        });
+
         let cfg = AgentsConfig {
             default_agent: "agentic-chat".to_string(),
             enabled: HashSet::from(["agentic-chat".to_string()]),
@@ -3443,7 +5641,11 @@ mod tests {
             news_query: None,
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
+            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
+                use_instruction_llm: false,
+            }),
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -3452,31 +5654,115 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("agentic-chat")
-            .expect("agentic-chat must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::Agentic);
-        assert_eq!(reg.agent.id(), "agentic-chat");
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "hello".to_string(),
+                session_id: None,
+                usage: None,
+
+                timing: None,
+
+                thinking: None,
+            },
+            tx,
+        );
+
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                content,
+                session_id,
+                ..
+            }) => {
+                assert_eq!(content, "[fake] hello");
+                assert!(
+                    session_id.is_some(),
+                    "agentic loop must return a session_id"
+                );
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
-    #[cfg(feature = "plugin-news-agent")]
+    /// Verifies that a second message with the returned session_id reuses the same session.
+    #[cfg(feature = "plugin-agentic-chat")]
     #[tokio::test]
-    async fn news_agent_classified_as_specialized() {
-        let (_bus, handle) = echo_bus();
+    async fn agentic_chat_second_turn_reuses_session() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut bus_rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        // Handle 4 sequential LLM requests (2 per turn: instruct + complete).
+        tokio::spawn(async move {
+            for i in 0..4u32 {
+                if let Some(araliya_core::bus::message::BusMessage::Request {
+                    payload,
+                    reply_tx,
+                    ..
+                }) = bus_rx.recv().await
+                    && let BusPayload::LlmRequest { channel_id, .. } = payload
+                {
+                    let content = if i % 2 == 0 {
+                        "[]".to_string() // instruct pass → no tools
+                    } else {
+                        format!("[fake-turn-{}] response", i / 2 + 1)
+                    };
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content,
+                        session_id: None,
+                        usage: None,
+
+                        timing: None,
+
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
         let cfg = AgentsConfig {
-            default_agent: "echo".to_string(),
-            enabled: HashSet::from(["echo".to_string(), "news".to_string()]),
+            default_agent: "agentic-chat".to_string(),
+            enabled: HashSet::from(["agentic-chat".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
             news_query: None,
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
+            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
+                use_instruction_llm: false,
+            }),
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
@@ -3485,100 +5771,202 @@ mod tests {
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("news")
-            .expect("news must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::Specialized);
-        assert_eq!(reg.agent.id(), "news");
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        // Turn 1 — no session_id provided.
+        let (tx1, rx1) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "first message".to_string(),
+                session_id: None,
+                usage: None,
+
+                timing: None,
+
+                thinking: None,
+            },
+            tx1,
+        );
+        let session_id = match rx1.await.unwrap() {
+            Ok(BusPayload::CommsMessage { session_id, .. }) => {
+                session_id.expect("first turn must return a session_id")
+            }
+            other => panic!("unexpected response: {other:?}"),
+        };
+
+        // Turn 2 — pass the session_id back.
+        let (tx2, rx2) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "second message".to_string(),
+                session_id: Some(session_id.clone()),
+                usage: None,
+
+                timing: None,
+
+                thinking: None,
+            },
+            tx2,
+        );
+        match rx2.await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                session_id: sid2, ..
+            }) => {
+                assert_eq!(
+                    sid2,
+                    Some(session_id),
+                    "session_id must be reused across turns"
+                );
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
-    #[cfg(feature = "plugin-runtime-cmd")]
+    /// Verifies debug_logging writes turn KV data when enabled.
+    ///
+    /// Checks that the session returned by the loop has a session_id — the actual
+    /// KV key contents are verifiable via the debug API endpoint once it is wired up.
+    /// This test ensures the flag does not break the loop.
+    #[cfg(feature = "plugin-agentic-chat")]
     #[tokio::test]
-    async fn runtime_cmd_agent_classified_as_specialized() {
-        let (_bus, handle) = echo_bus();
+    async fn agentic_chat_debug_logging_does_not_break_loop() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut bus_rx = bus.rx;
         let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            for _ in 0..2u32 {
+                if let Some(araliya_core::bus::message::BusMessage::Request {
+                    payload,
+                    reply_tx,
+                    ..
+                }) = bus_rx.recv().await
+                    && let BusPayload::LlmRequest { channel_id, .. } = payload
+                {
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "[]".to_string(),
+                        session_id: None,
+                        usage: None,
+
+                        timing: None,
+
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
         let cfg = AgentsConfig {
-            default_agent: "echo".to_string(),
-            enabled: HashSet::from(["echo".to_string(), "runtime_cmd".to_string()]),
+            default_agent: "agentic-chat".to_string(),
+            enabled: HashSet::from(["agentic-chat".to_string()]),
             channel_map: HashMap::new(),
             agent_memory: HashMap::new(),
             agent_skills: HashMap::new(),
             news_query: None,
             gdelt_query: None,
             agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
+            agentic_chat: Some(araliya_core::config::AgenticChatConfig {
+                use_instruction_llm: false,
+            }),
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
-            debug_logging: false,
+            debug_logging: true,
             uniweb_session_id: None,
             uniweb_use_instruction_llm: false,
             newsroom_query: None,
             agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
         };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
-        let reg = subsystem
-            .agents
-            .get("runtime_cmd")
-            .expect("runtime_cmd must be registered");
-        assert_eq!(reg.runtime_class, AgentRuntimeClass::Specialized);
-        assert_eq!(reg.agent.id(), "runtime_cmd");
-    }
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
-    /// Verifies that `agents/list` includes a `runtime_class` field for each agent.
-    #[cfg(feature = "plugin-echo")]
-    #[tokio::test]
-    async fn agents_list_includes_runtime_class() {
-        let (_bus, handle) = echo_bus();
-        let (_dir, memory) = test_memory();
-        let cfg = AgentsConfig {
-            default_agent: "echo".to_string(),
-            enabled: HashSet::from(["echo".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
-            runtime_cmd: None,
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
-        };
-        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "test debug".to_string(),
+                session_id: None,
+                usage: None,
 
-        let (tx, rx) = oneshot::channel();
-        subsystem.handle_request("agents/list", BusPayload::Empty, tx);
-        let payload = rx.await.unwrap().unwrap();
-        let BusPayload::JsonResponse { data } = payload else {
-            panic!("expected JsonResponse");
-        };
-        let value: serde_json::Value = serde_json::from_str(&data).unwrap();
-        let agents = value["agents"].as_array().expect("agents array");
-        let echo_entry = agents
-            .iter()
-            .find(|e| e["agent_id"] == "echo")
-            .expect("echo entry in list");
-        assert_eq!(
-            echo_entry["runtime_class"],
-            serde_json::Value::String("request_response".to_string()),
-            "echo must be listed with runtime_class=request_response"
+                timing: None,
+
+                thinking: None,
+            },
+            tx,
         );
+
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { session_id, .. }) => {
+                assert!(
+                    session_id.is_some(),
+                    "debug_logging=true must not break session creation"
+                );
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
     }
 
-    /// When `runtimes/exec` returns a non-zero exit code the reply must contain
-    /// the error message from stderr.
+    // ── runtime_cmd tests ─────────────────────────────────────────────────────
+
+    /// When `runtime_cmd` is the default and only enabled agent, an unrouted
+    /// `CommsMessage` must reach the runtime_cmd plugin, trigger `runtimes/init`
+    /// then `runtimes/exec` on the bus, and return the formatted stdout.
     #[cfg(feature = "plugin-runtime-cmd")]
     #[tokio::test]
-    async fn runtime_cmd_exec_error_is_formatted() {
+    async fn runtime_cmd_is_routed_as_default_and_execs() {
         use araliya_core::config::RuntimeCmdAgentConfig;
 
         let bus = SupervisorBus::new(16);
@@ -3586,52 +5974,43 @@ mod tests {
         let mut rx = bus.rx;
         let (_dir, memory) = test_memory();
 
+        // Fake runtimes subsystem: respond to init then exec.
         tokio::spawn(async move {
-            // init
+            // First message: runtimes/init — just ack success.
             if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
-                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
-                    data: serde_json::json!({
-                        "success": true, "exit_code": null,
-                        "stdout": "", "stderr": "", "runtime_dir": "/tmp/test"
-                    })
-                    .to_string(),
-                }));
+                let init_result = serde_json::json!({
+                    "success": true,
+                    "exit_code": null,
+                    "stdout": "",
+                    "stderr": "",
+                    "runtime_dir": "/tmp/test"
+                })
+                .to_string();
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data: init_result }));
             }
-            // exec — simulate a syntax error
+            // Second message: runtimes/exec — return "2" as stdout.
             if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
-                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
-                    data: serde_json::json!({
-                        "success": false, "exit_code": 1,
-                        "stdout": "", "stderr": "SyntaxError: Unexpected token\n",
-                        "duration_ms": 3
-                    })
-                    .to_string(),
-                }));
+                let exec_result = serde_json::json!({
+                    "success": true,
+                    "exit_code": 0,
+                    "stdout": "2\n",
+                    "stderr": "",
+                    "duration_ms": 5
+                })
+                .to_string();
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data: exec_result }));
             }
         });
 
         let cfg = AgentsConfig {
             default_agent: "runtime_cmd".to_string(),
             enabled: HashSet::from(["runtime_cmd".to_string()]),
-            channel_map: HashMap::new(),
-            agent_memory: HashMap::new(),
-            agent_skills: HashMap::new(),
-            news_query: None,
-            gdelt_query: None,
-            agent_docs: std::collections::HashMap::new(),
-            agentic_chat: None,
             runtime_cmd: Some(RuntimeCmdAgentConfig {
                 runtime: "node".to_string(),
                 command: "node".to_string(),
                 setup_script: None,
             }),
-            webbuilder: None,
-            homebuilder: None,
-            debug_logging: false,
-            uniweb_session_id: None,
-            uniweb_use_instruction_llm: false,
-            newsroom_query: None,
-            agent_aggregation_targets: std::collections::HashMap::new(),
+            ..AgentsConfig::default()
         };
         let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
 
@@ -3640,7 +6019,7 @@ mod tests {
             "agents",
             BusPayload::CommsMessage {
                 channel_id: "pty0".to_string(),
-                content: "console.log(1+1".to_string(),
+                content: "console.log(1+1)".to_string(),
                 session_id: None,
                 usage: None,
 
@@ -3652,11 +6031,1908 @@ mod tests {
         );
 
         match rx_reply.await.unwrap() {
-            Ok(BusPayload::CommsMessage { content, .. }) => {
-                assert!(content.contains("Error (exit 1)"), "got: {content}");
-                assert!(content.contains("SyntaxError"), "got: {content}");
-            }
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "2"),
             other => panic!("unexpected response: {other:?}"),
         }
     }
+
+    // ── v0.6 PR1: Runtime class registration tests ────────────────────────────
+    //
+    // These tests verify that each built-in agent is registered with the
+    // expected runtime class.  They are the acceptance tests for the first-class
+    // runtime classification layer introduced in agents v0.6 PR1.
+    //
+    // Ground rules verified here:
+    //   - Existing agent behavior is not changed (routing tests above still pass)
+    //   - Runtime class mapping matches the PR1 spec table
+    //   - AgentRegistration wraps the agent without changing its ID
+
+    #[cfg(feature = "plugin-echo")]
+    #[tokio::test]
+    async fn echo_agent_classified_as_request_response() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("echo")
+            .expect("echo must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::RequestResponse);
+        assert_eq!(reg.agent.id(), "echo");
+    }
+
+    #[cfg(feature = "plugin-basic-chat")]
+    #[tokio::test]
+    async fn basic_chat_agent_classified_as_request_response() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "basic_chat".to_string(),
+            enabled: HashSet::from(["basic_chat".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("basic_chat")
+            .expect("basic_chat must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::RequestResponse);
+        assert_eq!(reg.agent.id(), "basic_chat");
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_agent_classified_as_session() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "chat".to_string(),
+            enabled: HashSet::from(["chat".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("chat")
+            .expect("chat must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::Session);
+        assert_eq!(reg.agent.id(), "chat");
+    }
+
+    /// Send `messages` to `chat` in one session, with an optional context
+    /// `budget` and `context_turns` cap, and return the prompt the LLM saw
+    /// on the last one.
+    #[cfg(feature = "plugin-chat")]
+    async fn last_chat_prompt(
+        messages: &[&str],
+        budget: Option<ContextBudget>,
+        context_turns: Option<usize>,
+    ) -> String {
+        last_chat_request(messages, budget, context_turns, None)
+            .await
+            .0
+    }
+
+    /// [`last_chat_prompt`], with `llm/resolve` answered by `resolved`
+    /// (unanswered when `None`), also returning the provider the last
+    /// completion was pinned to.
+    #[cfg(feature = "plugin-chat")]
+    async fn last_chat_request(
+        messages: &[&str],
+        budget: Option<ContextBudget>,
+        context_turns: Option<usize>,
+        resolved: Option<serde_json::Value>,
+    ) -> (String, Option<String>) {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+        let (prompts_tx, mut prompts) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                if method == "llm/resolve" {
+                    if let Some(resolved) = &resolved {
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: resolved.to_string(),
+                        }));
+                    }
+                    continue;
+                }
+                if let BusPayload::LlmRequest {
+                    channel_id,
+                    content,
+                    provider_override,
+                    ..
+                } = payload
+                {
+                    let _ = prompts_tx.send((content, provider_override));
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "noted".to_string(),
+                        session_id: None,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "chat".to_string(),
+            enabled: HashSet::from(["chat".to_string()]),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: context_turns
+                .map(|turns| HashMap::from([("chat".to_string(), turns)]))
+                .unwrap_or_default(),
+            agent_seed_memory: HashMap::new(),
+            agent_max_sessions: HashMap::new(),
+            ..AgentsConfig::default()
+        };
+        let mut agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        if let Some(budget) = budget {
+            agents = agents.with_context_budget(budget);
+        }
+
+        let mut session_id = None;
+        let mut request = (String::new(), None);
+        for message in messages {
+            let (tx, reply) = oneshot::channel();
+            agents.handle_request(
+                "agents",
+                BusPayload::CommsMessage {
+                    channel_id: "pty0".to_string(),
+                    content: message.to_string(),
+                    session_id: session_id.take(),
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                },
+                tx,
+            );
+            let Ok(BusPayload::CommsMessage { session_id: id, .. }) = reply.await.unwrap() else {
+                panic!("turn {message:?} failed");
+            };
+            session_id = id;
+            request = prompts.recv().await.unwrap();
+        }
+        request
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_history_is_trimmed_to_the_context_window() {
+        let messages = ["remember alpha", "and beta"];
+        let roomy = last_chat_prompt(&messages, Some(ContextBudget::new(128_000, 0)), None).await;
+        assert!(roomy.contains("user: remember alpha"), "{roomy}");
+        assert!(roomy.contains("assistant: noted"), "{roomy}");
+
+        // 40 tokens less a 20-token reply reserve leaves no room beside the
+        // prompt template, so the earlier turn is dropped.
+        let tight = last_chat_prompt(&messages, Some(ContextBudget::new(40, 0)), None).await;
+        assert!(!tight.contains("remember alpha"), "{tight}");
+        assert!(tight.contains("and beta"), "{tight}");
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_history_fits_the_provider_the_request_resolves_to() {
+        let messages = ["remember alpha", "and beta"];
+        // The startup default is roomy, but the request resolves to a
+        // provider with a tight window: history is sized to that one, and
+        // the completion is pinned to it.
+        let resolved = serde_json::json!({
+            "provider": "small",
+            "model": "small-model",
+            "context_window": 40,
+            "max_tokens": 0,
+        });
+        let (prompt, provider) = last_chat_request(
+            &messages,
+            Some(ContextBudget::new(128_000, 0)),
+            None,
+            Some(resolved),
+        )
+        .await;
+        assert!(!prompt.contains("remember alpha"), "{prompt}");
+        assert!(prompt.contains("and beta"), "{prompt}");
+        assert_eq!(provider.as_deref(), Some("small"));
+
+        // Unresolvable: the startup budget, and nothing pinned.
+        let (prompt, provider) =
+            last_chat_request(&messages, Some(ContextBudget::new(128_000, 0)), None, None).await;
+        assert!(prompt.contains("remember alpha"), "{prompt}");
+        assert_eq!(provider, None);
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_history_is_capped_at_context_turns() {
+        let messages = ["turn one", "turn two", "turn three", "turn four", "now"];
+        let all = last_chat_prompt(&messages, None, None).await;
+        assert!(all.contains("user: turn one"), "{all}");
+
+        let capped = last_chat_prompt(&messages, None, Some(2)).await;
+        assert!(!capped.contains("turn one"), "{capped}");
+        assert!(!capped.contains("turn two"), "{capped}");
+        assert!(capped.contains("user: turn three"), "{capped}");
+        assert!(capped.contains("user: turn four"), "{capped}");
+        assert_eq!(capped.matches("assistant: noted").count(), 2, "{capped}");
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_system_prompt_carries_seeded_working_memory() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory = MemorySystem::new(
+            dir.path(),
+            araliya_memory::MemoryConfig {
+                seed_memory: HashMap::from([("chat".to_string(), "- name: Ada".to_string())]),
+                ..araliya_memory::MemoryConfig::default()
+            },
+        )
+        .unwrap();
+        let (systems_tx, mut systems) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = rx.recv().await
+            {
+                if let BusPayload::LlmRequest {
+                    channel_id, system, ..
+                } = payload
+                {
+                    let _ = systems_tx.send(system.unwrap_or_default());
+                    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content: "hello Ada".to_string(),
+                        session_id: None,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    }));
+                }
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "chat".to_string(),
+            enabled: HashSet::from(["chat".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, Arc::new(memory)).unwrap();
+        let (tx, reply) = oneshot::channel();
+        agents.handle_request("agents", chat_message("who am I?"), tx);
+        assert!(matches!(
+            reply.await.unwrap(),
+            Ok(BusPayload::CommsMessage { .. })
+        ));
+
+        let system = systems.recv().await.unwrap();
+        assert!(
+            system.contains("## Working memory\n\n- name: Ada"),
+            "{system}"
+        );
+    }
+
+    #[cfg(feature = "plugin-chat")]
+    #[tokio::test]
+    async fn session_chat_titles_a_session_once_after_its_first_turn() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        let mut failed_once = false;
+
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+
+        // Fake LLM: the first chat completion fails, later ones say "noted";
+        // each title call is numbered.
+        let title_calls = Arc::new(AtomicUsize::new(0));
+        let calls = title_calls.clone();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                let BusPayload::LlmRequest { channel_id, .. } = payload else {
+                    continue;
+                };
+                let content = if method == "llm/instruct" {
+                    let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    format!("\"Kandy trip plan {n}.\"")
+                } else if !failed_once {
+                    // The session's first reply fails.
+                    failed_once = true;
+                    let _ = reply_tx.send(Err(BusError::new(-32000, "provider down")));
+                    continue;
+                } else {
+                    "noted".to_string()
+                };
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id,
+                    content,
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "chat".to_string(),
+            enabled: HashSet::from(["chat".to_string()]),
+            auto_title: true,
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        // Chat sessions live in the chat agent's store; `agents/sessions`
+        // lists them with their titles.
+        let listed = || {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request("agents/sessions", BusPayload::Empty, tx);
+            async {
+                match rx.await.unwrap() {
+                    Ok(BusPayload::JsonResponse { data }) => {
+                        let v: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        v["sessions"].as_array().unwrap().clone()
+                    }
+                    other => panic!("unexpected response: {other:?}"),
+                }
+            }
+        };
+        let title = |session_id: String| {
+            let listed = listed();
+            async move {
+                listed
+                    .await
+                    .iter()
+                    .find(|s| s["session_id"] == session_id.as_str())
+                    .and_then(|s| s["title"].as_str().map(str::to_string))
+            }
+        };
+        let turn = |content: &str, session_id: Option<String>| {
+            let (tx, reply) = oneshot::channel();
+            agents.handle_request(
+                "agents",
+                BusPayload::CommsMessage {
+                    channel_id: "pty0".to_string(),
+                    content: content.to_string(),
+                    session_id,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                },
+                tx,
+            );
+            reply
+        };
+
+        // The first reply fails: nothing to title yet.
+        let _ = turn("help me plan a trip to Kandy", None).await.unwrap();
+        let sessions = listed().await;
+        assert_eq!(sessions.len(), 1, "{sessions:?}");
+        let session_id = sessions[0]["session_id"].as_str().unwrap().to_string();
+        assert_eq!(sessions[0]["last_agent"], "chat");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(title_calls.load(Ordering::SeqCst), 0);
+
+        // The first turn with a reply titles it.
+        let retried = turn("help me plan a trip to Kandy", Some(session_id.clone()))
+            .await
+            .unwrap();
+        assert!(retried.is_ok(), "{retried:?}");
+        let titled = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(t) = title(session_id.clone()).await {
+                    break t;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("session was not titled");
+        assert_eq!(titled, "Kandy trip plan 1");
+
+        let later = turn("and a train?", Some(session_id.clone()))
+            .await
+            .unwrap();
+        assert!(later.is_ok(), "{later:?}");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(title_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            title(session_id.clone()).await.as_deref(),
+            Some("Kandy trip plan 1")
+        );
+    }
+
+    #[cfg(feature = "plugin-agentic-chat")]
+    #[tokio::test]
+    async fn agentic_chat_agent_classified_as_agentic() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "agentic-chat".to_string(),
+            enabled: HashSet::from(["agentic-chat".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("agentic-chat")
+            .expect("agentic-chat must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::Agentic);
+        assert_eq!(reg.agent.id(), "agentic-chat");
+    }
+
+    #[cfg(feature = "plugin-news-agent")]
+    #[tokio::test]
+    async fn news_agent_classified_as_specialized() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string(), "news".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("news")
+            .expect("news must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::Specialized);
+        assert_eq!(reg.agent.id(), "news");
+    }
+
+    #[cfg(feature = "plugin-runtime-cmd")]
+    #[tokio::test]
+    async fn runtime_cmd_agent_classified_as_specialized() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string(), "runtime_cmd".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let reg = subsystem
+            .agents
+            .get("runtime_cmd")
+            .expect("runtime_cmd must be registered");
+        assert_eq!(reg.runtime_class, AgentRuntimeClass::Specialized);
+        assert_eq!(reg.agent.id(), "runtime_cmd");
+    }
+
+    /// Verifies that `agents/list` includes a `runtime_class` field for each agent.
+    #[cfg(feature = "plugin-echo")]
+    #[tokio::test]
+    async fn agents_list_includes_runtime_class() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            channel_map: HashMap::new(),
+            agent_memory: HashMap::new(),
+            agent_skills: HashMap::new(),
+            news_query: None,
+            gdelt_query: None,
+            agent_docs: std::collections::HashMap::new(),
+            agentic_chat: None,
+            echo: None,
+            summarize: None,
+            runtime_cmd: None,
+            webbuilder: None,
+            homebuilder: None,
+            debug_logging: false,
+            uniweb_session_id: None,
+            uniweb_use_instruction_llm: false,
+            newsroom_query: None,
+            agent_aggregation_targets: std::collections::HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: std::collections::HashMap::new(),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: std::collections::HashMap::new(),
+            agent_seed_memory: std::collections::HashMap::new(),
+            agent_max_sessions: std::collections::HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            agent_empty_replies: HashMap::new(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: Default::default(),
+            persist_context: HashSet::new(),
+        };
+        let subsystem = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        subsystem.handle_request("agents/list", BusPayload::Empty, tx);
+        let payload = rx.await.unwrap().unwrap();
+        let BusPayload::JsonResponse { data } = payload else {
+            panic!("expected JsonResponse");
+        };
+        let value: serde_json::Value = serde_json::from_str(&data).unwrap();
+        let agents = value["agents"].as_array().expect("agents array");
+        let echo_entry = agents
+            .iter()
+            .find(|e| e["agent_id"] == "echo")
+            .expect("echo entry in list");
+        assert_eq!(
+            echo_entry["runtime_class"],
+            serde_json::Value::String("request_response".to_string()),
+            "echo must be listed with runtime_class=request_response"
+        );
+    }
+
+    /// When `runtimes/exec` returns a non-zero exit code the reply must contain
+    /// the error message from stderr.
+    #[cfg(feature = "plugin-runtime-cmd")]
+    #[tokio::test]
+    async fn runtime_cmd_exec_error_is_formatted() {
+        use araliya_core::config::RuntimeCmdAgentConfig;
+
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+
+        tokio::spawn(async move {
+            // init
+            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: serde_json::json!({
+                        "success": true, "exit_code": null,
+                        "stdout": "", "stderr": "", "runtime_dir": "/tmp/test"
+                    })
+                    .to_string(),
+                }));
+            }
+            // exec — simulate a syntax error
+            if let Some(BusMessage::Request { reply_tx, .. }) = rx.recv().await {
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: serde_json::json!({
+                        "success": false, "exit_code": 1,
+                        "stdout": "", "stderr": "SyntaxError: Unexpected token\n",
+                        "duration_ms": 3
+                    })
+                    .to_string(),
+                }));
+            }
+        });
+
+        let cfg = AgentsConfig {
+            default_agent: "runtime_cmd".to_string(),
+            enabled: HashSet::from(["runtime_cmd".to_string()]),
+            runtime_cmd: Some(RuntimeCmdAgentConfig {
+                runtime: "node".to_string(),
+                command: "node".to_string(),
+                setup_script: None,
+            }),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let (tx, rx_reply) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "console.log(1+1".to_string(),
+                session_id: None,
+                usage: None,
+
+                timing: None,
+
+                thinking: None,
+            },
+            tx,
+        );
+
+        match rx_reply.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => {
+                assert!(content.contains("Error (exit 1)"), "got: {content}");
+                assert!(content.contains("SyntaxError"), "got: {content}");
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    fn routing_agents(channel_map: HashMap<String, String>) -> AgentsSubsystem {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string(), "chat".to_string(), "docs".to_string()]),
+            channel_map,
+            ..AgentsConfig::default()
+        };
+        AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_channel_defaults(HashMap::from([(
+                "telegram".to_string(),
+                "chat".to_string(),
+            )]))
+    }
+
+    #[test]
+    fn channel_type_strips_instance_suffix() {
+        assert_eq!(channel_type("pty0"), "pty");
+        assert_eq!(channel_type("telegram0:12345"), "telegram");
+        assert_eq!(channel_type("axum0"), "axum");
+        assert_eq!(channel_type("http"), "http");
+    }
+
+    #[tokio::test]
+    async fn resolve_agent_precedence() {
+        let agents = routing_agents(HashMap::from([(
+            "telegram0:42".to_string(),
+            "docs".to_string(),
+        )]));
+
+        // 1. Explicit method agent wins over everything.
+        assert_eq!(
+            agents.resolve_agent(Some("echo"), "telegram0:42").unwrap(),
+            "echo"
+        );
+        // 2. Exact channel_map entry beats the channel-type default.
+        assert_eq!(agents.resolve_agent(None, "telegram0:42").unwrap(), "docs");
+        // 3. Channel-type default applies to unmapped ids of that type.
+        assert_eq!(agents.resolve_agent(None, "telegram0:99").unwrap(), "chat");
+        // 4. Other channel types fall back to the global default.
+        assert_eq!(agents.resolve_agent(None, "http0").unwrap(), "echo");
+    }
+
+    #[tokio::test]
+    async fn resolve_names_the_routed_agent_without_running_it() {
+        let agents = routing_agents(HashMap::new());
+        let resolve = |channel_id: &str| {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                "agents/resolve",
+                BusPayload::CommsMessage {
+                    channel_id: channel_id.to_string(),
+                    content: "never answered".to_string(),
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                },
+                tx,
+            );
+            rx
+        };
+        match resolve("telegram0:7").await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => assert_eq!(data, r#"{"agent_id":"chat"}"#),
+            other => panic!("unexpected response: {other:?}"),
+        }
+        match resolve("selftest").await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => assert_eq!(data, r#"{"agent_id":"echo"}"#),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_channel_default_falls_back_to_global() {
+        let agents = routing_agents(HashMap::new())
+            .with_channel_defaults(HashMap::from([("http".to_string(), "gmail".to_string())]));
+        assert_eq!(agents.resolve_agent(None, "http0").unwrap(), "echo");
+    }
+
+    #[tokio::test]
+    async fn opened_session_records_stateless_agent_transcript() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_session_transcripts(true);
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents/sessions/open",
+            BusPayload::JsonRequest {
+                data: r#"{"channel_id":"pty0"}"#.to_string(),
+            },
+            tx,
+        );
+        let opened: serde_json::Value = match rx.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => serde_json::from_str(&data).unwrap(),
+            other => panic!("unexpected response: {other:?}"),
+        };
+        assert_eq!(opened["agent_id"], "echo");
+        let session_id = opened["session_id"].as_str().unwrap().to_string();
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "remember me".to_string(),
+                session_id: Some(session_id.clone()),
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                content,
+                session_id: reply_session,
+                ..
+            }) => {
+                assert_eq!(content, "remember me");
+                assert_eq!(reply_session.as_deref(), Some(session_id.as_str()));
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        let handle = agents
+            .state
+            .open_agent_session("echo", Some(&session_id))
+            .unwrap();
+        let entries = handle.transcript_read_last(10).await.unwrap();
+        let roles: Vec<&str> = entries.iter().map(|e| e.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant"]);
+    }
+
+    #[tokio::test]
+    async fn switched_session_moves_to_the_new_agent_with_its_transcript() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string(), "docs".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+        let session_id = session.session_id.clone();
+        session.transcript_append("user", "hello").await.unwrap();
+        drop(session);
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents/sessions/switch",
+            BusPayload::JsonRequest {
+                data: serde_json::json!({
+                    "channel_id": "pty0",
+                    "session_id": session_id,
+                    "to_agent": "docs",
+                })
+                .to_string(),
+            },
+            tx,
+        );
+        let switched: serde_json::Value = match rx.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => serde_json::from_str(&data).unwrap(),
+            other => panic!("unexpected response: {other:?}"),
+        };
+        assert_eq!(switched["agent_id"], "docs");
+        assert_eq!(switched["session_id"], session_id.as_str());
+
+        assert!(
+            agents
+                .state
+                .open_agent_session("echo", Some(&session_id))
+                .is_err()
+        );
+        let moved = agents
+            .state
+            .open_agent_session("docs", Some(&session_id))
+            .unwrap();
+        let entries = moved.transcript_read_last(10).await.unwrap();
+        assert_eq!(entries[0].content, "hello");
+    }
+
+    #[tokio::test]
+    async fn session_create_is_listed_and_validates_store_types() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_session_transcripts(true);
+
+        async fn request(agents: &AgentsSubsystem, method: &str, data: &str) -> BusResult {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                method,
+                BusPayload::JsonRequest {
+                    data: data.to_string(),
+                },
+                tx,
+            );
+            rx.await.unwrap()
+        }
+
+        let created = match request(
+            &agents,
+            "agents/sessions/create",
+            r#"{"agent_id":"echo","store_types":["basic_session"]}"#,
+        )
+        .await
+        {
+            Ok(BusPayload::JsonResponse { data }) => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap()
+            }
+            other => panic!("unexpected response: {other:?}"),
+        };
+        let session_id = created["session_id"].as_str().unwrap();
+        assert_eq!(created["agent_id"], "echo");
+
+        let listed = match request(&agents, "agents/sessions", "{}").await {
+            Ok(BusPayload::JsonResponse { data }) => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap()
+            }
+            other => panic!("unexpected response: {other:?}"),
+        };
+        let entry = listed["sessions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["session_id"] == session_id)
+            .expect("created session is listed");
+        assert_eq!(entry["store_types"], serde_json::json!(["basic_session"]));
+        assert_eq!(entry["last_agent"], "echo");
+
+        // A message sent with the new id lands in that session.
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "http0".to_string(),
+                content: "first message".to_string(),
+                session_id: Some(session_id.to_string()),
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                session_id: replied,
+                ..
+            }) => assert_eq!(replied.as_deref(), Some(session_id)),
+            other => panic!("unexpected response: {other:?}"),
+        }
+        let transcript = agents
+            .state
+            .open_agent_session("echo", Some(session_id))
+            .unwrap()
+            .transcript_read_last(10)
+            .await
+            .unwrap();
+        let roles: Vec<&str> = transcript.iter().map(|e| e.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant"]);
+        assert_eq!(transcript[0].content, "first message");
+
+        for bad in [
+            r#"{"store_types":["no_such_store"]}"#,
+            r#"{"store_types":[]}"#,
+            r#"{"agent_id":"ghost"}"#,
+        ] {
+            match request(&agents, "agents/sessions/create", bad).await {
+                Err(e) => assert_eq!(e.code, -32602, "{bad}: {}", e.message),
+                other => panic!("{bad}: unexpected response: {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn ephemeral_session_open_writes_no_session_dir() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        async fn open(agents: &AgentsSubsystem, data: &str) -> String {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                "agents/sessions/open",
+                BusPayload::JsonRequest {
+                    data: data.to_string(),
+                },
+                tx,
+            );
+            match rx.await.unwrap() {
+                Ok(BusPayload::JsonResponse { data }) => {
+                    let v: serde_json::Value = serde_json::from_str(&data).unwrap();
+                    v["session_id"].as_str().unwrap().to_string()
+                }
+                other => panic!("unexpected response: {other:?}"),
+            }
+        }
+
+        let ephemeral = open(&agents, r#"{"channel_id":"http0","ephemeral":true}"#).await;
+        let persistent = open(&agents, r#"{"channel_id":"pty0"}"#).await;
+
+        let store = agents.state.open_agent_store("echo").unwrap();
+        let sessions_dir = store.agent_sessions_dir();
+        assert!(!sessions_dir.join(&ephemeral).exists());
+        assert!(sessions_dir.join(&persistent).is_dir());
+
+        // Closing drops the ephemeral session's entry; persistent ones stay.
+        let close = |session_id: &str| {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                "agents/sessions/close",
+                BusPayload::JsonRequest {
+                    data: serde_json::json!({ "channel_id": "http0", "session_id": session_id })
+                        .to_string(),
+                },
+                tx,
+            );
+            rx
+        };
+        assert!(close(&ephemeral).await.unwrap().is_ok());
+        assert!(close(&persistent).await.unwrap().is_err());
+        let indexed: Vec<String> = MemorySystem::list_sessions_in(&store.agent_sessions_index())
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        assert_eq!(indexed, [persistent]);
+    }
+
+    #[tokio::test]
+    async fn session_transcripts_off_leaves_session_untouched() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "forget me".to_string(),
+                session_id: Some(session.session_id.clone()),
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
+        assert!(matches!(
+            rx.await.unwrap(),
+            Ok(BusPayload::CommsMessage { .. })
+        ));
+        assert!(session.transcript_read_last(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn spend_alert_is_sent_once_per_session() {
+        let bus = SupervisorBus::new(16);
+        let handle = bus.handle.clone();
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            spend_alert_usd: Some(2.5),
+            ..AgentsConfig::default()
+        };
+        // $1 per input token.
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_llm_rates(
+                "test-model",
+                ModelRates {
+                    input_per_million_usd: 1_000_000.0,
+                    output_per_million_usd: 0.0,
+                    cached_input_per_million_usd: 0.0,
+                },
+            );
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+        let usage = LlmUsage {
+            input_tokens: 1,
+            ..Default::default()
+        };
+
+        let mut alerts = Vec::new();
+        for _ in 0..5 {
+            agents.state.record_spend(&session, &usage).await.unwrap();
+            while let Ok(msg) = rx.try_recv() {
+                if let BusMessage::Notification { method, payload } = msg
+                    && method == "manage/spend/alert"
+                {
+                    alerts.push(payload);
+                }
+            }
+        }
+
+        assert_eq!(alerts.len(), 1, "expected exactly one alert: {alerts:?}");
+        match &alerts[0] {
+            BusPayload::SpendAlert {
+                session_id,
+                total_cost_usd,
+            } => {
+                assert_eq!(session_id, &session.session_id);
+                assert!((total_cost_usd - 3.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected payload: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reply_meta_names_the_model_that_ran_the_reply() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let agents = AgentsSubsystem::new(AgentsConfig::default(), handle, memory)
+            .unwrap()
+            .with_llm_rates("default-model", ModelRates::default());
+        let usage = LlmUsage {
+            output_tokens: 7,
+            model: Some("routed-model".to_string()),
+            ..Default::default()
+        };
+
+        let meta = agents.state.reply_meta(Some(&usage), None);
+        assert_eq!(meta["model"], "routed-model");
+        assert_eq!(meta["usage"]["output_tokens"], 7);
+        // Unreported: the active model, as spend is counted.
+        assert_eq!(
+            agents.state.reply_meta(None, None),
+            serde_json::json!({ "model": "default-model" })
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_hits_are_recorded_and_billed_at_the_cached_rate() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_llm_rates(
+                "test-model",
+                ModelRates {
+                    input_per_million_usd: 1.0,
+                    output_per_million_usd: 4.0,
+                    cached_input_per_million_usd: 0.1,
+                },
+            );
+        let fresh = LlmUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000,
+            ..Default::default()
+        };
+        let cached = LlmUsage {
+            cached_input_tokens: 800_000,
+            ..fresh.clone()
+        };
+
+        let a = agents.state.open_agent_session("echo", None).unwrap();
+        let b = agents.state.open_agent_session("echo", None).unwrap();
+        let fresh_spend = agents.state.record_spend(&a, &fresh).await.unwrap();
+        let cached_spend = agents.state.record_spend(&b, &cached).await.unwrap();
+
+        assert_eq!(fresh_spend.total_cached_tokens, 0);
+        assert_eq!(cached_spend.total_cached_tokens, 800_000);
+        assert_eq!(
+            b.read_spend().await.unwrap().unwrap().total_cached_tokens,
+            800_000
+        );
+        // 0.2M fresh at $1 + 0.8M cached at $0.10, versus 1M fresh at $1.
+        assert!((fresh_spend.total_cost_usd - 1.004).abs() < 1e-9);
+        assert!((cached_spend.total_cost_usd - 0.284).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn spend_is_booked_under_the_model_that_ran_the_completion() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let rates = |input_per_million_usd| ModelRates {
+            input_per_million_usd,
+            ..ModelRates::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_llm_rates("default-model", rates(1.0))
+            .with_model_rates(HashMap::from([
+                ("default-model".to_string(), rates(1.0)),
+                ("strong-model".to_string(), rates(10.0)),
+            ]));
+        let usage = |model: Option<&str>| LlmUsage {
+            input_tokens: 1_000_000,
+            model: model.map(str::to_string),
+            ..Default::default()
+        };
+
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+        agents
+            .state
+            .record_spend(&session, &usage(Some("strong-model")))
+            .await
+            .unwrap();
+        let spend = agents
+            .state
+            .record_spend(&session, &usage(None))
+            .await
+            .unwrap();
+
+        assert!((spend.by_model["strong-model"].cost_usd - 10.0).abs() < 1e-9);
+        assert!((spend.by_model["default-model"].cost_usd - 1.0).abs() < 1e-9);
+        assert!((spend.total_cost_usd - 11.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn daily_output_budget_refuses_until_the_next_utc_day() {
+        use araliya_memory::clock::MockClock;
+
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            daily_output_token_budget: Some(100),
+            ..AgentsConfig::default()
+        };
+        // 2023-11-14T22:13:20Z — under two hours to midnight.
+        let clock = Arc::new(MockClock::new(1_700_000_000));
+        let (_bus, handle) = echo_bus();
+        let agents = AgentsSubsystem::new(cfg.clone(), handle, memory.clone())
+            .unwrap()
+            .with_clock(clock.clone());
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+        let usage = LlmUsage {
+            output_tokens: 60,
+            ..Default::default()
+        };
+
+        agents.state.record_spend(&session, &usage).await.unwrap();
+        assert!(
+            agents
+                .state
+                .daily_budget_refusal(&session, "pty0")
+                .await
+                .is_none()
+        );
+
+        agents.state.record_spend(&session, &usage).await.unwrap();
+        match agents.state.daily_budget_refusal(&session, "pty0").await {
+            Some(Ok(BusPayload::CommsMessage {
+                content,
+                session_id,
+                usage: None,
+                ..
+            })) => {
+                assert_eq!(content, DAILY_BUDGET_REPLY);
+                assert_eq!(session_id.as_deref(), Some(session.session_id.as_str()));
+            }
+            other => panic!("expected a refusal, got {other:?}"),
+        }
+
+        // The count lives in spend.json, so a restart keeps the refusal.
+        let session_id = session.session_id.clone();
+        drop(session);
+        drop(agents);
+        let (_bus, handle) = echo_bus();
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_clock(clock.clone());
+        let session = agents
+            .state
+            .open_agent_session("echo", Some(&session_id))
+            .unwrap();
+        assert!(
+            agents
+                .state
+                .daily_budget_refusal(&session, "pty0")
+                .await
+                .is_some()
+        );
+
+        clock.advance(Duration::from_secs(2 * 3600));
+        assert!(
+            agents
+                .state
+                .daily_budget_refusal(&session, "pty0")
+                .await
+                .is_none()
+        );
+        let spend = agents.state.record_spend(&session, &usage).await.unwrap();
+        assert_eq!(spend.day, "2023-11-15");
+        assert_eq!(spend.day_output_tokens, 60);
+        assert_eq!(spend.total_output_tokens, 180);
+    }
+
+    #[tokio::test]
+    async fn session_memory_clear_empties_working_memory() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        let session = agents.state.open_agent_session("echo", None).unwrap();
+        session.working_memory_write("stale notes").await.unwrap();
+        session.transcript_append("user", "keep me").await.unwrap();
+
+        let query = |session_id: &str| BusPayload::SessionQuery {
+            session_id: session_id.to_string(),
+            agent_id: Some("echo".to_string()),
+        };
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request(
+            "agents/sessions/memory/clear",
+            query(&session.session_id),
+            tx,
+        );
+        assert!(matches!(
+            rx.await.unwrap(),
+            Ok(BusPayload::JsonResponse { .. })
+        ));
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request("agents/sessions/memory", query(&session.session_id), tx);
+        match rx.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => {
+                let body: serde_json::Value = serde_json::from_str(&data).unwrap();
+                assert_eq!(body["content"], "");
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert_eq!(session.transcript_read_last(10).await.unwrap().len(), 1);
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request("agents/sessions/memory/clear", query("missing"), tx);
+        match rx.await.unwrap() {
+            Err(e) => assert!(e.message.contains("session not found"), "{}", e.message),
+            other => panic!("expected an error, got {other:?}"),
+        }
+    }
+
+    fn unknown_default_config(strict_default: bool) -> AgentsConfig {
+        AgentsConfig {
+            default_agent: "ghost".to_string(),
+            enabled: HashSet::from(["ghost".to_string()]),
+            strict_default,
+            ..AgentsConfig::default()
+        }
+    }
+
+    #[cfg(feature = "plugin-echo")]
+    #[tokio::test]
+    async fn missing_default_agent_falls_back_to_echo() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let agents = AgentsSubsystem::new(unknown_default_config(false), handle, memory).unwrap();
+        assert_eq!(agents.default_agent, "echo");
+        assert_eq!(agents.resolve_agent(None, "pty0").unwrap(), "echo");
+    }
+
+    #[cfg(feature = "plugin-echo")]
+    #[tokio::test]
+    async fn missing_default_agent_is_fatal_when_strict() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let err = match AgentsSubsystem::new(unknown_default_config(true), handle, memory) {
+            Ok(_) => panic!("strict_default must reject an unknown default agent"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("'ghost'"), "{err}");
+        assert!(err.contains("strict_default"), "{err}");
+    }
+
+    #[test]
+    fn empty_agent_registry_is_refused_unless_noop_fallback() {
+        let mut agents = HashMap::new();
+        let err = ensure_agents_registered("echo".into(), HashSet::new(), &mut agents, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no agents compiled in"), "{err}");
+        assert!(err.contains("plugin-*"), "{err}");
+        assert!(agents.is_empty());
+
+        let (default_agent, enabled) = ensure_agents_registered(
+            "echo".into(),
+            HashSet::from(["echo".to_string()]),
+            &mut agents,
+            true,
+        )
+        .unwrap();
+        assert_eq!(default_agent, NOOP_AGENT);
+        assert!(enabled.is_empty());
+        assert!(agents.contains_key(NOOP_AGENT));
+    }
+
+    /// Only meaningful in a build without any agent plugin, e.g.
+    /// `cargo test -p araliya-agents --no-default-features --features subsystem-agents`.
+    #[cfg(not(any(
+        feature = "plugin-echo",
+        feature = "plugin-basic-chat",
+        feature = "plugin-chat",
+        feature = "plugin-agentic-chat",
+        feature = "plugin-docs",
+        feature = "plugin-gmail-agent",
+        feature = "plugin-news-agent",
+        feature = "plugin-gdelt-news-agent",
+        feature = "plugin-newsroom-agent",
+        feature = "plugin-news-aggregator",
+        feature = "plugin-test-rssnews",
+        feature = "plugin-summarize",
+        feature = "plugin-sysinfo",
+        feature = "plugin-runtime-cmd",
+        feature = "plugin-uniweb",
+        feature = "plugin-webbuilder",
+        feature = "plugin-homebuilder",
+    )))]
+    #[tokio::test]
+    async fn subsystem_without_plugins_reports_clear_error() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let err = match AgentsSubsystem::new(AgentsConfig::default(), handle.clone(), memory) {
+            Ok(_) => panic!("a build without agents must not start silently"),
+            Err(e) => e.to_string(),
+        };
+        assert!(
+            err.contains("no agents compiled in; enable at least one plugin-* feature"),
+            "{err}"
+        );
+
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            noop_fallback: true,
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+        assert_eq!(agents.resolve_agent(None, "pty0").unwrap(), NOOP_AGENT);
+    }
+
+    #[cfg(not(feature = "plugin-gmail-agent"))]
+    #[test]
+    fn missing_agent_reason_names_feature_flag() {
+        let reason = missing_agent_reason("gmail");
+        assert!(reason.contains("`plugin-gmail-agent`"), "{reason}");
+    }
+
+    #[tokio::test]
+    async fn maintenance_mode_short_circuits_agent_routing() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            ..AgentsConfig::default()
+        };
+        let maintenance = Maintenance::new(true, "back soon");
+        let agents = AgentsSubsystem::new(cfg, handle, memory)
+            .unwrap()
+            .with_maintenance(maintenance.clone());
+
+        let send = |content: &str| {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                "agents",
+                BusPayload::CommsMessage {
+                    channel_id: "pty0".to_string(),
+                    content: content.to_string(),
+                    session_id: Some("s1".to_string()),
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                },
+                tx,
+            );
+            rx
+        };
+
+        match send("hello").await.unwrap() {
+            Ok(BusPayload::CommsMessage {
+                content,
+                session_id,
+                ..
+            }) => {
+                assert_eq!(content, "back soon");
+                assert_eq!(session_id.as_deref(), Some("s1"));
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        let (tx, rx) = oneshot::channel();
+        agents.handle_request("agents/health", BusPayload::Empty, tx);
+        assert!(matches!(
+            rx.await.unwrap(),
+            Ok(BusPayload::JsonResponse { .. })
+        ));
+
+        maintenance.set_enabled(false);
+        match send("hello").await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "hello"),
+            other => panic!("unexpected response: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn cooldown_rejects_rapid_calls_per_channel() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            agent_cooldowns: HashMap::from([("echo".to_string(), 200)]),
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: Default::default(),
+            agent_context_turns: HashMap::new(),
+            agent_seed_memory: HashMap::new(),
+            agent_max_sessions: HashMap::new(),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let send = |channel_id: &str, content: &str| {
+            let (tx, rx) = oneshot::channel();
+            agents.handle_request(
+                "agents",
+                BusPayload::CommsMessage {
+                    channel_id: channel_id.to_string(),
+                    content: content.to_string(),
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                },
+                tx,
+            );
+            rx
+        };
+        let content = |result: BusResult| match result {
+            Ok(BusPayload::CommsMessage { content, .. }) => content,
+            other => panic!("unexpected response: {other:?}"),
+        };
+
+        assert_eq!(content(send("pty0", "one").await.unwrap()), "one");
+        let wait = content(send("pty0", "two").await.unwrap());
+        assert!(wait.starts_with("Please wait"), "got: {wait}");
+        assert!(wait.contains("'echo'"));
+
+        // Other channels have their own timer.
+        assert_eq!(content(send("pty1", "three").await.unwrap()), "three");
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(content(send("pty0", "four").await.unwrap()), "four");
+    }
+
+    // ── echo agent options ───────────────────────────────────────────────────
+
+    #[cfg(feature = "plugin-echo")]
+    #[test]
+    fn echo_defaults_to_verbatim() {
+        let echo = EchoAgent::new(Default::default());
+        assert_eq!(echo.transform("Hello World"), "Hello World");
+    }
+
+    #[cfg(feature = "plugin-echo")]
+    #[test]
+    fn echo_applies_prefix_and_suffix() {
+        let echo = EchoAgent::new(araliya_core::config::EchoAgentConfig {
+            prefix: "> ".to_string(),
+            suffix: " <".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(echo.transform("hi"), "> hi <");
+    }
+
+    #[cfg(feature = "plugin-echo")]
+    #[test]
+    fn echo_applies_case_transform() {
+        use araliya_core::config::{EchoAgentConfig, EchoCase};
+        let upper = EchoAgent::new(EchoAgentConfig {
+            case: EchoCase::Upper,
+            ..Default::default()
+        });
+        assert_eq!(upper.transform("Hello World"), "HELLO WORLD");
+        let lower = EchoAgent::new(EchoAgentConfig {
+            prefix: "A: ".to_string(),
+            case: EchoCase::Lower,
+            ..Default::default()
+        });
+        // The prefix is not case-transformed.
+        assert_eq!(lower.transform("Hello World"), "A: hello world");
+    }
+
+    #[cfg(feature = "plugin-echo")]
+    #[tokio::test]
+    async fn echo_delay_postpones_reply() {
+        let (_bus, handle) = echo_bus();
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            default_agent: "echo".to_string(),
+            enabled: HashSet::from(["echo".to_string()]),
+            echo: Some(araliya_core::config::EchoAgentConfig {
+                delay_ms: 100,
+                ..Default::default()
+            }),
+            ..AgentsConfig::default()
+        };
+        let agents = AgentsSubsystem::new(cfg, handle, memory).unwrap();
+
+        let start = Instant::now();
+        let (tx, mut rx) = oneshot::channel();
+        agents.handle_request(
+            "agents",
+            BusPayload::CommsMessage {
+                channel_id: "pty0".to_string(),
+                content: "slow".to_string(),
+                session_id: None,
+                usage: None,
+                timing: None,
+                thinking: None,
+            },
+            tx,
+        );
+        assert!(rx.try_recv().is_err(), "reply should not be immediate");
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "slow"),
+            other => panic!("unexpected response: {other:?}"),
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    /// Hands every message on to `to`, replying with whatever comes back.
+    struct HandoffAgent {
+        id: &'static str,
+        to: &'static str,
+    }
+
+    impl Agent for HandoffAgent {
+        fn id(&self) -> &str {
+            self.id
+        }
+        fn handle(
+            &self,
+            _action: String,
+            channel_id: String,
+            content: String,
+            session_id: Option<String>,
+            reply_tx: oneshot::Sender<BusResult>,
+            state: Arc<AgentsState>,
+        ) {
+            let to = self.to;
+            request::spawn(async move {
+                let reply = state
+                    .dispatch_to_agent(to, "handle", &content, &channel_id, session_id)
+                    .await;
+                let _ = reply_tx.send(reply);
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn handoff_cycle_stops_at_max_depth() {
+        let bus = SupervisorBus::new(16);
+        let mut rx = bus.rx;
+        let (_dir, memory) = test_memory();
+        let cfg = AgentsConfig {
+            enabled: HashSet::from(["ping".to_string(), "pong".to_string()]),
+            max_depth: 4,
+            ..AgentsConfig::default()
+        };
+        let mut agents = AgentsSubsystem::new(cfg, bus.handle.clone(), memory).unwrap();
+        for (id, to) in [("ping", "pong"), ("pong", "ping")] {
+            agents.agents.insert(
+                id.to_string(),
+                AgentRegistration::new(
+                    AgentRuntimeClass::RequestResponse,
+                    Box::new(HandoffAgent { id, to }),
+                ),
+            );
+        }
+        let agents = Arc::new(agents);
+
+        // Route handoffs back into the subsystem, counting them.
+        let router = agents.clone();
+        let hops = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = hops.clone();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                router.handle_request(&method, payload, reply_tx);
+            }
+        });
+
+        let (tx, reply) = oneshot::channel();
+        agents.handle_request("agents/ping", chat_message("hello"), tx);
+        let err = reply.await.unwrap().unwrap_err();
+        assert_eq!(err.code, depth::ERR_MAX_DEPTH);
+        assert!(err.message.contains("max agent depth exceeded"), "{err:?}");
+        // ping -> pong -> ping -> pong -> ping; the fifth hop is refused.
+        assert_eq!(hops.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,335 @@
+//! Link post-processing for chat replies (`[agents] link_policy`).
+//!
+//! [`apply`] finds the `http://` and `https://` links in a reply and, per
+//! [`LinkPolicy`], leaves them, removes them, or replaces each with the
+//! configured template.  A link ends at whitespace, a quote or an angle
+//! bracket; trailing sentence punctuation and unbalanced closing brackets
+//! are not part of it, so "see (https://example.com)." keeps its `).`.
+//!
+//! [`filter_stream`] does the same to a streamed reply, holding back each
+//! delta's tail until no link in it can still grow.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use araliya_core::config::LinkPolicy;
+use araliya_core::types::llm::StreamChunk;
+use tokio::sync::mpsc;
+
+/// `text` with its links handled per `policy`.
+pub fn apply<'a>(policy: &LinkPolicy, text: &'a str) -> Cow<'a, str> {
+    if *policy == LinkPolicy::Allow {
+        return Cow::Borrowed(text);
+    }
+    let links = find_links(text);
+    if links.is_empty() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for link in links {
+        let before = &text[pos..link.start];
+        let url = &text[link.clone()];
+        let after = &text[link.end..];
+        pos = link.end;
+        match policy {
+            LinkPolicy::Allow => unreachable!(),
+            LinkPolicy::Strip => {
+                // `[label](url)` keeps just its label.
+                if let Some(head) = before.strip_suffix("](")
+                    && after.starts_with(')')
+                    && let Some(open) = head.rfind('[')
+                {
+                    out.push_str(&head[..open]);
+                    out.push_str(&head[open + 1..]);
+                    pos += 1;
+                    continue;
+                }
+                out.push_str(before);
+                // Do not leave a double space where the link was.
+                if out.ends_with(' ') && after.starts_with(' ') {
+                    pos += 1;
+                }
+            }
+            LinkPolicy::Rewrite { template } => {
+                out.push_str(before);
+                out.push_str(&template.replace("{url}", url).replace("{host}", host(url)));
+            }
+        }
+    }
+    out.push_str(&text[pos..]);
+    Cow::Owned(out)
+}
+
+/// `rx` with the links in its content and thinking deltas handled per
+/// `policy`.  Dropping the returned receiver drops `rx`.
+pub fn filter_stream(
+    policy: LinkPolicy,
+    mut rx: mpsc::Receiver<StreamChunk>,
+) -> mpsc::Receiver<StreamChunk> {
+    let (tx, filtered) = mpsc::channel(64);
+    tokio::spawn(async move {
+        let mut content = Pending::default();
+        let mut thinking = Pending::default();
+        while let Some(chunk) = rx.recv().await {
+            let chunks = match chunk {
+                StreamChunk::Content(delta) => {
+                    vec![StreamChunk::Content(content.push(&policy, &delta))]
+                }
+                StreamChunk::Thinking(delta) => {
+                    vec![StreamChunk::Thinking(thinking.push(&policy, &delta))]
+                }
+                done @ StreamChunk::Done { .. } => vec![
+                    StreamChunk::Thinking(thinking.finish(&policy)),
+                    StreamChunk::Content(content.finish(&policy)),
+                    done,
+                ],
+            };
+            for chunk in chunks {
+                if matches!(&chunk, StreamChunk::Content(d) | StreamChunk::Thinking(d) if d.is_empty())
+                {
+                    continue;
+                }
+                if tx.send(chunk).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    filtered
+}
+
+/// Streamed text not yet sent, because a link in it may not have ended.
+#[derive(Default)]
+struct Pending {
+    text: String,
+    /// The text sent so far ends with a space.
+    sent_space: bool,
+}
+
+impl Pending {
+    /// Add `delta`; returns the text that is now safe to send.
+    fn push(&mut self, policy: &LinkPolicy, delta: &str) -> String {
+        self.text.push_str(delta);
+        let ready = ready_len(&self.text);
+        let text: String = self.text.drain(..ready).collect();
+        self.send(policy, &text)
+    }
+
+    /// Everything still held back, at the end of the stream.
+    fn finish(&mut self, policy: &LinkPolicy) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.send(policy, &text)
+    }
+
+    fn send(&mut self, policy: &LinkPolicy, text: &str) -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        // With the space already sent in front, a stripped link at the start
+        // of `text` does not leave a double space.
+        let out = if self.sent_space {
+            let out = apply(policy, &format!(" {text}")).into_owned();
+            out[1..].to_string()
+        } else {
+            apply(policy, text).into_owned()
+        };
+        if !out.is_empty() {
+            self.sent_space = out.ends_with(' ');
+        }
+        out
+    }
+}
+
+/// How much of streamed `text` can be handled now: through its last
+/// whitespace (a link ends there), and not past a Markdown `[label](` whose
+/// link has not closed, so `strip` can still keep just the label.
+fn ready_len(text: &str) -> usize {
+    let mut ready = text
+        .char_indices()
+        .rfind(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    if let Some(open) = text[..ready].rfind('[') {
+        let label_open = match text[open..].find(']') {
+            None => true,
+            Some(close) => {
+                let rest = &text[open + close..];
+                rest.starts_with("](") && !rest.contains(')')
+            }
+        };
+        if label_open {
+            ready = open;
+        }
+    }
+    ready
+}
+
+/// Byte ranges of the `http(s)://` links in `text`.
+fn find_links(text: &str) -> Vec<Range<usize>> {
+    // ASCII lowercasing keeps byte offsets, so ranges apply to `text`.
+    let lower = text.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find("http") {
+        let start = from + found;
+        let scheme_len = if lower[start..].starts_with("https://") {
+            8
+        } else if lower[start..].starts_with("http://") {
+            7
+        } else {
+            from = start + 4;
+            continue;
+        };
+        // "xhttp://" is not a link.
+        if text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+        {
+            from = start + scheme_len;
+            continue;
+        }
+
+        let body = start + scheme_len;
+        let mut end = text[body..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '`'))
+            .map_or(text.len(), |i| body + i);
+        while end > body {
+            let url = &text[start..end];
+            let last = url.chars().next_back().unwrap_or_default();
+            let trailing = match last {
+                '.' | ',' | ';' | ':' | '!' | '?' => true,
+                ')' => url.matches('(').count() < url.matches(')').count(),
+                ']' => url.matches('[').count() < url.matches(']').count(),
+                _ => false,
+            };
+            if !trailing {
+                break;
+            }
+            end -= last.len_utf8();
+        }
+
+        if end > body {
+            links.push(start..end);
+        }
+        from = end.max(body);
+    }
+    links
+}
+
+/// Host part of `url`: no scheme, credentials, port or path.
+fn host(url: &str) -> &str {
+    let authority = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = authority.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+    if authority.starts_with('[') {
+        // IPv6 literal.
+        return authority
+            .split_once(']')
+            .map_or(authority, |(h, _)| &authority[..h.len() + 1]);
+    }
+    authority.split(':').next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLY: &str = "Read https://docs.example.com/guide?x=1. Also see \
+                         [the FAQ](http://example.org/faq) or (https://en.wikipedia.org/wiki/Rust_(language)).";
+
+    #[test]
+    fn allow_leaves_links_untouched() {
+        assert!(matches!(
+            apply(&LinkPolicy::Allow, REPLY),
+            Cow::Borrowed(text) if text == REPLY
+        ));
+    }
+
+    #[test]
+    fn strip_removes_links() {
+        assert_eq!(
+            apply(&LinkPolicy::Strip, REPLY),
+            "Read . Also see the FAQ or ()."
+        );
+        assert_eq!(
+            apply(&LinkPolicy::Strip, "go to https://x.io now"),
+            "go to now"
+        );
+        // Not links: no scheme, or a scheme inside a word.
+        let plain = "visit example.com or xhttp://nope";
+        assert_eq!(apply(&LinkPolicy::Strip, plain), plain);
+    }
+
+    #[test]
+    fn rewrite_applies_the_template() {
+        let policy = LinkPolicy::Rewrite {
+            template: "https://safe.example/?u={url} [{host}]".to_string(),
+        };
+        assert_eq!(
+            apply(&policy, "See HTTPS://user@Docs.Example.com:8443/a, thanks"),
+            "See https://safe.example/?u=HTTPS://user@Docs.Example.com:8443/a \
+             [Docs.Example.com], thanks"
+        );
+        let policy = LinkPolicy::Rewrite {
+            template: "[link removed]".to_string(),
+        };
+        assert_eq!(
+            apply(&policy, REPLY),
+            "Read [link removed]. Also see [the FAQ]([link removed]) or ([link removed])."
+        );
+    }
+
+    #[tokio::test]
+    async fn streamed_links_are_handled_across_deltas() {
+        let (tx, rx) = mpsc::channel(16);
+        let mut filtered = filter_stream(LinkPolicy::Strip, rx);
+        for delta in [
+            "Read htt",
+            "ps://docs.exam",
+            "ple.com/guide?x=1. Also see [the ",
+            "FAQ](http://example.org",
+            "/faq) or (https://en.wikipedia.org/wiki/Rust_(language)).",
+        ] {
+            tx.send(StreamChunk::Content(delta.to_string()))
+                .await
+                .unwrap();
+        }
+        tx.send(StreamChunk::Thinking("ask ".to_string()))
+            .await
+            .unwrap();
+        tx.send(StreamChunk::Thinking("https://a.b first".to_string()))
+            .await
+            .unwrap();
+        tx.send(StreamChunk::Done {
+            usage: None,
+            timing: None,
+            error: None,
+            stopped: false,
+        })
+        .await
+        .unwrap();
+        drop(tx);
+
+        let (mut content, mut thinking, mut done) = (String::new(), String::new(), false);
+        while let Some(chunk) = filtered.recv().await {
+            assert!(!done, "nothing follows Done");
+            match chunk {
+                StreamChunk::Content(d) => content.push_str(&d),
+                StreamChunk::Thinking(d) => thinking.push_str(&d),
+                StreamChunk::Done { .. } => done = true,
+            }
+        }
+        assert!(done);
+        assert_eq!(content, "Read . Also see the FAQ or ().");
+        assert_eq!(thinking, "ask first");
+    }
+
+    #[test]
+    fn host_drops_credentials_port_and_path() {
+        assert_eq!(host("https://a.b/c"), "a.b");
+        assert_eq!(host("http://u:p@a.b:80?q"), "a.b");
+        assert_eq!(host("http://[::1]:8080/x"), "[::1]");
+    }
+}
@@ -0,0 +1,121 @@
+//! Content moderation for chat agents (`[agents.moderation]`).
+//!
+//! A [`Moderator`] decides whether a piece of text may pass.  Chat agents run
+//! it on user input before the LLM call and on the model's reply before it
+//! goes out; blocked content is answered with the configured refusal.  With
+//! no `[agents.moderation]` section nothing is checked.
+
+use std::sync::Arc;
+
+use regex::Regex;
+
+use araliya_core::config::ModerationConfig;
+use araliya_core::error::AppError;
+
+/// Outcome of a [`Moderator::check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModerationResult {
+    Allowed,
+    /// Blocked; `reason` names the rule that matched (for logs only).
+    Blocked {
+        reason: String,
+    },
+}
+
+/// Pluggable content check.
+pub trait Moderator: Send + Sync {
+    fn check(&self, text: &str) -> ModerationResult;
+}
+
+/// Blocks text containing any configured term (case-insensitive) or
+/// matching any configured regular expression.
+pub struct BlocklistModerator {
+    terms: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl BlocklistModerator {
+    /// Compile the blocklist; an invalid pattern is a config error.
+    pub fn new(terms: &[String], patterns: &[String]) -> Result<Self, AppError> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| {
+                    AppError::Config(format!("agents.moderation: invalid pattern {p:?}: {e}"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            terms: terms.iter().map(|t| t.to_lowercase()).collect(),
+            patterns,
+        })
+    }
+}
+
+impl Moderator for BlocklistModerator {
+    fn check(&self, text: &str) -> ModerationResult {
+        let lower = text.to_lowercase();
+        if let Some(term) = self.terms.iter().find(|t| lower.contains(t.as_str())) {
+            return ModerationResult::Blocked {
+                reason: format!("term {term:?}"),
+            };
+        }
+        if let Some(re) = self.patterns.iter().find(|re| re.is_match(text)) {
+            return ModerationResult::Blocked {
+                reason: format!("pattern {:?}", re.as_str()),
+            };
+        }
+        ModerationResult::Allowed
+    }
+}
+
+/// A moderator paired with the reply that replaces blocked content.
+#[derive(Clone)]
+pub struct Moderation {
+    pub moderator: Arc<dyn Moderator>,
+    pub refusal: String,
+}
+
+impl Moderation {
+    /// Build the configured [`BlocklistModerator`].
+    pub fn from_config(config: &ModerationConfig) -> Result<Self, AppError> {
+        Ok(Self {
+            moderator: Arc::new(BlocklistModerator::new(
+                &config.blocked_terms,
+                &config.blocked_patterns,
+            )?),
+            refusal: config.refusal.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocklist_matches_terms_and_patterns() {
+        let m = BlocklistModerator::new(
+            &["Forbidden".to_string()],
+            &[r"\bcard\s+\d{4}\b".to_string()],
+        )
+        .unwrap();
+        assert_eq!(m.check("all fine here"), ModerationResult::Allowed);
+        assert!(matches!(
+            m.check("this is FORBIDDEN talk"),
+            ModerationResult::Blocked { .. }
+        ));
+        assert!(matches!(
+            m.check("my card 1234 please"),
+            ModerationResult::Blocked { .. }
+        ));
+    }
+
+    #[test]
+    fn invalid_pattern_is_a_config_error() {
+        let err = BlocklistModerator::new(&[], &["(".to_string()])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("invalid pattern"), "{err}");
+    }
+}
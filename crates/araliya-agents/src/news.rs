@@ -9,7 +9,8 @@ use araliya_core::bus::message::{BusError, BusPayload, BusResult, ERR_METHOD_NOT
 use araliya_memory::stores::agent::TextItem;
 
 use super::core::prompt::PromptBuilder;
-use super::{Agent, AgentsState};
+use super::tool_output::truncate_tool_output;
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 const NO_NEWS_MSG: &str = "No new news emails.";
 
@@ -20,6 +21,10 @@ impl Agent for NewsAgentPlugin {
         "news"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Summarises new newsletter emails.").with_tools()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -29,7 +34,7 @@ impl Agent for NewsAgentPlugin {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             if action == "health" {
                 let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
                     channel_id,
@@ -56,6 +61,7 @@ impl Agent for NewsAgentPlugin {
             // ── 1. Fetch from tool ──────────────────────────────────────
             let result = state
                 .execute_tool(
+                    "news",
                     "newsmail_aggregator",
                     tool_action,
                     state.news_query_args_json.clone(),
@@ -78,13 +84,7 @@ impl Agent for NewsAgentPlugin {
                 Ok(BusPayload::ToolResponse {
                     ok: false, error, ..
                 }) => {
-                    let _ = reply_tx.send(Err(BusError::new(
-                        -32000,
-                        format!(
-                            "newsmail_aggregator tool error: {}",
-                            error.unwrap_or_else(|| "unknown".to_string())
-                        ),
-                    )));
+                    let _ = reply_tx.send(Err(BusError::tool_failed("newsmail_aggregator", error)));
                     return;
                 }
                 Ok(other) => {
@@ -167,10 +167,19 @@ impl Agent for NewsAgentPlugin {
                 return;
             }
 
-            // ── 6. Ask LLM to summarise ─────────────────────────────────
+            // ── 6. Ask LLM to summarise (bounded by [agents.tool_output]) ──
+            let shaped = truncate_tool_output(
+                serde_json::from_str(&raw_json).unwrap_or_default(),
+                &state.tool_output,
+            );
+            let prompt_items = tool_items(shaped.value.clone());
             let news_skills = state.agent_skills.get("news").cloned().unwrap_or_default();
-            let (system, user_prompt) =
-                build_summary_prompt(&items, &news_skills, &state.agents_dir);
+            let (system, user_prompt) = build_summary_prompt(
+                &prompt_items,
+                shaped.note().as_deref(),
+                &news_skills,
+                &state.agents_dir,
+            );
             let llm_result = state
                 .complete_via_llm_with_system(&channel_id, &user_prompt, Some(&system))
                 .await;
@@ -214,14 +223,15 @@ impl Agent for NewsAgentPlugin {
             // This would require parsing the email JSON for a URL field or using
             // an LLM pass to extract URLs from email bodies.
 
-            let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+            let reply = Ok(BusPayload::CommsMessage {
                 channel_id,
                 content: summary,
                 session_id,
                 usage,
                 timing: None,
                 thinking,
-            }));
+            });
+            let _ = reply_tx.send(state.replace_empty_reply("news", reply));
         });
     }
 }
@@ -260,9 +270,11 @@ async fn persist_summary(state: &Arc<AgentsState>, cache_key: &str, summary: &st
 /// Format [`TextItem`]s into an LLM summarisation prompt using layered templates.
 ///
 /// Returns `(system, user)`: the preamble layers as the system message and the
-/// task-specific body as the user message.
+/// task-specific body as the user message.  `truncation_note` follows the
+/// item list when the tool output was cut.
 fn build_summary_prompt(
     items: &[TextItem],
+    truncation_note: Option<&str>,
     tools: &[String],
     agents_dir: &str,
 ) -> (String, String) {
@@ -283,6 +295,11 @@ fn build_summary_prompt(
             date
         ));
     }
+    if let Some(note) = truncation_note {
+        items_str.push('\n');
+        items_str.push_str(note);
+        items_str.push('\n');
+    }
 
     let system = super::core::prompt::preamble(agents_dir, tools).build();
 
@@ -304,7 +321,14 @@ fn build_summary_prompt(
 /// fields (`"subject"`, `"from"`, `"date"`, `"id"`) are also extracted
 /// into `metadata` for later filtering or display.
 fn parse_tool_items(json: &str) -> Vec<TextItem> {
-    let Ok(serde_json::Value::Array(arr)) = serde_json::from_str(json) else {
+    serde_json::from_str(json)
+        .map(tool_items)
+        .unwrap_or_default()
+}
+
+/// [`parse_tool_items`] for an already-parsed value; non-arrays yield nothing.
+fn tool_items(value: serde_json::Value) -> Vec<TextItem> {
+    let serde_json::Value::Array(arr) = value else {
         return Vec::new();
     };
 
@@ -322,3 +346,32 @@ fn parse_tool_items(json: &str) -> Vec<TextItem> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::config::ToolOutputLimits;
+
+    #[test]
+    fn oversized_tool_output_is_truncated_in_prompt() {
+        let raw: Vec<serde_json::Value> = (0..30)
+            .map(|i| serde_json::json!({ "subject": format!("Issue {i}"), "from": "news@x" }))
+            .collect();
+        let limits = ToolOutputLimits {
+            max_chars: 2000,
+            max_items: 5,
+        };
+        let shaped = truncate_tool_output(serde_json::Value::Array(raw), &limits);
+        let items = tool_items(shaped.value.clone());
+        assert_eq!(items.len(), 5);
+
+        let (_, user) =
+            build_summary_prompt(&items, shaped.note().as_deref(), &[], "/nonexistent/agents");
+        assert!(user.contains("[5] Issue 4"), "{user}");
+        assert!(!user.contains("Issue 5"), "{user}");
+        assert!(
+            user.contains("[Tool output truncated: showing the first 5 of 30 items.]"),
+            "{user}"
+        );
+    }
+}
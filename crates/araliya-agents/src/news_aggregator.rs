@@ -34,7 +34,7 @@ use araliya_core::bus::message::{BusError, BusPayload, BusResult, ERR_METHOD_NOT
 use araliya_memory::stores::kg_docstore::{IKGDocStore, KgConfig};
 use araliya_memory::stores::sqlite_core::Document;
 
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState};
 
 const MAX_ARTICLE_CHARS: usize = 4_000;
 #[allow(dead_code)]
@@ -85,6 +85,10 @@ impl Agent for NewsAggregatorAgent {
         "news_aggregator"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Summarises article URLs from source agents into a knowledge graph.")
+    }
+
     fn handle(
         &self,
         action: String,
@@ -27,7 +27,7 @@ use araliya_memory::stores::sqlite_core::now_iso8601;
 use araliya_memory::stores::sqlite_store::{SqlValue, SqliteStore};
 
 use super::core::prompt::PromptBuilder;
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 /// Maximum number of events to retain in the SQLite store.
 const EVENT_CAP: i64 = 2500;
@@ -94,6 +94,11 @@ impl Agent for NewsroomAgent {
         "newsroom"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Keeps a store of GDELT events and summarises new ones.")
+            .with_tools()
+    }
+
     fn handle(
         &self,
         action: String,
@@ -111,7 +116,7 @@ impl Agent for NewsroomAgent {
         } else {
             action
         };
-        tokio::spawn(async move {
+        request::spawn(async move {
             match effective.as_str() {
                 "health" => handle_health(channel_id, session_id, state, reply_tx).await,
                 "status" => handle_status(channel_id, session_id, state, reply_tx).await,
@@ -140,6 +145,7 @@ async fn handle_health(
 ) {
     let result = state
         .execute_tool(
+            "newsroom",
             "gdelt_bigquery",
             "health",
             "{}".to_string(),
@@ -248,6 +254,7 @@ async fn handle_read(
     // ── 1. Fetch from BigQuery tool ──────────────────────────────────────────
     let result = state
         .execute_tool(
+            "newsroom",
             "gdelt_bigquery",
             "fetch",
             state.newsroom_query_args_json.clone(),
@@ -270,13 +277,7 @@ async fn handle_read(
         Ok(BusPayload::ToolResponse {
             ok: false, error, ..
         }) => {
-            let _ = reply_tx.send(Err(BusError::new(
-                -32000,
-                format!(
-                    "newsroom: gdelt_bigquery error: {}",
-                    error.unwrap_or_else(|| "unknown".to_string())
-                ),
-            )));
+            let _ = reply_tx.send(Err(BusError::tool_failed("gdelt_bigquery", error)));
             return;
         }
         Ok(other) => {
@@ -623,7 +624,7 @@ async fn handle_read(
                 "newsroom: triggering aggregator with URLs"
             );
 
-            tokio::spawn(async move {
+            request::spawn(async move {
                 let payload = serde_json::json!({
                     "urls": new_event_urls,
                     "source_agent": "newsroom"
@@ -650,14 +651,15 @@ async fn handle_read(
         }
     }
 
-    let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+    let reply = Ok(BusPayload::CommsMessage {
         channel_id,
         content: summary,
         session_id,
         usage,
         timing: None,
         thinking,
-    }));
+    });
+    let _ = reply_tx.send(state.replace_empty_reply("newsroom", reply));
 }
 
 // ── latest ────────────────────────────────────────────────────────────────────
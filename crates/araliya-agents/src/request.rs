@@ -0,0 +1,87 @@
+//! Per-request context for agent work.
+//!
+//! Every request the agents subsystem handles runs in a [`RequestScope`]:
+//! the [`AgentChain`] its hops are counted on (see [`depth`]) and the reply
+//! format the caller asked for, if any.  Both arrive on the bus in
+//! [`BusPayload::AgentRequest`]; a plain payload gets a new chain and no
+//! format override.
+//!
+//! The scope lives in a task-local, so it follows the request only as far as
+//! its task does: agents spawn their work with [`spawn`] rather than
+//! `tokio::spawn` to keep it.
+//!
+//! [`depth`]: crate::depth
+//! [`BusPayload::AgentRequest`]: araliya_core::bus::message::BusPayload::AgentRequest
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use araliya_core::bus::message::AgentChain;
+use araliya_core::types::llm::ResponseFormat;
+use tokio::task::JoinHandle;
+
+tokio::task_local! {
+    static REQUEST: Arc<RequestScope>;
+}
+
+/// What one inbound request carries through the agent serving it.
+#[derive(Debug)]
+pub struct RequestScope {
+    pub(crate) chain: Mutex<AgentChain>,
+    response_format: Option<ResponseFormat>,
+}
+
+/// Run `f` as part of a request on `chain`, answering in `response_format`
+/// when one was asked for.
+pub fn scope<R>(
+    chain: AgentChain,
+    response_format: Option<ResponseFormat>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let scope = RequestScope {
+        chain: Mutex::new(chain),
+        response_format,
+    };
+    REQUEST.sync_scope(Arc::new(scope), f)
+}
+
+/// Whether the current task is serving a request.
+pub fn in_request() -> bool {
+    REQUEST.try_with(|_| ()).is_ok()
+}
+
+/// The current request's scope, if the task is serving one.
+pub(crate) fn current() -> Option<Arc<RequestScope>> {
+    REQUEST.try_with(Arc::clone).ok()
+}
+
+/// The reply format the current request asked for, if any.
+pub fn response_format() -> Option<ResponseFormat> {
+    REQUEST.try_with(|r| r.response_format).ok().flatten()
+}
+
+/// `tokio::spawn` that keeps the spawned task in the current request.
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match current() {
+        Some(request) => tokio::spawn(REQUEST.scope(request, future)),
+        None => tokio::spawn(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spawned_work_keeps_the_requested_format() {
+        let task = scope(AgentChain::start(), Some(ResponseFormat::Json), || {
+            spawn(async { spawn(async { response_format() }).await.unwrap() })
+        });
+        assert_eq!(task.await.unwrap(), Some(ResponseFormat::Json));
+        assert_eq!(response_format(), None);
+    }
+}
@@ -12,7 +12,7 @@ use tracing::{debug, warn};
 use araliya_core::bus::message::{BusPayload, BusResult};
 use araliya_core::config::RuntimeCmdAgentConfig;
 
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState};
 
 pub(crate) struct RuntimeCmdPlugin {
     runtime: String,
@@ -37,6 +37,10 @@ impl Agent for RuntimeCmdPlugin {
         "runtime_cmd"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Runs each message as code in an external runtime.")
+    }
+
     fn handle(
         &self,
         _action: String,
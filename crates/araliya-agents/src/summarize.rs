@@ -0,0 +1,104 @@
+//! `summarize` agent — one-shot TL;DR of whatever text the user sends.
+//!
+//! Stateless: no session memory is read or written.  The message content is
+//! forwarded verbatim as the user turn, and the `[agents.summarize]` style and
+//! word budget are expressed through the system prompt.
+
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+
+use araliya_core::bus::message::{BusPayload, BusResult};
+use araliya_core::config::{SummarizeAgentConfig, SummaryStyle};
+
+use super::{Agent, AgentCapabilities, AgentsState};
+
+pub(crate) struct SummarizeAgent {
+    system_prompt: String,
+}
+
+impl SummarizeAgent {
+    pub(crate) fn new(config: &SummarizeAgentConfig) -> Self {
+        Self {
+            system_prompt: system_prompt(config),
+        }
+    }
+}
+
+/// Build the system prompt for `config`.
+fn system_prompt(config: &SummarizeAgentConfig) -> String {
+    let shape = match config.style {
+        SummaryStyle::Bullets => {
+            "Write the summary as a short bulleted list, one key point per bullet, using \"- \" markers."
+        }
+        SummaryStyle::Prose => {
+            "Write the summary as one or two plain paragraphs without bullets or headings."
+        }
+    };
+    format!(
+        "You summarize text supplied by the user. Capture the main points, decisions and \
+         figures faithfully; do not add information that is not in the text. {shape} \
+         Use at most {} words. Reply with the summary only, without preamble.",
+        config.max_words
+    )
+}
+
+impl Agent for SummarizeAgent {
+    fn id(&self) -> &str {
+        "summarize"
+    }
+
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("One-shot TL;DR of the text you send.")
+    }
+
+    fn handle(
+        &self,
+        _action: String,
+        channel_id: String,
+        content: String,
+        session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        state: Arc<AgentsState>,
+    ) {
+        if content.trim().is_empty() {
+            let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                channel_id,
+                content: "Nothing to summarize — paste the text in your message.".to_string(),
+                session_id,
+                usage: None,
+                timing: None,
+                thinking: None,
+            }));
+            return;
+        }
+
+        let system = self.system_prompt.clone();
+        tokio::spawn(async move {
+            let result = state
+                .complete_via_llm_with_system(&channel_id, &content, Some(&system))
+                .await;
+            let _ = reply_tx.send(state.replace_empty_reply("summarize", result));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_reflects_style_and_budget() {
+        let bullets = system_prompt(&SummarizeAgentConfig::default());
+        assert!(bullets.contains("bulleted list"));
+        assert!(bullets.contains("at most 150 words"));
+
+        let prose = system_prompt(&SummarizeAgentConfig {
+            style: SummaryStyle::Prose,
+            max_words: 40,
+        });
+        assert!(prose.contains("paragraphs"));
+        assert!(!prose.contains("bulleted"));
+        assert!(prose.contains("at most 40 words"));
+    }
+}
@@ -0,0 +1,184 @@
+//! `sysinfo` agent — answers questions about the running bot ("what model
+//! are you using", "how many sessions", "what's your uptime") from live bus
+//! state, without calling the LLM.
+//!
+//! Each [`Topic`] is picked by keyword and answered from one query:
+//!
+//! | Topic      | Keywords                         | Source                      |
+//! |------------|----------------------------------|-----------------------------|
+//! | `uptime`   | uptime, how long, running        | `manage/http/get`           |
+//! | `model`    | model, provider, llm             | `llm/detailed_status`       |
+//! | `sessions` | session                          | `agents/sessions`           |
+//! | `health`   | health, status, subsystem        | `manage/http/get`           |
+//!
+//! A message naming no topic gets all four.  A query that fails is reported
+//! on its own line; the others still answer.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use araliya_core::bus::message::{BusPayload, BusResult};
+
+use super::{Agent, AgentCapabilities, AgentsState};
+
+pub(crate) struct SysinfoAgent;
+
+/// One thing the agent can report on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Topic {
+    Uptime,
+    Model,
+    Sessions,
+    Health,
+}
+
+impl Topic {
+    const ALL: [Topic; 4] = [Topic::Uptime, Topic::Model, Topic::Sessions, Topic::Health];
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Topic::Uptime => &["uptime", "how long", "running"],
+            Topic::Model => &["model", "provider", "llm"],
+            Topic::Sessions => &["session"],
+            Topic::Health => &["health", "status", "subsystem"],
+        }
+    }
+}
+
+/// Topics `question` asks about, in table order; all of them when it names
+/// none.
+fn topics(question: &str) -> Vec<Topic> {
+    let question = question.to_lowercase();
+    let asked: Vec<Topic> = Topic::ALL
+        .into_iter()
+        .filter(|t| t.keywords().iter().any(|k| question.contains(k)))
+        .collect();
+    if asked.is_empty() {
+        Topic::ALL.to_vec()
+    } else {
+        asked
+    }
+}
+
+/// The JSON body of a bus reply, whichever payload carries it.
+fn reply_json(result: BusResult) -> Result<Value, String> {
+    let text = match result {
+        Ok(BusPayload::JsonResponse { data }) => data,
+        Ok(BusPayload::CommsMessage { content, .. }) => content,
+        Ok(other) => return Err(format!("unexpected reply: {other:?}")),
+        Err(e) => return Err(e.message),
+    };
+    serde_json::from_str(&text).map_err(|e| format!("malformed reply: {e}"))
+}
+
+/// `ms` as a short human duration: `3d 4h`, `2h 5m`, `4m 12s`, `9s`.
+fn format_uptime(ms: u64) -> String {
+    let secs = ms / 1_000;
+    let (d, h, m, s) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60, secs % 60);
+    match (d, h, m) {
+        (0, 0, 0) => format!("{s}s"),
+        (0, 0, _) => format!("{m}m {s}s"),
+        (0, _, _) => format!("{h}h {m}m"),
+        _ => format!("{d}d {h}h"),
+    }
+}
+
+async fn answer(topic: Topic, state: &AgentsState) -> String {
+    let line = match topic {
+        Topic::Uptime => reply_json(state.bot_status().await).map(|v| {
+            let ms = v["uptime_ms"].as_u64().unwrap_or(0);
+            format!("Uptime: {}", format_uptime(ms))
+        }),
+        Topic::Model => reply_json(state.llm_status().await).map(|v| {
+            let provider = v["provider"].as_str().unwrap_or("unknown");
+            let model = v["model"].as_str().unwrap_or("unknown");
+            match v["context_window"].as_u64() {
+                Some(window) => {
+                    format!("Model: {model} via {provider} ({window}-token context)")
+                }
+                None => format!("Model: {model} via {provider}"),
+            }
+        }),
+        Topic::Sessions => reply_json(state.session_list().await).map(|v| {
+            let count = v["sessions"].as_array().map_or(0, Vec::len);
+            format!("Sessions: {count}")
+        }),
+        Topic::Health => reply_json(state.bot_status().await).map(|v| {
+            let status = v["status"].as_str().unwrap_or("unknown");
+            let subsystems = v["subsystems"].as_array().cloned().unwrap_or_default();
+            let unhealthy: Vec<&str> = subsystems
+                .iter()
+                .filter(|s| s["healthy"] == Value::Bool(false))
+                .filter_map(|s| s["id"].as_str())
+                .collect();
+            if unhealthy.is_empty() {
+                format!("Health: {status} ({} subsystems)", subsystems.len())
+            } else {
+                format!("Health: {status} (unhealthy: {})", unhealthy.join(", "))
+            }
+        }),
+    };
+    line.unwrap_or_else(|e| format!("{topic:?}: unavailable ({e})"))
+}
+
+impl Agent for SysinfoAgent {
+    fn id(&self) -> &str {
+        "sysinfo"
+    }
+
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Uptime, model, session count and health of the running bot.")
+    }
+
+    fn handle(
+        &self,
+        _action: String,
+        channel_id: String,
+        content: String,
+        session_id: Option<String>,
+        reply_tx: oneshot::Sender<BusResult>,
+        state: Arc<AgentsState>,
+    ) {
+        let topics = topics(&content);
+        tokio::spawn(async move {
+            let mut lines = Vec::with_capacity(topics.len());
+            for topic in topics {
+                lines.push(answer(topic, &state).await);
+            }
+            let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                channel_id,
+                content: lines.join("\n"),
+                session_id,
+                usage: None,
+                timing: None,
+                thinking: None,
+            }));
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_select_topics() {
+        assert_eq!(topics("What's your uptime?"), [Topic::Uptime]);
+        assert_eq!(topics("Which MODEL are you using"), [Topic::Model]);
+        assert_eq!(
+            topics("how many sessions, and which provider?"),
+            [Topic::Model, Topic::Sessions]
+        );
+        assert_eq!(topics("tell me about yourself"), Topic::ALL);
+    }
+
+    #[test]
+    fn uptime_is_formatted_at_two_units() {
+        assert_eq!(format_uptime(9_400), "9s");
+        assert_eq!(format_uptime(252_000), "4m 12s");
+        assert_eq!(format_uptime(7_500_000), "2h 5m");
+        assert_eq!(format_uptime(273_600_000), "3d 4h");
+    }
+}
@@ -13,7 +13,7 @@ use tracing::warn;
 
 use araliya_core::bus::message::{BusError, BusPayload, BusResult};
 
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 const DEFAULT_FEEDS: &[&str] = &[
     "https://feeds.bbci.co.uk/news/rss.xml",
@@ -39,6 +39,10 @@ impl Agent for TestRssNewsAgent {
         "test_rssnews"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Briefing from a fixed set of RSS feeds.").with_tools()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -48,7 +52,7 @@ impl Agent for TestRssNewsAgent {
         reply_tx: oneshot::Sender<BusResult>,
         state: Arc<AgentsState>,
     ) {
-        tokio::spawn(async move {
+        request::spawn(async move {
             let reply = run(channel_id, session_id, state).await;
             let _ = reply_tx.send(reply);
         });
@@ -69,7 +73,14 @@ async fn run(channel_id: String, session_id: Option<String>, state: Arc<AgentsSt
 
     // 2. Call the rss_fetch tool
     let tool_result = state
-        .execute_tool("rss_fetch", "fetch", args_json, &channel_id, session_id)
+        .execute_tool(
+            "test_rssnews",
+            "rss_fetch",
+            "fetch",
+            args_json,
+            &channel_id,
+            session_id,
+        )
         .await;
 
     let data_json = match tool_result {
@@ -81,9 +92,8 @@ async fn run(channel_id: String, session_id: Option<String>, state: Arc<AgentsSt
         Ok(BusPayload::ToolResponse {
             ok: false, error, ..
         }) => {
-            let msg = error.unwrap_or_else(|| "rss_fetch tool error".to_string());
-            warn!(error = %msg, "test_rssnews: tool returned error");
-            return Err(BusError::new(-32000, msg));
+            warn!(error = ?error, "test_rssnews: tool returned error");
+            return Err(BusError::tool_failed("rss_fetch", error));
         }
         Ok(_) => {
             warn!("test_rssnews: unexpected tool response type");
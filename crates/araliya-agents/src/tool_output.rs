@@ -0,0 +1,150 @@
+//! Bounding tool results before they reach an LLM prompt
+//! (`[agents.tool_output]`).
+//!
+//! A mailbox or feed fetch can return any amount of data.  Agents pass tool
+//! output through [`truncate_tool_output`] first, which drops array items
+//! past `max_items` and cuts string values longer than `max_chars`, keeping
+//! the JSON well-formed.  [`TruncatedOutput::note`] tells the model (and the
+//! user) that it is seeing a shortened result.
+
+use serde_json::Value;
+
+use araliya_core::config::ToolOutputLimits;
+
+/// A tool result after [`truncate_tool_output`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncatedOutput {
+    pub value: Value,
+    /// Items in the top-level array before truncation.
+    pub total_items: usize,
+    /// Items dropped from the top-level array.
+    pub dropped_items: usize,
+    /// String values that were cut to `max_chars`.
+    pub shortened_strings: usize,
+    max_chars: usize,
+}
+
+impl TruncatedOutput {
+    pub fn is_truncated(&self) -> bool {
+        self.dropped_items > 0 || self.shortened_strings > 0
+    }
+
+    /// A one-line notice for the prompt, or `None` when nothing was cut.
+    pub fn note(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.dropped_items > 0 {
+            parts.push(format!(
+                "showing the first {} of {} items",
+                self.total_items - self.dropped_items,
+                self.total_items
+            ));
+        }
+        if self.shortened_strings > 0 {
+            parts.push(format!(
+                "{} long field(s) cut to {} characters",
+                self.shortened_strings, self.max_chars
+            ));
+        }
+        (!parts.is_empty()).then(|| format!("[Tool output truncated: {}.]", parts.join("; ")))
+    }
+}
+
+/// Apply `limits` to a tool result.  Only the top-level array is capped at
+/// `max_items`; `max_chars` applies to every string at any depth.
+pub fn truncate_tool_output(value: Value, limits: &ToolOutputLimits) -> TruncatedOutput {
+    let mut value = value;
+    let mut total_items = 0;
+    let mut dropped_items = 0;
+    if let Value::Array(items) = &mut value {
+        total_items = items.len();
+        if limits.max_items > 0 && items.len() > limits.max_items {
+            dropped_items = items.len() - limits.max_items;
+            items.truncate(limits.max_items);
+        }
+    }
+
+    let mut shortened_strings = 0;
+    if limits.max_chars > 0 {
+        shorten_strings(&mut value, limits.max_chars, &mut shortened_strings);
+    }
+
+    TruncatedOutput {
+        value,
+        total_items,
+        dropped_items,
+        shortened_strings,
+        max_chars: limits.max_chars,
+    }
+}
+
+fn shorten_strings(value: &mut Value, max_chars: usize, shortened: &mut usize) {
+    match value {
+        Value::String(s) => {
+            if let Some((cut, _)) = s.char_indices().nth(max_chars) {
+                s.truncate(cut);
+                s.push('…');
+                *shortened += 1;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                shorten_strings(item, max_chars, shortened);
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                shorten_strings(item, max_chars, shortened);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_output_is_cut_to_limits() {
+        let items: Vec<Value> = (0..10)
+            .map(|i| serde_json::json!({ "id": i, "body": "x".repeat(50) }))
+            .collect();
+        let limits = ToolOutputLimits {
+            max_chars: 20,
+            max_items: 3,
+        };
+        let out = truncate_tool_output(Value::Array(items), &limits);
+
+        let kept = out.value.as_array().unwrap();
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[0]["body"].as_str().unwrap().chars().count(), 21);
+        assert_eq!(out.dropped_items, 7);
+        assert_eq!(out.shortened_strings, 3);
+        assert_eq!(
+            out.note().unwrap(),
+            "[Tool output truncated: showing the first 3 of 10 items; \
+             3 long field(s) cut to 20 characters.]"
+        );
+    }
+
+    #[test]
+    fn output_within_limits_is_untouched() {
+        let value = serde_json::json!({ "subject": "héllo", "snippet": "short" });
+        let out = truncate_tool_output(value.clone(), &ToolOutputLimits::default());
+        assert_eq!(out.value, value);
+        assert!(!out.is_truncated());
+        assert_eq!(out.note(), None);
+    }
+
+    #[test]
+    fn zero_lifts_the_limits() {
+        let value = serde_json::json!(["a".repeat(5000), "b", "c"]);
+        let limits = ToolOutputLimits {
+            max_chars: 0,
+            max_items: 0,
+        };
+        let out = truncate_tool_output(value.clone(), &limits);
+        assert_eq!(out.value, value);
+        assert!(!out.is_truncated());
+    }
+}
@@ -18,7 +18,7 @@ use tokio::sync::{Semaphore, oneshot};
 use tracing::info;
 
 use super::core::agentic::{AgenticLoop, LocalTool};
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 use araliya_core::bus::message::{BusError, BusResult};
 use araliya_core::error::AppError;
 
@@ -152,6 +152,13 @@ impl Agent for UniwebAgent {
         "uniweb"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Shared-session chat for all web visitors.")
+            .with_sessions()
+            .with_tools()
+            .with_streaming()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -167,7 +174,7 @@ impl Agent for UniwebAgent {
         let loop_ = self.build_loop(&state);
         let bootstrap_state = state.clone();
 
-        tokio::spawn(async move {
+        request::spawn(async move {
             depth.fetch_add(1, Ordering::Relaxed);
             let _permit = sem.acquire().await.expect("semaphore closed");
             depth.fetch_sub(1, Ordering::Relaxed);
@@ -200,7 +207,7 @@ impl Agent for UniwebAgent {
         let loop_ = self.build_loop(&state);
         let bootstrap_state = state.clone();
 
-        tokio::spawn(async move {
+        request::spawn(async move {
             depth.fetch_add(1, Ordering::Relaxed);
             let _permit = sem.acquire().await.expect("semaphore closed");
             depth.fetch_sub(1, Ordering::Relaxed);
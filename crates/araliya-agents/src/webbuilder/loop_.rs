@@ -267,6 +267,7 @@ impl WebBuilderLoop {
 
         Ok(BusPayload::LlmStreamResult {
             rx: StreamReceiver(rx),
+            request_id: None,
         })
     }
 }
@@ -324,6 +325,7 @@ impl HomebuilderLoop {
 
         Ok(BusPayload::LlmStreamResult {
             rx: StreamReceiver(rx),
+            request_id: None,
         })
     }
 }
@@ -476,6 +478,8 @@ async fn run_llm_modify(
                 .send(StreamChunk::Done {
                     usage: None,
                     timing: None,
+                    error: None,
+                    stopped: false,
                 })
                 .await;
             return;
@@ -498,6 +502,8 @@ async fn run_llm_modify(
                 .send(StreamChunk::Done {
                     usage: None,
                     timing: None,
+                    error: None,
+                    stopped: false,
                 })
                 .await;
             return;
@@ -572,6 +578,8 @@ async fn run_llm_modify(
         .send(StreamChunk::Done {
             usage: None,
             timing: None,
+            error: None,
+            stopped: false,
         })
         .await;
 }
@@ -767,6 +775,8 @@ async fn run_loop(
                     .send(StreamChunk::Done {
                         usage: None,
                         timing: None,
+                        error: None,
+                        stopped: false,
                     })
                     .await;
                 return;
@@ -795,6 +805,8 @@ async fn run_loop(
                     .send(StreamChunk::Done {
                         usage: None,
                         timing: None,
+                        error: None,
+                        stopped: false,
                     })
                     .await;
                 return;
@@ -832,6 +844,8 @@ async fn run_loop(
                     .send(StreamChunk::Done {
                         usage: None,
                         timing: None,
+                        error: None,
+                        stopped: false,
                     })
                     .await;
                 return;
@@ -877,6 +891,8 @@ async fn run_loop(
                     .send(StreamChunk::Done {
                         usage: None,
                         timing: None,
+                        error: None,
+                        stopped: false,
                     })
                     .await;
                 return;
@@ -1128,6 +1144,8 @@ async fn run_loop(
         .send(StreamChunk::Done {
             usage: None,
             timing: None,
+            error: None,
+            stopped: false,
         })
         .await;
 }
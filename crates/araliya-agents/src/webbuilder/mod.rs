@@ -18,7 +18,7 @@ use araliya_core::config::HomebuildAgentConfig;
 use araliya_core::config::WebBuilderAgentConfig;
 use araliya_llm::StreamChunk;
 
-use super::{Agent, AgentsState};
+use super::{Agent, AgentCapabilities, AgentsState, request};
 
 #[cfg(feature = "plugin-homebuilder")]
 pub(crate) mod init_home;
@@ -46,6 +46,13 @@ impl Agent for WebBuilderAgent {
         "webbuilder"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Builds static Svelte pages in an LLM-driven loop.")
+            .with_sessions()
+            .with_tools()
+            .with_streaming()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -57,17 +64,23 @@ impl Agent for WebBuilderAgent {
     ) {
         let max_iterations = self.max_iterations;
         let theme_guides_dir = self.theme_guides_dir.clone();
-        tokio::spawn(async move {
+        request::spawn(async move {
             // Run the streaming loop and collect the full response.
             let loop_ = loop_::WebBuilderLoop::new(max_iterations, theme_guides_dir);
             let stream_result = loop_
-                .run_stream(channel_id.clone(), content, session_id.clone(), state)
+                .run_stream(
+                    channel_id.clone(),
+                    content,
+                    session_id.clone(),
+                    state.clone(),
+                )
                 .await;
 
             // Drain the stream to collect the final content.
             let buffered = match stream_result {
                 Ok(BusPayload::LlmStreamResult {
                     rx: StreamReceiver(mut rx),
+                    ..
                 }) => {
                     let mut buf = String::new();
                     while let Some(chunk) = rx.recv().await {
@@ -89,7 +102,7 @@ impl Agent for WebBuilderAgent {
                 Err(e) => Err(e),
                 other => other,
             };
-            let _ = reply_tx.send(buffered);
+            let _ = reply_tx.send(state.replace_empty_reply("webbuilder", buffered));
         });
     }
 
@@ -103,7 +116,7 @@ impl Agent for WebBuilderAgent {
     ) {
         let max_iterations = self.max_iterations;
         let theme_guides_dir = self.theme_guides_dir.clone();
-        tokio::spawn(async move {
+        request::spawn(async move {
             let loop_ = loop_::WebBuilderLoop::new(max_iterations, theme_guides_dir);
             let result = loop_
                 .run_stream(channel_id, content, session_id, state)
@@ -142,6 +155,13 @@ impl Agent for HomebuilderAgent {
         "homebuilder"
     }
 
+    fn capabilities(&self) -> AgentCapabilities {
+        AgentCapabilities::new("Builds and edits a personal home page from your notes.")
+            .with_sessions()
+            .with_tools()
+            .with_streaming()
+    }
+
     fn handle(
         &self,
         _action: String,
@@ -154,15 +174,21 @@ impl Agent for HomebuilderAgent {
         let user_name = self.user_name.clone();
         let notes_dir = self.notes_dir.clone();
 
-        tokio::spawn(async move {
+        request::spawn(async move {
             let loop_ = loop_::HomebuilderLoop::new(user_name, notes_dir);
             let stream_result = loop_
-                .run_stream(channel_id.clone(), content, session_id.clone(), state)
+                .run_stream(
+                    channel_id.clone(),
+                    content,
+                    session_id.clone(),
+                    state.clone(),
+                )
                 .await;
 
             let buffered = match stream_result {
                 Ok(BusPayload::LlmStreamResult {
                     rx: StreamReceiver(mut rx),
+                    ..
                 }) => {
                     let mut buf = String::new();
                     while let Some(chunk) = rx.recv().await {
@@ -184,7 +210,7 @@ impl Agent for HomebuilderAgent {
                 Err(e) => Err(e),
                 other => other,
             };
-            let _ = reply_tx.send(buffered);
+            let _ = reply_tx.send(state.replace_empty_reply("homebuilder", buffered));
         });
     }
 
@@ -199,7 +225,7 @@ impl Agent for HomebuilderAgent {
         let user_name = self.user_name.clone();
         let notes_dir = self.notes_dir.clone();
 
-        tokio::spawn(async move {
+        request::spawn(async move {
             let loop_ = loop_::HomebuilderLoop::new(user_name, notes_dir);
             let result = loop_
                 .run_stream(channel_id, content, session_id, state)
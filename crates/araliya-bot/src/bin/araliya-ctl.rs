@@ -42,6 +42,7 @@ enum ControlCommand {
     SubsystemEnable { id: String },
     SubsystemDisable { id: String },
     Shutdown,
+    Config,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -59,9 +60,18 @@ enum ControlResponse {
     ComponentTree {
         tree_json: String,
     },
+    Config {
+        config_json: String,
+    },
     Ack {
         message: String,
     },
+    /// Handshake sent ahead of any reply (`[supervisor] control_handshake`).
+    Hello {
+        protocol_version: u32,
+        features: Vec<String>,
+        bot_id: String,
+    },
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -125,6 +135,7 @@ fn print_help() {
     eprintln!("  status              show uptime + active subsystem handlers");
     eprintln!("  subsystems          list registered subsystem handlers");
     eprintln!("  tree                print full component tree (JSON)");
+    eprintln!("  config              print effective config (JSON, secrets redacted)");
     eprintln!("  shutdown            request graceful daemon shutdown");
     eprintln!();
     eprintln!("flags:");
@@ -176,6 +187,7 @@ fn build_command(cmd: &str, rest: &[String]) -> Result<ControlCommand, String> {
         "status" => Ok(ControlCommand::Status),
         "subsystems" | "subsys" => Ok(ControlCommand::SubsystemsList),
         "tree" => Ok(ControlCommand::ComponentTree),
+        "config" => Ok(ControlCommand::Config),
         "shutdown" => Ok(ControlCommand::Shutdown),
         "enable" => {
             let id = rest.first().ok_or("usage: araliya-ctl enable <id>")?;
@@ -225,9 +237,27 @@ fn print_response(resp: WireResponse) {
                     Err(_) => println!("{tree_json}"),
                 }
             }
+            ControlResponse::Config { config_json } => {
+                let pretty = serde_json::from_str::<serde_json::Value>(&config_json)
+                    .and_then(|value| serde_json::to_string_pretty(&value));
+                match pretty {
+                    Ok(s) => println!("{s}"),
+                    Err(_) => println!("{config_json}"),
+                }
+            }
             ControlResponse::Ack { message } => {
                 println!("ok  {message}");
             }
+            ControlResponse::Hello {
+                protocol_version,
+                features,
+                bot_id,
+            } => {
+                println!(
+                    "ok  {bot_id} protocol v{protocol_version} features: {}",
+                    features.join(", ")
+                );
+            }
         },
         WireResponse::Err(e) => {
             let msg = match e {
@@ -306,15 +336,20 @@ async fn run(socket_path: PathBuf, cmd: ControlCommand) -> Result<(), String> {
         .await
         .map_err(|e| format!("send error: {e}"))?;
 
-    let line = lines
-        .next_line()
-        .await
-        .map_err(|e| format!("recv error: {e}"))?
-        .ok_or_else(|| "daemon closed connection without responding".to_string())?;
+    // The first line may be the handshake; the reply follows it.
+    loop {
+        let line = lines
+            .next_line()
+            .await
+            .map_err(|e| format!("recv error: {e}"))?
+            .ok_or_else(|| "daemon closed connection without responding".to_string())?;
 
-    let resp: WireResponse = serde_json::from_str(&line)
-        .map_err(|e| format!("parse response error: {e}\n  raw: {line}"))?;
+        let resp: WireResponse = serde_json::from_str(&line)
+            .map_err(|e| format!("parse response error: {e}\n  raw: {line}"))?;
 
-    print_response(resp);
-    Ok(())
+        if !matches!(resp, WireResponse::Ok(ControlResponse::Hello { .. })) {
+            print_response(resp);
+            return Ok(());
+        }
+    }
 }
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 use tracing::{debug, error, info, warn};
 
-use araliya_core::config::{ApiType, Config, ProviderConfig, resolve_api_key};
+use araliya_core::config::{
+    ApiType, Config, ProviderConfig, default_context_window, resolve_api_key,
+};
 use rusqlite::Connection;
 
 /// Initializes the dynamic SQLite configuration database and loads any
@@ -86,6 +88,7 @@ pub fn load_providers(config: &mut Config) {
         };
 
         let resolved_api_key = resolve_api_key(api_key, api_key_file);
+        let context_window = default_context_window(&model);
 
         Ok((
             name,
@@ -98,6 +101,8 @@ pub fn load_providers(config: &mut Config) {
                 reasoning_effort,
                 timeout_seconds: timeout_seconds as u64,
                 max_tokens: max_tokens as usize,
+                prompt_caching: false,
+                context_window,
                 input_per_million_usd: input_cost,
                 output_per_million_usd: output_cost,
                 cached_input_per_million_usd: cached_input_cost,
@@ -9,8 +9,10 @@
 //!   6. Start supervisor bus
 //!   7. Spawn Ctrl-C → shutdown signal watcher
 //!   8. Spawn supervisor run-loop
-//!   9. Run comms subsystem (drives console until shutdown)
-//!  10. Cancel token + join supervisor
+//!   9. Run the startup self-test (if `[supervisor] startup_self_test`),
+//!      then signal readiness (`[supervisor] ready_line` / `sd_notify`)
+//!  10. Run comms subsystem (drives console until shutdown)
+//!  11. Cancel token + join supervisor
 
 mod db;
 mod obs_layer;
@@ -22,13 +24,17 @@ mod setup;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 
+use araliya_comms::CommsState;
+use araliya_comms::sanitize::InputLimits;
 use araliya_core::bus::component::ComponentInfo;
 use araliya_core::bus::dispatch::BusHandler;
-use araliya_core::bus::handle::SupervisorBus;
+use araliya_core::bus::handle::{BusHandle, SupervisorBus};
 use araliya_core::bus::health::HealthRegistry;
+use araliya_core::bus::maintenance::Maintenance;
 use araliya_core::obs::{ObsBus, ObsLevel};
 use araliya_core::{config, error, identity, logger};
 use araliya_supervisor::control::SupervisorControl;
@@ -160,10 +166,11 @@ async fn run() -> Result<(), error::AppError> {
     let mut config = config::load(args.config_path.as_deref())?;
     db::load_providers(&mut config);
 
-    // Without -i, no stdio channels are active (daemon-safe default).
+    // Without -i, no stdio channels are active (daemon-safe default).  This
+    // also overrides a `[comms] channels` list that names pty.
     // TODO: warn in this case that pty is available only when interactive.
     if !args.interactive {
-        config.comms.pty.enabled = false;
+        config.comms.disable_channel("pty");
     }
 
     let effective_log_level = args.log_level.unwrap_or(config.log_level.as_str());
@@ -173,6 +180,10 @@ async fn run() -> Result<(), error::AppError> {
     // forward events.  Subsystems and the management ring buffer subscribe later.
     let obs_bus = ObsBus::new();
 
+    // Recent log records for `manage/logs` (`[supervisor] log_buffer_capacity`).
+    let log_buffer = (config.log_buffer_capacity > 0)
+        .then(|| logger::LogBuffer::new(config.log_buffer_capacity));
+
     // Build the subscriber with the obs layer composed in.  logger::init stays
     // a plain tracing-subscriber setup in araliya-core; the obs bridge lives
     // here in the binary crate to keep core dependency-light.
@@ -185,14 +196,34 @@ async fn run() -> Result<(), error::AppError> {
         let fmt_layer = tracing_subscriber::fmt::layer().with_writer(writer);
         let obs_layer = obs_layer::ObsTracingLayer::new(&obs_bus, ObsLevel::Info);
 
-        tracing_subscriber::registry()
+        let subscriber = tracing_subscriber::registry()
             .with(filter)
             .with(fmt_layer)
             .with(obs_layer)
+            .with(log_buffer.as_ref().map(logger::LogBuffer::layer));
+        // OTLP span export when `[supervisor] otel_endpoint` is set.
+        #[cfg(feature = "otel")]
+        let subscriber = subscriber.with(
+            config
+                .otel_endpoint
+                .as_deref()
+                .map(logger::build_otel_layer)
+                .transpose()?,
+        );
+        subscriber
             .try_init()
             .map_err(|e| error::AppError::Logger(format!("failed to set subscriber: {e}")))?;
     }
 
+    #[cfg(not(feature = "otel"))]
+    if let Some(endpoint) = &config.otel_endpoint {
+        tracing::warn!(
+            %endpoint,
+            "[supervisor] otel_endpoint is set but this binary was compiled without the \
+             `otel` feature — spans will not be exported. Rebuild with `--features otel`."
+        );
+    }
+
     info!(
         bot_name = %config.bot_name,
         work_dir = %config.work_dir.display(),
@@ -216,13 +247,41 @@ async fn run() -> Result<(), error::AppError> {
         let mem_config = MemoryConfig {
             kv_cap: config.memory_kv_cap,
             transcript_cap: config.memory_transcript_cap,
+            max_sessions: config.memory_max_sessions,
+            session_overflow: config.memory_session_overflow,
+            namespace: config.bot_namespace.clone(),
+            transcript_format: config.memory_transcript_format,
+            compress_archived_transcripts: config.memory_compress_archived_transcripts,
+            write_fallback: config.memory_write_fallback,
+            seed_memory: config.agents.agent_seed_memory.clone(),
+            agent_max_sessions: config.agents.agent_max_sessions.clone(),
         };
         let mut mem = MemorySystem::new(&identity.identity_dir, mem_config)
             .map_err(|e| error::AppError::Memory(e.to_string()))?;
         // CHECK: We are only starting the docstore manager, so this might be ok.
         #[cfg(feature = "idocstore")]
         mem.start_docstore_manager(shutdown.clone());
-        std::sync::Arc::new(mem)
+        let mem = std::sync::Arc::new(mem);
+        if let Some(minutes) = config.memory_session_idle_archive_minutes {
+            MemorySystem::spawn_idle_archiver(
+                mem.clone(),
+                std::time::Duration::from_secs(minutes.saturating_mul(60)),
+                std::sync::Arc::new(araliya_memory::clock::SystemClock),
+                shutdown.clone(),
+            );
+        }
+        if let Some(minutes) = config.memory_spend_snapshot_minutes {
+            MemorySystem::spawn_spend_snapshots(
+                mem.clone(),
+                config
+                    .work_dir
+                    .join(araliya_memory::SPEND_SNAPSHOTS_DIRNAME),
+                std::time::Duration::from_secs(minutes.saturating_mul(60)),
+                std::sync::Arc::new(araliya_memory::clock::SystemClock),
+                shutdown.clone(),
+            );
+        }
+        mem
     };
 
     // Build the supervisor bus (buffer = 64 messages).
@@ -264,13 +323,24 @@ async fn run() -> Result<(), error::AppError> {
     let mut configured_handlers: Vec<String> = vec!["management".to_string()];
 
     // Shared health registry — subsystems push their state; management reads it.
-    let health_registry = HealthRegistry::new();
+    let health_registry = HealthRegistry::new().with_startup_grace(std::time::Duration::from_secs(
+        config.health.startup_grace_seconds,
+    ));
+
+    // Maintenance switch — management toggles it; agents check it per request.
+    let maintenance = Maintenance::new(config.maintenance, config.maintenance_message.clone());
 
     // OnceLock bridge: comms::start() will populate this once channel list is known.
     // ManagementSubsystem reads it when building the component tree.
     let comms_info: Arc<OnceLock<ComponentInfo>> = Arc::new(OnceLock::new());
+    // Per-channel liveness: comms::start() writes it, the comms status handler reads it.
+    #[cfg(feature = "subsystem-comms")]
+    let channel_health = HealthRegistry::new();
+    // Comms events: the status handler relays agent steps, channels consume them.
+    #[cfg(feature = "subsystem-comms")]
+    let comms_events = araliya_comms::event_channel(config.comms.event_buffer);
 
-    handlers.push(Box::new(ManagementSubsystem::new(
+    let mut management = ManagementSubsystem::new(
         control_handle.clone(),
         bus_handle.clone(),
         ManagementInfo {
@@ -292,7 +362,16 @@ async fn run() -> Result<(), error::AppError> {
         comms_info.clone(),
         health_registry.clone(),
         obs_bus.clone(),
-    )));
+    )
+    .with_effective_config(&config)
+    .with_maintenance(maintenance.clone())
+    .with_refresh_timeout(std::time::Duration::from_millis(
+        config.health.refresh_timeout_ms,
+    ));
+    if let Some(buffer) = log_buffer {
+        management = management.with_log_buffer(buffer);
+    }
+    handlers.push(Box::new(management));
 
     #[cfg(feature = "subsystem-llm")]
     {
@@ -305,15 +384,6 @@ async fn run() -> Result<(), error::AppError> {
         configured_handlers.push("llm".to_string());
     }
 
-    #[cfg(feature = "subsystem-tools")]
-    {
-        handlers.push(Box::new(
-            ToolsSubsystem::new(config.tools.newsmail_aggregator.clone())
-                .with_health_reporter(health_registry.reporter("tools")),
-        ));
-        configured_handlers.push("tools".to_string());
-    }
-
     #[cfg(feature = "subsystem-runtimes")]
     {
         if config.runtimes.enabled {
@@ -325,6 +395,11 @@ async fn run() -> Result<(), error::AppError> {
         }
     }
 
+    // Identity dirs of the registered agents, for the memory bus handler and
+    // the memory tool; empty without the agents subsystem.
+    #[allow(unused_mut, unused_variables, unused_assignments)]
+    let mut agent_id_dirs: Arc<HashMap<String, PathBuf>> = Arc::default();
+
     #[cfg(all(feature = "subsystem-agents", feature = "subsystem-memory"))]
     {
         let rates = config
@@ -341,30 +416,92 @@ async fn run() -> Result<(), error::AppError> {
                 output_per_million_usd: 0.0,
                 cached_input_per_million_usd: 0.0,
             });
-        let agents =
+        let model = config
+            .llm
+            .providers
+            .get(&config.llm.default)
+            .map(|p| p.model.clone())
+            .unwrap_or_default();
+        let context_budget = config
+            .llm
+            .providers
+            .get(&config.llm.default)
+            .map(|p| {
+                araliya_agents::context_budget::ContextBudget::new(p.context_window, p.max_tokens)
+            })
+            .unwrap_or_else(|| {
+                araliya_agents::context_budget::ContextBudget::new(
+                    config::FALLBACK_CONTEXT_WINDOW,
+                    0,
+                )
+            });
+        let model_rates = config
+            .llm
+            .providers
+            .values()
+            .map(|p| {
+                let rates = araliya_llm::ModelRates {
+                    input_per_million_usd: p.input_per_million_usd,
+                    output_per_million_usd: p.output_per_million_usd,
+                    cached_input_per_million_usd: p.cached_input_per_million_usd,
+                };
+                (p.model.clone(), rates)
+            })
+            .collect();
+        let mut agents =
             AgentsSubsystem::new(config.agents.clone(), bus_handle.clone(), memory.clone())?
-                .with_llm_rates(rates)
+                .with_llm_rates(model, rates)
+                .with_model_rates(model_rates)
+                .with_context_budget(context_budget)
+                .with_channel_defaults(config.comms.channel_default_agents())
+                .with_maintenance(maintenance.clone())
+                .with_session_transcripts(config.comms.auto_session)
                 .with_health_reporter(health_registry.reporter("agents"))
                 .with_observability(obs_bus.handle());
+        if let Some(embedding) = &config.llm.embedding {
+            agents = agents.with_embedding_model(embedding.model.clone());
+        }
         #[cfg(feature = "plugin-docs")]
         agents.init_docs().await?;
 
-        // Share agent identity dirs with the memory bus handler.
-        let agent_id_dirs = Arc::new(agents.agent_identity_dirs());
+        agent_id_dirs = Arc::new(agents.agent_identity_dirs());
         handlers.push(Box::new(
-            MemoryBusHandler::new(agent_id_dirs)
+            MemoryBusHandler::new(agent_id_dirs.clone())
+                .with_memory_system(memory.clone())
+                .with_maintenance(maintenance.clone())
                 .with_health_reporter(health_registry.reporter("memory")),
         ));
+        // `[memory] write_fallback` degradation shows up as memory health.
+        memory.set_health_reporter(health_registry.reporter("memory"));
         configured_handlers.push("memory".to_string());
 
         handlers.push(Box::new(agents));
         configured_handlers.push("agents".to_string());
     }
 
+    #[cfg(feature = "subsystem-tools")]
+    {
+        let tools = ToolsSubsystem::new(config.tools.newsmail_aggregator.clone())
+            .with_health_reporter(health_registry.reporter("tools"));
+        #[cfg(feature = "plugin-memory-tool")]
+        let tools = tools.with_memory(araliya_tools::memory::MemoryTool::new(
+            memory.clone(),
+            agent_id_dirs.clone(),
+            &config.tools.memory,
+        ));
+        handlers.push(Box::new(tools));
+        configured_handlers.push("tools".to_string());
+    }
+
     #[cfg(feature = "subsystem-cron")]
     {
-        let cron = CronSubsystem::new(bus_handle.clone(), shutdown.clone())
-            .with_health_reporter(health_registry.reporter("cron"));
+        let cron = CronSubsystem::new(
+            bus_handle.clone(),
+            shutdown.clone(),
+            config.cron.quiet_hours,
+            config.cron.retry,
+        )
+        .with_health_reporter(health_registry.reporter("cron"));
         handlers.push(Box::new(cron));
         configured_handlers.push("cron".to_string());
     }
@@ -376,17 +513,35 @@ async fn run() -> Result<(), error::AppError> {
     {
         handlers.push(Box::new(
             CommsStatusHandler::new(comms_info.clone())
-                .with_health_reporter(health_registry.reporter("comms")),
+                .with_health_reporter(health_registry.reporter("comms"))
+                .with_channel_health(channel_health.clone())
+                .with_events(comms_events.clone()),
         ));
         configured_handlers.push("comms".to_string());
     }
 
     // Spawn supervisor run-loop (owns the bus receiver).
     let sup_token = shutdown.clone();
+    let panic_health = config.panic_hook.then(|| {
+        araliya_supervisor::panic_hook::install();
+        health_registry.clone()
+    });
+    let coalescer = config
+        .coalesce_requests
+        .then(araliya_supervisor::coalesce::Coalescer::new);
     let sup_handle = tokio::spawn(async move {
-        araliya_supervisor::run::run(bus, control, sup_token, handlers).await;
+        araliya_supervisor::run::run(bus, control, sup_token, handlers, panic_health, coalescer)
+            .await;
     });
 
+    // --pipe: one message in, one reply out, no channels or adapters.
+    if args.pipe {
+        let result = run_pipe(&config, bus_handle.restricted()).await;
+        shutdown.cancel();
+        sup_handle.await.ok();
+        return result;
+    }
+
     // Start supervisor-internal transport adapters for control/chat over stdio.
     // The management adapter is only active when the user passes -i / --interactive.
     // The Unix domain socket adapter always starts (daemon management).
@@ -397,9 +552,36 @@ async fn run() -> Result<(), error::AppError> {
         shutdown.clone(),
         args.interactive,
         socket_path,
+        araliya_supervisor::adapters::BindRetry::from_config(&config),
+        config.presence_events.then(|| health_registry.clone()),
+        config.control_handshake.then(|| identity.public_id.clone()),
     );
 
+    // Optional canary checks, so misconfiguration surfaces before the banner.
+    if config.startup_self_test {
+        let report = araliya_supervisor::self_test::run(
+            &bus_handle,
+            &configured_handlers,
+            araliya_supervisor::self_test::DEFAULT_CHECK_TIMEOUT,
+        )
+        .await;
+        report.log();
+        if config.startup_self_test_fatal && report.critical_failure() {
+            shutdown.cancel();
+            sup_handle.await.ok();
+            return Err(error::AppError::Config(format!(
+                "startup self-test failed: {}",
+                report.summary()
+            )));
+        }
+    }
+
     print_startup_summary(&config, &identity, args.interactive, &configured_handlers);
+    araliya_supervisor::readiness::signal_ready(
+        config.ready_line,
+        config.sd_notify,
+        env!("CARGO_PKG_VERSION"),
+    );
 
     // Start comms channels as independent concurrent tasks.
     #[cfg(feature = "subsystem-comms")]
@@ -408,9 +590,10 @@ async fn run() -> Result<(), error::AppError> {
         #[cfg(feature = "subsystem-ui")]
         let ui_handle = araliya_ui::start(&config);
 
+        // Channels act for untrusted clients: protected methods are refused.
         let comms = araliya_comms::start(
             &config,
-            bus_handle,
+            bus_handle.restricted(),
             shutdown.clone(),
             #[cfg(feature = "subsystem-ui")]
             ui_handle,
@@ -423,6 +606,8 @@ async fn run() -> Result<(), error::AppError> {
                 .as_ref()
                 .and_then(|hb| hb.notes_dir.clone()),
             comms_info,
+            channel_health,
+            comms_events,
             araliya_supervisor::adapters::stdio::stdio_control_active(),
             #[cfg(feature = "channel-axum")]
             Some(obs_bus.clone()),
@@ -437,6 +622,9 @@ async fn run() -> Result<(), error::AppError> {
 
     sup_handle.await.ok();
 
+    // Flush spans still batched for the OTLP exporter (no-op without `otel`).
+    let _ = tokio::task::spawn_blocking(logger::shutdown_otel).await;
+
     // In interactive mode, print a clean exit line so the shell prompt
     // appears below the tracing output.  In daemon mode, exit silently.
     // CHECK: what the correct way to do this.
@@ -570,7 +758,7 @@ fn print_startup_summary(
 
     #[cfg(feature = "channel-pty")]
     {
-        let pty_status = if config.comms.pty.enabled {
+        let pty_status = if config.comms_pty_should_load() {
             if interactive {
                 "disabled (interactive uses stdio control)"
             } else {
@@ -584,7 +772,7 @@ fn print_startup_summary(
 
     #[cfg(feature = "channel-telegram")]
     {
-        let status = if config.comms.telegram.enabled {
+        let status = if config.comms_telegram_should_load() {
             "enabled"
         } else {
             "disabled"
@@ -592,9 +780,18 @@ fn print_startup_summary(
         comms_lines.push(format!("✈️  telegram: {}", status));
     }
 
+    #[cfg(feature = "channel-email")]
+    {
+        if config.comms_email_should_load() {
+            comms_lines.push(format!("📧 email: {}", config.comms.email.from_address));
+        } else {
+            comms_lines.push("📧 email: disabled".to_string());
+        }
+    }
+
     #[cfg(feature = "channel-http")]
     {
-        if config.comms.http.enabled {
+        if config.comms_http_should_load() {
             comms_lines.push(format!("🌐 http: {}", config.comms.http.bind));
         } else {
             comms_lines.push("🌐 http: disabled".to_string());
@@ -603,7 +800,7 @@ fn print_startup_summary(
 
     #[cfg(feature = "channel-axum")]
     {
-        if config.comms.axum_channel.enabled {
+        if config.comms_axum_should_load() {
             comms_lines.push(format!("🧩 axum: {}", config.comms.axum_channel.bind));
         } else {
             comms_lines.push("🧩 axum: disabled".to_string());
@@ -611,12 +808,12 @@ fn print_startup_summary(
     }
 
     #[cfg(not(feature = "channel-http"))]
-    if config.comms.http.enabled {
+    if config.comms_http_should_load() {
         comms_lines.push("🌐 http: configured but not compiled in".to_string());
     }
 
     #[cfg(not(feature = "channel-axum"))]
-    if config.comms.axum_channel.enabled {
+    if config.comms_axum_should_load() {
         // CHECK: whats the use of this?
         comms_lines.push("🧩 axum: configured but not compiled in".to_string());
     }
@@ -664,10 +861,39 @@ fn print_startup_summary(
     }
 }
 
+/// Read one message from stdin, send it to the default agent, and print the
+/// reply to stdout.  Input limits from `[comms]` apply as on any channel.
+async fn run_pipe(config: &config::Config, bus: BusHandle) -> Result<(), error::AppError> {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let mut input = String::new();
+    tokio::io::stdin().read_to_string(&mut input).await?;
+    let content = input.trim_end_matches(['\r', '\n']);
+    if content.trim().is_empty() {
+        return Err(error::AppError::Comms(
+            "--pipe: no message on stdin".to_string(),
+        ));
+    }
+
+    let (event_tx, _) = tokio::sync::broadcast::channel(1);
+    let state = CommsState::new(bus, event_tx).with_input_limits(InputLimits::from(&config.comms));
+    let reply = state
+        .send_message("pipe", content.to_string(), None, None)
+        .await?;
+
+    let mut stdout = tokio::io::stdout();
+    stdout.write_all(reply.reply.as_bytes()).await?;
+    stdout.write_all(b"\n").await?;
+    stdout.flush().await?;
+    Ok(())
+}
+
 // TODO: We used to use clap, but for lean core, we use basic parsing. Check later.
 struct CliArgs {
     log_level: Option<&'static str>,
     interactive: bool,
+    /// `--pipe`: answer one stdin message on stdout and exit.
+    pipe: bool,
     config_path: Option<String>,
     log_file: Option<PathBuf>,
 }
@@ -675,6 +901,7 @@ struct CliArgs {
 fn parse_cli_args() -> CliArgs {
     let mut verbosity = 0u8;
     let mut interactive = false;
+    let mut pipe = false;
     let mut config_path = None;
     let mut log_file = None;
 
@@ -693,6 +920,9 @@ fn parse_cli_args() -> CliArgs {
                 println!(
                     "  -i, --interactive          Run in interactive mode (enables PTY console)"
                 );
+                println!(
+                    "      --pipe                 Read one message from stdin, print the reply, and exit"
+                );
                 println!(
                     "  -f, --config <PATH>        Path to configuration file (default: config/default.toml)"
                 );
@@ -703,6 +933,7 @@ fn parse_cli_args() -> CliArgs {
                 std::process::exit(0);
             }
             "-i" | "--interactive" => interactive = true,
+            "--pipe" => pipe = true,
             "-f" | "--config" => {
                 if let Some(path) = iter.next() {
                     config_path = Some(path);
@@ -727,6 +958,11 @@ fn parse_cli_args() -> CliArgs {
         }
     }
 
+    if pipe && interactive {
+        eprintln!("error: --pipe cannot be combined with -i/--interactive");
+        std::process::exit(1);
+    }
+
     // Each -v raises verbosity one tier from the config default:
     //   -v      → warn   (suppress info noise, show warnings+errors only)
     //   -vv     → info   (normal operational output — the typical default)
@@ -743,6 +979,7 @@ fn parse_cli_args() -> CliArgs {
     CliArgs {
         log_level,
         interactive,
+        pipe,
         config_path,
         log_file,
     }
@@ -0,0 +1,275 @@
+//! Append-only JSONL audit sink for LLM requests.
+//!
+//! Enabled by `[llm] audit_log = "<path>"`.  Every completion handled by the
+//! LLM subsystem appends exactly one JSON line recording who asked, which
+//! model answered, a SHA-256 of the prompt, token usage and cost.  With
+//! `audit_full = true` the prompt, system prompt and completion text are
+//! recorded verbatim as well.
+//!
+//! The sink is independent of `tracing` — it is always structured and is
+//! never filtered by log level.  Provider API keys are scrubbed from any
+//! recorded text before it reaches disk.
+//!
+//! With `audit_max_bytes` set, a log that has reached that size is renamed
+//! to `<audit_log>.1` (replacing any previous rotation) before the next
+//! line is written, so at most two files are ever kept.
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use araliya_core::types::llm::LlmUsage;
+use araliya_llm::{LlmResponse, ModelRates, ProviderError};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+pub(super) struct AuditRecord {
+    pub ts_unix_ms: u64,
+    pub method: String,
+    pub channel_id: String,
+    pub provider: String,
+    pub model: String,
+    pub prompt_sha256: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<String>,
+    pub usage: Option<LlmUsage>,
+    pub cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Request-side context captured before the provider call.
+pub(super) struct AuditRequest {
+    pub method: String,
+    pub channel_id: String,
+    pub provider: String,
+    pub model: String,
+    pub system: Option<String>,
+    pub prompt: String,
+}
+
+/// Serialised writer for the audit file.
+pub(super) struct AuditSink {
+    path: PathBuf,
+    full: bool,
+    /// Rotate once the file reaches this size; `None` = never.
+    max_bytes: Option<u64>,
+    /// Secrets that must never be written (provider API keys).
+    secrets: Vec<String>,
+    /// Serialises appends so concurrent completions never interleave lines.
+    lock: Mutex<()>,
+}
+
+impl AuditSink {
+    pub fn new(path: PathBuf, full: bool, max_bytes: Option<u64>, secrets: Vec<String>) -> Self {
+        let secrets = secrets.into_iter().filter(|s| !s.is_empty()).collect();
+        Self {
+            path,
+            full,
+            max_bytes,
+            secrets,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Record the outcome of a one-shot completion.
+    pub async fn record_response(
+        &self,
+        req: &AuditRequest,
+        result: &Result<LlmResponse, ProviderError>,
+        rates: &ModelRates,
+    ) {
+        let outcome = match result {
+            Ok(resp) => Ok((resp.text.as_str(), resp.usage.as_ref())),
+            Err(e) => Err(e.to_string()),
+        };
+        self.record(req, outcome, rates).await;
+    }
+
+    /// Build the record for a finished request and append it.
+    ///
+    /// Failures are logged and swallowed — auditing must never fail the
+    /// completion it describes.
+    pub async fn record(
+        &self,
+        req: &AuditRequest,
+        completion: Result<(&str, Option<&LlmUsage>), String>,
+        rates: &ModelRates,
+    ) {
+        let (text, usage, error) = match completion {
+            Ok((text, usage)) => (Some(text), usage.cloned(), None),
+            Err(e) => (None, None, Some(self.redact(&e))),
+        };
+        let record = AuditRecord {
+            ts_unix_ms: unix_ms_now(),
+            method: req.method.clone(),
+            channel_id: req.channel_id.clone(),
+            provider: req.provider.clone(),
+            model: req.model.clone(),
+            prompt_sha256: prompt_hash(req.system.as_deref(), &req.prompt),
+            system: self
+                .full
+                .then(|| req.system.as_deref().map(|s| self.redact(s)))
+                .flatten(),
+            prompt: self.full.then(|| self.redact(&req.prompt)),
+            completion: self.full.then(|| text.map(|t| self.redact(t))).flatten(),
+            cost_usd: usage.as_ref().map(|u| u.cost_usd(rates)),
+            usage,
+            error,
+        };
+        self.append(&record).await;
+    }
+
+    async fn append(&self, record: &AuditRecord) {
+        let mut line = match serde_json::to_string(record) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!(error = %e, "llm audit: serialise failed");
+                return;
+            }
+        };
+        line.push('\n');
+
+        let _guard = self.lock.lock().await;
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        self.rotate_if_full().await;
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await;
+        match file {
+            Ok(mut f) => {
+                // tokio's `File` buffers; flush so the line is on disk
+                // before the lock is released.
+                let written = f.write_all(line.as_bytes()).await;
+                if let Err(e) = written.and(f.flush().await) {
+                    warn!(path = %self.path.display(), error = %e, "llm audit: write failed");
+                }
+            }
+            Err(e) => {
+                warn!(path = %self.path.display(), error = %e, "llm audit: open failed");
+            }
+        }
+    }
+
+    /// Move a log that has reached `max_bytes` aside to `<path>.1`.
+    async fn rotate_if_full(&self) {
+        let Some(max) = self.max_bytes else {
+            return;
+        };
+        let Ok(meta) = tokio::fs::metadata(&self.path).await else {
+            return;
+        };
+        if meta.len() < max {
+            return;
+        }
+        let rotated = self.rotated_path();
+        match tokio::fs::rename(&self.path, &rotated).await {
+            Ok(()) => info!(path = %rotated.display(), "llm audit: log rotated"),
+            Err(e) => warn!(path = %self.path.display(), error = %e, "llm audit: rotate failed"),
+        }
+    }
+
+    /// `<path>.1`.
+    fn rotated_path(&self) -> PathBuf {
+        let mut name = OsString::from(self.path.as_os_str());
+        name.push(".1");
+        name.into()
+    }
+
+    fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |acc, secret| {
+            acc.replace(secret, REDACTED)
+        })
+    }
+}
+
+/// SHA-256 over the system prompt and user content, hex-encoded.
+fn prompt_hash(system: Option<&str>, content: &str) -> String {
+    let mut hasher = Sha256::new();
+    if let Some(system) = system {
+        hasher.update(system.as_bytes());
+    }
+    hasher.update([0u8]);
+    hasher.update(content.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn unix_ms_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_hash_is_stable_and_system_sensitive() {
+        let a = prompt_hash(None, "hello");
+        assert_eq!(a, prompt_hash(None, "hello"));
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, prompt_hash(Some("sys"), "hello"));
+    }
+
+    #[test]
+    fn redact_scrubs_secrets() {
+        let sink = AuditSink::new(
+            PathBuf::from("/dev/null"),
+            true,
+            None,
+            vec!["sk-secret".into(), String::new()],
+        );
+        assert_eq!(sink.redact("key=sk-secret!"), "key=[REDACTED]!");
+        assert_eq!(sink.redact("nothing here"), "nothing here");
+    }
+
+    #[tokio::test]
+    async fn rotates_past_size_threshold() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let sink = AuditSink::new(path.clone(), false, Some(64), Vec::new());
+        let req = AuditRequest {
+            method: "complete".into(),
+            channel_id: "test".into(),
+            provider: "dummy".into(),
+            model: "m".into(),
+            system: None,
+            prompt: "hello".into(),
+        };
+        let rates = ModelRates::default();
+
+        sink.record(&req, Ok(("hi", None)), &rates).await;
+        let first = std::fs::read_to_string(&path).unwrap();
+        assert!(first.len() as u64 >= 64);
+        assert!(!sink.rotated_path().exists());
+
+        sink.record(&req, Ok(("again", None)), &rates).await;
+        assert_eq!(std::fs::read_to_string(sink.rotated_path()).unwrap(), first);
+        let second = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(second.lines().count(), 1);
+        assert!(second.contains("\"complete\""));
+    }
+}
@@ -0,0 +1,59 @@
+//! Size-based provider selection (`[[llm.auto_route]]`).
+//!
+//! Requests that name no provider are matched against the configured rules
+//! by an estimate of their prompt size; the first rule whose bounds contain
+//! the estimate picks the provider.  No match (or no rules) leaves the active
+//! default in charge.
+
+use araliya_core::config::AutoRouteRule;
+
+/// Rough token count for `system` + `content`: one token per four bytes,
+/// rounded up.  Good enough to tell a one-liner from a document.
+pub(super) fn estimate_tokens(system: Option<&str>, content: &str) -> usize {
+    (system.map_or(0, str::len) + content.len()).div_ceil(4)
+}
+
+/// Provider named by the first rule matching a prompt of `tokens`.
+pub(super) fn pick(rules: &[AutoRouteRule], tokens: usize) -> Option<&str> {
+    rules
+        .iter()
+        .find(|rule| {
+            rule.min_tokens.is_none_or(|min| tokens >= min)
+                && rule.max_tokens.is_none_or(|max| tokens <= max)
+        })
+        .map(|rule| rule.provider.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(min: Option<usize>, max: Option<usize>, provider: &str) -> AutoRouteRule {
+        AutoRouteRule {
+            min_tokens: min,
+            max_tokens: max,
+            provider: provider.into(),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = [
+            rule(None, Some(50), "cheap"),
+            rule(Some(1000), None, "strong"),
+            rule(None, None, "fallback"),
+        ];
+        assert_eq!(pick(&rules, 0), Some("cheap"));
+        assert_eq!(pick(&rules, 50), Some("cheap"));
+        assert_eq!(pick(&rules, 51), Some("fallback"));
+        assert_eq!(pick(&rules, 1000), Some("strong"));
+        assert_eq!(pick(&[], 10), None);
+    }
+
+    #[test]
+    fn estimate_counts_system_and_content() {
+        assert_eq!(estimate_tokens(None, ""), 0);
+        assert_eq!(estimate_tokens(None, "hello"), 2);
+        assert_eq!(estimate_tokens(Some("abcd"), "abcd"), 2);
+    }
+}
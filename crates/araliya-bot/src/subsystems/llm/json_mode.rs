@@ -0,0 +1,84 @@
+//! JSON response format (`LlmRequest.response_format = json`).
+//!
+//! Providers with a native JSON mode (chat-completions endpoints) are asked
+//! for a JSON object; every provider also gets [`JSON_INSTRUCTION`] appended
+//! to the system prompt, which OpenAI requires and which is the only lever
+//! for providers without a native mode.  Buffered replies are then checked
+//! with [`validate`], which makes one repair attempt before giving up.
+
+use araliya_core::types::llm::ResponseFormat;
+use araliya_llm::LlmProvider;
+
+/// Appended to the system prompt of every JSON-format request.
+pub(super) const JSON_INSTRUCTION: &str =
+    "Respond only with valid JSON. Do not wrap it in code fences or add any other text.";
+
+/// Provider and system prompt to use for a request in `format`.
+///
+/// Text requests pass through unchanged.
+pub(super) fn prepare(
+    provider: &LlmProvider,
+    system: Option<&str>,
+    format: ResponseFormat,
+) -> (LlmProvider, Option<String>) {
+    match format {
+        ResponseFormat::Text => (provider.clone(), system.map(str::to_string)),
+        ResponseFormat::Json => {
+            let provider = provider
+                .with_json_mode()
+                .unwrap_or_else(|| provider.clone());
+            let system = match system {
+                Some(s) if !s.trim().is_empty() => format!("{s}\n\n{JSON_INSTRUCTION}"),
+                _ => JSON_INSTRUCTION.to_string(),
+            };
+            (provider, Some(system))
+        }
+    }
+}
+
+/// Return `text` if it parses as JSON, otherwise a repaired copy.
+///
+/// The single repair attempt drops surrounding prose and code fences by
+/// taking the span from an opening `{` / `[` to the last `}` / `]`, trying
+/// each opening bracket in turn so a bracketed prefix does not hide the
+/// payload.
+pub(super) fn validate(text: &str) -> Result<String, String> {
+    let err = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(_) => return Ok(text.to_string()),
+        Err(e) => e.to_string(),
+    };
+    let Some(end) = text.rfind(['}', ']']) else {
+        return Err(err);
+    };
+    text[..end]
+        .match_indices(['{', '['])
+        .map(|(start, _)| &text[start..=end])
+        .find(|candidate| serde_json::from_str::<serde_json::Value>(candidate).is_ok())
+        .map(str::to_string)
+        .ok_or(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_repairs_fenced_json() {
+        assert_eq!(validate(r#"{"a":1}"#).unwrap(), r#"{"a":1}"#);
+        assert_eq!(
+            validate("Sure:\n```json\n{\"a\": [1, 2]}\n```").unwrap(),
+            "{\"a\": [1, 2]}"
+        );
+        assert_eq!(validate(r#"[echo] {"a":1}"#).unwrap(), r#"{"a":1}"#);
+        assert!(validate("[echo] hello").is_err());
+    }
+
+    #[test]
+    fn prepare_appends_instruction() {
+        let p = LlmProvider::Dummy(araliya_llm::providers::dummy::DummyProvider);
+        let (_, system) = prepare(&p, Some("Be brief."), ResponseFormat::Json);
+        assert_eq!(system.unwrap(), format!("Be brief.\n\n{JSON_INSTRUCTION}"));
+        let (_, system) = prepare(&p, None, ResponseFormat::Text);
+        assert!(system.is_none());
+    }
+}
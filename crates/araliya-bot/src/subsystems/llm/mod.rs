@@ -10,7 +10,9 @@
 //! alive in a `HashMap<String, ProviderEntry>`.  The `active` key selects
 //! the current default provider; callers can override per-request via the
 //! `provider_override` / `model_override` fields in `LlmRequest`, or use
-//! symbolic route hints configured in `[llm.routes]`.
+//! symbolic route hints configured in `[llm.routes]`.  Requests that name no
+//! provider can be routed by prompt size through `[[llm.auto_route]]` (see
+//! [`auto_route`]).
 //!
 //! # Bus methods
 //!
@@ -21,22 +23,83 @@
 //! | `llm/{id}/status`          | —             | Provider-scoped status                 |
 //! | `llm/detailed_status`      | —             | Provider + model info                  |
 //! | `llm/list_providers`       | —             | All named providers and their models   |
+//! | `llm/resolve`              | `JsonRequest` | Provider + window for a prompt size    |
 //! | `llm/set_default`          | `JsonRequest` | Switch active provider at runtime      |
+//! | `llm/embed`                | `JsonRequest` | Embeddings via `[llm] embedding_*`     |
 //! | `llm/instruct`             | `LlmRequest`  | Instruction-pass completion (1024 max) |
 //! | `llm/stream`               | `LlmRequest`  | Streaming completion                   |
+//! | `llm/stream/stop`          | `JsonRequest` | End an in-flight stream early          |
+//! | `llm/batch`                | `LlmBatch`    | Several completions, bounded in flight |
 //! | `llm/complete` (default)   | `LlmRequest`  | Standard completion                    |
+//!
+//! # Audit log
+//!
+//! When `[llm] audit_log` is set, every `instruct`, `stream` and `complete`
+//! request — and every item of a `batch` — appends one JSON line to that
+//! file (see [`audit`]).
+//!
+//! # Deadline
+//!
+//! `[llm] overall_deadline_seconds` bounds each `complete` and `instruct`
+//! request, and each `batch` item, as a whole; past it the reply is
+//! [`ERR_DEADLINE_EXCEEDED`].  Streams are not bounded.
+//!
+//! # Stripping
+//!
+//! `[llm] strip_patterns` / `strip_reasoning` remove delimited regions such as
+//! `<think>...</think>` from `complete`, `instruct` and `stream` replies (see
+//! [`strip`]).  Streams are filtered incrementally.  The audit log still
+//! records the raw text.
+//!
+//! # JSON response format
+//!
+//! `stream` and `complete` requests with `response_format: json` are sent
+//! with a JSON-only instruction (and the provider's native JSON mode where it
+//! has one).  `complete` replies are then validated — with one repair attempt
+//! — and an unparseable reply becomes an error (see [`json_mode`]).  Streamed
+//! replies are not validated.
+//!
+//! # Stream failures
+//!
+//! A stream that fails — even after some deltas — still ends with a
+//! [`StreamChunk::Done`] carrying the error, so channels can show the
+//! partial reply and an error note instead of a stream that just stops.
+//!
+//! # Stopping a stream
+//!
+//! Every `llm/stream` reply carries a `request_id`.  `llm/stream/stop` with
+//! `{"request_id": "..."}` drops the provider call — closing its connection,
+//! so the provider stops generating — and ends the stream with a
+//! [`StreamChunk::Done`] marked `stopped`.  Deltas already queued before the
+//! stop are still delivered; none follow it.
+//!
+//! # Warm-up
+//!
+//! With `[llm] warmup`, the first health check that reaches the active
+//! provider is followed by one throwaway completion of [`WARMUP_PROMPT`],
+//! so the first real request does not pay for a cold connection or a cold
+//! provider.  It runs once per startup, in the background.
+
+mod audit;
+mod auto_route;
+mod json_mode;
+mod strip;
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
 use std::time::Duration;
 
-use tokio::sync::oneshot;
+use tokio::sync::{Semaphore, oneshot};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
-use araliya_core::config::{LlmConfig, RouteConfig};
+use araliya_core::config::{
+    AutoRouteRule, FALLBACK_CONTEXT_WINDOW, LlmConfig, RouteConfig, StripPattern,
+};
 use araliya_core::obs::ObservabilityHandle;
+use araliya_core::types::llm::ResponseFormat;
 use araliya_llm::providers;
+use araliya_llm::providers::embeddings::EmbeddingProvider;
 use araliya_llm::{LlmProvider, ModelRates, ProviderError};
 use tokio::sync::mpsc;
 
@@ -46,10 +109,25 @@ use araliya_core::bus::health::HealthReporter;
 use araliya_core::bus::message::{
     BusError, BusPayload, BusResult, ERR_METHOD_NOT_FOUND, StreamReceiver,
 };
+use araliya_core::types::llm::{LlmUsage, StreamChunk};
+
+use audit::{AuditRequest, AuditSink};
 
 /// Interval between background provider reachability checks.
 const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 
+/// Prompt of the `[llm] warmup` completion; its reply is discarded.
+const WARMUP_PROMPT: &str = "ping";
+
+/// Error code for a prompt over `[llm] max_prompt_chars`.
+pub const ERR_PROMPT_TOO_LARGE: i32 = -32005;
+
+/// Error code for a completion past `[llm] overall_deadline_seconds`.
+pub const ERR_DEADLINE_EXCEEDED: i32 = -32001;
+
+/// Error code for a request resolving to a model outside `[llm] allowed_models`.
+pub const ERR_MODEL_NOT_ALLOWED: i32 = -32007;
+
 // ── Provider entry ──────────────────────────────────────────────────────────
 
 /// A named provider in the pool, with its config-level metadata.
@@ -57,8 +135,11 @@ const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 struct ProviderEntry {
     provider: LlmProvider,
     model: String,
-    #[allow(dead_code)] // Used for cost tracking (future).
     rates: ModelRates,
+    /// Tokens per request, prompt and reply (`context_window`).
+    context_window: usize,
+    /// Reply cap in tokens (`max_tokens`, 0 = uncapped).
+    max_tokens: usize,
 }
 
 // ── LlmSubsystem ───────────────────────────────────────────────────────────
@@ -72,8 +153,28 @@ pub struct LlmSubsystem {
     instruction_name: Option<String>,
     /// Symbolic route hints → (provider, optional model) from `[llm.routes]`.
     routes: HashMap<String, RouteConfig>,
+    /// Size-based provider rules from `[[llm.auto_route]]`.
+    auto_route: Vec<AutoRouteRule>,
     reporter: Option<HealthReporter>,
     obs: Option<ObservabilityHandle>,
+    /// Structured per-request audit sink (`[llm] audit_log`).
+    audit: Option<Arc<AuditSink>>,
+    /// Backend for `llm/embed`; separate from the chat pool.
+    embedder: Option<EmbeddingProvider>,
+    /// Regions removed from buffered completions (`[llm] strip_patterns`).
+    strip_patterns: Arc<[StripPattern]>,
+    /// Prompts longer than this are rejected (`[llm] max_prompt_chars`).
+    max_prompt_chars: Option<usize>,
+    /// Items of one `llm/batch` in flight at once (`[llm] batch_concurrency`).
+    batch_concurrency: usize,
+    /// Bound on one whole completion (`[llm] overall_deadline_seconds`).
+    deadline: Option<Duration>,
+    /// Warm the active provider once it is first reachable (`[llm] warmup`).
+    warmup: bool,
+    /// Models requests may select (`[llm] allowed_models`); empty = any.
+    allowed_models: Vec<String>,
+    /// In-flight `llm/stream` requests by id, for `llm/stream/stop`.
+    streams: Arc<Mutex<HashMap<String, CancellationToken>>>,
 }
 
 impl LlmSubsystem {
@@ -83,9 +184,10 @@ impl LlmSubsystem {
     pub fn new(config: &LlmConfig, api_key: Option<String>) -> Result<Self, ProviderError> {
         let mut pool = HashMap::new();
 
-        // Build every named provider.
+        // Build every named provider around one pooled HTTP client.
+        let client = providers::http_client(&config.pool)?;
         for (name, pcfg) in &config.providers {
-            let provider = providers::build_from_provider(pcfg, api_key.clone())?;
+            let provider = providers::build_from_provider(pcfg, api_key.clone(), &client)?;
             pool.insert(
                 name.clone(),
                 ProviderEntry {
@@ -96,6 +198,8 @@ impl LlmSubsystem {
                         output_per_million_usd: pcfg.output_per_million_usd,
                         cached_input_per_million_usd: pcfg.cached_input_per_million_usd,
                     },
+                    context_window: pcfg.context_window,
+                    max_tokens: pcfg.max_tokens,
                 },
             );
         }
@@ -108,6 +212,21 @@ impl LlmSubsystem {
                     provider: LlmProvider::Dummy(araliya_llm::providers::dummy::DummyProvider),
                     model: "dummy".to_string(),
                     rates: ModelRates::default(),
+                    context_window: FALLBACK_CONTEXT_WINDOW,
+                    max_tokens: 0,
+                },
+            );
+        }
+
+        if config.default == "faulty" && !pool.contains_key("faulty") {
+            pool.insert(
+                "faulty".to_string(),
+                ProviderEntry {
+                    provider: providers::build_faulty(&config.faulty)?,
+                    model: "faulty".to_string(),
+                    rates: ModelRates::default(),
+                    context_window: FALLBACK_CONTEXT_WINDOW,
+                    max_tokens: 0,
                 },
             );
         }
@@ -119,10 +238,44 @@ impl LlmSubsystem {
             return Err(ProviderError::UnknownProvider(instr_name.clone()));
         }
 
+        // Validate auto-route targets.
+        if let Some(rule) = config
+            .auto_route
+            .iter()
+            .find(|r| !pool.contains_key(&r.provider))
+        {
+            return Err(ProviderError::UnknownProvider(rule.provider.clone()));
+        }
+
+        let audit = config.audit_log.as_ref().map(|path| {
+            // Keys are only used for redaction; they never leave the process.
+            let secrets = config
+                .providers
+                .values()
+                .filter_map(|p| p.api_key.clone())
+                .chain(api_key.clone())
+                .collect();
+            info!(path = %path.display(), full = config.audit_full, "llm audit log enabled");
+            Arc::new(AuditSink::new(
+                path.clone(),
+                config.audit_full,
+                config.audit_max_bytes,
+                secrets,
+            ))
+        });
+
+        let embedder = config
+            .embedding
+            .as_ref()
+            .map(|cfg| providers::embeddings::build(cfg, api_key.clone()))
+            .transpose()?;
+
         info!(
             active = %config.default,
             pool_size = pool.len(),
+            embedding = ?config.embedding.as_ref().map(|e| &e.provider),
             routes = config.routes.len(),
+            auto_route = config.auto_route.len(),
             "llm subsystem initialised"
         );
 
@@ -131,8 +284,18 @@ impl LlmSubsystem {
             active: Arc::new(RwLock::new(config.default.clone())),
             instruction_name: config.instruction.clone(),
             routes: config.routes.clone(),
+            auto_route: config.auto_route.clone(),
             reporter: None,
             obs: None,
+            audit,
+            embedder,
+            strip_patterns: config.strip_patterns.clone().into(),
+            max_prompt_chars: config.max_prompt_chars,
+            batch_concurrency: config.batch_concurrency.max(1),
+            deadline: config.overall_deadline_seconds.map(Duration::from_secs),
+            warmup: config.warmup,
+            allowed_models: config.allowed_models.clone(),
+            streams: Arc::default(),
         })
     }
 
@@ -150,7 +313,9 @@ impl LlmSubsystem {
 
     /// Spawn a background task that probes the active provider endpoint periodically.
     ///
-    /// The task stops when `shutdown` is cancelled.  No-op if no reporter is set.
+    /// With `[llm] warmup`, the first check that finds the provider reachable
+    /// also spawns the warm-up completion.  The task stops when `shutdown` is
+    /// cancelled.  No-op if no reporter is set.
     pub fn spawn_health_checker(&self, shutdown: CancellationToken) {
         let reporter = match &self.reporter {
             Some(r) => r.clone(),
@@ -158,29 +323,162 @@ impl LlmSubsystem {
         };
         let active = self.active.clone();
         let pool = self.pool.clone();
+        let mut warmup = self.warmup.then(|| self.audit.clone());
+        let allowed_models = self.allowed_models.clone();
         tokio::spawn(async move {
+            let check = async |warmup: &mut Option<Option<Arc<AuditSink>>>| {
+                let Some((name, entry)) = Self::active_entry_from(&active, &pool) else {
+                    return;
+                };
+                let reachable =
+                    Self::run_check(&name, &entry.provider, &entry.model, &reporter).await;
+                if reachable
+                    && let Some(audit) = warmup.take()
+                    && Self::check_model_allowed(&allowed_models, &name, &entry.model).is_ok()
+                {
+                    tokio::spawn(Self::warm_up(name, entry, audit));
+                }
+            };
             // Immediate check on startup.
-            if let Some((name, entry)) = Self::active_entry_from(&active, &pool) {
-                Self::run_check(&name, &entry.provider, &entry.model, &reporter).await;
-            }
+            check(&mut warmup).await;
             let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
             interval.tick().await; // consume the first (immediate) tick
             loop {
                 tokio::select! {
                     biased;
                     _ = shutdown.cancelled() => break,
-                    _ = interval.tick() => {
-                        if let Some((name, entry)) = Self::active_entry_from(&active, &pool) {
-                            Self::run_check(&name, &entry.provider, &entry.model, &reporter).await;
-                        }
-                    }
+                    _ = interval.tick() => check(&mut warmup).await,
                 }
             }
         });
     }
 
+    /// Send [`WARMUP_PROMPT`] to `entry` and discard the reply.  Audited as
+    /// `llm/warmup`, since it is a real (billed) request.
+    async fn warm_up(name: String, entry: ProviderEntry, audit: Option<Arc<AuditSink>>) {
+        let result = entry.provider.complete(WARMUP_PROMPT, None, None).await;
+        if let Some(sink) = audit {
+            let req = AuditRequest {
+                method: "llm/warmup".to_string(),
+                channel_id: "warmup".to_string(),
+                provider: name.clone(),
+                model: entry.model.clone(),
+                system: None,
+                prompt: WARMUP_PROMPT.to_string(),
+            };
+            sink.record_response(&req, &result, &entry.rates).await;
+        }
+        match result {
+            Ok(_) => info!(provider = %name, model = %entry.model, "llm provider warmed up"),
+            Err(e) => warn!(provider = %name, error = %e, "llm warm-up failed"),
+        }
+    }
+
     // ── Internal helpers ────────────────────────────────────────────────────
 
+    /// Reject an `LlmRequest` whose system prompt and content together
+    /// exceed `max_prompt_chars`.
+    fn check_prompt_size(&self, payload: &BusPayload) -> Result<(), BusError> {
+        let (
+            Some(max),
+            BusPayload::LlmRequest {
+                content, system, ..
+            },
+        ) = (self.max_prompt_chars, payload)
+        else {
+            return Ok(());
+        };
+        let chars = content.chars().count() + system.as_deref().map_or(0, |s| s.chars().count());
+        if chars <= max {
+            return Ok(());
+        }
+        warn!(chars, max, "rejecting oversized llm prompt");
+        Err(BusError::new(
+            ERR_PROMPT_TOO_LARGE,
+            format!("prompt too large: {chars} chars exceeds max_prompt_chars = {max}"),
+        ))
+    }
+
+    /// Resolve the provider for one `LlmRequest` and build the future that
+    /// runs it.  This is `llm/complete`; `llm/batch` runs one per item.
+    fn completion(
+        &self,
+        method: &str,
+        payload: BusPayload,
+    ) -> Result<impl Future<Output = BusResult> + Send + 'static, BusError> {
+        let BusPayload::LlmRequest {
+            channel_id,
+            content,
+            system,
+            provider_override,
+            model_override,
+            response_format,
+        } = payload
+        else {
+            return Err(BusError::new(
+                ERR_METHOD_NOT_FOUND,
+                format!("unsupported payload for method: {method}"),
+            ));
+        };
+        let provider_override =
+            provider_override.or_else(|| self.auto_provider(system.as_deref(), &content));
+        let (provider_name, entry, _model) =
+            self.resolve_provider(provider_override.as_deref(), model_override.as_deref())?;
+        // Providers always run their configured model, so that is what the
+        // audit records.
+        let audit = self.audit_request(
+            method,
+            &channel_id,
+            &provider_name,
+            &entry.model,
+            system.as_deref(),
+            &content,
+        );
+        let (provider, system) =
+            json_mode::prepare(&entry.provider, system.as_deref(), response_format);
+        let strip_patterns = self.strip_patterns.clone();
+        debug!(%method, %channel_id, "dispatching to llm provider");
+        Ok(within_deadline(self.deadline, async move {
+            let result = provider.complete(&content, system.as_deref(), None).await;
+            if let Some((sink, req)) = audit {
+                sink.record_response(&req, &result, &entry.rates).await;
+            }
+            result
+                .map_err(|e| BusError::new(-32000, e.to_string()))
+                .and_then(|resp| {
+                    if let Some(u) = &resp.usage {
+                        tracing::debug!(
+                            input_tokens = u.input_tokens,
+                            output_tokens = u.output_tokens,
+                            cached_tokens = u.cached_input_tokens,
+                            "llm usage"
+                        );
+                    }
+                    let mut content = strip::strip_regions(&resp.text, &strip_patterns);
+                    if content.trim().is_empty() {
+                        warn!(
+                            %channel_id,
+                            finish_reason = resp.finish_reason.as_deref().unwrap_or("unknown"),
+                            "llm returned an empty completion"
+                        );
+                    }
+                    if response_format == ResponseFormat::Json {
+                        content = json_mode::validate(&content).map_err(|e| {
+                            BusError::new(-32000, format!("llm reply is not valid JSON: {e}"))
+                        })?;
+                    }
+                    Ok(BusPayload::CommsMessage {
+                        channel_id,
+                        content,
+                        session_id: None,
+                        usage: resp.usage,
+                        timing: resp.timing,
+                        thinking: resp.thinking,
+                    })
+                })
+        }))
+    }
+
     /// Read the current active provider name.
     fn active_name(&self) -> String {
         self.active.read().unwrap().clone()
@@ -203,13 +501,14 @@ impl LlmSubsystem {
     /// 3. Active default provider.
     ///
     /// `model_override` from the request always wins over the provider's
-    /// configured model when present.
+    /// configured model when present.  Providers still run their configured
+    /// model, so that is the one checked against `[llm] allowed_models`.
     fn resolve_provider(
         &self,
         provider_override: Option<&str>,
         model_override: Option<&str>,
-    ) -> Result<(LlmProvider, String), BusError> {
-        let (entry, resolved_model) =
+    ) -> Result<(String, ProviderEntry, String), BusError> {
+        let (name, entry, resolved_model) =
             match provider_override {
                 // Route hint: "hint:<name>" resolves through the routes table.
                 Some(hint_str) if hint_str.starts_with("hint:") => {
@@ -227,14 +526,14 @@ impl LlmSubsystem {
                         )
                     })?;
                     let model = route.model.as_deref().unwrap_or(&entry.model);
-                    (entry.clone(), model.to_string())
+                    (route.provider.clone(), entry.clone(), model.to_string())
                 }
                 // Explicit provider name.
                 Some(name) => {
                     let entry = self.pool.get(name).ok_or_else(|| {
                         BusError::new(-32001, format!("unknown provider: {name}"))
                     })?;
-                    (entry.clone(), entry.model.clone())
+                    (name.to_string(), entry.clone(), entry.model.clone())
                 }
                 // Active default.
                 None => {
@@ -242,39 +541,109 @@ impl LlmSubsystem {
                     let entry = self.pool.get(&name).ok_or_else(|| {
                         BusError::new(-32001, format!("active provider '{}' not in pool", name))
                     })?;
-                    (entry.clone(), entry.model.clone())
+                    (name, entry.clone(), entry.model.clone())
                 }
             };
         // model_override from the request always wins.
         let final_model = model_override
             .map(|m| m.to_string())
             .unwrap_or(resolved_model);
-        Ok((entry.provider, final_model))
+        Self::check_model_allowed(&self.allowed_models, &name, &entry.model)?;
+        Ok((name, entry, final_model))
+    }
+
+    /// Fail with [`ERR_MODEL_NOT_ALLOWED`] if `model` — the one `provider`
+    /// will actually run — is outside a non-empty `[llm] allowed_models`.
+    fn check_model_allowed(
+        allowed: &[String],
+        provider: &str,
+        model: &str,
+    ) -> Result<(), BusError> {
+        if allowed.is_empty() || allowed.iter().any(|m| m == model) {
+            return Ok(());
+        }
+        warn!(
+            provider,
+            model, "llm request for a model outside allowed_models"
+        );
+        Err(BusError::new(
+            ERR_MODEL_NOT_ALLOWED,
+            format!("model not allowed: {model}"),
+        ))
+    }
+
+    /// Provider picked by `[[llm.auto_route]]` for a request that names
+    /// none.  `None` leaves the active default in charge.
+    fn auto_provider(&self, system: Option<&str>, content: &str) -> Option<String> {
+        if self.auto_route.is_empty() {
+            return None;
+        }
+        self.auto_provider_for(auto_route::estimate_tokens(system, content))
+    }
+
+    /// [`Self::auto_provider`] for a prompt of `tokens`.
+    fn auto_provider_for(&self, tokens: usize) -> Option<String> {
+        let name = auto_route::pick(&self.auto_route, tokens)?;
+        let model = self.pool.get(name).map_or("unknown", |e| e.model.as_str());
+        debug!(provider = name, model, tokens, "llm request auto-routed");
+        Some(name.to_string())
     }
 
     /// Resolve the instruction-pass provider (falls back to active default).
-    fn instruction_provider(&self) -> LlmProvider {
+    fn instruction_provider(&self) -> (String, ProviderEntry) {
         if let Some(ref name) = self.instruction_name
             && let Some(entry) = self.pool.get(name)
         {
-            return entry.provider.clone();
+            return (name.clone(), entry.clone());
         }
         // Fall back to active default.
         let name = self.active_name();
-        self.pool
-            .get(&name)
-            .map(|e| e.provider.clone())
-            .unwrap_or(LlmProvider::Dummy(
-                araliya_llm::providers::dummy::DummyProvider,
-            ))
+        match self.pool.get(&name) {
+            Some(entry) => (name, entry.clone()),
+            None => (
+                "dummy".to_string(),
+                ProviderEntry {
+                    provider: LlmProvider::Dummy(araliya_llm::providers::dummy::DummyProvider),
+                    model: "dummy".to_string(),
+                    rates: ModelRates::default(),
+                    context_window: FALLBACK_CONTEXT_WINDOW,
+                    max_tokens: 0,
+                },
+            ),
+        }
+    }
+
+    /// Capture the request context for the audit sink, if one is configured.
+    fn audit_request(
+        &self,
+        method: &str,
+        channel_id: &str,
+        provider: &str,
+        model: &str,
+        system: Option<&str>,
+        content: &str,
+    ) -> Option<(Arc<AuditSink>, AuditRequest)> {
+        let sink = self.audit.clone()?;
+        Some((
+            sink,
+            AuditRequest {
+                method: method.to_string(),
+                channel_id: channel_id.to_string(),
+                provider: provider.to_string(),
+                model: model.to_string(),
+                system: system.map(str::to_string),
+                prompt: content.to_string(),
+            },
+        ))
     }
 
+    /// Ping `provider` and report the result; `true` if it was reachable.
     async fn run_check(
         provider_name: &str,
         provider: &LlmProvider,
         model: &str,
         reporter: &HealthReporter,
-    ) {
+    ) -> bool {
         match provider.ping().await {
             Ok(()) => {
                 debug!(model, "llm provider reachable");
@@ -284,6 +653,7 @@ impl LlmSubsystem {
                         Some(serde_json::json!({ "provider": provider_name, "model": model })),
                     )
                     .await;
+                true
             }
             Err(e) => {
                 warn!(model, error = %e, "llm provider unreachable");
@@ -293,11 +663,138 @@ impl LlmSubsystem {
                         Some(serde_json::json!({ "provider": provider_name, "model": model })),
                     )
                     .await;
+                false
             }
         }
     }
 }
 
+/// Run one completion within `[llm] overall_deadline_seconds`.  The bound
+/// covers the whole request — every provider call it makes — while each
+/// provider's `timeout_seconds` limits a single HTTP call.
+async fn within_deadline(
+    deadline: Option<Duration>,
+    run: impl Future<Output = BusResult>,
+) -> BusResult {
+    let Some(limit) = deadline else {
+        return run.await;
+    };
+    tokio::time::timeout(limit, run).await.unwrap_or_else(|_| {
+        warn!(?limit, "llm completion exceeded overall deadline");
+        Err(BusError::new(
+            ERR_DEADLINE_EXCEEDED,
+            format!(
+                "llm completion exceeded overall_deadline_seconds = {}",
+                limit.as_secs()
+            ),
+        ))
+    })
+}
+
+/// Run a streaming completion into `tx`.  If it fails, the stream still
+/// ends with a [`StreamChunk::Done`] carrying the error, after whatever
+/// deltas were already sent.
+async fn stream_with_error_done<F, Fut>(
+    tx: mpsc::Sender<StreamChunk>,
+    run: F,
+) -> Result<(), ProviderError>
+where
+    F: FnOnce(mpsc::Sender<StreamChunk>) -> Fut,
+    Fut: Future<Output = Result<(), ProviderError>>,
+{
+    let result = run(tx.clone()).await;
+    if let Err(e) = &result {
+        let _ = tx
+            .send(StreamChunk::Done {
+                usage: None,
+                timing: None,
+                error: Some(e.to_string()),
+                stopped: false,
+            })
+            .await;
+    }
+    result
+}
+
+/// Run [`stream_with_error_done`] until `stop` fires; then the provider
+/// call is dropped and the stream ends with a `Done` marked `stopped`.
+async fn stream_until_stopped<F, Fut>(
+    tx: mpsc::Sender<StreamChunk>,
+    stop: CancellationToken,
+    run: F,
+) -> Result<(), ProviderError>
+where
+    F: FnOnce(mpsc::Sender<StreamChunk>) -> Fut,
+    Fut: Future<Output = Result<(), ProviderError>>,
+{
+    tokio::select! {
+        biased;
+        _ = stop.cancelled() => {
+            let _ = tx
+                .send(StreamChunk::Done {
+                    usage: None,
+                    timing: None,
+                    error: None,
+                    stopped: true,
+                })
+                .await;
+            Ok(())
+        }
+        result = stream_with_error_done(tx.clone(), run) => result,
+    }
+}
+
+/// Forward stream chunks to the caller while accumulating the visible text
+/// and final usage for the audit record.
+///
+/// Stops as soon as the caller's receiver is gone; dropping `rx` then makes
+/// the provider's next send fail, so a cancelled stream stops generating.
+async fn forward_stream_tap(
+    mut rx: mpsc::Receiver<StreamChunk>,
+    tx: mpsc::Sender<StreamChunk>,
+) -> (String, Option<LlmUsage>) {
+    let mut text = String::new();
+    let mut usage = None;
+    while let Some(chunk) = rx.recv().await {
+        match &chunk {
+            StreamChunk::Content(delta) => text.push_str(delta),
+            StreamChunk::Done { usage: u, .. } => usage = u.clone(),
+            StreamChunk::Thinking(_) => {}
+        }
+        if tx.send(chunk).await.is_err() {
+            break;
+        }
+    }
+    (text, usage)
+}
+
+/// Extract the `input` field of an `llm/embed` request as a list of strings.
+fn parse_embed_inputs(payload: &BusPayload) -> Result<Vec<String>, BusError> {
+    let BusPayload::JsonRequest { data } = payload else {
+        return Err(BusError::new(
+            ERR_METHOD_NOT_FOUND,
+            "llm/embed requires JsonRequest payload".to_string(),
+        ));
+    };
+    let v: serde_json::Value = serde_json::from_str(data)
+        .map_err(|e| BusError::new(-32001, format!("invalid JSON: {e}")))?;
+    match v.get("input") {
+        Some(serde_json::Value::String(s)) => Ok(vec![s.clone()]),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .map(|item| {
+                item.as_str().map(str::to_string).ok_or_else(|| {
+                    BusError::new(-32001, "'input' array must contain strings".to_string())
+                })
+            })
+            .collect(),
+        _ => Err(BusError::new(
+            -32001,
+            "missing 'input' field (string or array of strings)".to_string(),
+        )),
+    }
+}
+
 impl BusHandler for LlmSubsystem {
     fn prefix(&self) -> &str {
         "llm"
@@ -382,12 +879,13 @@ impl BusHandler for LlmSubsystem {
         if method == "llm/detailed_status" {
             let reporter = self.reporter.clone();
             let active_name = self.active_name();
-            let model = self
+            let (model, context_window) = self
                 .pool
                 .get(&active_name)
-                .map(|e| e.model.clone())
-                .unwrap_or_else(|| "unknown".to_string());
+                .map(|e| (e.model.clone(), Some(e.context_window)))
+                .unwrap_or_else(|| ("unknown".to_string(), None));
             let pool_names: Vec<String> = self.pool.keys().cloned().collect();
+            let auto_route = self.auto_route.clone();
             tokio::spawn(async move {
                 let base = match reporter {
                     Some(r) => match r.get_current().await {
@@ -403,7 +901,9 @@ impl BusHandler for LlmSubsystem {
                     "state": base.state,
                     "provider": active_name,
                     "model": model,
+                    "context_window": context_window,
                     "pool": pool_names,
+                    "auto_route": auto_route,
                 });
                 let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
                     data: data.to_string(),
@@ -449,6 +949,34 @@ impl BusHandler for LlmSubsystem {
             return;
         }
 
+        // ── llm/resolve ─────────────────────────────────────────────────────
+        // Which provider a request naming none would use for a prompt of
+        // `{"prompt_tokens": n}` (auto-route, else the active default), with
+        // its context window, so callers can size the prompt to it.
+        if method == "llm/resolve" {
+            let tokens = match &payload {
+                BusPayload::JsonRequest { data } => serde_json::from_str::<serde_json::Value>(data)
+                    .ok()
+                    .and_then(|v| v.get("prompt_tokens")?.as_u64())
+                    .unwrap_or(0) as usize,
+                _ => 0,
+            };
+            let provider = self.auto_provider_for(tokens);
+            let reply =
+                self.resolve_provider(provider.as_deref(), None)
+                    .map(|(name, entry, model)| BusPayload::JsonResponse {
+                        data: serde_json::json!({
+                            "provider": name,
+                            "model": model,
+                            "context_window": entry.context_window,
+                            "max_tokens": entry.max_tokens,
+                        })
+                        .to_string(),
+                    });
+            let _ = reply_tx.send(reply);
+            return;
+        }
+
         // ── llm/set_default ─────────────────────────────────────────────────
         // Switch the active provider at runtime. Expects JsonRequest with a
         // "provider" field naming a key in the pool.
@@ -499,6 +1027,102 @@ impl BusHandler for LlmSubsystem {
             return;
         }
 
+        // ── llm/embed ───────────────────────────────────────────────────────
+        // Expects JsonRequest `{"input": "text" | ["text", ...]}` and replies
+        // with `{"model", "embeddings": [[f32, ...], ...]}`.
+        if method == "llm/embed" {
+            let Some(embedder) = self.embedder.clone() else {
+                let _ = reply_tx.send(Err(BusError::new(
+                    -32001,
+                    "no embedding provider configured ([llm] embedding_provider)".to_string(),
+                )));
+                return;
+            };
+            let inputs = match parse_embed_inputs(&payload) {
+                Ok(inputs) => inputs,
+                Err(e) => {
+                    let _ = reply_tx.send(Err(e));
+                    return;
+                }
+            };
+            debug!(
+                inputs = inputs.len(),
+                model = embedder.model(),
+                "dispatching to embedding provider"
+            );
+            tokio::spawn(async move {
+                let result = embedder
+                    .embed(&inputs)
+                    .await
+                    .map(|embeddings| BusPayload::JsonResponse {
+                        data: serde_json::json!({
+                            "model": embedder.model(),
+                            "embeddings": embeddings,
+                        })
+                        .to_string(),
+                    })
+                    .map_err(|e| BusError::new(-32000, e.to_string()));
+                let _ = reply_tx.send(result);
+            });
+            return;
+        }
+
+        // ── llm/batch ───────────────────────────────────────────────────────
+        // Each item runs as an `llm/complete`, at most `batch_concurrency` at
+        // a time.  Results keep request order whatever order items finish in.
+        if method == "llm/batch" {
+            let BusPayload::LlmBatch { items } = payload else {
+                let _ = reply_tx.send(Err(BusError::new(
+                    ERR_METHOD_NOT_FOUND,
+                    "llm/batch requires LlmBatch payload".to_string(),
+                )));
+                return;
+            };
+            // An item that cannot start (oversized, unknown provider, not an
+            // `LlmRequest`) fails in its own slot; the others still run.
+            let runs: Vec<_> = items
+                .into_iter()
+                .map(|item| {
+                    self.check_prompt_size(&item)?;
+                    self.completion(method, item)
+                })
+                .collect();
+            let slots = Arc::new(Semaphore::new(self.batch_concurrency));
+            debug!(items = runs.len(), "dispatching llm batch");
+            tokio::spawn(async move {
+                let tasks: Vec<_> = runs
+                    .into_iter()
+                    .map(|run| {
+                        let slots = slots.clone();
+                        tokio::spawn(async move {
+                            let run = run?;
+                            let _slot = slots.acquire().await;
+                            run.await
+                        })
+                    })
+                    .collect();
+                let mut results = Vec::with_capacity(tasks.len());
+                let mut usage: Option<LlmUsage> = None;
+                for task in tasks {
+                    let result = task.await.unwrap_or_else(|e| {
+                        Err(BusError::new(-32000, format!("batch item failed: {e}")))
+                    });
+                    if let Ok(BusPayload::CommsMessage { usage: Some(u), .. }) = &result {
+                        usage.get_or_insert_default().add(u);
+                    }
+                    results.push(result);
+                }
+                let _ = reply_tx.send(Ok(BusPayload::LlmBatchResponse { results, usage }));
+            });
+            return;
+        }
+
+        // Every completion path (instruct, stream, complete) is size-capped.
+        if let Err(e) = self.check_prompt_size(&payload) {
+            let _ = reply_tx.send(Err(e));
+            return;
+        }
+
         // ── llm/instruct ────────────────────────────────────────────────────
         // Instruction-pass completion; uses the instruction provider if configured,
         // otherwise falls back to the active default.
@@ -510,12 +1134,32 @@ impl BusHandler for LlmSubsystem {
                 ..
             } = payload
             {
-                let provider = self.instruction_provider();
+                let (provider_name, entry) = self.instruction_provider();
+                if let Err(e) =
+                    Self::check_model_allowed(&self.allowed_models, &provider_name, &entry.model)
+                {
+                    let _ = reply_tx.send(Err(e));
+                    return;
+                }
+                let audit = self.audit_request(
+                    method,
+                    &channel_id,
+                    &provider_name,
+                    &entry.model,
+                    system.as_deref(),
+                    &content,
+                );
+                let strip_patterns = self.strip_patterns.clone();
                 debug!(%channel_id, "dispatching to instruction llm provider");
-                tokio::spawn(async move {
-                    let result = provider
+                let run = within_deadline(self.deadline, async move {
+                    let result = entry
+                        .provider
                         .complete(&content, system.as_deref(), Some(1024))
-                        .await
+                        .await;
+                    if let Some((sink, req)) = audit {
+                        sink.record_response(&req, &result, &entry.rates).await;
+                    }
+                    result
                         .map(|resp| {
                             if let Some(u) = &resp.usage {
                                 tracing::debug!(
@@ -527,15 +1171,17 @@ impl BusHandler for LlmSubsystem {
                             }
                             BusPayload::CommsMessage {
                                 channel_id,
-                                content: resp.text,
+                                content: strip::strip_regions(&resp.text, &strip_patterns),
                                 session_id: None,
                                 usage: resp.usage,
                                 timing: resp.timing,
                                 thinking: resp.thinking,
                             }
                         })
-                        .map_err(|e| BusError::new(-32000, e.to_string()));
-                    let _ = reply_tx.send(result);
+                        .map_err(|e| BusError::new(-32000, e.to_string()))
+                });
+                tokio::spawn(async move {
+                    let _ = reply_tx.send(run.await);
                 });
             } else {
                 let _ = reply_tx.send(Err(BusError::new(
@@ -546,6 +1192,45 @@ impl BusHandler for LlmSubsystem {
             return;
         }
 
+        // ── llm/stream/stop ────────────────────────────────────────────────
+        // Expects JsonRequest `{"request_id": "..."}` naming an in-flight
+        // `llm/stream`; replies `{"ok": true}` once the stop is signalled.
+        if method == "llm/stream/stop" {
+            let request_id = match &payload {
+                BusPayload::JsonRequest { data } => serde_json::from_str::<serde_json::Value>(data)
+                    .ok()
+                    .and_then(|v| v["request_id"].as_str().map(str::to_string)),
+                _ => None,
+            };
+            let Some(request_id) = request_id else {
+                let _ = reply_tx.send(Err(BusError::new(
+                    -32001,
+                    "llm/stream/stop requires JsonRequest {\"request_id\": \"...\"}".to_string(),
+                )));
+                return;
+            };
+            let stop = self
+                .streams
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&request_id);
+            let _ = reply_tx.send(match stop {
+                Some(stop) => {
+                    info!(%request_id, "stopping llm stream");
+                    stop.cancel();
+                    Ok(BusPayload::JsonResponse {
+                        data: serde_json::json!({ "ok": true, "request_id": request_id })
+                            .to_string(),
+                    })
+                }
+                None => Err(BusError::new(
+                    -32001,
+                    format!("no stream in flight with request_id '{request_id}'"),
+                )),
+            });
+            return;
+        }
+
         // ── llm/stream ─────────────────────────────────────────────────────
         if method == "llm/stream" {
             if let BusPayload::LlmRequest {
@@ -554,23 +1239,92 @@ impl BusHandler for LlmSubsystem {
                 system,
                 provider_override,
                 model_override,
+                response_format,
             } = payload
             {
+                let provider_override =
+                    provider_override.or_else(|| self.auto_provider(system.as_deref(), &content));
                 match self.resolve_provider(provider_override.as_deref(), model_override.as_deref())
                 {
-                    Ok((provider, _model)) => {
-                        debug!(%method, %channel_id, "dispatching streaming to llm provider");
+                    Ok((provider_name, entry, _model)) => {
+                        // Providers always run their configured model, so
+                        // that is what the audit records.
+                        let audit = self.audit_request(
+                            method,
+                            &channel_id,
+                            &provider_name,
+                            &entry.model,
+                            system.as_deref(),
+                            &content,
+                        );
+                        let (provider, system) =
+                            json_mode::prepare(&entry.provider, system.as_deref(), response_format);
+                        let strip_patterns = self.strip_patterns.clone();
+                        let request_id = uuid::Uuid::new_v4().to_string();
+                        let stop = CancellationToken::new();
+                        let streams = self.streams.clone();
+                        streams
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .insert(request_id.clone(), stop.clone());
+                        debug!(%method, %channel_id, %request_id, "dispatching streaming to llm provider");
                         tokio::spawn(async move {
                             let (tx, rx) = mpsc::channel(64);
                             let _ = reply_tx.send(Ok(BusPayload::LlmStreamResult {
                                 rx: StreamReceiver(rx),
+                                request_id: Some(request_id.clone()),
                             }));
-                            if let Err(e) = provider
-                                .complete_stream(&content, system.as_deref(), tx, None)
-                                .await
-                            {
-                                warn!(error = %e, "streaming LLM provider error");
+                            let tx = if strip_patterns.is_empty() {
+                                tx
+                            } else {
+                                let (strip_tx, strip_rx) = mpsc::channel(64);
+                                tokio::spawn(strip::forward_stripped(strip_rx, tx, strip_patterns));
+                                strip_tx
+                            };
+                            match audit {
+                                None => {
+                                    if let Err(e) = stream_until_stopped(tx, stop, |tx| {
+                                        provider.complete_stream(
+                                            &content,
+                                            system.as_deref(),
+                                            tx,
+                                            None,
+                                        )
+                                    })
+                                    .await
+                                    {
+                                        warn!(error = %e, "streaming LLM provider error");
+                                    }
+                                }
+                                Some((sink, req)) => {
+                                    // Tap the stream so the audit line sees the
+                                    // assembled text and final usage.
+                                    let (tap_tx, tap_rx) = mpsc::channel(64);
+                                    let tap = tokio::spawn(forward_stream_tap(tap_rx, tx));
+                                    let result = stream_until_stopped(tap_tx, stop, |tx| {
+                                        provider.complete_stream(
+                                            &content,
+                                            system.as_deref(),
+                                            tx,
+                                            None,
+                                        )
+                                    })
+                                    .await;
+                                    let (text, usage) = tap.await.unwrap_or_default();
+                                    let outcome = match &result {
+                                        Ok(_) => Ok((text.as_str(), usage.as_ref())),
+                                        Err(e) => {
+                                            warn!(error = %e, "streaming LLM provider error");
+                                            Err(e.to_string())
+                                        }
+                                    };
+                                    sink.record(&req, outcome, &entry.rates).await;
+                                }
                             }
+                            streams
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .remove(&request_id);
                         });
                     }
                     Err(e) => {
@@ -587,54 +1341,14 @@ impl BusHandler for LlmSubsystem {
         }
 
         // ── Default: llm/complete (and any other method) ────────────────────
-        match payload {
-            BusPayload::LlmRequest {
-                channel_id,
-                content,
-                system,
-                provider_override,
-                model_override,
-            } => {
-                match self.resolve_provider(provider_override.as_deref(), model_override.as_deref())
-                {
-                    Ok((provider, _model)) => {
-                        debug!(%method, %channel_id, "dispatching to llm provider");
-                        tokio::spawn(async move {
-                            let result = provider
-                                .complete(&content, system.as_deref(), None)
-                                .await
-                                .map(|resp| {
-                                    if let Some(u) = &resp.usage {
-                                        tracing::debug!(
-                                            input_tokens = u.input_tokens,
-                                            output_tokens = u.output_tokens,
-                                            cached_tokens = u.cached_input_tokens,
-                                            "llm usage"
-                                        );
-                                    }
-                                    BusPayload::CommsMessage {
-                                        channel_id,
-                                        content: resp.text,
-                                        session_id: None,
-                                        usage: resp.usage,
-                                        timing: resp.timing,
-                                        thinking: resp.thinking,
-                                    }
-                                })
-                                .map_err(|e| BusError::new(-32000, e.to_string()));
-                            let _ = reply_tx.send(result);
-                        });
-                    }
-                    Err(e) => {
-                        let _ = reply_tx.send(Err(e));
-                    }
-                }
+        match self.completion(method, payload) {
+            Ok(run) => {
+                tokio::spawn(async move {
+                    let _ = reply_tx.send(run.await);
+                });
             }
-            _ => {
-                let _ = reply_tx.send(Err(BusError::new(
-                    ERR_METHOD_NOT_FOUND,
-                    format!("unsupported payload for method: {method}"),
-                )));
+            Err(e) => {
+                let _ = reply_tx.send(Err(e));
             }
         }
     }
@@ -678,3 +1392,840 @@ impl BusHandler for LlmSubsystem {
         ComponentInfo::running("llm", "LLM", children)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy_config(audit_log: Option<std::path::PathBuf>) -> LlmConfig {
+        LlmConfig {
+            default: "dummy".into(),
+            providers: HashMap::new(),
+            instruction: None,
+            routes: HashMap::new(),
+            auto_route: Vec::new(),
+            audit_log,
+            audit_full: true,
+            audit_max_bytes: None,
+            max_prompt_chars: None,
+            batch_concurrency: 4,
+            overall_deadline_seconds: None,
+            embedding: None,
+            strip_patterns: Vec::new(),
+            faulty: Default::default(),
+            pool: Default::default(),
+            warmup: false,
+            allowed_models: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn completion_writes_one_audit_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("audit/llm.jsonl");
+        let llm =
+            LlmSubsystem::new(&dummy_config(Some(path.clone())), Some("sk-test".into())).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        rx.await.unwrap().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let v: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(v["method"], "llm/complete");
+        assert_eq!(v["channel_id"], "pty0");
+        assert_eq!(v["provider"], "dummy");
+        assert_eq!(v["model"], "dummy");
+        assert_eq!(v["prompt"], "hello");
+        assert_eq!(v["completion"], "[echo] hello");
+        assert_eq!(v["prompt_sha256"].as_str().unwrap().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn oversized_prompts_are_rejected_before_the_provider() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("llm.jsonl");
+        let config = LlmConfig {
+            max_prompt_chars: Some(10),
+            ..dummy_config(Some(path.clone()))
+        };
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+        let request = |content: &str, system: Option<&str>| BusPayload::LlmRequest {
+            channel_id: "pty0".into(),
+            content: content.into(),
+            system: system.map(str::to_string),
+            provider_override: None,
+            model_override: None,
+            response_format: ResponseFormat::Text,
+        };
+
+        for method in ["llm/complete", "llm/stream", "llm/instruct"] {
+            let (tx, rx) = oneshot::channel();
+            llm.handle_request(method, request("hello", Some("be brief")), tx);
+            let err = rx.await.unwrap().unwrap_err();
+            assert_eq!(err.code, ERR_PROMPT_TOO_LARGE, "{method}");
+            assert!(err.message.contains("prompt too large"), "{method}");
+        }
+        // Nothing reached the provider, so nothing was audited.
+        assert!(!path.exists());
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request("llm/complete", request("hello", Some("hi")), tx);
+        rx.await.unwrap().unwrap();
+        assert!(path.exists());
+    }
+
+    #[tokio::test]
+    async fn audit_records_the_model_the_provider_ran() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("llm.jsonl");
+        let llm = LlmSubsystem::new(&dummy_config(Some(path.clone())), None).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: Some("gpt-imaginary".into()),
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        rx.await.unwrap().unwrap();
+
+        let line = std::fs::read_to_string(&path).unwrap();
+        let v: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(v["model"], "dummy");
+    }
+
+    #[tokio::test]
+    async fn failed_stream_keeps_its_deltas_and_ends_with_an_error() {
+        let (tx, mut rx) = mpsc::channel(8);
+        let result = stream_with_error_done(tx, |tx| async move {
+            for delta in ["Hello", " wor"] {
+                tx.send(StreamChunk::Content(delta.into())).await.unwrap();
+            }
+            Err(ProviderError::Request(
+                "stream read error: connection reset".into(),
+            ))
+        })
+        .await;
+        assert!(result.is_err());
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            chunks.push(chunk);
+        }
+        assert_eq!(chunks.len(), 3);
+        assert!(matches!(&chunks[0], StreamChunk::Content(d) if d == "Hello"));
+        assert!(matches!(&chunks[1], StreamChunk::Content(d) if d == " wor"));
+        assert!(matches!(
+            &chunks[2],
+            StreamChunk::Done { error: Some(e), usage: None, .. } if e.contains("connection reset")
+        ));
+    }
+
+    #[tokio::test]
+    async fn stream_tap_stops_when_caller_goes_away() {
+        let (provider_tx, provider_rx) = mpsc::channel(1);
+        let (caller_tx, caller_rx) = mpsc::channel(1);
+        let tap = tokio::spawn(forward_stream_tap(provider_rx, caller_tx));
+        drop(caller_rx);
+
+        provider_tx
+            .send(StreamChunk::Content("partial".into()))
+            .await
+            .unwrap();
+        let (text, usage) = tap.await.unwrap();
+        assert_eq!(text, "partial");
+        assert!(usage.is_none());
+        // The tap dropped its receiver, so the provider sees the cancellation.
+        assert!(
+            provider_tx
+                .send(StreamChunk::Content("more".into()))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn no_audit_file_without_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let llm = LlmSubsystem::new(&dummy_config(None), None).unwrap();
+        assert!(llm.audit.is_none());
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        rx.await.unwrap().unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn embed_without_provider_errors() {
+        let llm = LlmSubsystem::new(&dummy_config(None), None).unwrap();
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/embed",
+            BusPayload::JsonRequest {
+                data: r#"{"input": "hello"}"#.into(),
+            },
+            tx,
+        );
+        let err = rx.await.unwrap().unwrap_err();
+        assert!(err.message.contains("no embedding provider"));
+    }
+
+    #[test]
+    fn embed_inputs_accept_string_or_array() {
+        let one = BusPayload::JsonRequest {
+            data: r#"{"input": "a"}"#.into(),
+        };
+        assert_eq!(parse_embed_inputs(&one).unwrap(), vec!["a"]);
+        let many = BusPayload::JsonRequest {
+            data: r#"{"input": ["a", "b"]}"#.into(),
+        };
+        assert_eq!(parse_embed_inputs(&many).unwrap(), vec!["a", "b"]);
+        let bad = BusPayload::JsonRequest {
+            data: r#"{"input": [1]}"#.into(),
+        };
+        assert!(parse_embed_inputs(&bad).is_err());
+    }
+
+    #[tokio::test]
+    async fn completion_is_stripped_but_audit_keeps_raw_text() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("llm.jsonl");
+        let mut config = dummy_config(Some(path.clone()));
+        config.strip_patterns = vec![StripPattern::parse("<think>...</think>").unwrap()];
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "<think>plan</think>hi".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, "[echo] hi"),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+
+        let line = std::fs::read_to_string(&path).unwrap();
+        let v: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(v["completion"], "[echo] <think>plan</think>hi");
+    }
+
+    async fn complete_json(llm: &LlmSubsystem, content: &str) -> Result<BusPayload, BusError> {
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: content.into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Json,
+            },
+            tx,
+        );
+        rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn json_format_repairs_or_rejects_the_reply() {
+        let llm = LlmSubsystem::new(&dummy_config(None), None).unwrap();
+
+        // The dummy echoes with a "[echo] " prefix, which the repair drops.
+        match complete_json(&llm, r#"{"a":1}"#).await {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, r#"{"a":1}"#),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+
+        let err = complete_json(&llm, "hello").await.unwrap_err();
+        assert!(err.message.starts_with("llm reply is not valid JSON"));
+    }
+
+    fn dummy_provider(model: &str) -> araliya_core::config::ProviderConfig {
+        araliya_core::config::ProviderConfig {
+            api_type: araliya_core::config::ApiType::Dummy,
+            api_base_url: String::new(),
+            model: model.into(),
+            temperature: 0.0,
+            api_key: None,
+            reasoning_effort: None,
+            timeout_seconds: 1,
+            max_tokens: 0,
+            prompt_caching: false,
+            context_window: 8_192,
+            input_per_million_usd: 0.0,
+            output_per_million_usd: 0.0,
+            cached_input_per_million_usd: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn auto_route_picks_provider_by_prompt_size() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("llm.jsonl");
+        let mut config = dummy_config(Some(path.clone()));
+        config.providers = HashMap::from([
+            ("cheap".to_string(), dummy_provider("cheap-model")),
+            ("strong".to_string(), dummy_provider("strong-model")),
+        ]);
+        config.auto_route = vec![
+            AutoRouteRule {
+                min_tokens: None,
+                max_tokens: Some(20),
+                provider: "cheap".into(),
+            },
+            AutoRouteRule {
+                min_tokens: Some(200),
+                max_tokens: None,
+                provider: "strong".into(),
+            },
+        ];
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        for content in ["hi".to_string(), "word ".repeat(200), "x".repeat(400)] {
+            let (tx, rx) = oneshot::channel();
+            llm.handle_request(
+                "llm/complete",
+                BusPayload::LlmRequest {
+                    channel_id: "pty0".into(),
+                    content,
+                    system: None,
+                    provider_override: None,
+                    model_override: None,
+                    response_format: ResponseFormat::Text,
+                },
+                tx,
+            );
+            rx.await.unwrap().unwrap();
+        }
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let models: Vec<String> = text
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["model"].to_string())
+            .collect();
+        // Short → cheap, long → strong, in between → the default.
+        assert_eq!(
+            models,
+            [r#""cheap-model""#, r#""strong-model""#, r#""dummy""#]
+        );
+    }
+
+    async fn resolve(llm: &LlmSubsystem, prompt_tokens: usize) -> serde_json::Value {
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/resolve",
+            BusPayload::JsonRequest {
+                data: serde_json::json!({ "prompt_tokens": prompt_tokens }).to_string(),
+            },
+            tx,
+        );
+        match rx.await.unwrap().unwrap() {
+            BusPayload::JsonResponse { data } => serde_json::from_str(&data).unwrap(),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_names_the_provider_a_request_would_use() {
+        let mut config = dummy_config(None);
+        let mut strong = dummy_provider("strong-model");
+        strong.context_window = 200_000;
+        strong.max_tokens = 4_096;
+        config.providers = HashMap::from([
+            ("cheap".to_string(), dummy_provider("cheap-model")),
+            ("strong".to_string(), strong),
+        ]);
+        config.auto_route = vec![AutoRouteRule {
+            min_tokens: Some(200),
+            max_tokens: None,
+            provider: "strong".into(),
+        }];
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        let big = resolve(&llm, 500).await;
+        assert_eq!(big["provider"], "strong");
+        assert_eq!(big["model"], "strong-model");
+        assert_eq!(big["context_window"], 200_000);
+        assert_eq!(big["max_tokens"], 4_096);
+
+        // Below every rule: the active default, which can change at runtime.
+        assert_eq!(resolve(&llm, 10).await["provider"], "dummy");
+        *llm.active.write().unwrap() = "cheap".to_string();
+        let small = resolve(&llm, 10).await;
+        assert_eq!(small["provider"], "cheap");
+        assert_eq!(small["context_window"], 8_192);
+    }
+
+    /// Chat-completions endpoint answering `reply to <prompt>` with fixed
+    /// usage, one request per connection.  A prompt of `slow` takes longer
+    /// than the rest.  Returns the URL and the most requests ever in flight.
+    async fn slow_chat_server() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let max_seen = peak.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        stream.read_line(&mut line).await.unwrap();
+                        let line = line.trim_end().to_ascii_lowercase();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some(n) = line.strip_prefix("content-length:") {
+                            content_length = n.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    stream.read_exact(&mut body).await.unwrap();
+                    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let prompt = body["messages"]
+                        .as_array()
+                        .and_then(|m| m.last())
+                        .and_then(|m| m["content"].as_str())
+                        .unwrap_or_default()
+                        .to_string();
+
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    let delay = if prompt == "slow" { 200 } else { 50 };
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let reply = serde_json::json!({
+                        "choices": [{"message": {"content": format!("reply to {prompt}")}}],
+                        "usage": {"prompt_tokens": 3, "completion_tokens": 5}
+                    })
+                    .to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                         content-length: {}\r\nconnection: close\r\n\r\n{reply}",
+                        reply.len()
+                    );
+                    let _ = stream.get_mut().write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (url, max_seen)
+    }
+
+    #[tokio::test]
+    async fn batch_replies_in_order_within_the_concurrency_limit() {
+        let (url, peak) = slow_chat_server().await;
+        let mut config = dummy_config(None);
+        config.default = "fake".into();
+        config.batch_concurrency = 2;
+        config.providers = HashMap::from([(
+            "fake".to_string(),
+            araliya_core::config::ProviderConfig {
+                api_type: araliya_core::config::ApiType::ChatCompletions,
+                api_base_url: url,
+                timeout_seconds: 5,
+                ..dummy_provider("fake-model")
+            },
+        )]);
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        // The first item finishes last; its result must still come first.
+        let items = ["slow", "two", "three"]
+            .map(|content| BusPayload::LlmRequest {
+                channel_id: "batch".into(),
+                content: content.into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            })
+            .to_vec();
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request("llm/batch", BusPayload::LlmBatch { items }, tx);
+
+        let Ok(BusPayload::LlmBatchResponse { results, usage }) = rx.await.unwrap() else {
+            panic!("expected LlmBatchResponse");
+        };
+        let replies: Vec<String> = results
+            .into_iter()
+            .map(|r| match r.unwrap() {
+                BusPayload::CommsMessage { content, .. } => content,
+                other => panic!("unexpected item reply: {other:?}"),
+            })
+            .collect();
+        assert_eq!(replies, ["reply to slow", "reply to two", "reply to three"]);
+        let usage = usage.unwrap();
+        assert_eq!((usage.input_tokens, usage.output_tokens), (9, 15));
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn empty_completion_is_logged_with_its_finish_reason() {
+        use araliya_core::logger::LogBuffer;
+        use araliya_core::obs::ObsLevel;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let logs = LogBuffer::new(16);
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(logs.layer()));
+
+        // An endpoint whose only answer is a blank, length-capped completion.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let reply = serde_json::json!({
+                "choices": [{"message": {"content": ""}, "finish_reason": "length"}]
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+                 content-length: {}\r\nconnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+
+        let mut config = dummy_config(None);
+        config.default = "fake".into();
+        config.providers = HashMap::from([(
+            "fake".to_string(),
+            araliya_core::config::ProviderConfig {
+                api_type: araliya_core::config::ApiType::ChatCompletions,
+                api_base_url: url,
+                timeout_seconds: 5,
+                ..dummy_provider("fake-model")
+            },
+        )]);
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        match rx.await.unwrap() {
+            Ok(BusPayload::CommsMessage { content, .. }) => assert_eq!(content, ""),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+        let warned = logs.recent(ObsLevel::Warn, 16);
+        assert!(
+            warned
+                .iter()
+                .any(|e| e.message.contains("empty completion")
+                    && e.fields["finish_reason"] == "length"),
+            "{warned:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn stopped_stream_ends_with_a_stopped_done() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // An endpoint that streams one delta and then stalls mid-stream,
+        // with more deltas behind it.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    let delta = |text: &str| {
+                        format!(
+                            "data: {}\n\n",
+                            serde_json::json!({"choices": [{"delta": {"content": text}}]})
+                        )
+                    };
+                    let head = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\
+                                connection: close\r\n\r\n";
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    let _ = stream.write_all(delta("first").as_bytes()).await;
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    let _ = stream.write_all(delta("second").as_bytes()).await;
+                });
+            }
+        });
+
+        let mut config = dummy_config(None);
+        config.default = "fake".into();
+        config.providers = HashMap::from([(
+            "fake".to_string(),
+            araliya_core::config::ProviderConfig {
+                api_type: araliya_core::config::ApiType::ChatCompletions,
+                api_base_url: url,
+                timeout_seconds: 60,
+                ..dummy_provider("fake-model")
+            },
+        )]);
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/stream",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        let Ok(BusPayload::LlmStreamResult {
+            rx: StreamReceiver(mut chunks),
+            request_id: Some(request_id),
+        }) = rx.await.unwrap()
+        else {
+            panic!("expected LlmStreamResult with a request_id");
+        };
+        assert!(matches!(chunks.recv().await, Some(StreamChunk::Content(t)) if t == "first"));
+
+        let stop = |request_id: &str| {
+            let (tx, rx) = oneshot::channel();
+            llm.handle_request(
+                "llm/stream/stop",
+                BusPayload::JsonRequest {
+                    data: serde_json::json!({ "request_id": request_id }).to_string(),
+                },
+                tx,
+            );
+            rx
+        };
+        assert!(stop(&request_id).await.unwrap().is_ok());
+
+        let rest = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut rest = Vec::new();
+            while let Some(chunk) = chunks.recv().await {
+                rest.push(chunk);
+            }
+            rest
+        })
+        .await
+        .expect("stopped stream did not close");
+        assert!(
+            matches!(
+                rest.as_slice(),
+                [StreamChunk::Done {
+                    stopped: true,
+                    error: None,
+                    ..
+                }]
+            ),
+            "{rest:?}"
+        );
+
+        // The stream is gone once stopped.
+        let err = stop(&request_id).await.unwrap().unwrap_err();
+        assert_eq!(err.code, -32001);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completion_is_cut_off_at_the_overall_deadline() {
+        // An endpoint that accepts connections and never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+
+        let mut config = dummy_config(None);
+        config.default = "stalled".into();
+        config.overall_deadline_seconds = Some(20);
+        // Each call may take 30 s: the overall bound must win.
+        config.providers = HashMap::from([(
+            "stalled".to_string(),
+            araliya_core::config::ProviderConfig {
+                api_type: araliya_core::config::ApiType::ChatCompletions,
+                api_base_url: url,
+                timeout_seconds: 30,
+                ..dummy_provider("stalled-model")
+            },
+        )]);
+        let llm = LlmSubsystem::new(&config, None).unwrap();
+
+        let started = tokio::time::Instant::now();
+        let (tx, rx) = oneshot::channel();
+        llm.handle_request(
+            "llm/complete",
+            BusPayload::LlmRequest {
+                channel_id: "pty0".into(),
+                content: "hello".into(),
+                system: None,
+                provider_override: None,
+                model_override: None,
+                response_format: ResponseFormat::Text,
+            },
+            tx,
+        );
+        let err = rx.await.unwrap().unwrap_err();
+        assert_eq!(err.code, ERR_DEADLINE_EXCEEDED);
+        assert_eq!(started.elapsed(), Duration::from_secs(20));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn warmup_is_sent_once_after_the_provider_is_reachable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("llm.jsonl");
+        let config = LlmConfig {
+            warmup: true,
+            ..dummy_config(Some(path.clone()))
+        };
+        let registry = araliya_core::bus::health::HealthRegistry::new();
+        let llm = LlmSubsystem::new(&config, None)
+            .unwrap()
+            .with_health_reporter(registry.reporter("llm"));
+        let shutdown = CancellationToken::new();
+        llm.spawn_health_checker(shutdown.clone());
+
+        // The startup check and three periodic ones all find it reachable.
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL * 3 + Duration::from_secs(1)).await;
+        shutdown.cancel();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let warmups: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .filter(|v: &serde_json::Value| v["method"] == "llm/warmup")
+            .collect();
+        assert_eq!(warmups.len(), 1);
+        assert_eq!(warmups[0]["prompt"], WARMUP_PROMPT);
+        assert_eq!(warmups[0]["provider"], "dummy");
+    }
+
+    #[tokio::test]
+    async fn only_allowed_models_can_be_selected() {
+        let llm_with = |allowed: &[&str]| {
+            let config = LlmConfig {
+                allowed_models: allowed.iter().map(|m| m.to_string()).collect(),
+                ..dummy_config(None)
+            };
+            LlmSubsystem::new(&config, None).unwrap()
+        };
+        let request = |llm: &LlmSubsystem, method: &str, model_override: Option<&str>| {
+            let (tx, rx) = oneshot::channel();
+            llm.handle_request(
+                method,
+                BusPayload::LlmRequest {
+                    channel_id: "pty0".into(),
+                    content: "hello".into(),
+                    system: None,
+                    provider_override: None,
+                    model_override: model_override.map(str::to_string),
+                    response_format: ResponseFormat::Text,
+                },
+                tx,
+            );
+            rx
+        };
+
+        // The dummy provider always runs its configured model, "dummy":
+        // that is what is checked, whatever the override names.
+        let llm = llm_with(&["dummy"]);
+        assert!(request(&llm, "llm/complete", None).await.unwrap().is_ok());
+        assert!(
+            request(&llm, "llm/complete", Some("gpt-5-pro"))
+                .await
+                .unwrap()
+                .is_ok()
+        );
+
+        let llm = llm_with(&["cheap-model"]);
+        for method in ["llm/complete", "llm/stream", "llm/instruct"] {
+            let err = request(&llm, method, Some("cheap-model"))
+                .await
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(err.code, ERR_MODEL_NOT_ALLOWED, "{method}");
+            assert_eq!(err.message, "model not allowed: dummy", "{method}");
+        }
+    }
+
+    #[test]
+    fn auto_route_to_unknown_provider_is_rejected() {
+        let mut config = dummy_config(None);
+        config.auto_route = vec![AutoRouteRule {
+            min_tokens: None,
+            max_tokens: None,
+            provider: "missing".into(),
+        }];
+        assert!(matches!(
+            LlmSubsystem::new(&config, None),
+            Err(ProviderError::UnknownProvider(p)) if p == "missing"
+        ));
+    }
+}
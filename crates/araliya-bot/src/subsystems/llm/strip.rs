@@ -0,0 +1,271 @@
+//! Completion post-processing: remove configured `open...close` regions
+//! (e.g. `<think>...</think>` from reasoning models) before the text is
+//! returned to callers.  The audit log records the raw provider output.
+//!
+//! Buffered replies go through [`strip_regions`]; streams through
+//! [`forward_stripped`], which holds back only the few bytes that could be
+//! the start of a delimiter split across chunks.
+
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use araliya_core::config::StripPattern;
+use araliya_core::types::llm::StreamChunk;
+
+/// Remove every region delimited by one of `patterns`, then trim.
+///
+/// An opener without a matching closer strips to the end of the text, so a
+/// truncated reasoning block does not leak.  Text with no matches is
+/// returned unchanged (not even trimmed).
+pub(super) fn strip_regions(text: &str, patterns: &[StripPattern]) -> String {
+    let mut out = text.to_string();
+    let mut changed = false;
+    for p in patterns {
+        while let Some(start) = out.find(&p.open) {
+            let after_open = start + p.open.len();
+            let end = out[after_open..]
+                .find(&p.close)
+                .map_or(out.len(), |i| after_open + i + p.close.len());
+            out.replace_range(start..end, "");
+            changed = true;
+        }
+    }
+    if changed { out.trim().to_string() } else { out }
+}
+
+/// Incremental [`strip_regions`] over a sequence of text deltas.
+///
+/// Leading whitespace left behind by a stripped region is dropped, as the
+/// buffered path trims; trailing whitespace cannot be known until the end and
+/// is passed through.
+struct StreamStripper {
+    patterns: Arc<[StripPattern]>,
+    /// Index of the pattern whose region we are inside.
+    inside: Option<usize>,
+    /// Text not yet emitted: a possible delimiter prefix.
+    pending: String,
+    stripped: bool,
+    emitted_text: bool,
+}
+
+impl StreamStripper {
+    fn new(patterns: Arc<[StripPattern]>) -> Self {
+        Self {
+            patterns,
+            inside: None,
+            pending: String::new(),
+            stripped: false,
+            emitted_text: false,
+        }
+    }
+
+    /// Feed one delta; returns the text that is safe to emit now.
+    fn push(&mut self, delta: &str) -> String {
+        self.pending.push_str(delta);
+        let mut out = String::new();
+        loop {
+            match self.inside {
+                None => {
+                    let next = self
+                        .patterns
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, p)| self.pending.find(&p.open).map(|pos| (pos, i)))
+                        .min();
+                    match next {
+                        Some((pos, i)) => {
+                            out.push_str(&self.pending[..pos]);
+                            self.pending
+                                .replace_range(..pos + self.patterns[i].open.len(), "");
+                            self.inside = Some(i);
+                            self.stripped = true;
+                        }
+                        None => {
+                            let keep = self
+                                .patterns
+                                .iter()
+                                .map(|p| partial_suffix(&self.pending, &p.open))
+                                .max()
+                                .unwrap_or(0);
+                            let cut = self.pending.len() - keep;
+                            out.push_str(&self.pending[..cut]);
+                            self.pending.replace_range(..cut, "");
+                            break;
+                        }
+                    }
+                }
+                Some(i) => {
+                    let close = &self.patterns[i].close;
+                    match self.pending.find(close.as_str()) {
+                        Some(pos) => {
+                            self.pending.replace_range(..pos + close.len(), "");
+                            self.inside = None;
+                        }
+                        None => {
+                            let keep = partial_suffix(&self.pending, close);
+                            let cut = self.pending.len() - keep;
+                            self.pending.replace_range(..cut, "");
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.emit(out)
+    }
+
+    /// End of stream: flush held-back text unless it is inside a region
+    /// (an unclosed opener strips to the end, as in [`strip_regions`]).
+    fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.pending);
+        match self.inside {
+            Some(_) => String::new(),
+            None => self.emit(rest),
+        }
+    }
+
+    fn emit(&mut self, mut out: String) -> String {
+        if self.stripped && !self.emitted_text {
+            out = out.trim_start().to_string();
+        }
+        if !out.is_empty() {
+            self.emitted_text = true;
+        }
+        out
+    }
+}
+
+/// Length of the longest proper prefix of `delim` that `text` ends with.
+fn partial_suffix(text: &str, delim: &str) -> usize {
+    (1..delim.len())
+        .rev()
+        .find(|&n| delim.is_char_boundary(n) && text.ends_with(&delim[..n]))
+        .unwrap_or(0)
+}
+
+/// Forward `rx` to `tx`, stripping `patterns` from content deltas.  Stops
+/// when the caller goes away, which in turn cancels the provider.
+pub(super) async fn forward_stripped(
+    mut rx: mpsc::Receiver<StreamChunk>,
+    tx: mpsc::Sender<StreamChunk>,
+    patterns: Arc<[StripPattern]>,
+) {
+    let mut stripper = StreamStripper::new(patterns);
+    while let Some(chunk) = rx.recv().await {
+        let chunk = match chunk {
+            StreamChunk::Content(delta) => {
+                let text = stripper.push(&delta);
+                if text.is_empty() {
+                    continue;
+                }
+                StreamChunk::Content(text)
+            }
+            done @ StreamChunk::Done { .. } => {
+                let rest = stripper.finish();
+                if !rest.is_empty() && tx.send(StreamChunk::Content(rest)).await.is_err() {
+                    return;
+                }
+                done
+            }
+            other => other,
+        };
+        if tx.send(chunk).await.is_err() {
+            return;
+        }
+    }
+    let rest = stripper.finish();
+    if !rest.is_empty() {
+        let _ = tx.send(StreamChunk::Content(rest)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn think() -> Vec<StripPattern> {
+        vec![StripPattern::parse("<think>...</think>").unwrap()]
+    }
+
+    #[test]
+    fn think_block_is_removed() {
+        let raw = "<think>\nThe user wants a greeting.\n</think>\n\nHello there!";
+        assert_eq!(strip_regions(raw, &think()), "Hello there!");
+    }
+
+    #[test]
+    fn multiple_and_unclosed_blocks_are_removed() {
+        let raw = "A <think>x</think>B<think>y</think> C <think>truncated";
+        assert_eq!(strip_regions(raw, &think()), "A B C");
+    }
+
+    #[test]
+    fn text_without_blocks_is_unchanged() {
+        let raw = "  plain answer with <b>markup</b>\n";
+        assert_eq!(strip_regions(raw, &think()), raw);
+        assert_eq!(strip_regions(raw, &[]), raw);
+    }
+
+    fn stream(deltas: &[&str]) -> String {
+        let mut stripper = StreamStripper::new(think().into());
+        let mut out: String = deltas.iter().map(|d| stripper.push(d)).collect();
+        out.push_str(&stripper.finish());
+        out
+    }
+
+    #[test]
+    fn stream_strips_delimiters_split_across_deltas() {
+        assert_eq!(
+            stream(&["<thi", "nk>plan", " more</th", "ink>\n\nHel", "lo"]),
+            "Hello"
+        );
+        assert_eq!(stream(&["A <think>x</think>B", "<think>y"]), "A B");
+    }
+
+    #[test]
+    fn stream_releases_false_delimiter_prefixes() {
+        assert_eq!(stream(&["a <th", "ing> b <"]), "a <thing> b <");
+    }
+
+    #[test]
+    fn stream_holds_back_only_a_possible_opener() {
+        let mut stripper = StreamStripper::new(think().into());
+        assert_eq!(stripper.push("Hello <thi"), "Hello ");
+        assert_eq!(stripper.push("s"), "<this");
+    }
+
+    #[tokio::test]
+    async fn forward_flushes_held_text_before_done() {
+        let (in_tx, in_rx) = mpsc::channel(8);
+        let (out_tx, mut out_rx) = mpsc::channel(8);
+        let task = tokio::spawn(forward_stripped(in_rx, out_tx, think().into()));
+        for delta in ["<think>x</think>ok <", "3"] {
+            in_tx
+                .send(StreamChunk::Content(delta.into()))
+                .await
+                .unwrap();
+        }
+        in_tx
+            .send(StreamChunk::Done {
+                usage: None,
+                timing: None,
+                error: None,
+                stopped: false,
+            })
+            .await
+            .unwrap();
+        drop(in_tx);
+        task.await.unwrap();
+
+        let mut text = String::new();
+        while let Some(chunk) = out_rx.recv().await {
+            match chunk {
+                StreamChunk::Content(delta) => text.push_str(&delta),
+                StreamChunk::Done { .. } => break,
+                StreamChunk::Thinking(_) => {}
+            }
+        }
+        assert_eq!(text, "ok <3");
+    }
+}
@@ -0,0 +1,56 @@
+//! End-to-end test for `araliya-bot --pipe`: one stdin message in, the echo
+//! agent's reply out on stdout.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn pipe_prints_reply_and_exits() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("pipe.toml");
+    std::fs::write(
+        &config,
+        format!(
+            r#"
+[supervisor]
+bot_name = "pipe-test"
+work_dir = "{}"
+log_level = "error"
+
+[agents]
+default = "echo"
+"#,
+            dir.path().display()
+        ),
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_araliya-bot"))
+        .arg("--pipe")
+        .arg("-f")
+        .arg(&config)
+        .current_dir(dir.path())
+        .env("HOME", dir.path())
+        .env("XDG_DATA_HOME", dir.path().join("data"))
+        .env_remove("ARALIYA_WORK_DIR")
+        .env_remove("ARALIYA_LOG_LEVEL")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"hello from a pipe\n")
+        .unwrap();
+
+    let out = child.wait_with_output().unwrap();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "hello from a pipe\n");
+}
@@ -3,7 +3,7 @@
 use std::time::Duration;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, RawQuery, State},
     http::StatusCode,
     response::{
         sse::{Event, KeepAlive, Sse},
@@ -19,9 +19,10 @@ use serde_json::json;
 use tokio_stream::StreamExt;
 use tracing::warn;
 
-use araliya_core::types::llm::StreamChunk;
+use araliya_core::types::llm::{ResponseFormat, StreamChunk};
 
 use super::AxumState;
+use crate::state::CommsEvent;
 
 const NO_SESSION_ID: &str = "00000000-0000-0000-0000-000000000000";
 
@@ -33,6 +34,14 @@ pub(super) struct MessageRequest {
     session_id: Option<String>,
     agent_id: Option<String>,
     mode: Option<String>,
+    /// `"json"` for a reply that is a single valid JSON value.
+    #[serde(default)]
+    response_format: Option<ResponseFormat>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct StopStreamRequest {
+    request_id: String,
 }
 
 #[derive(Deserialize)]
@@ -48,14 +57,35 @@ fn json_error(code: &str, msg: impl std::fmt::Display) -> Json<serde_json::Value
 
 // ── Handlers ──────────────────────────────────────────────────────────────────
 
-pub(super) async fn health(State(state): State<AxumState>) -> Response {
-    match tokio::time::timeout(Duration::from_secs(3), state.comms.management_http_get()).await {
-        Ok(Ok(body)) => (
-            StatusCode::OK,
-            [(axum::http::header::CONTENT_TYPE, "application/json")],
-            body,
-        )
-            .into_response(),
+/// A JSON answer for a cacheable GET route, with its cache headers.
+fn cached_json(cached: crate::cache::CachedResponse) -> Response {
+    let headers = cached.headers();
+    let mut response = (
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        cached.body,
+    )
+        .into_response();
+    for (name, value) in headers {
+        if let Ok(value) = axum::http::HeaderValue::from_str(&value) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+fn refresh_requested(query: Option<String>) -> bool {
+    crate::http::query_flag(query.as_deref().unwrap_or(""), "refresh")
+}
+
+pub(super) async fn health(State(state): State<AxumState>, RawQuery(query): RawQuery) -> Response {
+    let fetch = state.comms.cached_get(
+        "/api/health",
+        refresh_requested(query),
+        state.comms.management_http_get(),
+    );
+    match tokio::time::timeout(Duration::from_secs(3), fetch).await {
+        Ok(Ok(cached)) => cached_json(cached),
         Ok(Err(e)) => {
             warn!(channel_id = %state.channel_id, "management health request failed: {e}");
             (StatusCode::BAD_GATEWAY, "management adapter error\n").into_response()
@@ -91,20 +121,44 @@ pub(super) async fn health_refresh(State(state): State<AxumState>) -> Response {
     }
 }
 
-pub(super) async fn tree(State(state): State<AxumState>) -> Response {
-    match tokio::time::timeout(Duration::from_secs(3), state.comms.management_http_tree()).await {
-        Ok(Ok(body)) => (
+pub(super) async fn tree(State(state): State<AxumState>, RawQuery(query): RawQuery) -> Response {
+    let fetch = state.comms.cached_get(
+        "/api/tree",
+        refresh_requested(query),
+        state.comms.management_http_tree(),
+    );
+    match tokio::time::timeout(Duration::from_secs(3), fetch).await {
+        Ok(Ok(cached)) => cached_json(cached),
+        Ok(Err(e)) => {
+            warn!(channel_id = %state.channel_id, "management tree request failed: {e}");
+            (StatusCode::BAD_GATEWAY, "management adapter error\n").into_response()
+        }
+        Err(_) => {
+            warn!(channel_id = %state.channel_id, "management tree request timed out");
+            (StatusCode::GATEWAY_TIMEOUT, "management adapter timeout\n").into_response()
+        }
+    }
+}
+
+pub(super) async fn openapi() -> Response {
+    axum::Json(crate::http::openapi::document()).into_response()
+}
+
+pub(super) async fn config(State(state): State<AxumState>) -> Response {
+    match tokio::time::timeout(Duration::from_secs(3), state.comms.management_config()).await {
+        Ok(Ok(Some(body))) => (
             StatusCode::OK,
             [(axum::http::header::CONTENT_TYPE, "application/json")],
             body,
         )
             .into_response(),
+        Ok(Ok(None)) => (StatusCode::FORBIDDEN, "forbidden\n").into_response(),
         Ok(Err(e)) => {
-            warn!(channel_id = %state.channel_id, "management tree request failed: {e}");
+            warn!(channel_id = %state.channel_id, "management config request failed: {e}");
             (StatusCode::BAD_GATEWAY, "management adapter error\n").into_response()
         }
         Err(_) => {
-            warn!(channel_id = %state.channel_id, "management tree request timed out");
+            warn!(channel_id = %state.channel_id, "management config request timed out");
             (StatusCode::GATEWAY_TIMEOUT, "management adapter timeout\n").into_response()
         }
     }
@@ -114,6 +168,7 @@ pub(super) async fn message(
     State(state): State<AxumState>,
     Json(req): Json<MessageRequest>,
 ) -> Response {
+    let _session = state.comms.session_scope(&state.channel_id);
     let session_id = req
         .session_id
         .as_deref()
@@ -122,11 +177,12 @@ pub(super) async fn message(
 
     match tokio::time::timeout(
         Duration::from_secs(120),
-        state.comms.send_message(
+        state.comms.send_message_as(
             &state.channel_id,
             req.message.clone(),
             session_id,
             req.agent_id.clone(),
+            req.response_format,
         ),
     )
     .await
@@ -167,25 +223,33 @@ pub(super) async fn message_stream(
     Json(req): Json<MessageRequest>,
 ) -> Response {
     let channel_id = state.channel_id.to_string();
+    // Ends with the SSE stream (or when the client goes away).
+    let session = state.comms.session_scope(&channel_id);
     let session_id = req
         .session_id
         .as_deref()
         .filter(|s| !s.is_empty() && *s != NO_SESSION_ID)
         .map(ToString::to_string);
 
-    let rx = match state
+    let (rx, request_id) = match state
         .comms
-        .stream_via_agent(&channel_id, req.message, session_id, req.agent_id.clone())
+        .stream_via_agent(
+            &channel_id,
+            req.message,
+            session_id,
+            req.agent_id.clone(),
+            req.response_format,
+        )
         .await
     {
-        Ok(rx) => rx,
+        Ok(stream) => stream,
         Err(e) => {
             warn!(%channel_id, "stream_via_agent failed: {e}");
             return (StatusCode::BAD_GATEWAY, json_error("internal", e)).into_response();
         }
     };
 
-    let event_stream = stream::unfold(rx, |mut rx| async move {
+    let event_stream = stream::unfold((rx, session), |(mut rx, session)| async move {
         let chunk = rx.recv().await?;
         let event: Result<Event, Infallible> = Ok(match chunk {
             StreamChunk::Thinking(delta) => {
@@ -196,7 +260,12 @@ pub(super) async fn message_stream(
                 let data = json!({"delta": delta}).to_string();
                 Event::default().event("content").data(data)
             }
-            StreamChunk::Done { usage, timing } => {
+            StreamChunk::Done {
+                usage,
+                timing,
+                error,
+                stopped,
+            } => {
                 let data = json!({
                     "usage": usage.map(|u| json!({
                         "prompt_tokens": u.input_tokens,
@@ -207,20 +276,65 @@ pub(super) async fn message_stream(
                     "timing": timing.map(|t| json!({
                         "ttft_ms": t.ttft_ms,
                         "total_ms": t.total_ms,
-                    }))
+                    })),
+                    "error": error,
+                    "stopped": stopped,
                 })
                 .to_string();
                 Event::default().event("done").data(data)
             }
         });
-        Some((event, rx))
+        Some((event, (rx, session)))
     });
 
-    Sse::new(event_stream).into_response()
+    // A stoppable stream opens with its id, for `/api/message/stream/stop`.
+    let start = stream::iter(request_id.map(|id| {
+        let data = json!({ "request_id": id }).to_string();
+        Ok::<_, Infallible>(Event::default().event("start").data(data))
+    }));
+
+    // Keep-alive comments count as traffic for `[comms] idle_timeout_seconds`
+    // while the agent is still thinking.
+    Sse::new(start.chain(event_stream))
+        .keep_alive(KeepAlive::default())
+        .into_response()
 }
 
-pub(super) async fn sessions(State(state): State<AxumState>) -> Response {
-    match tokio::time::timeout(Duration::from_secs(10), state.comms.request_sessions()).await {
+pub(super) async fn message_stream_stop(
+    State(state): State<AxumState>,
+    Json(req): Json<StopStreamRequest>,
+) -> Response {
+    match state.comms.stop_stream(&req.request_id).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(json!({ "ok": true, "request_id": req.request_id })),
+        )
+            .into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            json_error("not_found", "no stream in flight with that request_id"),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(channel_id = %state.channel_id, "stream stop failed: {e}");
+            (StatusCode::BAD_GATEWAY, json_error("internal", e)).into_response()
+        }
+    }
+}
+
+pub(super) async fn sessions(
+    State(state): State<AxumState>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let query = query.as_deref().unwrap_or("");
+    let include_archived = crate::http::query_flag(query, "include_archived");
+    let sort = crate::http::query_param(query, "sort");
+    match tokio::time::timeout(
+        Duration::from_secs(10),
+        state.comms.request_sessions(include_archived, sort),
+    )
+    .await
+    {
         Ok(Ok(data)) => (
             StatusCode::OK,
             [(axum::http::header::CONTENT_TYPE, "application/json")],
@@ -239,14 +353,14 @@ pub(super) async fn sessions(State(state): State<AxumState>) -> Response {
     }
 }
 
-pub(super) async fn agents(State(state): State<AxumState>) -> Response {
-    match tokio::time::timeout(Duration::from_secs(10), state.comms.request_agents()).await {
-        Ok(Ok(data)) => (
-            StatusCode::OK,
-            [(axum::http::header::CONTENT_TYPE, "application/json")],
-            data,
-        )
-            .into_response(),
+pub(super) async fn agents(State(state): State<AxumState>, RawQuery(query): RawQuery) -> Response {
+    let fetch = state.comms.cached_get(
+        "/api/agents",
+        refresh_requested(query),
+        state.comms.request_agents(),
+    );
+    match tokio::time::timeout(Duration::from_secs(10), fetch).await {
+        Ok(Ok(cached)) => cached_json(cached),
         Ok(Err(e)) => {
             warn!(channel_id = %state.channel_id, "agents request failed: {e}");
             (StatusCode::BAD_GATEWAY, json_error("internal", e)).into_response()
@@ -596,7 +710,9 @@ pub(super) async fn observe_events(State(state): State<AxumState>) -> impl IntoR
     };
 
     let rx = obs_bus.subscribe();
-    let stream = tokio_stream::wrappers::BroadcastStream::new(rx).filter_map(|r| match r {
+    let stream = tokio_stream::wrappers::BroadcastStream::new(rx);
+    let stream = futures_util::StreamExt::take_until(stream, state.shutdown.cancelled_owned());
+    let stream = stream.filter_map(|r| match r {
         Ok(event) => serde_json::to_string(&event)
             .ok()
             .map(|data| Ok::<Event, Infallible>(Event::default().data(data))),
@@ -613,6 +729,81 @@ pub(super) async fn observe_events(State(state): State<AxumState>) -> impl IntoR
         .into_response()
 }
 
+/// `GET /api/events` — Server-Sent Events stream of comms lifecycle events
+/// (session started/ended, channel shutdown, the shutdown message), for live
+/// connection dashboards.  `?session_id=<id>` adds the agent steps of that
+/// one session, for a chat's live trace; steps are never sent unasked.
+///
+/// Each event is a JSON-serialized [`crate::state::CommsEvent`]; overflow is
+/// reported as `event: lagged` with `{"skipped": N}`, as for `/api/observe/events`.
+/// The stream ends when the channel shuts down, after the events already
+/// queued — the shutdown message among them.
+pub(super) async fn comms_events(
+    State(state): State<AxumState>,
+    RawQuery(query): RawQuery,
+) -> impl IntoResponse {
+    let session_id = crate::http::query_param(query.as_deref().unwrap_or(""), "session_id")
+        .map(ToString::to_string);
+    let rx = state.comms.subscribe_events();
+    let stream = events_until(rx, state.shutdown.clone()).filter_map(move |r| match r {
+        Ok(event) if !delivered_to(session_id.as_deref(), &event) => None,
+        Ok(event) => serde_json::to_string(&event)
+            .ok()
+            .map(|data| Ok::<Event, Infallible>(Event::default().data(data))),
+        Err(n) => Some(Ok(Event::default()
+            .event("lagged")
+            .data(format!(r#"{{"skipped":{n}}}"#)))),
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Events from `rx` until `shutdown` fires, then those already queued.
+/// `Err(n)` reports `n` events skipped by a lagging receiver.
+fn events_until(
+    rx: tokio::sync::broadcast::Receiver<CommsEvent>,
+    shutdown: tokio_util::sync::CancellationToken,
+) -> impl tokio_stream::Stream<Item = Result<CommsEvent, u64>> {
+    use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+    stream::unfold((rx, shutdown), |(mut rx, shutdown)| async move {
+        let next = if shutdown.is_cancelled() {
+            match rx.try_recv() {
+                Ok(event) => Ok(event),
+                Err(TryRecvError::Lagged(n)) => Err(n),
+                Err(_) => return None,
+            }
+        } else {
+            tokio::select! {
+                biased;
+                r = rx.recv() => match r {
+                    Ok(event) => Ok(event),
+                    Err(RecvError::Lagged(n)) => Err(n),
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = shutdown.cancelled() => match rx.try_recv() {
+                    Ok(event) => Ok(event),
+                    Err(TryRecvError::Lagged(n)) => Err(n),
+                    Err(_) => return None,
+                },
+            }
+        };
+        Some((next, (rx, shutdown)))
+    })
+}
+
+/// Whether `/api/events` sends `event` to a subscriber watching
+/// `session_id`: lifecycle events always, agent steps only for that session.
+fn delivered_to(session_id: Option<&str>, event: &CommsEvent) -> bool {
+    match event {
+        CommsEvent::AgentStep {
+            session_id: step_session,
+            ..
+        } => session_id.is_some() && step_session.as_deref() == session_id,
+        _ => true,
+    }
+}
+
 // ── Notes routes ─────────────────────────────────────────────────────────────
 
 /// `GET /notes/` — list all markdown files in notes_dir.
@@ -736,3 +927,107 @@ fn render_markdown(md: &str) -> String {
     html::push_html(&mut html_out, parser);
     html_out
 }
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use araliya_core::bus::{BusMessage, BusPayload, SupervisorBus};
+
+    use super::*;
+    use crate::state::CommsState;
+
+    #[test]
+    fn agent_steps_go_only_to_their_session_subscriber() {
+        let step = CommsEvent::AgentStep {
+            channel_id: "axum0".into(),
+            session_id: Some("s1".into()),
+            step: "searching".into(),
+        };
+        assert!(delivered_to(Some("s1"), &step));
+        assert!(!delivered_to(Some("s2"), &step));
+        assert!(!delivered_to(None, &step));
+        let started = CommsEvent::SessionStarted {
+            channel_id: "axum0".into(),
+        };
+        assert!(delivered_to(None, &started));
+        assert!(delivered_to(Some("s2"), &started));
+    }
+
+    #[tokio::test]
+    async fn message_reports_session_events_to_subscribers() {
+        let mut bus = SupervisorBus::new(4);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(8);
+        let comms = Arc::new(CommsState::new(bus.handle.clone(), ev_tx));
+        let mut events = comms.subscribe_events();
+
+        tokio::spawn(async move {
+            if let Some(BusMessage::Request {
+                payload: BusPayload::CommsMessage { content, .. },
+                reply_tx,
+                ..
+            }) = bus.rx.recv().await
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id: "axum0".into(),
+                    content,
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
+        let state = AxumState {
+            channel_id: Arc::from("axum0"),
+            comms,
+            ui: None,
+            obs_bus: None,
+            #[cfg(any(feature = "plugin-homebuilder", feature = "plugin-webbuilder"))]
+            preview_root: None,
+            #[cfg(feature = "plugin-homebuilder")]
+            notes_dir: None,
+            shutdown: tokio_util::sync::CancellationToken::new(),
+        };
+        let req: MessageRequest = serde_json::from_value(json!({ "message": "hi" })).unwrap();
+        let response = message(State(state), Json(req)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        match events.recv().await.unwrap() {
+            CommsEvent::SessionStarted { channel_id } => assert_eq!(channel_id, "axum0"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match events.recv().await.unwrap() {
+            CommsEvent::SessionEnded { channel_id } => assert_eq!(channel_id, "axum0"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn event_stream_ends_at_shutdown_after_the_shutdown_message() {
+        let bus = SupervisorBus::new(4);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(8);
+        let comms = Arc::new(CommsState::new(bus.handle.clone(), ev_tx));
+        let rx = comms.subscribe_events();
+        let shutdown = tokio_util::sync::CancellationToken::new();
+
+        comms.report_event(CommsEvent::ShuttingDown {
+            message: "back soon".into(),
+        });
+        shutdown.cancel();
+
+        let events: Vec<_> = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            events_until(rx, shutdown).collect::<Vec<_>>(),
+        )
+        .await
+        .expect("the stream ends once shutdown fires");
+        assert!(
+            matches!(&events[..], [Ok(CommsEvent::ShuttingDown { message })] if message == "back soon"),
+            "{events:?}"
+        );
+    }
+}
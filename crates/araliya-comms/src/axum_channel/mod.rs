@@ -7,9 +7,13 @@ mod preview;
 mod ui;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
-    http::StatusCode,
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::{self, Next},
+    response::Response,
     routing::{get, post},
     Router,
 };
@@ -23,6 +27,7 @@ use araliya_core::runtime::{Component, ComponentFuture};
 #[cfg(feature = "subsystem-ui")]
 use araliya_core::ui::UiServeHandle;
 
+use crate::idle::{self, IdleListener, InFlight};
 use crate::state::CommsState;
 
 // ── Type alias (mirrors http/mod.rs pattern) ──────────────────────────────────
@@ -45,6 +50,9 @@ pub(crate) struct AxumState {
     pub preview_root: Option<std::path::PathBuf>,
     #[cfg(feature = "plugin-homebuilder")]
     pub notes_dir: Option<std::path::PathBuf>,
+    /// The channel's shutdown token; event streams end when it fires, or
+    /// graceful shutdown would wait on them forever.
+    pub shutdown: CancellationToken,
 }
 
 // ── AxumChannel ───────────────────────────────────────────────────────────────
@@ -59,6 +67,7 @@ pub struct AxumChannel {
     preview_root: Option<std::path::PathBuf>,
     #[cfg(feature = "plugin-homebuilder")]
     notes_dir: Option<std::path::PathBuf>,
+    idle_timeout: Option<Duration>,
 }
 
 impl AxumChannel {
@@ -82,8 +91,16 @@ impl AxumChannel {
             preview_root,
             #[cfg(feature = "plugin-homebuilder")]
             notes_dir,
+            idle_timeout: None,
         }
     }
+
+    /// Close connections that move no bytes for `secs` seconds
+    /// (`[comms] idle_timeout_seconds`).  `None` keeps them open.
+    pub fn with_idle_timeout_secs(mut self, secs: Option<u64>) -> Self {
+        self.idle_timeout = secs.map(Duration::from_secs);
+        self
+    }
 }
 
 impl Component for AxumChannel {
@@ -102,6 +119,7 @@ impl Component for AxumChannel {
             self.preview_root,
             #[cfg(feature = "plugin-homebuilder")]
             self.notes_dir,
+            self.idle_timeout,
             shutdown,
         ))
     }
@@ -120,6 +138,7 @@ async fn run_axum(
         std::path::PathBuf,
     >,
     #[cfg(feature = "plugin-homebuilder")] notes_dir: Option<std::path::PathBuf>,
+    idle_timeout: Option<Duration>,
     shutdown: CancellationToken,
 ) -> Result<(), AppError> {
     let axum_state = AxumState {
@@ -131,6 +150,7 @@ async fn run_axum(
         preview_root,
         #[cfg(feature = "plugin-homebuilder")]
         notes_dir,
+        shutdown: shutdown.clone(),
     };
 
     let router = build_router(axum_state);
@@ -141,10 +161,13 @@ async fn run_axum(
 
     info!(%channel_id, %bind_addr, "axum channel listening");
 
-    axum::serve(listener, router)
-        .with_graceful_shutdown(async move { shutdown.cancelled().await })
-        .await
-        .map_err(|e| AppError::Comms(format!("axum server error: {e}")))?;
+    axum::serve(
+        IdleListener::new(listener, idle_timeout),
+        router.into_make_service_with_connect_info::<InFlight>(),
+    )
+    .with_graceful_shutdown(async move { shutdown.cancelled().await })
+    .await
+    .map_err(|e| AppError::Comms(format!("axum server error: {e}")))?;
 
     info!(%channel_id, "axum channel shut down");
     Ok(())
@@ -157,11 +180,15 @@ fn build_router(state: AxumState) -> Router {
         .route("/api/health", get(api::health))
         .route("/api/health/refresh", post(api::health_refresh))
         .route("/api/tree", get(api::tree))
+        .route("/api/config", get(api::config))
+        .route(crate::http::openapi::OPENAPI_PATH, get(api::openapi))
+        .route("/api/events", get(api::comms_events))
         .route("/api/observe/events", get(api::observe_events))
         .route("/api/observe/snapshot", get(api::observe_snapshot))
         .route("/api/observe/clear", post(api::observe_clear))
         .route("/api/message", post(api::message))
         .route("/api/message/stream", post(api::message_stream))
+        .route("/api/message/stream/stop", post(api::message_stream_stop))
         .route("/api/sessions", get(api::sessions))
         .route("/api/agents", get(api::agents))
         .route("/api/llm/providers", get(api::llm_providers))
@@ -220,5 +247,23 @@ fn build_router(state: AxumState) -> Router {
     // Catch-all MUST be last so specific routes above take precedence.
     let router = router.route("/{*path}", get(ui::serve_path));
 
-    router.with_state(state)
+    router
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            invalidate_cache_on_write,
+        ))
+        .layer(middleware::from_fn(idle::hold_while_in_flight))
+        .with_state(state)
+}
+
+/// Any non-GET request may change what the cached GET routes report.
+async fn invalidate_cache_on_write(
+    State(state): State<AxumState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        state.comms.invalidate_response_cache();
+    }
+    next.run(request).await
 }
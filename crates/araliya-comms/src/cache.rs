@@ -0,0 +1,83 @@
+//! Response cache for read-only JSON routes (`[comms.http] cache_ttl_ms`).
+//!
+//! Dashboards poll `/api/tree`, `/api/health` and `/api/agents` far more
+//! often than their answers change.  [`ResponseCache`] keeps the last body
+//! per route for that route's TTL, so repeated polls cost no bus request.
+//! Both the HTTP and axum channels share the one on [`crate::CommsState`].
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// A JSON body for a GET route, and how it relates to the cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub body: String,
+    /// How long the route is cached; `None` when it is not.
+    pub ttl: Option<Duration>,
+    /// Time since the body was fetched, when it came from the cache.
+    pub age: Option<Duration>,
+}
+
+impl CachedResponse {
+    /// `Cache-Control` and `Age` headers describing this response.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(ttl) = self.ttl {
+            headers.push(("Cache-Control", format!("max-age={}", ttl.as_secs())));
+        }
+        if let Some(age) = self.age {
+            headers.push(("Age", age.as_secs().to_string()));
+        }
+        headers
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ResponseCache {
+    ttls: HashMap<String, Duration>,
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttls_ms: impl IntoIterator<Item = (String, u64)>) -> Self {
+        Self {
+            ttls: ttls_ms
+                .into_iter()
+                .map(|(route, ms)| (route, Duration::from_millis(ms)))
+                .collect(),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn ttl(&self, route: &str) -> Option<Duration> {
+        self.ttls.get(route).copied()
+    }
+
+    /// The cached body for `route` and its age, if still fresh.
+    pub(crate) fn get(&self, route: &str) -> Option<(String, Duration)> {
+        let ttl = self.ttl(route)?;
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let (fetched, body) = entries.get(route)?;
+        let age = fetched.elapsed();
+        (age < ttl).then(|| (body.clone(), age))
+    }
+
+    pub(crate) fn put(&self, route: &str, body: &str) {
+        if self.ttls.contains_key(route) {
+            self.entries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(route.to_string(), (Instant::now(), body.to_string()));
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
@@ -0,0 +1,206 @@
+//! Minimal IMAP4rev1 client: log in, select a mailbox, fetch unseen
+//! messages and flag them `\Seen`.  Generic over the stream so tests can
+//! script the server side.
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use araliya_core::error::AppError;
+
+/// One untagged server response; `literal` holds a `{n}` payload, if any.
+#[derive(Debug)]
+struct Response {
+    text: String,
+    literal: Option<Vec<u8>>,
+}
+
+pub(super) struct ImapSession<S> {
+    stream: BufReader<S>,
+    next_tag: u32,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    /// Wrap a connected stream and consume the server greeting.
+    pub async fn start(stream: S) -> Result<Self, AppError> {
+        let mut session = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+        };
+        let greeting = session.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(imap_error(format!("unexpected greeting: {greeting}")));
+        }
+        Ok(session)
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<(), AppError> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .await
+            .map(drop)
+    }
+
+    pub async fn select(&mut self, mailbox: &str) -> Result<(), AppError> {
+        self.command(&format!("SELECT {}", quote(mailbox)))
+            .await
+            .map(drop)
+    }
+
+    /// UIDs of messages without the `\Seen` flag.
+    pub async fn search_unseen(&mut self) -> Result<Vec<u32>, AppError> {
+        let responses = self.command("UID SEARCH UNSEEN").await?;
+        Ok(responses
+            .iter()
+            .filter_map(|r| r.text.strip_prefix("* SEARCH"))
+            .flat_map(|ids| ids.split_whitespace().filter_map(|id| id.parse().ok()))
+            .collect())
+    }
+
+    /// The full raw message for `uid`, without setting `\Seen`.
+    pub async fn fetch(&mut self, uid: u32) -> Result<Vec<u8>, AppError> {
+        self.command(&format!("UID FETCH {uid} BODY.PEEK[]"))
+            .await?
+            .into_iter()
+            .find_map(|r| r.literal)
+            .ok_or_else(|| imap_error(format!("no body returned for uid {uid}")))
+    }
+
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<(), AppError> {
+        self.command(&format!("UID STORE {uid} +FLAGS (\\Seen)"))
+            .await
+            .map(drop)
+    }
+
+    pub async fn logout(&mut self) -> Result<(), AppError> {
+        self.command("LOGOUT").await.map(drop)
+    }
+
+    /// Send one tagged command and collect untagged responses until the
+    /// tagged completion, which must be `OK`.
+    async fn command(&mut self, command: &str) -> Result<Vec<Response>, AppError> {
+        let tag = format!("a{}", self.next_tag);
+        self.next_tag += 1;
+        let line = format!("{tag} {command}\r\n");
+        let stream = self.stream.get_mut();
+        stream.write_all(line.as_bytes()).await.map_err(io_error)?;
+        stream.flush().await.map_err(io_error)?;
+
+        let mut responses = Vec::new();
+        loop {
+            let response = self.read_response().await?;
+            if let Some(status) = response.text.strip_prefix(&format!("{tag} ")) {
+                if status.starts_with("OK") {
+                    return Ok(responses);
+                }
+                // Name the command without its arguments (LOGIN carries the password).
+                let words = if command.starts_with("UID ") { 2 } else { 1 };
+                let verb = command.split(' ').take(words).collect::<Vec<_>>().join(" ");
+                return Err(imap_error(format!("{verb} failed: {status}")));
+            }
+            responses.push(response);
+        }
+    }
+
+    /// Read one response, including a trailing `{n}` literal and the rest of
+    /// the line after it.
+    async fn read_response(&mut self) -> Result<Response, AppError> {
+        let mut text = self.read_line().await?;
+        let mut literal = None;
+        while let Some(len) = literal_len(&text) {
+            let mut buf = vec![0u8; len];
+            self.stream.read_exact(&mut buf).await.map_err(io_error)?;
+            literal = Some(buf);
+            text.push_str(&self.read_line().await?);
+        }
+        Ok(Response { text, literal })
+    }
+
+    async fn read_line(&mut self) -> Result<String, AppError> {
+        let mut line = String::new();
+        let n = self.stream.read_line(&mut line).await.map_err(io_error)?;
+        if n == 0 {
+            return Err(imap_error("connection closed".to_string()));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// `n` when `line` ends with a `{n}` literal marker.
+fn literal_len(line: &str) -> Option<usize> {
+    line.strip_suffix('}')?.rsplit_once('{')?.1.parse().ok()
+}
+
+/// An IMAP quoted string.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    imap_error(e.to_string())
+}
+
+fn imap_error(message: String) -> AppError {
+    AppError::Comms(format!("imap: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Play `script` as the server: for each (expected command, reply) pair,
+    /// read one line, check it, then write the reply.
+    async fn serve(server: tokio::io::DuplexStream, greeting: &str, script: Vec<(&str, String)>) {
+        let (read, mut write) = tokio::io::split(server);
+        let mut read = BufReader::new(read);
+        write.write_all(greeting.as_bytes()).await.unwrap();
+        for (expected, reply) in script {
+            let mut line = String::new();
+            read.read_line(&mut line).await.unwrap();
+            assert_eq!(line.trim_end(), expected);
+            write.write_all(reply.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn fetches_unseen_messages_and_flags_them() {
+        let message = "From: alice@example.com\r\n\r\nhello\r\n";
+        let (client, server) = tokio::io::duplex(4096);
+        let server = tokio::spawn(serve(
+            server,
+            "* OK IMAP ready\r\n",
+            vec![
+                (
+                    "a1 LOGIN \"bot@example.com\" \"pa\\\"ss\"",
+                    "a1 OK logged in\r\n".into(),
+                ),
+                (
+                    "a2 SELECT \"INBOX\"",
+                    "* 3 EXISTS\r\na2 OK [READ-WRITE] done\r\n".into(),
+                ),
+                (
+                    "a3 UID SEARCH UNSEEN",
+                    "* SEARCH 7\r\na3 OK done\r\n".into(),
+                ),
+                (
+                    "a4 UID FETCH 7 BODY.PEEK[]",
+                    format!(
+                        "* 1 FETCH (UID 7 BODY[] {{{}}}\r\n{message})\r\na4 OK done\r\n",
+                        message.len()
+                    ),
+                ),
+                (
+                    "a5 UID STORE 7 +FLAGS (\\Seen)",
+                    "a5 NO read-only\r\n".into(),
+                ),
+            ],
+        ));
+
+        let mut imap = ImapSession::start(client).await.unwrap();
+        imap.login("bot@example.com", "pa\"ss").await.unwrap();
+        imap.select("INBOX").await.unwrap();
+        let uids = imap.search_unseen().await.unwrap();
+        assert_eq!(uids, [7]);
+        assert_eq!(imap.fetch(7).await.unwrap(), message.as_bytes());
+        let err = imap.mark_seen(7).await.unwrap_err().to_string();
+        assert!(err.contains("UID STORE failed: NO read-only"), "got: {err}");
+        server.await.unwrap();
+    }
+}
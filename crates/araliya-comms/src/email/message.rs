@@ -0,0 +1,369 @@
+//! Just enough RFC 5322 / MIME to read a plain-text question out of an
+//! inbound email and to write a threaded plain-text reply.
+
+use base64::Engine as _;
+
+const B64: base64::engine::GeneralPurpose = base64::engine::general_purpose::STANDARD;
+
+/// The parts of an inbound email the channel acts on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboundMail {
+    /// Bare, lowercased sender address.
+    pub from: String,
+    pub subject: String,
+    pub message_id: Option<String>,
+    /// `References` ids, oldest first.
+    pub references: Vec<String>,
+    /// First `text/plain` part, decoded, with the quoted history removed.
+    pub body: String,
+    /// Set for auto-replies and bulk mail (`Auto-Submitted`, `Precedence`),
+    /// which the channel must not answer.
+    pub automated: bool,
+}
+
+/// A plain-text reply, threaded onto the mail it answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutgoingMail {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub body: String,
+}
+
+impl InboundMail {
+    /// Parse a raw message.  `None` when there is no usable `From` address.
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        let raw = String::from_utf8_lossy(raw);
+        let (headers, body) = split_entity(&raw);
+        let from = address(header(&headers, "from")?)?;
+        let automated = header(&headers, "auto-submitted")
+            .is_some_and(|v| !v.eq_ignore_ascii_case("no"))
+            || header(&headers, "precedence").is_some_and(|v| {
+                matches!(v.to_ascii_lowercase().as_str(), "bulk" | "list" | "junk")
+            });
+        Some(Self {
+            from,
+            subject: header(&headers, "subject")
+                .map(decode_words)
+                .unwrap_or_default(),
+            message_id: header(&headers, "message-id").and_then(|v| msg_ids(v).into_iter().next()),
+            references: header(&headers, "references")
+                .map(msg_ids)
+                .unwrap_or_default(),
+            body: strip_quoted(&text_body(&headers, body).unwrap_or_default()),
+            automated,
+        })
+    }
+}
+
+impl OutgoingMail {
+    /// A reply to `mail` from `from`, in the same thread.
+    pub fn reply_to(mail: &InboundMail, from: &str, body: String) -> Self {
+        let subject = if mail
+            .subject
+            .get(..3)
+            .is_some_and(|p| p.eq_ignore_ascii_case("re:"))
+        {
+            mail.subject.clone()
+        } else if mail.subject.is_empty() {
+            "Re: your message".to_string()
+        } else {
+            format!("Re: {}", mail.subject)
+        };
+        let mut references = mail.references.clone();
+        references.extend(mail.message_id.clone());
+        Self {
+            from: from.to_string(),
+            to: mail.from.clone(),
+            subject,
+            in_reply_to: mail.message_id.clone(),
+            references,
+            body,
+        }
+    }
+
+    /// Render as a CRLF-terminated RFC 5322 message.  `date` is an RFC 2822
+    /// timestamp and `message_id` includes its angle brackets.
+    pub fn render(&self, date: &str, message_id: &str) -> String {
+        let mut out = String::new();
+        let mut push = |name: &str, value: &str| {
+            out.push_str(name);
+            out.push_str(": ");
+            out.push_str(value);
+            out.push_str("\r\n");
+        };
+        push("From", &self.from);
+        push("To", &self.to);
+        push("Subject", &encode_word(&self.subject));
+        push("Date", date);
+        push("Message-ID", message_id);
+        if let Some(id) = &self.in_reply_to {
+            push("In-Reply-To", id);
+        }
+        if !self.references.is_empty() {
+            push("References", &self.references.join(" "));
+        }
+        push("Auto-Submitted", "auto-replied");
+        push("MIME-Version", "1.0");
+        push("Content-Type", "text/plain; charset=utf-8");
+        push("Content-Transfer-Encoding", "base64");
+        out.push_str("\r\n");
+        let encoded = B64.encode(self.body.as_bytes());
+        for line in encoded.as_bytes().chunks(76) {
+            out.push_str(std::str::from_utf8(line).unwrap_or_default());
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+// ── Parsing helpers ──────────────────────────────────────────────────────────
+
+/// Split a MIME entity into unfolded `(name, value)` headers and its body.
+fn split_entity(raw: &str) -> (Vec<(String, String)>, &str) {
+    let (head, body) = match (raw.find("\r\n\r\n"), raw.find("\n\n")) {
+        (Some(crlf), Some(lf)) if lf < crlf => (&raw[..lf], &raw[lf + 2..]),
+        (Some(crlf), _) => (&raw[..crlf], &raw[crlf + 4..]),
+        (None, Some(lf)) => (&raw[..lf], &raw[lf + 2..]),
+        (None, None) => (raw, ""),
+    };
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// `"Alice <Alice@Example.com>"` → `alice@example.com`.
+fn address(value: &str) -> Option<String> {
+    let addr = match (value.rfind('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &value[start + 1..end],
+        _ => value,
+    };
+    let addr = addr.trim();
+    addr.contains('@').then(|| addr.to_ascii_lowercase())
+}
+
+/// Every `<...>` id in a `Message-ID` / `References` value.
+fn msg_ids(value: &str) -> Vec<String> {
+    value
+        .split('<')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('>').map(|(id, _)| format!("<{id}>")))
+        .collect()
+}
+
+/// A `name=value` parameter of a structured header such as `Content-Type`.
+fn param<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';').skip(1).find_map(|p| {
+        let (k, v) = p.split_once('=')?;
+        k.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| v.trim().trim_matches('"'))
+    })
+}
+
+/// The decoded first `text/plain` part of an entity.
+fn text_body(headers: &[(String, String)], body: &str) -> Option<String> {
+    let content_type = header(headers, "content-type").unwrap_or("text/plain");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if mime.starts_with("multipart/") {
+        let delimiter = format!("--{}", param(content_type, "boundary")?);
+        return body
+            .split(delimiter.as_str())
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .find_map(|part| {
+                let part = part.trim_start_matches(['\r', '\n']);
+                let (headers, body) = split_entity(part);
+                text_body(&headers, body)
+            });
+    }
+    if mime != "text/plain" {
+        return None;
+    }
+    let encoding = header(headers, "content-transfer-encoding")
+        .unwrap_or("7bit")
+        .to_ascii_lowercase();
+    Some(match encoding.as_str() {
+        "base64" => {
+            let compact: String = body.chars().filter(|c| !c.is_whitespace()).collect();
+            let bytes = B64.decode(compact).ok()?;
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_string(),
+    })
+}
+
+fn decode_quoted_printable(body: &str) -> String {
+    let mut bytes = Vec::with_capacity(body.len());
+    for line in body.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let (line, soft_break) = match line.strip_suffix('=') {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        let raw = line.as_bytes();
+        let mut i = 0;
+        while i < raw.len() {
+            let hex = raw
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+            match (raw[i], hex) {
+                (b'=', Some(byte)) => {
+                    bytes.push(byte);
+                    i += 3;
+                }
+                (b, _) => {
+                    bytes.push(b);
+                    i += 1;
+                }
+            }
+        }
+        if !soft_break {
+            bytes.push(b'\n');
+        }
+    }
+    if body.ends_with('\n') {
+        bytes.pop();
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Decode RFC 2047 `=?charset?B|Q?text?=` words (charset assumed UTF-8).
+fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_word = false;
+    while let Some(start) = rest.find("=?") {
+        let Some((word, len)) = encoded_word(&rest[start..]) else {
+            break;
+        };
+        // Whitespace between adjacent encoded words is not part of the text.
+        let between = &rest[..start];
+        if !(after_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&word);
+        after_word = true;
+        rest = &rest[start + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decode the encoded word at the start of `s`: its text and byte length.
+fn encoded_word(s: &str) -> Option<(String, usize)> {
+    let inner = s.strip_prefix("=?")?;
+    let (_charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let text = &inner[..end];
+    let word = match encoding {
+        "B" | "b" => String::from_utf8_lossy(&B64.decode(text).ok()?).into_owned(),
+        "Q" | "q" => decode_quoted_printable(&text.replace('_', " ")),
+        _ => return None,
+    };
+    Some((word, s.len() - inner.len() + end + 2))
+}
+
+/// Subject line for the wire: as-is when ASCII, one UTF-8 `B` word otherwise.
+fn encode_word(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", B64.encode(value.as_bytes()))
+    }
+}
+
+/// Drop quoted history (`> ...` lines and the `On ... wrote:` line above them).
+fn strip_quoted(body: &str) -> String {
+    let mut lines: Vec<&str> = body
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('>'))
+        .collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    if lines
+        .last()
+        .is_some_and(|l| l.trim_end().ends_with("wrote:"))
+    {
+        lines.pop();
+    }
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multipart_mail_yields_plain_text_question() {
+        let raw = "From: \"Alice\" <Alice@Example.com>\r\n\
+                   Subject: =?UTF-8?B?Q2Fmw6k=?= hours\r\n\
+                   Message-ID: <m2@example.com>\r\n\
+                   References: <m0@example.com>\r\n <m1@example.com>\r\n\
+                   Content-Type: multipart/alternative; boundary=\"b1\"\r\n\
+                   \r\n\
+                   --b1\r\n\
+                   Content-Type: text/plain; charset=utf-8\r\n\
+                   Content-Transfer-Encoding: quoted-printable\r\n\
+                   \r\n\
+                   When does the caf=C3=A9 open?\r\n\
+                   \r\n\
+                   On Mon, Bot wrote:\r\n\
+                   > Earlier answer\r\n\
+                   --b1\r\n\
+                   Content-Type: text/html\r\n\
+                   \r\n\
+                   <p>ignored</p>\r\n\
+                   --b1--\r\n";
+        let mail = InboundMail::parse(raw.as_bytes()).unwrap();
+        assert_eq!(mail.from, "alice@example.com");
+        assert_eq!(mail.subject, "Café hours");
+        assert_eq!(mail.body, "When does the café open?");
+        assert_eq!(mail.message_id.as_deref(), Some("<m2@example.com>"));
+        assert!(!mail.automated);
+
+        let reply = OutgoingMail::reply_to(&mail, "bot@example.com", "At 8.".into());
+        assert_eq!(reply.subject, "Re: Café hours");
+        let rendered = reply.render("Mon, 1 Jan 2024 00:00:00 +0000", "<r1@example.com>");
+        assert!(rendered.contains("To: alice@example.com\r\n"));
+        assert!(rendered.contains("In-Reply-To: <m2@example.com>\r\n"));
+        assert!(
+            rendered.contains("References: <m0@example.com> <m1@example.com> <m2@example.com>\r\n")
+        );
+        assert!(rendered.contains("Subject: =?UTF-8?B?"));
+        assert!(rendered.ends_with(&format!("\r\n\r\n{}\r\n", B64.encode("At 8."))));
+    }
+
+    #[test]
+    fn auto_replies_are_flagged() {
+        let raw = "From: daemon@example.com\nAuto-Submitted: auto-replied\n\nOut of office";
+        assert!(InboundMail::parse(raw.as_bytes()).unwrap().automated);
+        assert!(InboundMail::parse(b"Subject: no sender\n\nhi").is_none());
+    }
+}
@@ -0,0 +1,509 @@
+//! Email comms channel — polls an IMAP inbox, routes each new message from
+//! an allowed sender to the agents, and answers over SMTP in the same thread.
+//!
+//! Each sender is its own conversation: messages go out on channel id
+//! `email:{address}` and keep one session per sender.  A message is flagged
+//! seen only once it has been handled, so mail is never lost to a failed poll
+//! or a restart (at worst it is answered twice).  Both connections use
+//! implicit TLS; an IMAP session is opened fresh for every poll and for every
+//! message flagged seen.
+
+mod imap;
+pub mod message;
+mod smtp;
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{self, pki_types::ServerName};
+use tokio_rustls::TlsConnector;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::state::CommsState;
+use araliya_core::bus::health::HealthReporter;
+use araliya_core::config::EmailConfig;
+use araliya_core::error::AppError;
+use araliya_core::runtime::{Component, ComponentFuture};
+
+pub use message::{InboundMail, OutgoingMail};
+
+/// Channel-id prefix for email conversations (`email:{address}`).
+pub const CHANNEL_PREFIX: &str = "email:";
+
+/// Where new mail comes from.
+pub trait MailSource: Send {
+    /// Messages not yet seen, as `(uid, raw message)`.  Fetching leaves them
+    /// unseen: they are returned again until [`mark_seen`](Self::mark_seen).
+    fn fetch_unseen(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<(u32, Vec<u8>)>, AppError>> + Send;
+
+    /// Flag a handled message seen so it is not fetched again.
+    fn mark_seen(&mut self, uid: u32) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+/// Where replies go.
+pub trait MailTransport: Send + Sync {
+    fn send(&self, mail: &OutgoingMail) -> impl Future<Output = Result<(), AppError>> + Send;
+}
+
+// ── EmailChannel ─────────────────────────────────────────────────────────────
+
+pub struct EmailChannel {
+    channel_id: String,
+    state: Arc<CommsState>,
+    config: EmailConfig,
+    health: Option<HealthReporter>,
+}
+
+impl EmailChannel {
+    pub fn new(channel_id: impl Into<String>, state: Arc<CommsState>, config: EmailConfig) -> Self {
+        Self {
+            channel_id: channel_id.into(),
+            state,
+            config,
+            health: None,
+        }
+    }
+}
+
+impl Component for EmailChannel {
+    fn id(&self) -> &str {
+        &self.channel_id
+    }
+
+    fn run(self: Box<Self>, shutdown: CancellationToken) -> ComponentFuture {
+        Box::pin(async move {
+            let Some(password) = self.config.password.clone() else {
+                warn!(channel_id = %self.channel_id, "[comms.email] password not set, email channel exiting");
+                return Ok(());
+            };
+            if self.config.imap_host.is_empty() || self.config.username.is_empty() {
+                warn!(channel_id = %self.channel_id, "[comms.email] imap_host or username not set, email channel exiting");
+                return Ok(());
+            }
+            if self.config.allowed_senders.is_empty() {
+                warn!(channel_id = %self.channel_id, "[comms.email] allowed_senders is empty — no mail will be answered");
+            }
+            let source = ImapSource {
+                config: self.config.clone(),
+                password: password.clone(),
+            };
+            let transport = SmtpTransport {
+                config: self.config.clone(),
+                password,
+            };
+            run_email(
+                self.channel_id,
+                self.state,
+                self.config,
+                source,
+                transport,
+                self.health,
+                shutdown,
+            )
+            .await
+        })
+    }
+
+    /// Report whether the last inbox poll reached the IMAP server
+    /// (`connected` in the channel's health details).
+    fn attach_health(&mut self, reporter: HealthReporter) {
+        self.health = Some(reporter);
+    }
+}
+
+// ── Poll loop ────────────────────────────────────────────────────────────────
+
+async fn run_email<S: MailSource, T: MailTransport>(
+    channel_id: String,
+    state: Arc<CommsState>,
+    config: EmailConfig,
+    mut source: S,
+    transport: T,
+    health: Option<HealthReporter>,
+    shutdown: CancellationToken,
+) -> Result<(), AppError> {
+    info!(%channel_id, host = %config.imap_host, "email channel starting");
+    let mut interval = tokio::time::interval(Duration::from_secs(config.poll_interval_secs));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut connected: Option<bool> = None;
+
+    loop {
+        let fetched = tokio::select! {
+            biased;
+            _ = shutdown.cancelled() => break,
+            _ = interval.tick() => tokio::select! {
+                biased;
+                _ = shutdown.cancelled() => break,
+                fetched = source.fetch_unseen() => fetched,
+            },
+        };
+
+        let now_connected = fetched.is_ok();
+        if connected != Some(now_connected) {
+            connected = Some(now_connected);
+            if let Some(health) = &health {
+                match &fetched {
+                    Ok(_) => {
+                        health
+                            .set_healthy_with(
+                                "running",
+                                Some(serde_json::json!({ "connected": true })),
+                            )
+                            .await
+                    }
+                    Err(e) => {
+                        health
+                            .set_unhealthy_with(
+                                format!("not connected: {e}"),
+                                Some(serde_json::json!({ "connected": false })),
+                            )
+                            .await
+                    }
+                }
+            }
+        }
+
+        match fetched {
+            Ok(messages) => {
+                for (uid, raw) in messages {
+                    handle_mail(&state, &config, &transport, &raw).await;
+                    if let Err(e) = source.mark_seen(uid).await {
+                        warn!(%channel_id, uid, "failed to flag handled mail seen: {e}");
+                    }
+                }
+            }
+            Err(e) => warn!(%channel_id, "email poll failed: {e}"),
+        }
+    }
+
+    info!(%channel_id, "shutdown signal received — closing email channel");
+    Ok(())
+}
+
+/// Route one inbound message and send the agent's reply.
+async fn handle_mail<T: MailTransport>(
+    state: &CommsState,
+    config: &EmailConfig,
+    transport: &T,
+    raw: &[u8],
+) {
+    let Some(mail) = InboundMail::parse(raw) else {
+        warn!("email: skipping message without a sender address");
+        return;
+    };
+    if mail.automated || mail.from.eq_ignore_ascii_case(&config.from_address) {
+        debug!(from = %mail.from, "email: not answering automated or own mail");
+        return;
+    }
+    if !config.allowed_senders.contains(&mail.from) {
+        info!(from = %mail.from, "email: sender not in allowed_senders — ignored");
+        return;
+    }
+
+    let content = if mail.body.is_empty() {
+        mail.subject.clone()
+    } else {
+        mail.body.clone()
+    };
+    let channel_id = format!("{CHANNEL_PREFIX}{}", mail.from);
+    debug!(%channel_id, "email received message");
+
+    let _session = state.session_scope(&channel_id);
+    let reply = match state
+        .send_message_from(&channel_id, &mail.from, content, None, None)
+        .await
+    {
+        Ok(reply) if reply.reply.is_empty() => "(empty response)".to_string(),
+        Ok(reply) => reply.reply,
+        Err(e) => {
+            warn!(%channel_id, "send_message error: {e}");
+            "Internal error processing message.".to_string()
+        }
+    };
+
+    let outgoing = OutgoingMail::reply_to(&mail, &config.from_address, reply);
+    if let Err(e) = transport.send(&outgoing).await {
+        warn!(%channel_id, "failed to send email reply: {e}");
+    }
+}
+
+// ── IMAP / SMTP over TLS ─────────────────────────────────────────────────────
+
+struct ImapSource {
+    config: EmailConfig,
+    password: String,
+}
+
+impl ImapSource {
+    /// A logged-in session with the configured mailbox selected.
+    async fn open(&self) -> Result<imap::ImapSession<TlsStream<TcpStream>>, AppError> {
+        let stream = connect_tls(&self.config.imap_host, self.config.imap_port).await?;
+        let mut imap = imap::ImapSession::start(stream).await?;
+        imap.login(&self.config.username, &self.password).await?;
+        imap.select(&self.config.mailbox).await?;
+        Ok(imap)
+    }
+}
+
+impl MailSource for ImapSource {
+    async fn fetch_unseen(&mut self) -> Result<Vec<(u32, Vec<u8>)>, AppError> {
+        let mut imap = self.open().await?;
+        let mut messages = Vec::new();
+        for uid in imap.search_unseen().await? {
+            match imap.fetch(uid).await {
+                Ok(raw) => messages.push((uid, raw)),
+                // Hand over what was fetched; the rest stays unseen for the
+                // next poll.
+                Err(e) if !messages.is_empty() => {
+                    warn!(
+                        uid,
+                        "imap fetch failed, delivering the earlier messages: {e}"
+                    );
+                    return Ok(messages);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if let Err(e) = imap.logout().await {
+            debug!("imap logout: {e}");
+        }
+        Ok(messages)
+    }
+
+    async fn mark_seen(&mut self, uid: u32) -> Result<(), AppError> {
+        let mut imap = self.open().await?;
+        imap.mark_seen(uid).await?;
+        if let Err(e) = imap.logout().await {
+            debug!("imap logout: {e}");
+        }
+        Ok(())
+    }
+}
+
+struct SmtpTransport {
+    config: EmailConfig,
+    password: String,
+}
+
+impl MailTransport for SmtpTransport {
+    async fn send(&self, mail: &OutgoingMail) -> Result<(), AppError> {
+        let domain = mail
+            .from
+            .rsplit_once('@')
+            .map(|(_, d)| d)
+            .unwrap_or("localhost");
+        let now = chrono::Utc::now();
+        let message_id = format!(
+            "<{}.{}@{domain}>",
+            now.timestamp_nanos_opt().unwrap_or_default(),
+            std::process::id()
+        );
+        let message = mail.render(&now.to_rfc2822(), &message_id);
+        let stream = connect_tls(&self.config.smtp_host, self.config.smtp_port).await?;
+        smtp::submit(
+            stream,
+            domain,
+            &self.config.username,
+            &self.password,
+            &mail.from,
+            &mail.to,
+            &message,
+        )
+        .await
+    }
+}
+
+/// Open an implicit-TLS connection verified against the webpki roots.
+async fn connect_tls(host: &str, port: u16) -> Result<TlsStream<TcpStream>, AppError> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| AppError::Comms(format!("tls config: {e}")))?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|e| AppError::Comms(format!("invalid host {host:?}: {e}")))?;
+    let tcp = TcpStream::connect((host, port))
+        .await
+        .map_err(|e| AppError::Comms(format!("connect {host}:{port}: {e}")))?;
+    TlsConnector::from(Arc::new(tls))
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| AppError::Comms(format!("tls handshake with {host}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use araliya_core::bus::{BusMessage, BusPayload, SupervisorBus};
+
+    /// Keeps handing out arrived mail until it is flagged seen; one more
+    /// batch arrives per poll.
+    #[derive(Clone, Default)]
+    struct FakeInbox {
+        arriving: Arc<Mutex<Vec<Vec<Vec<u8>>>>>,
+        unseen: Arc<Mutex<Vec<(u32, Vec<u8>)>>>,
+        next_uid: u32,
+    }
+
+    impl FakeInbox {
+        fn new(batches: Vec<Vec<Vec<u8>>>) -> Self {
+            Self {
+                arriving: Arc::new(Mutex::new(batches)),
+                ..Self::default()
+            }
+        }
+    }
+
+    impl MailSource for FakeInbox {
+        async fn fetch_unseen(&mut self) -> Result<Vec<(u32, Vec<u8>)>, AppError> {
+            let mut arriving = self.arriving.lock().unwrap();
+            let mut unseen = self.unseen.lock().unwrap();
+            if !arriving.is_empty() {
+                for raw in arriving.remove(0) {
+                    self.next_uid += 1;
+                    unseen.push((self.next_uid, raw));
+                }
+            }
+            Ok(unseen.clone())
+        }
+
+        async fn mark_seen(&mut self, uid: u32) -> Result<(), AppError> {
+            self.unseen.lock().unwrap().retain(|(u, _)| *u != uid);
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeOutbox(Arc<Mutex<Vec<OutgoingMail>>>);
+
+    impl MailTransport for FakeOutbox {
+        async fn send(&self, mail: &OutgoingMail) -> Result<(), AppError> {
+            self.0.lock().unwrap().push(mail.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn polled_mail_is_routed_per_sender_and_answered_in_thread() {
+        let mut sbus = SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(8);
+        let state = Arc::new(
+            CommsState::new(sbus.handle.clone(), ev_tx).with_conversation_sessions(CHANNEL_PREFIX),
+        );
+
+        // Stand-in for the agents subsystem: opens one session per sender
+        // and echoes the question.
+        let agents = tokio::spawn(async move {
+            let mut opens = 0;
+            let mut routed = Vec::new();
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/open" => {
+                        let v: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        opens += 1;
+                        let session_id = format!("s-{}", v["channel_id"].as_str().unwrap());
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: serde_json::json!({ "session_id": session_id }).to_string(),
+                        }));
+                    }
+                    BusPayload::CommsMessage {
+                        channel_id,
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        routed.push((channel_id.clone(), session_id.clone()));
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id,
+                            content: format!("answer to: {content}"),
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                        if routed.len() == 2 {
+                            break;
+                        }
+                    }
+                    other => panic!("unexpected request {method}: {other:?}"),
+                }
+            }
+            (opens, routed)
+        });
+
+        let config = EmailConfig {
+            poll_interval_secs: 1,
+            from_address: "bot@example.com".into(),
+            allowed_senders: vec!["alice@example.com".into()],
+            ..EmailConfig::default()
+        };
+        let inbox = FakeInbox::new(vec![
+            vec![
+                b"From: Alice <alice@example.com>\r\nSubject: Hours\r\nMessage-ID: <q1@example.com>\r\n\r\nWhen do you open?\r\n".to_vec(),
+                b"From: mallory@example.com\r\nSubject: Hi\r\n\r\nLet me in\r\n".to_vec(),
+                b"From: alice@example.com\r\nAuto-Submitted: auto-replied\r\n\r\nOut of office\r\n".to_vec(),
+            ],
+            vec![b"From: ALICE@example.com\r\nSubject: Re: Hours\r\n\r\nAnd Sundays?\r\n".to_vec()],
+        ]);
+        let outbox = FakeOutbox::default();
+        let shutdown = CancellationToken::new();
+        let channel = tokio::spawn(run_email(
+            "email0".into(),
+            state.clone(),
+            config,
+            inbox.clone(),
+            outbox.clone(),
+            None,
+            shutdown.clone(),
+        ));
+
+        for _ in 0..200 {
+            if outbox.0.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        shutdown.cancel();
+        channel.await.unwrap().unwrap();
+        drop(state);
+
+        let sent = outbox.0.lock().unwrap().clone();
+        assert_eq!(sent.len(), 2, "only the allowed human sender is answered");
+        assert_eq!(sent[0].to, "alice@example.com");
+        assert_eq!(sent[0].from, "bot@example.com");
+        assert_eq!(sent[0].subject, "Re: Hours");
+        assert_eq!(sent[0].in_reply_to.as_deref(), Some("<q1@example.com>"));
+        assert_eq!(sent[0].body, "answer to: When do you open?");
+        assert_eq!(sent[1].subject, "Re: Hours");
+        assert!(
+            inbox.unseen.lock().unwrap().is_empty(),
+            "handled mail is flagged seen"
+        );
+
+        let (opens, routed) = agents.await.unwrap();
+        assert_eq!(opens, 1, "one session per sender");
+        let alice = (
+            "email:alice@example.com".to_string(),
+            Some("s-email:alice@example.com".to_string()),
+        );
+        assert_eq!(routed, [alice.clone(), alice]);
+    }
+}
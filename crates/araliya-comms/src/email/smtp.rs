@@ -0,0 +1,171 @@
+//! Minimal SMTP submission client: `EHLO`, `AUTH PLAIN`, one message, `QUIT`.
+//! Generic over the stream so tests can script the server side.
+
+use base64::Engine as _;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use araliya_core::error::AppError;
+
+/// Submit `message` (a rendered RFC 5322 message) from `from` to `to`.
+pub(super) async fn submit<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    helo_name: &str,
+    username: &str,
+    password: &str,
+    from: &str,
+    to: &str,
+    message: &str,
+) -> Result<(), AppError> {
+    let mut smtp = BufReader::new(stream);
+    expect(&mut smtp, 220).await?;
+    command(&mut smtp, &format!("EHLO {helo_name}"), 250).await?;
+    let credentials =
+        base64::engine::general_purpose::STANDARD.encode(format!("\0{username}\0{password}"));
+    command(&mut smtp, &format!("AUTH PLAIN {credentials}"), 235).await?;
+    command(&mut smtp, &format!("MAIL FROM:<{from}>"), 250).await?;
+    command(&mut smtp, &format!("RCPT TO:<{to}>"), 250).await?;
+    command(&mut smtp, "DATA", 354).await?;
+    command(&mut smtp, &format!("{}.", dot_stuff(message)), 250).await?;
+    // The message is accepted; a failed QUIT does not un-send it.
+    let _ = command(&mut smtp, "QUIT", 221).await;
+    Ok(())
+}
+
+/// Write `line` and expect a reply with status `code`.
+async fn command<S: AsyncRead + AsyncWrite + Unpin>(
+    smtp: &mut BufReader<S>,
+    line: &str,
+    code: u16,
+) -> Result<(), AppError> {
+    let stream = smtp.get_mut();
+    stream
+        .write_all(format!("{line}\r\n").as_bytes())
+        .await
+        .map_err(io_error)?;
+    stream.flush().await.map_err(io_error)?;
+    expect(smtp, code).await.map_err(|e| {
+        // Never echo credentials into logs.
+        let verb = line
+            .split_whitespace()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let verb = if verb.starts_with("AUTH") {
+            "AUTH"
+        } else {
+            verb.as_str()
+        };
+        AppError::Comms(format!("{e} (after {verb})"))
+    })
+}
+
+/// Read a (possibly multi-line) reply and check its status code.
+async fn expect<S: AsyncRead + Unpin>(smtp: &mut BufReader<S>, code: u16) -> Result<(), AppError> {
+    let mut reply = String::new();
+    loop {
+        let mut line = String::new();
+        if smtp.read_line(&mut line).await.map_err(io_error)? == 0 {
+            return Err(smtp_error("connection closed".to_string()));
+        }
+        let line = line.trim_end();
+        reply.push_str(line);
+        // `250-...` continues, `250 ...` ends.
+        if line.as_bytes().get(3) != Some(&b'-') {
+            break;
+        }
+        reply.push(' ');
+    }
+    match reply.get(..3).and_then(|c| c.parse::<u16>().ok()) {
+        Some(got) if got == code => Ok(()),
+        _ => Err(smtp_error(format!("expected {code}, got: {reply}"))),
+    }
+}
+
+/// CRLF-normalise and escape leading dots for the `DATA` phase.
+fn dot_stuff(message: &str) -> String {
+    let mut out = String::with_capacity(message.len() + 16);
+    for line in message.lines() {
+        if line.starts_with('.') {
+            out.push('.');
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn io_error(e: std::io::Error) -> AppError {
+    smtp_error(e.to_string())
+}
+
+fn smtp_error(message: String) -> AppError {
+    AppError::Comms(format!("smtp: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn submits_one_message() {
+        let (client, server) = tokio::io::duplex(4096);
+        let server = tokio::spawn(async move {
+            let (read, mut write) = tokio::io::split(server);
+            let mut read = BufReader::new(read);
+            write.write_all(b"220 mx ready\r\n").await.unwrap();
+            let mut lines = Vec::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if read.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                let line = line.trim_end().to_string();
+                let reply: &[u8] = if in_data {
+                    if line == "." {
+                        in_data = false;
+                        b"250 queued\r\n"
+                    } else {
+                        b""
+                    }
+                } else if line.starts_with("EHLO") {
+                    b"250-mx\r\n250 AUTH PLAIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 ok\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go\r\n"
+                } else if line == "QUIT" {
+                    b"221 bye\r\n"
+                } else {
+                    b"250 ok\r\n"
+                };
+                lines.push(line);
+                write.write_all(reply).await.unwrap();
+            }
+            lines
+        });
+
+        submit(
+            client,
+            "example.com",
+            "bot@example.com",
+            "secret",
+            "bot@example.com",
+            "alice@example.com",
+            "Subject: hi\r\n\r\n.leading dot\r\n",
+        )
+        .await
+        .unwrap();
+
+        let lines = server.await.unwrap();
+        assert_eq!(lines[0], "EHLO example.com");
+        assert_eq!(lines[2], "MAIL FROM:<bot@example.com>");
+        assert_eq!(lines[3], "RCPT TO:<alice@example.com>");
+        assert_eq!(
+            &lines[4..9],
+            ["DATA", "Subject: hi", "", "..leading dot", "."]
+        );
+        assert_eq!(lines.last().map(String::as_str), Some("QUIT"));
+    }
+}
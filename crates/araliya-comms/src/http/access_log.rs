@@ -0,0 +1,183 @@
+//! Access log for the HTTP channel (`[comms.http] access_log`).
+//!
+//! Writes one Combined Log Format line per request, independent of tracing,
+//! so standard log analyzers can consume it:
+//!
+//! ```text
+//! 127.0.0.1 - - [10/Oct/2026:13:55:36 +0000] "GET /api/health HTTP/1.1" 200 512 "-" "curl/8.5.0"
+//! ```
+
+use std::fs::OpenOptions;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use araliya_core::error::AppError;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Append-only access log file shared by all connections of one channel.
+pub(super) struct AccessLog {
+    /// Async lock and file, so a slow disk parks the connection's task
+    /// instead of blocking a runtime worker (and every task queued on it).
+    file: Mutex<File>,
+}
+
+/// The fields of one access log line.
+pub(super) struct AccessEntry<'a> {
+    pub peer: Option<SocketAddr>,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub version: &'a str,
+    /// `None` when no response was written.
+    pub status: Option<u16>,
+    pub bytes: usize,
+    pub referer: Option<&'a str>,
+    pub user_agent: Option<&'a str>,
+}
+
+impl AccessLog {
+    /// Open `path` for appending, creating it (and its directory) if needed.
+    pub(super) fn open(path: &Path) -> Result<Self, AppError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| AppError::Comms(format!("cannot create {}: {e}", dir.display())))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                AppError::Comms(format!(
+                    "cannot open http access log {}: {e}",
+                    path.display()
+                ))
+            })?;
+        Ok(Self {
+            file: Mutex::new(File::from_std(file)),
+        })
+    }
+
+    /// Append one line for `entry`.  A failed write is logged, never
+    /// returned: the request it describes has already been answered.
+    pub(super) async fn record(&self, entry: &AccessEntry<'_>) {
+        let line = format_line(entry, SystemTime::now());
+        let mut file = self.file.lock().await;
+        // tokio's `File` buffers; flush so the line is on disk before the
+        // lock is released.
+        let written = match file.write_all(line.as_bytes()).await {
+            Ok(()) => file.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            warn!("http access log write failed: {e}");
+        }
+    }
+}
+
+/// Render `entry` as a Combined Log Format line, newline included.
+fn format_line(entry: &AccessEntry<'_>, now: SystemTime) -> String {
+    let host = entry
+        .peer
+        .map(|p| p.ip().to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let status = entry
+        .status
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let bytes = match entry.bytes {
+        0 => "-".to_string(),
+        n => n.to_string(),
+    };
+    format!(
+        "{host} - - [{}] \"{} {} {}\" {status} {bytes} \"{}\" \"{}\"\n",
+        clf_time(now),
+        escape(entry.method),
+        escape(entry.path),
+        escape(entry.version),
+        entry.referer.map(escape).unwrap_or_else(|| "-".to_string()),
+        entry
+            .user_agent
+            .map(escape)
+            .unwrap_or_else(|| "-".to_string()),
+    )
+}
+
+/// Escape quotes, backslashes and control characters so a field cannot
+/// break the line format.
+fn escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `10/Oct/2026:13:55:36 +0000` — CLF timestamp, always in UTC.
+fn clf_time(now: SystemTime) -> String {
+    let secs = now
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!(
+        "{day:02}/{}/{year:04}:{:02}:{:02}:{:02} +0000",
+        MONTHS[(month - 1) as usize],
+        (secs / 3600) % 24,
+        (secs / 60) % 60,
+        secs % 60
+    )
+}
+
+/// Days since 1970-01-01 → (year, month 1–12, day 1–31), proleptic
+/// Gregorian.  Avoids a date-time dependency for one timestamp.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn formats_a_combined_log_line() {
+        // 2026-10-16T08:09:05Z
+        let now = UNIX_EPOCH + Duration::from_secs(1_792_138_145);
+        let entry = AccessEntry {
+            peer: Some("10.0.0.7:51234".parse().unwrap()),
+            method: "GET",
+            path: "/api/health",
+            version: "HTTP/1.1",
+            status: Some(200),
+            bytes: 512,
+            referer: None,
+            user_agent: Some("curl/8.5.0 \"x\""),
+        };
+        assert_eq!(
+            format_line(&entry, now),
+            "10.0.0.7 - - [16/Oct/2026:08:09:05 +0000] \"GET /api/health HTTP/1.1\" 200 512 \"-\" \"curl/8.5.0 \\\"x\\\"\"\n"
+        );
+    }
+}
@@ -6,8 +6,10 @@ use std::time::Duration;
 use serde::Deserialize;
 use tracing::warn;
 
+use super::HttpConn;
 use crate::CommsState;
 use araliya_core::error::AppError;
+use araliya_core::types::llm::ResponseFormat;
 
 const NO_SESSION_ID: &str = "00000000-0000-0000-0000-000000000000";
 
@@ -19,19 +21,27 @@ struct MessageRequest {
     session_id: Option<String>,
     agent_id: Option<String>,
     mode: Option<String>,
+    /// `"json"` for a reply that is a single valid JSON value.
+    #[serde(default)]
+    response_format: Option<ResponseFormat>,
 }
 
 // ── Handlers ──────────────────────────────────────────────────────────────────
 
 pub(super) async fn handle_health(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
+    refresh: bool,
 ) -> Result<(), AppError> {
-    let response = tokio::time::timeout(Duration::from_secs(3), state.management_http_get()).await;
+    let response = tokio::time::timeout(
+        Duration::from_secs(3),
+        state.cached_get("/api/health", refresh, state.management_http_get()),
+    )
+    .await;
 
     match response {
-        Ok(Ok(body)) => super::write_json_response(socket, "200 OK", body.as_bytes()).await,
+        Ok(Ok(cached)) => super::write_cached_response(socket, &cached).await,
         Ok(Err(e)) => {
             warn!(%channel_id, "management health request failed: {e}");
             super::write_response(
@@ -56,7 +66,7 @@ pub(super) async fn handle_health(
 }
 
 pub(super) async fn handle_health_refresh(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
 ) -> Result<(), AppError> {
@@ -88,15 +98,25 @@ pub(super) async fn handle_health_refresh(
     }
 }
 
+pub(super) async fn handle_openapi(socket: &mut HttpConn) -> Result<(), AppError> {
+    let body = super::openapi::document().to_string();
+    super::write_json_response(socket, "200 OK", body.as_bytes()).await
+}
+
 pub(super) async fn handle_tree(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
+    refresh: bool,
 ) -> Result<(), AppError> {
-    let response = tokio::time::timeout(Duration::from_secs(3), state.management_http_tree()).await;
+    let response = tokio::time::timeout(
+        Duration::from_secs(3),
+        state.cached_get("/api/tree", refresh, state.management_http_tree()),
+    )
+    .await;
 
     match response {
-        Ok(Ok(body)) => super::write_json_response(socket, "200 OK", body.as_bytes()).await,
+        Ok(Ok(cached)) => super::write_cached_response(socket, &cached).await,
         Ok(Err(e)) => {
             warn!(%channel_id, "management tree request failed: {e}");
             super::write_response(
@@ -120,8 +140,49 @@ pub(super) async fn handle_tree(
     }
 }
 
+pub(super) async fn handle_config(
+    socket: &mut HttpConn,
+    state: &Arc<CommsState>,
+    channel_id: &str,
+) -> Result<(), AppError> {
+    let response = tokio::time::timeout(Duration::from_secs(3), state.management_config()).await;
+
+    match response {
+        Ok(Ok(Some(body))) => super::write_json_response(socket, "200 OK", body.as_bytes()).await,
+        Ok(Ok(None)) => {
+            super::write_response(
+                socket,
+                "403 Forbidden",
+                "text/plain; charset=utf-8",
+                b"forbidden\n",
+            )
+            .await
+        }
+        Ok(Err(e)) => {
+            warn!(%channel_id, "management config request failed: {e}");
+            super::write_response(
+                socket,
+                "502 Bad Gateway",
+                "text/plain; charset=utf-8",
+                b"management adapter error\n",
+            )
+            .await
+        }
+        Err(_) => {
+            warn!(%channel_id, "management config request timed out");
+            super::write_response(
+                socket,
+                "504 Gateway Timeout",
+                "text/plain; charset=utf-8",
+                b"management adapter timeout\n",
+            )
+            .await
+        }
+    }
+}
+
 pub(super) async fn handle_message(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     body: Vec<u8>,
@@ -153,11 +214,12 @@ pub(super) async fn handle_message(
 
     let reply_result = tokio::time::timeout(
         Duration::from_secs(120),
-        state.send_message(
+        state.send_message_as(
             channel_id,
             msg_req.message.clone(),
             requested_session_id,
             msg_req.agent_id.clone(),
+            msg_req.response_format,
         ),
     )
     .await;
@@ -198,11 +260,17 @@ pub(super) async fn handle_message(
 }
 
 pub(super) async fn handle_sessions(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
+    include_archived: bool,
+    sort: Option<&str>,
 ) -> Result<(), AppError> {
-    let result = tokio::time::timeout(Duration::from_secs(10), state.request_sessions()).await;
+    let result = tokio::time::timeout(
+        Duration::from_secs(10),
+        state.request_sessions(include_archived, sort),
+    )
+    .await;
 
     match result {
         Ok(Ok(data)) => super::write_json_response(socket, "200 OK", data.as_bytes()).await,
@@ -231,7 +299,7 @@ pub(super) async fn handle_sessions(
 }
 
 pub(super) async fn handle_session_detail(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     session_id: &str,
@@ -269,7 +337,7 @@ pub(super) async fn handle_session_detail(
 }
 
 pub(super) async fn handle_session_memory(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     session_id: &str,
@@ -307,7 +375,7 @@ pub(super) async fn handle_session_memory(
 }
 
 pub(super) async fn handle_agent_kg(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     agent_id: &str,
@@ -342,7 +410,7 @@ pub(super) async fn handle_agent_kg(
 }
 
 pub(super) async fn handle_memory_agent_kg(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     agent_id: &str,
@@ -377,7 +445,7 @@ pub(super) async fn handle_memory_agent_kg(
 }
 
 pub(super) async fn handle_session_debug(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     session_id: &str,
@@ -415,7 +483,7 @@ pub(super) async fn handle_session_debug(
 }
 
 pub(super) async fn handle_session_files(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     session_id: &str,
@@ -453,7 +521,7 @@ pub(super) async fn handle_session_files(
 }
 
 pub(super) async fn handle_llm_providers(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
 ) -> Result<(), AppError> {
@@ -479,7 +547,7 @@ pub(super) async fn handle_llm_providers(
 }
 
 pub(super) async fn handle_llm_set_default(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     state: &Arc<CommsState>,
     channel_id: &str,
     body: Vec<u8>,
@@ -1,23 +1,41 @@
 //! HTTP comms channel — serves API endpoints under `/api/` and delegates
 //! all other paths to the UI backend when a [`UiServeHandle`] is provided.
 
+mod access_log;
 mod api;
+pub(crate) mod openapi;
 mod ui;
 
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 use crate::state::{CommsEvent, CommsState};
+use access_log::{AccessEntry, AccessLog};
+use araliya_core::bus::health::HealthReporter;
+use araliya_core::config::{ConnectionOverflow, DEFAULT_HTTP_MAX_HEADER_BYTES};
 use araliya_core::error::AppError;
 use araliya_core::runtime::{Component, ComponentFuture};
 #[cfg(feature = "subsystem-ui")]
 use araliya_core::ui::UiServeHandle;
 
-const MAX_HEADER_BYTES: usize = 8 * 1024;
+/// Largest request body read; a bigger `Content-Length` gets `413`.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Most unread request bytes discarded after a rejection, so the close does
+/// not reset the connection before the client reads the response.
+const MAX_DISCARD_BYTES: usize = 64 * 1024;
+const DISCARD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a connection over `max_connections` waits for a slot in
+/// [`ConnectionOverflow::Wait`] mode before it is answered `503`.
+const CONNECTION_WAIT: Duration = Duration::from_secs(5);
 
 #[cfg(feature = "subsystem-ui")]
 type OptionalUiHandle = Option<UiServeHandle>;
@@ -29,6 +47,47 @@ pub struct HttpChannel {
     bind_addr: String,
     state: Arc<CommsState>,
     ui_handle: OptionalUiHandle,
+    slow_request_ms: Option<u64>,
+    read_timeout: Option<Duration>,
+    max_header_bytes: usize,
+    access_log: Option<PathBuf>,
+    connection_limit: Option<ConnectionLimit>,
+    health: Option<HealthReporter>,
+}
+
+/// `[comms.http] max_connections`: one semaphore permit per open
+/// connection, released when its handler returns.  In
+/// [`ConnectionOverflow::Wait`] mode at most `max` more connections queue
+/// for a slot; the rest are answered `503` at once.
+#[derive(Clone)]
+struct ConnectionLimit {
+    slots: Arc<Semaphore>,
+    waiters: Arc<Semaphore>,
+    max: usize,
+    overflow: ConnectionOverflow,
+}
+
+impl ConnectionLimit {
+    /// A slot for a new connection, or `None` when it should get `503`.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        match self.overflow {
+            ConnectionOverflow::Reject => self.slots.clone().try_acquire_owned().ok(),
+            ConnectionOverflow::Wait => {
+                if let Ok(slot) = self.slots.clone().try_acquire_owned() {
+                    return Some(slot);
+                }
+                let _waiting = self.waiters.try_acquire().ok()?;
+                tokio::time::timeout(CONNECTION_WAIT, self.slots.clone().acquire_owned())
+                    .await
+                    .ok()?
+                    .ok()
+            }
+        }
+    }
+
+    fn active(&self) -> usize {
+        self.max - self.slots.available_permits()
+    }
 }
 
 impl HttpChannel {
@@ -43,8 +102,54 @@ impl HttpChannel {
             bind_addr: bind_addr.into(),
             state,
             ui_handle,
+            slow_request_ms: None,
+            read_timeout: None,
+            max_header_bytes: DEFAULT_HTTP_MAX_HEADER_BYTES,
+            access_log: None,
+            connection_limit: None,
+            health: None,
         }
     }
+
+    /// Log requests taking at least `ms` milliseconds at WARN.
+    pub fn with_slow_request_ms(mut self, ms: Option<u64>) -> Self {
+        self.slow_request_ms = ms;
+        self
+    }
+
+    /// Close connections whose request reads stall for `ms` milliseconds.
+    pub fn with_read_timeout_ms(mut self, ms: Option<u64>) -> Self {
+        self.read_timeout = ms.map(Duration::from_millis);
+        self
+    }
+
+    /// Answer `431` to requests whose line and headers exceed `bytes`.
+    pub fn with_max_header_bytes(mut self, bytes: usize) -> Self {
+        self.max_header_bytes = bytes;
+        self
+    }
+
+    /// Append a Combined Log Format line per request to `path`.
+    pub fn with_access_log(mut self, path: Option<PathBuf>) -> Self {
+        self.access_log = path;
+        self
+    }
+
+    /// Serve at most `max` connections at once; `overflow` decides what
+    /// happens to the rest.  `None` leaves connections unlimited.
+    pub fn with_max_connections(
+        mut self,
+        max: Option<usize>,
+        overflow: ConnectionOverflow,
+    ) -> Self {
+        self.connection_limit = max.map(|max| ConnectionLimit {
+            slots: Arc::new(Semaphore::new(max)),
+            waiters: Arc::new(Semaphore::new(max)),
+            max,
+            overflow,
+        });
+        self
+    }
 }
 
 impl Component for HttpChannel {
@@ -53,30 +158,50 @@ impl Component for HttpChannel {
     }
 
     fn run(self: Box<Self>, shutdown: CancellationToken) -> ComponentFuture {
-        Box::pin(run_http(
-            self.channel_id,
-            self.bind_addr,
-            self.state,
-            self.ui_handle,
-            shutdown,
-        ))
+        Box::pin(run_http(*self, shutdown))
+    }
+
+    /// Record the time of the last accepted connection (`last_accept`,
+    /// Unix seconds) in the channel's health details.
+    fn attach_health(&mut self, reporter: HealthReporter) {
+        self.health = Some(reporter);
     }
 }
 
 // ── Server loop ───────────────────────────────────────────────────────────────
 
-async fn run_http(
-    channel_id: String,
-    bind_addr: String,
-    state: Arc<CommsState>,
-    ui_handle: OptionalUiHandle,
-    shutdown: CancellationToken,
-) -> Result<(), AppError> {
-    let listener = TcpListener::bind(&bind_addr)
+async fn run_http(channel: HttpChannel, shutdown: CancellationToken) -> Result<(), AppError> {
+    let bind_addr = &channel.bind_addr;
+    let listener = TcpListener::bind(bind_addr)
         .await
         .map_err(|e| AppError::Comms(format!("http bind failed on {bind_addr}: {e}")))?;
+    info!(channel_id = %channel.channel_id, %bind_addr, "http channel listening");
+    serve_http(channel, listener, shutdown).await
+}
 
-    info!(%channel_id, %bind_addr, "http channel listening");
+/// Accept connections on `listener` until `shutdown`.
+async fn serve_http(
+    channel: HttpChannel,
+    listener: TcpListener,
+    shutdown: CancellationToken,
+) -> Result<(), AppError> {
+    let HttpChannel {
+        channel_id,
+        state,
+        ui_handle,
+        slow_request_ms,
+        read_timeout,
+        max_header_bytes,
+        access_log,
+        connection_limit,
+        health,
+        ..
+    } = channel;
+    let access_log = access_log
+        .as_deref()
+        .map(AccessLog::open)
+        .transpose()?
+        .map(Arc::new);
 
     loop {
         tokio::select! {
@@ -91,14 +216,44 @@ async fn run_http(
                 match accepted {
                     Ok((socket, peer)) => {
                         debug!(%channel_id, %peer, "http client connected");
-                        state.report_event(CommsEvent::SessionStarted { channel_id: channel_id.clone() });
+                        if let Some(health) = &health {
+                            let now = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map(|d| d.as_secs())
+                                .unwrap_or(0);
+                            let mut details = serde_json::json!({ "last_accept": now });
+                            if let Some(limit) = &connection_limit {
+                                details["active_connections"] = limit.active().into();
+                            }
+                            health.set_healthy_with("running", Some(details)).await;
+                        }
+                        // Replies are small and written in pieces; do not
+                        // let Nagle hold them back.
+                        if let Err(e) = socket.set_nodelay(true) {
+                            debug!(%channel_id, %peer, "cannot set TCP_NODELAY: {e}");
+                        }
+                        let session = state.session_scope(&channel_id);
                         let state = state.clone();
                         let channel_id = channel_id.clone();
                         let ui_handle = ui_handle.clone();
+                        let access_log = access_log.clone();
+                        let connection_limit = connection_limit.clone();
                         tokio::spawn(async move {
-                            if let Err(e) = handle_connection(state, channel_id, socket, ui_handle).await {
+                            let _slot = match &connection_limit {
+                                Some(limit) => match limit.acquire().await {
+                                    Some(slot) => Some(slot),
+                                    None => {
+                                        debug!(%channel_id, %peer, "http connection limit reached");
+                                        reject_busy(socket).await;
+                                        return;
+                                    }
+                                },
+                                None => None,
+                            };
+                            if let Err(e) = handle_connection(state, channel_id, socket, ui_handle, slow_request_ms, read_timeout, max_header_bytes, access_log).await {
                                 warn!("http connection handling failed: {e}");
                             }
+                            drop(session);
                         });
                     }
                     Err(e) => {
@@ -113,23 +268,59 @@ async fn run_http(
     Ok(())
 }
 
+/// Answer `503` to a connection over `max_connections` and close it.
+async fn reject_busy(stream: TcpStream) {
+    let mut socket = HttpConn::new(stream);
+    let body = serde_json::json!({
+        "error": "unavailable",
+        "message": "too many connections, try again shortly",
+    });
+    if write_json_response(
+        &mut socket,
+        "503 Service Unavailable",
+        body.to_string().as_bytes(),
+    )
+    .await
+    .is_ok()
+    {
+        discard_input(&mut socket.stream).await;
+    }
+}
+
 // ── Connection dispatch ───────────────────────────────────────────────────────
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_connection(
     state: Arc<CommsState>,
     channel_id: String,
-    mut socket: tokio::net::TcpStream,
+    stream: TcpStream,
     ui_handle: OptionalUiHandle,
+    slow_request_ms: Option<u64>,
+    read_timeout: Option<Duration>,
+    max_header_bytes: usize,
+    access_log: Option<Arc<AccessLog>>,
 ) -> Result<(), AppError> {
-    let request = read_request(&mut socket).await?;
-
-    let Some(req) = request else {
-        return Ok(());
+    let peer = stream.peer_addr().ok();
+    let mut socket = HttpConn::new(stream);
+    let req = match read_request(&mut socket.stream, read_timeout, max_header_bytes).await? {
+        ReadOutcome::Request(req) => req,
+        ReadOutcome::Closed => return Ok(()),
+        ReadOutcome::Rejected { status, message } => {
+            debug!(%channel_id, ?peer, status, "rejecting http request: {message}");
+            let body = serde_json::json!({ "error": "bad_request", "message": message });
+            write_json_response(&mut socket, status, body.to_string().as_bytes()).await?;
+            discard_input(&mut socket.stream).await;
+            return Ok(());
+        }
     };
 
+    let started = Instant::now();
     let method = req.method;
     let path = req.path;
     let body = req.body;
+    let version = req.version;
+    let referer = req.referer;
+    let user_agent = req.user_agent;
 
     let session_memory = parse_session_subresource_path(&path, "memory");
     let session_debug = parse_session_subresource_path(&path, "debug");
@@ -137,16 +328,33 @@ async fn handle_connection(
     let agent_kg = parse_agent_subresource_path(&path, "kg");
     let memory_agent_kg = parse_memory_agent_subresource_path(&path, "kg");
 
-    match (method.as_str(), path.as_str()) {
-        ("GET", "/api/health") => api::handle_health(&mut socket, &state, &channel_id).await,
+    let (route, query) = path.split_once('?').unwrap_or((path.as_str(), ""));
+
+    if method != "GET" {
+        state.invalidate_response_cache();
+    }
+    let refresh = query_flag(query, "refresh");
+
+    let result = match (method.as_str(), path.as_str()) {
+        ("GET", _) if route == "/api/health" => {
+            api::handle_health(&mut socket, &state, &channel_id, refresh).await
+        }
         ("POST", "/api/health/refresh") => {
             api::handle_health_refresh(&mut socket, &state, &channel_id).await
         }
-        ("GET", "/api/tree") => api::handle_tree(&mut socket, &state, &channel_id).await,
+        ("GET", _) if route == "/api/tree" => {
+            api::handle_tree(&mut socket, &state, &channel_id, refresh).await
+        }
+        ("GET", "/api/config") => api::handle_config(&mut socket, &state, &channel_id).await,
+        ("GET", openapi::OPENAPI_PATH) => api::handle_openapi(&mut socket).await,
         ("POST", "/api/message") => {
             api::handle_message(&mut socket, &state, &channel_id, body).await
         }
-        ("GET", "/api/sessions") => api::handle_sessions(&mut socket, &state, &channel_id).await,
+        ("GET", _) if route == "/api/sessions" => {
+            let include_archived = query_flag(query, "include_archived");
+            let sort = query_param(query, "sort");
+            api::handle_sessions(&mut socket, &state, &channel_id, include_archived, sort).await
+        }
         ("GET", "/api/llm/providers") => {
             api::handle_llm_providers(&mut socket, &state, &channel_id).await
         }
@@ -182,6 +390,48 @@ async fn handle_connection(
         ("GET", "/" | "/index.html") => ui::handle_root(&mut socket).await,
         ("GET", p) if p.starts_with("/ui") => ui::handle_ui_path(&mut socket, p, &ui_handle).await,
         _ => ui::handle_not_found(&mut socket).await,
+    };
+
+    log_request(
+        &channel_id,
+        &method,
+        &path,
+        &socket,
+        started.elapsed(),
+        slow_request_ms,
+    );
+    if let Some(log) = &access_log {
+        log.record(&AccessEntry {
+            peer,
+            method: &method,
+            path: &path,
+            version: &version,
+            status: socket.status,
+            bytes: socket.response_bytes,
+            referer: referer.as_deref(),
+            user_agent: user_agent.as_deref(),
+        })
+        .await;
+    }
+    result
+}
+
+/// One line per request: DEBUG normally, WARN at or above `slow_request_ms`.
+fn log_request(
+    channel_id: &str,
+    method: &str,
+    path: &str,
+    conn: &HttpConn,
+    elapsed: Duration,
+    slow_request_ms: Option<u64>,
+) {
+    let elapsed_ms = elapsed.as_millis() as u64;
+    let status = conn.status.unwrap_or(0);
+    let bytes = conn.response_bytes;
+    if slow_request_ms.is_some_and(|threshold| elapsed_ms >= threshold) {
+        warn!(%channel_id, %method, %path, status, bytes, elapsed_ms, "slow http request");
+    } else {
+        debug!(%channel_id, %method, %path, status, bytes, elapsed_ms, "http request");
     }
 }
 
@@ -217,6 +467,23 @@ fn parse_memory_agent_subresource_path<'a>(path: &'a str, subresource: &str) ->
     Some(inner)
 }
 
+/// `true` when the query string sets `name` to `1` or `true`.
+pub(crate) fn query_flag(query: &str, name: &str) -> bool {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .any(|(key, value)| key == name && matches!(value, "1" | "true"))
+}
+
+/// The value of query parameter `name`, if present and non-empty.
+pub(crate) fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
 fn parse_session_subresource_path<'a>(path: &'a str, subresource: &str) -> Option<&'a str> {
     let prefix = "/api/sessions/";
     let suffix = format!("/{subresource}");
@@ -238,76 +505,139 @@ fn parse_session_subresource_path<'a>(path: &'a str, subresource: &str) -> Optio
 struct HttpRequest {
     method: String,
     path: String,
+    version: String,
+    referer: Option<String>,
+    user_agent: Option<String>,
     body: Vec<u8>,
 }
 
-async fn read_request(socket: &mut tokio::net::TcpStream) -> Result<Option<HttpRequest>, AppError> {
+/// Read one read's worth into `buf`.  `Ok(None)` when `deadline` passes
+/// first.
+async fn read_some(
+    socket: &mut TcpStream,
+    buf: &mut [u8],
+    deadline: Option<tokio::time::Instant>,
+) -> Result<Option<usize>, AppError> {
+    match deadline {
+        Some(deadline) => match tokio::time::timeout_at(deadline, socket.read(buf)).await {
+            Ok(n) => Ok(Some(n?)),
+            Err(_) => Ok(None),
+        },
+        None => Ok(Some(socket.read(buf).await?)),
+    }
+}
+
+/// What [`read_request`] made of the bytes on the connection.
+enum ReadOutcome {
+    Request(HttpRequest),
+    /// The client closed the connection before sending anything, or did not
+    /// finish its request within the read timeout.  Nothing to answer.
+    Closed,
+    /// Unacceptable request; answer `status` and close.
+    Rejected {
+        status: &'static str,
+        message: String,
+    },
+}
+
+fn rejected(status: &'static str, message: impl Into<String>) -> ReadOutcome {
+    ReadOutcome::Rejected {
+        status,
+        message: message.into(),
+    }
+}
+
+/// Read the next request.  Heads over `max_header_bytes` are rejected with
+/// `431`, bodies over [`MAX_BODY_BYTES`] with `413` and malformed request
+/// lines or headers with `400`; only I/O failures are errors.
+///
+/// `read_timeout` bounds the whole request, not each read, so a client
+/// trickling bytes cannot hold the connection open.
+async fn read_request(
+    socket: &mut TcpStream,
+    read_timeout: Option<Duration>,
+    max_header_bytes: usize,
+) -> Result<ReadOutcome, AppError> {
+    let deadline = read_timeout.map(|limit| tokio::time::Instant::now() + limit);
     let mut buffer = Vec::with_capacity(1024);
     let mut chunk = [0u8; 1024];
 
-    loop {
-        let n = socket.read(&mut chunk).await?;
+    let header_end = loop {
+        let Some(n) = read_some(socket, &mut chunk, deadline).await? else {
+            debug!(received = buffer.len(), "http read timed out; closing");
+            return Ok(ReadOutcome::Closed);
+        };
         if n == 0 {
             if buffer.is_empty() {
-                return Ok(None);
+                return Ok(ReadOutcome::Closed);
             }
             return Err(AppError::Comms("http request truncated".to_string()));
         }
 
         buffer.extend_from_slice(&chunk[..n]);
 
-        if buffer.len() > MAX_HEADER_BYTES {
-            return Err(AppError::Comms(
-                "http request headers too large".to_string(),
-            ));
-        }
-
-        if buffer.windows(4).any(|w| w == b"\r\n\r\n") {
-            break;
+        match buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) if end > max_header_bytes => break None,
+            Some(end) => break Some(end),
+            None if buffer.len() > max_header_bytes => break None,
+            None => {}
         }
-    }
-
-    let header_end = buffer.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    };
+    let Some(header_end) = header_end else {
+        return Ok(rejected(
+            "431 Request Header Fields Too Large",
+            format!("request headers exceed {max_header_bytes} bytes"),
+        ));
+    };
     let body_start = header_end + 4;
     let header_bytes = &buffer[..header_end];
 
-    let header_str = std::str::from_utf8(header_bytes)
-        .map_err(|_| AppError::Comms("http request was not valid utf-8".to_string()))?;
-
-    let first_line = header_str
-        .lines()
-        .next()
-        .ok_or_else(|| AppError::Comms("empty http request".to_string()))?;
+    let Ok(header_str) = std::str::from_utf8(header_bytes) else {
+        return Ok(rejected(
+            "400 Bad Request",
+            "request headers are not valid utf-8",
+        ));
+    };
 
+    let first_line = header_str.lines().next().unwrap_or_default();
     let mut parts = first_line.split_whitespace();
-    let method = parts
-        .next()
-        .ok_or_else(|| AppError::Comms("missing http method".to_string()))?
-        .to_string();
-    let path = parts
-        .next()
-        .ok_or_else(|| AppError::Comms("missing http path".to_string()))?
-        .to_string();
-
-    let content_length: usize = header_str
-        .lines()
-        .skip(1)
-        .find_map(|line| {
-            let lower = line.to_ascii_lowercase();
-            if lower.starts_with("content-length:") {
-                line.split_once(':')
-                    .and_then(|(_, v)| v.trim().parse().ok())
-            } else {
-                None
-            }
-        })
-        .unwrap_or(0);
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(rejected("400 Bad Request", "malformed request line"));
+    };
+    let method = method.to_string();
+    let path = path.to_string();
+    let version = parts.next().unwrap_or("HTTP/1.0").to_string();
+    if parts.next().is_some() || !version.starts_with("HTTP/") {
+        return Ok(rejected("400 Bad Request", "malformed request line"));
+    }
+
+    let content_length: usize = match header_value(header_str, "content-length") {
+        None => 0,
+        Some(v) => match v.parse() {
+            Ok(n) => n,
+            Err(_) => return Ok(rejected("400 Bad Request", "invalid Content-Length")),
+        },
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Ok(rejected(
+            "413 Payload Too Large",
+            format!("request body exceeds {MAX_BODY_BYTES} bytes"),
+        ));
+    }
+    let referer = header_value(header_str, "referer").map(str::to_string);
+    let user_agent = header_value(header_str, "user-agent").map(str::to_string);
 
     let mut body = buffer[body_start..].to_vec();
     while body.len() < content_length {
         let remaining = content_length - body.len();
         let mut read_buf = vec![0u8; remaining.min(8192)];
-        let n = socket.read(&mut read_buf).await?;
+        let Some(n) = read_some(socket, &mut read_buf, deadline).await? else {
+            debug!(
+                received = body.len(),
+                content_length, "http body read timed out; closing"
+            );
+            return Ok(ReadOutcome::Closed);
+        };
         if n == 0 {
             return Err(AppError::Comms("http request body truncated".to_string()));
         }
@@ -315,13 +645,63 @@ async fn read_request(socket: &mut tokio::net::TcpStream) -> Result<Option<HttpR
     }
     body.truncate(content_length);
 
-    Ok(Some(HttpRequest { method, path, body }))
+    Ok(ReadOutcome::Request(HttpRequest {
+        method,
+        path,
+        version,
+        referer,
+        user_agent,
+        body,
+    }))
+}
+
+/// Drain what the client is still sending (bounded in bytes and time)
+/// before the socket drops.  Closing with unread input resets the
+/// connection, which can discard a response the client has not read yet.
+async fn discard_input(socket: &mut TcpStream) {
+    let mut chunk = [0u8; 4096];
+    let mut discarded = 0;
+    let _ = tokio::time::timeout(DISCARD_TIMEOUT, async {
+        while discarded < MAX_DISCARD_BYTES {
+            match socket.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => discarded += n,
+            }
+        }
+    })
+    .await;
+}
+
+/// Trimmed value of the first header named `name` (case-insensitive).
+fn header_value<'a>(header_str: &'a str, name: &str) -> Option<&'a str> {
+    header_str.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
 }
 
 // ── Response helpers ──────────────────────────────────────────────────────────
 
+/// Client socket that remembers the response written to it, so the request
+/// log can report status and size without every handler returning them.
+pub(super) struct HttpConn {
+    stream: TcpStream,
+    status: Option<u16>,
+    response_bytes: usize,
+}
+
+impl HttpConn {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            status: None,
+            response_bytes: 0,
+        }
+    }
+}
+
 async fn write_json_response(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     status: &str,
     body: &[u8],
 ) -> Result<(), AppError> {
@@ -329,18 +709,619 @@ async fn write_json_response(
 }
 
 async fn write_response(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), AppError> {
+    write_response_with_headers(socket, status, content_type, &[], body).await
+}
+
+/// A JSON answer for a cacheable GET route, with its cache headers.
+async fn write_cached_response(
+    socket: &mut HttpConn,
+    response: &crate::cache::CachedResponse,
+) -> Result<(), AppError> {
+    write_response_with_headers(
+        socket,
+        "200 OK",
+        "application/json",
+        &response.headers(),
+        response.body.as_bytes(),
+    )
+    .await
+}
+
+async fn write_response_with_headers(
+    socket: &mut HttpConn,
     status: &str,
     content_type: &str,
+    extra_headers: &[(&str, String)],
     body: &[u8],
 ) -> Result<(), AppError> {
+    let extra: String = extra_headers
+        .iter()
+        .map(|(name, value)| format!("{name}: {value}\r\n"))
+        .collect();
     let header = format!(
-        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n{extra}Connection: close\r\n\r\n",
         body.len()
     );
+    socket.status = status
+        .split_whitespace()
+        .next()
+        .and_then(|c| c.parse().ok());
+    socket.response_bytes = body.len();
 
-    socket.write_all(header.as_bytes()).await?;
-    socket.write_all(body).await?;
-    socket.shutdown().await?;
+    socket.stream.write_all(header.as_bytes()).await?;
+    socket.stream.write_all(body).await?;
+    socket.stream.shutdown().await?;
     Ok(())
 }
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// `MakeWriter` target collecting formatted log lines.
+    #[derive(Clone, Default)]
+    struct LogBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn query_flag_accepts_one_and_true() {
+        assert!(query_flag("include_archived=1", "include_archived"));
+        assert!(query_flag("a=b&include_archived=true", "include_archived"));
+        assert!(!query_flag("include_archived=0", "include_archived"));
+        assert!(!query_flag("", "include_archived"));
+    }
+
+    #[test]
+    fn query_param_finds_named_value() {
+        assert_eq!(
+            query_param("include_archived=1&sort=created_at", "sort"),
+            Some("created_at")
+        );
+        assert_eq!(query_param("sort=", "sort"), None);
+        assert_eq!(query_param("", "sort"), None);
+    }
+
+    /// Send `GET /favicon.ico` on `stream` and read the whole response.
+    async fn get_favicon(mut stream: TcpStream) -> String {
+        stream
+            .write_all(b"GET /favicon.ico HTTP/1.1\r\nHost: test\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await.unwrap();
+        String::from_utf8_lossy(&buf).into_owned()
+    }
+
+    #[tokio::test]
+    async fn connections_over_the_limit_get_503_until_a_slot_frees() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        let channel = HttpChannel::new("http0", addr.to_string(), state, None)
+            .with_max_connections(Some(1), ConnectionOverflow::Reject);
+        let shutdown = CancellationToken::new();
+        tokio::spawn(serve_http(channel, listener, shutdown.clone()));
+
+        // The first connection holds the only slot while its request is
+        // still unsent.
+        let first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let busy = get_favicon(TcpStream::connect(addr).await.unwrap()).await;
+        assert!(busy.starts_with("HTTP/1.1 503"), "{busy}");
+        assert!(busy.contains("too many connections"));
+
+        // Finishing the first request frees its slot for the next client.
+        assert!(get_favicon(first).await.starts_with("HTTP/1.1 204"));
+        // The permit drops as the handler task ends, just after the close.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let next = get_favicon(TcpStream::connect(addr).await.unwrap()).await;
+        assert!(next.starts_with("HTTP/1.1 204"), "{next}");
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn waiting_connections_are_bounded() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        let channel = HttpChannel::new("http0", addr.to_string(), state, None)
+            .with_max_connections(Some(1), ConnectionOverflow::Wait);
+        let shutdown = CancellationToken::new();
+        tokio::spawn(serve_http(channel, listener, shutdown.clone()));
+
+        // One connection holds the slot and one waits for it.
+        let first = TcpStream::connect(addr).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let waiting = tokio::spawn(get_favicon(TcpStream::connect(addr).await.unwrap()));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The queue is full: the next one is refused without waiting.
+        let busy = tokio::time::timeout(
+            Duration::from_secs(2),
+            get_favicon(TcpStream::connect(addr).await.unwrap()),
+        )
+        .await
+        .expect("refused at once");
+        assert!(busy.starts_with("HTTP/1.1 503"), "{busy}");
+
+        // The waiter gets the slot once the first request finishes.
+        assert!(get_favicon(first).await.starts_with("HTTP/1.1 204"));
+        let next = waiting.await.unwrap();
+        assert!(next.starts_with("HTTP/1.1 204"), "{next}");
+
+        shutdown.cancel();
+    }
+
+    /// Serve one `GET /favicon.ico` through `handle_connection` and return
+    /// the raw response and the captured log output.
+    async fn serve_logged(slow_request_ms: Option<u64>) -> (Vec<u8>, String) {
+        let logs = LogBuf::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut s = TcpStream::connect(addr).await.unwrap();
+            s.write_all(b"GET /favicon.ico HTTP/1.1\r\nHost: test\r\n\r\n")
+                .await
+                .unwrap();
+            let mut buf = Vec::new();
+            s.read_to_end(&mut buf).await.unwrap();
+            buf
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        handle_connection(
+            state,
+            "http0".into(),
+            stream,
+            None,
+            slow_request_ms,
+            None,
+            DEFAULT_HTTP_MAX_HEADER_BYTES,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let response = client.await.unwrap();
+        let out = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        (response, out)
+    }
+
+    #[tokio::test]
+    async fn request_log_records_method_path_status_bytes_and_duration() {
+        let (response, logs) = serve_logged(None).await;
+        assert!(response.starts_with(b"HTTP/1.1 204"));
+
+        let line = logs
+            .lines()
+            .find(|l| l.contains("http request"))
+            .expect("request log line");
+        assert!(line.contains("DEBUG"), "{line}");
+        for field in [
+            "channel_id=http0",
+            "method=GET",
+            "path=/favicon.ico",
+            "status=204",
+            "bytes=0",
+            "elapsed_ms=",
+        ] {
+            assert!(line.contains(field), "missing {field} in {line}");
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_request_escalates_to_warn() {
+        let (_, logs) = serve_logged(Some(0)).await;
+        let line = logs
+            .lines()
+            .find(|l| l.contains("slow http request"))
+            .expect("slow request log line");
+        assert!(line.contains("WARN"), "{line}");
+        assert!(line.contains("status=204"), "{line}");
+    }
+
+    #[tokio::test]
+    async fn access_log_records_a_combined_log_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("logs/access.log");
+        let access_log = Arc::new(AccessLog::open(&log_path).unwrap());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut s = TcpStream::connect(addr).await.unwrap();
+            s.write_all(
+                b"GET /favicon.ico HTTP/1.1\r\nHost: test\r\nReferer: http://example.com/\r\nUser-Agent: probe/1.0\r\n\r\n",
+            )
+            .await
+            .unwrap();
+            let mut buf = Vec::new();
+            s.read_to_end(&mut buf).await.unwrap();
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        handle_connection(
+            state,
+            "http0".into(),
+            stream,
+            None,
+            None,
+            None,
+            DEFAULT_HTTP_MAX_HEADER_BYTES,
+            Some(access_log),
+        )
+        .await
+        .unwrap();
+        client.await.unwrap();
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        let line = log.strip_suffix('\n').expect("one newline-terminated line");
+        assert!(!line.contains('\n'), "{log}");
+        let (head, rest) = line.split_once(" [").unwrap();
+        assert_eq!(head, "127.0.0.1 - -");
+        let (time, rest) = rest.split_once("] ").unwrap();
+        // dd/Mon/yyyy:hh:mm:ss +0000
+        assert_eq!(time.len(), 26, "{time}");
+        assert!(time.ends_with(" +0000"), "{time}");
+        assert_eq!(
+            rest,
+            "\"GET /favicon.ico HTTP/1.1\" 204 - \"http://example.com/\" \"probe/1.0\""
+        );
+    }
+
+    #[tokio::test]
+    async fn silent_client_is_dropped_after_read_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        let served = tokio::time::timeout(
+            Duration::from_secs(5),
+            handle_connection(
+                state,
+                "http0".into(),
+                stream,
+                None,
+                None,
+                Some(Duration::from_millis(50)),
+                DEFAULT_HTTP_MAX_HEADER_BYTES,
+                None,
+            ),
+        )
+        .await
+        .expect("connection should close once the read times out");
+        assert!(served.is_ok());
+
+        // The server side is gone: the client reads EOF without a response.
+        let mut buf = Vec::new();
+        client.read_to_end(&mut buf).await.unwrap();
+        assert!(buf.is_empty());
+    }
+
+    #[tokio::test]
+    async fn trickling_client_is_dropped_when_the_request_deadline_passes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+
+        // One byte every 20 ms never trips a 100 ms per-read timeout.
+        let trickle = tokio::spawn(async move {
+            for byte in b"GET /api/health HTTP/1.1\r\nHost: x\r\n".iter().cycle() {
+                if client.write_all(&[*byte]).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        });
+
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        let served = tokio::time::timeout(
+            Duration::from_secs(5),
+            handle_connection(
+                state,
+                "http0".into(),
+                stream,
+                None,
+                None,
+                Some(Duration::from_millis(100)),
+                DEFAULT_HTTP_MAX_HEADER_BYTES,
+                None,
+            ),
+        )
+        .await
+        .expect("connection should close once the request deadline passes");
+        assert!(served.is_ok());
+        trickle.abort();
+    }
+
+    #[tokio::test]
+    async fn cached_tree_is_fetched_once_within_its_ttl() {
+        use araliya_core::bus::{BusMessage, BusPayload};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(
+            CommsState::new(sbus.handle.clone(), ev_tx)
+                .with_response_cache([("/api/tree".to_string(), 60_000)]),
+        );
+        let tree_calls = Arc::new(AtomicUsize::new(0));
+        let calls = tree_calls.clone();
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method, reply_tx, ..
+            }) = sbus.rx.recv().await
+            {
+                assert_eq!(method, "manage/http/tree");
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id: "http0".into(),
+                    content: format!(r#"{{"fetch":{n}}}"#),
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let request = |raw: &'static str| {
+            let state = state.clone();
+            let listener = &listener;
+            async move {
+                let client = tokio::spawn(async move {
+                    let mut s = TcpStream::connect(addr).await.unwrap();
+                    s.write_all(raw.as_bytes()).await.unwrap();
+                    let mut buf = Vec::new();
+                    s.read_to_end(&mut buf).await.unwrap();
+                    String::from_utf8(buf).unwrap()
+                });
+                let (stream, _) = listener.accept().await.unwrap();
+                handle_connection(
+                    state,
+                    "http0".into(),
+                    stream,
+                    None,
+                    None,
+                    None,
+                    DEFAULT_HTTP_MAX_HEADER_BYTES,
+                    None,
+                )
+                .await
+                .unwrap();
+                client.await.unwrap()
+            }
+        };
+        const GET: &str = "GET /api/tree HTTP/1.1\r\nHost: test\r\n\r\n";
+
+        let first = request(GET).await;
+        assert!(first.contains("Cache-Control: max-age=60\r\n"), "{first}");
+        assert!(!first.contains("Age:"), "{first}");
+        let second = request(GET).await;
+        assert!(second.contains("\r\nAge: 0\r\n"), "{second}");
+        assert!(second.ends_with(r#"{"fetch":1}"#), "{second}");
+        assert_eq!(tree_calls.load(Ordering::SeqCst), 1);
+
+        // `?refresh=1` and any POST go back to the bus.
+        let refreshed = request("GET /api/tree?refresh=1 HTTP/1.1\r\nHost: test\r\n\r\n").await;
+        assert!(refreshed.ends_with(r#"{"fetch":2}"#), "{refreshed}");
+        request("POST /api/nothing HTTP/1.1\r\nHost: test\r\nContent-Length: 0\r\n\r\n").await;
+        let after_post = request(GET).await;
+        assert!(after_post.ends_with(r#"{"fetch":3}"#), "{after_post}");
+        assert_eq!(tree_calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn documented_routes_are_served_and_axum_only_ones_are_not() {
+        use araliya_core::bus::{BusError, BusMessage};
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        tokio::spawn(async move {
+            while let Some(msg) = sbus.rx.recv().await {
+                if let BusMessage::Request { reply_tx, .. } = msg {
+                    let _ = reply_tx.send(Err(BusError::new(-32000, "test bus")));
+                }
+            }
+        });
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+
+        for route in openapi::API_ROUTES {
+            let path = route
+                .path
+                .split('/')
+                .map(|s| if s.starts_with('{') { "x" } else { s })
+                .collect::<Vec<_>>()
+                .join("/");
+            let request = format!(
+                "{} {path} HTTP/1.1\r\nHost: test\r\nContent-Length: 2\r\n\r\n{{}}",
+                route.method
+            );
+            let response = serve_on(state.clone(), request).await;
+            let unrouted = response.ends_with("\r\n\r\nnot found\n");
+            assert_eq!(
+                unrouted, route.axum_only,
+                "{} {}: {response}",
+                route.method, route.path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn config_is_served_redacted_only_when_exposed() {
+        use std::io::Write as _;
+
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(b"[supervisor]\nbot_name = \"cfg-bot\"\nwork_dir = \"/tmp/araliya-test\"\nlog_level = \"info\"\n\n[llm]\ndefault = \"dummy\"\n")
+            .unwrap();
+        let mut config = araliya_core::config::load_from(f.path(), None, None).unwrap();
+        config.openai_api_key = Some("sk-very-secret".to_string());
+        const GET: &str = "GET /api/config HTTP/1.1\r\nHost: test\r\n\r\n";
+
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let restricted = Arc::new(CommsState::new(sbus.handle.restricted(), ev_tx.clone()));
+        let response = serve_on(restricted, GET.into()).await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+        let exposed = Arc::new(
+            CommsState::new(sbus.handle.restricted(), ev_tx).with_effective_config(&config),
+        );
+        let response = serve_on(exposed, GET.into()).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(!response.contains("sk-very-secret"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let v: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(v["openai_api_key"], araliya_core::config::REDACTED);
+        assert_eq!(v["bot_name"], "cfg-bot");
+    }
+
+    /// Send `request` through `handle_connection` on `state` and return the
+    /// raw response.
+    async fn serve_on(state: Arc<CommsState>, request: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut s = TcpStream::connect(addr).await.unwrap();
+            s.write_all(request.as_bytes()).await.unwrap();
+            let mut buf = Vec::new();
+            s.read_to_end(&mut buf).await.unwrap();
+            String::from_utf8(buf).unwrap()
+        });
+        let (stream, _) = listener.accept().await.unwrap();
+        handle_connection(
+            state,
+            "http0".into(),
+            stream,
+            None,
+            None,
+            None,
+            DEFAULT_HTTP_MAX_HEADER_BYTES,
+            None,
+        )
+        .await
+        .unwrap();
+        client.await.unwrap()
+    }
+
+    /// Send `request` through `handle_connection` with a `max_header_bytes`
+    /// limit and return the raw response.
+    async fn serve_raw(request: Vec<u8>, max_header_bytes: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut s = TcpStream::connect(addr).await.unwrap();
+            s.write_all(&request).await.unwrap();
+            let mut buf = Vec::new();
+            s.read_to_end(&mut buf).await.unwrap();
+            buf
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = Arc::new(CommsState::new(sbus.handle, ev_tx));
+        handle_connection(
+            state,
+            "http0".into(),
+            stream,
+            None,
+            None,
+            None,
+            max_header_bytes,
+            None,
+        )
+        .await
+        .unwrap();
+
+        String::from_utf8(client.await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn oversized_headers_get_431() {
+        let mut request = b"GET /favicon.ico HTTP/1.1\r\nHost: test\r\n".to_vec();
+        request.extend_from_slice(format!("X-Pad: {}\r\n\r\n", "a".repeat(4096)).as_bytes());
+
+        let response = serve_raw(request.clone(), 1024).await;
+        assert!(
+            response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"),
+            "{response}"
+        );
+        assert!(response.contains("exceed 1024 bytes"), "{response}");
+
+        // The same request fits the default limit.
+        let response = serve_raw(request, DEFAULT_HTTP_MAX_HEADER_BYTES).await;
+        assert!(response.starts_with("HTTP/1.1 204"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn malformed_requests_get_400_or_413() {
+        for request in [
+            "GARBAGE\r\n\r\n",
+            "GET /favicon.ico HTTP/1.1 extra\r\n\r\n",
+            "POST /api/message HTTP/1.1\r\nContent-Length: lots\r\n\r\n",
+        ] {
+            let response = serve_raw(request.into(), DEFAULT_HTTP_MAX_HEADER_BYTES).await;
+            assert!(
+                response.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+                "{request:?}: {response}"
+            );
+        }
+
+        let request = format!(
+            "POST /api/message HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        let response = serve_raw(request.into(), DEFAULT_HTTP_MAX_HEADER_BYTES).await;
+        assert!(
+            response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"),
+            "{response}"
+        );
+    }
+}
@@ -0,0 +1,523 @@
+//! OpenAPI 3 description of the `/api/*` surface, served at
+//! `GET /api/openapi.json` by both the HTTP and axum channels.
+//!
+//! The document is generated from [`API_ROUTES`]; a route added to the
+//! channels without a row here is invisible to generated clients.  Tests
+//! check the table against both channels' routes.
+
+use serde_json::{json, Map, Value};
+
+pub(crate) const OPENAPI_PATH: &str = "/api/openapi.json";
+
+/// One documented endpoint.
+pub(crate) struct ApiRoute {
+    pub method: &'static str,
+    /// Path with `{name}` placeholders for path parameters.
+    pub path: &'static str,
+    pub summary: &'static str,
+    /// Optional `(name, description)` query parameters.
+    pub query: &'static [(&'static str, &'static str)],
+    /// Schema in `components.schemas` for the JSON request body, if any.
+    pub request: Option<&'static str>,
+    /// Schema in `components.schemas` for the `200` response;
+    /// `EventStream` for a server-sent event stream.
+    pub response: &'static str,
+    /// Served by the axum channel only, not the plain HTTP channel.
+    pub axum_only: bool,
+}
+
+const fn route(
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    response: &'static str,
+) -> ApiRoute {
+    ApiRoute {
+        method,
+        path,
+        summary,
+        query: &[],
+        request: None,
+        response,
+        axum_only: false,
+    }
+}
+
+pub(crate) const API_ROUTES: &[ApiRoute] = &[
+    route(
+        "GET",
+        "/api/health",
+        "Health of the bot and every subsystem",
+        "Health",
+    ),
+    route(
+        "POST",
+        "/api/health/refresh",
+        "Re-run subsystem health checks, then report",
+        "Health",
+    ),
+    route("GET", "/api/tree", "Component tree", "Component"),
+    route(
+        "GET",
+        "/api/config",
+        "Effective configuration, secrets redacted; 403 unless `[comms] expose_config`",
+        "Object",
+    ),
+    ApiRoute {
+        request: Some("MessageRequest"),
+        ..route(
+            "POST",
+            "/api/message",
+            "Send a message to an agent and wait for the reply",
+            "MessageResponse",
+        )
+    },
+    ApiRoute {
+        query: &[
+            (
+                "include_archived",
+                "`1` or `true` to list archived sessions too",
+            ),
+            (
+                "sort",
+                "`updated_at` (default), `created_at` or `session_id`",
+            ),
+        ],
+        ..route("GET", "/api/sessions", "List sessions", "SessionList")
+    },
+    route(
+        "GET",
+        "/api/session/{session_id}",
+        "Session transcript",
+        "SessionDetail",
+    ),
+    route(
+        "GET",
+        "/api/sessions/{session_id}/memory",
+        "Session working memory",
+        "Object",
+    ),
+    route(
+        "GET",
+        "/api/sessions/{session_id}/debug",
+        "Session debug snapshot",
+        "Object",
+    ),
+    route(
+        "GET",
+        "/api/sessions/{session_id}/files",
+        "Files in the session directory",
+        "Object",
+    ),
+    route(
+        "GET",
+        "/api/agents/{agent_id}/kg",
+        "Agent knowledge graph",
+        "Object",
+    ),
+    route(
+        "GET",
+        "/api/memory/agents/{agent_id}/kg",
+        "Agent knowledge graph from its memory store",
+        "Object",
+    ),
+    route(
+        "GET",
+        "/api/llm/providers",
+        "LLM provider pool and route hints",
+        "Object",
+    ),
+    ApiRoute {
+        request: Some("SetDefaultProvider"),
+        ..route(
+            "POST",
+            "/api/llm/default",
+            "Switch the active LLM provider",
+            "Object",
+        )
+    },
+    ApiRoute {
+        request: Some("MessageRequest"),
+        axum_only: true,
+        ..route(
+            "POST",
+            "/api/message/stream",
+            "Send a message and stream the reply as it is generated",
+            "EventStream",
+        )
+    },
+    ApiRoute {
+        request: Some("StopStream"),
+        axum_only: true,
+        ..route(
+            "POST",
+            "/api/message/stream/stop",
+            "Stop an in-flight streamed reply",
+            "Object",
+        )
+    },
+    ApiRoute {
+        query: &[("session_id", "Also receive agent steps for this session")],
+        axum_only: true,
+        ..route(
+            "GET",
+            "/api/events",
+            "Comms events: session lifecycle and agent steps",
+            "EventStream",
+        )
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route("GET", "/api/agents", "Registered agents", "Object")
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route(
+            "GET",
+            "/api/agents/{agent_id}/session",
+            "The agent's current session",
+            "Object",
+        )
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route(
+            "GET",
+            "/api/agents/{agent_id}/spend",
+            "The agent's spend across its sessions",
+            "Object",
+        )
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route(
+            "GET",
+            "/api/observe/events",
+            "Observability events",
+            "EventStream",
+        )
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route(
+            "GET",
+            "/api/observe/snapshot",
+            "Buffered observability events",
+            "Object",
+        )
+    },
+    ApiRoute {
+        axum_only: true,
+        ..route(
+            "POST",
+            "/api/observe/clear",
+            "Clear the observability buffer",
+            "Object",
+        )
+    },
+    route("GET", OPENAPI_PATH, "This document", "Object"),
+];
+
+/// The OpenAPI 3 document for [`API_ROUTES`].
+pub(crate) fn document() -> Value {
+    let mut paths = Map::new();
+    for r in API_ROUTES {
+        let mut parameters: Vec<Value> = path_params(r.path)
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        parameters.extend(r.query.iter().map(|(name, description)| {
+            json!({
+                "name": name,
+                "in": "query",
+                "required": false,
+                "description": description,
+                "schema": { "type": "string" },
+            })
+        }));
+
+        let content_type = if r.response == "EventStream" {
+            "text/event-stream"
+        } else {
+            "application/json"
+        };
+        let mut operation = json!({
+            "summary": r.summary,
+            "responses": {
+                "200": {
+                    "description": "OK",
+                    "content": { content_type: { "schema": schema_ref(r.response) } },
+                },
+                "default": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": schema_ref("Error") } },
+                },
+            },
+        });
+        if r.axum_only {
+            operation["description"] = json!("Served by the axum channel only.");
+        }
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if let Some(request) = r.request {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema_ref(request) } },
+            });
+        }
+
+        let item = paths
+            .entry(r.path)
+            .or_insert_with(|| Value::Object(Map::new()));
+        item[r.method.to_ascii_lowercase()] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Araliya Bot API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": { "schemas": schemas() },
+    })
+}
+
+/// Names of the `{name}` placeholders in `path`.
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn schemas() -> Value {
+    json!({
+        "Object": { "type": "object", "additionalProperties": true },
+        "EventStream": {
+            "type": "string",
+            "description": "Server-sent events; each `data:` line is one JSON object.",
+        },
+        "Error": {
+            "type": "object",
+            "properties": {
+                "error": { "type": "string" },
+                "message": { "type": "string" },
+            },
+        },
+        "Health": {
+            "type": "object",
+            "additionalProperties": true,
+            "properties": {
+                "status": { "type": "string" },
+                "uptime_ms": { "type": "integer" },
+                "bot_id": { "type": "string" },
+                "main_process": {
+                    "type": "object",
+                    "description": "The supervisor; `details` lists active cron schedules.",
+                    "properties": {
+                        "id": { "type": "string" },
+                        "status": { "type": "string" },
+                        "uptime_ms": { "type": "integer" },
+                        "details": {
+                            "type": "object",
+                            "properties": {
+                                "cron_active": { "type": "integer" },
+                                "cron_schedules": {
+                                    "type": "array",
+                                    "items": { "type": "object", "additionalProperties": true },
+                                },
+                            },
+                        },
+                    },
+                },
+                "subsystems": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "id": { "type": "string" },
+                            "healthy": { "type": "boolean" },
+                            "message": { "type": "string" },
+                            "details": { "type": "object", "additionalProperties": true },
+                        },
+                    },
+                },
+            },
+        },
+        "Component": {
+            "type": "object",
+            "required": ["id", "name", "status", "state"],
+            "properties": {
+                "id": { "type": "string" },
+                "name": { "type": "string" },
+                "status": { "type": "string" },
+                "state": {},
+                "uptime_ms": { "type": "integer" },
+                "children": {
+                    "type": "array",
+                    "items": { "$ref": "#/components/schemas/Component" },
+                },
+            },
+        },
+        "MessageRequest": {
+            "type": "object",
+            "required": ["message"],
+            "properties": {
+                "message": { "type": "string" },
+                "session_id": { "type": "string" },
+                "agent_id": { "type": "string" },
+                "mode": { "type": "string" },
+                "response_format": { "type": "string", "enum": ["text", "json"] },
+            },
+        },
+        "MessageResponse": {
+            "type": "object",
+            "properties": {
+                "session_id": { "type": "string" },
+                "mode": { "type": "string" },
+                "reply": { "type": "string" },
+                "thinking": { "type": "string", "nullable": true },
+                "working_memory_updated": { "type": "boolean" },
+            },
+        },
+        "SessionList": {
+            "type": "object",
+            "properties": {
+                "sessions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "session_id": { "type": "string" },
+                            "created_at": { "type": "string" },
+                            "updated_at": { "type": "string" },
+                            "store_types": { "type": "array", "items": { "type": "string" } },
+                            "last_agent": { "type": "string", "nullable": true },
+                            "archived": { "type": "boolean" },
+                            "title": { "type": "string", "nullable": true },
+                        },
+                    },
+                },
+            },
+        },
+        "SessionDetail": {
+            "type": "object",
+            "additionalProperties": true,
+            "properties": {
+                "session_id": { "type": "string" },
+                "transcript": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "role": { "type": "string" },
+                            "timestamp": { "type": "string" },
+                            "content": { "type": "string" },
+                        },
+                    },
+                },
+            },
+        },
+        "StopStream": {
+            "type": "object",
+            "required": ["request_id"],
+            "properties": { "request_id": { "type": "string" } },
+        },
+        "SetDefaultProvider": {
+            "type": "object",
+            "required": ["provider"],
+            "properties": { "provider": { "type": "string" } },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_lists_known_paths_with_resolvable_schemas() {
+        let text = document().to_string();
+        let doc: Value = serde_json::from_str(&text).unwrap();
+
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(doc["info"]["title"].is_string());
+        let paths = doc["paths"].as_object().unwrap();
+        for (method, path) in [
+            ("get", "/api/health"),
+            ("get", "/api/tree"),
+            ("post", "/api/message"),
+            ("get", "/api/sessions"),
+            ("get", "/api/session/{session_id}"),
+            ("get", OPENAPI_PATH),
+        ] {
+            assert!(
+                paths[path][method]["responses"]["200"].is_object(),
+                "{method} {path}"
+            );
+        }
+        let params = &paths["/api/session/{session_id}"]["get"]["parameters"];
+        assert_eq!(params[0]["name"], "session_id");
+        assert_eq!(params[0]["in"], "path");
+
+        // Every `$ref` points at a defined schema.
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "undefined schema {name}");
+        }
+    }
+
+    /// `(METHOD, path)` of every `.route(...)` in the axum channel's
+    /// `build_router`, read from its source.
+    #[cfg(feature = "channel-axum")]
+    fn axum_routes() -> Vec<(String, String)> {
+        let source = include_str!("../axum_channel/mod.rs");
+        let start = source.find("fn build_router").unwrap();
+        let end = start + source[start..].find("\"/favicon.ico\"").unwrap();
+        source[start..end]
+            .split(".route(")
+            .skip(1)
+            .map(str::trim_start)
+            .filter(|call| !call.is_empty())
+            .map(|call| {
+                let path = match call.strip_prefix('"') {
+                    Some(rest) => rest[..rest.find('"').unwrap()].to_string(),
+                    None if call.starts_with("crate::http::openapi::OPENAPI_PATH") => {
+                        OPENAPI_PATH.to_string()
+                    }
+                    None => panic!("unrecognised route: {call}"),
+                };
+                let method = if call.contains("post(") {
+                    "POST"
+                } else {
+                    "GET"
+                };
+                (method.to_string(), path)
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "channel-axum")]
+    #[test]
+    fn table_matches_the_axum_router() {
+        let mut routed = axum_routes();
+        routed.sort();
+        let mut documented: Vec<_> = API_ROUTES
+            .iter()
+            .map(|r| (r.method.to_string(), r.path.to_string()))
+            .collect();
+        documented.sort();
+        assert_eq!(routed, documented);
+    }
+}
@@ -1,6 +1,7 @@
 //! UI route handlers — serves the root welcome page and delegates to the
 //! embedded UI backend when the `subsystem-ui` feature is enabled.
 
+use super::HttpConn;
 use araliya_core::error::AppError;
 
 const ROOT_INDEX_HTML: &str = r#"<!doctype html>
@@ -45,7 +46,7 @@ const ROOT_INDEX_HTML: &str = r#"<!doctype html>
 
 // ── Handlers ──────────────────────────────────────────────────────────────────
 
-pub(super) async fn handle_root(socket: &mut tokio::net::TcpStream) -> Result<(), AppError> {
+pub(super) async fn handle_root(socket: &mut HttpConn) -> Result<(), AppError> {
     super::write_response(
         socket,
         "200 OK",
@@ -56,7 +57,7 @@ pub(super) async fn handle_root(socket: &mut tokio::net::TcpStream) -> Result<()
 }
 
 pub(super) async fn handle_ui_path(
-    socket: &mut tokio::net::TcpStream,
+    socket: &mut HttpConn,
     path: &str,
     ui_handle: &super::OptionalUiHandle,
 ) -> Result<(), AppError> {
@@ -72,7 +73,7 @@ pub(super) async fn handle_ui_path(
     handle_not_found(socket).await
 }
 
-pub(super) async fn handle_not_found(socket: &mut tokio::net::TcpStream) -> Result<(), AppError> {
+pub(super) async fn handle_not_found(socket: &mut HttpConn) -> Result<(), AppError> {
     super::write_response(
         socket,
         "404 Not Found",
@@ -0,0 +1,290 @@
+//! Idle timeout for server connections (`[comms] idle_timeout_seconds`).
+//!
+//! [`IdleTimeout`] wraps an accepted socket.  Every read or write that moves
+//! bytes pushes the deadline out; once a read or write has to wait past it,
+//! that call fails with `TimedOut` and the server drops the connection.  A
+//! half-open peer that will never send again is closed instead of holding a
+//! task forever.
+//!
+//! Waiting on a handler is not idleness: while a request on the connection
+//! is in flight ([`InFlight::begin`]), the clock is held, and writing the
+//! response starts it again.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{Instant, Sleep};
+
+/// Requests in flight on one connection, shared between its
+/// [`IdleTimeout`] and the router (as the connection's `ConnectInfo`).
+#[derive(Clone, Default)]
+pub(crate) struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    /// Mark a request in flight until the returned guard drops.
+    pub(crate) fn begin(&self) -> InFlightGuard {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.0.clone())
+    }
+
+    fn busy(&self) -> bool {
+        self.0.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Ends a request's in-flight mark when dropped.
+pub(crate) struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A stream that fails once no bytes have moved for `limit` (`None` =
+/// never; the stream is then a plain pass-through).
+pub(crate) struct IdleTimeout<S> {
+    inner: S,
+    limit: Option<Duration>,
+    deadline: Option<Pin<Box<Sleep>>>,
+    in_flight: InFlight,
+}
+
+impl<S> IdleTimeout<S> {
+    pub(crate) fn new(inner: S, limit: Option<Duration>) -> Self {
+        Self {
+            inner,
+            limit,
+            deadline: limit.map(|limit| Box::pin(tokio::time::sleep(limit))),
+            in_flight: InFlight::default(),
+        }
+    }
+
+    /// The connection's in-flight request count.
+    pub(crate) fn in_flight(&self) -> InFlight {
+        self.in_flight.clone()
+    }
+
+    fn touch(&mut self) {
+        if let (Some(limit), Some(deadline)) = (self.limit, &mut self.deadline) {
+            deadline.as_mut().reset(Instant::now() + limit);
+        }
+    }
+
+    /// The result for an I/O call that is waiting on the peer.
+    fn pending<T>(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<T>> {
+        if self.in_flight.busy() {
+            // A handler is working: restart the clock rather than run it.
+            self.touch();
+            return Poll::Pending;
+        }
+        let Some(deadline) = &mut self.deadline else {
+            return Poll::Pending;
+        };
+        match deadline.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "connection idle",
+            ))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleTimeout<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(result) => {
+                if buf.filled().len() > before {
+                    this.touch();
+                }
+                Poll::Ready(result)
+            }
+            Poll::Pending => this.pending(cx),
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleTimeout<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inner).poll_write(cx, buf) {
+            Poll::Ready(result) => {
+                if matches!(result, Ok(n) if n > 0) {
+                    this.touch();
+                }
+                Poll::Ready(result)
+            }
+            Poll::Pending => this.pending(cx),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.inner).poll_flush(cx) {
+            Poll::Ready(result) => Poll::Ready(result),
+            Poll::Pending => this.pending(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// A TCP listener whose connections close after `idle` without traffic
+/// (`None` = never), for `axum::serve`.
+pub(crate) struct IdleListener {
+    listener: TcpListener,
+    idle: Option<Duration>,
+}
+
+impl IdleListener {
+    pub(crate) fn new(listener: TcpListener, idle: Option<Duration>) -> Self {
+        Self { listener, idle }
+    }
+}
+
+impl axum::serve::Listener for IdleListener {
+    type Io = IdleTimeout<TcpStream>;
+    type Addr = std::net::SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, addr) = axum::serve::Listener::accept(&mut self.listener).await;
+        (IdleTimeout::new(stream, self.idle), addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+impl axum::extract::connect_info::Connected<axum::serve::IncomingStream<'_, IdleListener>>
+    for InFlight
+{
+    fn connect_info(stream: axum::serve::IncomingStream<'_, IdleListener>) -> Self {
+        stream.io().in_flight()
+    }
+}
+
+/// Middleware: hold the connection's idle clock while the request runs.
+/// Requests served without an [`IdleListener`] (e.g. router tests) pass
+/// straight through.
+pub(crate) async fn hold_while_in_flight(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let _busy = request
+        .extensions()
+        .get::<axum::extract::ConnectInfo<InFlight>>()
+        .map(|info| info.0.begin());
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test(start_paused = true)]
+    async fn silent_peer_times_out_and_traffic_keeps_it_open() {
+        let (mut peer, ours) = tokio::io::duplex(64);
+        let mut conn = IdleTimeout::new(ours, Some(Duration::from_secs(10)));
+        let mut buf = [0u8; 8];
+
+        // Bytes every 6 s keep the connection alive past the 10 s limit.
+        for _ in 0..3 {
+            tokio::time::sleep(Duration::from_secs(6)).await;
+            peer.write_all(b"ping").await.unwrap();
+            assert_eq!(conn.read(&mut buf).await.unwrap(), 4);
+        }
+
+        // Then the peer goes quiet.
+        let started = Instant::now();
+        let err = conn.read(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn axum_closes_a_silent_keep_alive_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = axum::Router::new().route("/", axum::routing::get(|| async { "ok" }));
+        let listener = IdleListener::new(listener, Some(Duration::from_millis(200)));
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: test\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let n = client.read(&mut buf).await.unwrap();
+        assert!(buf[..n].starts_with(b"HTTP/1.1 200"));
+
+        // The client keeps the connection but never sends again: the server
+        // hangs up once the idle limit passes.
+        let started = std::time::Instant::now();
+        let n = tokio::time::timeout(Duration::from_secs(5), client.read(&mut buf))
+            .await
+            .expect("server kept the idle connection open")
+            .unwrap_or(0);
+        assert_eq!(n, 0);
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn a_slow_handler_outlasts_the_idle_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = axum::Router::new()
+            .route(
+                "/",
+                axum::routing::get(|| async {
+                    tokio::time::sleep(Duration::from_millis(600)).await;
+                    "slow"
+                }),
+            )
+            .layer(axum::middleware::from_fn(hold_while_in_flight));
+        let listener = IdleListener::new(listener, Some(Duration::from_millis(200)));
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<InFlight>(),
+            )
+            .await
+        });
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: test\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        let n = tokio::time::timeout(Duration::from_secs(5), client.read(&mut buf))
+            .await
+            .expect("no response")
+            .unwrap();
+        let response = String::from_utf8_lossy(&buf[..n]);
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("slow"), "{response}");
+    }
+}
@@ -1,27 +1,36 @@
 //! Comms subsystem — manages all external I/O channels.
 //!
-//! Each channel (PTY, HTTP, Telegram…) implements [`araliya_core::runtime::Component`]
+//! Each channel (PTY, HTTP, Telegram, email…) implements [`araliya_core::runtime::Component`]
 //! and is spawned as an independent concurrent task by [`start`].
 
 #[cfg(feature = "channel-axum")]
 pub mod axum_channel;
+pub mod cache;
+#[cfg(feature = "channel-email")]
+pub mod email;
 pub mod http;
+#[cfg(feature = "channel-axum")]
+mod idle;
 pub mod pty;
+mod recent;
+pub mod sanitize;
 pub mod state;
 #[cfg(feature = "channel-telegram")]
 pub mod telegram;
 
-pub use state::{CommsEvent, CommsState};
+pub use state::{pending_shutdown_message, CommsEvent, CommsState};
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, OnceLock};
 
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, oneshot};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
 #[cfg(feature = "subsystem-ui")]
 use araliya_core::ui::UiServeHandle;
 
+use araliya_core::bus::health::HealthRegistry;
 use araliya_core::bus::{
     BusError, BusHandle, BusHandler, BusPayload, BusResult, ComponentInfo, ComponentStatusResponse,
     ERR_METHOD_NOT_FOUND,
@@ -38,6 +47,10 @@ use araliya_core::runtime::{spawn_components, Component, SubsystemHandle};
 pub struct CommsStatusHandler {
     comms_info: Arc<OnceLock<ComponentInfo>>,
     reporter: Option<araliya_core::bus::health::HealthReporter>,
+    /// Per-channel liveness written by [`start`]; keyed by channel id.
+    channel_health: Option<HealthRegistry>,
+    /// Where `comms/agent_step` notifications are relayed for channels.
+    events: Option<CommsEvents>,
 }
 
 impl CommsStatusHandler {
@@ -45,9 +58,26 @@ impl CommsStatusHandler {
         Self {
             comms_info,
             reporter: None,
+            channel_health: None,
+            events: None,
         }
     }
 
+    /// Relay `comms/agent_step` notifications as [`CommsEvent::AgentStep`]
+    /// on `events`, the channel later passed to [`start`], and report its
+    /// dropped-event count in `comms/detailed_status`.
+    pub fn with_events(mut self, events: CommsEvents) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Report `comms/{channel_id}/status` from the registry passed to
+    /// [`start`] instead of assuming every configured channel is running.
+    pub fn with_channel_health(mut self, registry: HealthRegistry) -> Self {
+        self.channel_health = Some(registry);
+        self
+    }
+
     pub fn with_health_reporter(
         mut self,
         reporter: araliya_core::bus::health::HealthReporter,
@@ -69,6 +99,28 @@ impl BusHandler for CommsStatusHandler {
             .unwrap_or_else(|| ComponentInfo::running("comms", "Comms", vec![]))
     }
 
+    fn handle_notification(&self, method: &str, payload: BusPayload) {
+        match (method, payload, &self.events) {
+            (
+                "comms/agent_step",
+                BusPayload::AgentStep {
+                    channel_id,
+                    session_id,
+                    step,
+                },
+                Some(events),
+            ) => {
+                // No subscriber means no channel is interested; drop it.
+                let _ = events.tx.send(CommsEvent::AgentStep {
+                    channel_id,
+                    session_id,
+                    step,
+                });
+            }
+            (method, _, _) => debug!(method, "comms: ignoring notification"),
+        }
+    }
+
     fn handle_request(
         &self,
         method: &str,
@@ -94,6 +146,7 @@ impl BusHandler for CommsStatusHandler {
                 "status": "running",
                 "state": "on",
                 "channels": channel_ids,
+                "dropped_events": self.events.as_ref().map_or(0, CommsEvents::dropped),
             });
             let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
                 data: data.to_string(),
@@ -111,14 +164,30 @@ impl BusHandler for CommsStatusHandler {
                 .get()
                 .map(|info| info.children.iter().any(|c| c.id == channel_id))
                 .unwrap_or(false);
-            let resp = if exists {
-                ComponentStatusResponse::running(channel_id)
-            } else {
-                ComponentStatusResponse::error(channel_id, "not found")
+            if !exists {
+                let resp = ComponentStatusResponse::error(channel_id, "not found");
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: resp.to_json(),
+                }));
+                return;
+            }
+            let Some(registry) = self.channel_health.clone() else {
+                let resp = ComponentStatusResponse::running(channel_id);
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: resp.to_json(),
+                }));
+                return;
             };
-            let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
-                data: resp.to_json(),
-            }));
+            let channel_id = channel_id.to_string();
+            tokio::spawn(async move {
+                let resp = match registry.reporter(&channel_id).get_current().await {
+                    Some(h) if !h.healthy => ComponentStatusResponse::error(channel_id, h.message),
+                    _ => ComponentStatusResponse::running(channel_id),
+                };
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: resp.to_json(),
+                }));
+            });
             return;
         }
 
@@ -131,6 +200,92 @@ impl BusHandler for CommsStatusHandler {
 
 // ── start ───────────────────────────────────────────────────────────────────
 
+/// The broadcast channel for [`CommsEvent`]s, and how many events the comms
+/// event log has missed on it.  Sending never waits: a subscriber more than
+/// the capacity behind skips the oldest events instead of stalling channels.
+#[derive(Clone)]
+pub struct CommsEvents {
+    pub tx: broadcast::Sender<CommsEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl CommsEvents {
+    /// Events the comms event log skipped because it fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Create the [`CommsEvents`] channel holding `capacity` events
+/// (`[comms] event_buffer`).  Created before [`start`] so
+/// [`CommsStatusHandler::with_events`] can relay agent steps into it.
+pub fn event_channel(capacity: usize) -> CommsEvents {
+    CommsEvents {
+        tx: broadcast::channel(capacity.max(1)).0,
+        dropped: Arc::new(AtomicU64::new(0)),
+    }
+}
+
+/// Log `rx`'s events until every sender is gone, counting the ones it
+/// lagged past into `dropped`.
+async fn log_events(mut rx: broadcast::Receiver<CommsEvent>, dropped: Arc<AtomicU64>) {
+    loop {
+        match rx.recv().await {
+            Ok(CommsEvent::ChannelShutdown { ref channel_id }) => {
+                debug!(channel_id, "channel reported shutdown");
+            }
+            Ok(CommsEvent::SessionStarted { ref channel_id }) => {
+                debug!(channel_id, "channel session started");
+            }
+            Ok(CommsEvent::SessionEnded { ref channel_id }) => {
+                debug!(channel_id, "channel session ended");
+            }
+            Ok(CommsEvent::AgentStep {
+                ref channel_id,
+                ref step,
+                ..
+            }) => {
+                debug!(channel_id, step, "agent step");
+            }
+            Ok(CommsEvent::ShuttingDown { ref message }) => {
+                debug!(message, "shutdown message broadcast");
+            }
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                let total = dropped.fetch_add(n, Ordering::Relaxed) + n;
+                debug!(skipped = n, total, "comms event log lagged");
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+/// The token channels stop on.  It is cancelled once `shutdown` is, right
+/// after `message` (`[comms] shutdown_message`) goes out as
+/// [`CommsEvent::ShuttingDown`], so a channel that drains its events on
+/// shutdown (see [`pending_shutdown_message`]) always finds it.  A failed
+/// channel cancels it from below, which is passed on to `shutdown`.
+fn channel_shutdown(
+    shutdown: CancellationToken,
+    message: Option<String>,
+    events: broadcast::Sender<CommsEvent>,
+) -> CancellationToken {
+    let channels = CancellationToken::new();
+    let token = channels.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                if let Some(message) = message {
+                    info!("broadcasting shutdown message to channels");
+                    let _ = events.send(CommsEvent::ShuttingDown { message });
+                }
+                token.cancel();
+            }
+            _ = token.cancelled() => shutdown.cancel(),
+        }
+    });
+    channels
+}
+
 /// Spawn all configured comms channels and return a [`SubsystemHandle`].
 #[allow(clippy::too_many_arguments)]
 pub fn start(
@@ -143,13 +298,42 @@ pub fn start(
     >,
     #[cfg(feature = "plugin-homebuilder")] notes_dir: Option<std::path::PathBuf>,
     comms_info: Arc<OnceLock<ComponentInfo>>,
+    // Per-channel liveness, read by `CommsStatusHandler::with_channel_health`.
+    channel_health: HealthRegistry,
+    // From `event_channel()`; shared with `CommsStatusHandler::with_events`.
+    events: CommsEvents,
     stdio_control_active: bool,
     // Observability bus — forwarded to the Axum channel for the SSE stream endpoint.
     // Pass `None` to disable live event streaming (e.g. in minimal builds).
     #[cfg(feature = "channel-axum")] obs_bus: Option<ObsBus>,
 ) -> SubsystemHandle {
-    let (event_tx, event_rx) = mpsc::channel::<CommsEvent>(32);
-    let state = Arc::new(CommsState::new(bus, event_tx));
+    let event_rx = events.tx.subscribe();
+    let mut comms_state = CommsState::new(bus, events.tx.clone())
+        .with_input_limits(sanitize::InputLimits::from(&config.comms))
+        .with_auto_session(config.comms.auto_session)
+        .with_command_prefix(config.comms.command_prefix.clone())
+        .with_error_message(config.agents.error_message.clone())
+        .with_response_cache(config.comms.http.cache_ttl_ms.clone());
+    if config.comms.expose_config {
+        comms_state = comms_state.with_effective_config(config);
+    }
+    if !config.comms.http.persist_sessions {
+        comms_state = comms_state.with_ephemeral_sessions("http0");
+    }
+    if config.comms.inline_routing {
+        comms_state = comms_state.with_inline_routing(config.agents.enabled.iter().cloned());
+    }
+    if config.comms.agent_switching {
+        comms_state = comms_state.with_agent_switching(config.agents.enabled.iter().cloned());
+    }
+    if config.comms.pty.tool_retry {
+        comms_state = comms_state.with_tool_retry("pty0");
+    }
+    #[cfg(feature = "channel-email")]
+    if config.comms_email_should_load() {
+        comms_state = comms_state.with_conversation_sessions(email::CHANNEL_PREFIX);
+    }
+    let state = Arc::new(comms_state);
 
     let mut components: Vec<Box<dyn Component>> = Vec::new();
 
@@ -171,12 +355,32 @@ pub fn start(
     {
         if config.comms_telegram_should_load() {
             info!("loading telegram channel");
-            components.push(Box::new(telegram::TelegramChannel::new(
-                "telegram0",
+            components.push(Box::new(
+                telegram::TelegramChannel::new("telegram0", state.clone())
+                    .with_respond_mode(config.comms.telegram.respond_mode),
+            ));
+        }
+    }
+
+    #[cfg(feature = "channel-email")]
+    {
+        if config.comms_email_should_load() {
+            info!(host = %config.comms.email.imap_host, "loading email channel");
+            components.push(Box::new(email::EmailChannel::new(
+                "email0",
                 state.clone(),
+                config.comms.email.clone(),
             )));
         }
     }
+    #[cfg(not(feature = "channel-email"))]
+    if config.comms_email_should_load() {
+        tracing::warn!(
+            "config has [comms.email] enabled = true but this binary was compiled \
+             without the `channel-email` feature — channel will not start. \
+             Rebuild with `--features channel-email` or set enabled = false."
+        );
+    }
 
     #[cfg(feature = "channel-http")]
     {
@@ -186,12 +390,24 @@ pub fn start(
             let ui = ui_handle.clone();
             #[cfg(not(feature = "subsystem-ui"))]
             let ui: Option<()> = None;
-            components.push(Box::new(http::HttpChannel::new(
-                "http0",
-                config.comms.http.bind.clone(),
-                state.clone(),
-                ui,
-            )));
+            components.push(Box::new(
+                http::HttpChannel::new("http0", config.comms.http.bind.clone(), state.clone(), ui)
+                    .with_slow_request_ms(config.comms.http.slow_request_ms)
+                    // One request per connection: the idle timeout only
+                    // matters while reading it.
+                    .with_read_timeout_ms(
+                        config.comms.http.read_timeout_ms.or(config
+                            .comms
+                            .idle_timeout_seconds
+                            .map(|secs| secs.saturating_mul(1_000))),
+                    )
+                    .with_max_header_bytes(config.comms.http.max_header_bytes)
+                    .with_access_log(config.comms.http.access_log.clone())
+                    .with_max_connections(
+                        config.comms.http.max_connections,
+                        config.comms.http.connection_overflow,
+                    ),
+            ));
         }
     }
     #[cfg(not(feature = "channel-http"))]
@@ -211,17 +427,20 @@ pub fn start(
             let ui = ui_handle.clone();
             #[cfg(not(feature = "subsystem-ui"))]
             let ui: Option<()> = None;
-            components.push(Box::new(axum_channel::AxumChannel::new(
-                "axum0",
-                config.comms.axum_channel.bind.clone(),
-                state.clone(),
-                ui,
-                obs_bus,
-                #[cfg(any(feature = "plugin-homebuilder", feature = "plugin-webbuilder"))]
-                preview_root.clone(),
-                #[cfg(feature = "plugin-homebuilder")]
-                notes_dir,
-            )));
+            components.push(Box::new(
+                axum_channel::AxumChannel::new(
+                    "axum0",
+                    config.comms.axum_channel.bind.clone(),
+                    state.clone(),
+                    ui,
+                    obs_bus,
+                    #[cfg(any(feature = "plugin-homebuilder", feature = "plugin-webbuilder"))]
+                    preview_root.clone(),
+                    #[cfg(feature = "plugin-homebuilder")]
+                    notes_dir,
+                )
+                .with_idle_timeout_secs(config.comms.idle_timeout_seconds),
+            ));
         }
     }
     #[cfg(not(feature = "channel-axum"))]
@@ -245,19 +464,181 @@ pub fn start(
         let _ = comms_info.set(ComponentInfo::running("comms", "Comms", channel_children));
     }
 
-    tokio::spawn(async move {
-        let mut rx = event_rx;
-        while let Some(event) = rx.recv().await {
-            match event {
-                CommsEvent::ChannelShutdown { ref channel_id } => {
-                    debug!(channel_id, "channel reported shutdown");
-                }
-                CommsEvent::SessionStarted { ref channel_id } => {
-                    debug!(channel_id, "channel session started");
-                }
-            }
+    tokio::spawn(log_events(event_rx, events.dropped.clone()));
+
+    let channels = channel_shutdown(
+        shutdown,
+        config.comms.shutdown_message.clone(),
+        events.tx.clone(),
+    );
+    spawn_components(components, channels, Some(channel_health))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::error::AppError;
+    use araliya_core::runtime::ComponentFuture;
+
+    /// A channel whose task returns as soon as it starts.
+    struct ExitingChannel;
+
+    impl Component for ExitingChannel {
+        fn id(&self) -> &str {
+            "dead0"
         }
-    });
 
-    spawn_components(components, shutdown)
+        fn run(self: Box<Self>, _shutdown: CancellationToken) -> ComponentFuture {
+            Box::pin(async { Ok::<(), AppError>(()) })
+        }
+    }
+
+    async fn channel_status(
+        handler: &CommsStatusHandler,
+        channel_id: &str,
+    ) -> ComponentStatusResponse {
+        let (tx, rx) = oneshot::channel();
+        handler.handle_request(&format!("comms/{channel_id}/status"), BusPayload::Empty, tx);
+        match rx.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => serde_json::from_str(&data).unwrap(),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn exited_channel_is_not_reported_running() {
+        let comms_info = Arc::new(OnceLock::new());
+        let _ = comms_info.set(ComponentInfo::running(
+            "comms",
+            "Comms",
+            vec![ComponentInfo::leaf("dead0", "Dead0")],
+        ));
+        let channel_health = HealthRegistry::new();
+        let handler =
+            CommsStatusHandler::new(comms_info).with_channel_health(channel_health.clone());
+
+        let components: Vec<Box<dyn Component>> = vec![Box::new(ExitingChannel)];
+        spawn_components(components, CancellationToken::new(), Some(channel_health))
+            .join()
+            .await
+            .unwrap();
+
+        let status = channel_status(&handler, "dead0").await;
+        assert_eq!(status.state, araliya_core::bus::ComponentStatus::Err);
+        assert_eq!(status.status, "exited");
+    }
+
+    /// A channel that records the shutdown message it finds on exit.
+    struct FakeChannel {
+        events: broadcast::Receiver<CommsEvent>,
+        seen: Arc<std::sync::Mutex<Option<String>>>,
+    }
+
+    impl Component for FakeChannel {
+        fn id(&self) -> &str {
+            "fake0"
+        }
+
+        fn run(mut self: Box<Self>, shutdown: CancellationToken) -> ComponentFuture {
+            Box::pin(async move {
+                shutdown.cancelled().await;
+                *self.seen.lock().unwrap() = pending_shutdown_message(&mut self.events);
+                Ok::<(), AppError>(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_message_reaches_channels_before_they_exit() {
+        let events = event_channel(8);
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        let channel = FakeChannel {
+            events: events.tx.subscribe(),
+            seen: seen.clone(),
+        };
+        let shutdown = CancellationToken::new();
+        let channels = channel_shutdown(
+            shutdown.clone(),
+            Some("Going offline for an upgrade.".to_string()),
+            events.tx.clone(),
+        );
+        let handle = spawn_components(vec![Box::new(channel)], channels, None);
+
+        shutdown.cancel();
+        tokio::time::timeout(std::time::Duration::from_secs(5), handle.join())
+            .await
+            .expect("channel did not exit")
+            .unwrap();
+        assert_eq!(
+            seen.lock().unwrap().as_deref(),
+            Some("Going offline for an upgrade.")
+        );
+    }
+
+    #[test]
+    fn agent_step_notifications_are_relayed_as_comms_events() {
+        let events = event_channel(8);
+        let mut rx = events.tx.subscribe();
+        let handler =
+            CommsStatusHandler::new(Arc::new(OnceLock::new())).with_events(events.clone());
+
+        handler.handle_notification(
+            "comms/agent_step",
+            BusPayload::AgentStep {
+                channel_id: "pty0".into(),
+                session_id: Some("s1".into()),
+                step: "calling tool docs/search".into(),
+            },
+        );
+        handler.handle_notification("comms/other", BusPayload::Empty);
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({
+                "event": "agent_step",
+                "channel_id": "pty0",
+                "session_id": "s1",
+                "step": "calling tool docs/search",
+            })
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn flooded_events_never_block_and_are_counted_as_dropped() {
+        let events = event_channel(4);
+        let rx = events.tx.subscribe();
+        let handler =
+            CommsStatusHandler::new(Arc::new(OnceLock::new())).with_events(events.clone());
+        let state = CommsState::new(
+            araliya_core::bus::SupervisorBus::new(1).handle,
+            events.tx.clone(),
+        );
+
+        // Nothing drains the channel while the reporter floods it.
+        for n in 0..10 {
+            state.report_event(CommsEvent::SessionStarted {
+                channel_id: format!("axum{n}"),
+            });
+        }
+        // The log task then skips what no longer fits and reads the rest.
+        tokio::spawn(log_events(rx, events.dropped.clone()));
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while events.dropped() < 6 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(events.dropped(), 6);
+
+        let (tx, reply) = oneshot::channel();
+        handler.handle_request("comms/detailed_status", BusPayload::Empty, tx);
+        let Ok(BusPayload::JsonResponse { data }) = reply.await.unwrap() else {
+            panic!("unexpected reply");
+        };
+        let status: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(status["dropped_events"], 6);
+    }
 }
@@ -1,13 +1,15 @@
 //! PTY (console) comms channel — reads lines from stdin, sends to supervisor,
-//! prints the reply to stdout.
+//! prints the reply to stdout.  Agent steps (`[agents] emit_steps`) for this
+//! console are printed as dim lines while a reply is pending.
 
 use std::sync::Arc;
 
 use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
-use crate::state::{CommsEvent, CommsState};
+use crate::state::{pending_shutdown_message, CommsEvent, CommsState};
 use araliya_core::error::AppError;
 use araliya_core::runtime::{Component, ComponentFuture};
 
@@ -49,6 +51,10 @@ async fn run_pty(
     println!(" Araliya console  (Ctrl-C to quit)");
     println!("─────────────────────────────────");
 
+    let steps = tokio::spawn(print_steps(state.subscribe_events(), channel_id.clone()));
+    // Read only on shutdown, for `[comms] shutdown_message`.
+    let mut notices = state.subscribe_events();
+
     let stdin = tokio::io::stdin();
     let mut lines = BufReader::new(stdin).lines();
 
@@ -61,6 +67,7 @@ async fn run_pty(
             biased;
 
             _ = shutdown.cancelled() => {
+                print_shutdown_message(&mut notices);
                 println!("\n[pty] shutdown signal received — closing console channel");
                 info!("pty channel shutting down");
                 break;
@@ -88,9 +95,10 @@ async fn run_pty(
                             biased;
                             _ = shutdown.cancelled() => {
                                 println!();
+                                print_shutdown_message(&mut notices);
                                 break;
                             }
-                            r = state.send_message(&channel_id, input, None, None) => r,
+                            r = state.send_message_from(&channel_id, &channel_id, input, None, None) => r,
                         };
                         match result {
                             Err(e) => {
@@ -105,6 +113,29 @@ async fn run_pty(
         }
     }
 
+    steps.abort();
     state.report_event(CommsEvent::ChannelShutdown { channel_id });
     Ok(())
 }
+
+/// Print the `[comms] shutdown_message` broadcast, if one was.
+fn print_shutdown_message(notices: &mut broadcast::Receiver<CommsEvent>) {
+    if let Some(message) = pending_shutdown_message(notices) {
+        println!("\n{message}");
+    }
+}
+
+/// Print agent steps addressed to `channel_id` as dim (`SGR 2`) lines.
+async fn print_steps(mut events: broadcast::Receiver<CommsEvent>, channel_id: String) {
+    loop {
+        match events.recv().await {
+            Ok(CommsEvent::AgentStep {
+                channel_id: target,
+                step,
+                ..
+            }) if target == channel_id => println!("\u{1b}[2m  · {step}\u{1b}[0m"),
+            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
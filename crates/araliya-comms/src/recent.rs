@@ -0,0 +1,87 @@
+//! A map that keeps only its most recently used entries.
+//!
+//! Per-conversation state on [`crate::CommsState`] is keyed by ids the
+//! outside world picks (Telegram chats, email senders), so it is capped:
+//! once full, inserting a new key forgets the least recently used one.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub(crate) struct RecentMap<K, V> {
+    cap: usize,
+    /// Use counter; an entry's stamp is the count at its last use.
+    clock: u64,
+    entries: HashMap<K, (V, u64)>,
+}
+
+impl<K: Eq + Hash + Clone, V> RecentMap<K, V> {
+    /// An empty map holding at most `cap` entries (at least one).
+    pub(crate) fn new(cap: usize) -> Self {
+        Self {
+            cap: cap.max(1),
+            clock: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The value for `key`, marking it used.
+    pub(crate) fn get(&mut self, key: &K) -> Option<&V> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(key).map(|(value, used)| {
+            *used = clock;
+            &*value
+        })
+    }
+
+    /// Set `key`, forgetting the least recently used entry when a new key
+    /// would exceed the cap.
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.clock += 1;
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.cap {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    pub(crate) fn remove(&mut self, key: &K) -> Option<V> {
+        self.entries.remove(key).map(|(value, _)| value)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn least_recently_used_entry_is_forgotten_when_full() {
+        let mut map = RecentMap::new(2);
+        map.insert("a", 1);
+        map.insert("b", 2);
+        // Reading `a` makes `b` the oldest.
+        assert_eq!(map.get(&"a"), Some(&1));
+        map.insert("c", 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&"b"), None);
+        assert_eq!(map.get(&"a"), Some(&1));
+        assert_eq!(map.get(&"c"), Some(&3));
+
+        // Replacing a key never evicts another.
+        map.insert("c", 4);
+        assert_eq!(map.get(&"a"), Some(&1));
+        assert_eq!(map.remove(&"c"), Some(4));
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,189 @@
+//! Inbound message sanitization shared by all comms channels.
+//!
+//! Every channel funnels user text through [`CommsState`](crate::state::CommsState),
+//! which calls [`sanitize_input`] before anything reaches the bus.  The helper
+//! optionally strips terminal control sequences (so pasted ANSI escapes cannot
+//! corrupt the PTY display) and caps the message at `[comms] max_input_chars`.
+
+use araliya_core::config::{CommsConfig, InputOverflow};
+
+/// Resolved input limits applied by [`CommsState`](crate::state::CommsState).
+#[derive(Debug, Clone)]
+pub struct InputLimits {
+    /// Maximum accepted characters per message. `None` = unlimited.
+    pub max_chars: Option<usize>,
+    /// What to do with messages longer than `max_chars`.
+    pub overflow: InputOverflow,
+    /// Strip ANSI escape sequences and other control characters.
+    pub strip_control: bool,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: None,
+            overflow: InputOverflow::Truncate,
+            strip_control: false,
+        }
+    }
+}
+
+impl From<&CommsConfig> for InputLimits {
+    fn from(cfg: &CommsConfig) -> Self {
+        Self {
+            max_chars: cfg.max_input_chars,
+            overflow: cfg.input_overflow.clone(),
+            strip_control: cfg.strip_control_chars,
+        }
+    }
+}
+
+/// Result of [`sanitize_input`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanitizedInput {
+    /// Cleaned (and possibly truncated) text.
+    pub text: String,
+    /// `true` when the text was cut at `max_chars`.
+    pub truncated: bool,
+    /// Character count after control stripping but before truncation.
+    pub original_chars: usize,
+}
+
+/// Clean `text` for delivery to an agent.
+///
+/// When `strip_control` is set, ANSI/VT escape sequences (CSI, OSC and
+/// two-byte `ESC x` forms) and C0/C1 control characters are removed; newlines
+/// and tabs are kept.  The result is then truncated to `max_chars` characters
+/// (not bytes), so multi-byte Unicode is never split.
+pub fn sanitize_input(text: &str, max_chars: Option<usize>, strip_control: bool) -> SanitizedInput {
+    let cleaned = if strip_control {
+        strip_control_chars(text)
+    } else {
+        text.to_string()
+    };
+
+    let original_chars = cleaned.chars().count();
+    match max_chars {
+        Some(max) if original_chars > max => SanitizedInput {
+            text: cleaned.chars().take(max).collect(),
+            truncated: true,
+            original_chars,
+        },
+        _ => SanitizedInput {
+            text: cleaned,
+            truncated: false,
+            original_chars,
+        },
+    }
+}
+
+/// Remove terminal escape sequences and non-printing control characters.
+fn strip_control_chars(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\u{1b}' => match chars.peek() {
+                // CSI: ESC [ params… final byte in 0x40..=0x7E
+                Some('[') => {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if ('\u{40}'..='\u{7e}').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: ESC ] … terminated by BEL or ST (ESC \)
+                Some(']') => {
+                    chars.next();
+                    while let Some(c) = chars.next() {
+                        if c == '\u{07}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Two-character escape (ESC c, ESC 7, …)
+                Some(_) => {
+                    chars.next();
+                }
+                None => {}
+            },
+            '\n' | '\t' => out.push(ch),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_short_input_unchanged() {
+        let r = sanitize_input("hello", Some(10), true);
+        assert_eq!(r.text, "hello");
+        assert!(!r.truncated);
+        assert_eq!(r.original_chars, 5);
+    }
+
+    #[test]
+    fn input_at_limit_is_not_truncated() {
+        let r = sanitize_input("abcde", Some(5), false);
+        assert_eq!(r.text, "abcde");
+        assert!(!r.truncated);
+    }
+
+    #[test]
+    fn truncates_past_limit_on_char_boundary() {
+        let r = sanitize_input("héllo wörld", Some(5), false);
+        assert_eq!(r.text, "héllo");
+        assert!(r.truncated);
+        assert_eq!(r.original_chars, 11);
+    }
+
+    #[test]
+    fn no_limit_keeps_everything() {
+        let long = "x".repeat(100_000);
+        let r = sanitize_input(&long, None, false);
+        assert_eq!(r.text.len(), 100_000);
+        assert!(!r.truncated);
+    }
+
+    #[test]
+    fn strips_ansi_csi_and_osc_sequences() {
+        let input = "\u{1b}[31mred\u{1b}[0m \u{1b}]0;title\u{07}text \u{1b}]8;;x\u{1b}\\link";
+        let r = sanitize_input(input, None, true);
+        assert_eq!(r.text, "red text link");
+    }
+
+    #[test]
+    fn strips_control_chars_but_keeps_newlines_tabs_and_unicode() {
+        let input = "a\u{0}b\u{7}c\r\n\td\u{9b}e — 日本語 🌸";
+        let r = sanitize_input(input, None, true);
+        assert_eq!(r.text, "abc\n\tde — 日本語 🌸");
+    }
+
+    #[test]
+    fn control_chars_kept_when_stripping_disabled() {
+        let input = "\u{1b}[1mbold";
+        let r = sanitize_input(input, None, false);
+        assert_eq!(r.text, input);
+    }
+
+    #[test]
+    fn limit_applies_after_stripping() {
+        let r = sanitize_input("\u{1b}[31mabcdef", Some(3), true);
+        assert_eq!(r.text, "abc");
+        assert!(r.truncated);
+        assert_eq!(r.original_chars, 6);
+    }
+}
@@ -1,11 +1,22 @@
 //! Shared state for the Comms subsystem — capability boundary for channels.
 
-use tokio::sync::mpsc;
-use tracing::warn;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
 
-use araliya_core::bus::{BusHandle, BusPayload, StreamReceiver};
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+use araliya_core::bus::message::ERR_TOOL_FAILED;
+use araliya_core::bus::{acl, BusHandle, BusPayload, StreamReceiver};
+use araliya_core::config::{InputOverflow, DEFAULT_AGENT_ERROR_MESSAGE, DEFAULT_COMMAND_PREFIX};
 use araliya_core::error::AppError;
-use araliya_core::types::llm::StreamChunk;
+use araliya_core::types::llm::{ResponseFormat, StreamChunk};
+
+use crate::cache::{CachedResponse, ResponseCache};
+use crate::recent::RecentMap;
+use crate::sanitize::{sanitize_input, InputLimits};
 
 #[derive(Debug, Clone)]
 pub struct CommsReply {
@@ -18,24 +29,597 @@ pub struct CommsReply {
 
 // ── Events ────────────────────────────────────────────────────────────────────
 
-#[derive(Debug)]
+/// Lifecycle events reported by channels, plus agent steps relayed from the
+/// bus (`comms/agent_step`, see [`crate::CommsStatusHandler`]).
+///
+/// Fanned out over a broadcast channel: the subsystem logs them, and any
+/// number of extra consumers (e.g. the `/api/events` SSE stream) can attach
+/// via [`CommsState::subscribe_events`].  Serialises as
+/// `{"event": "session_started", "channel_id": "..."}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
 pub enum CommsEvent {
-    ChannelShutdown { channel_id: String },
-    SessionStarted { channel_id: String },
+    ChannelShutdown {
+        channel_id: String,
+    },
+    SessionStarted {
+        channel_id: String,
+    },
+    SessionEnded {
+        channel_id: String,
+    },
+    /// An agent working on a request from `channel_id` started `step`.
+    AgentStep {
+        channel_id: String,
+        session_id: Option<String>,
+        step: String,
+    },
+    /// The bot is shutting down (`[comms] shutdown_message`); sent just
+    /// before the channels are told to stop.
+    ShuttingDown {
+        message: String,
+    },
+}
+
+/// The [`CommsEvent::ShuttingDown`] message waiting on `events`, if any —
+/// for a channel to show its users once its shutdown token fires.  Other
+/// queued events are skipped.
+pub fn pending_shutdown_message(events: &mut broadcast::Receiver<CommsEvent>) -> Option<String> {
+    loop {
+        match events.try_recv() {
+            Ok(CommsEvent::ShuttingDown { message }) => return Some(message),
+            Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => {}
+            Err(_) => return None,
+        }
+    }
 }
 
 // ── State ─────────────────────────────────────────────────────────────────────
 
 pub struct CommsState {
     bus: BusHandle,
-    event_tx: mpsc::Sender<CommsEvent>,
+    event_tx: broadcast::Sender<CommsEvent>,
+    input_limits: InputLimits,
+    /// `[comms] auto_session` — open a session for a conversation on its
+    /// first session-less message and reuse it afterwards.
+    auto_session: bool,
+    /// Session id remembered for `auto_session`, per conversation and agent;
+    /// the [`MAX_CONVERSATIONS`] most recently used.
+    channel_sessions: Mutex<RecentMap<SessionKey, String>>,
+    /// `(channel_id, conversation)` each remembered session was handed to,
+    /// by session id; an inline `@agent:session` from a conversation may
+    /// only name one of its own.
+    session_owners: Mutex<RecentMap<String, (String, String)>>,
+    /// Channels whose session-less messages get a throwaway `tmp` session
+    /// (`[comms.http] persist_sessions = false`).
+    ephemeral_channels: HashSet<String>,
+    /// Channel-id prefixes that get a session per conversation even with
+    /// `auto_session` off (the email channel: one session per sender).
+    conversation_session_channels: Vec<String>,
+    /// `[agents] error_message` — what conversation channels show instead
+    /// of a failed request's raw error.
+    error_message: String,
+    /// `[comms] inline_routing` — agents a leading `@agent[:session]` token
+    /// may address; `None` leaves messages untouched.
+    routable_agents: Option<HashSet<String>>,
+    /// `[comms] agent_switching` — agents `/switch` may hand a conversation
+    /// to; `None` leaves `/switch` as an ordinary message.
+    switchable_agents: Option<HashSet<String>>,
+    /// Agent picked by `/switch`, per `(channel_id, conversation)`; the
+    /// [`MAX_CONVERSATIONS`] most recently used.
+    conversation_agents: Mutex<RecentMap<(String, String), String>>,
+    /// `[comms] command_prefix` — marks a conversation message as a
+    /// command; empty disables commands.
+    command_prefix: String,
+    /// `[comms.http] cache_ttl_ms` — recent bodies of read-only GET routes.
+    response_cache: ResponseCache,
+    /// Channels whose conversations are offered `/retry` after a tool
+    /// failure (`[comms.pty] tool_retry`).
+    retry_channels: HashSet<String>,
+    /// The request that last failed on a tool, per `(channel_id,
+    /// conversation)`; what `/retry` sends again.
+    failed_requests: Mutex<HashMap<(String, String), RetryRequest>>,
+    /// `[comms] expose_config` — the redacted effective config served at
+    /// `GET /api/config`.
+    effective_config: Option<String>,
+}
+
+/// A request as it went to the agents, kept for `/retry`.
+#[derive(Debug, Clone)]
+struct RetryRequest {
+    content: String,
+    session_id: Option<String>,
+    agent_id: Option<String>,
+    response_format: Option<ResponseFormat>,
+}
+
+/// How many conversations per-conversation state is kept for.  The oldest
+/// is forgotten first; its next message starts afresh.
+const MAX_CONVERSATIONS: usize = 4096;
+
+/// `(channel_id, conversation, agent_id)` — one auto session each.  The
+/// conversation is whatever the channel uses to tell its users apart (the
+/// Telegram chat id; the channel id itself for the single-user PTY).
+type SessionKey = (String, String, String);
+
+/// A leading `@agent[:session]` token split off a message.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InlineRoute<'a> {
+    pub agent: &'a str,
+    pub session: Option<&'a str>,
+    /// The message with the token and the whitespace after it removed.
+    pub rest: &'a str,
+}
+
+/// Split a leading `@agent` or `@agent:session` token off `content`.
+/// Names are ASCII letters, digits, `-` and `_`; the token must be followed
+/// by whitespace or end the message.  Anything else is not a route.
+pub fn parse_inline_route(content: &str) -> Option<InlineRoute<'_>> {
+    let body = content.trim_start().strip_prefix('@')?;
+    let end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let (token, rest) = body.split_at(end);
+    let (agent, session) = match token.split_once(':') {
+        Some((agent, session)) => (agent, Some(session)),
+        None => (token, None),
+    };
+    let is_name = |s: &str| {
+        !s.is_empty()
+            && s.bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    };
+    if !is_name(agent) || !session.is_none_or(is_name) {
+        return None;
+    }
+    Some(InlineRoute {
+        agent,
+        session,
+        rest: rest.trim_start(),
+    })
+}
+
+/// A conversation message read against `[comms] command_prefix`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParsedInput<'a> {
+    /// `<prefix><name> [args]`, with `args` trimmed.
+    Command { name: &'a str, args: &'a str },
+    /// Anything else.  A doubled prefix is unescaped to one, so `//etc`
+    /// reaches the agent as `/etc`.
+    Content(Cow<'a, str>),
+}
+
+/// Split `content` into a command or content.  An empty `prefix` disables
+/// commands: everything is content, unchanged.
+pub fn parse_command<'a>(prefix: &str, content: &'a str) -> ParsedInput<'a> {
+    if prefix.is_empty() {
+        return ParsedInput::Content(Cow::Borrowed(content));
+    }
+    let Some(rest) = content.trim_start().strip_prefix(prefix) else {
+        return ParsedInput::Content(Cow::Borrowed(content));
+    };
+    if rest.starts_with(prefix) {
+        return ParsedInput::Content(Cow::Owned(rest.to_string()));
+    }
+    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let (name, args) = rest.split_at(end);
+    if name.is_empty() {
+        return ParsedInput::Content(Cow::Borrowed(content));
+    }
+    ParsedInput::Command {
+        name,
+        args: args.trim(),
+    }
+}
+
+/// Outcome of applying [`InputLimits`] to one inbound message.
+enum PreparedInput {
+    /// Forward `text`; `notice` is shown to the user alongside the reply.
+    Accept {
+        text: String,
+        notice: Option<String>,
+    },
+    /// Do not forward; reply with the notice instead.
+    Reject { notice: String },
 }
 
 impl CommsState {
-    pub fn new(bus: BusHandle, event_tx: mpsc::Sender<CommsEvent>) -> Self {
-        Self { bus, event_tx }
+    pub fn new(bus: BusHandle, event_tx: broadcast::Sender<CommsEvent>) -> Self {
+        Self {
+            bus,
+            event_tx,
+            input_limits: InputLimits::default(),
+            auto_session: false,
+            channel_sessions: Mutex::new(RecentMap::new(MAX_CONVERSATIONS)),
+            session_owners: Mutex::new(RecentMap::new(MAX_CONVERSATIONS)),
+            ephemeral_channels: HashSet::new(),
+            conversation_session_channels: Vec::new(),
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            routable_agents: None,
+            switchable_agents: None,
+            conversation_agents: Mutex::new(RecentMap::new(MAX_CONVERSATIONS)),
+            command_prefix: DEFAULT_COMMAND_PREFIX.to_string(),
+            response_cache: ResponseCache::default(),
+            retry_channels: HashSet::new(),
+            failed_requests: Mutex::new(HashMap::new()),
+            effective_config: None,
+        }
+    }
+
+    /// Apply size limits and control-character stripping to inbound messages.
+    pub fn with_input_limits(mut self, limits: InputLimits) -> Self {
+        self.input_limits = limits;
+        self
+    }
+
+    /// Give every channel a session automatically (see `[comms] auto_session`).
+    pub fn with_auto_session(mut self, enabled: bool) -> Self {
+        self.auto_session = enabled;
+        self
+    }
+
+    /// Honour a leading `@agent[:session]` token addressed to one of
+    /// `agents` (see `[comms] inline_routing`).
+    pub fn with_inline_routing(mut self, agents: impl IntoIterator<Item = String>) -> Self {
+        self.routable_agents = Some(agents.into_iter().collect());
+        self
+    }
+
+    /// Treat `/switch <agent>` from a conversation as a command handing it
+    /// to one of `agents` (see `[comms] agent_switching`).
+    pub fn with_agent_switching(mut self, agents: impl IntoIterator<Item = String>) -> Self {
+        self.switchable_agents = Some(agents.into_iter().collect());
+        self
+    }
+
+    /// Prefix that marks a conversation message as a command; `""` makes
+    /// every message content (see `[comms] command_prefix`).
+    pub fn with_command_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.command_prefix = prefix.into();
+        self
+    }
+
+    /// Cache the JSON of these GET routes for the given milliseconds (see
+    /// `[comms.http] cache_ttl_ms`).
+    pub fn with_response_cache(mut self, ttls_ms: impl IntoIterator<Item = (String, u64)>) -> Self {
+        self.response_cache = ResponseCache::new(ttls_ms);
+        self
+    }
+
+    /// Give session-less messages on `channel_id` a fresh in-memory session
+    /// instead of the agent's persistent default one.
+    pub fn with_ephemeral_sessions(mut self, channel_id: impl Into<String>) -> Self {
+        self.ephemeral_channels.insert(channel_id.into());
+        self
+    }
+
+    /// Answer a request on `channel_id` that failed on a tool with the error
+    /// and a `/retry` offer; `/retry` sends that request again.
+    pub fn with_tool_retry(mut self, channel_id: impl Into<String>) -> Self {
+        self.retry_channels.insert(channel_id.into());
+        self
+    }
+
+    /// Give each conversation on channels whose id starts with `prefix` its
+    /// own session, as `auto_session` would, regardless of that setting.
+    pub fn with_conversation_sessions(mut self, prefix: impl Into<String>) -> Self {
+        self.conversation_session_channels.push(prefix.into());
+        self
+    }
+
+    /// Reply template for failed requests on conversation channels;
+    /// `{error}` expands to the error text.
+    pub fn with_error_message(mut self, template: impl Into<String>) -> Self {
+        self.error_message = template.into();
+        self
+    }
+
+    /// Resolve the session for a message.  An explicit `session_id` always
+    /// wins; otherwise, with `auto_session` on (or a channel registered via
+    /// [`with_conversation_sessions`](Self::with_conversation_sessions)) and
+    /// a known `conversation`,
+    /// the conversation's remembered session for this agent is reused or a
+    /// new one is opened via `agents/sessions/open`.  Session-less messages
+    /// on an ephemeral channel get a fresh `tmp` session that is never
+    /// remembered, and is closed once the message is answered.
+    ///
+    /// Returns the session id and the key it is remembered under, if it came
+    /// from the auto path.  Failing to open a session is logged and the
+    /// message goes out session-less rather than being dropped.
+    async fn resolve_session(
+        &self,
+        channel_id: &str,
+        conversation: Option<&str>,
+        session_id: Option<String>,
+        agent_id: Option<&str>,
+    ) -> (Option<String>, Option<SessionKey>) {
+        if session_id.is_some() {
+            return (session_id, None);
+        }
+        if self.ephemeral_channels.contains(channel_id) {
+            return (self.open_session(channel_id, agent_id, true).await, None);
+        }
+        let Some(conversation) = conversation else {
+            return (None, None);
+        };
+        let per_conversation = self
+            .conversation_session_channels
+            .iter()
+            .any(|prefix| channel_id.starts_with(prefix.as_str()));
+        if !self.auto_session && !per_conversation {
+            return (None, None);
+        }
+        let key = (
+            channel_id.to_string(),
+            conversation.to_string(),
+            agent_id.unwrap_or_default().trim().to_string(),
+        );
+        if let Some(existing) = self.remembered_session(&key) {
+            return (Some(existing), Some(key));
+        }
+
+        let opened = self.open_session(channel_id, agent_id, false).await;
+        if let Some(id) = &opened {
+            self.remember_session(&key, id);
+        }
+        (opened, Some(key))
+    }
+
+    /// Discard the ephemeral session `session_id` via
+    /// `agents/sessions/close`; a failure is only logged.
+    async fn close_session(&self, channel_id: &str, agent_id: Option<&str>, session_id: &str) {
+        let payload = BusPayload::JsonRequest {
+            data: serde_json::json!({
+                "channel_id": channel_id,
+                "agent_id": agent_id,
+                "session_id": session_id,
+            })
+            .to_string(),
+        };
+        match self.bus.request("agents/sessions/close", payload).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => {
+                warn!(%channel_id, %session_id, "session close failed {}: {}", e.code, e.message)
+            }
+            Err(e) => warn!(%channel_id, %session_id, "session close: bus error: {e}"),
+        }
+    }
+
+    /// Ask the agents subsystem for a new session; `None` (logged) on failure.
+    async fn open_session(
+        &self,
+        channel_id: &str,
+        agent_id: Option<&str>,
+        ephemeral: bool,
+    ) -> Option<String> {
+        let payload = BusPayload::JsonRequest {
+            data: serde_json::json!({
+                "channel_id": channel_id,
+                "agent_id": agent_id,
+                "ephemeral": ephemeral,
+            })
+            .to_string(),
+        };
+        match self.bus.request("agents/sessions/open", payload).await {
+            Ok(Ok(BusPayload::JsonResponse { data })) => {
+                serde_json::from_str::<serde_json::Value>(&data)
+                    .ok()
+                    .and_then(|v| v.get("session_id")?.as_str().map(str::to_string))
+            }
+            Ok(Err(e)) => {
+                warn!(%channel_id, "session open failed {}: {}", e.code, e.message);
+                None
+            }
+            Err(e) => {
+                warn!(%channel_id, "session open: bus error: {e}");
+                None
+            }
+            Ok(Ok(_)) => {
+                warn!(%channel_id, "session open: unexpected reply payload");
+                None
+            }
+        }
+    }
+
+    fn remembered_session(&self, key: &SessionKey) -> Option<String> {
+        self.channel_sessions
+            .lock()
+            .ok()
+            .and_then(|mut map| map.get(key).cloned())
+    }
+
+    fn remember_session(&self, key: &SessionKey, session_id: &str) {
+        if let Ok(mut map) = self.channel_sessions.lock() {
+            map.insert(key.clone(), session_id.to_string());
+        }
+        if let Ok(mut owners) = self.session_owners.lock() {
+            owners.insert(session_id.to_string(), (key.0.clone(), key.1.clone()));
+        }
+    }
+
+    /// Whether `session_id` was handed to `conversation` on `channel_id`.
+    fn owns_session(&self, channel_id: &str, conversation: &str, session_id: &str) -> bool {
+        self.session_owners.lock().is_ok_and(|mut owners| {
+            owners
+                .get(&session_id.to_string())
+                .is_some_and(|(channel, owner)| channel == channel_id && owner == conversation)
+        })
+    }
+
+    /// Apply an inline `@agent[:session]` token: it replaces the channel's
+    /// agent (and session, if named) for this message and is stripped from
+    /// the content.  Tokens naming an agent that is not enabled are left in
+    /// place, so `@someone` in ordinary text passes through unchanged.
+    ///
+    /// Many conversations share one channel id (every Telegram chat is on
+    /// `telegram`), so a `conversation` may only name a session it was
+    /// handed itself; any other is refused with the returned notice.
+    /// Anonymous API clients already pick their session explicitly.
+    fn apply_inline_route(
+        &self,
+        channel_id: &str,
+        conversation: Option<&str>,
+        content: String,
+        session_id: Option<String>,
+        agent_id: Option<String>,
+    ) -> Result<(String, Option<String>, Option<String>), String> {
+        let Some(agents) = &self.routable_agents else {
+            return Ok((content, session_id, agent_id));
+        };
+        match parse_inline_route(&content) {
+            Some(route) if agents.contains(route.agent) => {
+                if let (Some(conversation), Some(session)) = (conversation, route.session) {
+                    if !self.owns_session(channel_id, conversation, session) {
+                        warn!(%channel_id, %conversation, %session, "inline route to a session of another conversation refused");
+                        return Err(format!("Unknown session: {session}"));
+                    }
+                }
+                debug!(%channel_id, agent = route.agent, session = ?route.session, "inline route");
+                let session_id = route.session.map(str::to_string).or(session_id);
+                let agent_id = Some(route.agent.to_string());
+                Ok((route.rest.to_string(), session_id, agent_id))
+            }
+            _ => Ok((content, session_id, agent_id)),
+        }
+    }
+
+    /// The agent a conversation was switched to, if any.
+    fn conversation_agent(&self, channel_id: &str, conversation: &str) -> Option<String> {
+        self.conversation_agents.lock().ok().and_then(|mut map| {
+            map.get(&(channel_id.to_string(), conversation.to_string()))
+                .cloned()
+        })
     }
 
+    /// Handle `/switch <agent>`: later messages from `conversation` go to
+    /// `target`, and its current session (the explicit one, else the one
+    /// remembered for the current agent) moves along via
+    /// `agents/sessions/switch`.  Returns the reply to show the user.
+    async fn switch_agent(
+        &self,
+        channel_id: &str,
+        conversation: &str,
+        target: &str,
+        session_id: Option<String>,
+        agent_id: Option<String>,
+    ) -> CommsReply {
+        let reply = |text: String, session_id: Option<String>| CommsReply {
+            reply: text,
+            session_id,
+            thinking: None,
+            usage: None,
+            timing: None,
+        };
+        let agents = self.switchable_agents.as_ref();
+        if target.is_empty() || !agents.is_some_and(|agents| agents.contains(target)) {
+            let mut names: Vec<&str> = agents.into_iter().flatten().map(String::as_str).collect();
+            names.sort_unstable();
+            let usage = format!(
+                "Usage: {}switch <agent>. Agents: {}",
+                self.command_prefix,
+                names.join(", ")
+            );
+            let text = if target.is_empty() {
+                usage
+            } else {
+                format!("Unknown agent '{target}'. {usage}")
+            };
+            return reply(text, session_id);
+        }
+
+        let current = self
+            .conversation_agent(channel_id, conversation)
+            .or(agent_id);
+        let key = |agent: Option<&str>| -> SessionKey {
+            (
+                channel_id.to_string(),
+                conversation.to_string(),
+                agent.unwrap_or_default().trim().to_string(),
+            )
+        };
+        let current_key = key(current.as_deref());
+        let session_id = session_id.or_else(|| self.remembered_session(&current_key));
+        if let Some(session) = &session_id {
+            let payload = BusPayload::JsonRequest {
+                data: serde_json::json!({
+                    "channel_id": channel_id,
+                    "session_id": session,
+                    "from_agent": current,
+                    "to_agent": target,
+                })
+                .to_string(),
+            };
+            let error = match self.bus.request("agents/sessions/switch", payload).await {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(e.message),
+                Err(e) => Some(format!("bus error: {e}")),
+            };
+            if let Some(error) = error {
+                warn!(%channel_id, %session, "session switch failed: {error}");
+                return reply(format!("Could not switch to {target}: {error}"), session_id);
+            }
+            if let Ok(mut map) = self.channel_sessions.lock() {
+                map.remove(&current_key);
+            }
+            self.remember_session(&key(Some(target)), session);
+        }
+
+        if let Ok(mut map) = self.conversation_agents.lock() {
+            map.insert(
+                (channel_id.to_string(), conversation.to_string()),
+                target.to_string(),
+            );
+        }
+        debug!(%channel_id, %conversation, agent = target, session = ?session_id, "agent switched");
+        reply(format!("Switched to {target}."), session_id)
+    }
+
+    fn prepare_input(&self, channel_id: &str, content: &str) -> PreparedInput {
+        let limits = &self.input_limits;
+        let sanitized = sanitize_input(content, limits.max_chars, limits.strip_control);
+        if !sanitized.truncated {
+            return PreparedInput::Accept {
+                text: sanitized.text,
+                notice: None,
+            };
+        }
+
+        let max = limits.max_chars.unwrap_or_default();
+        warn!(
+            %channel_id,
+            chars = sanitized.original_chars,
+            max,
+            overflow = ?limits.overflow,
+            "inbound message exceeds max_input_chars"
+        );
+        match limits.overflow {
+            InputOverflow::Truncate => PreparedInput::Accept {
+                text: sanitized.text,
+                notice: Some(format!(
+                    "[input truncated from {} to {max} characters]",
+                    sanitized.original_chars
+                )),
+            },
+            InputOverflow::Reject => PreparedInput::Reject {
+                notice: format!(
+                    "Message rejected: {} characters exceeds the limit of {max}.",
+                    sanitized.original_chars
+                ),
+            },
+        }
+    }
+
+    /// Streams have no reply envelope for a notice, so truncation is silent
+    /// (logged only) and rejection surfaces as an error.
+    fn prepare_stream_input(&self, channel_id: &str, content: &str) -> Result<String, AppError> {
+        match self.prepare_input(channel_id, content) {
+            PreparedInput::Accept { text, .. } => Ok(text),
+            PreparedInput::Reject { notice } => Err(AppError::Comms(notice)),
+        }
+    }
+
+    /// Send a message on behalf of an anonymous client.  No auto session is
+    /// opened: multi-client channels (HTTP, axum) cannot tell their users
+    /// apart, so sharing one would leak history between them.  Ephemeral
+    /// channels still get a one-off `tmp` session per message.
     pub async fn send_message(
         &self,
         channel_id: &str,
@@ -43,20 +627,249 @@ impl CommsState {
         session_id: Option<String>,
         agent_id: Option<String>,
     ) -> Result<CommsReply, AppError> {
+        self.send(channel_id, None, content, session_id, agent_id, None)
+            .await
+    }
+
+    /// [`send_message`](Self::send_message), asking the agent to reply in
+    /// `response_format` (when given) instead of its configured
+    /// `[agents.<id>] response_format`.
+    pub async fn send_message_as(
+        &self,
+        channel_id: &str,
+        content: String,
+        session_id: Option<String>,
+        agent_id: Option<String>,
+        response_format: Option<ResponseFormat>,
+    ) -> Result<CommsReply, AppError> {
+        self.send(
+            channel_id,
+            None,
+            content,
+            session_id,
+            agent_id,
+            response_format,
+        )
+        .await
+    }
+
+    /// Send a message from a known conversation (e.g. a Telegram chat).
+    /// With `auto_session` on, each conversation gets its own session per
+    /// agent.
+    ///
+    /// A failed request is logged and answered with the `[agents]
+    /// error_message` template rather than returned as an error; use
+    /// [`send_message`](Self::send_message) to get the raw error.
+    pub async fn send_message_from(
+        &self,
+        channel_id: &str,
+        conversation: &str,
+        content: String,
+        session_id: Option<String>,
+        agent_id: Option<String>,
+    ) -> Result<CommsReply, AppError> {
+        let session = session_id.clone();
+        match self
+            .send(
+                channel_id,
+                Some(conversation),
+                content,
+                session_id,
+                agent_id,
+                None,
+            )
+            .await
+        {
+            Err(e) => {
+                warn!(%channel_id, "agent request failed: {e}");
+                let detail = match &e {
+                    AppError::Comms(message) => message.clone(),
+                    other => other.to_string(),
+                };
+                Ok(CommsReply {
+                    reply: self.error_message.replace("{error}", &detail),
+                    session_id: session,
+                    thinking: None,
+                    usage: None,
+                    timing: None,
+                })
+            }
+            ok => ok,
+        }
+    }
+
+    async fn send(
+        &self,
+        channel_id: &str,
+        conversation: Option<&str>,
+        content: String,
+        session_id: Option<String>,
+        agent_id: Option<String>,
+        response_format: Option<ResponseFormat>,
+    ) -> Result<CommsReply, AppError> {
+        let (content, notice) = match self.prepare_input(channel_id, &content) {
+            PreparedInput::Accept { text, notice } => (text, notice),
+            PreparedInput::Reject { notice } => {
+                return Ok(CommsReply {
+                    reply: notice,
+                    session_id,
+                    thinking: None,
+                    usage: None,
+                    timing: None,
+                });
+            }
+        };
+        // Commands come from conversations only; API clients' messages are
+        // passed through as sent.
+        let parsed = match conversation {
+            Some(_) => parse_command(&self.command_prefix, &content),
+            None => ParsedInput::Content(Cow::Borrowed(&content)),
+        };
+        let command = match &parsed {
+            ParsedInput::Command { name, args } => Some((*name, *args)),
+            ParsedInput::Content(_) => None,
+        };
+        let retry_key = conversation
+            .filter(|_| !self.command_prefix.is_empty() && self.retry_channels.contains(channel_id))
+            .map(|conversation| (channel_id.to_string(), conversation.to_string()));
+        if let (Some(key), Some(("retry", ""))) = (&retry_key, command) {
+            let failed = self
+                .failed_requests
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(key);
+            let Some(request) = failed else {
+                return Ok(CommsReply {
+                    reply: "Nothing to retry.".to_string(),
+                    session_id,
+                    thinking: None,
+                    usage: None,
+                    timing: None,
+                });
+            };
+            return self
+                .dispatch(channel_id, conversation, request, None, retry_key)
+                .await;
+        }
+        let mut agent_id = agent_id;
+        if let (Some(_), Some(conversation)) = (&self.switchable_agents, conversation) {
+            if let Some(("switch", target)) = command {
+                return Ok(self
+                    .switch_agent(channel_id, conversation, target, session_id, agent_id)
+                    .await);
+            }
+            agent_id = self
+                .conversation_agent(channel_id, conversation)
+                .or(agent_id);
+        }
+        let unescaped = match parsed {
+            ParsedInput::Content(Cow::Owned(text)) => Some(text),
+            _ => None,
+        };
+        let content = unescaped.unwrap_or(content);
+        let (content, session_id, agent_id) = match self.apply_inline_route(
+            channel_id,
+            conversation,
+            content,
+            session_id,
+            agent_id,
+        ) {
+            Ok(routed) => routed,
+            Err(notice) => {
+                return Ok(CommsReply {
+                    reply: notice,
+                    session_id: None,
+                    thinking: None,
+                    usage: None,
+                    timing: None,
+                });
+            }
+        };
+        let request = RetryRequest {
+            content,
+            session_id,
+            agent_id,
+            response_format,
+        };
+        self.dispatch(channel_id, conversation, request, notice, retry_key)
+            .await
+    }
+
+    /// Send `request` to its agent.  With a `retry_key`, a tool failure is
+    /// answered with the error and a `/retry` offer and the request is kept
+    /// for that; any other outcome forgets the kept one.
+    async fn dispatch(
+        &self,
+        channel_id: &str,
+        conversation: Option<&str>,
+        request: RetryRequest,
+        notice: Option<String>,
+        retry_key: Option<(String, String)>,
+    ) -> Result<CommsReply, AppError> {
+        let kept = retry_key.as_ref().map(|_| request.clone());
+        let RetryRequest {
+            content,
+            session_id,
+            agent_id,
+            response_format,
+        } = request;
+        let ephemeral = session_id.is_none() && self.ephemeral_channels.contains(channel_id);
+        let (session_id, auto_key) = self
+            .resolve_session(channel_id, conversation, session_id, agent_id.as_deref())
+            .await;
         let method = match agent_id.as_deref() {
             Some(agent) if !agent.trim().is_empty() => format!("agents/{agent}"),
             _ => "agents".to_string(),
         };
-        let payload = BusPayload::CommsMessage {
+        let mut payload = BusPayload::CommsMessage {
             channel_id: channel_id.to_string(),
             content,
-            session_id,
+            session_id: session_id.clone(),
             usage: None,
             timing: None,
             thinking: None,
         };
+        if response_format.is_some() {
+            payload = BusPayload::AgentRequest {
+                chain: None,
+                response_format,
+                payload: Box::new(payload),
+            };
+        }
 
-        match self.bus.request(method, payload).await {
+        let result = self.bus.request(method, payload).await;
+        // An ephemeral session ends with its request; the caller never sees it.
+        let session_id = match session_id {
+            Some(id) if ephemeral => {
+                self.close_session(channel_id, agent_id.as_deref(), &id)
+                    .await;
+                None
+            }
+            other => other,
+        };
+        if let Some(key) = retry_key {
+            let mut failed = self
+                .failed_requests
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            match (&result, kept) {
+                (Ok(Err(e)), Some(request)) if e.code == ERR_TOOL_FAILED => {
+                    warn!(%channel_id, "agent request failed on a tool: {}", e.message);
+                    failed.insert(key, request);
+                    return Ok(CommsReply {
+                        reply: format!("{}. Retry? ({}retry)", e.message, self.command_prefix),
+                        session_id,
+                        thinking: None,
+                        usage: None,
+                        timing: None,
+                    });
+                }
+                _ => {
+                    failed.remove(&key);
+                }
+            }
+        }
+        match result {
             Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
             Ok(Err(e)) => Err(AppError::Comms(format!(
                 "agent error {}: {}",
@@ -64,38 +877,63 @@ impl CommsState {
             ))),
             Ok(Ok(BusPayload::CommsMessage {
                 content: reply,
-                session_id,
+                session_id: replied_session,
                 thinking,
                 usage,
                 timing,
                 ..
-            })) => Ok(CommsReply {
-                reply,
-                session_id,
-                thinking,
-                usage,
-                timing,
-            }),
+            })) => {
+                // The agent may have rotated the session; follow it.
+                let session_id = if ephemeral { None } else { replied_session };
+                if let (Some(key), Some(id)) = (&auto_key, &session_id) {
+                    self.remember_session(key, id);
+                }
+                Ok(CommsReply {
+                    reply: match notice {
+                        Some(notice) => format!("{notice}\n{reply}"),
+                        None => reply,
+                    },
+                    session_id,
+                    thinking,
+                    usage,
+                    timing,
+                })
+            }
             Ok(Ok(_)) => Err(AppError::Comms("unexpected reply payload".to_string())),
         }
     }
 
+    /// Stream an agent's reply, in `response_format` when given (streamed
+    /// JSON is not validated).  Also returns the stream's `request_id` for
+    /// [`stop_stream`](Self::stop_stream), when the agent's stream has one.
     pub async fn stream_via_agent(
         &self,
         channel_id: &str,
         content: String,
         session_id: Option<String>,
         agent_id: Option<String>,
-    ) -> Result<mpsc::Receiver<StreamChunk>, AppError> {
+        response_format: Option<ResponseFormat>,
+    ) -> Result<(mpsc::Receiver<StreamChunk>, Option<String>), AppError> {
+        let content = self.prepare_stream_input(channel_id, &content)?;
+        let (content, session_id, agent_id) = self
+            .apply_inline_route(channel_id, None, content, session_id, agent_id)
+            .map_err(AppError::Comms)?;
         let method = match agent_id.as_deref() {
             Some(agent) if !agent.trim().is_empty() => format!("agents/{agent}"),
             _ => "agents".to_string(),
         };
-        let payload = BusPayload::CommsStreamRequest {
+        let mut payload = BusPayload::CommsStreamRequest {
             channel_id: channel_id.to_string(),
             content,
             session_id,
         };
+        if response_format.is_some() {
+            payload = BusPayload::AgentRequest {
+                chain: None,
+                response_format,
+                payload: Box::new(payload),
+            };
+        }
         match self.bus.request(method, payload).await {
             Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
             Ok(Err(e)) => Err(AppError::Comms(format!(
@@ -104,19 +942,38 @@ impl CommsState {
             ))),
             Ok(Ok(BusPayload::LlmStreamResult {
                 rx: StreamReceiver(rx),
-            })) => Ok(rx),
+                request_id,
+            })) => Ok((rx, request_id)),
             Ok(Ok(_)) => Err(AppError::Comms(
                 "unexpected reply to agent stream request".to_string(),
             )),
         }
     }
 
+    /// End the stream `request_id` (from [`stream_via_agent`](Self::stream_via_agent))
+    /// early via `llm/stream/stop`.  `Ok(false)` when no such stream is in
+    /// flight any more.
+    pub async fn stop_stream(&self, request_id: &str) -> Result<bool, AppError> {
+        let payload = BusPayload::JsonRequest {
+            data: serde_json::json!({ "request_id": request_id }).to_string(),
+        };
+        match self.bus.request("llm/stream/stop", payload).await {
+            Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
+            Ok(Ok(_)) => Ok(true),
+            // The request is well-formed, so -32001 can only mean the id
+            // is unknown: the stream already ended or was stopped.
+            Ok(Err(e)) if e.code == -32001 => Ok(false),
+            Ok(Err(e)) => Err(AppError::Comms(format!("stream stop error: {}", e.message))),
+        }
+    }
+
     pub async fn stream_direct(
         &self,
         channel_id: &str,
         content: String,
         system: Option<String>,
     ) -> Result<mpsc::Receiver<StreamChunk>, AppError> {
+        let content = self.prepare_stream_input(channel_id, &content)?;
         let result = self
             .bus
             .request(
@@ -127,6 +984,7 @@ impl CommsState {
                     system,
                     provider_override: None,
                     model_override: None,
+                    response_format: ResponseFormat::Text,
                 },
             )
             .await;
@@ -135,6 +993,7 @@ impl CommsState {
             Ok(Err(e)) => Err(AppError::Comms(format!("llm stream error: {}", e.message))),
             Ok(Ok(BusPayload::LlmStreamResult {
                 rx: StreamReceiver(rx),
+                ..
             })) => Ok(rx),
             Ok(Ok(_)) => Err(AppError::Comms(
                 "unexpected reply to llm/stream".to_string(),
@@ -142,6 +1001,39 @@ impl CommsState {
         }
     }
 
+    /// The JSON for GET `route`: the cached body while it is younger than
+    /// the route's TTL, else `fetch`'s (cached when it succeeds).
+    /// `refresh` skips the cached body.
+    pub async fn cached_get(
+        &self,
+        route: &str,
+        refresh: bool,
+        fetch: impl std::future::Future<Output = Result<String, AppError>>,
+    ) -> Result<CachedResponse, AppError> {
+        let ttl = self.response_cache.ttl(route);
+        if !refresh {
+            if let Some((body, age)) = self.response_cache.get(route) {
+                return Ok(CachedResponse {
+                    body,
+                    ttl,
+                    age: Some(age),
+                });
+            }
+        }
+        let body = fetch.await?;
+        self.response_cache.put(route, &body);
+        Ok(CachedResponse {
+            body,
+            ttl,
+            age: None,
+        })
+    }
+
+    /// Drop every cached GET body; called on any write request.
+    pub fn invalidate_response_cache(&self) {
+        self.response_cache.clear();
+    }
+
     pub async fn management_http_get(&self) -> Result<String, AppError> {
         match self.bus.request("manage/http/get", BusPayload::Empty).await {
             Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
@@ -188,6 +1080,38 @@ impl CommsState {
         }
     }
 
+    /// Serve `config` (secrets redacted by its `Serialize` impl) from
+    /// [`management_config`](Self::management_config) without asking the bus.
+    pub fn with_effective_config(mut self, config: &araliya_core::config::Config) -> Self {
+        match serde_json::to_string(config) {
+            Ok(json) => self.effective_config = Some(json),
+            Err(e) => warn!("expose_config: failed to serialize config: {e}"),
+        }
+        self
+    }
+
+    /// Effective config JSON: the snapshot from
+    /// [`with_effective_config`](Self::with_effective_config), else
+    /// `manage/config`.  `Ok(None)` when the bus refuses the protected
+    /// method, which it does for channel handles.
+    pub async fn management_config(&self) -> Result<Option<String>, AppError> {
+        if let Some(json) = &self.effective_config {
+            return Ok(Some(json.clone()));
+        }
+        match self.bus.request("manage/config", BusPayload::Empty).await {
+            Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
+            Ok(Err(e)) if e.code == acl::ERR_FORBIDDEN => Ok(None),
+            Ok(Err(e)) => Err(AppError::Comms(format!(
+                "management error {}: {}",
+                e.code, e.message
+            ))),
+            Ok(Ok(BusPayload::JsonResponse { data })) => Ok(Some(data)),
+            Ok(Ok(_)) => Err(AppError::Comms(
+                "unexpected management reply payload".to_string(),
+            )),
+        }
+    }
+
     pub async fn management_http_tree(&self) -> Result<String, AppError> {
         match self
             .bus
@@ -206,8 +1130,23 @@ impl CommsState {
         }
     }
 
-    pub async fn request_sessions(&self) -> Result<String, AppError> {
-        match self.bus.request("agents/sessions", BusPayload::Empty).await {
+    /// List sessions; archived ones only when `include_archived` is set.
+    /// `sort` (`updated_at`, `created_at` or `session_id`) overrides the
+    /// default most-recently-updated-first order.
+    pub async fn request_sessions(
+        &self,
+        include_archived: bool,
+        sort: Option<&str>,
+    ) -> Result<String, AppError> {
+        let payload = if include_archived || sort.is_some() {
+            BusPayload::JsonRequest {
+                data: serde_json::json!({ "include_archived": include_archived, "sort": sort })
+                    .to_string(),
+            }
+        } else {
+            BusPayload::Empty
+        };
+        match self.bus.request("agents/sessions", payload).await {
             Err(e) => Err(AppError::Comms(format!("bus error: {e}"))),
             Ok(Err(e)) => Err(AppError::Comms(format!(
                 "agents error {}: {}",
@@ -489,11 +1428,45 @@ impl CommsState {
         }
     }
 
+    /// Publish a channel lifecycle event to every current subscriber.
     pub fn report_event(&self, event: CommsEvent) {
-        if let Err(e) = self.event_tx.try_send(event) {
-            warn!("comms event dropped: {e}");
+        if let Err(e) = self.event_tx.send(event) {
+            debug!("comms event has no subscribers: {:?}", e.0);
         }
     }
+
+    /// Receive channel lifecycle events from now on.  Slow receivers lag
+    /// (see [`broadcast::error::RecvError::Lagged`]) rather than block channels.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<CommsEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Report `SessionStarted` now and `SessionEnded` when the returned
+    /// guard drops — for channels whose sessions have no explicit end
+    /// (one axum request or stream, one Telegram message).
+    pub fn session_scope(&self, channel_id: &str) -> SessionScope {
+        self.report_event(CommsEvent::SessionStarted {
+            channel_id: channel_id.to_string(),
+        });
+        SessionScope {
+            event_tx: self.event_tx.clone(),
+            channel_id: channel_id.to_string(),
+        }
+    }
+}
+
+/// Guard returned by [`CommsState::session_scope`].
+pub struct SessionScope {
+    event_tx: broadcast::Sender<CommsEvent>,
+    channel_id: String,
+}
+
+impl Drop for SessionScope {
+    fn drop(&mut self) {
+        let _ = self.event_tx.send(CommsEvent::SessionEnded {
+            channel_id: std::mem::take(&mut self.channel_id),
+        });
+    }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -536,7 +1509,7 @@ mod tests {
     fn report_event_drops_gracefully_when_channel_closed() {
         let sbus = araliya_core::bus::SupervisorBus::new(1);
         let bus = sbus.handle;
-        let (ev_tx, ev_rx) = tokio::sync::mpsc::channel(1);
+        let (ev_tx, ev_rx) = tokio::sync::broadcast::channel(1);
         let state = CommsState::new(bus, ev_tx);
         // Drop the receiver so the channel is closed.
         drop(ev_rx);
@@ -545,4 +1518,854 @@ mod tests {
             channel_id: "pty0".to_string(),
         });
     }
+
+    #[tokio::test]
+    async fn subscribers_receive_reported_events() {
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(8);
+        let state = CommsState::new(sbus.handle, ev_tx);
+        let mut a = state.subscribe_events();
+        let mut b = state.subscribe_events();
+
+        state.report_event(CommsEvent::SessionStarted {
+            channel_id: "http0".to_string(),
+        });
+
+        for rx in [&mut a, &mut b] {
+            match rx.recv().await.unwrap() {
+                CommsEvent::SessionStarted { channel_id } => assert_eq!(channel_id, "http0"),
+                other => panic!("unexpected event: {other:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn management_config_is_refused_on_restricted_handle() {
+        let mut sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.restricted(), ev_tx);
+
+        assert!(matches!(state.management_config().await, Ok(None)));
+        assert!(sbus.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn comms_event_serialises_with_event_tag() {
+        let ev = CommsEvent::SessionEnded {
+            channel_id: "http0".to_string(),
+        };
+        let v = serde_json::to_value(&ev).unwrap();
+        assert_eq!(v["event"], "session_ended");
+        assert_eq!(v["channel_id"], "http0");
+    }
+
+    #[tokio::test]
+    async fn agent_stream_can_be_stopped_by_its_request_id() {
+        use araliya_core::bus::message::BusError;
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(4);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx);
+
+        // Stands in for the agents and LLM subsystems: one stream, "r1",
+        // which can be stopped once.
+        tokio::spawn(async move {
+            let mut live = true;
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                let reply = match (method.as_str(), payload) {
+                    ("agents", BusPayload::CommsStreamRequest { .. }) => {
+                        let (_tx, rx) = mpsc::channel(1);
+                        Ok(BusPayload::LlmStreamResult {
+                            rx: StreamReceiver(rx),
+                            request_id: Some("r1".into()),
+                        })
+                    }
+                    ("llm/stream/stop", BusPayload::JsonRequest { data }) => {
+                        let v: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        if v["request_id"] == "r1" && std::mem::take(&mut live) {
+                            Ok(BusPayload::JsonResponse {
+                                data: r#"{"ok":true}"#.into(),
+                            })
+                        } else {
+                            Err(BusError::new(-32001, "no stream in flight"))
+                        }
+                    }
+                    (method, _) => panic!("unexpected request: {method}"),
+                };
+                let _ = reply_tx.send(reply);
+            }
+        });
+
+        let (_rx, request_id) = state
+            .stream_via_agent("http0", "hello".into(), None, None, None)
+            .await
+            .unwrap();
+        let request_id = request_id.unwrap();
+        assert_eq!(request_id, "r1");
+        assert!(state.stop_stream(&request_id).await.unwrap());
+        assert!(!state.stop_stream(&request_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn send_message_rejects_oversized_input_without_bus_call() {
+        let sbus = araliya_core::bus::SupervisorBus::new(1);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle, ev_tx).with_input_limits(InputLimits {
+            max_chars: Some(4),
+            overflow: InputOverflow::Reject,
+            strip_control: false,
+        });
+        let reply = state
+            .send_message("pty0", "too long".to_string(), Some("s1".into()), None)
+            .await
+            .unwrap();
+        assert!(reply.reply.starts_with("Message rejected"));
+        assert_eq!(reply.session_id.as_deref(), Some("s1"));
+    }
+
+    #[tokio::test]
+    async fn send_message_truncates_and_prefixes_notice() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(4);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx).with_input_limits(InputLimits {
+            max_chars: Some(3),
+            overflow: InputOverflow::Truncate,
+            strip_control: true,
+        });
+
+        // Echo responder standing in for the agents subsystem.
+        tokio::spawn(async move {
+            if let Some(BusMessage::Request {
+                payload: BusPayload::CommsMessage { content, .. },
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id: "pty0".into(),
+                    content,
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
+        let reply = state
+            .send_message("pty0", "\u{1b}[1mabcdef".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(reply.reply, "[input truncated from 6 to 3 characters]\nabc");
+    }
+
+    #[tokio::test]
+    async fn send_message_as_carries_the_response_format_to_the_agent() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(4);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx);
+
+        let responder = tokio::spawn(async move {
+            let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = sbus.rx.recv().await
+            else {
+                panic!("no request");
+            };
+            let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                channel_id: "http0".into(),
+                content: "{}".into(),
+                session_id: None,
+                usage: None,
+                timing: None,
+                thinking: None,
+            }));
+            payload
+        });
+
+        let reply = state
+            .send_message_as("http0", "hi".into(), None, None, Some(ResponseFormat::Json))
+            .await
+            .unwrap();
+        assert_eq!(reply.reply, "{}");
+        let BusPayload::AgentRequest {
+            chain,
+            response_format,
+            payload,
+        } = responder.await.unwrap()
+        else {
+            panic!("expected an AgentRequest");
+        };
+        assert!(chain.is_none());
+        assert_eq!(response_format, Some(ResponseFormat::Json));
+        assert!(matches!(*payload, BusPayload::CommsMessage { content, .. } if content == "hi"));
+    }
+
+    #[test]
+    fn parse_command_splits_commands_and_unescapes_literals() {
+        assert_eq!(
+            parse_command("/", "  /switch  docs "),
+            ParsedInput::Command {
+                name: "switch",
+                args: "docs"
+            }
+        );
+        assert_eq!(
+            parse_command("!", "!retry"),
+            ParsedInput::Command {
+                name: "retry",
+                args: ""
+            }
+        );
+        // A doubled prefix is one literal prefix.
+        assert_eq!(
+            parse_command("/", "//etc/hosts is missing"),
+            ParsedInput::Content(Cow::Owned("/etc/hosts is missing".to_string()))
+        );
+        // Another prefix leaves slashes alone; a bare prefix is content.
+        assert_eq!(
+            parse_command("!", "/switch docs"),
+            ParsedInput::Content(Cow::Borrowed("/switch docs"))
+        );
+        assert_eq!(
+            parse_command("/", "/ alone"),
+            ParsedInput::Content(Cow::Borrowed("/ alone"))
+        );
+        // No prefix: everything is content, even a doubled slash.
+        assert_eq!(
+            parse_command("", "//switch docs"),
+            ParsedInput::Content(Cow::Borrowed("//switch docs"))
+        );
+    }
+
+    #[tokio::test]
+    async fn command_prefix_decides_what_reaches_the_agent() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let agents = ["chat".to_string(), "docs".to_string()];
+        let bang = CommsState::new(sbus.handle.clone(), ev_tx.clone())
+            .with_agent_switching(agents.clone())
+            .with_command_prefix("!");
+        let disabled = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_agent_switching(agents)
+            .with_command_prefix("");
+
+        // Stand-in for the agents subsystem: echoes, recording each message.
+        let responder = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            while let Some(BusMessage::Request {
+                payload: BusPayload::CommsMessage { content, .. },
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                seen.push(content.clone());
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id: "pty0".into(),
+                    content,
+                    session_id: None,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+                if seen.len() == 3 {
+                    break;
+                }
+            }
+            seen
+        });
+
+        let switched = bang
+            .send_message_from("pty0", "pty0", "!switch docs".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(switched.reply, "Switched to docs.");
+        for (state, content) in [
+            (&bang, "/switch docs"),
+            (&bang, "!!switch is a command here"),
+            (&disabled, "!switch docs"),
+        ] {
+            state
+                .send_message_from("pty0", "pty0", content.to_string(), None, None)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            responder.await.unwrap(),
+            ["/switch docs", "!switch is a command here", "!switch docs"]
+        );
+    }
+
+    #[tokio::test]
+    async fn switch_moves_the_conversation_and_its_session_to_another_agent() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_auto_session(true)
+            .with_agent_switching(["chat".to_string(), "docs".to_string()]);
+
+        // Stand-in for the agents subsystem: opens "s-1", accepts switches
+        // and records which method and session each message arrived on.
+        let responder = tokio::spawn(async move {
+            let (mut opens, mut switches, mut seen) = (0, Vec::new(), Vec::new());
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/open" => {
+                        opens += 1;
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: r#"{"session_id":"s-1","agent_id":"chat"}"#.into(),
+                        }));
+                        assert!(data.contains("pty0"));
+                    }
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/switch" => {
+                        let req: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        switches.push((req["session_id"].clone(), req["to_agent"].clone()));
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data }));
+                    }
+                    BusPayload::CommsMessage {
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        seen.push((method, session_id.clone()));
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id: "pty0".into(),
+                            content,
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                        if seen.len() == 3 {
+                            break;
+                        }
+                    }
+                    other => panic!("unexpected request {method}: {other:?}"),
+                }
+            }
+            (opens, switches, seen)
+        });
+
+        let send = |content: &str| {
+            state.send_message_from("pty0", "pty0", content.to_string(), None, None)
+        };
+        send("one").await.unwrap();
+        let unknown = send("/switch nobody").await.unwrap();
+        assert_eq!(
+            unknown.reply,
+            "Unknown agent 'nobody'. Usage: /switch <agent>. Agents: chat, docs"
+        );
+        let switched = send("/switch docs").await.unwrap();
+        assert_eq!(switched.reply, "Switched to docs.");
+        assert_eq!(switched.session_id.as_deref(), Some("s-1"));
+        send("two").await.unwrap();
+        // Only the bare word is a command; this goes to the agent.
+        send("/switching gears").await.unwrap();
+
+        let (opens, switches, seen) = responder.await.unwrap();
+        assert_eq!(opens, 1);
+        assert_eq!(switches, [("s-1".into(), "docs".into())]);
+        let s1 = Some("s-1".to_string());
+        assert_eq!(
+            seen,
+            [
+                ("agents".to_string(), s1.clone()),
+                ("agents/docs".to_string(), s1.clone()),
+                ("agents/docs".to_string(), s1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_resends_a_request_that_failed_on_a_tool() {
+        use araliya_core::bus::message::BusError;
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx).with_tool_retry("pty0");
+
+        // Stand-in for the agents subsystem: the gmail tool fails the first
+        // time and works the second.
+        let responder = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            while let Some(BusMessage::Request {
+                method,
+                payload: BusPayload::CommsMessage { content, .. },
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                seen.push((method, content.clone()));
+                let reply = if seen.len() == 1 {
+                    Err(BusError::tool_failed("gmail", Some("auth expired".into())))
+                } else {
+                    Ok(BusPayload::CommsMessage {
+                        channel_id: "pty0".into(),
+                        content: "3 new messages".into(),
+                        session_id: None,
+                        usage: None,
+                        timing: None,
+                        thinking: None,
+                    })
+                };
+                let _ = reply_tx.send(reply);
+                if seen.len() == 2 {
+                    break;
+                }
+            }
+            seen
+        });
+
+        let send = |content: &str| {
+            state.send_message_from("pty0", "pty0", content.to_string(), None, None)
+        };
+        let failed = send("check mail").await.unwrap();
+        assert_eq!(
+            failed.reply,
+            "tool gmail failed: auth expired. Retry? (/retry)"
+        );
+        let retried = send("/retry").await.unwrap();
+        assert_eq!(retried.reply, "3 new messages");
+        // The retry succeeded, so there is nothing left to retry.
+        let again = send("/retry").await.unwrap();
+        assert_eq!(again.reply, "Nothing to retry.");
+
+        let seen = responder.await.unwrap();
+        assert_eq!(
+            seen,
+            [
+                ("agents".to_string(), "check mail".to_string()),
+                ("agents".to_string(), "check mail".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_session_opens_once_and_reuses_per_conversation() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx).with_auto_session(true);
+
+        // Stand-in for the agents subsystem: opens "s-1" and echoes the
+        // session id each message arrived with.
+        let responder = tokio::spawn(async move {
+            let mut opens = 0;
+            let mut seen = Vec::new();
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/open" => {
+                        assert!(data.contains("pty0"));
+                        opens += 1;
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: r#"{"session_id":"s-1","agent_id":"echo"}"#.into(),
+                        }));
+                    }
+                    BusPayload::CommsMessage {
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        seen.push(session_id.clone());
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id: "pty0".into(),
+                            content,
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                        if seen.len() == 2 {
+                            break;
+                        }
+                    }
+                    other => panic!("unexpected request {method}: {other:?}"),
+                }
+            }
+            (opens, seen)
+        });
+
+        let first = state
+            .send_message_from("pty0", "pty0", "one".to_string(), None, None)
+            .await
+            .unwrap();
+        let second = state
+            .send_message_from("pty0", "pty0", "two".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(first.session_id.as_deref(), Some("s-1"));
+        assert_eq!(second.session_id.as_deref(), Some("s-1"));
+
+        let (opens, seen) = responder.await.unwrap();
+        assert_eq!(opens, 1);
+        assert_eq!(seen, [Some("s-1".to_string()), Some("s-1".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn ephemeral_channel_opens_tmp_session_per_message() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_auto_session(true)
+            .with_ephemeral_sessions("http0");
+
+        // Records each `agents/sessions/open` request's `ephemeral` flag, and
+        // the sessions closed.
+        let responder = tokio::spawn(async move {
+            let mut opens = Vec::new();
+            let mut closes = Vec::new();
+            let mut messages = 0;
+            while let Some(BusMessage::Request {
+                method,
+                payload,
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/open" => {
+                        let req: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        opens.push((
+                            req["channel_id"].as_str().unwrap().to_string(),
+                            req["ephemeral"].as_bool().unwrap(),
+                        ));
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: format!(r#"{{"session_id":"s-{}"}}"#, opens.len()),
+                        }));
+                    }
+                    BusPayload::JsonRequest { data } if method == "agents/sessions/close" => {
+                        let req: serde_json::Value = serde_json::from_str(&data).unwrap();
+                        closes.push(req["session_id"].as_str().unwrap().to_string());
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: r#"{"ok":true}"#.to_string(),
+                        }));
+                    }
+                    BusPayload::CommsMessage {
+                        channel_id,
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id,
+                            content,
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                        messages += 1;
+                        if messages == 4 {
+                            break;
+                        }
+                    }
+                    other => panic!("unexpected request {method}: {other:?}"),
+                }
+            }
+            (opens, closes)
+        });
+
+        for text in ["one", "two"] {
+            let http = state
+                .send_message("http0", text.to_string(), None, None)
+                .await
+                .unwrap();
+            assert_eq!(http.session_id, None);
+            let pty = state
+                .send_message_from("pty0", "pty0", text.to_string(), None, None)
+                .await
+                .unwrap();
+            assert_eq!(pty.session_id.as_deref(), Some("s-2"));
+        }
+
+        let (opens, closes) = responder.await.unwrap();
+        assert_eq!(closes, ["s-1", "s-3"]);
+        assert_eq!(
+            opens,
+            [
+                ("http0".to_string(), true),
+                ("pty0".to_string(), false),
+                ("http0".to_string(), true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_session_is_not_shared_across_conversations_or_agents() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx).with_auto_session(true);
+
+        // Opens "s-1", "s-2", ... and echoes the session id back.
+        tokio::spawn(async move {
+            let mut opens = 0;
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { .. } => {
+                        opens += 1;
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: format!(r#"{{"session_id":"s-{opens}"}}"#),
+                        }));
+                    }
+                    BusPayload::CommsMessage {
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id: "telegram0".into(),
+                            content,
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        async fn send(state: &CommsState, chat: &str, agent: Option<&str>) -> Option<String> {
+            state
+                .send_message_from(
+                    "telegram0",
+                    chat,
+                    "hi".to_string(),
+                    None,
+                    agent.map(str::to_string),
+                )
+                .await
+                .unwrap()
+                .session_id
+        }
+        let alice = send(&state, "100", None).await;
+        let bob = send(&state, "200", None).await;
+        let alice_docs = send(&state, "100", Some("docs")).await;
+        assert_eq!(alice.as_deref(), Some("s-1"));
+        assert_eq!(bob.as_deref(), Some("s-2"));
+        assert_eq!(alice_docs.as_deref(), Some("s-3"));
+        assert_eq!(send(&state, "100", None).await, alice);
+
+        // Anonymous (HTTP) clients never get an auto session.
+        let anon = state
+            .send_message("telegram0", "hi".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(anon.session_id, None);
+    }
+
+    #[tokio::test]
+    async fn conversation_errors_use_template_but_api_gets_raw_error() {
+        use araliya_core::bus::{BusError, BusMessage};
+
+        let (ev_tx, _) = broadcast::channel(4);
+        let sbus = araliya_core::bus::SupervisorBus::new(8);
+        let mut rx = sbus.rx;
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let BusMessage::Request { reply_tx, .. } = msg {
+                    let _ = reply_tx.send(Err(BusError::new(-32000, "llm timed out")));
+                }
+            }
+        });
+        let state = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_error_message("Oops, try again later ({error}).");
+
+        let chat = state
+            .send_message_from("telegram0", "100", "hi".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            chat.reply,
+            "Oops, try again later (agent error -32000: llm timed out)."
+        );
+
+        let api = state
+            .send_message("http0", "hi".to_string(), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            api.to_string(),
+            "comms error: agent error -32000: llm timed out"
+        );
+    }
+
+    #[test]
+    fn inline_route_parses_agent_and_session_tokens() {
+        assert_eq!(
+            parse_inline_route("@docs how do I deploy?"),
+            Some(InlineRoute {
+                agent: "docs",
+                session: None,
+                rest: "how do I deploy?",
+            })
+        );
+        assert_eq!(
+            parse_inline_route("@chat:sess123   hello"),
+            Some(InlineRoute {
+                agent: "chat",
+                session: Some("sess123"),
+                rest: "hello",
+            })
+        );
+        assert_eq!(parse_inline_route("hello @docs"), None);
+        assert_eq!(parse_inline_route("@chat: hello"), None);
+        assert_eq!(parse_inline_route("@ hello"), None);
+        assert_eq!(parse_inline_route("mail me@example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn inline_route_sets_method_and_session_for_enabled_agents() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_inline_routing(["chat".to_string(), "docs".to_string()]);
+
+        // Echoes "<method> <session> <content>" back.
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload:
+                    BusPayload::CommsMessage {
+                        content,
+                        session_id,
+                        ..
+                    },
+                reply_tx,
+                ..
+            }) = sbus.rx.recv().await
+            {
+                let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                    channel_id: "pty0".into(),
+                    content: format!(
+                        "{method} {} {content}",
+                        session_id.as_deref().unwrap_or("-")
+                    ),
+                    session_id,
+                    usage: None,
+                    timing: None,
+                    thinking: None,
+                }));
+            }
+        });
+
+        let send = |content: &str| {
+            state.send_message("pty0", content.to_string(), Some("s0".into()), None)
+        };
+        assert_eq!(
+            send("@docs how do I ...").await.unwrap().reply,
+            "agents/docs s0 how do I ..."
+        );
+        assert_eq!(
+            send("@chat:sess123 hello").await.unwrap().reply,
+            "agents/chat sess123 hello"
+        );
+        assert_eq!(send("hello").await.unwrap().reply, "agents s0 hello");
+        // Not an enabled agent: left as ordinary text.
+        assert_eq!(
+            send("@nobody hello").await.unwrap().reply,
+            "agents s0 @nobody hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn inline_route_only_reaches_the_conversations_own_sessions() {
+        use araliya_core::bus::BusMessage;
+
+        let mut sbus = araliya_core::bus::SupervisorBus::new(8);
+        let (ev_tx, _ev_rx) = tokio::sync::broadcast::channel(1);
+        let state = CommsState::new(sbus.handle.clone(), ev_tx)
+            .with_auto_session(true)
+            .with_inline_routing(["chat".to_string()]);
+
+        // Opens "s-1", "s-2", ... and echoes "<session> <content>" back.
+        tokio::spawn(async move {
+            let mut opens = 0;
+            while let Some(BusMessage::Request {
+                payload, reply_tx, ..
+            }) = sbus.rx.recv().await
+            {
+                match payload {
+                    BusPayload::JsonRequest { .. } => {
+                        opens += 1;
+                        let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                            data: format!(r#"{{"session_id":"s-{opens}"}}"#),
+                        }));
+                    }
+                    BusPayload::CommsMessage {
+                        content,
+                        session_id,
+                        ..
+                    } => {
+                        let _ = reply_tx.send(Ok(BusPayload::CommsMessage {
+                            channel_id: "telegram0".into(),
+                            content: format!("{} {content}", session_id.as_deref().unwrap_or("-")),
+                            session_id,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        let send = |chat: &'static str, content: &str| {
+            state.send_message_from("telegram0", chat, content.to_string(), None, None)
+        };
+        assert_eq!(send("100", "hi").await.unwrap().reply, "s-1 hi");
+        assert_eq!(send("200", "hi").await.unwrap().reply, "s-2 hi");
+
+        // Bob cannot step into Alice's session on the shared channel id.
+        let refused = send("200", "@chat:s-1 show me").await.unwrap();
+        assert_eq!(refused.reply, "Unknown session: s-1");
+        assert_eq!(refused.session_id, None);
+
+        // Alice can address her own.
+        assert_eq!(
+            send("100", "@chat:s-1 hello").await.unwrap().reply,
+            "s-1 hello"
+        );
+    }
 }
@@ -1,14 +1,25 @@
 //! Telegram comms channel — receives messages via Telegram API, sends to supervisor,
 //! and replies back to the user.
+//!
+//! In groups, `[comms.telegram] respond_mode` decides which messages reach
+//! [`CommsState`] at all: every one, only those that @-mention the bot, or
+//! only replies to the bot.  The mention is removed before dispatch.
+//!
+//! On shutdown, the `[comms] shutdown_message` is sent to the chats the bot
+//! answered most recently.
 
+use std::collections::VecDeque;
 use std::env;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use teloxide::prelude::*;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
-use crate::state::CommsState;
+use crate::state::{pending_shutdown_message, CommsState};
+use araliya_core::bus::health::HealthReporter;
+use araliya_core::config::RespondMode;
 use araliya_core::error::AppError;
 use araliya_core::runtime::{Component, ComponentFuture};
 
@@ -16,11 +27,19 @@ use araliya_core::runtime::{Component, ComponentFuture};
 
 const MAX_MESSAGE_LENGTH: usize = 4000;
 
+/// How many distinct chats are told about a shutdown.
+const RECENT_CHATS: usize = 50;
+
+/// How long sending the shutdown message may hold up shutdown.
+const SHUTDOWN_NOTICE_TIMEOUT: Duration = Duration::from_secs(5);
+
 // ── TelegramChannel ──────────────────────────────────────────────────────────
 
 pub struct TelegramChannel {
     channel_id: String,
     state: Arc<CommsState>,
+    health: Option<HealthReporter>,
+    respond_mode: RespondMode,
 }
 
 impl TelegramChannel {
@@ -28,8 +47,16 @@ impl TelegramChannel {
         Self {
             channel_id: channel_id.into(),
             state,
+            health: None,
+            respond_mode: RespondMode::Always,
         }
     }
+
+    /// Which group messages are answered (`[comms.telegram] respond_mode`).
+    pub fn with_respond_mode(mut self, mode: RespondMode) -> Self {
+        self.respond_mode = mode;
+        self
+    }
 }
 
 impl Component for TelegramChannel {
@@ -38,7 +65,19 @@ impl Component for TelegramChannel {
     }
 
     fn run(self: Box<Self>, shutdown: CancellationToken) -> ComponentFuture {
-        Box::pin(run_telegram(self.channel_id, self.state, shutdown))
+        Box::pin(run_telegram(
+            self.channel_id,
+            self.state,
+            self.health,
+            self.respond_mode,
+            shutdown,
+        ))
+    }
+
+    /// Report whether the bot token was accepted by the Telegram API
+    /// (`connected` in the channel's health details).
+    fn attach_health(&mut self, reporter: HealthReporter) {
+        self.health = Some(reporter);
     }
 }
 
@@ -47,6 +86,8 @@ impl Component for TelegramChannel {
 async fn run_telegram(
     channel_id: String,
     state: Arc<CommsState>,
+    health: Option<HealthReporter>,
+    respond_mode: RespondMode,
     shutdown: CancellationToken,
 ) -> Result<(), AppError> {
     let token = match env::var("TELEGRAM_BOT_TOKEN") {
@@ -61,18 +102,75 @@ async fn run_telegram(
 
     let bot = Bot::new(token);
 
+    // The bot's own identity, for spotting mentions of and replies to it.
+    let me = match bot.get_me().await {
+        Ok(me) => {
+            if let Some(health) = &health {
+                health
+                    .set_healthy_with("running", Some(serde_json::json!({ "connected": true })))
+                    .await;
+            }
+            Some(me.user)
+        }
+        Err(e) => {
+            warn!(%channel_id, "telegram getMe failed: {e}");
+            if let Some(health) = &health {
+                health
+                    .set_unhealthy_with(
+                        format!("not connected: {e}"),
+                        Some(serde_json::json!({ "connected": false })),
+                    )
+                    .await;
+            }
+            None
+        }
+    };
+    if me.is_none() && respond_mode != RespondMode::Always {
+        warn!(%channel_id, ?respond_mode, "bot identity unknown — group messages will be ignored");
+    }
+    let me = Arc::new(me);
+
+    // Read only on shutdown, for `[comms] shutdown_message`.
+    let mut notices = state.subscribe_events();
+    let recent = Arc::new(Mutex::new(VecDeque::new()));
+
     let state_clone = state.clone();
     let channel_id_clone = channel_id.clone();
+    let recent_clone = recent.clone();
 
     let handler = Update::filter_message().endpoint(
         move |bot: Bot, msg: Message| {
             let state = state_clone.clone();
             let channel_id = channel_id_clone.clone();
+            let me = me.clone();
+            let recent = recent_clone.clone();
             async move {
                 if let Some(text) = msg.text() {
                     debug!(%channel_id, from = ?msg.from.as_ref().and_then(|u| u.username.as_ref()), "telegram received message");
 
-                    match state.send_message(&channel_id, text.to_string(), None, None).await {
+                    let me = Option::as_ref(&me);
+                    let replies_to_bot = match (me, msg.reply_to_message()) {
+                        (Some(me), Some(parent)) => {
+                            parent.from.as_ref().is_some_and(|u| u.id == me.id)
+                        }
+                        _ => false,
+                    };
+                    let bot_username = me.and_then(|u| u.username.as_deref());
+                    let Some(text) = filter_message(
+                        respond_mode,
+                        msg.chat.is_private(),
+                        text,
+                        bot_username,
+                        replies_to_bot,
+                    ) else {
+                        debug!(%channel_id, ?respond_mode, "telegram group message not addressed to the bot");
+                        return respond(());
+                    };
+
+                    remember_chat(&mut recent.lock().unwrap(), msg.chat.id);
+                    let _session = state.session_scope(&channel_id);
+                    let chat = msg.chat.id.to_string();
+                    match state.send_message_from(&channel_id, &chat, text, None, None).await {
                         Ok(reply) => {
                             let mut text = reply.reply;
                             if text.is_empty() {
@@ -99,13 +197,26 @@ async fn run_telegram(
         }
     );
 
-    let mut dispatcher = Dispatcher::builder(bot, handler).build();
+    let mut dispatcher = Dispatcher::builder(bot.clone(), handler).build();
 
     tokio::select! {
         biased;
 
         _ = shutdown.cancelled() => {
             info!(%channel_id, "shutdown signal received — closing telegram channel");
+            if let Some(message) = pending_shutdown_message(&mut notices) {
+                let chats: Vec<ChatId> = recent.lock().unwrap().iter().copied().collect();
+                let notify = async {
+                    for chat in chats {
+                        if let Err(e) = bot.send_message(chat, message.as_str()).await {
+                            warn!(%channel_id, "failed to send telegram shutdown message: {e}");
+                        }
+                    }
+                };
+                if tokio::time::timeout(SHUTDOWN_NOTICE_TIMEOUT, notify).await.is_err() {
+                    warn!(%channel_id, "telegram shutdown message timed out");
+                }
+            }
         }
         _ = dispatcher.dispatch() => {
             warn!(%channel_id, "telegram dispatcher exited unexpectedly");
@@ -114,3 +225,155 @@ async fn run_telegram(
 
     Ok(())
 }
+
+/// Move `chat` to the front of `recent`, keeping at most [`RECENT_CHATS`].
+fn remember_chat(recent: &mut VecDeque<ChatId>, chat: ChatId) {
+    recent.retain(|c| *c != chat);
+    recent.push_front(chat);
+    recent.truncate(RECENT_CHATS);
+}
+
+// ── Group filtering ──────────────────────────────────────────────────────────
+
+/// The text to dispatch for one incoming message, or `None` to ignore it.
+///
+/// Private chats and `always` mode pass everything; in groups, `mention`
+/// needs an `@bot_username` and `reply` a reply to one of the bot's
+/// messages.  A mention of the bot is removed from the text either way, and
+/// a message that is nothing but the mention is ignored.
+fn filter_message(
+    mode: RespondMode,
+    private: bool,
+    text: &str,
+    bot_username: Option<&str>,
+    replies_to_bot: bool,
+) -> Option<String> {
+    let stripped = bot_username.and_then(|name| strip_mention(text, name));
+    let addressed = private
+        || match mode {
+            RespondMode::Always => true,
+            RespondMode::Mention => stripped.is_some(),
+            RespondMode::Reply => replies_to_bot,
+        };
+    if !addressed {
+        return None;
+    }
+    let text = stripped.unwrap_or_else(|| text.trim().to_string());
+    (!text.is_empty()).then_some(text)
+}
+
+/// `text` without its `@username` mentions (case-insensitive, with a
+/// trailing `,` or `:`), or `None` when it does not mention `username`.
+fn strip_mention(text: &str, username: &str) -> Option<String> {
+    let needle = format!("@{}", username.to_ascii_lowercase());
+    // ASCII lowercasing keeps byte offsets, so positions map back to `text`.
+    let lower = text.to_ascii_lowercase();
+    let mut out = String::with_capacity(text.len());
+    let mut found = false;
+    let (mut kept, mut from) = (0, 0);
+    while let Some(pos) = lower[from..].find(&needle) {
+        let start = from + pos;
+        let end = start + needle.len();
+        from = end;
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        if before.is_some_and(|c| !c.is_whitespace())
+            || after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+        found = true;
+        out.push_str(&text[kept..start]);
+        let mut rest = &text[end..];
+        rest = rest.strip_prefix([',', ':']).unwrap_or(rest);
+        if out.is_empty() || out.ends_with(char::is_whitespace) {
+            rest = rest.trim_start_matches(' ');
+        }
+        kept = text.len() - rest.len();
+        from = kept;
+    }
+    out.push_str(&text[kept..]);
+    found.then(|| out.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mention_mode_ignores_unaddressed_group_messages() {
+        let mode = RespondMode::Mention;
+        assert_eq!(
+            filter_message(mode, false, "lunch anyone?", Some("araliya_bot"), false),
+            None
+        );
+        // A mention of someone else, or inside a longer name, does not count.
+        for text in ["ask @alice", "ask @araliya_bot2", "mail me@araliya_bot"] {
+            assert_eq!(
+                filter_message(mode, false, text, Some("araliya_bot"), false),
+                None
+            );
+        }
+        // Private chats are always answered.
+        assert_eq!(
+            filter_message(mode, true, "hello", Some("araliya_bot"), false).as_deref(),
+            Some("hello")
+        );
+    }
+
+    #[test]
+    fn mentions_are_routed_with_the_mention_removed() {
+        let mode = RespondMode::Mention;
+        for (text, expected) in [
+            ("@araliya_bot what's the weather?", "what's the weather?"),
+            (
+                "hey @Araliya_Bot, what's the weather?",
+                "hey what's the weather?",
+            ),
+            ("what's the weather @araliya_bot", "what's the weather"),
+            ("@araliya_bot: two\nlines", "two\nlines"),
+        ] {
+            assert_eq!(
+                filter_message(mode, false, text, Some("araliya_bot"), false).as_deref(),
+                Some(expected),
+                "{text}"
+            );
+        }
+        // A bare mention carries nothing to answer.
+        assert_eq!(
+            filter_message(mode, false, "@araliya_bot", Some("araliya_bot"), false),
+            None
+        );
+    }
+
+    #[test]
+    fn recent_chats_are_distinct_and_bounded() {
+        let mut recent = VecDeque::new();
+        for id in 0..RECENT_CHATS as i64 + 10 {
+            remember_chat(&mut recent, ChatId(id));
+        }
+        remember_chat(&mut recent, ChatId(20));
+        assert_eq!(recent.len(), RECENT_CHATS);
+        assert_eq!(recent.front(), Some(&ChatId(20)));
+        assert_eq!(recent.iter().filter(|c| **c == ChatId(20)).count(), 1);
+        // The oldest chats were dropped.
+        assert!(!recent.contains(&ChatId(0)));
+    }
+
+    #[test]
+    fn reply_mode_routes_only_replies_to_the_bot() {
+        let mode = RespondMode::Reply;
+        assert_eq!(
+            filter_message(mode, false, "@araliya_bot hi", Some("araliya_bot"), false),
+            None
+        );
+        assert_eq!(
+            filter_message(mode, false, "and tomorrow?", Some("araliya_bot"), true).as_deref(),
+            Some("and tomorrow?")
+        );
+        assert_eq!(
+            filter_message(RespondMode::Always, false, "hi all", None, false).as_deref(),
+            Some("hi all")
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Protected bus methods.
+//!
+//! Some `manage/*` methods expose settings or bulk data and are meant only
+//! for trusted in-process callers (the supervisor adapters, tests).  The
+//! check is enforced by [`BusHandle::restricted`]: components that act for
+//! untrusted callers — the comms channels, a remote bridge — are given a
+//! restricted handle, which answers protected methods with [`ERR_FORBIDDEN`].
+//!
+//! [`BusHandle::restricted`]: super::handle::BusHandle::restricted
+
+/// JSON-RPC server-error code for a call refused by the ACL.
+pub const ERR_FORBIDDEN: i32 = -32003;
+
+/// Methods that must never be reachable from untrusted callers.
+pub const PROTECTED_METHODS: &[&str] = &[
+    "manage/config",
+    "manage/logs",
+    "manage/maintenance/on",
+    "manage/maintenance/off",
+    "manage/memory/export",
+    "manage/memory/import",
+    "memory/export",
+    "memory/import",
+];
+
+/// Returns `true` if `method` is in [`PROTECTED_METHODS`].
+///
+/// Query strings (`manage/logs?limit=10`) are ignored when matching.
+pub fn is_protected(method: &str) -> bool {
+    let base = method.split('?').next().unwrap_or(method);
+    PROTECTED_METHODS.contains(&base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manage_config_is_protected() {
+        assert!(is_protected("manage/config"));
+        assert!(is_protected("manage/config?x=1"));
+    }
+
+    #[test]
+    fn manage_logs_is_protected_with_query() {
+        assert!(is_protected("manage/logs"));
+        assert!(is_protected("manage/logs?level=debug&limit=200"));
+    }
+
+    #[tokio::test]
+    async fn restricted_handle_refuses_protected_methods() {
+        use crate::bus::{BusMessage, BusPayload, SupervisorBus};
+
+        let mut bus = SupervisorBus::new(4);
+        let restricted = bus.handle.restricted();
+
+        // Refused locally: nothing reaches the supervisor side.
+        let err = restricted
+            .request("manage/config", BusPayload::Empty)
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code, ERR_FORBIDDEN);
+        assert!(bus.rx.try_recv().is_err());
+
+        // Ordinary methods still go through.
+        restricted.notify("manage/tree", BusPayload::Empty).unwrap();
+        match bus.rx.try_recv() {
+            Ok(BusMessage::Notification { method, .. }) => assert_eq!(method, "manage/tree"),
+            _ => panic!("expected the notification to reach the supervisor"),
+        }
+    }
+
+    #[tokio::test]
+    async fn restricted_handle_cannot_toggle_maintenance() {
+        use crate::bus::{BusPayload, SupervisorBus};
+
+        let mut bus = SupervisorBus::new(4);
+        let restricted = bus.handle.restricted();
+        for method in ["manage/maintenance/on", "manage/maintenance/off"] {
+            let err = restricted
+                .request(method, BusPayload::Empty)
+                .await
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(err.code, ERR_FORBIDDEN, "{method}");
+        }
+        restricted
+            .notify("manage/maintenance/on", BusPayload::Empty)
+            .unwrap();
+        assert!(bus.rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn ordinary_methods_are_not_protected() {
+        assert!(!is_protected("manage/tree"));
+        assert!(!is_protected("agents"));
+        assert!(!is_protected("manage/configure"));
+    }
+}
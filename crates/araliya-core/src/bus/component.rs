@@ -53,6 +53,10 @@ pub struct ComponentInfo {
     pub uptime_ms: Option<u64>,
     /// Child components, sorted by id.
     pub children: Vec<ComponentInfo>,
+    /// Optional component-specific details for UIs (e.g. an agent's
+    /// capabilities).  Omitted from the JSON when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 // ── ComponentStatusResponse ───────────────────────────────────────────────────
@@ -117,9 +121,16 @@ impl ComponentInfo {
             state: ComponentStatus::On,
             uptime_ms: None,
             children,
+            metadata: None,
         }
     }
 
+    /// Attach component-specific metadata to this node.
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
     /// A running leaf node (no children).
     pub fn leaf(id: &str, name: &str) -> Self {
         Self::running(id, name, vec![])
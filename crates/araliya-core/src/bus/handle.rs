@@ -4,7 +4,8 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{debug, trace, warn};
 use uuid::Uuid;
 
-use super::message::{BusMessage, BusPayload, BusResult};
+use super::acl;
+use super::message::{BusError, BusMessage, BusPayload, BusResult};
 
 // ── Call error ───────────────────────────────────────────────────────────────
 
@@ -38,9 +39,23 @@ impl std::error::Error for BusCallError {}
 #[derive(Clone)]
 pub struct BusHandle {
     tx: mpsc::Sender<BusMessage>,
+    /// Refuse [`acl::PROTECTED_METHODS`]; set on handles given to components
+    /// that forward requests from untrusted callers.
+    restricted: bool,
 }
 
 impl BusHandle {
+    /// A clone of this handle that cannot call protected methods.
+    ///
+    /// Requests for a method in [`acl::PROTECTED_METHODS`] are answered with
+    /// [`acl::ERR_FORBIDDEN`] without reaching the supervisor.
+    pub fn restricted(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            restricted: true,
+        }
+    }
+
     /// Send a request and wait for exactly one reply.
     pub async fn request(
         &self,
@@ -50,6 +65,13 @@ impl BusHandle {
         let (reply_tx, reply_rx) = oneshot::channel();
         let id = Uuid::new_v4();
         let method = method.into();
+        if self.restricted && acl::is_protected(&method) {
+            warn!(%id, %method, "bus: protected method refused on restricted handle");
+            return Ok(Err(BusError::new(
+                acl::ERR_FORBIDDEN,
+                format!("method not permitted: {method}"),
+            )));
+        }
         debug!(%id, %method, "bus: sending request");
         trace!(%id, %method, payload = ?payload, "bus: request payload");
         self.tx
@@ -85,6 +107,10 @@ impl BusHandle {
         payload: BusPayload,
     ) -> Result<(), BusCallError> {
         let method = method.into();
+        if self.restricted && acl::is_protected(&method) {
+            warn!(%method, "bus: protected notification dropped on restricted handle");
+            return Ok(());
+        }
         debug!(%method, "bus: sending notification");
         trace!(%method, payload = ?payload, "bus: notification payload");
         match self
@@ -130,7 +156,10 @@ impl SupervisorBus {
         let (tx, rx) = mpsc::channel(buffer);
         Self {
             rx,
-            handle: BusHandle { tx },
+            handle: BusHandle {
+                tx,
+                restricted: false,
+            },
         }
     }
 }
@@ -16,12 +16,28 @@
 //! spawn a background task that runs a lightweight check on a timer and calls
 //! the reporter.  Other subsystems simply set healthy at startup and unhealthy
 //! on errors.
+//!
+//! # Startup grace
+//!
+//! A registry built with [`HealthRegistry::with_startup_grace`] forgives
+//! failures for a while after startup (`[health] startup_grace_seconds`):
+//! until the grace ends, `set_unhealthy*` records the subsystem as
+//! *starting* — healthy but not ready — so a provider that is still warming
+//! up does not fail the bot's health check.
+//!
+//! # Change feed
+//!
+//! Every write that changes a subsystem's `healthy`, `ready` or `message` is
+//! also broadcast to [`HealthRegistry::subscribe`] receivers, so live
+//! clients (the management socket's `Subscribe` command) need not poll.
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
+use tokio::time::Instant;
 
 // ── SubsystemHealth ───────────────────────────────────────────────────────────
 
@@ -32,6 +48,10 @@ pub struct SubsystemHealth {
     pub id: String,
     /// `true` = healthy; `false` = unhealthy or degraded.
     pub healthy: bool,
+    /// `false` while the subsystem is still starting (a failure inside the
+    /// startup grace period).
+    #[serde(default = "default_ready")]
+    pub ready: bool,
     /// Human-readable status message.
     pub message: String,
     /// Optional structured extra fields (latency, counts, …).
@@ -39,11 +59,16 @@ pub struct SubsystemHealth {
     pub details: Option<serde_json::Value>,
 }
 
+fn default_ready() -> bool {
+    true
+}
+
 impl SubsystemHealth {
     pub fn ok(id: impl Into<String>) -> Self {
         Self {
             id: id.into(),
             healthy: true,
+            ready: true,
             message: "ok".into(),
             details: None,
         }
@@ -53,11 +78,24 @@ impl SubsystemHealth {
         Self {
             id: id.into(),
             healthy: false,
+            ready: true,
             message: message.into(),
             details: None,
         }
     }
 
+    /// A failure inside the startup grace period: healthy, not yet ready.
+    /// The failure reason is kept in `details.last_error`.
+    pub fn starting(id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            healthy: true,
+            ready: false,
+            message: "starting".into(),
+            details: Some(serde_json::json!({ "last_error": reason.into() })),
+        }
+    }
+
     pub fn with_details(mut self, details: serde_json::Value) -> Self {
         self.details = Some(details);
         self
@@ -66,12 +104,29 @@ impl SubsystemHealth {
 
 // ── HealthRegistry ────────────────────────────────────────────────────────────
 
+/// Changes a slow subscriber may fall behind by before it misses some.
+const CHANGE_FEED_CAPACITY: usize = 64;
+
 /// Shared registry of per-subsystem health states.
 ///
 /// Clone freely — it is backed by an `Arc` and is `Send + Sync`.
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct HealthRegistry {
     inner: Arc<RwLock<HashMap<String, SubsystemHealth>>>,
+    /// End of the startup grace period; `None` = no grace.
+    grace_until: Option<Instant>,
+    /// Feed of state changes (see [`subscribe`](Self::subscribe)).
+    changes: broadcast::Sender<SubsystemHealth>,
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self {
+            inner: Arc::default(),
+            grace_until: None,
+            changes: broadcast::channel(CHANGE_FEED_CAPACITY).0,
+        }
+    }
 }
 
 impl HealthRegistry {
@@ -79,6 +134,18 @@ impl HealthRegistry {
         Self::default()
     }
 
+    /// Report failures as *starting* rather than unhealthy for `grace` from
+    /// now.  Call before handing out reporters; they copy the setting.
+    pub fn with_startup_grace(mut self, grace: Duration) -> Self {
+        self.grace_until = (!grace.is_zero()).then(|| Instant::now() + grace);
+        self
+    }
+
+    /// Whether the startup grace period is still running.
+    fn in_grace(&self) -> bool {
+        self.grace_until.is_some_and(|until| Instant::now() < until)
+    }
+
     /// Create a reporter handle for a subsystem.
     ///
     /// The reporter writes into this registry under the given `id`.
@@ -89,6 +156,12 @@ impl HealthRegistry {
         }
     }
 
+    /// Receive every subsystem state that differs from the one before it
+    /// (in `healthy`, `ready` or `message`) from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<SubsystemHealth> {
+        self.changes.subscribe()
+    }
+
     /// Snapshot all current health states, sorted by id.
     pub async fn snapshot(&self) -> Vec<SubsystemHealth> {
         let map = self.inner.read().await;
@@ -101,6 +174,15 @@ impl HealthRegistry {
     pub async fn all_healthy(&self) -> bool {
         self.inner.read().await.values().all(|h| h.healthy)
     }
+
+    /// `true` if every registered subsystem is healthy and done starting.
+    pub async fn all_ready(&self) -> bool {
+        self.inner
+            .read()
+            .await
+            .values()
+            .all(|h| h.healthy && h.ready)
+    }
 }
 
 // ── HealthReporter ────────────────────────────────────────────────────────────
@@ -136,20 +218,32 @@ impl HealthReporter {
         self.write(h).await;
     }
 
-    /// Mark the subsystem as unhealthy with a reason message.
+    /// Mark the subsystem as unhealthy with a reason message — or as
+    /// starting, inside the startup grace period.
     pub async fn set_unhealthy(&self, message: impl Into<String>) {
-        self.write(SubsystemHealth::degraded(&self.id, message))
-            .await;
+        self.set_unhealthy_with(message, None).await;
     }
 
-    /// Mark as unhealthy with a reason and optional structured details.
+    /// Mark as unhealthy with a reason and optional structured details — or
+    /// as starting, inside the startup grace period.
     pub async fn set_unhealthy_with(
         &self,
         message: impl Into<String>,
         details: Option<serde_json::Value>,
     ) {
-        let mut h = SubsystemHealth::degraded(&self.id, message);
-        h.details = details;
+        let h = if self.registry.in_grace() {
+            let mut h = SubsystemHealth::starting(&self.id, message);
+            if let (Some(serde_json::Value::Object(extra)), Some(serde_json::Value::Object(d))) =
+                (details, h.details.as_mut())
+            {
+                d.extend(extra);
+            }
+            h
+        } else {
+            let mut h = SubsystemHealth::degraded(&self.id, message);
+            h.details = details;
+            h
+        };
         self.write(h).await;
     }
 
@@ -162,7 +256,18 @@ impl HealthReporter {
     }
 
     async fn write(&self, h: SubsystemHealth) {
-        self.registry.inner.write().await.insert(self.id.clone(), h);
+        let previous = self
+            .registry
+            .inner
+            .write()
+            .await
+            .insert(self.id.clone(), h.clone());
+        let changed = previous
+            .is_none_or(|p| p.healthy != h.healthy || p.ready != h.ready || p.message != h.message);
+        if changed {
+            // No subscribers is fine.
+            let _ = self.registry.changes.send(h);
+        }
     }
 }
 
@@ -235,6 +340,24 @@ mod tests {
         assert_eq!(ids, vec!["agents", "cron", "tools"]);
     }
 
+    #[tokio::test]
+    async fn subscribers_see_changes_but_not_repeats() {
+        let registry = HealthRegistry::new();
+        let mut changes = registry.subscribe();
+        let reporter = registry.reporter("llm");
+
+        reporter.set_healthy().await;
+        reporter.set_healthy().await;
+        reporter.set_unhealthy("timeout").await;
+
+        let first = changes.recv().await.unwrap();
+        assert!(first.healthy);
+        let second = changes.recv().await.unwrap();
+        assert!(!second.healthy);
+        assert_eq!(second.message, "timeout");
+        assert!(changes.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn get_current_returns_none_before_first_write() {
         let registry = HealthRegistry::new();
@@ -303,6 +426,32 @@ mod tests {
         assert_eq!(details["latency_ms"], 120);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn failures_inside_the_startup_grace_report_starting() {
+        let registry = HealthRegistry::new().with_startup_grace(Duration::from_secs(30));
+        let reporter = registry.reporter("llm");
+
+        reporter.set_unhealthy("dns not ready").await;
+        let h = reporter.get_current().await.unwrap();
+        assert!(h.healthy);
+        assert!(!h.ready);
+        assert_eq!(h.message, "starting");
+        assert_eq!(h.details.unwrap()["last_error"], "dns not ready");
+        assert!(registry.all_healthy().await);
+        assert!(!registry.all_ready().await);
+
+        // Success during the grace is plain ready.
+        reporter.set_healthy().await;
+        assert!(registry.all_ready().await);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        reporter.set_unhealthy("connection refused").await;
+        let h = reporter.get_current().await.unwrap();
+        assert!(!h.healthy);
+        assert_eq!(h.message, "connection refused");
+        assert!(!registry.all_healthy().await);
+    }
+
     #[tokio::test]
     async fn empty_registry_all_healthy_is_true() {
         let registry = HealthRegistry::new();
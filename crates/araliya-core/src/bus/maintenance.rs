@@ -0,0 +1,74 @@
+//! Maintenance mode — a shared switch that short-circuits agent requests.
+//!
+//! The supervisor builds one [`Maintenance`] from `[supervisor] maintenance`
+//! and hands clones to the agents subsystem, which answers every routed
+//! agent request with [`Maintenance::message`] while the switch is on, and to
+//! the management subsystem, which flips it via `manage/maintenance/{on,off}`.
+//! Health and status routes are unaffected.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::DEFAULT_MAINTENANCE_MESSAGE;
+
+/// Shared maintenance switch.
+///
+/// Clone freely — it is backed by an `Arc` and is `Send + Sync`.
+#[derive(Debug, Clone)]
+pub struct Maintenance {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    enabled: AtomicBool,
+    message: String,
+}
+
+impl Maintenance {
+    pub fn new(enabled: bool, message: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                enabled: AtomicBool::new(enabled),
+                message: message.into(),
+            }),
+        }
+    }
+
+    /// `true` while agent requests are being answered with the fixed message.
+    pub fn is_enabled(&self) -> bool {
+        self.inner.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turn maintenance mode on or off. Returns the previous state.
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        self.inner.enabled.swap(enabled, Ordering::Relaxed)
+    }
+
+    /// Reply sent for agent requests while maintenance mode is on.
+    pub fn message(&self) -> &str {
+        &self.inner.message
+    }
+}
+
+impl Default for Maintenance {
+    fn default() -> Self {
+        Self::new(false, DEFAULT_MAINTENANCE_MESSAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_state() {
+        let a = Maintenance::default();
+        let b = a.clone();
+        assert!(!b.is_enabled());
+        assert!(!a.set_enabled(true));
+        assert!(b.is_enabled());
+        assert!(b.set_enabled(false));
+        assert!(!a.is_enabled());
+    }
+}
@@ -55,6 +55,62 @@ impl Clone for StreamReceiver {
     }
 }
 
+/// Receiver for a [`BusPayload::ByteStream`]: chunks in order, then the
+/// channel closes.  An `Err` chunk ends the stream early.
+///
+/// In-process only, like [`StreamReceiver`].
+pub struct ByteReceiver(pub mpsc::Receiver<Result<Vec<u8>, String>>);
+
+impl Serialize for ByteReceiver {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteReceiver {
+    fn deserialize<D: serde::Deserializer<'de>>(_d: D) -> Result<Self, D::Error> {
+        Err(serde::de::Error::custom(
+            "ByteReceiver cannot be deserialized (in-process only)",
+        ))
+    }
+}
+
+impl std::fmt::Debug for ByteReceiver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteReceiver").finish_non_exhaustive()
+    }
+}
+
+impl Clone for ByteReceiver {
+    fn clone(&self) -> Self {
+        panic!("ByteReceiver::clone called — ByteStream must not be cloned");
+    }
+}
+
+// ── Agent chains ─────────────────────────────────────────────────────────────
+
+/// The hops one inbound agent request has taken so far — handoffs and tool
+/// calls, in order — counted against `[agents] max_depth`.
+///
+/// `id` is minted when the request first reaches the agents subsystem and
+/// travels with every handoff ([`BusPayload::AgentRequest`]), so the whole
+/// chain shows up under one id in the logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentChain {
+    pub id: Uuid,
+    pub hops: Vec<String>,
+}
+
+impl AgentChain {
+    /// A new chain with no hops yet.
+    pub fn start() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            hops: Vec::new(),
+        }
+    }
+}
+
 // ── Payload ──────────────────────────────────────────────────────────────────
 
 /// All known message bodies. Add one variant per new message type.
@@ -105,6 +161,10 @@ pub enum BusPayload {
         /// Override the provider's configured model for this request only.
         #[serde(default)]
         model_override: Option<String>,
+        /// `Json` turns on the provider's JSON mode (or a JSON-only
+        /// instruction) and validates the reply.
+        #[serde(default)]
+        response_format: crate::types::llm::ResponseFormat,
     },
     /// Request tool execution in the tools subsystem.
     ToolRequest {
@@ -113,6 +173,10 @@ pub enum BusPayload {
         args_json: String,
         channel_id: String,
         session_id: Option<String>,
+        /// Agent making the call.  Tools over agent-owned data (`memory`)
+        /// only show that agent's own.
+        #[serde(default)]
+        agent_id: Option<String>,
     },
     /// Structured tool execution reply.
     ToolResponse {
@@ -144,6 +208,13 @@ pub enum BusPayload {
         payload_json: String,
         /// Scheduling specification.
         spec: CronScheduleSpec,
+        /// Hold firings that land in `[cron] quiet_hours`.
+        #[serde(default)]
+        quiet_hours: bool,
+        /// Send each firing as a request and treat an error reply (or none)
+        /// as a failed dispatch, retried per `[cron] retry_attempts`.
+        #[serde(default)]
+        await_reply: bool,
     },
     /// Reply to a successful `cron/schedule` request.
     CronScheduleResult { schedule_id: String },
@@ -167,13 +238,62 @@ pub enum BusPayload {
     /// Reply to an `llm/stream` request: the caller reads chunks from `rx`.
     ///
     /// The receiver is in-process only and not serializable; see [`StreamReceiver`].
-    LlmStreamResult { rx: StreamReceiver },
+    /// `request_id` is set by the LLM subsystem and can be passed to
+    /// `llm/stream/stop` to end the stream early.  Agents forward it from
+    /// their streamed response pass; it is `None` when the stream is not a
+    /// single `llm/stream` (fixed replies, buffered fallbacks, webbuilder's
+    /// progress stream), which cannot be stopped.
+    LlmStreamResult {
+        rx: StreamReceiver,
+        request_id: Option<String>,
+    },
+
+    /// `payload` for an agent (`agents/{id}/{action}`) with per-request
+    /// context: the chain of the agent handing it off, and the reply format
+    /// the caller asked for in place of `[agents.<id>] response_format`.
+    AgentRequest {
+        chain: Option<AgentChain>,
+        response_format: Option<crate::types::llm::ResponseFormat>,
+        payload: Box<BusPayload>,
+    },
+
+    /// A binary reply read from `rx` as it is produced (`memory/export`
+    /// without a `path`).
+    ByteStream { rx: ByteReceiver },
+
+    /// Several completions in one `llm/batch` request.  Every item must be
+    /// an `LlmRequest`; at most `[llm] batch_concurrency` run at once.
+    LlmBatch { items: Vec<BusPayload> },
+
+    /// Reply to `llm/batch`: one result per item, in request order, and the
+    /// summed usage of the items that reported any.
+    LlmBatchResponse {
+        results: Vec<BusResult>,
+        usage: Option<crate::types::llm::LlmUsage>,
+    },
 
     /// Generic JSON request payload.
     /// Used for control-plane methods like `llm/set_default` that carry
     /// structured data not tied to a specific subsystem type.
     JsonRequest { data: String },
 
+    /// A session's cumulative cost reached `[agents] spend_alert_usd`.
+    /// Sent once per session as a `manage/spend/alert` notification.
+    SpendAlert {
+        session_id: String,
+        total_cost_usd: f64,
+    },
+
+    /// One intermediate step of an agent turn ("calling tool docs/search"),
+    /// sent as a `comms/agent_step` notification when `[agents] emit_steps`
+    /// is on.  Channels may show it; the reply itself is unaffected.
+    AgentStep {
+        channel_id: String,
+        #[serde(default)]
+        session_id: Option<String>,
+        step: String,
+    },
+
     /// No payload — used by notifications whose meaning is in the method alone.
     Empty,
 }
@@ -196,6 +316,15 @@ pub struct CronEntryInfo {
     pub target_method: String,
     pub spec: CronScheduleSpec,
     pub next_fire_unix_ms: u64,
+    /// Whether the schedule observes `[cron] quiet_hours`.
+    #[serde(default)]
+    pub quiet_hours: bool,
+    /// Dispatches of this schedule that failed, retries included.
+    #[serde(default)]
+    pub failures: u64,
+    /// Whether firings are sent as requests and their replies checked.
+    #[serde(default)]
+    pub await_reply: bool,
 }
 
 // ── Error ────────────────────────────────────────────────────────────────────
@@ -217,11 +346,27 @@ impl BusError {
             message: message.into(),
         }
     }
+
+    /// An agent's request failed because `tool` answered `ok: false`.
+    pub fn tool_failed(tool: &str, error: Option<String>) -> Self {
+        Self::new(
+            ERR_TOOL_FAILED,
+            format!(
+                "tool {tool} failed: {}",
+                error.unwrap_or_else(|| "unknown".to_string())
+            ),
+        )
+    }
 }
 
 /// Method not found — mirrors JSON-RPC 2.0 error code -32601.
 pub const ERR_METHOD_NOT_FOUND: i32 = -32601;
 
+/// A tool the agent relied on failed (`ToolResponse { ok: false }`); the
+/// message reads `"tool {tool} failed: {error}"`.  Interactive channels may
+/// offer to retry the request.
+pub const ERR_TOOL_FAILED: i32 = -32008;
+
 pub type BusResult = Result<BusPayload, BusError>;
 
 // ── Message ──────────────────────────────────────────────────────────────────
@@ -3,10 +3,12 @@
 //! This module defines the "language" of the bus. The actual dispatch loop
 //! (supervisor) lives in a separate crate.
 
+pub mod acl;
 pub mod component;
 pub mod dispatch;
 pub mod handle;
 pub mod health;
+pub mod maintenance;
 pub mod message;
 
 // Re-export key types at `bus::` level for convenience.
@@ -14,7 +16,8 @@ pub use component::{ComponentInfo, ComponentStatus, ComponentStatusResponse};
 pub use dispatch::BusHandler;
 pub use handle::{BusCallError, BusHandle, SupervisorBus};
 pub use health::{HealthRegistry, HealthReporter, SubsystemHealth};
+pub use maintenance::Maintenance;
 pub use message::{
-    BusError, BusMessage, BusPayload, BusResult, CronEntryInfo, CronScheduleSpec,
-    ERR_METHOD_NOT_FOUND, StreamReceiver,
+    AgentChain, BusError, BusMessage, BusPayload, BusResult, ByteReceiver, CronEntryInfo,
+    CronScheduleSpec, ERR_METHOD_NOT_FOUND, StreamReceiver,
 };
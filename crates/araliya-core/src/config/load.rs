@@ -10,9 +10,11 @@ use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 
-use crate::error::AppError;
+use crate::error::{AppError, ConfigParseError};
+use crate::logger::DEFAULT_LOG_BUFFER_CAPACITY;
+use crate::types::llm::ResponseFormat;
 
-use super::raw::{self, RawConfig};
+use super::raw::{self, RawConfig, RawSeedMemory};
 use super::types::*;
 
 /// Deep-merge two TOML values.
@@ -35,23 +37,72 @@ fn merge_toml(base: toml::Value, overlay: toml::Value) -> toml::Value {
     }
 }
 
+/// Build a [`ConfigParseError`] for `path`, resolving the error span in `src`
+/// to a line, column and source line.
+fn parse_error(
+    path: &Path,
+    src: &str,
+    err: &toml::de::Error,
+    included_from: &[PathBuf],
+) -> AppError {
+    let (line, column, snippet) = match err.span().and_then(|span| src.get(..span.start)) {
+        Some(before) => {
+            let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+            let snippet = src[line_start..].lines().next().unwrap_or("").to_string();
+            (
+                Some(before.matches('\n').count() + 1),
+                Some(before[line_start..].chars().count() + 1),
+                Some(snippet),
+            )
+        }
+        None => (None, None, None),
+    };
+    AppError::ConfigParse(Box::new(ConfigParseError {
+        path: path.to_path_buf(),
+        line,
+        column,
+        snippet,
+        message: err.message().to_string(),
+        included_from: included_from.to_vec(),
+    }))
+}
+
+/// Suffix naming the files that included `path`, for non-parse chain errors.
+fn chain_note(included_from: &[PathBuf]) -> String {
+    match included_from.last() {
+        Some(parent) => format!(" (base of {})", parent.display()),
+        None => String::new(),
+    }
+}
+
 /// Read a config file, follow any `[meta] base = "..."` chain, and return the
 /// fully merged `toml::Value`. `visited` carries canonicalized paths already
-/// seen in this chain so circular references are caught early.
-fn load_raw_merged(path: &Path, visited: &mut HashSet<PathBuf>) -> Result<toml::Value, AppError> {
+/// seen in this chain so circular references are caught early;
+/// `included_from` lists the files that led here, outermost first.
+fn load_raw_merged(
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    included_from: &[PathBuf],
+) -> Result<toml::Value, AppError> {
     let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
     if !visited.insert(canonical) {
         return Err(AppError::Config(format!(
-            "circular base reference detected at: {}",
-            path.display()
+            "circular base reference detected at: {}{}",
+            path.display(),
+            chain_note(included_from)
         )));
     }
 
-    let raw = fs::read_to_string(path)
-        .map_err(|e| AppError::Config(format!("cannot read {}: {e}", path.display())))?;
+    let raw = fs::read_to_string(path).map_err(|e| {
+        AppError::Config(format!(
+            "cannot read {}: {e}{}",
+            path.display(),
+            chain_note(included_from)
+        ))
+    })?;
 
-    let overlay_val: toml::Value = toml::from_str(&raw)
-        .map_err(|e| AppError::Config(format!("parse error in {}: {e}", path.display())))?;
+    let overlay_val: toml::Value =
+        toml::from_str(&raw).map_err(|e| parse_error(path, &raw, &e, included_from))?;
 
     if let Some(base_str) = overlay_val
         .get("meta")
@@ -63,7 +114,9 @@ fn load_raw_merged(path: &Path, visited: &mut HashSet<PathBuf>) -> Result<toml::
         } else {
             path.parent().unwrap_or(Path::new(".")).join(base_str)
         };
-        let base_val = load_raw_merged(&base_path, visited)?;
+        let mut chain = included_from.to_vec();
+        chain.push(path.to_path_buf());
+        let base_val = load_raw_merged(&base_path, visited, &chain)?;
         Ok(merge_toml(base_val, overlay_val))
     } else {
         Ok(overlay_val)
@@ -101,18 +154,64 @@ pub fn load(config_path: Option<&str>) -> Result<Config, AppError> {
             bot_name: "araliya".to_string(),
             work_dir,
             identity_dir: None,
+            bot_namespace: None,
             log_level,
+            maintenance: false,
+            maintenance_message: DEFAULT_MAINTENANCE_MESSAGE.to_string(),
+            startup_self_test: false,
+            startup_self_test_fatal: false,
+            panic_hook: false,
+            coalesce_requests: false,
+            ready_line: false,
+            sd_notify: false,
+            otel_endpoint: None,
+            log_buffer_capacity: DEFAULT_LOG_BUFFER_CAPACITY,
+            socket_bind_attempts: DEFAULT_SOCKET_BIND_ATTEMPTS,
+            socket_bind_backoff_ms: DEFAULT_SOCKET_BIND_BACKOFF_MS,
+            presence_events: true,
+            control_handshake: false,
             comms: CommsConfig {
-                pty: PtyConfig { enabled: true },
-                telegram: TelegramConfig { enabled: false },
+                pty: PtyConfig {
+                    enabled: true,
+                    default_agent: None,
+                    tool_retry: false,
+                },
+                telegram: TelegramConfig {
+                    enabled: false,
+                    default_agent: None,
+                    respond_mode: RespondMode::Always,
+                },
+                email: EmailConfig::default(),
                 http: HttpConfig {
                     enabled: false,
                     bind: "127.0.0.1:8080".to_string(),
+                    default_agent: None,
+                    slow_request_ms: None,
+                    read_timeout_ms: None,
+                    max_header_bytes: DEFAULT_HTTP_MAX_HEADER_BYTES,
+                    access_log: None,
+                    persist_sessions: true,
+                    max_connections: None,
+                    connection_overflow: ConnectionOverflow::Reject,
+                    cache_ttl_ms: HashMap::new(),
                 },
                 axum_channel: AxumChannelConfig {
                     enabled: false,
                     bind: "127.0.0.1:8080".to_string(),
+                    default_agent: None,
                 },
+                max_input_chars: None,
+                input_overflow: InputOverflow::Truncate,
+                strip_control_chars: false,
+                channels: None,
+                auto_session: false,
+                inline_routing: false,
+                agent_switching: false,
+                command_prefix: DEFAULT_COMMAND_PREFIX.to_string(),
+                event_buffer: DEFAULT_COMMS_EVENT_BUFFER,
+                idle_timeout_seconds: None,
+                shutdown_message: None,
+                expose_config: false,
             },
             agents: AgentsConfig {
                 default_agent: "basic_chat".to_string(),
@@ -121,15 +220,39 @@ pub fn load(config_path: Option<&str>) -> Result<Config, AppError> {
                 agent_memory: HashMap::new(),
                 agent_skills: HashMap::new(),
                 agent_aggregation_targets: HashMap::new(),
+                strict_default: false,
+                noop_fallback: false,
+                agent_cooldowns: HashMap::new(),
+                agent_context_turns: HashMap::new(),
+                agent_seed_memory: HashMap::new(),
+                agent_max_sessions: HashMap::new(),
+                spend_alert_usd: None,
+                daily_output_token_budget: None,
+                empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+                allow_empty_input: HashSet::new(),
+                empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+                auto_title: false,
+                debug_prompt: false,
+                agent_empty_replies: HashMap::new(),
+                persist_context: HashSet::new(),
+                agent_response_formats: HashMap::new(),
+                moderation: None,
+                error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+                tool_output: ToolOutputLimits::default(),
                 news_query: None,
                 gdelt_query: None,
                 newsroom_query: None,
                 agent_docs: HashMap::new(),
                 agentic_chat: None,
+                echo: None,
+                summarize: None,
                 runtime_cmd: None,
                 webbuilder: None,
                 homebuilder: None,
                 debug_logging: false,
+                emit_steps: false,
+                max_depth: DEFAULT_AGENT_MAX_DEPTH,
+                link_policy: LinkPolicy::Allow,
                 uniweb_session_id: None,
                 uniweb_use_instruction_llm: false,
             },
@@ -138,6 +261,19 @@ pub fn load(config_path: Option<&str>) -> Result<Config, AppError> {
                 providers: HashMap::new(),
                 instruction: None,
                 routes: HashMap::new(),
+                auto_route: Vec::new(),
+                audit_log: None,
+                audit_full: false,
+                audit_max_bytes: None,
+                max_prompt_chars: None,
+                batch_concurrency: DEFAULT_LLM_BATCH_CONCURRENCY,
+                overall_deadline_seconds: None,
+                warmup: false,
+                allowed_models: Vec::new(),
+                embedding: None,
+                strip_patterns: Vec::new(),
+                faulty: FaultyConfig::default(),
+                pool: HttpPoolConfig::default(),
             },
             openai_api_key: env::var("OPENAI_API_KEY").ok(),
             ui: UiConfig {
@@ -153,13 +289,29 @@ pub fn load(config_path: Option<&str>) -> Result<Config, AppError> {
                     tsec_last: None,
                     q: None,
                 },
+                memory: MemoryToolConfig {
+                    max_results: raw::default_memory_tool_max_results(),
+                    all_sessions: false,
+                },
             },
             runtimes: RuntimesConfig {
                 enabled: true,
                 default_timeout_secs: 30,
             },
+            health: HealthConfig {
+                refresh_timeout_ms: raw::default_health_refresh_timeout_ms(),
+                startup_grace_seconds: 0,
+            },
+            cron: CronConfig::default(),
             memory_kv_cap: Some(200),
             memory_transcript_cap: Some(500),
+            memory_max_sessions: None,
+            memory_session_overflow: SessionOverflow::Evict,
+            memory_transcript_format: TranscriptFormat::Md,
+            memory_session_idle_archive_minutes: None,
+            memory_spend_snapshot_minutes: None,
+            memory_compress_archived_transcripts: false,
+            memory_write_fallback: false,
         })
     }
 }
@@ -172,7 +324,7 @@ pub fn load_from(
     work_dir_override: Option<&str>,
     log_level_override: Option<&str>,
 ) -> Result<Config, AppError> {
-    let merged_val = load_raw_merged(path, &mut HashSet::new())?;
+    let merged_val = load_raw_merged(path, &mut HashSet::new(), &[])?;
 
     let parsed: RawConfig =
         Deserialize::deserialize(merged_val).map_err(|e: toml::de::Error| {
@@ -188,6 +340,24 @@ pub fn load_from(
         let p = PathBuf::from(identity_dir);
         if p.is_absolute() { p } else { work_dir.join(p) }
     });
+    let http_access_log = parsed.comms.http.access_log.as_deref().map(|p| {
+        let p = expand_home(p);
+        if p.is_absolute() { p } else { work_dir.join(p) }
+    });
+    let bot_namespace = match s.bot_namespace.map(|ns| ns.trim().to_string()) {
+        Some(ns) if ns.is_empty() => None,
+        Some(ns)
+            if !ns
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            return Err(AppError::Config(format!(
+                "config error in {}: supervisor.bot_namespace may only contain letters, digits, '-' and '_', got {ns:?}",
+                path.display()
+            )));
+        }
+        ns => ns,
+    };
 
     let news_query = parsed
         .agents
@@ -258,6 +428,9 @@ pub fn load_from(
                     index: entry.index.clone(),
                     use_kg: entry.use_kg,
                     kg,
+                    embedding_cache_entries: entry
+                        .embedding_cache_entries
+                        .unwrap_or(DEFAULT_EMBEDDING_CACHE_ENTRIES),
                 },
             )
         })
@@ -272,6 +445,56 @@ pub fn load_from(
                 use_instruction_llm: entry.use_instruction_llm,
             });
 
+    let echo_cfg = match parsed.agents.entries.get("echo") {
+        Some(entry) => {
+            let case = match entry.case.as_deref() {
+                None | Some("verbatim") => EchoCase::Verbatim,
+                Some("upper") => EchoCase::Upper,
+                Some("lower") => EchoCase::Lower,
+                Some(other) => {
+                    return Err(AppError::Config(format!(
+                        "config error in {}: agents.echo.case must be \"verbatim\", \"upper\" or \"lower\", got {other:?}",
+                        path.display()
+                    )));
+                }
+            };
+            Some(EchoAgentConfig {
+                prefix: entry.prefix.clone().unwrap_or_default(),
+                suffix: entry.suffix.clone().unwrap_or_default(),
+                case,
+                delay_ms: entry.delay_ms.unwrap_or(0),
+            })
+        }
+        None => None,
+    };
+
+    let summarize_cfg = match parsed.agents.entries.get("summarize") {
+        Some(entry) => {
+            let style = match entry.style.as_deref() {
+                None | Some("bullets") => SummaryStyle::Bullets,
+                Some("prose") => SummaryStyle::Prose,
+                Some(other) => {
+                    return Err(AppError::Config(format!(
+                        "config error in {}: agents.summarize.style must be \"bullets\" or \"prose\", got {other:?}",
+                        path.display()
+                    )));
+                }
+            };
+            let max_words = match entry.max_words {
+                None => DEFAULT_SUMMARY_MAX_WORDS,
+                Some(0) => {
+                    return Err(AppError::Config(format!(
+                        "config error in {}: agents.summarize.max_words must be greater than 0",
+                        path.display()
+                    )));
+                }
+                Some(n) => n,
+            };
+            Some(SummarizeAgentConfig { style, max_words })
+        }
+        None => None,
+    };
+
     let runtime_cmd_cfg = parsed.agents.entries.get("runtime_cmd").map(|entry| {
         let defaults = RuntimeCmdAgentConfig::default();
         RuntimeCmdAgentConfig {
@@ -304,9 +527,198 @@ pub fn load_from(
         }
     });
 
+    let comms_channels = parsed.comms.channels.clone();
+    if let Some(unknown) = comms_channels
+        .iter()
+        .flatten()
+        .find(|c| !COMMS_CHANNELS.contains(&c.as_str()))
+    {
+        return Err(AppError::Config(format!(
+            "config error in {}: unknown channel {unknown:?} in comms.channels (expected one of {COMMS_CHANNELS:?})",
+            path.display()
+        )));
+    }
+
+    let input_overflow = match parsed.comms.input_overflow.as_deref() {
+        None | Some("truncate") => InputOverflow::Truncate,
+        Some("reject") => InputOverflow::Reject,
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: comms.input_overflow must be \"truncate\" or \"reject\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let http_connection_overflow = match parsed.comms.http.connection_overflow.as_deref() {
+        None | Some("reject") => ConnectionOverflow::Reject,
+        Some("wait") => ConnectionOverflow::Wait,
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: comms.http.connection_overflow must be \"reject\" or \"wait\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let link_policy = match (
+        parsed.agents.link_policy.as_deref(),
+        parsed.agents.link_rewrite.clone(),
+    ) {
+        (None | Some("allow"), _) => LinkPolicy::Allow,
+        (Some("strip"), _) => LinkPolicy::Strip,
+        (Some("rewrite"), Some(template)) => LinkPolicy::Rewrite { template },
+        (Some("rewrite"), None) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: agents.link_policy = \"rewrite\" needs agents.link_rewrite",
+                path.display()
+            )));
+        }
+        (Some(other), _) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: agents.link_policy must be \"allow\", \"strip\" or \"rewrite\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let telegram_respond_mode = match parsed.comms.telegram.respond_mode.as_deref() {
+        None | Some("always") => RespondMode::Always,
+        Some("mention") => RespondMode::Mention,
+        Some("reply") => RespondMode::Reply,
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: comms.telegram.respond_mode must be \"always\", \"mention\" or \"reply\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let memory_session_overflow = match parsed.memory.session_overflow.as_deref() {
+        None | Some("evict") => SessionOverflow::Evict,
+        Some("reject") => SessionOverflow::Reject,
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: memory.session_overflow must be \"evict\" or \"reject\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let quiet_hours = parsed
+        .cron
+        .quiet_hours
+        .map(|raw| {
+            parse_quiet_hours(&raw).map_err(|e| {
+                AppError::Config(format!(
+                    "config error in {}: cron.quiet_hours {e}",
+                    path.display()
+                ))
+            })
+        })
+        .transpose()?;
+
+    let memory_transcript_format = match parsed.memory.transcript_format.as_deref() {
+        None | Some("md") => TranscriptFormat::Md,
+        Some("jsonl") => TranscriptFormat::Jsonl,
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: memory.transcript_format must be \"md\" or \"jsonl\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let mut agent_response_formats = HashMap::new();
+    for (id, entry) in &parsed.agents.entries {
+        let format = match entry.response_format.as_deref() {
+            None | Some("text") => continue,
+            Some("json") => ResponseFormat::Json,
+            Some(other) => {
+                return Err(AppError::Config(format!(
+                    "config error in {}: agents.{id}.response_format must be \"text\" or \"json\", got {other:?}",
+                    path.display()
+                )));
+            }
+        };
+        agent_response_formats.insert(id.clone(), format);
+    }
+
+    let embedding = match parsed.llm.embedding_provider.as_deref() {
+        None => None,
+        Some("openai") => Some(EmbeddingConfig {
+            provider: EmbeddingProviderKind::OpenAi,
+            base_url: parsed
+                .llm
+                .embedding_base_url
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_EMBEDDINGS_URL.to_string()),
+            model: parsed
+                .llm
+                .embedding_model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OPENAI_EMBEDDING_MODEL.to_string()),
+        }),
+        Some("local") => {
+            let Some(base_url) = parsed.llm.embedding_base_url.clone() else {
+                return Err(AppError::Config(format!(
+                    "config error in {}: llm.embedding_provider = \"local\" requires llm.embedding_base_url",
+                    path.display()
+                )));
+            };
+            Some(EmbeddingConfig {
+                provider: EmbeddingProviderKind::Local,
+                base_url,
+                model: parsed.llm.embedding_model.clone().unwrap_or_default(),
+            })
+        }
+        Some(other) => {
+            return Err(AppError::Config(format!(
+                "config error in {}: llm.embedding_provider must be \"openai\" or \"local\", got {other:?}",
+                path.display()
+            )));
+        }
+    };
+
+    let mut strip_patterns = Vec::new();
+    let reasoning = parsed
+        .llm
+        .strip_reasoning
+        .then_some(REASONING_STRIP_PATTERN);
+    for raw in parsed
+        .llm
+        .strip_patterns
+        .iter()
+        .map(String::as_str)
+        .chain(reasoning)
+    {
+        let Some(pattern) = StripPattern::parse(raw) else {
+            return Err(AppError::Config(format!(
+                "config error in {}: llm.strip_patterns entry {raw:?} must look like \"OPEN...CLOSE\"",
+                path.display()
+            )));
+        };
+        if !strip_patterns.contains(&pattern) {
+            strip_patterns.push(pattern);
+        }
+    }
+
+    if parsed.llm.faulty.fail_percent > 100 {
+        return Err(AppError::Config(format!(
+            "config error in {}: llm.faulty.fail_percent must be 0-100, got {}",
+            path.display(),
+            parsed.llm.faulty.fail_percent
+        )));
+    }
+
     // Instruction LLM: just a reference to another provider name in the same map.
     let instruction_llm = parsed.llm.instruction.clone();
 
+    let audit_log = parsed.llm.audit_log.as_deref().map(|p| {
+        let p = expand_home(p);
+        if p.is_absolute() { p } else { work_dir.join(p) }
+    });
+
     let providers: HashMap<String, ProviderConfig> = parsed
         .llm
         .providers
@@ -335,6 +747,10 @@ pub fn load_from(
                 ApiType::OpenAiResponses => "https://api.openai.com/v1/responses".to_string(),
                 ApiType::Dummy => String::new(),
             });
+            let context_window = raw
+                .context_window
+                .filter(|&n| n > 0)
+                .unwrap_or_else(|| default_context_window(&raw.model));
             Ok((
                 name,
                 ProviderConfig {
@@ -346,6 +762,8 @@ pub fn load_from(
                     reasoning_effort: raw.reasoning_effort,
                     timeout_seconds: raw.timeout_seconds,
                     max_tokens: raw.max_tokens,
+                    prompt_caching: raw.prompt_caching,
+                    context_window,
                     input_per_million_usd: raw.input_per_million_usd,
                     output_per_million_usd: raw.output_per_million_usd,
                     cached_input_per_million_usd: raw.cached_input_per_million_usd,
@@ -370,26 +788,111 @@ pub fn load_from(
         })
         .collect();
 
+    // Size-based provider selection, tried in order.
+    let auto_route: Vec<AutoRouteRule> = parsed
+        .llm
+        .auto_route
+        .into_iter()
+        .map(|raw| AutoRouteRule {
+            min_tokens: raw.min_tokens,
+            max_tokens: raw.max_tokens,
+            provider: raw.provider,
+        })
+        .collect();
+
     Ok(Config {
         bot_name: s.bot_name,
         work_dir,
         identity_dir,
+        bot_namespace,
         log_level,
+        maintenance: s.maintenance,
+        maintenance_message: s
+            .maintenance_message
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_MAINTENANCE_MESSAGE.to_string()),
+        startup_self_test: s.startup_self_test,
+        startup_self_test_fatal: s.startup_self_test_fatal,
+        panic_hook: s.panic_hook,
+        coalesce_requests: s.coalesce_requests,
+        ready_line: s.ready_line,
+        sd_notify: s.sd_notify,
+        otel_endpoint: s.otel_endpoint.filter(|e| !e.trim().is_empty()),
+        log_buffer_capacity: s.log_buffer_capacity.unwrap_or(DEFAULT_LOG_BUFFER_CAPACITY),
+        socket_bind_attempts: s
+            .socket_bind_attempts
+            .unwrap_or(DEFAULT_SOCKET_BIND_ATTEMPTS)
+            .max(1),
+        socket_bind_backoff_ms: s
+            .socket_bind_backoff_ms
+            .unwrap_or(DEFAULT_SOCKET_BIND_BACKOFF_MS),
+        presence_events: s.presence_events,
+        control_handshake: s.control_handshake,
         comms: CommsConfig {
             pty: PtyConfig {
                 enabled: parsed.comms.pty.enabled,
+                default_agent: parsed.comms.pty.default_agent,
+                tool_retry: parsed.comms.pty.tool_retry,
             },
             telegram: TelegramConfig {
                 enabled: parsed.comms.telegram.enabled,
+                default_agent: parsed.comms.telegram.default_agent,
+                respond_mode: telegram_respond_mode,
             },
+            email: email_config(parsed.comms.email),
             http: HttpConfig {
                 enabled: parsed.comms.http.enabled,
                 bind: parsed.comms.http.bind,
+                default_agent: parsed.comms.http.default_agent,
+                slow_request_ms: parsed.comms.http.slow_request_ms.filter(|&ms| ms > 0),
+                read_timeout_ms: parsed.comms.http.read_timeout_ms.filter(|&ms| ms > 0),
+                max_header_bytes: parsed
+                    .comms
+                    .http
+                    .max_header_bytes
+                    .filter(|&n| n > 0)
+                    .unwrap_or(DEFAULT_HTTP_MAX_HEADER_BYTES),
+                access_log: http_access_log,
+                persist_sessions: parsed.comms.http.persist_sessions,
+                max_connections: parsed.comms.http.max_connections.filter(|&n| n > 0),
+                connection_overflow: http_connection_overflow,
+                cache_ttl_ms: parsed
+                    .comms
+                    .http
+                    .cache_ttl_ms
+                    .into_iter()
+                    .filter(|&(_, ms)| ms > 0)
+                    .collect(),
             },
             axum_channel: AxumChannelConfig {
                 enabled: parsed.comms.axum_channel.enabled,
                 bind: parsed.comms.axum_channel.bind,
+                default_agent: parsed.comms.axum_channel.default_agent,
             },
+            max_input_chars: parsed.comms.max_input_chars.filter(|&n| n > 0),
+            input_overflow,
+            strip_control_chars: parsed.comms.strip_control_chars,
+            channels: comms_channels,
+            auto_session: parsed.comms.auto_session,
+            inline_routing: parsed.comms.inline_routing,
+            agent_switching: parsed.comms.agent_switching,
+            command_prefix: parsed
+                .comms
+                .command_prefix
+                .map(|prefix| prefix.trim().to_string())
+                .unwrap_or_else(|| DEFAULT_COMMAND_PREFIX.to_string()),
+            event_buffer: parsed
+                .comms
+                .event_buffer
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_COMMS_EVENT_BUFFER),
+            idle_timeout_seconds: parsed.comms.idle_timeout_seconds.filter(|&n| n > 0),
+            shutdown_message: parsed
+                .comms
+                .shutdown_message
+                .map(|message| message.trim().to_string())
+                .filter(|message| !message.is_empty()),
+            expose_config: parsed.comms.expose_config,
         },
         agents: AgentsConfig {
             default_agent: parsed.agents.default_agent,
@@ -421,15 +924,146 @@ pub fn load_from(
                 .iter()
                 .filter_map(|(id, e)| e.target_agent.as_ref().map(|t| (id.clone(), t.clone())))
                 .collect(),
+            strict_default: parsed.agents.strict_default,
+            noop_fallback: parsed.agents.noop_fallback,
+            spend_alert_usd: parsed.agents.spend_alert_usd.filter(|&usd| usd > 0.0),
+            daily_output_token_budget: parsed
+                .agents
+                .daily_output_token_budget
+                .filter(|&tokens| tokens > 0),
+            empty_input_reply: parsed
+                .agents
+                .empty_input_reply
+                .clone()
+                .filter(|reply| !reply.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EMPTY_INPUT_REPLY.to_string()),
+            allow_empty_input: parsed
+                .agents
+                .entries
+                .iter()
+                .filter(|(_, e)| e.allow_empty_input)
+                .map(|(id, _)| id.clone())
+                .collect(),
+            empty_reply: parsed
+                .agents
+                .empty_reply
+                .clone()
+                .filter(|reply| !reply.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_EMPTY_REPLY.to_string()),
+            agent_empty_replies: parsed
+                .agents
+                .entries
+                .iter()
+                .filter_map(|(id, e)| {
+                    let reply = e.empty_reply.as_ref()?;
+                    (!reply.trim().is_empty()).then(|| (id.clone(), reply.clone()))
+                })
+                .collect(),
+            auto_title: parsed.agents.auto_title,
+            debug_prompt: parsed.agents.debug_prompt,
+            persist_context: parsed
+                .agents
+                .entries
+                .iter()
+                .filter(|(_, e)| e.persist_context)
+                .map(|(id, _)| id.clone())
+                .collect(),
+            agent_cooldowns: parsed
+                .agents
+                .entries
+                .iter()
+                .filter_map(|(id, e)| {
+                    e.cooldown_ms
+                        .filter(|&ms| ms > 0)
+                        .map(|ms| (id.clone(), ms))
+                })
+                .collect(),
+            agent_context_turns: parsed
+                .agents
+                .entries
+                .iter()
+                .filter_map(|(id, e)| {
+                    e.context_turns
+                        .filter(|&turns| turns > 0)
+                        .map(|turns| (id.clone(), turns))
+                })
+                .collect(),
+            agent_seed_memory: parsed
+                .agents
+                .entries
+                .iter()
+                .filter_map(|(id, e)| {
+                    let seed = match e.seed_memory.as_ref()? {
+                        RawSeedMemory::Text(text) => text.trim().to_string(),
+                        RawSeedMemory::Facts(facts) => facts
+                            .iter()
+                            .map(|(key, value)| format!("- {key}: {value}"))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    };
+                    (!seed.is_empty()).then(|| (id.clone(), seed))
+                })
+                .collect(),
+            agent_max_sessions: parsed
+                .agents
+                .entries
+                .iter()
+                .filter_map(|(id, e)| e.max_sessions.filter(|&n| n > 0).map(|n| (id.clone(), n)))
+                .collect(),
+            agent_response_formats,
+            error_message: parsed
+                .agents
+                .error_message
+                .clone()
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_AGENT_ERROR_MESSAGE.to_string()),
+            tool_output: parsed
+                .agents
+                .tool_output
+                .as_ref()
+                .map(|t| ToolOutputLimits {
+                    max_chars: t.max_chars.unwrap_or(DEFAULT_TOOL_OUTPUT_MAX_CHARS),
+                    max_items: t.max_items.unwrap_or(DEFAULT_TOOL_OUTPUT_MAX_ITEMS),
+                })
+                .unwrap_or_default(),
+            moderation: parsed.agents.moderation.as_ref().and_then(|m| {
+                let blocked_terms: Vec<String> = m
+                    .blocklist
+                    .iter()
+                    .filter(|t| !t.trim().is_empty())
+                    .cloned()
+                    .collect();
+                if blocked_terms.is_empty() && m.patterns.is_empty() {
+                    return None;
+                }
+                Some(ModerationConfig {
+                    blocked_terms,
+                    blocked_patterns: m.patterns.clone(),
+                    refusal: m
+                        .refusal
+                        .clone()
+                        .filter(|r| !r.trim().is_empty())
+                        .unwrap_or_else(|| DEFAULT_MODERATION_REFUSAL.to_string()),
+                })
+            }),
             news_query,
             gdelt_query,
             newsroom_query,
             agent_docs,
             agentic_chat: agentic_chat_cfg,
+            echo: echo_cfg,
+            summarize: summarize_cfg,
             runtime_cmd: runtime_cmd_cfg,
             webbuilder: webbuilder_cfg,
             homebuilder: homebuilder_cfg,
             debug_logging: parsed.agents.debug_logging,
+            emit_steps: parsed.agents.emit_steps,
+            max_depth: parsed
+                .agents
+                .max_depth
+                .unwrap_or(DEFAULT_AGENT_MAX_DEPTH)
+                .max(1),
+            link_policy,
             uniweb_session_id: parsed
                 .agents
                 .entries
@@ -447,6 +1081,45 @@ pub fn load_from(
             providers,
             instruction: instruction_llm,
             routes,
+            auto_route,
+            audit_log,
+            audit_full: parsed.llm.audit_full,
+            audit_max_bytes: parsed.llm.audit_max_bytes.filter(|&n| n > 0),
+            max_prompt_chars: parsed.llm.max_prompt_chars.filter(|&n| n > 0),
+            batch_concurrency: parsed
+                .llm
+                .batch_concurrency
+                .unwrap_or(DEFAULT_LLM_BATCH_CONCURRENCY)
+                .max(1),
+            overall_deadline_seconds: parsed.llm.overall_deadline_seconds.filter(|&n| n > 0),
+            warmup: parsed.llm.warmup,
+            allowed_models: parsed
+                .llm
+                .allowed_models
+                .iter()
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty())
+                .collect(),
+            embedding,
+            strip_patterns,
+            faulty: FaultyConfig {
+                fail_percent: parsed.llm.faulty.fail_percent,
+                latency_ms: parsed.llm.faulty.latency_ms,
+                statuses: parsed.llm.faulty.statuses,
+            },
+            pool: {
+                let default = HttpPoolConfig::default();
+                HttpPoolConfig {
+                    idle_timeout_secs: parsed
+                        .llm
+                        .pool_idle_timeout
+                        .unwrap_or(default.idle_timeout_secs),
+                    max_idle_per_host: parsed
+                        .llm
+                        .pool_max_idle_per_host
+                        .unwrap_or(default.max_idle_per_host),
+                }
+            },
         },
         openai_api_key: env::var("OPENAI_API_KEY").ok(),
         ui: UiConfig {
@@ -462,16 +1135,97 @@ pub fn load_from(
                 tsec_last: parsed.tools.newsmail_aggregator.tsec_last,
                 q: parsed.tools.newsmail_aggregator.q,
             },
+            memory: MemoryToolConfig {
+                max_results: parsed.tools.memory.max_results.max(1),
+                all_sessions: parsed.tools.memory.all_sessions,
+            },
         },
         runtimes: RuntimesConfig {
             enabled: parsed.runtimes.enabled,
             default_timeout_secs: parsed.runtimes.default_timeout_secs,
         },
+        health: HealthConfig {
+            refresh_timeout_ms: parsed.health.refresh_timeout_ms.max(1),
+            startup_grace_seconds: parsed.health.startup_grace_seconds,
+        },
+        cron: CronConfig {
+            quiet_hours,
+            retry: CronRetry {
+                attempts: parsed.cron.retry_attempts,
+                backoff_ms: parsed
+                    .cron
+                    .retry_backoff_ms
+                    .unwrap_or(CronRetry::default().backoff_ms),
+            },
+        },
         memory_kv_cap: parsed.memory.basic_session.kv_cap,
         memory_transcript_cap: parsed.memory.basic_session.transcript_cap,
+        memory_max_sessions: parsed.memory.max_sessions.filter(|&n| n > 0),
+        memory_session_overflow,
+        memory_transcript_format,
+        memory_session_idle_archive_minutes: parsed
+            .memory
+            .session_idle_archive_minutes
+            .filter(|&n| n > 0),
+        memory_spend_snapshot_minutes: parsed.memory.spend_snapshot_minutes.filter(|&n| n > 0),
+        memory_compress_archived_transcripts: parsed.memory.compress_archived_transcripts,
+        memory_write_fallback: parsed.memory.write_fallback,
+    })
+}
+
+/// Resolve `[cron] quiet_hours`.  Errors are phrased to follow the key name.
+fn parse_quiet_hours(raw: &raw::RawQuietHours) -> Result<QuietHours, String> {
+    let start_minute = parse_clock(&raw.start)
+        .ok_or_else(|| format!("start must be \"HH:MM\", got {:?}", raw.start))?;
+    let end_minute =
+        parse_clock(&raw.end).ok_or_else(|| format!("end must be \"HH:MM\", got {:?}", raw.end))?;
+    let tz = match raw.tz.as_deref() {
+        None => QuietHoursTz::Offset(0),
+        Some(tz) => match parse_utc_offset(tz) {
+            Some(minutes) => QuietHoursTz::Offset(minutes),
+            None => QuietHoursTz::Zone(tz.trim().parse().map_err(|_| {
+                format!(
+                    "tz must be \"UTC\", \"+HH:MM\" or a zone name such as \"Europe/London\", got {tz:?}"
+                )
+            })?),
+        },
+    };
+    let policy = match raw.mode.as_deref() {
+        None | Some("defer") => QuietHoursPolicy::Defer,
+        Some("drop") => QuietHoursPolicy::Drop,
+        Some(other) => return Err(format!("mode must be \"defer\" or \"drop\", got {other:?}")),
+    };
+    Ok(QuietHours {
+        start_minute,
+        end_minute,
+        tz,
+        policy,
     })
 }
 
+/// `"HH:MM"` → minutes after midnight.
+fn parse_clock(s: &str) -> Option<u16> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (u16, u16) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+/// `"UTC"` / `"Z"` / `"±HH:MM"` → minutes east of UTC.
+fn parse_utc_offset(s: &str) -> Option<i16> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("utc") || s == "Z" {
+        return Some(0);
+    }
+    let (sign, rest) = match s.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (h, m) = rest.split_once(':')?;
+    let (h, m): (i16, i16) = (h.parse().ok()?, m.parse().ok()?);
+    (h <= 14 && m < 60).then_some(sign * (h * 60 + m))
+}
+
 /// Expand a leading `~` to the user's home directory.
 /// Absolute or relative paths without `~` are returned unchanged.
 pub fn expand_home(path: &str) -> PathBuf {
@@ -488,6 +1242,35 @@ pub fn expand_home(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Resolve `[comms.email]`, filling defaults and the password reference.
+fn email_config(raw: raw::RawEmail) -> EmailConfig {
+    let defaults = EmailConfig::default();
+    let username = raw.username.unwrap_or_default();
+    let imap_host = raw.imap_host.unwrap_or_default();
+    EmailConfig {
+        enabled: raw.enabled,
+        default_agent: raw.default_agent,
+        smtp_host: raw.smtp_host.unwrap_or_else(|| imap_host.clone()),
+        imap_host,
+        imap_port: raw.imap_port.unwrap_or(defaults.imap_port),
+        smtp_port: raw.smtp_port.unwrap_or(defaults.smtp_port),
+        password: resolve_api_key(raw.password, raw.password_file),
+        from_address: raw.from_address.unwrap_or_else(|| username.clone()),
+        username,
+        mailbox: raw.mailbox.unwrap_or(defaults.mailbox),
+        poll_interval_secs: raw
+            .poll_interval_secs
+            .unwrap_or(defaults.poll_interval_secs)
+            .max(1),
+        allowed_senders: raw
+            .allowed_senders
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    }
+}
+
 /// Resolves an API key from either a direct string, a `secret:<name>` prefix,
 /// or a dedicated `api_key_file` path.
 /// Secrets are looked up in `~/.local/share/araliya/secrets/` (or platform equivalent).
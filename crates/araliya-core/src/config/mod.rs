@@ -28,18 +28,64 @@ impl Config {
             bot_name: "test".into(),
             work_dir: work_dir.to_path_buf(),
             identity_dir: None,
+            bot_namespace: None,
             log_level: "info".into(),
+            maintenance: false,
+            maintenance_message: DEFAULT_MAINTENANCE_MESSAGE.into(),
+            startup_self_test: false,
+            startup_self_test_fatal: false,
+            panic_hook: false,
+            coalesce_requests: false,
+            ready_line: false,
+            sd_notify: false,
+            otel_endpoint: None,
+            log_buffer_capacity: crate::logger::DEFAULT_LOG_BUFFER_CAPACITY,
+            socket_bind_attempts: DEFAULT_SOCKET_BIND_ATTEMPTS,
+            socket_bind_backoff_ms: DEFAULT_SOCKET_BIND_BACKOFF_MS,
+            presence_events: true,
+            control_handshake: false,
             comms: CommsConfig {
-                pty: PtyConfig { enabled: true },
-                telegram: TelegramConfig { enabled: false },
+                pty: PtyConfig {
+                    enabled: true,
+                    default_agent: None,
+                    tool_retry: false,
+                },
+                telegram: TelegramConfig {
+                    enabled: false,
+                    default_agent: None,
+                    respond_mode: RespondMode::Always,
+                },
+                email: EmailConfig::default(),
                 http: HttpConfig {
                     enabled: false,
                     bind: raw::default_http_bind(),
+                    default_agent: None,
+                    slow_request_ms: None,
+                    read_timeout_ms: None,
+                    max_header_bytes: DEFAULT_HTTP_MAX_HEADER_BYTES,
+                    access_log: None,
+                    persist_sessions: true,
+                    max_connections: None,
+                    connection_overflow: ConnectionOverflow::Reject,
+                    cache_ttl_ms: std::collections::HashMap::new(),
                 },
                 axum_channel: AxumChannelConfig {
                     enabled: false,
                     bind: raw::default_http_bind(),
+                    default_agent: None,
                 },
+                max_input_chars: None,
+                input_overflow: InputOverflow::Truncate,
+                strip_control_chars: false,
+                channels: None,
+                auto_session: false,
+                inline_routing: false,
+                agent_switching: false,
+                command_prefix: DEFAULT_COMMAND_PREFIX.to_string(),
+                event_buffer: DEFAULT_COMMS_EVENT_BUFFER,
+                idle_timeout_seconds: None,
+                shutdown_message: None,
+                expose_config: false,
             },
             agents: AgentsConfig {
                 default_agent: "echo".into(),
@@ -51,12 +97,36 @@ impl Config {
                 gdelt_query: None,
                 newsroom_query: None,
                 agent_aggregation_targets: std::collections::HashMap::new(),
+                strict_default: false,
+                noop_fallback: false,
+                agent_cooldowns: std::collections::HashMap::new(),
+                agent_context_turns: std::collections::HashMap::new(),
+                agent_seed_memory: std::collections::HashMap::new(),
+                agent_max_sessions: std::collections::HashMap::new(),
+                spend_alert_usd: None,
+                daily_output_token_budget: None,
+                empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+                allow_empty_input: std::collections::HashSet::new(),
+                empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+                auto_title: false,
+                debug_prompt: false,
+                agent_empty_replies: std::collections::HashMap::new(),
+                persist_context: std::collections::HashSet::new(),
+                agent_response_formats: std::collections::HashMap::new(),
+                moderation: None,
+                error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+                tool_output: ToolOutputLimits::default(),
                 agent_docs: std::collections::HashMap::new(),
                 agentic_chat: None,
+                echo: None,
+                summarize: None,
                 runtime_cmd: None,
                 webbuilder: None,
                 homebuilder: None,
                 debug_logging: false,
+                emit_steps: false,
+                max_depth: DEFAULT_AGENT_MAX_DEPTH,
+                link_policy: LinkPolicy::Allow,
                 uniweb_session_id: None,
                 uniweb_use_instruction_llm: false,
             },
@@ -65,6 +135,19 @@ impl Config {
                 providers: std::collections::HashMap::new(),
                 instruction: None,
                 routes: std::collections::HashMap::new(),
+                auto_route: Vec::new(),
+                audit_log: None,
+                audit_full: false,
+                audit_max_bytes: None,
+                max_prompt_chars: None,
+                batch_concurrency: DEFAULT_LLM_BATCH_CONCURRENCY,
+                overall_deadline_seconds: None,
+                warmup: false,
+                allowed_models: Vec::new(),
+                embedding: None,
+                strip_patterns: Vec::new(),
+                faulty: FaultyConfig::default(),
+                pool: HttpPoolConfig::default(),
             },
             openai_api_key: None,
             ui: UiConfig {
@@ -80,13 +163,29 @@ impl Config {
                     tsec_last: None,
                     q: None,
                 },
+                memory: MemoryToolConfig {
+                    max_results: raw::default_memory_tool_max_results(),
+                    all_sessions: false,
+                },
             },
             runtimes: RuntimesConfig {
                 enabled: true,
                 default_timeout_secs: 30,
             },
+            health: HealthConfig {
+                refresh_timeout_ms: 5000,
+                startup_grace_seconds: 0,
+            },
+            cron: CronConfig::default(),
             memory_kv_cap: None,
             memory_transcript_cap: None,
+            memory_max_sessions: None,
+            memory_session_overflow: SessionOverflow::Evict,
+            memory_transcript_format: TranscriptFormat::Md,
+            memory_session_idle_archive_minutes: None,
+            memory_spend_snapshot_minutes: None,
+            memory_compress_archived_transcripts: false,
+            memory_write_fallback: false,
         }
     }
 }
@@ -94,6 +193,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::AppError;
     use std::io::Write;
     use tempfile::{NamedTempFile, TempDir};
 
@@ -168,6 +268,392 @@ index = "index.md"
         let docs = cfg.agents.agent_docs.get("docs").unwrap();
         assert_eq!(docs.docsdir.as_deref(), Some("docs/guide/"));
         assert_eq!(docs.index.as_deref(), Some("index.md"));
+        assert_eq!(
+            docs.embedding_cache_entries,
+            DEFAULT_EMBEDDING_CACHE_ENTRIES
+        );
+
+        let toml = format!("{toml}embedding_cache_entries = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.agent_docs["docs"].embedding_cache_entries, 0);
+    }
+
+    #[test]
+    fn parse_spend_alert_usd() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.spend_alert_usd, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nspend_alert_usd = 2.5\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.spend_alert_usd, Some(2.5));
+    }
+
+    #[test]
+    fn parse_provider_prompt_caching() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm.providers.claude]\nmodel = \"m\"\nprompt_caching = true\n\n[llm.providers.plain]\nmodel = \"m\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.llm.providers["claude"].prompt_caching);
+        assert!(!cfg.llm.providers["plain"].prompt_caching);
+    }
+
+    #[test]
+    fn parse_provider_context_window() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm.providers.big]\nmodel = \"local-model\"\ncontext_window = 32768\n\n[llm.providers.known]\nmodel = \"gpt-4o-mini\"\n\n[llm.providers.unknown]\nmodel = \"local-model\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.providers["big"].context_window, 32_768);
+        assert_eq!(cfg.llm.providers["known"].context_window, 128_000);
+        assert_eq!(
+            cfg.llm.providers["unknown"].context_window,
+            FALLBACK_CONTEXT_WINDOW
+        );
+    }
+
+    #[test]
+    fn default_context_window_matches_model_prefixes() {
+        assert_eq!(default_context_window("gpt-4o-2024-08-06"), 128_000);
+        assert_eq!(default_context_window("gpt-4"), 8_192);
+        assert_eq!(default_context_window("gpt-4.1-mini"), 1_047_576);
+        assert_eq!(default_context_window("anthropic/claude-sonnet-4"), 200_000);
+        assert_eq!(
+            default_context_window("qwen2.5:7b"),
+            FALLBACK_CONTEXT_WINDOW
+        );
+    }
+
+    #[test]
+    fn parse_daily_output_token_budget() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.daily_output_token_budget, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\ndaily_output_token_budget = 20000\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.daily_output_token_budget, Some(20000));
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\ndaily_output_token_budget = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.daily_output_token_budget, None);
+    }
+
+    #[test]
+    fn parse_agents_moderation() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .agents
+                .moderation
+                .is_none()
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.moderation]\nblocklist = [\"badword\", \" \"]\npatterns = [\"(?i)secret\\\\s+plan\"]\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let moderation = cfg.agents.moderation.unwrap();
+        assert_eq!(moderation.blocked_terms, ["badword"]);
+        assert_eq!(moderation.blocked_patterns, ["(?i)secret\\s+plan"]);
+        assert_eq!(moderation.refusal, DEFAULT_MODERATION_REFUSAL);
+        assert!(!cfg.agents.enabled.contains("moderation"));
+
+        let toml = format!("{MINIMAL_TOML}\n[agents.moderation]\nrefusal = \"No.\"\n");
+        let f = write_toml(&toml);
+        assert!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .agents
+                .moderation
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn parse_agents_tool_output() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.tool_output, ToolOutputLimits::default());
+
+        let toml = format!("{MINIMAL_TOML}\n[agents.tool_output]\nmax_items = 10\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.tool_output.max_items, 10);
+        assert_eq!(
+            cfg.agents.tool_output.max_chars,
+            DEFAULT_TOOL_OUTPUT_MAX_CHARS
+        );
+        assert!(!cfg.agents.enabled.contains("tool_output"));
+    }
+
+    #[test]
+    fn parse_agents_error_message() {
+        let f = write_toml(MINIMAL_TOML);
+        assert_eq!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .agents
+                .error_message,
+            DEFAULT_AGENT_ERROR_MESSAGE
+        );
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nerror_message = \"Oops: {{error}}\"\n");
+        let f = write_toml(&toml);
+        assert_eq!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .agents
+                .error_message,
+            "Oops: {error}"
+        );
+    }
+
+    #[test]
+    fn parse_empty_input_guard() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.empty_input_reply, DEFAULT_EMPTY_INPUT_REPLY);
+        assert!(cfg.agents.allow_empty_input.is_empty());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents]\nempty_input_reply = \"Ask away.\"\n\n[agents.news]\nallow_empty_input = true\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.empty_input_reply, "Ask away.");
+        assert!(cfg.agents.allow_empty_input.contains("news"));
+    }
+
+    #[test]
+    fn parse_empty_reply() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.empty_reply, DEFAULT_EMPTY_REPLY);
+        assert!(cfg.agents.agent_empty_replies.is_empty());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents]\nempty_reply = \"No answer.\"\n\n[agents.chat]\nempty_reply = \"Try again?\"\n\n[agents.docs]\nempty_reply = \" \"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.empty_reply, "No answer.");
+        assert_eq!(cfg.agents.agent_empty_replies["chat"], "Try again?");
+        // A blank override falls back to the global reply.
+        assert!(!cfg.agents.agent_empty_replies.contains_key("docs"));
+    }
+
+    #[test]
+    fn parse_agents_persist_context() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.agents.persist_context.is_empty());
+
+        let toml = format!("{MINIMAL_TOML}\n[agents.docs]\npersist_context = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.agents.persist_context.contains("docs"));
+    }
+
+    #[test]
+    fn parse_agent_cooldowns() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.docs]\ncooldown_ms = 5000\n\n[agents.echo]\ncooldown_ms = 0\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.agent_cooldowns.get("docs"), Some(&5000));
+        assert!(!cfg.agents.agent_cooldowns.contains_key("echo"));
+    }
+
+    #[test]
+    fn parse_agent_seed_memory() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.chat]\nseed_memory = \"  The user's name is Ada.\\n\"\n\n\
+             [agents.docs.seed_memory]\ntimezone = \"Europe/London\"\nname = \"Ada\"\n\n\
+             [agents.echo]\nseed_memory = \" \"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let seeds = &cfg.agents.agent_seed_memory;
+        assert_eq!(seeds["chat"], "The user's name is Ada.");
+        assert_eq!(seeds["docs"], "- name: Ada\n- timezone: Europe/London");
+        assert!(!seeds.contains_key("echo"));
+    }
+
+    #[test]
+    fn parse_agent_max_sessions() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.news]\nmax_sessions = 30\n\n[agents.chat]\nmax_sessions = 0\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.agent_max_sessions.get("news"), Some(&30));
+        assert!(!cfg.agents.agent_max_sessions.contains_key("chat"));
+    }
+
+    #[test]
+    fn parse_agent_context_turns() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.chat]\ncontext_turns = 2\n\n[agents.docs]\ncontext_turns = 0\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.agent_context_turns.get("chat"), Some(&2));
+        assert!(!cfg.agents.agent_context_turns.contains_key("docs"));
+    }
+
+    #[test]
+    fn parse_agents_link_policy() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.link_policy, LinkPolicy::Allow);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nlink_policy = \"strip\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.link_policy, LinkPolicy::Strip);
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents]\nlink_policy = \"rewrite\"\nlink_rewrite = \"<{{host}}>\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.agents.link_policy,
+            LinkPolicy::Rewrite {
+                template: "<{host}>".to_string()
+            }
+        );
+
+        for bad in ["link_policy = \"rewrite\"", "link_policy = \"block\""] {
+            let toml = format!("{MINIMAL_TOML}\n[agents]\n{bad}\n");
+            let f = write_toml(&toml);
+            let err = load_from(f.path(), None, None).unwrap_err().to_string();
+            assert!(err.contains("link_policy"), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_agents_max_depth() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.agents.max_depth, DEFAULT_AGENT_MAX_DEPTH);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nmax_depth = 3\n");
+        let f = write_toml(&toml);
+        assert_eq!(load_from(f.path(), None, None).unwrap().agents.max_depth, 3);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nmax_depth = 0\n");
+        let f = write_toml(&toml);
+        assert_eq!(load_from(f.path(), None, None).unwrap().agents.max_depth, 1);
+    }
+
+    #[test]
+    fn parse_agents_auto_title() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(!load_from(f.path(), None, None).unwrap().agents.auto_title);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nauto_title = true\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).unwrap().agents.auto_title);
+    }
+
+    #[test]
+    fn parse_agents_debug_prompt() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(!load_from(f.path(), None, None).unwrap().agents.debug_prompt);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\ndebug_prompt = true\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).unwrap().agents.debug_prompt);
+    }
+
+    #[test]
+    fn parse_agents_emit_steps() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(!load_from(f.path(), None, None).unwrap().agents.emit_steps);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents]\nemit_steps = true\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).unwrap().agents.emit_steps);
+    }
+
+    #[test]
+    fn parse_agent_response_format() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.chat]\nresponse_format = \"json\"\n\n[agents.echo]\nresponse_format = \"text\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.agents.agent_response_formats.get("chat"),
+            Some(&crate::types::llm::ResponseFormat::Json)
+        );
+        assert!(!cfg.agents.agent_response_formats.contains_key("echo"));
+
+        let f = write_toml(&format!(
+            "{MINIMAL_TOML}\n[agents.chat]\nresponse_format = \"yaml\"\n"
+        ));
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn parse_echo_agent_config() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.agents.echo.is_none());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[agents.echo]\nprefix = \"> \"\nsuffix = \"!\"\ncase = \"upper\"\ndelay_ms = 50\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let echo = cfg.agents.echo.unwrap();
+        assert_eq!(echo.prefix, "> ");
+        assert_eq!(echo.suffix, "!");
+        assert_eq!(echo.case, EchoCase::Upper);
+        assert_eq!(echo.delay_ms, 50);
+    }
+
+    #[test]
+    fn invalid_echo_case_errors() {
+        let toml = format!("{MINIMAL_TOML}\n[agents.echo]\ncase = \"title\"\n");
+        let f = write_toml(&toml);
+        let msg = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(msg.contains("agents.echo.case"));
+    }
+
+    #[test]
+    fn parse_summarize_agent_config() {
+        let toml = format!("{MINIMAL_TOML}\n[agents.summarize]\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let summarize = cfg.agents.summarize.unwrap();
+        assert_eq!(summarize.style, SummaryStyle::Bullets);
+        assert_eq!(summarize.max_words, DEFAULT_SUMMARY_MAX_WORDS);
+
+        let toml =
+            format!("{MINIMAL_TOML}\n[agents.summarize]\nstyle = \"prose\"\nmax_words = 60\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let summarize = cfg.agents.summarize.unwrap();
+        assert_eq!(summarize.style, SummaryStyle::Prose);
+        assert_eq!(summarize.max_words, 60);
+
+        let toml = format!("{MINIMAL_TOML}\n[agents.summarize]\nstyle = \"haiku\"\n");
+        let f = write_toml(&toml);
+        let msg = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(msg.contains("agents.summarize.style"));
     }
 
     #[test]
@@ -204,109 +690,1109 @@ index = "index.md"
         assert_eq!(cfg.log_level, "debug");
     }
 
-    const BASE_TOML: &str = r#"
-[supervisor]
-bot_name = "base-bot"
-work_dir = "~/.araliya"
-log_level = "info"
+    #[test]
+    fn parse_comms_input_limits() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms]\nmax_input_chars = 4000\ninput_overflow = \"reject\"\nstrip_control_chars = true\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.max_input_chars, Some(4000));
+        assert_eq!(cfg.comms.input_overflow, InputOverflow::Reject);
+        assert!(cfg.comms.strip_control_chars);
+    }
 
-[agents]
-default = "echo"
+    #[test]
+    fn comms_input_limits_default_to_unlimited() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.max_input_chars, None);
+        assert_eq!(cfg.comms.input_overflow, InputOverflow::Truncate);
+        assert!(!cfg.comms.strip_control_chars);
+    }
 
-[llm]
-default = "dummy"
+    #[test]
+    fn parse_supervisor_maintenance() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.maintenance);
+        assert_eq!(cfg.maintenance_message, DEFAULT_MAINTENANCE_MESSAGE);
 
-[llm.providers.openai]
-api_type = "chat_completions"
-api_base_url = "https://api.openai.com/v1/chat/completions"
-model = "gpt-base"
-temperature = 0.1
-timeout_seconds = 30
-"#;
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nmaintenance = true\nmaintenance_message = \"Back at 5pm.\"\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.maintenance);
+        assert_eq!(cfg.maintenance_message, "Back at 5pm.");
+    }
 
-    fn write_named(dir: &TempDir, name: &str, content: &str) -> std::path::PathBuf {
-        let p = dir.path().join(name);
-        std::fs::write(&p, content).unwrap();
-        p
+    #[test]
+    fn parse_supervisor_startup_self_test() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.startup_self_test);
+        assert!(!cfg.startup_self_test_fatal);
+
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nstartup_self_test = true\nstartup_self_test_fatal = true\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.startup_self_test);
+        assert!(cfg.startup_self_test_fatal);
     }
 
     #[test]
-    fn overlay_keeps_base_fields() {
-        let dir = TempDir::new().unwrap();
-        write_named(&dir, "base.toml", BASE_TOML);
-        let overlay = r#"
-[meta]
-base = "base.toml"
+    fn parse_supervisor_panic_hook() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(!load_from(f.path(), None, None).unwrap().panic_hook);
 
-[supervisor]
-log_level = "debug"
-"#;
-        let overlay_path = write_named(&dir, "overlay.toml", overlay);
-        let cfg = load_from(&overlay_path, None, None).unwrap();
-        assert_eq!(cfg.bot_name, "base-bot");
-        assert_eq!(cfg.log_level, "debug");
+        let toml = MINIMAL_TOML.replace("[supervisor]\n", "[supervisor]\npanic_hook = true\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).unwrap().panic_hook);
     }
 
     #[test]
-    fn overlay_wins_scalar() {
-        let dir = TempDir::new().unwrap();
-        write_named(&dir, "base.toml", BASE_TOML);
-        let overlay = r#"
-[meta]
-base = "base.toml"
+    fn parse_supervisor_coalesce_requests() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(!load_from(f.path(), None, None).unwrap().coalesce_requests);
 
-[llm.providers.openai]
-model = "gpt-overlay"
-"#;
-        let overlay_path = write_named(&dir, "overlay.toml", overlay);
-        let cfg = load_from(&overlay_path, None, None).unwrap();
-        let openai = cfg.llm.providers.get("openai").unwrap();
-        assert_eq!(openai.model, "gpt-overlay");
-        assert_eq!(openai.temperature, 0.1);
+        let toml =
+            MINIMAL_TOML.replace("[supervisor]\n", "[supervisor]\ncoalesce_requests = true\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).unwrap().coalesce_requests);
     }
 
     #[test]
-    fn chained_bases() {
-        let dir = TempDir::new().unwrap();
-        write_named(&dir, "grandbase.toml", BASE_TOML);
-        let middle = r#"
-[meta]
-base = "grandbase.toml"
+    fn parse_supervisor_readiness_signals() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.ready_line);
+        assert!(!cfg.sd_notify);
 
-[supervisor]
-bot_name = "middle-bot"
-"#;
-        write_named(&dir, "middle.toml", middle);
-        let top = r#"
-[meta]
-base = "middle.toml"
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nready_line = true\nsd_notify = true\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.ready_line);
+        assert!(cfg.sd_notify);
+    }
 
-[supervisor]
-log_level = "warn"
-"#;
-        let top_path = write_named(&dir, "top.toml", top);
-        let cfg = load_from(&top_path, None, None).unwrap();
-        assert_eq!(cfg.bot_name, "middle-bot");
-        assert_eq!(cfg.log_level, "warn");
+    #[test]
+    fn parse_supervisor_log_buffer_capacity() {
+        let f = write_toml(MINIMAL_TOML);
+        assert_eq!(
+            load_from(f.path(), None, None).unwrap().log_buffer_capacity,
+            crate::logger::DEFAULT_LOG_BUFFER_CAPACITY
+        );
+
+        let toml =
+            MINIMAL_TOML.replace("[supervisor]\n", "[supervisor]\nlog_buffer_capacity = 50\n");
+        let f = write_toml(&toml);
+        assert_eq!(
+            load_from(f.path(), None, None).unwrap().log_buffer_capacity,
+            50
+        );
     }
 
     #[test]
-    fn missing_base_errors() {
-        let dir = TempDir::new().unwrap();
-        let overlay = r#"
-[meta]
-base = "nonexistent.toml"
+    fn parse_supervisor_socket_bind_retry() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.socket_bind_attempts, DEFAULT_SOCKET_BIND_ATTEMPTS);
+        assert_eq!(cfg.socket_bind_backoff_ms, DEFAULT_SOCKET_BIND_BACKOFF_MS);
 
-[supervisor]
-bot_name = "x"
-work_dir = "~/.araliya"
-log_level = "info"
-"#;
-        let overlay_path = write_named(&dir, "overlay.toml", overlay);
-        let result = load_from(&overlay_path, None, None);
-        assert!(result.is_err());
-        let msg = result.unwrap_err().to_string();
-        assert!(msg.contains("cannot read") || msg.contains("config error"));
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nsocket_bind_attempts = 0\nsocket_bind_backoff_ms = 250\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.socket_bind_attempts, 1);
+        assert_eq!(cfg.socket_bind_backoff_ms, 250);
+    }
+
+    #[test]
+    fn parse_supervisor_control_handshake() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.control_handshake);
+
+        let toml =
+            MINIMAL_TOML.replace("[supervisor]\n", "[supervisor]\ncontrol_handshake = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.control_handshake);
+    }
+
+    #[test]
+    fn parse_supervisor_presence_events() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.presence_events);
+
+        let toml =
+            MINIMAL_TOML.replace("[supervisor]\n", "[supervisor]\npresence_events = false\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.presence_events);
+    }
+
+    #[test]
+    fn parse_supervisor_otel_endpoint() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.otel_endpoint.is_none());
+
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\notel_endpoint = \"http://localhost:4317\"\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.otel_endpoint.as_deref(), Some("http://localhost:4317"));
+    }
+
+    #[test]
+    fn bot_name_with_path_chars_is_kept_for_display() {
+        let toml = MINIMAL_TOML.replace("\"test-bot\"", "\"Ops / Night Shift 🌙\"");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.bot_name, "Ops / Night Shift 🌙");
+        assert_eq!(
+            crate::identity::slugify(&cfg.bot_name, "bot"),
+            "Ops-Night-Shift"
+        );
+    }
+
+    #[test]
+    fn parse_tools_memory_max_results() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.tools.memory.max_results, 20);
+        assert!(!cfg.tools.memory.all_sessions);
+
+        let toml =
+            format!("{MINIMAL_TOML}\n[tools.memory]\nmax_results = 5\nall_sessions = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.tools.memory.max_results, 5);
+        assert!(cfg.tools.memory.all_sessions);
+    }
+
+    #[test]
+    fn parse_bot_namespace() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.bot_namespace, None);
+
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nbot_namespace = \"persona-b\"\n",
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.bot_namespace.as_deref(), Some("persona-b"));
+
+        let toml = MINIMAL_TOML.replace(
+            "[supervisor]\n",
+            "[supervisor]\nbot_namespace = \"../other\"\n",
+        );
+        let f = write_toml(&toml);
+        let err = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(err.contains("bot_namespace"), "{err}");
+    }
+
+    #[test]
+    fn parse_http_slow_request_ms() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.slow_request_ms, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nslow_request_ms = 250\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.slow_request_ms, Some(250));
+    }
+
+    #[test]
+    fn parse_http_access_log() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.access_log, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\naccess_log = \"logs/access.log\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.comms.http.access_log,
+            Some(cfg.work_dir.join("logs/access.log"))
+        );
+    }
+
+    #[test]
+    fn parse_http_persist_sessions() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.http.persist_sessions);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\npersist_sessions = false\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.http.persist_sessions);
+    }
+
+    #[test]
+    fn parse_http_cache_ttl_ms() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.http.cache_ttl_ms.is_empty());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms.http.cache_ttl_ms]\n\"/api/tree\" = 2000\n\"/api/health\" = 0\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.comms.http.cache_ttl_ms,
+            std::collections::HashMap::from([("/api/tree".to_string(), 2000)])
+        );
+    }
+
+    #[test]
+    fn parse_http_max_connections() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.max_connections, None);
+        assert_eq!(
+            cfg.comms.http.connection_overflow,
+            ConnectionOverflow::Reject
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms.http]\nmax_connections = 64\nconnection_overflow = \"wait\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.max_connections, Some(64));
+        assert_eq!(cfg.comms.http.connection_overflow, ConnectionOverflow::Wait);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nmax_connections = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.max_connections, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nconnection_overflow = \"drop\"\n");
+        let f = write_toml(&toml);
+        let err = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(err.contains("connection_overflow"), "{err}");
+    }
+
+    #[test]
+    fn parse_http_read_timeout_ms() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.read_timeout_ms, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nread_timeout_ms = 5000\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.read_timeout_ms, Some(5000));
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nread_timeout_ms = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.read_timeout_ms, None);
+    }
+
+    #[test]
+    fn parse_http_max_header_bytes() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.comms.http.max_header_bytes,
+            DEFAULT_HTTP_MAX_HEADER_BYTES
+        );
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.http]\nmax_header_bytes = 16384\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.http.max_header_bytes, 16384);
+    }
+
+    #[test]
+    fn parse_comms_auto_session() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.auto_session);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nauto_session = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.auto_session);
+    }
+
+    #[test]
+    fn parse_comms_inline_routing() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.inline_routing);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\ninline_routing = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.inline_routing);
+    }
+
+    #[test]
+    fn parse_comms_agent_switching() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.agent_switching);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nagent_switching = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.agent_switching);
+    }
+
+    #[test]
+    fn parse_comms_pty_tool_retry() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.pty.tool_retry);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.pty]\ntool_retry = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.pty.tool_retry);
+        assert!(cfg.comms.pty.enabled);
+    }
+
+    #[test]
+    fn parse_comms_command_prefix() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.command_prefix, "/");
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\ncommand_prefix = \"!\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.command_prefix, "!");
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\ncommand_prefix = \"\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.command_prefix, "");
+    }
+
+    #[test]
+    fn parse_comms_event_buffer() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.event_buffer, DEFAULT_COMMS_EVENT_BUFFER);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nevent_buffer = 1024\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.event_buffer, 1024);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nevent_buffer = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.event_buffer, DEFAULT_COMMS_EVENT_BUFFER);
+    }
+
+    #[test]
+    fn parse_comms_shutdown_message() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.shutdown_message, None);
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms]\nshutdown_message = \" Going offline for an upgrade. \"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.comms.shutdown_message.as_deref(),
+            Some("Going offline for an upgrade.")
+        );
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nshutdown_message = \"  \"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.shutdown_message, None);
+    }
+
+    #[test]
+    fn parse_comms_expose_config() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms.expose_config);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nexpose_config = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.expose_config);
+    }
+
+    #[test]
+    fn parse_comms_idle_timeout_seconds() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.idle_timeout_seconds, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nidle_timeout_seconds = 300\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.idle_timeout_seconds, Some(300));
+
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nidle_timeout_seconds = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.idle_timeout_seconds, None);
+    }
+
+    #[test]
+    fn parse_memory_max_sessions() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_max_sessions, None);
+        assert_eq!(cfg.memory_session_overflow, SessionOverflow::Evict);
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[memory]\nmax_sessions = 100\nsession_overflow = \"reject\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_max_sessions, Some(100));
+        assert_eq!(cfg.memory_session_overflow, SessionOverflow::Reject);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nsession_overflow = \"drop\"\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn parse_llm_faulty() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.faulty, FaultyConfig::default());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm.faulty]\nfail_percent = 30\nlatency_ms = 250\nstatuses = [429, 500, 200]\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.llm.faulty,
+            FaultyConfig {
+                fail_percent: 30,
+                latency_ms: 250,
+                statuses: vec![429, 500, 200],
+            }
+        );
+
+        let toml = format!("{MINIMAL_TOML}\n[llm.faulty]\nfail_percent = 101\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn parse_llm_connection_pool() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.pool, HttpPoolConfig::default());
+
+        let toml =
+            format!("{MINIMAL_TOML}\n[llm]\npool_idle_timeout = 30\npool_max_idle_per_host = 4\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.llm.pool,
+            HttpPoolConfig {
+                idle_timeout_secs: 30,
+                max_idle_per_host: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_health_refresh_timeout() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.health.refresh_timeout_ms, 5000);
+
+        let toml = format!("{MINIMAL_TOML}\n[health]\nrefresh_timeout_ms = 1500\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.health.refresh_timeout_ms, 1500);
+    }
+
+    #[test]
+    fn parse_health_startup_grace_seconds() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.health.startup_grace_seconds, 0);
+
+        let toml = format!("{MINIMAL_TOML}\n[health]\nstartup_grace_seconds = 45\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.health.startup_grace_seconds, 45);
+    }
+
+    #[test]
+    fn parse_memory_transcript_format() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_transcript_format, TranscriptFormat::Md);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\ntranscript_format = \"jsonl\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_transcript_format, TranscriptFormat::Jsonl);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\ntranscript_format = \"yaml\"\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn parse_memory_session_idle_archive_minutes() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_session_idle_archive_minutes, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nsession_idle_archive_minutes = 90\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_session_idle_archive_minutes, Some(90));
+
+        // 0 disables archiving.
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nsession_idle_archive_minutes = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_session_idle_archive_minutes, None);
+    }
+
+    #[test]
+    fn parse_memory_spend_snapshot_minutes() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_spend_snapshot_minutes, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nspend_snapshot_minutes = 15\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_spend_snapshot_minutes, Some(15));
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nspend_snapshot_minutes = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.memory_spend_snapshot_minutes, None);
+    }
+
+    #[test]
+    fn parse_transcript_compression_and_audit_rotation() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.memory_compress_archived_transcripts);
+        assert_eq!(cfg.llm.audit_max_bytes, None);
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[memory]\ncompress_archived_transcripts = true\n\n[llm]\naudit_max_bytes = 1048576\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.memory_compress_archived_transcripts);
+        assert_eq!(cfg.llm.audit_max_bytes, Some(1_048_576));
+
+        // 0 disables rotation.
+        let toml = format!("{MINIMAL_TOML}\n[llm]\naudit_max_bytes = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.audit_max_bytes, None);
+    }
+
+    #[test]
+    fn parse_memory_write_fallback() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.memory_write_fallback);
+
+        let toml = format!("{MINIMAL_TOML}\n[memory]\nwrite_fallback = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.memory_write_fallback);
+    }
+
+    #[test]
+    fn parse_llm_max_prompt_chars() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.max_prompt_chars, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nmax_prompt_chars = 200000\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.max_prompt_chars, Some(200_000));
+
+        // 0 disables the limit.
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nmax_prompt_chars = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.max_prompt_chars, None);
+    }
+
+    #[test]
+    fn parse_llm_allowed_models() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.llm.allowed_models.is_empty());
+
+        // Names are trimmed; blank entries are dropped.
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm]\nallowed_models = [\"gpt-5-nano\", \" qwen2.5 \", \"\"]\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.allowed_models, ["gpt-5-nano", "qwen2.5"]);
+    }
+
+    #[test]
+    fn parse_llm_batch_concurrency() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.batch_concurrency, DEFAULT_LLM_BATCH_CONCURRENCY);
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nbatch_concurrency = 8\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.batch_concurrency, 8);
+
+        // 0 would stall every batch; it runs items one at a time instead.
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nbatch_concurrency = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.batch_concurrency, 1);
+    }
+
+    #[test]
+    fn parse_llm_warmup() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.llm.warmup);
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nwarmup = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.llm.warmup);
+    }
+
+    #[test]
+    fn parse_llm_overall_deadline_seconds() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.overall_deadline_seconds, None);
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\noverall_deadline_seconds = 90\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.overall_deadline_seconds, Some(90));
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\noverall_deadline_seconds = 0\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.llm.overall_deadline_seconds, None);
+    }
+
+    #[test]
+    fn parse_llm_auto_route() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .llm
+                .auto_route
+                .is_empty()
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[[llm.auto_route]]\nmax_tokens = 100\nprovider = \"fast\"\n\n[[llm.auto_route]]\nmin_tokens = 4000\nprovider = \"strong\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.llm.auto_route,
+            [
+                AutoRouteRule {
+                    min_tokens: None,
+                    max_tokens: Some(100),
+                    provider: "fast".into(),
+                },
+                AutoRouteRule {
+                    min_tokens: Some(4000),
+                    max_tokens: None,
+                    provider: "strong".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cron_quiet_hours() {
+        let f = write_toml(MINIMAL_TOML);
+        assert!(
+            load_from(f.path(), None, None)
+                .unwrap()
+                .cron
+                .quiet_hours
+                .is_none()
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[cron]\nquiet_hours = {{ start = \"22:00\", end = \"07:00\", tz = \"+02:00\", mode = \"drop\" }}\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.cron.quiet_hours,
+            Some(QuietHours {
+                start_minute: 22 * 60,
+                end_minute: 7 * 60,
+                tz: QuietHoursTz::Offset(120),
+                policy: QuietHoursPolicy::Drop,
+            })
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[cron]\nquiet_hours = {{ start = \"25:00\", end = \"07:00\" }}\n"
+        );
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[cron]\nquiet_hours = {{ start = \"22:00\", end = \"07:00\", tz = \"Europe/London\" }}\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.cron.quiet_hours.unwrap().tz,
+            QuietHoursTz::Zone(chrono_tz::Europe::London)
+        );
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[cron]\nquiet_hours = {{ start = \"22:00\", end = \"07:00\", tz = \"Mars/Olympus\" }}\n"
+        );
+        let f = write_toml(&toml);
+        let err = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(err.contains("zone name"), "{err}");
+    }
+
+    #[test]
+    fn parse_cron_retry() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.cron.retry, CronRetry::default());
+        assert_eq!(cfg.cron.retry.attempts, 0);
+
+        let toml = format!("{MINIMAL_TOML}\n[cron]\nretry_attempts = 3\nretry_backoff_ms = 250\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(
+            cfg.cron.retry,
+            CronRetry {
+                attempts: 3,
+                backoff_ms: 250,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_llm_embedding_provider() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.llm.embedding.is_none());
+
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm]\nembedding_provider = \"local\"\nembedding_base_url = \"http://127.0.0.1:8001/embed\"\nembedding_model = \"all-MiniLM-L6-v2\"\n"
+        );
+        let f = write_toml(&toml);
+        let emb = load_from(f.path(), None, None)
+            .unwrap()
+            .llm
+            .embedding
+            .unwrap();
+        assert_eq!(emb.provider, EmbeddingProviderKind::Local);
+        assert_eq!(emb.base_url, "http://127.0.0.1:8001/embed");
+        assert_eq!(emb.model, "all-MiniLM-L6-v2");
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nembedding_provider = \"openai\"\n");
+        let f = write_toml(&toml);
+        let emb = load_from(f.path(), None, None)
+            .unwrap()
+            .llm
+            .embedding
+            .unwrap();
+        assert_eq!(emb.provider, EmbeddingProviderKind::OpenAi);
+        assert_eq!(emb.base_url, DEFAULT_OPENAI_EMBEDDINGS_URL);
+        assert_eq!(emb.model, DEFAULT_OPENAI_EMBEDDING_MODEL);
+
+        // A local provider without a URL is a config error.
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nembedding_provider = \"local\"\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn parse_llm_strip_patterns() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[llm]\nstrip_patterns = [\"<scratch>...</scratch>\", \"<think>...</think>\"]\nstrip_reasoning = true\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        // strip_reasoning does not duplicate an explicit <think> pattern.
+        assert_eq!(
+            cfg.llm.strip_patterns,
+            vec![
+                StripPattern::parse("<scratch>...</scratch>").unwrap(),
+                StripPattern::parse(REASONING_STRIP_PATTERN).unwrap(),
+            ]
+        );
+
+        let toml = format!("{MINIMAL_TOML}\n[llm]\nstrip_patterns = [\"<think>\"]\n");
+        let f = write_toml(&toml);
+        assert!(load_from(f.path(), None, None).is_err());
+    }
+
+    #[test]
+    fn invalid_input_overflow_errors() {
+        let toml = format!("{MINIMAL_TOML}\n[comms]\ninput_overflow = \"drop\"\n");
+        let f = write_toml(&toml);
+        let msg = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(msg.contains("input_overflow"));
+    }
+
+    #[test]
+    fn parse_telegram_respond_mode() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.telegram.respond_mode, RespondMode::Always);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.telegram]\nrespond_mode = \"mention\"\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.telegram.respond_mode, RespondMode::Mention);
+
+        let toml = format!("{MINIMAL_TOML}\n[comms.telegram]\nrespond_mode = \"never\"\n");
+        let f = write_toml(&toml);
+        let msg = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(msg.contains("respond_mode"));
+    }
+
+    #[test]
+    fn parse_channel_default_agents() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms.telegram]\ndefault_agent = \"chat\"\n\n[comms.http]\ndefault_agent = \"docs\"\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert_eq!(cfg.comms.telegram.default_agent.as_deref(), Some("chat"));
+        let defaults = cfg.comms.channel_default_agents();
+        assert_eq!(defaults.len(), 2);
+        assert_eq!(defaults.get("telegram").map(String::as_str), Some("chat"));
+        assert_eq!(defaults.get("http").map(String::as_str), Some("docs"));
+        assert!(!defaults.contains_key("pty"));
+    }
+
+    #[test]
+    fn comms_channels_list_overrides_enabled_flags() {
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms]\nchannels = [\"http\", \"axum_channel\"]\n\n[comms.pty]\nenabled = true\n\n[comms.telegram]\nenabled = true\n"
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms_pty_should_load());
+        assert!(!cfg.comms_telegram_should_load());
+        assert!(cfg.comms_http_should_load());
+        assert!(cfg.comms_axum_should_load());
+    }
+
+    #[test]
+    fn disable_channel_overrides_channels_list() {
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nchannels = [\"pty\", \"http\"]\n");
+        let f = write_toml(&toml);
+        let mut cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms_pty_should_load());
+        cfg.comms.disable_channel("pty");
+        assert!(!cfg.comms_pty_should_load());
+        assert!(cfg.comms_http_should_load());
+    }
+
+    #[test]
+    fn comms_channels_absent_uses_enabled_flags() {
+        let toml = format!("{MINIMAL_TOML}\n[comms.telegram]\nenabled = true\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(cfg.comms.channels.is_none());
+        assert!(cfg.comms_pty_should_load());
+        assert!(cfg.comms_telegram_should_load());
+        assert!(!cfg.comms_http_should_load());
+        assert!(!cfg.comms_axum_should_load());
+    }
+
+    #[test]
+    fn parse_comms_email() {
+        let f = write_toml(MINIMAL_TOML);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms_email_should_load());
+        assert_eq!(cfg.comms.email.imap_port, DEFAULT_EMAIL_IMAP_PORT);
+
+        let password = write_toml("hunter2\n");
+        let toml = format!(
+            "{MINIMAL_TOML}\n[comms.email]\nenabled = true\nimap_host = \"mail.example.com\"\n\
+             username = \"bot@example.com\"\npassword_file = {:?}\npoll_interval_secs = 30\n\
+             allowed_senders = [\" Alice@Example.com \"]\n",
+            password.path()
+        );
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        let email = &cfg.comms.email;
+        assert!(cfg.comms_email_should_load());
+        assert_eq!(email.smtp_host, "mail.example.com");
+        assert_eq!(email.smtp_port, DEFAULT_EMAIL_SMTP_PORT);
+        assert_eq!(email.from_address, "bot@example.com");
+        assert_eq!(email.password.as_deref(), Some("hunter2"));
+        assert_eq!(email.mailbox, "INBOX");
+        assert_eq!(email.poll_interval_secs, 30);
+        assert_eq!(email.allowed_senders, ["alice@example.com"]);
+    }
+
+    #[test]
+    fn empty_comms_channels_disables_all() {
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nchannels = []\n");
+        let f = write_toml(&toml);
+        let cfg = load_from(f.path(), None, None).unwrap();
+        assert!(!cfg.comms_pty_should_load());
+    }
+
+    #[test]
+    fn unknown_comms_channel_errors() {
+        let toml = format!("{MINIMAL_TOML}\n[comms]\nchannels = [\"irc\"]\n");
+        let f = write_toml(&toml);
+        let msg = load_from(f.path(), None, None).unwrap_err().to_string();
+        assert!(msg.contains("irc"));
+    }
+
+    const BASE_TOML: &str = r#"
+[supervisor]
+bot_name = "base-bot"
+work_dir = "~/.araliya"
+log_level = "info"
+
+[agents]
+default = "echo"
+
+[llm]
+default = "dummy"
+
+[llm.providers.openai]
+api_type = "chat_completions"
+api_base_url = "https://api.openai.com/v1/chat/completions"
+model = "gpt-base"
+temperature = 0.1
+timeout_seconds = 30
+"#;
+
+    fn write_named(dir: &TempDir, name: &str, content: &str) -> std::path::PathBuf {
+        let p = dir.path().join(name);
+        std::fs::write(&p, content).unwrap();
+        p
+    }
+
+    #[test]
+    fn overlay_keeps_base_fields() {
+        let dir = TempDir::new().unwrap();
+        write_named(&dir, "base.toml", BASE_TOML);
+        let overlay = r#"
+[meta]
+base = "base.toml"
+
+[supervisor]
+log_level = "debug"
+"#;
+        let overlay_path = write_named(&dir, "overlay.toml", overlay);
+        let cfg = load_from(&overlay_path, None, None).unwrap();
+        assert_eq!(cfg.bot_name, "base-bot");
+        assert_eq!(cfg.log_level, "debug");
+    }
+
+    #[test]
+    fn overlay_wins_scalar() {
+        let dir = TempDir::new().unwrap();
+        write_named(&dir, "base.toml", BASE_TOML);
+        let overlay = r#"
+[meta]
+base = "base.toml"
+
+[llm.providers.openai]
+model = "gpt-overlay"
+"#;
+        let overlay_path = write_named(&dir, "overlay.toml", overlay);
+        let cfg = load_from(&overlay_path, None, None).unwrap();
+        let openai = cfg.llm.providers.get("openai").unwrap();
+        assert_eq!(openai.model, "gpt-overlay");
+        assert_eq!(openai.temperature, 0.1);
+    }
+
+    #[test]
+    fn chained_bases() {
+        let dir = TempDir::new().unwrap();
+        write_named(&dir, "grandbase.toml", BASE_TOML);
+        let middle = r#"
+[meta]
+base = "grandbase.toml"
+
+[supervisor]
+bot_name = "middle-bot"
+"#;
+        write_named(&dir, "middle.toml", middle);
+        let top = r#"
+[meta]
+base = "middle.toml"
+
+[supervisor]
+log_level = "warn"
+"#;
+        let top_path = write_named(&dir, "top.toml", top);
+        let cfg = load_from(&top_path, None, None).unwrap();
+        assert_eq!(cfg.bot_name, "middle-bot");
+        assert_eq!(cfg.log_level, "warn");
+    }
+
+    #[test]
+    fn missing_base_errors() {
+        let dir = TempDir::new().unwrap();
+        let overlay = r#"
+[meta]
+base = "nonexistent.toml"
+
+[supervisor]
+bot_name = "x"
+work_dir = "~/.araliya"
+log_level = "info"
+"#;
+        let overlay_path = write_named(&dir, "overlay.toml", overlay);
+        let result = load_from(&overlay_path, None, None);
+        assert!(result.is_err());
+        let msg = result.unwrap_err().to_string();
+        assert!(msg.contains("cannot read") || msg.contains("config error"));
     }
 
     #[test]
@@ -320,4 +1806,40 @@ log_level = "info"
         let msg = result.unwrap_err().to_string();
         assert!(msg.contains("circular"));
     }
+
+    #[test]
+    fn syntax_error_reports_file_line_and_snippet() {
+        let dir = TempDir::new().unwrap();
+        let bad = "[supervisor]\nbot_name = \"x\"\nwork_dir = = \"~/.araliya\"\n";
+        let path = write_named(&dir, "broken.toml", bad);
+        let err = load_from(&path, None, None).unwrap_err();
+
+        let AppError::ConfigParse(parse) = &err else {
+            panic!("expected ConfigParse, got {err:?}");
+        };
+        assert_eq!(parse.line, Some(3));
+        assert!(parse.included_from.is_empty());
+        let msg = err.to_string();
+        assert!(msg.contains("broken.toml"), "got: {msg}");
+        assert!(msg.contains("line 3"), "got: {msg}");
+        assert!(msg.contains("work_dir = = "), "got: {msg}");
+    }
+
+    #[test]
+    fn syntax_error_in_base_names_the_chain() {
+        let dir = TempDir::new().unwrap();
+        write_named(&dir, "base.toml", "[supervisor\nbot_name = \"x\"\n");
+        let overlay_path = write_named(&dir, "overlay.toml", "[meta]\nbase = \"base.toml\"\n");
+        let err = load_from(&overlay_path, None, None).unwrap_err();
+
+        let AppError::ConfigParse(parse) = &err else {
+            panic!("expected ConfigParse, got {err:?}");
+        };
+        assert!(parse.path.ends_with("base.toml"));
+        assert_eq!(parse.line, Some(1));
+        assert_eq!(parse.included_from, vec![overlay_path.clone()]);
+        let msg = err.to_string();
+        assert!(msg.contains("base.toml"), "got: {msg}");
+        assert!(msg.contains("overlay.toml"), "got: {msg}");
+    }
 }
@@ -27,6 +27,10 @@ pub(super) struct RawConfig {
     pub tools: RawTools,
     #[serde(default)]
     pub runtimes: RawRuntimes,
+    #[serde(default)]
+    pub health: RawHealth,
+    #[serde(default)]
+    pub cron: RawCron,
 }
 
 #[derive(Deserialize)]
@@ -35,7 +39,38 @@ pub(super) struct RawSupervisor {
     pub work_dir: String,
     #[serde(default)]
     pub identity_dir: Option<String>,
+    /// Separate memory tree within the identity directory.
+    #[serde(default)]
+    pub bot_namespace: Option<String>,
     pub log_level: String,
+    #[serde(default = "default_false")]
+    pub maintenance: bool,
+    #[serde(default)]
+    pub maintenance_message: Option<String>,
+    #[serde(default = "default_false")]
+    pub startup_self_test: bool,
+    #[serde(default = "default_false")]
+    pub startup_self_test_fatal: bool,
+    #[serde(default = "default_false")]
+    pub panic_hook: bool,
+    #[serde(default = "default_false")]
+    pub coalesce_requests: bool,
+    #[serde(default = "default_false")]
+    pub ready_line: bool,
+    #[serde(default = "default_false")]
+    pub sd_notify: bool,
+    #[serde(default)]
+    pub otel_endpoint: Option<String>,
+    #[serde(default)]
+    pub log_buffer_capacity: Option<usize>,
+    #[serde(default)]
+    pub socket_bind_attempts: Option<u32>,
+    #[serde(default)]
+    pub socket_bind_backoff_ms: Option<u64>,
+    #[serde(default = "default_true")]
+    pub presence_events: bool,
+    #[serde(default = "default_false")]
+    pub control_handshake: bool,
 }
 
 // ── Comms ───────────────────────────────────────────────────────────────────
@@ -47,21 +82,96 @@ pub(super) struct RawComms {
     #[serde(default)]
     pub telegram: RawTelegram,
     #[serde(default)]
+    pub email: RawEmail,
+    #[serde(default)]
     pub http: RawHttp,
     #[serde(default)]
     pub axum_channel: RawAxumChannel,
+    /// Maximum characters per inbound message (unset = unlimited).
+    #[serde(default)]
+    pub max_input_chars: Option<usize>,
+    /// `"truncate"` (default) or `"reject"`.
+    #[serde(default)]
+    pub input_overflow: Option<String>,
+    #[serde(default = "default_false")]
+    pub strip_control_chars: bool,
+    /// Channels to load; overrides per-channel `enabled` when present.
+    #[serde(default)]
+    pub channels: Option<Vec<String>>,
+    #[serde(default = "default_false")]
+    pub auto_session: bool,
+    #[serde(default = "default_false")]
+    pub inline_routing: bool,
+    #[serde(default = "default_false")]
+    pub agent_switching: bool,
+    /// Command prefix; `""` disables commands.
+    #[serde(default)]
+    pub command_prefix: Option<String>,
+    /// Comms event broadcast capacity; 0 = default.
+    #[serde(default)]
+    pub event_buffer: Option<usize>,
+    /// Close server connections idle this many seconds; 0 = never.
+    #[serde(default)]
+    pub idle_timeout_seconds: Option<u64>,
+    /// Message broadcast to channels on shutdown; blank = none.
+    #[serde(default)]
+    pub shutdown_message: Option<String>,
+    /// Serve the redacted effective config at `GET /api/config`.
+    #[serde(default = "default_false")]
+    pub expose_config: bool,
 }
 
 #[derive(Deserialize)]
 pub(super) struct RawPty {
     #[serde(default = "default_true")]
     pub enabled: bool,
+    #[serde(default)]
+    pub default_agent: Option<String>,
+    #[serde(default)]
+    pub tool_retry: bool,
 }
 
 #[derive(Deserialize, Default)]
 pub(super) struct RawTelegram {
     #[serde(default = "default_false")]
     pub enabled: bool,
+    #[serde(default)]
+    pub default_agent: Option<String>,
+    /// `"always"` (default), `"mention"` or `"reply"`.
+    #[serde(default)]
+    pub respond_mode: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct RawEmail {
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub default_agent: Option<String>,
+    #[serde(default)]
+    pub imap_host: Option<String>,
+    #[serde(default)]
+    pub imap_port: Option<u16>,
+    #[serde(default)]
+    pub smtp_host: Option<String>,
+    #[serde(default)]
+    pub smtp_port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Password or reference (`secret:name`, `${ENV_VAR}`).
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Path to a file containing the password.
+    #[serde(default)]
+    pub password_file: Option<String>,
+    #[serde(default)]
+    pub from_address: Option<String>,
+    #[serde(default)]
+    pub mailbox: Option<String>,
+    #[serde(default)]
+    pub poll_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub allowed_senders: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -70,6 +180,25 @@ pub(super) struct RawHttp {
     pub enabled: bool,
     #[serde(default = "default_http_bind")]
     pub bind: String,
+    #[serde(default)]
+    pub default_agent: Option<String>,
+    #[serde(default)]
+    pub slow_request_ms: Option<u64>,
+    #[serde(default)]
+    pub read_timeout_ms: Option<u64>,
+    #[serde(default)]
+    pub max_header_bytes: Option<usize>,
+    #[serde(default)]
+    pub access_log: Option<String>,
+    #[serde(default = "default_true")]
+    pub persist_sessions: bool,
+    #[serde(default)]
+    pub max_connections: Option<usize>,
+    #[serde(default)]
+    pub connection_overflow: Option<String>,
+    /// GET route → milliseconds its JSON is served from cache.
+    #[serde(default)]
+    pub cache_ttl_ms: HashMap<String, u64>,
 }
 
 #[derive(Deserialize)]
@@ -78,6 +207,8 @@ pub(super) struct RawAxumChannel {
     pub enabled: bool,
     #[serde(default = "default_http_bind")]
     pub bind: String,
+    #[serde(default)]
+    pub default_agent: Option<String>,
 }
 
 // ── LLM ─────────────────────────────────────────────────────────────────────
@@ -95,6 +226,65 @@ pub(super) struct RawLlm {
     /// Symbolic route hints → (provider, optional model) pairs.
     #[serde(default)]
     pub routes: HashMap<String, RawRouteConfig>,
+    /// `[[llm.auto_route]]` rules, tried in order.
+    #[serde(default)]
+    pub auto_route: Vec<RawAutoRouteRule>,
+    /// Path of the JSONL request audit log.
+    #[serde(default)]
+    pub audit_log: Option<String>,
+    #[serde(default)]
+    pub audit_full: bool,
+    /// Rotate `audit_log` once it grows past this many bytes; 0 = never.
+    #[serde(default)]
+    pub audit_max_bytes: Option<u64>,
+    /// Reject prompts (system + content) longer than this; 0 = no limit.
+    #[serde(default)]
+    pub max_prompt_chars: Option<usize>,
+    /// Completions of one `llm/batch` run at once; 0 is treated as 1.
+    #[serde(default)]
+    pub batch_concurrency: Option<usize>,
+    /// Cap on one whole completion, across provider calls; 0 = none.
+    #[serde(default)]
+    pub overall_deadline_seconds: Option<u64>,
+    /// Warm the active provider with a throwaway completion at startup.
+    #[serde(default)]
+    pub warmup: bool,
+    /// Models a request may select; empty = any.
+    #[serde(default)]
+    pub allowed_models: Vec<String>,
+    /// `"openai"` or `"local"`; unset disables `llm/embed`.
+    #[serde(default)]
+    pub embedding_provider: Option<String>,
+    #[serde(default)]
+    pub embedding_base_url: Option<String>,
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// `"OPEN...CLOSE"` regions stripped from completions.
+    #[serde(default)]
+    pub strip_patterns: Vec<String>,
+    /// Shorthand for stripping `<think>...</think>`.
+    #[serde(default)]
+    pub strip_reasoning: bool,
+    /// Fault injection knobs for `default = "faulty"`.
+    #[serde(default)]
+    pub faulty: RawFaulty,
+    /// Seconds idle provider connections stay open for reuse.
+    #[serde(default)]
+    pub pool_idle_timeout: Option<u64>,
+    /// Idle provider connections kept per host.
+    #[serde(default)]
+    pub pool_max_idle_per_host: Option<usize>,
+}
+
+/// `[llm.faulty]` — see `FaultyConfig`.
+#[derive(Deserialize, Default)]
+pub(super) struct RawFaulty {
+    #[serde(default)]
+    pub fail_percent: u8,
+    #[serde(default)]
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub statuses: Vec<u16>,
 }
 
 impl Default for RawLlm {
@@ -104,6 +294,23 @@ impl Default for RawLlm {
             providers: HashMap::new(),
             instruction: None,
             routes: HashMap::new(),
+            auto_route: Vec::new(),
+            audit_log: None,
+            audit_full: false,
+            audit_max_bytes: None,
+            max_prompt_chars: None,
+            batch_concurrency: None,
+            overall_deadline_seconds: None,
+            warmup: false,
+            allowed_models: Vec::new(),
+            embedding_provider: None,
+            embedding_base_url: None,
+            embedding_model: None,
+            strip_patterns: Vec::new(),
+            strip_reasoning: false,
+            faulty: RawFaulty::default(),
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
         }
     }
 }
@@ -116,6 +323,16 @@ pub(super) struct RawRouteConfig {
     pub model: Option<String>,
 }
 
+/// `[[llm.auto_route]]` — see `AutoRouteRule`.
+#[derive(Deserialize)]
+pub(super) struct RawAutoRouteRule {
+    #[serde(default)]
+    pub min_tokens: Option<usize>,
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    pub provider: String,
+}
+
 /// Configuration for a single named LLM provider.
 /// `api_type` determines the wire adapter; `api_base_url` defaults based on `api_type`.
 #[derive(Deserialize)]
@@ -144,6 +361,12 @@ pub(super) struct RawProviderConfig {
     /// Maximum output tokens (0 = no limit).
     #[serde(default)]
     pub max_tokens: usize,
+    /// Mark the system prompt as a cacheable prefix.
+    #[serde(default)]
+    pub prompt_caching: bool,
+    /// Model context window in tokens; unset or 0 = the known size for `model`.
+    #[serde(default)]
+    pub context_window: Option<usize>,
     #[serde(default)]
     pub input_per_million_usd: f64,
     #[serde(default)]
@@ -163,10 +386,73 @@ pub(super) struct RawAgents {
     /// Enable per-turn debug logging to the session KV store for agentic plugins.
     #[serde(default)]
     pub debug_logging: bool,
+    /// Send `comms/agent_step` notifications during agentic turns.
+    #[serde(default)]
+    pub emit_steps: bool,
+    /// Longest handoff/tool-call chain per conversation.
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+    /// `"allow"`, `"strip"` or `"rewrite"` for links in chat replies.
+    #[serde(default)]
+    pub link_policy: Option<String>,
+    /// Replacement for each link under `link_policy = "rewrite"`.
+    #[serde(default)]
+    pub link_rewrite: Option<String>,
+    /// Fail startup when the default agent is not loaded (no echo fallback).
+    #[serde(default)]
+    pub strict_default: bool,
+    /// Register a no-op agent when the build has no `plugin-*` agents.
+    #[serde(default)]
+    pub noop_fallback: bool,
+    /// Session cost in USD at which a spend alert is raised.
+    #[serde(default)]
+    pub spend_alert_usd: Option<f64>,
+    /// Output tokens a session may use per UTC day.
+    #[serde(default)]
+    pub daily_output_token_budget: Option<u64>,
+    /// Reply to empty chat input instead of calling the LLM.
+    #[serde(default)]
+    pub empty_input_reply: Option<String>,
+    /// Reply in place of an empty LLM completion.
+    #[serde(default)]
+    pub empty_reply: Option<String>,
+    /// Title new chat sessions after their first turn.
+    #[serde(default)]
+    pub auto_title: bool,
+    /// Serve `agents/{id}/debug_prompt`.
+    #[serde(default)]
+    pub debug_prompt: bool,
+    /// Reply template for failed agent requests on chat channels.
+    #[serde(default)]
+    pub error_message: Option<String>,
+    /// Blocklist checked on chat input and output.
+    #[serde(default)]
+    pub moderation: Option<RawModeration>,
+    /// Bounds on tool results fed into prompts.
+    #[serde(default)]
+    pub tool_output: Option<RawToolOutput>,
     #[serde(flatten)]
     pub entries: HashMap<String, RawAgentEntry>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct RawModeration {
+    #[serde(default)]
+    pub blocklist: Vec<String>,
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    #[serde(default)]
+    pub refusal: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RawToolOutput {
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+    #[serde(default)]
+    pub max_items: Option<usize>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct RawAgentEntry {
     #[serde(default = "default_true")]
@@ -183,6 +469,9 @@ pub(super) struct RawAgentEntry {
     pub use_kg: bool,
     #[serde(default)]
     pub kg: RawKgConfig,
+    /// Cap on the docs agent's embedding cache; `0` disables it.
+    #[serde(default)]
+    pub embedding_cache_entries: Option<usize>,
     /// Whether the `agentic-chat` plugin should route the instruction pass
     /// through `llm/instruct` (requires `[llm.instruction]` to be configured).
     #[serde(default)]
@@ -191,6 +480,49 @@ pub(super) struct RawAgentEntry {
     /// Defaults to empty — the agent can only use its own local tools.
     #[serde(default)]
     pub skills: Vec<String>,
+    /// Minimum milliseconds between invocations of this agent per channel.
+    #[serde(default)]
+    pub cooldown_ms: Option<u64>,
+    /// Forward empty or whitespace-only input to this agent's LLM call
+    /// instead of answering with `empty_input_reply`.
+    #[serde(default)]
+    pub allow_empty_input: bool,
+    /// Reply in place of an empty LLM completion for this agent.
+    #[serde(default)]
+    pub empty_reply: Option<String>,
+    /// Write retrieved/tool context into the session transcript.
+    #[serde(default)]
+    pub persist_context: bool,
+    /// Reply format for chat agents: `"text"` (default) or `"json"`.
+    #[serde(default)]
+    pub response_format: Option<String>,
+    /// Most recent user/assistant turns included in the prompt.
+    #[serde(default)]
+    pub context_turns: Option<usize>,
+    /// Working memory written into each new session for this agent.
+    #[serde(default)]
+    pub seed_memory: Option<RawSeedMemory>,
+    /// Sessions kept for this agent; older ones are pruned.  0 = no limit.
+    #[serde(default)]
+    pub max_sessions: Option<usize>,
+    /// Reply prefix for the `echo` agent.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// Reply suffix for the `echo` agent.
+    #[serde(default)]
+    pub suffix: Option<String>,
+    /// Case transform for the `echo` agent: `"verbatim"`, `"upper"` or `"lower"`.
+    #[serde(default)]
+    pub case: Option<String>,
+    /// Artificial reply delay for the `echo` agent, in milliseconds.
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+    /// Output style for the `summarize` agent: `"bullets"` or `"prose"`.
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Word budget for the `summarize` agent.
+    #[serde(default)]
+    pub max_words: Option<usize>,
     /// Runtime name for the `runtime_cmd` agent (e.g. `"node"`, `"bash"`).
     #[serde(default)]
     pub runtime: Option<String>,
@@ -228,6 +560,14 @@ pub(super) struct RawAgentEntry {
     pub target_agent: Option<String>,
 }
 
+/// `[agents.<id>] seed_memory`: free text, or a table of facts.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(super) enum RawSeedMemory {
+    Text(String),
+    Facts(std::collections::BTreeMap<String, String>),
+}
+
 #[derive(Deserialize, Default)]
 pub(super) struct RawKgConfig {
     #[serde(default)]
@@ -284,6 +624,26 @@ pub(super) struct RawGdeltAgentQuery {
 
 #[derive(Deserialize, Default)]
 pub(super) struct RawMemory {
+    #[serde(default)]
+    pub max_sessions: Option<usize>,
+    /// `"evict"` (default) or `"reject"`.
+    #[serde(default)]
+    pub session_overflow: Option<String>,
+    /// `"md"` (default) or `"jsonl"`.
+    #[serde(default)]
+    pub transcript_format: Option<String>,
+    /// Minutes without activity before a session is archived; 0 = never.
+    #[serde(default)]
+    pub session_idle_archive_minutes: Option<u64>,
+    /// Minutes between aggregate spend snapshots; 0 = never.
+    #[serde(default)]
+    pub spend_snapshot_minutes: Option<u64>,
+    /// Gzip the transcripts of archived sessions.
+    #[serde(default = "default_false")]
+    pub compress_archived_transcripts: bool,
+    /// Continue sessions in memory when disk writes fail.
+    #[serde(default = "default_false")]
+    pub write_fallback: bool,
     #[serde(default)]
     pub basic_session: RawBasicSessionConfig,
 }
@@ -316,6 +676,26 @@ pub(super) struct RawSvui {
 pub(super) struct RawTools {
     #[serde(default)]
     pub newsmail_aggregator: RawNewsmailAggregator,
+    #[serde(default)]
+    pub memory: RawMemoryTool,
+}
+
+/// `[tools.memory]` — the `memory` tool over an agent's own sessions.
+#[derive(Deserialize)]
+pub(super) struct RawMemoryTool {
+    #[serde(default = "default_memory_tool_max_results")]
+    pub max_results: usize,
+    #[serde(default)]
+    pub all_sessions: bool,
+}
+
+impl Default for RawMemoryTool {
+    fn default() -> Self {
+        Self {
+            max_results: default_memory_tool_max_results(),
+            all_sessions: false,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -361,11 +741,62 @@ impl Default for RawRuntimes {
     }
 }
 
+// ── Health ───────────────────────────────────────────────────────────────────
+
+/// Raw config for health reporting (`[health]`).
+#[derive(Deserialize)]
+pub(super) struct RawHealth {
+    #[serde(default = "default_health_refresh_timeout_ms")]
+    pub refresh_timeout_ms: u64,
+    /// Failures within this many seconds of startup report "starting".
+    #[serde(default)]
+    pub startup_grace_seconds: u64,
+}
+
+impl Default for RawHealth {
+    fn default() -> Self {
+        Self {
+            refresh_timeout_ms: default_health_refresh_timeout_ms(),
+            startup_grace_seconds: 0,
+        }
+    }
+}
+
+/// `[cron]` section.
+#[derive(Deserialize, Default)]
+pub(super) struct RawCron {
+    #[serde(default)]
+    pub quiet_hours: Option<RawQuietHours>,
+    /// Retries of a failed dispatch; 0 = none.
+    #[serde(default)]
+    pub retry_attempts: u32,
+    /// First retry delay, doubled per retry.
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
+}
+
+/// `[cron] quiet_hours = { start = "22:00", end = "07:00", tz = "+02:00" }`.
+#[derive(Deserialize)]
+pub(super) struct RawQuietHours {
+    pub start: String,
+    pub end: String,
+    /// `"UTC"`, a fixed `"±HH:MM"` offset, or an IANA zone name.
+    #[serde(default)]
+    pub tz: Option<String>,
+    /// `"defer"` (default) or `"drop"`.
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
 // ── Default impls for serde ──────────────────────────────────────────────────
 
 impl Default for RawPty {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            default_agent: None,
+            tool_retry: false,
+        }
     }
 }
 
@@ -374,6 +805,15 @@ impl Default for RawHttp {
         Self {
             enabled: false,
             bind: default_http_bind(),
+            default_agent: None,
+            slow_request_ms: None,
+            read_timeout_ms: None,
+            max_header_bytes: None,
+            access_log: None,
+            persist_sessions: true,
+            max_connections: None,
+            connection_overflow: None,
+            cache_ttl_ms: HashMap::new(),
         }
     }
 }
@@ -383,6 +823,7 @@ impl Default for RawAxumChannel {
         Self {
             enabled: false,
             bind: default_http_bind(),
+            default_agent: None,
         }
     }
 }
@@ -418,6 +859,10 @@ fn default_runtimes_timeout() -> u64 {
     30
 }
 
+pub(super) fn default_health_refresh_timeout_ms() -> u64 {
+    5000
+}
+
 fn default_agent_name() -> String {
     "basic_chat".to_string()
 }
@@ -428,3 +873,7 @@ pub(super) fn default_newsmail_label_ids() -> Vec<String> {
 pub(super) fn default_newsmail_n_last() -> usize {
     10
 }
+
+pub(super) fn default_memory_tool_max_results() -> usize {
+    20
+}
@@ -6,53 +6,373 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::types::llm::ResponseFormat;
+
+/// Placeholder emitted instead of secret values when a config is serialized.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Reply to agent requests in maintenance mode when no message is configured.
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str =
+    "The bot is down for maintenance and will be back soon.";
+
+/// Reply chat agents give to empty or whitespace-only input instead of
+/// calling the LLM, when no `[agents] empty_input_reply` is configured.
+pub const DEFAULT_EMPTY_INPUT_REPLY: &str = "Did you mean to ask something?";
+
+/// Reply chat agents send in place of an empty LLM completion, when no
+/// `[agents] empty_reply` is configured.
+pub const DEFAULT_EMPTY_REPLY: &str = "I didn't produce a response; try rephrasing.";
+
+/// Reply chat channels show when an agent request fails and no
+/// `[agents] error_message` is configured.  `{error}` expands to the error.
+pub const DEFAULT_AGENT_ERROR_MESSAGE: &str = "Sorry, something went wrong. Please try again.";
+
+/// Reply sent in place of blocked content when `[agents.moderation]` sets
+/// no `refusal`.
+pub const DEFAULT_MODERATION_REFUSAL: &str = "Sorry, I can't help with that.";
+
+/// Longest string value passed from a tool result into an LLM prompt when
+/// `[agents.tool_output]` sets no `max_chars`.
+pub const DEFAULT_TOOL_OUTPUT_MAX_CHARS: usize = 2000;
+
+/// Most array items passed from a tool result into an LLM prompt when
+/// `[agents.tool_output]` sets no `max_items`.
+pub const DEFAULT_TOOL_OUTPUT_MAX_ITEMS: usize = 50;
+
+/// Tries to bind the management socket before giving up, when
+/// `[supervisor] socket_bind_attempts` is unset.
+pub const DEFAULT_SOCKET_BIND_ATTEMPTS: u32 = 5;
+
+/// Wait before the first management socket bind retry (doubling after
+/// each), when `[supervisor] socket_bind_backoff_ms` is unset.
+pub const DEFAULT_SOCKET_BIND_BACKOFF_MS: u64 = 100;
+
+/// Largest request head (request line and headers) the HTTP channel reads,
+/// when `[comms.http] max_header_bytes` is unset.
+pub const DEFAULT_HTTP_MAX_HEADER_BYTES: usize = 8 * 1024;
+
+/// Completions of one `llm/batch` request run at once, when
+/// `[llm] batch_concurrency` is unset.
+pub const DEFAULT_LLM_BATCH_CONCURRENCY: usize = 4;
+
+/// Handoffs and tool calls one conversation may nest, when
+/// `[agents] max_depth` is unset.
+pub const DEFAULT_AGENT_MAX_DEPTH: usize = 8;
+
+/// Embeddings a docs agent keeps cached, when
+/// `[agents.<id>] embedding_cache_entries` is unset.
+pub const DEFAULT_EMBEDDING_CACHE_ENTRIES: usize = 10_000;
+
+/// IMAP over implicit TLS, when `[comms.email] imap_port` is unset.
+pub const DEFAULT_EMAIL_IMAP_PORT: u16 = 993;
+
+/// SMTP submission over implicit TLS, when `[comms.email] smtp_port` is unset.
+pub const DEFAULT_EMAIL_SMTP_PORT: u16 = 465;
+
+/// Seconds between inbox polls, when `[comms.email] poll_interval_secs` is unset.
+pub const DEFAULT_EMAIL_POLL_INTERVAL_SECS: u64 = 60;
+
+/// Serialize a secret as [`REDACTED`] (or `null` when absent).
+fn redact_secret<S: Serializer>(value: &Option<String>, s: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(_) => s.serialize_some(REDACTED),
+        None => s.serialize_none(),
+    }
+}
+
 // ── Comms ───────────────────────────────────────────────────────────────────
 
 /// PTY (console) channel configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PtyConfig {
     /// Whether the PTY channel is explicitly enabled.
     pub enabled: bool,
+    /// Agent for this channel type; see [`CommsConfig`].
+    pub default_agent: Option<String>,
+    /// Answer a request that failed on a tool with the error and a `/retry`
+    /// offer; `/retry` sends that request again.
+    pub tool_retry: bool,
 }
 
 /// Telegram channel configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TelegramConfig {
     /// Whether the Telegram channel is explicitly enabled.
     pub enabled: bool,
+    /// Agent for this channel type; see [`CommsConfig`].
+    pub default_agent: Option<String>,
+    /// Which group messages the bot answers (`respond_mode`).  Private
+    /// chats are always answered.
+    pub respond_mode: RespondMode,
+}
+
+/// Which messages in a group chat reach the bot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RespondMode {
+    /// Every message.
+    #[default]
+    Always,
+    /// Only messages that @-mention the bot.
+    Mention,
+    /// Only replies to one of the bot's own messages.
+    Reply,
+}
+
+/// Email channel configuration (`[comms.email]`): polls an IMAP inbox and
+/// answers over SMTP.  Both connections use implicit TLS.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailConfig {
+    /// Whether the email channel is explicitly enabled.
+    pub enabled: bool,
+    /// Agent for this channel type; see [`CommsConfig`].
+    pub default_agent: Option<String>,
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    /// Login for both IMAP and SMTP.
+    pub username: String,
+    /// Resolved password (`password` / `password_file`).
+    #[serde(serialize_with = "redact_secret")]
+    pub password: Option<String>,
+    /// Sender address on replies.  Defaults to `username`.
+    pub from_address: String,
+    /// Mailbox polled for unseen messages.
+    pub mailbox: String,
+    pub poll_interval_secs: u64,
+    /// Lowercased addresses the channel answers.  Mail from anyone else is
+    /// marked seen and ignored; an empty list answers nobody.
+    pub allowed_senders: Vec<String>,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_agent: None,
+            imap_host: String::new(),
+            imap_port: DEFAULT_EMAIL_IMAP_PORT,
+            smtp_host: String::new(),
+            smtp_port: DEFAULT_EMAIL_SMTP_PORT,
+            username: String::new(),
+            password: None,
+            from_address: String::new(),
+            mailbox: "INBOX".to_string(),
+            poll_interval_secs: DEFAULT_EMAIL_POLL_INTERVAL_SECS,
+            allowed_senders: Vec::new(),
+        }
+    }
 }
 
 /// HTTP channel configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HttpConfig {
     /// Whether the HTTP channel is explicitly enabled.
     pub enabled: bool,
     /// Socket address to bind the HTTP channel to.
     pub bind: String,
+    /// Agent for this channel type; see [`CommsConfig`].
+    pub default_agent: Option<String>,
+    /// Requests taking at least this long are logged at WARN instead of DEBUG.
+    pub slow_request_ms: Option<u64>,
+    /// Close a connection when a request read stalls this long.
+    pub read_timeout_ms: Option<u64>,
+    /// Largest request head accepted; bigger ones get `431`.
+    pub max_header_bytes: usize,
+    /// Append one Combined Log Format line per request to this file.
+    pub access_log: Option<PathBuf>,
+    /// Keep sessions for requests that name none.  `false` gives each such
+    /// request a throwaway in-memory session instead.
+    pub persist_sessions: bool,
+    /// Most connections served at once; unlimited when unset.
+    pub max_connections: Option<usize>,
+    /// What happens to a connection beyond `max_connections`.
+    pub connection_overflow: ConnectionOverflow,
+    /// Read-only GET route (`/api/tree`, `/api/health`, `/api/agents`) →
+    /// how long its JSON is served from cache.  Routes not listed are not
+    /// cached.
+    pub cache_ttl_ms: HashMap<String, u64>,
+}
+
+/// What the HTTP channel does with a connection accepted while
+/// `max_connections` are already open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionOverflow {
+    /// Answer `503 Service Unavailable` at once.
+    #[default]
+    Reject,
+    /// Wait briefly for a slot, then answer `503`.
+    Wait,
 }
 
 /// Axum HTTP channel configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AxumChannelConfig {
     /// Whether the axum channel is explicitly enabled.
     pub enabled: bool,
     /// Socket address to bind the axum listener to.
     pub bind: String,
+    /// Agent for this channel type; see [`CommsConfig`].
+    pub default_agent: Option<String>,
+}
+
+/// What a channel does with a message longer than `max_input_chars`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputOverflow {
+    /// Cut the message at the limit and prepend a notice to the reply.
+    Truncate,
+    /// Refuse the message and reply with a notice instead.
+    Reject,
+}
+
+/// What chat agents do with `http://` and `https://` links in model replies
+/// (`[agents] link_policy`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkPolicy {
+    /// Leave links as they are.
+    #[default]
+    Allow,
+    /// Remove links.
+    Strip,
+    /// Replace each link with `template`, filling in `{url}` and `{host}`.
+    Rewrite { template: String },
+}
+
+/// What the memory subsystem does when a new disk-backed session would
+/// exceed `[memory] max_sessions`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionOverflow {
+    /// Delete the least-recently-updated session to make room.
+    #[default]
+    Evict,
+    /// Refuse to create the session.
+    Reject,
+}
+
+/// On-disk transcript format for new `basic_session` sessions
+/// (`[memory] transcript_format`).  Existing sessions keep the format they
+/// were created with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptFormat {
+    /// `transcript.md` — `### {role} — {timestamp}` sections.
+    #[default]
+    Md,
+    /// `transcript.jsonl` — one JSON entry per line; lossless to read back.
+    Jsonl,
 }
 
 /// Comms subsystem configuration.
-#[derive(Debug, Clone)]
+///
+/// Each channel's `default_agent` serves every session on that channel
+/// type that `[agents] routing` has no exact entry for, and falls back
+/// to `[agents] default` when unset (see
+/// [`channel_default_agents`](Self::channel_default_agents)).
+#[derive(Debug, Clone, Serialize)]
 pub struct CommsConfig {
     pub pty: PtyConfig,
     pub telegram: TelegramConfig,
+    pub email: EmailConfig,
     pub http: HttpConfig,
     pub axum_channel: AxumChannelConfig,
+    /// Maximum characters accepted per inbound message. `None` = unlimited.
+    pub max_input_chars: Option<usize>,
+    /// Behaviour when a message exceeds `max_input_chars`.
+    pub input_overflow: InputOverflow,
+    /// Strip ANSI escape sequences and control characters from inbound text.
+    pub strip_control_chars: bool,
+    /// Authoritative channel selection from `[comms] channels`.  When set,
+    /// exactly these channels load and per-channel `enabled` flags are ignored.
+    pub channels: Option<Vec<String>>,
+    /// Open a session per channel on its first message and reuse it, so
+    /// agents see a consistent `session_id` without the client sending one.
+    pub auto_session: bool,
+    /// Let a leading `@agent[:session]` token pick the agent and session
+    /// for one message, overriding the channel's defaults.
+    pub inline_routing: bool,
+    /// Let `/switch <agent>` hand a conversation, session and all, to
+    /// another enabled agent for its later messages.
+    pub agent_switching: bool,
+    /// Prefix that marks a conversation message as a command (`/switch`,
+    /// `/retry`); a doubled prefix sends one literally.  Empty disables
+    /// commands.
+    pub command_prefix: String,
+    /// Capacity of the channel lifecycle event broadcast; a subscriber
+    /// that falls further behind misses events (counted, never blocking).
+    pub event_buffer: usize,
+    /// Close server-channel connections (HTTP, axum) that move no bytes in
+    /// either direction for this long.  `None` = never.
+    pub idle_timeout_seconds: Option<u64>,
+    /// Broadcast to channels when the bot starts shutting down, so
+    /// interactive users see why they are cut off.  `None` = say nothing.
+    pub shutdown_message: Option<String>,
+    /// Serve the effective config, secrets redacted, at `GET /api/config`
+    /// on the HTTP channels.  Off, the route answers `403`.
+    pub expose_config: bool,
+}
+
+/// Default `[comms] event_buffer`.
+pub const DEFAULT_COMMS_EVENT_BUFFER: usize = 64;
+
+/// Command prefix when `[comms] command_prefix` is not set.
+pub const DEFAULT_COMMAND_PREFIX: &str = "/";
+
+/// Channel names accepted in `[comms] channels`.
+pub const COMMS_CHANNELS: &[&str] = &["pty", "telegram", "email", "http", "axum_channel"];
+
+impl CommsConfig {
+    /// Whether channel `name` should load: membership in `channels` when the
+    /// list is present, otherwise the channel's own `enabled` flag.
+    pub fn channel_enabled(&self, name: &str, enabled: bool) -> bool {
+        match &self.channels {
+            Some(list) => list.iter().any(|c| c == name),
+            None => enabled,
+        }
+    }
+
+    /// Force channel `name` off: clears its `enabled` flag and drops it from
+    /// `channels`, so neither selection mechanism can load it.
+    pub fn disable_channel(&mut self, name: &str) {
+        match name {
+            "pty" => self.pty.enabled = false,
+            "telegram" => self.telegram.enabled = false,
+            "email" => self.email.enabled = false,
+            "http" => self.http.enabled = false,
+            "axum_channel" => self.axum_channel.enabled = false,
+            _ => {}
+        }
+        if let Some(list) = &mut self.channels {
+            list.retain(|c| c != name);
+        }
+    }
+
+    /// Per-channel-type default agents, keyed by the channel-id prefix the
+    /// comms subsystem assigns (`pty`, `telegram`, `email`, `http`, `axum`).
+    pub fn channel_default_agents(&self) -> HashMap<String, String> {
+        [
+            ("pty", &self.pty.default_agent),
+            ("telegram", &self.telegram.default_agent),
+            ("email", &self.email.default_agent),
+            ("http", &self.http.default_agent),
+            ("axum", &self.axum_channel.default_agent),
+        ]
+        .into_iter()
+        .filter_map(|(kind, agent)| agent.clone().map(|a| (kind.to_string(), a)))
+        .collect()
+    }
 }
 
 // ── UI ───────────────────────────────────────────────────────────────────────
 
 /// SvUI (Svelte web UI) configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SvuiConfig {
     /// Whether the svui backend is explicitly enabled.
     pub enabled: bool,
@@ -61,7 +381,7 @@ pub struct SvuiConfig {
 }
 
 /// UI subsystem configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct UiConfig {
     pub svui: SvuiConfig,
 }
@@ -69,7 +389,7 @@ pub struct UiConfig {
 // ── Tools ────────────────────────────────────────────────────────────────────
 
 /// Specialized newsmail aggregator tool defaults.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NewsmailAggregatorConfig {
     /// Label IDs to filter by (e.g. ["INBOX"] or ["Label_xxx"]).
     pub label_ids: Vec<String>,
@@ -79,16 +399,27 @@ pub struct NewsmailAggregatorConfig {
     pub q: Option<String>,
 }
 
+/// `memory` tool settings (`[tools.memory]`).
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryToolConfig {
+    /// Most sessions or search hits one call returns.
+    pub max_results: usize,
+    /// Reach every session in the calling agent's store, not only the
+    /// calling session.
+    pub all_sessions: bool,
+}
+
 /// Tools subsystem configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ToolsConfig {
     pub newsmail_aggregator: NewsmailAggregatorConfig,
+    pub memory: MemoryToolConfig,
 }
 
 // ── Runtimes ─────────────────────────────────────────────────────────────────
 
 /// Runtimes subsystem configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RuntimesConfig {
     /// Whether the runtimes subsystem is enabled.
     pub enabled: bool,
@@ -97,10 +428,105 @@ pub struct RuntimesConfig {
     pub default_timeout_secs: u64,
 }
 
+// ── Health ───────────────────────────────────────────────────────────────────
+
+/// Health reporting configuration (`[health]`).
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthConfig {
+    /// Per-subsystem timeout for `manage/health/refresh`, in milliseconds.
+    pub refresh_timeout_ms: u64,
+    /// Seconds after startup during which failures report "starting"
+    /// (healthy, not ready) instead of unhealthy.  0 = no grace.
+    pub startup_grace_seconds: u64,
+}
+
+// ── Cron ─────────────────────────────────────────────────────────────────────
+
+/// Cron subsystem configuration (`[cron]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CronConfig {
+    /// Window in which opted-in schedules do not fire.  `None` = no gate.
+    pub quiet_hours: Option<QuietHours>,
+    /// Retries of a firing whose dispatch fails.
+    pub retry: CronRetry,
+}
+
+/// How a cron firing whose notification could not be dispatched is retried
+/// (`[cron] retry_attempts`, `retry_backoff_ms`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CronRetry {
+    /// Retries after the first failed dispatch.  0 = give up at once.
+    pub attempts: u32,
+    /// Delay before the first retry; each further retry doubles it.
+    pub backoff_ms: u64,
+}
+
+impl Default for CronRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 0,
+            backoff_ms: 1_000,
+        }
+    }
+}
+
+/// A daily quiet window (`[cron] quiet_hours`).  `start == end` is empty;
+/// `start > end` wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QuietHours {
+    /// Start of the window, in minutes after local midnight (inclusive).
+    pub start_minute: u16,
+    /// End of the window, in minutes after local midnight (exclusive).
+    pub end_minute: u16,
+    /// Time zone the window's clock times are read in.
+    pub tz: QuietHoursTz,
+    /// What happens to a firing that lands inside the window.
+    pub policy: QuietHoursPolicy,
+}
+
+/// Time zone of a quiet window (`[cron] quiet_hours.tz`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietHoursTz {
+    /// A fixed offset east of UTC, in minutes.
+    Offset(i16),
+    /// An IANA zone; the window follows its daylight-saving changes.
+    Zone(chrono_tz::Tz),
+}
+
+impl Serialize for QuietHoursTz {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Offset(0) => serializer.serialize_str("UTC"),
+            Self::Offset(minutes) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                serializer.collect_str(&format_args!(
+                    "{sign}{:02}:{:02}",
+                    minutes / 60,
+                    minutes % 60
+                ))
+            }
+            Self::Zone(tz) => serializer.serialize_str(tz.name()),
+        }
+    }
+}
+
+/// Fate of a cron firing inside quiet hours.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietHoursPolicy {
+    /// Fire once at the end of the window instead.
+    #[default]
+    Defer,
+    /// Skip the firing; repeating schedules continue at their next interval.
+    Drop,
+}
+
 // ── LLM ──────────────────────────────────────────────────────────────────────
 
 /// Which wire adapter a provider uses.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ApiType {
     /// Dummy echo provider — no HTTP, no API key needed.
     Dummy,
@@ -111,30 +537,64 @@ pub enum ApiType {
 }
 
 /// Configuration for a single named LLM provider.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ProviderConfig {
     pub api_type: ApiType,
     pub api_base_url: String,
     pub model: String,
     pub temperature: f32,
     /// Resolved API key.
+    #[serde(serialize_with = "redact_secret")]
     pub api_key: Option<String>,
     /// Reasoning effort for `OpenAiResponses` adapter. `None` for others.
     pub reasoning_effort: Option<String>,
     pub timeout_seconds: u64,
     pub max_tokens: usize,
+    /// Mark the system prompt with `cache_control` (`chat_completions` only).
+    pub prompt_caching: bool,
+    /// Tokens the model accepts per request, prompt and reply together
+    /// (`context_window`, else [`default_context_window`] for `model`).
+    pub context_window: usize,
     pub input_per_million_usd: f64,
     pub output_per_million_usd: f64,
     pub cached_input_per_million_usd: f64,
 }
 
+/// Context window assumed for a model that is not in the known list.
+pub const FALLBACK_CONTEXT_WINDOW: usize = 8_192;
+
+/// Context window, in tokens, of a well-known model, matched by name prefix
+/// (so dated snapshots such as `gpt-4o-2024-08-06` match too).  Unknown
+/// models get [`FALLBACK_CONTEXT_WINDOW`]; set `context_window` for them.
+pub fn default_context_window(model: &str) -> usize {
+    // Longer prefixes first: `gpt-4o` must win over `gpt-4`.
+    const KNOWN: &[(&str, usize)] = &[
+        ("gpt-5", 400_000),
+        ("gpt-4.1", 1_047_576),
+        ("gpt-4o", 128_000),
+        ("gpt-4-turbo", 128_000),
+        ("gpt-4", 8_192),
+        ("gpt-3.5-turbo", 16_385),
+        ("o4-mini", 200_000),
+        ("o3", 200_000),
+        ("o1", 200_000),
+        ("claude", 200_000),
+        ("gemini", 1_048_576),
+    ];
+    let model = model.rsplit('/').next().unwrap_or(model);
+    KNOWN
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map_or(FALLBACK_CONTEXT_WINDOW, |&(_, tokens)| tokens)
+}
+
 /// A named route mapping a symbolic hint to a (provider, model) pair.
 ///
 /// Routes allow agents to request capabilities by name (e.g. `"fast"`,
 /// `"reasoning"`) while the actual provider + model resolution is config-driven.
 /// The `model` field is optional — when absent, the provider's default model
 /// from its `ProviderConfig` is used.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RouteConfig {
     /// Key into `LlmConfig::providers`.
     pub provider: String,
@@ -142,8 +602,23 @@ pub struct RouteConfig {
     pub model: Option<String>,
 }
 
+/// One `[[llm.auto_route]]` rule: send requests whose estimated prompt size
+/// falls within the bounds to `provider`.
+///
+/// Bounds are inclusive; an unset bound is open.  Rules are tried in order
+/// and the first match wins.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AutoRouteRule {
+    /// Match prompts of at least this many estimated tokens.
+    pub min_tokens: Option<usize>,
+    /// Match prompts of at most this many estimated tokens.
+    pub max_tokens: Option<usize>,
+    /// Key into `LlmConfig::providers`.
+    pub provider: String,
+}
+
 /// LLM subsystem configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct LlmConfig {
     /// Key into `providers` that is the active default provider.
     pub default: String,
@@ -156,12 +631,132 @@ pub struct LlmConfig {
     /// Agents can request `"hint:fast"` and config resolves it to a specific
     /// provider + model without the agent knowing which backend it is.
     pub routes: HashMap<String, RouteConfig>,
+    /// Size-based provider selection for requests without an explicit
+    /// provider (`[[llm.auto_route]]`).  Empty = always the active default.
+    pub auto_route: Vec<AutoRouteRule>,
+    /// Optional JSONL audit file — one line per provider request.
+    /// Relative paths resolve against `work_dir`.
+    pub audit_log: Option<PathBuf>,
+    /// Record full prompt/completion text in the audit log (not just a hash).
+    pub audit_full: bool,
+    /// Rotate `audit_log` to `<audit_log>.1` once it exceeds this size.
+    /// `None` = never rotate.
+    pub audit_max_bytes: Option<u64>,
+    /// Hard cap on the characters of an outgoing prompt (system + content).
+    /// Longer prompts are rejected before reaching a provider.  `None` = no cap.
+    pub max_prompt_chars: Option<usize>,
+    /// Items of one `llm/batch` request sent to providers at once (≥ 1).
+    pub batch_concurrency: usize,
+    /// Bound on one logical completion, however many provider calls it
+    /// takes.  Providers' own `timeout_seconds` still applies per call.
+    /// `None` = no bound.
+    pub overall_deadline_seconds: Option<u64>,
+    /// Send one throwaway completion once the active provider is first
+    /// reachable, so the first real request finds it warm.
+    pub warmup: bool,
+    /// Models any request may resolve to, by name; others are rejected
+    /// before reaching a provider.  Empty = unrestricted.
+    pub allowed_models: Vec<String>,
+    /// Backend for `llm/embed`, independent of the chat providers.
+    /// `None` when `embedding_provider` is not set.
+    pub embedding: Option<EmbeddingConfig>,
+    /// Delimited regions removed from buffered completions before they are
+    /// returned (from `strip_patterns` and `strip_reasoning`).
+    pub strip_patterns: Vec<StripPattern>,
+    /// Fault injection for `default = "faulty"` (from `[llm.faulty]`).
+    pub faulty: FaultyConfig,
+    /// Connection reuse for the HTTP client shared by every provider.
+    pub pool: HttpPoolConfig,
+}
+
+/// Keep-alive settings for the provider HTTP client
+/// (`[llm] pool_idle_timeout`, `pool_max_idle_per_host`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct HttpPoolConfig {
+    /// Seconds an idle connection is kept open for reuse.  0 closes
+    /// connections as soon as a request finishes.
+    pub idle_timeout_secs: u64,
+    /// Idle connections kept per host.
+    pub max_idle_per_host: usize,
+}
+
+impl Default for HttpPoolConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: 90,
+            max_idle_per_host: 16,
+        }
+    }
+}
+
+/// Knobs for the fault-injecting provider selected by `default = "faulty"`.
+/// Only available in builds with the `llm-faulty` feature; meant for
+/// exercising failure paths in tests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FaultyConfig {
+    /// Percentage (0–100) of requests that fail with HTTP 503, spread evenly
+    /// so every 100 consecutive requests contain exactly this many failures.
+    pub fail_percent: u8,
+    /// Delay before every response, in milliseconds.
+    pub latency_ms: u64,
+    /// HTTP statuses for the first requests, in order (2xx succeeds).
+    /// `fail_percent` applies once the sequence is used up.
+    pub statuses: Vec<u16>,
+}
+
+/// Pattern used by `strip_reasoning = true`.
+pub const REASONING_STRIP_PATTERN: &str = "<think>...</think>";
+
+/// An `open...close` region to strip from completions, e.g. `<think>...</think>`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StripPattern {
+    pub open: String,
+    pub close: String,
+}
+
+impl StripPattern {
+    /// Parse `"OPEN...CLOSE"`.  `None` when there is no `...` separator or
+    /// either delimiter is empty.
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let (open, close) = pattern.split_once("...")?;
+        if open.is_empty() || close.is_empty() {
+            return None;
+        }
+        Some(Self {
+            open: open.to_string(),
+            close: close.to_string(),
+        })
+    }
+}
+
+/// Default endpoint for `embedding_provider = "openai"`.
+pub const DEFAULT_OPENAI_EMBEDDINGS_URL: &str = "https://api.openai.com/v1/embeddings";
+/// Default model for `embedding_provider = "openai"`.
+pub const DEFAULT_OPENAI_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
+/// Which wire adapter serves `llm/embed`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingProviderKind {
+    /// OpenAI `/v1/embeddings`, authenticated with `OPENAI_API_KEY`.
+    OpenAi,
+    /// Local embedding server (e.g. a sentence-transformers HTTP wrapper).
+    Local,
+}
+
+/// Embedding backend configuration (`[llm] embedding_*`).
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingConfig {
+    pub provider: EmbeddingProviderKind,
+    /// Full URL requests are POSTed to.
+    pub base_url: String,
+    pub model: String,
 }
 
 // ── Agents ───────────────────────────────────────────────────────────────────
 
 /// Optional query defaults for the `news` agent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NewsAgentQueryConfig {
     pub label: Option<String>,
     pub n_last: Option<usize>,
@@ -171,7 +766,7 @@ pub struct NewsAgentQueryConfig {
 }
 
 /// Optional query defaults for the `gdelt_news` agent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GdeltAgentQueryConfig {
     /// How many minutes back to include (default 60).
     pub lookback_minutes: Option<u32>,
@@ -188,7 +783,7 @@ pub struct GdeltAgentQueryConfig {
 }
 
 /// Tuning parameters for the docs-agent KG pipeline.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DocsKgConfig {
     pub min_entity_mentions: usize,
     pub bfs_max_depth: usize,
@@ -212,7 +807,7 @@ impl Default for DocsKgConfig {
 }
 
 /// Configuration for the docs agent.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DocsAgentConfig {
     /// Directory containing the documentation tree to import into memory.
     pub docsdir: Option<String>,
@@ -222,10 +817,13 @@ pub struct DocsAgentConfig {
     pub use_kg: bool,
     /// Tuning parameters for the KG pipeline.
     pub kg: DocsKgConfig,
+    /// Embeddings kept in the agent's embedding cache, least recently used
+    /// dropped first; `0` disables the cache.
+    pub embedding_cache_entries: usize,
 }
 
 /// Configuration for the `agentic-chat` agent plugin.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AgenticChatConfig {
     /// When `true`, the instruction pass is routed through `llm/instruct`
     /// (uses the instruction LLM if configured, falls back to the main LLM).
@@ -234,7 +832,7 @@ pub struct AgenticChatConfig {
 }
 
 /// Configuration for the `webbuilder` agent plugin.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WebBuilderAgentConfig {
     /// Maximum LLM-tool iteration cycles before the agent gives up (default: 10).
     pub max_iterations: usize,
@@ -256,7 +854,7 @@ impl Default for WebBuilderAgentConfig {
 }
 
 /// Configuration for the `homebuilder` agent plugin.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HomebuildAgentConfig {
     /// Maximum LLM-tool iteration cycles before the agent gives up (default: 10).
     /// Kept for compat; unused by static init.
@@ -281,7 +879,7 @@ impl Default for HomebuildAgentConfig {
 }
 
 /// Configuration for the `runtime_cmd` agent plugin.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RuntimeCmdAgentConfig {
     /// Runtime environment name (default: `"bash"`).
     pub runtime: String,
@@ -301,6 +899,65 @@ impl Default for RuntimeCmdAgentConfig {
     }
 }
 
+/// Case transform applied by the `echo` agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EchoCase {
+    /// Leave the content as sent.
+    #[default]
+    Verbatim,
+    Upper,
+    Lower,
+}
+
+/// Configuration for the `echo` agent (`[agents.echo]`).
+///
+/// The defaults reflect content back unchanged and immediately.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EchoAgentConfig {
+    /// Text prepended to every reply.
+    pub prefix: String,
+    /// Text appended to every reply.
+    pub suffix: String,
+    /// Case transform applied to the content before prefix/suffix.
+    pub case: EchoCase,
+    /// Artificial delay before replying, in milliseconds.  Useful for
+    /// exercising timeouts and cancellation against a slow agent.
+    pub delay_ms: u64,
+}
+
+/// Output shape requested from the `summarize` agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryStyle {
+    /// A short bulleted list of key points.
+    #[default]
+    Bullets,
+    /// One or two plain paragraphs.
+    Prose,
+}
+
+/// Default word budget for the `summarize` agent.
+pub const DEFAULT_SUMMARY_MAX_WORDS: usize = 150;
+
+/// Configuration for the `summarize` agent (`[agents.summarize]`).
+#[derive(Debug, Clone, Serialize)]
+pub struct SummarizeAgentConfig {
+    /// Bullets or prose.
+    pub style: SummaryStyle,
+    /// Approximate upper bound on summary length, in words.
+    pub max_words: usize,
+}
+
+impl Default for SummarizeAgentConfig {
+    fn default() -> Self {
+        Self {
+            style: SummaryStyle::default(),
+            max_words: DEFAULT_SUMMARY_MAX_WORDS,
+        }
+    }
+}
+
 /// Agents subsystem configuration.
 ///
 /// # v0.6 extension points (PR2 — StaticAgent config)
@@ -322,7 +979,7 @@ impl Default for RuntimeCmdAgentConfig {
 //            skills, prompt_files fields.
 // TODO(PR2): add validation in load.rs that rejects Workflow/Background classes
 //            for static agents in this phase.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AgentsConfig {
     /// Agent that handles messages with no explicit routing.
     pub default_agent: String,
@@ -343,6 +1000,10 @@ pub struct AgentsConfig {
     pub agent_docs: HashMap<String, DocsAgentConfig>,
     /// Optional configuration for the `agentic-chat` agent.
     pub agentic_chat: Option<AgenticChatConfig>,
+    /// Optional configuration for the `echo` agent.
+    pub echo: Option<EchoAgentConfig>,
+    /// Optional configuration for the `summarize` agent.
+    pub summarize: Option<SummarizeAgentConfig>,
     /// Optional configuration for the `runtime_cmd` agent.
     pub runtime_cmd: Option<RuntimeCmdAgentConfig>,
     /// Optional configuration for the `webbuilder` agent.
@@ -359,6 +1020,14 @@ pub struct AgentsConfig {
     /// (`instruct_prompt`, `instruction_response`, `tool_calls_json`, etc.)
     /// under `debug:turn:{n}:*` KV keys.  Off by default.
     pub debug_logging: bool,
+    /// Notify `comms/agent_step` at each phase of an agentic turn
+    /// (planning, each tool call, writing the reply).  Off by default.
+    pub emit_steps: bool,
+    /// Longest chain of nested handoffs and tool calls in one conversation;
+    /// the next hop fails with `-32006` (at least 1).
+    pub max_depth: usize,
+    /// What chat agents do with links in model replies.
+    pub link_policy: LinkPolicy,
     /// Optional explicit session ID for the `uniweb` shared-session agent.
     /// If `None` or empty, a deterministic ID is derived automatically.
     pub uniweb_session_id: Option<String>,
@@ -368,6 +1037,62 @@ pub struct AgentsConfig {
     /// Source-agent → aggregator-agent mapping (e.g. "newsroom" → "news_aggregator").
     /// Used by source agents to dispatch article URLs to an aggregator for KG processing.
     pub agent_aggregation_targets: HashMap<String, String>,
+    /// Refuse to start when `default_agent` is not loaded, instead of
+    /// falling back to `echo` with a warning.
+    pub strict_default: bool,
+    /// When the build has no `plugin-*` agents, register a no-op agent
+    /// instead of refusing to start.
+    pub noop_fallback: bool,
+    /// Per-agent minimum interval between invocations on the same channel,
+    /// in milliseconds (from `[agents.<id>] cooldown_ms`).
+    pub agent_cooldowns: HashMap<String, u64>,
+    /// Per-agent cap on the recent user/assistant turns put in the prompt
+    /// as history (from `[agents.<id>] context_turns`).
+    pub agent_context_turns: HashMap<String, usize>,
+    /// Per-agent working memory for new sessions (from `[agents.<id>]
+    /// seed_memory`; a table of facts becomes one `- key: value` line each).
+    pub agent_seed_memory: HashMap<String, String>,
+    /// Per-agent cap on kept disk-backed sessions; the oldest are pruned
+    /// as new ones are created (from `[agents.<id>] max_sessions`).
+    pub agent_max_sessions: HashMap<String, usize>,
+    /// Notify `manage/spend/alert` once a session's cumulative cost reaches
+    /// this many USD.  `None` disables the alert.
+    pub spend_alert_usd: Option<f64>,
+    /// Output tokens a session may use per UTC day before chat agents
+    /// refuse further turns until the date rolls over.  `None` disables it.
+    pub daily_output_token_budget: Option<u64>,
+    /// Reply chat agents send for empty or whitespace-only input instead of
+    /// calling the LLM.
+    pub empty_input_reply: String,
+    /// Agents that pass empty input through to the LLM
+    /// (`[agents.<id>] allow_empty_input = true`).
+    pub allow_empty_input: HashSet<String>,
+    /// Reply chat agents send when the LLM returns empty or whitespace-only
+    /// content.
+    pub empty_reply: String,
+    /// Per-agent override of `empty_reply` (`[agents.<id>] empty_reply`).
+    pub agent_empty_replies: HashMap<String, String>,
+    /// After the first turn of a chat session, ask the instruction LLM for a
+    /// short title and store it on the session's index entry.  Off by default.
+    pub auto_title: bool,
+    /// Serve `agents/{id}/debug_prompt`, which returns the prompts an agent
+    /// would send (retrieved context included) without calling the LLM.
+    /// Off by default.
+    pub debug_prompt: bool,
+    /// Agents whose retrieved/tool context is written to the transcript
+    /// alongside the user turn and answer (`[agents.<id>] persist_context`).
+    pub persist_context: HashSet<String>,
+    /// Per-agent reply format for chat agents (`[agents.<id>] response_format`).
+    /// Agents not listed reply in plain text.
+    pub agent_response_formats: HashMap<String, ResponseFormat>,
+    /// Content blocklist applied to chat input and output
+    /// (`[agents.moderation]`).  `None` disables moderation.
+    pub moderation: Option<ModerationConfig>,
+    /// Template chat channels reply with when an agent request fails
+    /// (`[agents] error_message`); `{error}` expands to the error text.
+    pub error_message: String,
+    /// Bounds on tool results fed into prompts (`[agents.tool_output]`).
+    pub tool_output: ToolOutputLimits,
     // TODO(PR2): static_agents: Vec<StaticAgentConfig>,
 }
 
@@ -383,61 +1108,183 @@ impl Default for AgentsConfig {
             newsroom_query: None,
             agent_docs: HashMap::new(),
             agentic_chat: None,
+            echo: None,
+            summarize: None,
             runtime_cmd: None,
             webbuilder: None,
             homebuilder: None,
             agent_skills: HashMap::new(),
             debug_logging: false,
+            emit_steps: false,
+            max_depth: DEFAULT_AGENT_MAX_DEPTH,
+            link_policy: LinkPolicy::Allow,
             uniweb_session_id: None,
             uniweb_use_instruction_llm: false,
             agent_aggregation_targets: HashMap::new(),
+            strict_default: false,
+            noop_fallback: false,
+            agent_cooldowns: HashMap::new(),
+            agent_context_turns: HashMap::new(),
+            agent_seed_memory: HashMap::new(),
+            agent_max_sessions: HashMap::new(),
+            spend_alert_usd: None,
+            daily_output_token_budget: None,
+            empty_input_reply: DEFAULT_EMPTY_INPUT_REPLY.to_string(),
+            allow_empty_input: HashSet::new(),
+            empty_reply: DEFAULT_EMPTY_REPLY.to_string(),
+            auto_title: false,
+            debug_prompt: false,
+            agent_empty_replies: HashMap::new(),
+            persist_context: HashSet::new(),
+            agent_response_formats: HashMap::new(),
+            moderation: None,
+            error_message: DEFAULT_AGENT_ERROR_MESSAGE.to_string(),
+            tool_output: ToolOutputLimits::default(),
         }
     }
 }
 
+/// `[agents.tool_output]` — how much of a tool result agents pass to the
+/// LLM.  0 lifts a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ToolOutputLimits {
+    /// Longest string value kept; longer ones are cut.
+    pub max_chars: usize,
+    /// Most array items kept; the rest are dropped.
+    pub max_items: usize,
+}
+
+impl Default for ToolOutputLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: DEFAULT_TOOL_OUTPUT_MAX_CHARS,
+            max_items: DEFAULT_TOOL_OUTPUT_MAX_ITEMS,
+        }
+    }
+}
+
+/// `[agents.moderation]` — keyword/regex blocklist for chat agents.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModerationConfig {
+    /// Case-insensitive substrings that block a message.
+    pub blocked_terms: Vec<String>,
+    /// Regular expressions that block a message when they match.
+    pub blocked_patterns: Vec<String>,
+    /// Reply sent instead of blocked input or output.
+    pub refusal: String,
+}
+
 // ── Config (root) ────────────────────────────────────────────────────────────
 
 /// Fully-resolved supervisor configuration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Config {
     pub bot_name: String,
     /// Working directory for all persistent data (already expanded, no `~`).
     pub work_dir: PathBuf,
     /// Optional explicit identity directory (absolute path or relative to `work_dir`).
     pub identity_dir: Option<PathBuf>,
+    /// Memory namespace (`[supervisor] bot_namespace`): bots sharing an
+    /// identity get separate memory trees.  `None` uses the identity's own.
+    pub bot_namespace: Option<String>,
     pub log_level: String,
+    /// Start in maintenance mode (`[supervisor] maintenance`).
+    pub maintenance: bool,
+    /// Fixed reply to agent requests while in maintenance mode.
+    pub maintenance_message: String,
+    /// Run the startup self-test (`[supervisor] startup_self_test`).
+    pub startup_self_test: bool,
+    /// Abort startup when a critical self-test check fails.
+    pub startup_self_test_fatal: bool,
+    /// Report handler panics as subsystem health (`[supervisor] panic_hook`).
+    pub panic_hook: bool,
+    /// Share one reply among identical in-flight `llm/complete` and
+    /// read-only agent session requests (`[supervisor] coalesce_requests`).
+    pub coalesce_requests: bool,
+    /// Print a `READY` line to stdout once startup completes
+    /// (`[supervisor] ready_line`).
+    pub ready_line: bool,
+    /// Send `READY=1` to systemd once startup completes (`[supervisor] sd_notify`).
+    pub sd_notify: bool,
+    /// OTLP collector to export tracing spans to (`[supervisor] otel_endpoint`).
+    /// Needs the `otel` feature; `OTEL_EXPORTER_OTLP_ENDPOINT` overrides it.
+    pub otel_endpoint: Option<String>,
+    /// Records kept for `manage/logs` (`[supervisor] log_buffer_capacity`);
+    /// 0 disables the buffer.
+    pub log_buffer_capacity: usize,
+    /// Bind attempts for the management socket
+    /// (`[supervisor] socket_bind_attempts`, at least 1).
+    pub socket_bind_attempts: u32,
+    /// Backoff before the first bind retry, doubled after each failure
+    /// (`[supervisor] socket_bind_backoff_ms`).
+    pub socket_bind_backoff_ms: u64,
+    /// Let management socket clients `Subscribe` to live health changes
+    /// (`[supervisor] presence_events`).
+    pub presence_events: bool,
+    /// Open every management socket connection with a `Hello` frame
+    /// (protocol version, features, bot id) (`[supervisor] control_handshake`).
+    pub control_handshake: bool,
     pub comms: CommsConfig,
     pub agents: AgentsConfig,
     pub llm: LlmConfig,
     pub ui: UiConfig,
     pub tools: ToolsConfig,
     pub runtimes: RuntimesConfig,
+    pub health: HealthConfig,
+    pub cron: CronConfig,
     /// API key from `OPENAI_API_KEY` env var — never sourced from TOML.
+    #[serde(serialize_with = "redact_secret")]
     pub openai_api_key: Option<String>,
     /// Memory subsystem caps (from `[memory.basic_session]`).
     pub memory_kv_cap: Option<usize>,
     pub memory_transcript_cap: Option<usize>,
+    /// Cap on disk-backed sessions per session index (from `[memory] max_sessions`).
+    pub memory_max_sessions: Option<usize>,
+    /// Policy once `memory_max_sessions` is reached.
+    pub memory_session_overflow: SessionOverflow,
+    /// Transcript format for new sessions (from `[memory] transcript_format`).
+    pub memory_transcript_format: TranscriptFormat,
+    /// Archive sessions idle this many minutes (from `[memory]
+    /// session_idle_archive_minutes`).  `None` = never.
+    pub memory_session_idle_archive_minutes: Option<u64>,
+    /// Write an aggregate spend snapshot this often (from `[memory]
+    /// spend_snapshot_minutes`).  `None` = never.
+    pub memory_spend_snapshot_minutes: Option<u64>,
+    /// Gzip transcripts of archived sessions (from `[memory]
+    /// compress_archived_transcripts`).
+    pub memory_compress_archived_transcripts: bool,
+    /// Keep a session going in memory when its disk writes fail (from
+    /// `[memory] write_fallback`).
+    pub memory_write_fallback: bool,
 }
 
 impl Config {
     /// Returns `true` if the PTY channel should be loaded.
     pub fn comms_pty_should_load(&self) -> bool {
-        self.comms.pty.enabled
+        self.comms.channel_enabled("pty", self.comms.pty.enabled)
     }
 
     /// Returns `true` if the Telegram channel should be loaded.
     pub fn comms_telegram_should_load(&self) -> bool {
-        self.comms.telegram.enabled
+        self.comms
+            .channel_enabled("telegram", self.comms.telegram.enabled)
+    }
+
+    /// Returns `true` if the email channel should be loaded.
+    pub fn comms_email_should_load(&self) -> bool {
+        self.comms
+            .channel_enabled("email", self.comms.email.enabled)
     }
 
     /// Returns `true` if the HTTP channel should be loaded.
     pub fn comms_http_should_load(&self) -> bool {
-        self.comms.http.enabled
+        self.comms.channel_enabled("http", self.comms.http.enabled)
     }
 
     /// Returns `true` if the axum channel should be loaded.
     pub fn comms_axum_should_load(&self) -> bool {
-        self.comms.axum_channel.enabled
+        self.comms
+            .channel_enabled("axum_channel", self.comms.axum_channel.enabled)
     }
 
     /// Returns `true` if the svui UI backend should be loaded.
@@ -1,5 +1,8 @@
 //! Application-wide error types.
 
+use std::fmt;
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -7,6 +10,10 @@ pub enum AppError {
     #[error("config error: {0}")]
     Config(String),
 
+    /// TOML syntax error in one file of a config chain.
+    #[error("config error: {0}")]
+    ConfigParse(Box<ConfigParseError>),
+
     #[error("identity error: {0}")]
     Identity(String),
 
@@ -26,6 +33,43 @@ pub enum AppError {
     Runtime(String),
 }
 
+/// A config file that failed to parse, located as precisely as `toml` allows.
+#[derive(Debug)]
+pub struct ConfigParseError {
+    /// File that failed to parse.
+    pub path: PathBuf,
+    /// 1-based line and column of the error, when known.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// The offending source line.
+    pub snippet: Option<String>,
+    pub message: String,
+    /// Files that pulled `path` in via `[meta] base`, outermost first.
+    pub included_from: Vec<PathBuf>,
+}
+
+impl fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse error in {}", self.path.display())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, " at line {line}, column {column}")?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let (Some(line), Some(snippet)) = (self.line, &self.snippet) {
+            write!(f, "\n  {line} | {snippet}")?;
+        }
+        if !self.included_from.is_empty() {
+            let chain: Vec<String> = self
+                .included_from
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            write!(f, "\n  (base of {})", chain.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,6 +82,22 @@ mod tests {
         assert!(e.to_string().contains("missing field"));
     }
 
+    #[test]
+    fn config_parse_error_display() {
+        let e = AppError::ConfigParse(Box::new(ConfigParseError {
+            path: PathBuf::from("base.toml"),
+            line: Some(3),
+            column: Some(7),
+            snippet: Some("bind = = 1".into()),
+            message: "expected a value".into(),
+            included_from: vec![PathBuf::from("bot.toml")],
+        }));
+        let s = e.to_string();
+        assert!(s.contains("base.toml at line 3, column 7: expected a value"));
+        assert!(s.contains("3 | bind = = 1"));
+        assert!(s.contains("(base of bot.toml)"));
+    }
+
     #[test]
     fn identity_error_display() {
         let e = AppError::Identity("key not found".into());
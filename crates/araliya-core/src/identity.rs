@@ -101,9 +101,32 @@ pub fn setup(config: &Config) -> Result<Identity, AppError> {
     })
 }
 
-/// Scan `base_dir` for a directory starting with `{prefix}-`.
+/// `name` made safe as a single path component: ASCII letters, digits, `-`
+/// and `_` are kept, every other run of characters becomes one `-`, and
+/// leading/trailing dashes are dropped.  `fallback` is used when nothing is
+/// left (e.g. a name of only emoji).  Display names are kept as they are;
+/// only the directory name is slugged.
+pub fn slugify(name: &str, fallback: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Scan `base_dir` for a directory starting with `{slug}-`, where `slug` is
+/// [`slugify`]`(prefix)`.
 /// If found, load the keypair from it.
-/// If not, generate a new keypair, compute the `public_id`, create the directory `{base_dir}/{prefix}-{public_id}`,
+/// If not, generate a new keypair, compute the `public_id`, create the directory `{base_dir}/{slug}-{public_id}`,
 /// save the keypair, and return the `Identity`.
 pub fn setup_named_identity(base_dir: &Path, prefix: &str) -> Result<Identity, AppError> {
     if !base_dir.exists() {
@@ -114,7 +137,7 @@ pub fn setup_named_identity(base_dir: &Path, prefix: &str) -> Result<Identity, A
     let entries = fs::read_dir(base_dir)
         .map_err(|e| AppError::Identity(format!("cannot read base_dir: {e}")))?;
 
-    let prefix_dash = format!("{}-", prefix);
+    let prefix_dash = format!("{}-", slugify(prefix, "identity"));
     for entry in entries.flatten() {
         let name = entry.file_name();
         let name_str = name.to_string_lossy();
@@ -308,6 +331,27 @@ mod tests {
         assert_eq!(id1.identity_dir, id2.identity_dir);
     }
 
+    #[test]
+    fn slugify_makes_a_single_safe_path_component() {
+        assert_eq!(slugify("My Bot / v2", "x"), "My-Bot-v2");
+        assert_eq!(slugify("../../etc", "x"), "etc");
+        assert_eq!(slugify("araliya_bot-1", "x"), "araliya_bot-1");
+        assert_eq!(slugify("🌸 🌸", "fallback"), "fallback");
+    }
+
+    #[test]
+    fn named_identity_with_path_chars_stays_inside_base_dir() {
+        let tmp = TempDir::new().unwrap();
+        let id = setup_named_identity(tmp.path(), "ops/../team bot").unwrap();
+
+        assert_eq!(id.identity_dir.parent().unwrap(), tmp.path());
+        let name = id.identity_dir.file_name().unwrap().to_string_lossy();
+        assert_eq!(name, format!("ops-team-bot-{}", id.public_id));
+
+        let again = setup_named_identity(tmp.path(), "ops/../team bot").unwrap();
+        assert_eq!(again.identity_dir, id.identity_dir);
+    }
+
     #[test]
     fn setup_errors_when_multiple_identity_dirs_exist_without_explicit_config() {
         let tmp = TempDir::new().unwrap();
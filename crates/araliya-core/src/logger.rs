@@ -3,14 +3,33 @@
 //! Call [`init`] once at startup for a standard fmt subscriber, or use
 //! [`build_filter`] + [`build_writer`] to compose a custom layered
 //! subscriber (e.g. with an observability layer in the binary crate).
+//!
+//! With the `otel` feature, [`build_otel_layer`] adds span export to an OTLP
+//! collector (`[supervisor] otel_endpoint`); call [`shutdown_otel`] before
+//! exit to flush pending spans.
+//!
+//! A [`LogBuffer`] keeps the most recent records in memory (via
+//! [`LogBuffer::layer`]) so they can be served over the bus
+//! (`manage/logs`) without access to the log file.
 
+use std::collections::VecDeque;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 
+use tracing::field::{Field, Visit};
 use tracing::level_filters::LevelFilter;
+use tracing::{Event, Subscriber};
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
 use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 use crate::error::AppError;
+use crate::obs::{ObsEvent, ObsLevel};
+
+/// Default capacity of the in-memory log buffer (`[supervisor] log_buffer_capacity`).
+pub const DEFAULT_LOG_BUFFER_CAPACITY: usize = 1000;
 
 /// Build an [`EnvFilter`] from a level string and preference flag.
 ///
@@ -49,21 +68,220 @@ pub fn build_writer(log_file: Option<&Path>) -> Result<BoxMakeWriter, AppError>
     }
 }
 
-/// Initialise the global tracing subscriber with a standard fmt layer.
+/// Environment variable that overrides `[supervisor] otel_endpoint`.
+pub const OTEL_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// `service.name` reported on exported spans.
+#[cfg(feature = "otel")]
+const OTEL_SERVICE_NAME: &str = "araliya-bot";
+
+/// The OTLP endpoint to export to: `OTEL_EXPORTER_OTLP_ENDPOINT` when set
+/// and non-empty, otherwise `configured`.
+pub fn resolve_otel_endpoint(configured: &str) -> String {
+    std::env::var(OTEL_ENDPOINT_ENV)
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| configured.to_string())
+}
+
+/// Build a tracing layer that exports spans over OTLP/gRPC to `endpoint`
+/// (after [`resolve_otel_endpoint`]) and install its tracer provider as the
+/// global one.  Must be called inside a Tokio runtime: spans are batched on
+/// a background task.
+#[cfg(feature = "otel")]
+pub fn build_otel_layer<S>(
+    endpoint: &str,
+) -> Result<tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>, AppError>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let endpoint = resolve_otel_endpoint(endpoint);
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(&endpoint)
+        .build()
+        .map_err(|e| {
+            AppError::Logger(format!(
+                "failed to build OTLP exporter for '{endpoint}': {e}"
+            ))
+        })?;
+    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(opentelemetry_sdk::Resource::new([
+            opentelemetry::KeyValue::new("service.name", OTEL_SERVICE_NAME),
+        ]))
+        .build();
+    let tracer = provider.tracer(OTEL_SERVICE_NAME);
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Flush and shut down the global tracer provider installed by
+/// [`build_otel_layer`].  A no-op without the `otel` feature.
+pub fn shutdown_otel() {
+    #[cfg(feature = "otel")]
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Bounded in-memory ring of recent log records.
+///
+/// Cheap to clone; all clones share one buffer.  Records are stored as
+/// [`ObsEvent`]s so they serialize the same way as observability events.
+#[derive(Clone)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<ObsEvent>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    /// A buffer keeping at most `capacity` records (oldest evicted first).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// The tracing layer that feeds this buffer.
+    pub fn layer(&self) -> LogBufferLayer {
+        LogBufferLayer {
+            buffer: self.clone(),
+        }
+    }
+
+    /// The newest `limit` records at or above `min_level`, oldest first.
+    pub fn recent(&self, min_level: ObsLevel, limit: usize) -> Vec<ObsEvent> {
+        let records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<ObsEvent> = records
+            .iter()
+            .rev()
+            .filter(|r| r.level.is_at_least(min_level))
+            .take(limit)
+            .cloned()
+            .collect();
+        out.reverse();
+        out
+    }
+
+    fn push(&self, record: ObsEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap_or_else(|e| e.into_inner());
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+/// Tracing layer that records every event into a [`LogBuffer`].
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: tracing_subscriber::layer::Context<'_, S>) {
+        let meta = event.metadata();
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        self.buffer.push(ObsEvent {
+            level: ObsLevel::from_tracing(meta.level()),
+            target: meta.target().to_string(),
+            message: visitor.message,
+            fields: if visitor.fields.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::Value::Object(visitor.fields)
+            },
+            session_id: None,
+            request_id: None,
+            span_id: None,
+            ts_unix_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        });
+    }
+}
+
+/// Splits an event into its message and the remaining fields.
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl RecordVisitor {
+    fn insert(&mut self, field: &Field, value: serde_json::Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, value.into());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value.into());
+    }
+}
+
+/// Initialise the global tracing subscriber with a standard fmt layer, plus
+/// OTLP span export when `otel_endpoint` is set and the `otel` feature is on,
+/// and recording into `log_buffer` when given.
 ///
 /// For composing additional layers (e.g. an observability bridge), use
 /// [`build_filter`] + [`build_writer`] directly and call
 /// `tracing_subscriber::registry().with(filter).with(fmt).with(extra).try_init()`.
-pub fn init(level: &str, prefer_level: bool, log_file: Option<&Path>) -> Result<(), AppError> {
+pub fn init(
+    level: &str,
+    prefer_level: bool,
+    log_file: Option<&Path>,
+    otel_endpoint: Option<&str>,
+    log_buffer: Option<&LogBuffer>,
+) -> Result<(), AppError> {
     let filter = build_filter(level, prefer_level)?;
     let writer = build_writer(log_file)?;
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_writer(writer)
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(writer))
+        .with(log_buffer.map(LogBuffer::layer));
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(otel_endpoint.map(build_otel_layer).transpose()?);
+    subscriber
         .try_init()
         .map_err(|e| AppError::Logger(format!("failed to set subscriber: {e}")))?;
 
+    #[cfg(not(feature = "otel"))]
+    if let Some(endpoint) = otel_endpoint {
+        tracing::warn!(
+            endpoint,
+            "otel_endpoint is set but this binary was built without the `otel` feature — spans will not be exported"
+        );
+    }
+
     Ok(())
 }
 
@@ -99,7 +317,7 @@ mod tests {
     #[test]
     fn init_info_succeeds_or_already_init() {
         // May already be set by a prior test run in the same process — both outcomes are fine.
-        let result = init("info", false, None);
+        let result = init("info", false, None, None, None);
         match result {
             Ok(()) => {}
             Err(AppError::Logger(msg)) if msg.contains("set subscriber") => {}
@@ -124,4 +342,36 @@ mod tests {
         let w = build_writer(None);
         assert!(w.is_ok());
     }
+
+    #[test]
+    fn log_buffer_filters_by_level_and_limit() {
+        let buffer = LogBuffer::new(3);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("dropped by capacity");
+            tracing::info!(n = 1, "first");
+            tracing::debug!("second");
+            tracing::warn!("third");
+        });
+
+        let all = buffer.recent(ObsLevel::Trace, 10);
+        let messages: Vec<&str> = all.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(messages, ["first", "second", "third"]);
+        assert_eq!(all[0].fields["n"], 1);
+
+        let info: Vec<String> = buffer
+            .recent(ObsLevel::Info, 10)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(info, ["first", "third"]);
+        assert_eq!(buffer.recent(ObsLevel::Trace, 1)[0].message, "third");
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test]
+    async fn otel_layer_builds_with_dummy_endpoint() {
+        let layer = build_otel_layer::<tracing_subscriber::Registry>("http://127.0.0.1:4317");
+        assert!(layer.is_ok());
+    }
 }
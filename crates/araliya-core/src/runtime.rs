@@ -15,6 +15,14 @@
 //! Any component error cancels the shared [`CancellationToken`] so sibling
 //! components and the supervisor all shut down cleanly.
 //!
+//! # Liveness
+//!
+//! Given a [`HealthRegistry`], [`spawn_components`] keeps one entry per
+//! component (keyed by [`Component::id`]): healthy while its task runs,
+//! unhealthy once it exits.  Components that know more (last accepted
+//! connection, upstream connected) refine the entry through
+//! [`Component::attach_health`].
+//!
 //! # Intra-subsystem events
 //!
 //! Each subsystem owns an `mpsc` channel for component → manager signalling
@@ -22,13 +30,15 @@
 //! runtime because the event type is subsystem-specific; subsystems wire it
 //! up in their own `start()` function before calling [`spawn_components`].
 
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 
-use tokio::task::{JoinHandle, JoinSet};
+use tokio::task::{Id, JoinHandle, JoinSet};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error};
 
+use crate::bus::health::{HealthRegistry, HealthReporter};
 use crate::error::AppError;
 
 // ── Component ─────────────────────────────────────────────────────────────────
@@ -52,6 +62,12 @@ pub trait Component: Send + 'static {
     /// the Tokio thread pool. Capture the `CancellationToken` inside it to
     /// respect cooperative shutdown.
     fn run(self: Box<Self>, shutdown: CancellationToken) -> ComponentFuture;
+
+    /// Optional liveness hook, called before [`Component::run`] when
+    /// [`spawn_components`] is given a health registry.  Keep `reporter` to
+    /// report finer-grained state while running; the default ignores it and
+    /// the entry only tracks whether the task is alive.
+    fn attach_health(&mut self, _reporter: HealthReporter) {}
 }
 
 // ── SubsystemHandle ───────────────────────────────────────────────────────────
@@ -90,18 +106,31 @@ impl SubsystemHandle {
 ///   receive the cancellation signal and stop cooperatively.
 /// - The manager task then drains the remaining components and returns the
 ///   first error encountered.
+///
+/// With `health`, each component gets a reporter under its id, marked
+/// healthy (`"running"`) on spawn and unhealthy when its task exits.
 pub fn spawn_components(
     components: Vec<Box<dyn Component>>,
     shutdown: CancellationToken,
+    health: Option<HealthRegistry>,
 ) -> SubsystemHandle {
     let handle = tokio::spawn(async move {
         let mut set: JoinSet<Result<(), AppError>> = JoinSet::new();
+        let mut reporters: HashMap<Id, HealthReporter> = HashMap::new();
 
-        for component in components {
+        for mut component in components {
             let id = component.id().to_string();
+            let reporter = health.as_ref().map(|h| h.reporter(&id));
+            if let Some(reporter) = &reporter {
+                reporter.set_healthy_with("running", None).await;
+                component.attach_health(reporter.clone());
+            }
             let shutdown = shutdown.clone();
             debug!(component = %id, "spawning component");
-            set.spawn(component.run(shutdown));
+            let task = set.spawn(component.run(shutdown));
+            if let Some(reporter) = reporter {
+                reporters.insert(task.id(), reporter);
+            }
         }
 
         // If no components were registered, wait for the shutdown signal so
@@ -113,23 +142,27 @@ pub fn spawn_components(
 
         let mut first_err: Option<AppError> = None;
 
-        while let Some(res) = set.join_next().await {
+        while let Some(res) = set.join_next_with_id().await {
             match res {
                 // Component panicked.
                 Err(e) => {
                     error!("component panicked: {e}");
+                    report_exit(&mut reporters, e.id(), format!("panicked: {e}")).await;
                     shutdown.cancel();
                     first_err
                         .get_or_insert_with(|| AppError::Comms(format!("component panicked: {e}")));
                 }
                 // Component returned an error.
-                Ok(Err(e)) => {
+                Ok((id, Err(e))) => {
                     error!("component error: {e}");
+                    report_exit(&mut reporters, id, format!("exited: {e}")).await;
                     shutdown.cancel();
                     first_err.get_or_insert(e);
                 }
                 // Component exited cleanly.
-                Ok(Ok(())) => {}
+                Ok((id, Ok(()))) => {
+                    report_exit(&mut reporters, id, "exited".to_string()).await;
+                }
             }
         }
 
@@ -141,3 +174,10 @@ pub fn spawn_components(
 
     SubsystemHandle { inner: handle }
 }
+
+/// Mark the component behind task `id` as no longer running.
+async fn report_exit(reporters: &mut HashMap<Id, HealthReporter>, id: Id, message: String) {
+    if let Some(reporter) = reporters.remove(&id) {
+        reporter.set_unhealthy(message).await;
+    }
+}
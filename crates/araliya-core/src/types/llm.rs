@@ -13,6 +13,9 @@ use serde::{Deserialize, Serialize};
 /// Chunks arrive in two phases: first all `Thinking` deltas (reasoning content),
 /// then all `Content` deltas (final answer), then one `Done` with usage totals.
 /// Providers that do not support streaming emit exactly one `Content` + one `Done`.
+/// A stream that fails part-way still ends with a `Done`, carrying the error;
+/// the deltas already sent are the partial reply.  So does one stopped by
+/// `llm/stream/stop`, with `stopped` set.
 #[derive(Debug, Clone)]
 pub enum StreamChunk {
     /// A delta from the model's internal reasoning phase (`reasoning_content`).
@@ -23,6 +26,11 @@ pub enum StreamChunk {
     Done {
         usage: Option<LlmUsage>,
         timing: Option<LlmTiming>,
+        /// Why the stream stopped early; `None` when it completed.
+        error: Option<String>,
+        /// The caller stopped generation (`llm/stream/stop`) before the
+        /// model finished.
+        stopped: bool,
     },
 }
 
@@ -44,6 +52,7 @@ pub struct LlmTiming {
 /// Token counts returned by the provider for a single completion.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LlmUsage {
+    /// Prompt tokens, including any `cached_input_tokens`.
     pub input_tokens: u64,
     pub output_tokens: u64,
     /// Tokens that matched the provider-side prompt cache (billed at a lower rate).
@@ -53,6 +62,39 @@ pub struct LlmUsage {
     /// `reasoning_content` instead (Qwen3, DeepSeek-R1).
     #[serde(default)]
     pub reasoning_tokens: u64,
+    /// Configured model of the provider that ran the completion.  `None`
+    /// when the provider does not say, or for a sum over several models.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+impl LlmUsage {
+    /// Add `other`'s counts to these.  The model is kept only while every
+    /// completion added so far ran the same one.
+    pub fn add(&mut self, other: &LlmUsage) {
+        let first = self.input_tokens == 0 && self.output_tokens == 0 && self.model.is_none();
+        if first {
+            self.model = other.model.clone();
+        } else if self.model != other.model {
+            self.model = None;
+        }
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+    }
+}
+
+/// Shape the caller wants the completion in (`LlmRequest.response_format`,
+/// `[agents.<id>] response_format`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Free-form text.
+    #[default]
+    Text,
+    /// A single valid JSON value.
+    Json,
 }
 
 /// Per-model pricing rates (USD per 1 million tokens).
@@ -65,8 +107,10 @@ pub struct ModelRates {
 
 impl LlmUsage {
     /// Compute the cost in USD for this usage given the model's rates.
+    /// Cache hits are billed at the cached rate only, not also as fresh input.
     pub fn cost_usd(&self, rates: &ModelRates) -> f64 {
-        (self.input_tokens as f64 / 1_000_000.0) * rates.input_per_million_usd
+        let fresh_input = self.input_tokens.saturating_sub(self.cached_input_tokens);
+        (fresh_input as f64 / 1_000_000.0) * rates.input_per_million_usd
             + (self.output_tokens as f64 / 1_000_000.0) * rates.output_per_million_usd
             + (self.cached_input_tokens as f64 / 1_000_000.0) * rates.cached_input_per_million_usd
     }
@@ -89,6 +133,25 @@ mod tests {
         assert_eq!(usage.cost_usd(&rates), 0.0);
     }
 
+    #[test]
+    fn summed_usage_keeps_the_model_only_when_shared() {
+        let run = |model: &str| LlmUsage {
+            output_tokens: 10,
+            model: Some(model.to_string()),
+            ..Default::default()
+        };
+        let mut same = LlmUsage::default();
+        same.add(&run("a"));
+        same.add(&run("a"));
+        assert_eq!(same.model.as_deref(), Some("a"));
+        assert_eq!(same.output_tokens, 20);
+
+        let mut mixed = LlmUsage::default();
+        mixed.add(&run("a"));
+        mixed.add(&run("b"));
+        assert_eq!(mixed.model, None);
+    }
+
     #[test]
     fn cost_usd_normal() {
         let usage = LlmUsage {
@@ -96,6 +159,7 @@ mod tests {
             output_tokens: 500_000,
             cached_input_tokens: 0,
             reasoning_tokens: 0,
+            model: None,
         };
         let rates = ModelRates {
             input_per_million_usd: 1.10,
@@ -118,6 +182,7 @@ mod tests {
             output_tokens: 0,
             cached_input_tokens: 1_000_000,
             reasoning_tokens: 0,
+            model: None,
         };
         let rates = ModelRates {
             input_per_million_usd: 0.0,
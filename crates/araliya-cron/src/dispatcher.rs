@@ -26,6 +26,7 @@ use araliya_core::bus::{
     CronScheduleSpec, ERR_METHOD_NOT_FOUND,
 };
 use araliya_core::bus::{BusHandler, HealthReporter, SubsystemHealth};
+use araliya_core::config::{CronRetry, QuietHours};
 
 use crate::service::{CronCommand, CronService};
 
@@ -42,9 +43,17 @@ pub struct CronSubsystem {
 
 impl CronSubsystem {
     /// Create the cron subsystem. Spawns the background timer task immediately.
-    pub fn new(bus: BusHandle, shutdown: tokio_util::sync::CancellationToken) -> Self {
+    ///
+    /// `quiet_hours` gates schedules created with `quiet_hours: true`;
+    /// `retry` governs firings whose dispatch fails.
+    pub fn new(
+        bus: BusHandle,
+        shutdown: tokio_util::sync::CancellationToken,
+        quiet_hours: Option<QuietHours>,
+        retry: CronRetry,
+    ) -> Self {
         let (cmd_tx, cmd_rx) = mpsc::channel(64);
-        let svc = CronService::new(bus, cmd_rx, shutdown);
+        let svc = CronService::new(bus, cmd_rx, shutdown, quiet_hours, retry);
         tokio::spawn(svc.run());
         debug!("cron subsystem started");
         Self {
@@ -155,12 +164,14 @@ impl BusHandler for CronSubsystem {
 
         match method {
             "cron/schedule" => {
-                let (target_method, payload_json, spec) = match payload {
+                let (target_method, payload_json, spec, quiet_hours, await_reply) = match payload {
                     BusPayload::CronSchedule {
                         target_method,
                         payload_json,
                         spec,
-                    } => (target_method, payload_json, spec),
+                        quiet_hours,
+                        await_reply,
+                    } => (target_method, payload_json, spec, quiet_hours, await_reply),
                     _ => {
                         let _ = reply_tx.send(Err(BusError::new(
                             ERR_BAD_REQUEST,
@@ -187,6 +198,8 @@ impl BusHandler for CronSubsystem {
                         target_method,
                         payload_json,
                         spec,
+                        quiet_hours,
+                        await_reply,
                         reply: ack_tx,
                     };
                     if cmd_tx.send(cmd).await.is_err() {
@@ -1,6 +1,7 @@
 //! Cron subsystem — timer-based event scheduling.
 
 pub mod dispatcher;
+pub(crate) mod quiet_hours;
 pub(crate) mod service;
 
 pub use dispatcher::CronSubsystem;
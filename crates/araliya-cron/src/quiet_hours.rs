@@ -0,0 +1,191 @@
+//! Quiet-hours time math (`[cron] quiet_hours`).
+//!
+//! The window is a daily `[start, end)` range of wall-clock minutes in the
+//! configured time zone; a window whose start is after its end wraps past
+//! midnight.  With an IANA zone the check runs in local time, so the window
+//! follows daylight saving: on the night the clocks change it is an hour
+//! shorter or longer in real time.
+
+use araliya_core::config::{QuietHours, QuietHoursTz};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, TimeZone, Timelike};
+
+/// Seconds from `unix_secs` until `quiet` ends, or `None` when `unix_secs`
+/// is outside the window.
+pub(crate) fn remaining_secs(quiet: &QuietHours, unix_secs: u64) -> Option<u64> {
+    match quiet.tz {
+        QuietHoursTz::Offset(minutes) => remaining_in(
+            quiet,
+            &FixedOffset::east_opt(i32::from(minutes) * 60)?,
+            unix_secs,
+        ),
+        QuietHoursTz::Zone(tz) => remaining_in(quiet, &tz, unix_secs),
+    }
+}
+
+fn remaining_in<Tz: TimeZone>(quiet: &QuietHours, tz: &Tz, unix_secs: u64) -> Option<u64> {
+    let unix_secs = i64::try_from(unix_secs).ok()?;
+    let local = DateTime::from_timestamp(unix_secs, 0)?
+        .with_timezone(tz)
+        .naive_local();
+    let now = i64::from(local.num_seconds_from_midnight());
+    let start = i64::from(quiet.start_minute) * 60;
+    let end = i64::from(quiet.end_minute) * 60;
+    let inside = match start.cmp(&end) {
+        std::cmp::Ordering::Less => start <= now && now < end,
+        std::cmp::Ordering::Greater => now >= start || now < end,
+        std::cmp::Ordering::Equal => false,
+    };
+    if !inside {
+        return None;
+    }
+
+    // The window ends at the next local `end`: today if it is still ahead,
+    // else tomorrow (a window that wrapped past midnight).
+    let mut end_date = local.date();
+    if now >= end {
+        end_date = end_date.succ_opt()?;
+    }
+    let end_local = end_date.and_hms_opt(
+        u32::from(quiet.end_minute / 60),
+        u32::from(quiet.end_minute % 60),
+        0,
+    )?;
+    let end_at = resolve_local(tz, end_local)?;
+    Some(end_at.saturating_sub(unix_secs).max(0) as u64)
+}
+
+/// Unix seconds of local time `at` in `tz`.  A time the clocks skip (the
+/// spring-forward gap) resolves to the end of the gap; a repeated one to its
+/// first occurrence.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, at: NaiveDateTime) -> Option<i64> {
+    (0..=180).find_map(|minutes| {
+        tz.from_local_datetime(&(at + Duration::minutes(minutes)))
+            .earliest()
+            .map(|t| t.timestamp())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::config::QuietHoursPolicy;
+    use chrono::Utc;
+
+    fn minute(s: &str) -> u16 {
+        let (h, m) = s.split_once(':').unwrap();
+        h.parse::<u16>().unwrap() * 60 + m.parse::<u16>().unwrap()
+    }
+
+    fn window(start: &str, end: &str, offset_minutes: i16) -> QuietHours {
+        zoned(start, end, QuietHoursTz::Offset(offset_minutes))
+    }
+
+    fn zoned(start: &str, end: &str, tz: QuietHoursTz) -> QuietHours {
+        QuietHours {
+            start_minute: minute(start),
+            end_minute: minute(end),
+            tz,
+            policy: QuietHoursPolicy::Defer,
+        }
+    }
+
+    /// Unix seconds at `hh:mm` UTC on 1970-01-02.
+    fn at(hh: u64, mm: u64) -> u64 {
+        86_400 + hh * 3600 + mm * 60
+    }
+
+    /// Unix seconds at the given UTC date and time.
+    fn utc(y: i32, mo: u32, d: u32, hh: u32, mm: u32) -> u64 {
+        Utc.with_ymd_and_hms(y, mo, d, hh, mm, 0)
+            .unwrap()
+            .timestamp() as u64
+    }
+
+    #[test]
+    fn overnight_window_wraps_midnight() {
+        let quiet = window("22:00", "07:00", 0);
+        assert_eq!(remaining_secs(&quiet, at(23, 0)), Some(8 * 3600));
+        assert_eq!(remaining_secs(&quiet, at(3, 0)), Some(4 * 3600));
+        assert_eq!(remaining_secs(&quiet, at(7, 0)), None);
+        assert_eq!(remaining_secs(&quiet, at(12, 0)), None);
+        assert_eq!(remaining_secs(&quiet, at(22, 0)), Some(9 * 3600));
+    }
+
+    #[test]
+    fn daytime_window_and_empty_window() {
+        let quiet = window("12:00", "13:30", 0);
+        assert_eq!(remaining_secs(&quiet, at(12, 45)), Some(45 * 60));
+        assert_eq!(remaining_secs(&quiet, at(11, 59)), None);
+        assert_eq!(remaining_secs(&window("08:00", "08:00", 0), at(8, 0)), None);
+    }
+
+    #[test]
+    fn offset_shifts_the_window() {
+        // 22:00–07:00 at UTC+02:00 is 20:00–05:00 UTC.
+        let quiet = window("22:00", "07:00", 120);
+        assert_eq!(remaining_secs(&quiet, at(21, 0)), Some(8 * 3600));
+        assert_eq!(remaining_secs(&quiet, at(5, 0)), None);
+        // At UTC-05:00, 01:00 UTC is 20:00 local — outside.
+        assert_eq!(
+            remaining_secs(&window("22:00", "07:00", -300), at(1, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn zone_window_is_checked_in_local_time() {
+        let quiet = zoned(
+            "22:00",
+            "07:00",
+            QuietHoursTz::Zone(chrono_tz::America::New_York),
+        );
+        // 03:00 UTC on 1 July is 23:00 EDT (UTC-4): eight hours to go.
+        assert_eq!(
+            remaining_secs(&quiet, utc(2024, 7, 1, 3, 0)),
+            Some(8 * 3600)
+        );
+        // 03:00 UTC on 1 January is 22:00 EST (UTC-5): nine hours to go.
+        assert_eq!(
+            remaining_secs(&quiet, utc(2024, 1, 1, 3, 0)),
+            Some(9 * 3600)
+        );
+        // 12:00 UTC is morning in New York — outside.
+        assert_eq!(remaining_secs(&quiet, utc(2024, 7, 1, 12, 0)), None);
+    }
+
+    #[test]
+    fn zone_window_follows_daylight_saving_changes() {
+        let london = QuietHoursTz::Zone(chrono_tz::Europe::London);
+        let quiet = zoned("22:00", "07:00", london);
+
+        // Clocks go forward at 01:00 UTC on 31 March 2024: 23:00 GMT to
+        // 07:00 BST is seven hours, not eight.
+        assert_eq!(
+            remaining_secs(&quiet, utc(2024, 3, 30, 23, 0)),
+            Some(7 * 3600)
+        );
+        // After the change the window ends at 06:00 UTC.
+        assert_eq!(remaining_secs(&quiet, utc(2024, 3, 31, 5, 30)), Some(1800));
+        assert_eq!(remaining_secs(&quiet, utc(2024, 3, 31, 6, 0)), None);
+
+        // Clocks go back at 01:00 UTC on 27 October 2024: 23:00 BST to
+        // 07:00 GMT is nine hours.
+        assert_eq!(
+            remaining_secs(&quiet, utc(2024, 10, 26, 22, 0)),
+            Some(9 * 3600)
+        );
+        // The window starts at 22:00 local: 21:00 UTC in summer.
+        assert_eq!(
+            remaining_secs(&quiet, utc(2024, 7, 1, 21, 0)),
+            Some(9 * 3600)
+        );
+
+        // An end time the clocks skip (01:30 on 31 March) ends the window
+        // when the gap does, at 02:00 BST = 01:00 UTC.
+        let skipped = zoned("00:00", "01:30", london);
+        assert_eq!(
+            remaining_secs(&skipped, utc(2024, 3, 31, 0, 30)),
+            Some(1800)
+        );
+    }
+}
@@ -2,8 +2,21 @@
 //!
 //! Maintains a `BTreeMap<Instant, ScheduleEntry>` priority queue and sleeps
 //! until the next deadline via `tokio::time::sleep_until`.  Zero polling.
+//!
+//! Schedules created with `quiet_hours: true` are checked against
+//! `[cron] quiet_hours` when they come due: a firing inside the window is
+//! moved to its end (`defer`) or skipped (`drop`).
+//!
+//! A firing whose notification the bus refuses (full or shut down) is
+//! retried `[cron] retry_attempts` times, `retry_backoff_ms` apart and
+//! doubling, before it is logged and skipped.  Schedules created with
+//! `await_reply: true` are sent as requests instead, from a task of their
+//! own; an error reply, or none within [`REPLY_TIMEOUT`], fails the firing
+//! and is retried the same way.  Failures are counted per schedule and
+//! reported by `cron/list`.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::Instant;
@@ -12,6 +25,12 @@ use tracing::{debug, info, trace, warn};
 use uuid::Uuid;
 
 use araliya_core::bus::{BusHandle, BusPayload, CronEntryInfo, CronScheduleSpec};
+use araliya_core::config::{CronRetry, QuietHours, QuietHoursPolicy};
+
+use crate::quiet_hours;
+
+/// How long an `await_reply` firing waits for its target to answer.
+pub const REPLY_TIMEOUT: Duration = Duration::from_secs(120);
 
 // ── Commands ─────────────────────────────────────────────────────────────────
 
@@ -21,6 +40,8 @@ pub enum CronCommand {
         target_method: String,
         payload_json: String,
         spec: CronScheduleSpec,
+        quiet_hours: bool,
+        await_reply: bool,
         reply: oneshot::Sender<String>, // schedule_id
     },
     Cancel {
@@ -40,6 +61,34 @@ struct ScheduleEntry {
     target_method: String,
     payload_json: String,
     spec: CronScheduleSpec,
+    /// Observe `[cron] quiet_hours`.
+    quiet_hours: bool,
+    /// Already held until the end of a quiet window; the catch-up fires
+    /// without another check.
+    deferred: bool,
+    /// Dispatch as a request and check the reply.
+    await_reply: bool,
+    /// Failed dispatches so far, retries included.
+    failures: u64,
+    /// Set while a failed firing is being retried.
+    retry: Option<PendingRetry>,
+}
+
+/// A firing waiting for another dispatch attempt.
+#[derive(Debug, Clone, Copy)]
+struct PendingRetry {
+    /// Retries already made.
+    attempt: u32,
+    /// When the firing was originally due; interval schedules keep their
+    /// cadence from here.
+    due: Instant,
+}
+
+/// An `await_reply` firing whose reply has not come back yet.
+struct InFlight {
+    entry: ScheduleEntry,
+    due: Instant,
+    attempt: u32,
 }
 
 // ── Service ──────────────────────────────────────────────────────────────────
@@ -50,6 +99,8 @@ pub struct CronService {
     bus: BusHandle,
     cmd_rx: mpsc::Receiver<CronCommand>,
     shutdown: CancellationToken,
+    quiet_hours: Option<QuietHours>,
+    retry: CronRetry,
 }
 
 impl CronService {
@@ -57,11 +108,83 @@ impl CronService {
         bus: BusHandle,
         cmd_rx: mpsc::Receiver<CronCommand>,
         shutdown: CancellationToken,
+        quiet_hours: Option<QuietHours>,
+        retry: CronRetry,
     ) -> Self {
         Self {
             bus,
             cmd_rx,
             shutdown,
+            quiet_hours,
+            retry,
+        }
+    }
+
+    /// Policy and remaining window length when `entry` is due inside quiet
+    /// hours it observes.
+    fn quiet_window(&self, entry: &ScheduleEntry) -> Option<(QuietHoursPolicy, Duration)> {
+        let quiet = self
+            .quiet_hours
+            .as_ref()
+            .filter(|_| entry.quiet_hours && !entry.deferred && entry.retry.is_none())?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let secs = quiet_hours::remaining_secs(quiet, now)?;
+        Some((quiet.policy, Duration::from_secs(secs)))
+    }
+
+    /// Record how a firing went: a failure is retried while attempts
+    /// remain, then the entry is re-enqueued if it repeats.
+    fn settle(
+        &self,
+        mut entry: ScheduleEntry,
+        due: Instant,
+        attempt: u32,
+        outcome: Result<(), String>,
+        queue: &mut BTreeMap<Instant, ScheduleEntry>,
+        id_to_deadline: &mut HashMap<String, Instant>,
+    ) {
+        if let Err(e) = outcome {
+            entry.failures += 1;
+            if attempt < self.retry.attempts {
+                let backoff = Duration::from_millis(
+                    self.retry.backoff_ms.saturating_mul(1 << attempt.min(16)),
+                );
+                debug!(
+                    schedule_id = %entry.id,
+                    target = %entry.target_method,
+                    error = %e,
+                    attempt = attempt + 1,
+                    ?backoff,
+                    "cron: dispatch failed, retrying"
+                );
+                entry.retry = Some(PendingRetry {
+                    attempt: attempt + 1,
+                    due,
+                });
+                let id = entry.id.clone();
+                let next = insert_unique(queue, Instant::now() + backoff, entry);
+                id_to_deadline.insert(id, next);
+                return;
+            }
+            warn!(
+                schedule_id = %entry.id,
+                target = %entry.target_method,
+                error = %e,
+                retries = attempt,
+                "cron: failed to dispatch firing — skipping it"
+            );
+        }
+
+        // Re-enqueue if repeating.
+        if let CronScheduleSpec::Interval { every_secs } = &entry.spec {
+            let next = due + Duration::from_secs(*every_secs);
+            let id = entry.id.clone();
+            entry.deferred = false;
+            let next = insert_unique(queue, next, entry);
+            id_to_deadline.insert(id, next);
         }
     }
 
@@ -73,8 +196,13 @@ impl CronService {
         let mut queue: BTreeMap<Instant, ScheduleEntry> = BTreeMap::new();
 
         // Secondary index: schedule_id → deadline (for O(log n) cancel).
-        let mut id_to_deadline: std::collections::HashMap<String, Instant> =
-            std::collections::HashMap::new();
+        let mut id_to_deadline: HashMap<String, Instant> = HashMap::new();
+
+        // `await_reply` firings waiting on their target, and the channel
+        // their dispatch tasks report back on.
+        let mut in_flight: HashMap<String, InFlight> = HashMap::new();
+        let (outcome_tx, mut outcome_rx) =
+            mpsc::unbounded_channel::<(String, Result<(), String>)>();
 
         info!("cron service running");
 
@@ -94,7 +222,7 @@ impl CronService {
                 // ── Incoming command ─────────────────────────────────────
                 Some(cmd) = self.cmd_rx.recv() => {
                     match cmd {
-                        CronCommand::Schedule { target_method, payload_json, spec, reply } => {
+                        CronCommand::Schedule { target_method, payload_json, spec, quiet_hours, await_reply, reply } => {
                             let id = Uuid::new_v4().to_string();
                             let deadline = spec_to_instant(&spec);
                             let entry = ScheduleEntry {
@@ -102,6 +230,11 @@ impl CronService {
                                 target_method: target_method.clone(),
                                 payload_json,
                                 spec,
+                                quiet_hours,
+                                deferred: false,
+                                await_reply,
+                                failures: 0,
+                                retry: None,
                             };
                             let deadline = insert_unique(&mut queue, deadline, entry);
                             id_to_deadline.insert(id.clone(), deadline);
@@ -113,6 +246,10 @@ impl CronService {
                                 queue.remove(&deadline);
                                 debug!(%schedule_id, "cancelled");
                                 true
+                            } else if in_flight.remove(&schedule_id).is_some() {
+                                // The reply still comes back; it finds nothing to settle.
+                                debug!(%schedule_id, "cancelled while awaiting a reply");
+                                true
                             } else {
                                 debug!(%schedule_id, "cancel: not found");
                                 false
@@ -122,12 +259,17 @@ impl CronService {
                         CronCommand::List { reply } => {
                             let entries: Vec<CronEntryInfo> = queue
                                 .iter()
+                                .map(|(deadline, entry)| (*deadline, entry))
+                                .chain(in_flight.values().map(|f| (f.due, &f.entry)))
                                 .map(|(deadline, entry)| {
                                     CronEntryInfo {
                                         schedule_id: entry.id.clone(),
                                         target_method: entry.target_method.clone(),
                                         spec: entry.spec.clone(),
-                                        next_fire_unix_ms: instant_to_unix_ms(*deadline),
+                                        next_fire_unix_ms: instant_to_unix_ms(deadline),
+                                        quiet_hours: entry.quiet_hours,
+                                        failures: entry.failures,
+                                        await_reply: entry.await_reply,
                                     }
                                 })
                                 .collect();
@@ -137,6 +279,14 @@ impl CronService {
                     }
                 }
 
+                // ── A reply to an `await_reply` firing ───────────────────
+                Some((id, outcome)) = outcome_rx.recv() => {
+                    // Absent when the schedule was cancelled meanwhile.
+                    if let Some(InFlight { entry, due, attempt }) = in_flight.remove(&id) {
+                        self.settle(entry, due, attempt, outcome, &mut queue, &mut id_to_deadline);
+                    }
+                }
+
                 // ── Timer fires ──────────────────────────────────────────
                 _ = async {
                     match next_deadline {
@@ -145,9 +295,35 @@ impl CronService {
                     }
                 } => {
                     // Pop the front entry (earliest deadline).
-                    if let Some((deadline, entry)) = queue.pop_first() {
+                    if let Some((deadline, mut entry)) = queue.pop_first() {
                         id_to_deadline.remove(&entry.id);
 
+                        // Quiet hours: hold the firing until the window ends,
+                        // or skip it.
+                        if let Some((policy, wait)) = self.quiet_window(&entry) {
+                            let id = entry.id.clone();
+                            let next = match (policy, &entry.spec) {
+                                (QuietHoursPolicy::Defer, _) => Some(Instant::now() + wait),
+                                (QuietHoursPolicy::Drop, CronScheduleSpec::Interval { every_secs }) => {
+                                    Some(deadline + Duration::from_secs(*every_secs))
+                                }
+                                (QuietHoursPolicy::Drop, CronScheduleSpec::Once { .. }) => None,
+                            };
+                            debug!(
+                                schedule_id = %id,
+                                target = %entry.target_method,
+                                ?policy,
+                                ?next,
+                                "cron firing held by quiet hours"
+                            );
+                            if let Some(next) = next {
+                                entry.deferred = policy == QuietHoursPolicy::Defer;
+                                let next = insert_unique(&mut queue, next, entry);
+                                id_to_deadline.insert(id, next);
+                            }
+                            continue;
+                        }
+
                         // Deserialize the stored payload.
                         let payload = match serde_json::from_str::<BusPayload>(&entry.payload_json) {
                             Ok(p) => p,
@@ -168,23 +344,40 @@ impl CronService {
                             "cron firing"
                         );
 
-                        // Emit the event as a bus notification.
-                        if let Err(e) = self.bus.notify(&entry.target_method, payload) {
-                            warn!(
-                                schedule_id = %entry.id,
-                                target = %entry.target_method,
-                                error = %e,
-                                "cron: failed to emit notification"
-                            );
-                        }
+                        let retry = entry.retry.take();
+                        let due = retry.map_or(deadline, |r| r.due);
+                        let attempt = retry.map_or(0, |r| r.attempt);
 
-                        // Re-enqueue if repeating.
-                        if let CronScheduleSpec::Interval { every_secs } = &entry.spec {
-                            let next = deadline + std::time::Duration::from_secs(*every_secs);
+                        // Send it as a request and settle once the reply is in.
+                        if entry.await_reply {
+                            let bus = self.bus.clone();
+                            let tx = outcome_tx.clone();
                             let id = entry.id.clone();
-                            let next = insert_unique(&mut queue, next, entry);
-                            id_to_deadline.insert(id, next);
+                            let method = entry.target_method.clone();
+                            tokio::spawn(async move {
+                                let outcome = match tokio::time::timeout(
+                                    REPLY_TIMEOUT,
+                                    bus.request(method, payload),
+                                )
+                                .await
+                                {
+                                    Ok(Ok(Ok(_))) => Ok(()),
+                                    Ok(Ok(Err(e))) => Err(format!("{} (code {})", e.message, e.code)),
+                                    Ok(Err(e)) => Err(e.to_string()),
+                                    Err(_) => Err(format!("no reply within {REPLY_TIMEOUT:?}")),
+                                };
+                                let _ = tx.send((id, outcome));
+                            });
+                            in_flight.insert(entry.id.clone(), InFlight { entry, due, attempt });
+                            continue;
                         }
+
+                        // Emit the event as a bus notification.
+                        let outcome = self
+                            .bus
+                            .notify(&entry.target_method, payload)
+                            .map_err(|e| e.to_string());
+                        self.settle(entry, due, attempt, outcome, &mut queue, &mut id_to_deadline);
                     }
                 }
             }
@@ -250,6 +443,7 @@ fn instant_to_unix_ms(instant: Instant) -> u64 {
 mod tests {
     use super::*;
     use araliya_core::bus::SupervisorBus;
+    use araliya_core::config::QuietHoursTz;
     use tokio::time;
 
     /// Helper: spawn a CronService and return its command sender + a bus receiver
@@ -258,11 +452,28 @@ mod tests {
         mpsc::Sender<CronCommand>,
         CancellationToken,
         mpsc::Receiver<araliya_core::bus::BusMessage>,
+    ) {
+        spawn_test_cron_with(None)
+    }
+
+    /// As [`spawn_test_cron`], with `[cron] quiet_hours` set.
+    fn spawn_test_cron_with(
+        quiet_hours: Option<QuietHours>,
+    ) -> (
+        mpsc::Sender<CronCommand>,
+        CancellationToken,
+        mpsc::Receiver<araliya_core::bus::BusMessage>,
     ) {
         let bus = SupervisorBus::new(64);
         let (cmd_tx, cmd_rx) = mpsc::channel(64);
         let shutdown = CancellationToken::new();
-        let svc = CronService::new(bus.handle.clone(), cmd_rx, shutdown.clone());
+        let svc = CronService::new(
+            bus.handle.clone(),
+            cmd_rx,
+            shutdown.clone(),
+            quiet_hours,
+            CronRetry::default(),
+        );
         tokio::spawn(svc.run());
         // Return the bus receiver so tests can observe notifications.
         (cmd_tx, shutdown, bus.rx)
@@ -278,6 +489,8 @@ mod tests {
             target_method: "test/ping".into(),
             payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
             spec: CronScheduleSpec::Interval { every_secs: 3600 },
+            quiet_hours: false,
+            await_reply: false,
             reply: reply_tx,
         })
         .await
@@ -308,6 +521,8 @@ mod tests {
             target_method: "test/x".into(),
             payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
             spec: CronScheduleSpec::Interval { every_secs: 60 },
+            quiet_hours: false,
+            await_reply: false,
             reply: reply_tx,
         })
         .await
@@ -356,6 +571,8 @@ mod tests {
             target_method: "test/tick".into(),
             payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
             spec: CronScheduleSpec::Interval { every_secs: 1 },
+            quiet_hours: false,
+            await_reply: false,
             reply: reply_tx,
         })
         .await
@@ -393,6 +610,8 @@ mod tests {
             target_method: "test/once".into(),
             payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
             spec: CronScheduleSpec::Once { at_unix_ms: 0 },
+            quiet_hours: false,
+            await_reply: false,
             reply: reply_tx,
         })
         .await
@@ -423,4 +642,247 @@ mod tests {
 
         shutdown.cancel();
     }
+
+    /// A defer window over `[now + from, now + to)` minutes, UTC.
+    fn quiet_from_now(from: u16, to: u16) -> QuietHours {
+        let now_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let minute = ((now_secs / 60) % 1440) as u16;
+        QuietHours {
+            start_minute: (minute + from) % 1440,
+            end_minute: (minute + to) % 1440,
+            tz: QuietHoursTz::Offset(0),
+            policy: QuietHoursPolicy::Defer,
+        }
+    }
+
+    async fn schedule_now(tx: &mpsc::Sender<CronCommand>, method: &str, quiet_hours: bool) {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(CronCommand::Schedule {
+            target_method: method.into(),
+            payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
+            spec: CronScheduleSpec::Once { at_unix_ms: 0 },
+            quiet_hours,
+            await_reply: false,
+            reply: reply_tx,
+        })
+        .await
+        .unwrap();
+        reply_rx.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn firing_inside_quiet_hours_is_deferred() {
+        time::pause();
+
+        let (tx, shutdown, mut bus_rx) = spawn_test_cron_with(Some(quiet_from_now(0, 2)));
+        schedule_now(&tx, "test/quiet", true).await;
+        schedule_now(&tx, "test/loud", false).await;
+        time::advance(Duration::from_millis(50)).await;
+
+        // Only the schedule that did not opt in fires.
+        let msg = tokio::time::timeout(Duration::from_secs(1), bus_rx.recv())
+            .await
+            .expect("timeout")
+            .expect("bus closed");
+        match msg {
+            araliya_core::bus::BusMessage::Notification { method, .. } => {
+                assert_eq!(method, "test/loud");
+            }
+            _ => panic!("expected Notification"),
+        }
+        assert!(tokio::time::timeout(Duration::from_secs(1), bus_rx.recv())
+            .await
+            .is_err());
+
+        // The held firing waits for the end of the window (at least a minute).
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(CronCommand::List { reply: reply_tx })
+            .await
+            .unwrap();
+        let entries = reply_rx.await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].target_method, "test/quiet");
+        assert!(entries[0].quiet_hours);
+
+        // Past the window it fires.
+        time::advance(Duration::from_secs(180)).await;
+        let msg = tokio::time::timeout(Duration::from_secs(1), bus_rx.recv())
+            .await
+            .expect("timeout")
+            .expect("bus closed");
+        match msg {
+            araliya_core::bus::BusMessage::Notification { method, .. } => {
+                assert_eq!(method, "test/quiet");
+            }
+            _ => panic!("expected Notification"),
+        }
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn firing_outside_quiet_hours_fires_normally() {
+        time::pause();
+
+        let (tx, shutdown, mut bus_rx) = spawn_test_cron_with(Some(quiet_from_now(10, 20)));
+        schedule_now(&tx, "test/quiet", true).await;
+        time::advance(Duration::from_millis(50)).await;
+
+        let msg = tokio::time::timeout(Duration::from_secs(1), bus_rx.recv())
+            .await
+            .expect("timeout")
+            .expect("bus closed");
+        match msg {
+            araliya_core::bus::BusMessage::Notification { method, .. } => {
+                assert_eq!(method, "test/quiet");
+            }
+            _ => panic!("expected Notification"),
+        }
+
+        shutdown.cancel();
+    }
+
+    async fn list(tx: &mpsc::Sender<CronCommand>) -> Vec<CronEntryInfo> {
+        // Let a timer that just came due fire before the (biased) command.
+        time::sleep(Duration::from_millis(1)).await;
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(CronCommand::List { reply: reply_tx })
+            .await
+            .unwrap();
+        reply_rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn failed_dispatch_is_retried_until_delivered() {
+        time::pause();
+
+        // A one-slot bus, held full: every dispatch fails until it drains.
+        let bus = SupervisorBus::new(1);
+        let mut bus_rx = bus.rx;
+        bus.handle
+            .notify("test/blocker", BusPayload::Empty)
+            .unwrap();
+        let (tx, cmd_rx) = mpsc::channel(64);
+        let shutdown = CancellationToken::new();
+        let retry = CronRetry {
+            attempts: 3,
+            backoff_ms: 1_000,
+        };
+        let svc = CronService::new(bus.handle.clone(), cmd_rx, shutdown.clone(), None, retry);
+        tokio::spawn(svc.run());
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(CronCommand::Schedule {
+            target_method: "test/job".into(),
+            payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
+            spec: CronScheduleSpec::Interval { every_secs: 3600 },
+            quiet_hours: false,
+            await_reply: false,
+            reply: reply_tx,
+        })
+        .await
+        .unwrap();
+        reply_rx.await.unwrap();
+
+        // Due at 1h: first failure.  Retry 1s later: second failure.
+        time::advance(Duration::from_secs(3600)).await;
+        assert_eq!(list(&tx).await[0].failures, 1);
+        time::advance(Duration::from_millis(1_000)).await;
+        assert_eq!(list(&tx).await[0].failures, 2);
+
+        // The target recovers; the next retry (2s later) gets through.
+        match bus_rx.recv().await.unwrap() {
+            araliya_core::bus::BusMessage::Notification { method, .. } => {
+                assert_eq!(method, "test/blocker")
+            }
+            _ => panic!("expected Notification"),
+        }
+        time::advance(Duration::from_millis(2_000)).await;
+        let msg = tokio::time::timeout(Duration::from_secs(1), bus_rx.recv())
+            .await
+            .expect("timeout")
+            .expect("bus closed");
+        match msg {
+            araliya_core::bus::BusMessage::Notification { method, .. } => {
+                assert_eq!(method, "test/job");
+            }
+            _ => panic!("expected Notification"),
+        }
+
+        // The schedule keeps its hourly cadence and its failure count.
+        let entries = list(&tx).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].failures, 2);
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn error_replies_are_retried_when_awaiting_the_reply() {
+        time::pause();
+
+        let bus = SupervisorBus::new(64);
+        let mut bus_rx = bus.rx;
+        let (tx, cmd_rx) = mpsc::channel(64);
+        let shutdown = CancellationToken::new();
+        let retry = CronRetry {
+            attempts: 3,
+            backoff_ms: 1_000,
+        };
+        let svc = CronService::new(bus.handle.clone(), cmd_rx, shutdown.clone(), None, retry);
+        tokio::spawn(svc.run());
+
+        // The target fails twice, then succeeds.
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let seen = calls.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = bus_rx.recv().await {
+                if let araliya_core::bus::BusMessage::Request {
+                    method, reply_tx, ..
+                } = msg
+                {
+                    assert_eq!(method, "test/job");
+                    let n = seen.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    let reply = if n < 2 {
+                        Err(araliya_core::bus::BusError::new(-32000, "upstream busy"))
+                    } else {
+                        Ok(BusPayload::Empty)
+                    };
+                    let _ = reply_tx.send(reply);
+                }
+            }
+        });
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(CronCommand::Schedule {
+            target_method: "test/job".into(),
+            payload_json: serde_json::to_string(&BusPayload::Empty).unwrap(),
+            spec: CronScheduleSpec::Interval { every_secs: 3600 },
+            quiet_hours: false,
+            await_reply: true,
+            reply: reply_tx,
+        })
+        .await
+        .unwrap();
+        reply_rx.await.unwrap();
+
+        // Due at 1h: first error.  Retried 1s, then 2s later: delivered.
+        time::advance(Duration::from_secs(3600)).await;
+        assert_eq!(list(&tx).await[0].failures, 1);
+        time::advance(Duration::from_millis(1_000)).await;
+        assert_eq!(list(&tx).await[0].failures, 2);
+        time::advance(Duration::from_millis(2_000)).await;
+        let entries = list(&tx).await;
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // The schedule keeps its hourly cadence and its failure count.
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].failures, 2);
+        assert!(entries[0].await_reply);
+
+        shutdown.cancel();
+    }
 }
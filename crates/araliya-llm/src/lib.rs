@@ -10,7 +10,7 @@
 pub mod providers;
 
 // Re-export shared types from araliya-core so `use araliya_llm::*` provides everything.
-pub use araliya_core::types::llm::{LlmTiming, LlmUsage, ModelRates, StreamChunk};
+pub use araliya_core::types::llm::{LlmTiming, LlmUsage, ModelRates, ResponseFormat, StreamChunk};
 
 use thiserror::Error;
 
@@ -39,6 +39,9 @@ pub struct LlmResponse {
     /// Wall-clock latency for this completion.
     /// `None` for providers that do not measure timing (e.g. `DummyProvider`).
     pub timing: Option<LlmTiming>,
+    /// Why the model stopped (`"stop"`, `"length"`, `"content_filter"`, …).
+    /// `None` for providers that do not report it.
+    pub finish_reason: Option<String>,
 }
 
 // ── Provider enum ─────────────────────────────────────────────────────────────
@@ -58,6 +61,8 @@ pub enum LlmProvider {
     Dummy(providers::dummy::DummyProvider),
     ChatCompletions(providers::chat_completions::ChatCompletionsProvider),
     OpenAiResponses(providers::openai_responses::OpenAiResponsesProvider),
+    #[cfg(feature = "faulty")]
+    Faulty(providers::faulty::FaultyProvider),
 }
 
 impl LlmProvider {
@@ -77,6 +82,8 @@ impl LlmProvider {
             LlmProvider::OpenAiResponses(p) => {
                 p.complete(content, system, max_tokens_override).await
             }
+            #[cfg(feature = "faulty")]
+            LlmProvider::Faulty(p) => p.complete(content, system, max_tokens_override).await,
         }
     }
 
@@ -105,6 +112,11 @@ impl LlmProvider {
                 p.complete_stream(content, system, tx, max_tokens_override)
                     .await
             }
+            #[cfg(feature = "faulty")]
+            LlmProvider::Faulty(p) => {
+                p.complete_stream(content, system, tx, max_tokens_override)
+                    .await
+            }
         }
     }
 
@@ -118,6 +130,20 @@ impl LlmProvider {
             LlmProvider::Dummy(_) => Ok(()),
             LlmProvider::ChatCompletions(p) => p.ping().await,
             LlmProvider::OpenAiResponses(p) => p.ping().await,
+            #[cfg(feature = "faulty")]
+            LlmProvider::Faulty(p) => p.ping().await,
+        }
+    }
+
+    /// A copy of this provider that asks the endpoint for JSON replies, or
+    /// `None` when the provider has no native JSON mode.  Callers fall back
+    /// to instructing the model in the system prompt.
+    pub fn with_json_mode(&self) -> Option<LlmProvider> {
+        match self {
+            LlmProvider::ChatCompletions(p) => {
+                Some(LlmProvider::ChatCompletions(p.clone().with_json_mode()))
+            }
+            _ => None,
         }
     }
 }
@@ -178,6 +204,7 @@ mod tests {
             output_tokens: 500_000,
             cached_input_tokens: 0,
             reasoning_tokens: 0,
+            model: None,
         };
         let rates = ModelRates {
             input_per_million_usd: 1.10,
@@ -200,6 +227,7 @@ mod tests {
             output_tokens: 0,
             cached_input_tokens: 1_000_000,
             reasoning_tokens: 0,
+            model: None,
         };
         let rates = ModelRates {
             input_per_million_usd: 0.0,
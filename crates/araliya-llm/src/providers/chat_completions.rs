@@ -8,10 +8,11 @@
 use futures_util::StreamExt as _;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{debug, error, trace, warn};
 
+use super::parse_response;
 use crate::{LlmResponse, LlmTiming, LlmUsage, ProviderError, StreamChunk};
 
 // ── Public provider ───────────────────────────────────────────────────────────
@@ -19,40 +20,43 @@ use crate::{LlmResponse, LlmTiming, LlmUsage, ProviderError, StreamChunk};
 /// Adapter for any HTTP endpoint implementing `/v1/chat/completions`.
 ///
 /// Covers OpenAI, OpenAI-compatible local servers (Ollama, LM Studio…),
-/// and hosted alternatives. Constructed once at startup, then cheaply cloned
-/// because `reqwest::Client` is an `Arc` internally.
+/// and hosted alternatives. Constructed once at startup around the pooled
+/// client from [`super::http_client`], then cheaply cloned because
+/// `reqwest::Client` is an `Arc` internally.
 #[derive(Debug, Clone)]
 pub struct ChatCompletionsProvider {
     client: Client,
     api_base_url: String,
     model: String,
     temperature: f32,
-    #[allow(dead_code)]
+    /// Per-request timeout; the shared client itself has none.
     timeout_seconds: u64,
     api_key: Option<String>,
     /// Maximum output tokens sent in every request.  0 means no explicit limit.
     max_tokens: usize,
+    /// Ask the endpoint for a JSON object reply (`response_format`).
+    json_mode: bool,
+    /// Mark the system prompt with `cache_control` so providers with
+    /// explicit prompt caching (Anthropic via compatible gateways) reuse it.
+    prompt_caching: bool,
 }
 
 impl ChatCompletionsProvider {
     /// Build a provider from config values and an optional API key.
     ///
+    /// `client` is the shared, pooled client (see [`super::http_client`]).
     /// `api_key` is `None` for keyless local models. When present it is sent
     /// as `Authorization: Bearer <key>` on every request.
     pub fn new(
+        client: Client,
         api_base_url: String,
         model: String,
         temperature: f32,
         timeout_seconds: u64,
         api_key: Option<String>,
         max_tokens: usize,
-    ) -> Result<Self, ProviderError> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| ProviderError::Request(format!("failed to build HTTP client: {e}")))?;
-
-        Ok(Self {
+    ) -> Self {
+        Self {
             client,
             api_base_url,
             model,
@@ -60,6 +64,58 @@ impl ChatCompletionsProvider {
             timeout_seconds,
             api_key,
             max_tokens,
+            json_mode: false,
+            prompt_caching: false,
+        }
+    }
+
+    /// A copy of this provider that requests `{"type": "json_object"}` replies.
+    ///
+    /// The endpoint still expects the word "JSON" somewhere in the prompt;
+    /// callers are responsible for that instruction.
+    pub fn with_json_mode(mut self) -> Self {
+        self.json_mode = true;
+        self
+    }
+
+    /// A copy of this provider that marks the system prompt as a cacheable
+    /// prefix.  OpenAI caches long prefixes automatically and ignores the
+    /// marker; endpoints that need it bill repeated prefixes at the cached rate.
+    pub fn with_prompt_caching(mut self) -> Self {
+        self.prompt_caching = true;
+        self
+    }
+
+    /// The system message (if any) followed by the user message.  The system
+    /// prompt is the stable prefix across turns, so it carries the cache
+    /// marker when prompt caching is on.
+    fn messages(&self, content: &str, system: Option<&str>) -> Vec<Message> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system {
+            let content = if self.prompt_caching {
+                MessageContent::Parts(vec![ContentPart {
+                    kind: "text",
+                    text: sys.to_string(),
+                    cache_control: Some(CacheControl { kind: "ephemeral" }),
+                }])
+            } else {
+                MessageContent::Text(sys.to_string())
+            };
+            messages.push(Message {
+                role: "system".to_string(),
+                content,
+            });
+        }
+        messages.push(Message {
+            role: "user".to_string(),
+            content: MessageContent::Text(content.to_string()),
+        });
+        messages
+    }
+
+    fn response_format(&self) -> Option<WireResponseFormat> {
+        self.json_mode.then_some(WireResponseFormat {
+            kind: "json_object",
         })
     }
 
@@ -71,11 +127,10 @@ impl ChatCompletionsProvider {
     ///
     /// Uses a hard 5-second timeout regardless of the LLM timeout config.
     pub async fn ping(&self) -> Result<(), ProviderError> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(5))
-            .build()
-            .map_err(|e| ProviderError::Request(format!("failed to build ping client: {e}")))?;
-        let mut req = client.head(&self.api_base_url);
+        let mut req = self
+            .client
+            .head(&self.api_base_url)
+            .timeout(Duration::from_secs(5));
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
@@ -108,23 +163,14 @@ impl ChatCompletionsProvider {
             None
         });
 
-        let mut messages = Vec::new();
-        if let Some(sys) = system {
-            messages.push(Message {
-                role: "system".to_string(),
-                content: sys.to_string(),
-            });
-        }
-        messages.push(Message {
-            role: "user".to_string(),
-            content: content.to_string(),
-        });
+        let messages = self.messages(content, system);
 
         let payload = ChatCompletionRequest {
             model: self.model.clone(),
             messages,
             temperature,
             max_completion_tokens: effective_max_tokens.map(|m| m as u32),
+            response_format: self.response_format(),
         };
 
         debug!(
@@ -139,7 +185,11 @@ impl ChatCompletionsProvider {
             trace!(payload = %json, "full LLM request payload");
         }
 
-        let mut req = self.client.post(&self.api_base_url).json(&payload);
+        let mut req = self
+            .client
+            .post(&self.api_base_url)
+            .timeout(Duration::from_secs(self.timeout_seconds))
+            .json(&payload);
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
@@ -152,13 +202,12 @@ impl ChatCompletionsProvider {
 
         let response = check_status(response).await?;
 
-        let parsed = response
-            .json::<ChatCompletionResponse>()
-            .await
-            .map_err(|e| {
-                error!(error = %e, "failed to deserialize LLM response");
-                ProviderError::Request(format!("failed to parse response body: {e}"))
-            })?;
+        let body = response.text().await.map_err(|e| {
+            error!(error = %e, "failed to read LLM response body");
+            ProviderError::Request(format!("failed to read response body: {e}"))
+        })?;
+        let parsed = parse_response::<ChatCompletionResponse>(&body, &["choices"])
+            .inspect_err(|e| error!(error = %e, "failed to deserialize LLM response"))?;
 
         debug!(choices = parsed.choices.len(), "received LLM response");
         if tracing::enabled!(tracing::Level::TRACE) {
@@ -167,31 +216,30 @@ impl ChatCompletionsProvider {
             trace!(response = %json, "full LLM response payload");
         }
 
-        let first_choice = parsed.choices.into_iter().next();
+        let first_choice = parsed
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::Request("no choices in response".into()))?;
 
+        // Empty content is passed on (with the finish reason) rather than
+        // failed here; agents decide what to answer instead.
         let text = first_choice
-            .as_ref()
-            .and_then(|c| c.message.content.as_deref())
+            .message
+            .content
+            .as_deref()
             .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .ok_or_else(|| ProviderError::Request("empty or missing content in response".into()))?;
+            .unwrap_or_default();
 
         let thinking = first_choice
-            .and_then(|c| c.message.reasoning_content)
+            .message
+            .reasoning_content
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
 
         let usage = parsed.usage.map(|u| LlmUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-            cached_input_tokens: u
-                .prompt_tokens_details
-                .map(|d| d.cached_tokens)
-                .unwrap_or(0),
-            reasoning_tokens: u
-                .completion_tokens_details
-                .and_then(|d| d.reasoning_tokens)
-                .unwrap_or(0),
+            model: Some(self.model.clone()),
+            ..u.into()
         });
 
         Ok(LlmResponse {
@@ -202,6 +250,7 @@ impl ChatCompletionsProvider {
                 ttft_ms: None,
                 total_ms: req_start.elapsed().as_millis() as u64,
             }),
+            finish_reason: first_choice.finish_reason,
         })
     }
 
@@ -233,17 +282,7 @@ impl ChatCompletionsProvider {
             None
         });
 
-        let mut messages = Vec::new();
-        if let Some(sys) = system {
-            messages.push(Message {
-                role: "system".to_string(),
-                content: sys.to_string(),
-            });
-        }
-        messages.push(Message {
-            role: "user".to_string(),
-            content: content.to_string(),
-        });
+        let messages = self.messages(content, system);
 
         let payload = ChatCompletionStreamRequest {
             model: self.model.clone(),
@@ -254,11 +293,16 @@ impl ChatCompletionsProvider {
                 include_usage: true,
             }),
             max_completion_tokens: effective_max_tokens.map(|m| m as u32),
+            response_format: self.response_format(),
         };
 
         debug!(model = %payload.model, "sending streaming LLM request");
 
-        let mut req = self.client.post(&self.api_base_url).json(&payload);
+        let mut req = self
+            .client
+            .post(&self.api_base_url)
+            .timeout(Duration::from_secs(self.timeout_seconds))
+            .json(&payload);
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
@@ -314,6 +358,7 @@ impl ChatCompletionsProvider {
                             ["reasoning_tokens"]
                             .as_u64()
                             .unwrap_or(0),
+                        model: Some(self.model.clone()),
                     };
                     let _ = tx
                         .send(StreamChunk::Done {
@@ -322,6 +367,8 @@ impl ChatCompletionsProvider {
                                 ttft_ms,
                                 total_ms: req_start.elapsed().as_millis() as u64,
                             }),
+                            error: None,
+                            stopped: false,
                         })
                         .await;
                     return Ok(());
@@ -358,6 +405,8 @@ impl ChatCompletionsProvider {
                     ttft_ms,
                     total_ms: req_start.elapsed().as_millis() as u64,
                 }),
+                error: None,
+                stopped: false,
             })
             .await;
         Ok(())
@@ -369,7 +418,30 @@ impl ChatCompletionsProvider {
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// Plain text, or content parts when a part needs extra fields.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Serialize)]
+struct ContentPart {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    kind: &'static str,
 }
 
 #[derive(Debug, Serialize)]
@@ -380,6 +452,8 @@ struct ChatCompletionRequest {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<WireResponseFormat>,
 }
 
 #[derive(Debug, Serialize)]
@@ -393,6 +467,14 @@ struct ChatCompletionStreamRequest {
     stream_options: Option<StreamOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<WireResponseFormat>,
+}
+
+#[derive(Debug, Serialize)]
+struct WireResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
 }
 
 #[derive(Debug, Serialize)]
@@ -409,7 +491,9 @@ struct ChatCompletionResponse {
 
 #[derive(Debug, serde::Serialize, Deserialize)]
 struct UsageData {
+    #[serde(default)]
     prompt_tokens: u64,
+    #[serde(default)]
     completion_tokens: u64,
     #[serde(default)]
     prompt_tokens_details: Option<PromptTokensDetails>,
@@ -417,6 +501,26 @@ struct UsageData {
     completion_tokens_details: Option<CompletionTokensDetails>,
 }
 
+impl From<UsageData> for LlmUsage {
+    /// `prompt_tokens` includes prompt-cache hits, which are reported again
+    /// in `prompt_tokens_details.cached_tokens`.
+    fn from(u: UsageData) -> Self {
+        LlmUsage {
+            input_tokens: u.prompt_tokens,
+            output_tokens: u.completion_tokens,
+            cached_input_tokens: u
+                .prompt_tokens_details
+                .map(|d| d.cached_tokens)
+                .unwrap_or(0),
+            reasoning_tokens: u
+                .completion_tokens_details
+                .and_then(|d| d.reasoning_tokens)
+                .unwrap_or(0),
+            model: None,
+        }
+    }
+}
+
 #[derive(Debug, serde::Serialize, Deserialize)]
 struct PromptTokensDetails {
     #[serde(default)]
@@ -435,6 +539,8 @@ struct CompletionTokensDetails {
 #[derive(Debug, Serialize, Deserialize)]
 struct Choice {
     message: ChoiceMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -489,3 +595,171 @@ async fn check_status(response: reqwest::Response) -> Result<reqwest::Response,
     error!(%status, %message, "LLM request returned HTTP error");
     Err(ProviderError::Request(message))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> ChatCompletionsProvider {
+        ChatCompletionsProvider::new(
+            Client::new(),
+            "http://localhost:1/v1/chat/completions".into(),
+            "m".into(),
+            0.2,
+            5,
+            None,
+            0,
+        )
+    }
+
+    fn request(provider: &ChatCompletionsProvider) -> serde_json::Value {
+        serde_json::to_value(ChatCompletionRequest {
+            model: provider.model.clone(),
+            messages: provider.messages("hi", Some("You are terse.")),
+            temperature: None,
+            max_completion_tokens: None,
+            response_format: provider.response_format(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn json_mode_sets_response_format() {
+        let provider = provider();
+        assert!(request(&provider).get("response_format").is_none());
+        assert_eq!(
+            request(&provider.with_json_mode())["response_format"],
+            serde_json::json!({"type": "json_object"})
+        );
+    }
+
+    #[test]
+    fn prompt_caching_marks_only_the_system_prompt() {
+        let plain = request(&provider());
+        assert_eq!(plain["messages"][0]["content"], "You are terse.");
+
+        let cached = request(&provider().with_prompt_caching());
+        assert_eq!(
+            cached["messages"][0]["content"],
+            serde_json::json!([{
+                "type": "text",
+                "text": "You are terse.",
+                "cache_control": {"type": "ephemeral"}
+            }])
+        );
+        assert_eq!(cached["messages"][1]["content"], "hi");
+    }
+
+    #[test]
+    fn cache_hits_are_read_from_usage() {
+        let body = serde_json::json!({
+            "choices": [{"message": {"content": "ok"}}],
+            "usage": {
+                "prompt_tokens": 1200,
+                "completion_tokens": 10,
+                "prompt_tokens_details": {"cached_tokens": 1024}
+            }
+        });
+        let parsed: ChatCompletionResponse = serde_json::from_value(body).unwrap();
+        let usage = LlmUsage::from(parsed.usage.unwrap());
+        assert_eq!(usage.input_tokens, 1200);
+        assert_eq!(usage.cached_input_tokens, 1024);
+    }
+
+    #[test]
+    fn unexpected_response_shapes_name_what_arrived() {
+        let parse = |body: &str| {
+            parse_response::<ChatCompletionResponse>(body, &["choices"])
+                .unwrap_err()
+                .to_string()
+        };
+        // An Anthropic Messages reply from a misconfigured endpoint.
+        assert_eq!(
+            parse(r#"{"id":"msg_1","type":"message","content":[{"type":"text","text":"hi"}]}"#),
+            "provider request failed: unexpected response shape from provider \
+             (got keys: content, id, type): missing `choices`"
+        );
+        assert!(parse(r#"{"choices":{"message":{"content":"hi"}},"usage":null}"#)
+            .starts_with("provider request failed: unexpected response shape from provider (got keys: choices, usage): invalid type: map, expected a sequence"));
+        assert!(parse("<html>502 Bad Gateway</html>").starts_with(
+            "provider request failed: unexpected response shape from provider (not JSON): "
+        ));
+
+        // Extra keys and a sparse usage block are fine.
+        let parsed: ChatCompletionResponse = parse_response(
+            r#"{"object":"chat.completion","choices":[{"message":{"content":"ok"}}],"usage":{"total_tokens":3}}"#,
+            &["choices"],
+        )
+        .unwrap();
+        assert_eq!(parsed.choices.len(), 1);
+    }
+
+    /// Serves canned completions over keep-alive HTTP/1.1 and counts the
+    /// connections it accepts.
+    async fn keep_alive_server() -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/v1/chat/completions",
+            listener.local_addr().unwrap()
+        );
+        let accepted = std::sync::Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    loop {
+                        let mut content_length = 0;
+                        loop {
+                            let mut line = String::new();
+                            if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                                return;
+                            }
+                            let line = line.trim_end().to_ascii_lowercase();
+                            if line.is_empty() {
+                                break;
+                            }
+                            if let Some(n) = line.strip_prefix("content-length:") {
+                                content_length = n.trim().parse().unwrap();
+                            }
+                        }
+                        let mut body = vec![0; content_length];
+                        stream.read_exact(&mut body).await.unwrap();
+                        let reply = r#"{"choices":[{"message":{"content":"ok"}}]}"#;
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{reply}",
+                            reply.len()
+                        );
+                        stream
+                            .get_mut()
+                            .write_all(response.as_bytes())
+                            .await
+                            .unwrap();
+                    }
+                });
+            }
+        });
+        (url, accepted)
+    }
+
+    #[tokio::test]
+    async fn sequential_completions_reuse_one_pooled_connection() {
+        let (url, accepted) = keep_alive_server().await;
+        let client = super::super::http_client(&Default::default()).unwrap();
+        let chat = |model: &str| {
+            ChatCompletionsProvider::new(client.clone(), url.clone(), model.into(), 0.2, 5, None, 0)
+        };
+        // Two providers built from the same client share its pool.
+        let (fast, slow) = (chat("fast"), chat("slow"));
+
+        for provider in [&fast, &slow, &fast] {
+            let reply = provider.complete("hi", None, None).await.unwrap();
+            assert_eq!(reply.text, "ok");
+        }
+        assert_eq!(accepted.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
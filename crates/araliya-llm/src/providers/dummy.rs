@@ -20,6 +20,7 @@ impl DummyProvider {
             thinking: None,
             usage: None,
             timing: None,
+            finish_reason: None,
         })
     }
 
@@ -37,6 +38,8 @@ impl DummyProvider {
             .send(StreamChunk::Done {
                 usage: None,
                 timing: None,
+                error: None,
+                stopped: false,
             })
             .await;
         Ok(())
@@ -0,0 +1,301 @@
+//! Embedding providers — back the `llm/embed` bus method.
+//!
+//! Embeddings are configured separately from the chat providers
+//! (`[llm] embedding_provider`), so a RAG setup can pair a hosted chat model
+//! with a local embedding server or vice versa.
+//!
+//! - `openai` — OpenAI `/v1/embeddings`, authenticated with `OPENAI_API_KEY`.
+//! - `local`  — a local HTTP wrapper (e.g. sentence-transformers).  Receives
+//!   the same `{"model", "input"}` request; the response may be OpenAI-shaped,
+//!   `{"embeddings": [[...]]}`, or a bare `[[...]]` array.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use araliya_core::config::{EmbeddingConfig, EmbeddingProviderKind};
+
+use super::parse_response;
+use crate::ProviderError;
+
+/// Request timeout for embedding calls.
+const EMBED_TIMEOUT_SECS: u64 = 60;
+
+// ── Provider enum ─────────────────────────────────────────────────────────────
+
+/// All embedding backends, dispatched like [`crate::LlmProvider`].
+#[derive(Debug, Clone)]
+pub enum EmbeddingProvider {
+    OpenAi(OpenAiEmbeddingsProvider),
+    Local(LocalEmbeddingsProvider),
+}
+
+impl EmbeddingProvider {
+    /// Embed each input string; the result has one vector per input, in order.
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        match self {
+            EmbeddingProvider::OpenAi(p) => p.embed(inputs).await,
+            EmbeddingProvider::Local(p) => p.embed(inputs).await,
+        }
+    }
+
+    /// Model name sent with every request.
+    pub fn model(&self) -> &str {
+        match self {
+            EmbeddingProvider::OpenAi(p) => &p.http.model,
+            EmbeddingProvider::Local(p) => &p.http.model,
+        }
+    }
+}
+
+/// Construct the embedding provider described by `config`.
+///
+/// `api_key` (from `OPENAI_API_KEY`) is only used by the `openai` backend.
+pub fn build(
+    config: &EmbeddingConfig,
+    api_key: Option<String>,
+) -> Result<EmbeddingProvider, ProviderError> {
+    match config.provider {
+        EmbeddingProviderKind::OpenAi => Ok(EmbeddingProvider::OpenAi(
+            OpenAiEmbeddingsProvider::new(config.base_url.clone(), config.model.clone(), api_key)?,
+        )),
+        EmbeddingProviderKind::Local => Ok(EmbeddingProvider::Local(LocalEmbeddingsProvider::new(
+            config.base_url.clone(),
+            config.model.clone(),
+        )?)),
+    }
+}
+
+// ── Backends ──────────────────────────────────────────────────────────────────
+
+/// OpenAI `/v1/embeddings`.
+#[derive(Debug, Clone)]
+pub struct OpenAiEmbeddingsProvider {
+    http: HttpEmbeddings,
+    api_key: Option<String>,
+}
+
+impl OpenAiEmbeddingsProvider {
+    pub fn new(url: String, model: String, api_key: Option<String>) -> Result<Self, ProviderError> {
+        Ok(Self {
+            http: HttpEmbeddings::new(url, model)?,
+            api_key,
+        })
+    }
+
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let body = self.http.post(inputs, self.api_key.as_deref()).await?;
+        parse_response::<OpenAiEmbeddingResponse>(&body, &["data"]).map(|r| r.into_vectors())
+    }
+}
+
+/// Local embedding server (sentence-transformers wrapper, TEI, Ollama, …).
+#[derive(Debug, Clone)]
+pub struct LocalEmbeddingsProvider {
+    http: HttpEmbeddings,
+}
+
+impl LocalEmbeddingsProvider {
+    pub fn new(url: String, model: String) -> Result<Self, ProviderError> {
+        Ok(Self {
+            http: HttpEmbeddings::new(url, model)?,
+        })
+    }
+
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        let body = self.http.post(inputs, None).await?;
+        let vectors = match parse_response::<LocalEmbeddingResponse>(&body, &[])? {
+            LocalEmbeddingResponse::OpenAi(resp) => resp.into_vectors(),
+            LocalEmbeddingResponse::Embeddings { embeddings } => embeddings,
+            LocalEmbeddingResponse::Bare(vectors) => vectors,
+        };
+        if vectors.len() != inputs.len() {
+            return Err(ProviderError::Request(format!(
+                "embedding server returned {} vectors for {} inputs",
+                vectors.len(),
+                inputs.len()
+            )));
+        }
+        Ok(vectors)
+    }
+}
+
+// ── Shared HTTP plumbing ──────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+struct HttpEmbeddings {
+    client: Client,
+    url: String,
+    model: String,
+}
+
+impl HttpEmbeddings {
+    fn new(url: String, model: String) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(EMBED_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| ProviderError::Request(format!("failed to build HTTP client: {e}")))?;
+        Ok(Self { client, url, model })
+    }
+
+    /// POST `{"model", "input"}` and return the raw response body.
+    async fn post(
+        &self,
+        inputs: &[String],
+        api_key: Option<&str>,
+    ) -> Result<String, ProviderError> {
+        let payload = EmbeddingRequest {
+            model: &self.model,
+            input: inputs,
+        };
+        let mut req = self.client.post(&self.url).json(&payload);
+        if let Some(key) = api_key {
+            req = req.bearer_auth(key);
+        }
+        let response = req.send().await.map_err(|e| {
+            error!(url = %self.url, error = %e, "embedding request failed (transport)");
+            ProviderError::Request(e.to_string())
+        })?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ProviderError::Request(format!("failed to read response body: {e}")))?;
+        if !status.is_success() {
+            return Err(ProviderError::Request(format!("HTTP {status}: {body}")));
+        }
+        debug!(url = %self.url, inputs = inputs.len(), "received embedding response");
+        Ok(body)
+    }
+}
+
+// ── Wire types ────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+impl OpenAiEmbeddingResponse {
+    fn into_vectors(mut self) -> Vec<Vec<f32>> {
+        self.data.sort_by_key(|d| d.index);
+        self.data.into_iter().map(|d| d.embedding).collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LocalEmbeddingResponse {
+    OpenAi(OpenAiEmbeddingResponse),
+    Embeddings { embeddings: Vec<Vec<f32>> },
+    Bare(Vec<Vec<f32>>),
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc as std_mpsc;
+
+    /// Serve one HTTP request with `body` as JSON; returns the URL and a
+    /// receiver yielding the request body the server saw.
+    fn mock_server(body: &'static str) -> (String, std_mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/embed", listener.local_addr().unwrap());
+        let (seen_tx, seen_rx) = std_mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut req_body = vec![0; content_length];
+            reader.read_exact(&mut req_body).unwrap();
+            seen_tx.send(String::from_utf8(req_body).unwrap()).unwrap();
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+        (url, seen_rx)
+    }
+
+    fn local(url: String) -> EmbeddingProvider {
+        build(
+            &EmbeddingConfig {
+                provider: EmbeddingProviderKind::Local,
+                base_url: url,
+                model: "all-MiniLM-L6-v2".to_string(),
+            },
+            None,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_provider_returns_fixed_vectors() {
+        let (url, seen) = mock_server(r#"{"embeddings": [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]]}"#);
+        let provider = local(url);
+
+        let vectors = provider
+            .embed(&["hello".to_string(), "world".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(vectors, vec![vec![0.1, 0.2, 0.3], vec![0.4, 0.5, 0.6]]);
+
+        let req: serde_json::Value = serde_json::from_str(&seen.recv().unwrap()).unwrap();
+        assert_eq!(req["model"], "all-MiniLM-L6-v2");
+        assert_eq!(req["input"], serde_json::json!(["hello", "world"]));
+    }
+
+    #[tokio::test]
+    async fn local_provider_accepts_openai_shape_in_index_order() {
+        let (url, _seen) = mock_server(
+            r#"{"data": [{"index": 1, "embedding": [2.0]}, {"index": 0, "embedding": [1.0]}]}"#,
+        );
+        let vectors = local(url)
+            .embed(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(vectors, vec![vec![1.0], vec![2.0]]);
+    }
+
+    #[tokio::test]
+    async fn local_provider_rejects_vector_count_mismatch() {
+        let (url, _seen) = mock_server(r#"[[1.0, 2.0]]"#);
+        let err = local(url)
+            .embed(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1 vectors for 2 inputs"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Fault-injecting LLM provider — fails, stalls or returns chosen HTTP
+//! statuses on demand so failure paths can be tested without a flaky endpoint.
+//!
+//! Selected with `default = "faulty"` and tuned by `[llm.faulty]`
+//! ([`FaultyConfig`]).  Outcomes are deterministic: the `statuses` sequence
+//! is played first, then `fail_percent` failures are spread evenly over
+//! every 100 requests.  Successful replies echo the input like `DummyProvider`.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use araliya_core::config::FaultyConfig;
+
+use crate::{LlmResponse, ProviderError, StreamChunk};
+
+/// Status used for `fail_percent` failures.
+const INJECTED_FAILURE_STATUS: u16 = 503;
+
+#[derive(Debug, Clone)]
+pub struct FaultyProvider {
+    fail_percent: u64,
+    latency: Duration,
+    statuses: Arc<[u16]>,
+    /// Requests seen so far, shared by all clones.
+    calls: Arc<AtomicU64>,
+}
+
+impl FaultyProvider {
+    pub fn new(cfg: &FaultyConfig) -> Self {
+        Self {
+            fail_percent: u64::from(cfg.fail_percent.min(100)),
+            latency: Duration::from_millis(cfg.latency_ms),
+            statuses: cfg.statuses.clone().into(),
+            calls: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Outcome of the next request: `Err(status)` for an injected failure.
+    fn next_outcome(&self) -> Result<(), u16> {
+        let n = self.calls.fetch_add(1, Ordering::Relaxed);
+        if let Some(&status) = self.statuses.get(n as usize) {
+            return if (200..300).contains(&status) {
+                Ok(())
+            } else {
+                Err(status)
+            };
+        }
+        // Request k fails when it moves the running failure quota
+        // `k * fail_percent / 100` up by one.
+        let k = n - self.statuses.len() as u64;
+        if (k + 1) * self.fail_percent / 100 > k * self.fail_percent / 100 {
+            Err(INJECTED_FAILURE_STATUS)
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn inject(&self) -> Result<(), ProviderError> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        self.next_outcome()
+            .map_err(|status| ProviderError::Request(format!("HTTP {status}: injected fault")))
+    }
+
+    pub async fn complete(
+        &self,
+        content: &str,
+        _system: Option<&str>,
+        _max_tokens_override: Option<usize>,
+    ) -> Result<LlmResponse, ProviderError> {
+        self.inject().await?;
+        Ok(LlmResponse {
+            text: format!("[echo] {content}"),
+            thinking: None,
+            usage: None,
+            timing: None,
+            finish_reason: None,
+        })
+    }
+
+    pub async fn complete_stream(
+        &self,
+        content: &str,
+        _system: Option<&str>,
+        tx: mpsc::Sender<StreamChunk>,
+        _max_tokens_override: Option<usize>,
+    ) -> Result<(), ProviderError> {
+        self.inject().await?;
+        let _ = tx
+            .send(StreamChunk::Content(format!("[echo] {content}")))
+            .await;
+        let _ = tx
+            .send(StreamChunk::Done {
+                usage: None,
+                timing: None,
+                error: None,
+                stopped: false,
+            })
+            .await;
+        Ok(())
+    }
+
+    /// Unreachable only when every request is set to fail, so health checks
+    /// report a provider that can never answer.
+    pub async fn ping(&self) -> Result<(), ProviderError> {
+        if self.fail_percent >= 100 {
+            Err(ProviderError::Request(
+                "unreachable: injected fault".to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn faulty(fail_percent: u8, statuses: &[u16]) -> FaultyProvider {
+        FaultyProvider::new(&FaultyConfig {
+            fail_percent,
+            latency_ms: 0,
+            statuses: statuses.to_vec(),
+        })
+    }
+
+    #[tokio::test]
+    async fn full_failure_fails_every_request() {
+        let p = faulty(100, &[]);
+        for _ in 0..10 {
+            let err = p.complete("hi", None, None).await.unwrap_err();
+            assert!(err.to_string().contains("HTTP 503"), "{err}");
+        }
+        let (tx, _rx) = mpsc::channel(4);
+        assert!(p.complete_stream("hi", None, tx, None).await.is_err());
+        assert!(p.ping().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn statuses_are_played_in_order() {
+        let p = faulty(0, &[429, 500, 200]);
+        let results: Vec<_> = [0; 4].iter().map(|_| p.next_outcome()).collect();
+        assert_eq!(results, [Err(429), Err(500), Ok(()), Ok(())]);
+        assert!(p.ping().await.is_ok());
+    }
+
+    #[test]
+    fn fail_percent_is_spread_evenly() {
+        let p = faulty(25, &[]);
+        let failures = (0..100).filter(|_| p.next_outcome().is_err()).count();
+        assert_eq!(failures, 25);
+
+        // Clones share the request counter.
+        let clone = p.clone();
+        assert_eq!(clone.calls.load(Ordering::Relaxed), 100);
+        let failures = (0..100).filter(|_| clone.next_outcome().is_err()).count();
+        assert_eq!(failures, 25);
+    }
+
+    #[tokio::test]
+    async fn latency_is_injected() {
+        let p = FaultyProvider::new(&FaultyConfig {
+            latency_ms: 30,
+            ..FaultyConfig::default()
+        });
+        let started = std::time::Instant::now();
+        assert_eq!(
+            p.complete("hi", None, None).await.unwrap().text,
+            "[echo] hi"
+        );
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+}
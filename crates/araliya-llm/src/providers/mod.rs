@@ -2,12 +2,25 @@
 //!
 //! `build(config, api_key)` is the factory — called at startup.
 //! `api_type` in each provider config selects the wire adapter.
+//!
+//! Every HTTP provider shares one pooled `reqwest::Client` from
+//! [`http_client`], so consecutive completions reuse a kept-alive
+//! connection instead of paying a TCP + TLS handshake (often 50–150 ms to a
+//! hosted API) per request.
 
 pub mod chat_completions;
 pub mod dummy;
+pub mod embeddings;
+#[cfg(feature = "faulty")]
+pub mod faulty;
 pub mod openai_responses;
 
-use araliya_core::config::{ApiType, LlmConfig, ProviderConfig};
+use std::time::Duration;
+
+use araliya_core::config::{ApiType, FaultyConfig, HttpPoolConfig, LlmConfig, ProviderConfig};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use tracing::debug;
 
 use crate::{LlmProvider, ProviderError};
 
@@ -18,38 +31,114 @@ pub fn build(config: &LlmConfig, api_key: Option<String>) -> Result<LlmProvider,
     if config.default == "dummy" {
         return Ok(LlmProvider::Dummy(dummy::DummyProvider));
     }
+    if config.default == "faulty" {
+        return build_faulty(&config.faulty);
+    }
     let cfg = config
         .providers
         .get(&config.default)
         .ok_or_else(|| ProviderError::UnknownProvider(config.default.clone()))?;
-    build_from_provider(cfg, api_key)
+    build_from_provider(cfg, api_key, &http_client(&config.pool)?)
+}
+
+/// The HTTP client shared by every provider, with keep-alive pooling from
+/// `[llm] pool_idle_timeout` / `pool_max_idle_per_host`.
+///
+/// It has no overall timeout: each provider applies its own
+/// `timeout_seconds` per request.  Build it once and clone it — clones share
+/// the pool.
+pub fn http_client(pool: &HttpPoolConfig) -> Result<Client, ProviderError> {
+    Client::builder()
+        .pool_idle_timeout(Duration::from_secs(pool.idle_timeout_secs))
+        .pool_max_idle_per_host(pool.max_idle_per_host)
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()
+        .map_err(|e| ProviderError::Request(format!("failed to build HTTP client: {e}")))
+}
+
+/// Construct the fault-injecting provider selected by `default = "faulty"`.
+///
+/// Fails unless the crate is built with the `faulty` feature.
+pub fn build_faulty(cfg: &FaultyConfig) -> Result<LlmProvider, ProviderError> {
+    #[cfg(feature = "faulty")]
+    {
+        Ok(LlmProvider::Faulty(faulty::FaultyProvider::new(cfg)))
+    }
+    #[cfg(not(feature = "faulty"))]
+    {
+        let _ = cfg;
+        Err(ProviderError::UnknownProvider(
+            "faulty (build with the llm-faulty feature)".to_string(),
+        ))
+    }
+}
+
+/// Parse a provider response `body` as `T`, expecting a JSON object with
+/// every key in `required`.
+///
+/// Unknown keys are ignored, but a body of another shape (the provider
+/// changed its API, or a proxy answered instead) fails with "unexpected
+/// response shape from provider (got keys: …)" rather than a bare serde
+/// error.  The raw body is logged at DEBUG.
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    body: &str,
+    required: &[&str],
+) -> Result<T, ProviderError> {
+    let shape_error = |got: String, detail: String| {
+        debug!(body = %body, "unexpected provider response body");
+        ProviderError::Request(format!(
+            "unexpected response shape from provider ({got}): {detail}"
+        ))
+    };
+    let value: serde_json::Value = serde_json::from_str(body)
+        .map_err(|e| shape_error("not JSON".to_string(), e.to_string()))?;
+    let got = match &value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&str> = map.keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            format!("got keys: {}", keys.join(", "))
+        }
+        serde_json::Value::Array(_) => "got a JSON array".to_string(),
+        other => format!("got {other}"),
+    };
+    if let Some(missing) = required.iter().find(|k| value.get(**k).is_none()) {
+        return Err(shape_error(got, format!("missing `{missing}`")));
+    }
+    serde_json::from_value(value).map_err(|e| shape_error(got, e.to_string()))
 }
 
 /// Construct a `LlmProvider` directly from a `ProviderConfig`.
 ///
 /// Used for instruction-pass providers and any code that needs to build a
-/// provider without going through the full `LlmConfig`.
+/// provider without going through the full `LlmConfig`.  `client` is cloned
+/// into HTTP providers; pass the same one for every provider in a pool.
 pub fn build_from_provider(
     cfg: &ProviderConfig,
     api_key: Option<String>,
+    client: &Client,
 ) -> Result<LlmProvider, ProviderError> {
     match cfg.api_type {
         ApiType::Dummy => Ok(LlmProvider::Dummy(dummy::DummyProvider)),
         ApiType::ChatCompletions => {
             let effective_key = cfg.api_key.clone().or(api_key);
-            let p = chat_completions::ChatCompletionsProvider::new(
+            let mut p = chat_completions::ChatCompletionsProvider::new(
+                client.clone(),
                 cfg.api_base_url.clone(),
                 cfg.model.clone(),
                 cfg.temperature,
                 cfg.timeout_seconds,
                 effective_key,
                 cfg.max_tokens,
-            )?;
+            );
+            if cfg.prompt_caching {
+                p = p.with_prompt_caching();
+            }
             Ok(LlmProvider::ChatCompletions(p))
         }
         ApiType::OpenAiResponses => {
             let effective_key = cfg.api_key.clone().or(api_key);
             let p = openai_responses::OpenAiResponsesProvider::new(
+                client.clone(),
                 cfg.api_base_url.clone(),
                 cfg.model.clone(),
                 cfg.reasoning_effort
@@ -58,7 +147,7 @@ pub fn build_from_provider(
                 cfg.timeout_seconds,
                 effective_key,
                 cfg.max_tokens,
-            )?;
+            );
             Ok(LlmProvider::OpenAiResponses(p))
         }
     }
@@ -167,17 +167,24 @@ impl OpenAiCompatibleProvider {
             trace!(response = %json, "full LLM response payload");
         }
 
-        let first_choice = parsed.choices.into_iter().next();
-
+        let first_choice = parsed
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::Request("no choices in response".into()))?;
+
+        // Empty content is passed on (with the finish reason) rather than
+        // failed here; agents decide what to answer instead.
         let text = first_choice
-            .as_ref()
-            .and_then(|c| c.message.content.as_deref())
+            .message
+            .content
+            .as_deref()
             .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .ok_or_else(|| ProviderError::Request("empty or missing content in response".into()))?;
+            .unwrap_or_default();
 
         let thinking = first_choice
-            .and_then(|c| c.message.reasoning_content)
+            .message
+            .reasoning_content
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty());
 
@@ -192,6 +199,7 @@ impl OpenAiCompatibleProvider {
                 .completion_tokens_details
                 .and_then(|d| d.reasoning_tokens)
                 .unwrap_or(0),
+            model: Some(self.model.clone()),
         });
 
         Ok(LlmResponse {
@@ -202,6 +210,7 @@ impl OpenAiCompatibleProvider {
                 ttft_ms: None,
                 total_ms: req_start.elapsed().as_millis() as u64,
             }),
+            finish_reason: first_choice.finish_reason,
         })
     }
 
@@ -314,6 +323,7 @@ impl OpenAiCompatibleProvider {
                             ["reasoning_tokens"]
                             .as_u64()
                             .unwrap_or(0),
+                        model: Some(self.model.clone()),
                     };
                     let _ = tx
                         .send(StreamChunk::Done {
@@ -322,6 +332,8 @@ impl OpenAiCompatibleProvider {
                                 ttft_ms,
                                 total_ms: req_start.elapsed().as_millis() as u64,
                             }),
+                            error: None,
+                            stopped: false,
                         })
                         .await;
                     return Ok(());
@@ -358,6 +370,8 @@ impl OpenAiCompatibleProvider {
                     ttft_ms,
                     total_ms: req_start.elapsed().as_millis() as u64,
                 }),
+                error: None,
+                stopped: false,
             })
             .await;
         Ok(())
@@ -435,6 +449,8 @@ struct CompletionTokensDetails {
 #[derive(Debug, Serialize, Deserialize)]
 struct Choice {
     message: ChoiceMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,13 +9,14 @@
 //! response: { output: [{ content: [{ type: "output_text", text }] }], usage }
 //! ```
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tracing::{debug, error, warn};
 
+use super::parse_response;
 use crate::{LlmResponse, LlmTiming, LlmUsage, ProviderError, StreamChunk};
 
 // ── Provider struct ────────────────────────────────────────────────────────────
@@ -26,7 +27,7 @@ pub struct OpenAiResponsesProvider {
     api_base_url: String,
     model: String,
     reasoning_effort: String,
-    #[allow(dead_code)]
+    /// Per-request timeout; the shared client itself has none.
     timeout_seconds: u64,
     api_key: Option<String>,
     max_tokens: usize,
@@ -34,18 +35,15 @@ pub struct OpenAiResponsesProvider {
 
 impl OpenAiResponsesProvider {
     pub fn new(
+        client: Client,
         api_base_url: String,
         model: String,
         reasoning_effort: String,
         timeout_seconds: u64,
         api_key: Option<String>,
         max_tokens: usize,
-    ) -> Result<Self, ProviderError> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| ProviderError::Request(format!("failed to build HTTP client: {e}")))?;
-        Ok(Self {
+    ) -> Self {
+        Self {
             client,
             api_base_url,
             model,
@@ -53,7 +51,7 @@ impl OpenAiResponsesProvider {
             timeout_seconds,
             api_key,
             max_tokens,
-        })
+        }
     }
 
     pub async fn complete(
@@ -81,7 +79,11 @@ impl OpenAiResponsesProvider {
 
         debug!(model = %payload.model, "sending Responses API request");
 
-        let mut req = self.client.post(&self.api_base_url).json(&payload);
+        let mut req = self
+            .client
+            .post(&self.api_base_url)
+            .timeout(Duration::from_secs(self.timeout_seconds))
+            .json(&payload);
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
@@ -94,10 +96,12 @@ impl OpenAiResponsesProvider {
 
         let response = check_status(response).await?;
 
-        let parsed = response.json::<ResponsesResponse>().await.map_err(|e| {
-            error!(error = %e, "failed to deserialize Responses API response");
-            ProviderError::Request(format!("failed to parse response body: {e}"))
+        let body = response.text().await.map_err(|e| {
+            error!(error = %e, "failed to read Responses API response body");
+            ProviderError::Request(format!("failed to read response body: {e}"))
         })?;
+        let parsed = parse_response::<ResponsesResponse>(&body, &["output"])
+            .inspect_err(|e| error!(error = %e, "failed to deserialize Responses API response"))?;
 
         let text = extract_text(&parsed.output)
             .filter(|s| !s.is_empty())
@@ -113,6 +117,7 @@ impl OpenAiResponsesProvider {
                 .output_tokens_details
                 .and_then(|d| d.reasoning_tokens)
                 .unwrap_or(0),
+            model: Some(self.model.clone()),
         });
 
         Ok(LlmResponse {
@@ -123,6 +128,7 @@ impl OpenAiResponsesProvider {
                 ttft_ms: None,
                 total_ms: req_start.elapsed().as_millis() as u64,
             }),
+            finish_reason: None,
         })
     }
 
@@ -152,7 +158,11 @@ impl OpenAiResponsesProvider {
 
         debug!(model = %payload.model, "sending streaming Responses API request");
 
-        let mut req = self.client.post(&self.api_base_url).json(&payload);
+        let mut req = self
+            .client
+            .post(&self.api_base_url)
+            .timeout(Duration::from_secs(self.timeout_seconds))
+            .json(&payload);
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
@@ -206,6 +216,7 @@ impl OpenAiResponsesProvider {
                         reasoning_tokens: usage_val["output_tokens_details"]["reasoning_tokens"]
                             .as_u64()
                             .unwrap_or(0),
+                        model: Some(self.model.clone()),
                     };
                     let _ = tx
                         .send(StreamChunk::Done {
@@ -214,6 +225,8 @@ impl OpenAiResponsesProvider {
                                 ttft_ms,
                                 total_ms: req_start.elapsed().as_millis() as u64,
                             }),
+                            error: None,
+                            stopped: false,
                         })
                         .await;
                     return Ok(());
@@ -239,6 +252,8 @@ impl OpenAiResponsesProvider {
                     ttft_ms,
                     total_ms: req_start.elapsed().as_millis() as u64,
                 }),
+                error: None,
+                stopped: false,
             })
             .await;
         Ok(())
@@ -248,7 +263,7 @@ impl OpenAiResponsesProvider {
         let mut req = self
             .client
             .head(&self.api_base_url)
-            .timeout(std::time::Duration::from_secs(5));
+            .timeout(Duration::from_secs(5));
         if let Some(key) = &self.api_key {
             req = req.bearer_auth(key);
         }
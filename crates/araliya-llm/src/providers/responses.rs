@@ -116,6 +116,7 @@ impl ResponsesProvider {
                 .output_tokens_details
                 .and_then(|d| d.reasoning_tokens)
                 .unwrap_or(0),
+            model: Some(self.model.clone()),
         });
 
         Ok(LlmResponse {
@@ -126,6 +127,7 @@ impl ResponsesProvider {
                 ttft_ms: None,
                 total_ms: req_start.elapsed().as_millis() as u64,
             }),
+            finish_reason: None,
         })
     }
 
@@ -209,6 +211,7 @@ impl ResponsesProvider {
                         reasoning_tokens: usage_val["output_tokens_details"]["reasoning_tokens"]
                             .as_u64()
                             .unwrap_or(0),
+                        model: Some(self.model.clone()),
                     };
                     let _ = tx
                         .send(StreamChunk::Done {
@@ -217,6 +220,8 @@ impl ResponsesProvider {
                                 ttft_ms,
                                 total_ms: req_start.elapsed().as_millis() as u64,
                             }),
+                            error: None,
+                            stopped: false,
                         })
                         .await;
                     return Ok(());
@@ -239,6 +244,8 @@ impl ResponsesProvider {
                     ttft_ms,
                     total_ms: req_start.elapsed().as_millis() as u64,
                 }),
+                error: None,
+                stopped: false,
             })
             .await;
         Ok(())
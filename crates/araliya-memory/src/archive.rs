@@ -0,0 +1,366 @@
+//! Whole-tree backup of the memory root as a gzipped tar archive.
+//!
+//! [`export_tree`] streams every file under the memory root (the session
+//! index, sessions, agent identities and stores) into a `.tar.gz` one file
+//! at a time, so nothing is held in memory beyond a copy buffer.
+//! [`unpack_tree`] reverses it into an empty directory, refusing anything
+//! that is not a plain file or directory at a relative path inside the
+//! destination, and checks that the result looks like a memory root: a
+//! top-level `sessions.json` and `sessions/` directory, and every
+//! `sessions.json` in the tree a readable session index.
+//!
+//! Only the ustar subset the exporter writes is read back: regular files
+//! and directories, with paths up to 255 bytes.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use tracing::warn;
+
+use araliya_core::error::AppError;
+
+use crate::SessionIndex;
+
+const BLOCK: usize = 512;
+const INDEX_FILE: &str = "sessions.json";
+const SESSIONS_DIR: &str = "sessions";
+
+/// What an export wrote or an import restored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ArchiveSummary {
+    /// Regular files in the archive.
+    pub files: usize,
+    /// Total size of those files, uncompressed.
+    pub bytes: u64,
+}
+
+/// Write the tree under `root` to `out` as a gzipped tar archive, with
+/// paths relative to `root`.  Symlinks and other special files are skipped.
+pub fn export_tree<W: Write>(root: &Path, out: W) -> Result<ArchiveSummary, AppError> {
+    let mut tar = GzEncoder::new(out, Compression::default());
+    let mut summary = ArchiveSummary::default();
+    append_dir(&mut tar, root, Path::new(""), &mut summary)?;
+    // Two zero blocks end the archive.
+    tar.write_all(&[0u8; BLOCK * 2]).map_err(archive_io)?;
+    tar.finish().map_err(archive_io)?;
+    Ok(summary)
+}
+
+fn append_dir<W: Write>(
+    tar: &mut W,
+    root: &Path,
+    relative: &Path,
+    summary: &mut ArchiveSummary,
+) -> Result<(), AppError> {
+    let dir = root.join(relative);
+    let mut entries = fs::read_dir(&dir)
+        .map_err(|e| memory_error(&dir, e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| memory_error(&dir, e))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| memory_error(&path, e))?;
+        let metadata = entry.metadata().map_err(|e| memory_error(&path, e))?;
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        if file_type.is_dir() {
+            tar.write_all(&header(&path, b'5', 0, mtime)?)
+                .map_err(archive_io)?;
+            append_dir(tar, root, &path, summary)?;
+        } else if file_type.is_file() {
+            let full = root.join(&path);
+            let mut file = File::open(&full).map_err(|e| memory_error(&full, e))?;
+            // Size from the open file, so a concurrent append cannot make
+            // the entry longer than its header says.
+            let size = file.metadata().map_err(|e| memory_error(&full, e))?.len();
+            tar.write_all(&header(&path, b'0', size, mtime)?)
+                .map_err(archive_io)?;
+            let copied = io::copy(&mut (&mut file).take(size), tar).map_err(archive_io)?;
+            if copied < size {
+                return Err(AppError::Memory(format!(
+                    "{} shrank while being archived",
+                    full.display()
+                )));
+            }
+            tar.write_all(&[0u8; BLOCK][..padding(size)])
+                .map_err(archive_io)?;
+            summary.files += 1;
+            summary.bytes += size;
+        } else {
+            warn!(path = %path.display(), "memory export: skipping special file");
+        }
+    }
+    Ok(())
+}
+
+/// Extract the gzipped tar archive in `input` into `dest`, which must not
+/// exist yet, and validate it as a memory root.  On any error `dest` is
+/// removed again.
+pub fn unpack_tree<R: Read>(input: R, dest: &Path) -> Result<ArchiveSummary, AppError> {
+    fs::create_dir(dest).map_err(|e| memory_error(dest, e))?;
+    let result = unpack_into(GzDecoder::new(input), dest).and_then(|summary| {
+        validate_tree(dest)?;
+        Ok(summary)
+    });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(dest);
+    }
+    result
+}
+
+fn unpack_into<R: Read>(mut tar: R, dest: &Path) -> Result<ArchiveSummary, AppError> {
+    let mut summary = ArchiveSummary::default();
+    let mut block = [0u8; BLOCK];
+    loop {
+        tar.read_exact(&mut block).map_err(archive_io)?;
+        if block.iter().all(|&b| b == 0) {
+            return Ok(summary);
+        }
+        let entry = parse_header(&block)?;
+        let target = dest.join(&entry.path);
+        match entry.kind {
+            b'5' => fs::create_dir_all(&target).map_err(|e| memory_error(&target, e))?,
+            _ => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(|e| memory_error(parent, e))?;
+                }
+                let mut file = File::create(&target).map_err(|e| memory_error(&target, e))?;
+                let copied =
+                    io::copy(&mut (&mut tar).take(entry.size), &mut file).map_err(archive_io)?;
+                if copied < entry.size {
+                    return Err(invalid("archive ends inside a file"));
+                }
+                io::copy(
+                    &mut (&mut tar).take(padding(entry.size) as u64),
+                    &mut io::sink(),
+                )
+                .map_err(archive_io)?;
+                summary.files += 1;
+                summary.bytes += entry.size;
+            }
+        }
+    }
+}
+
+/// A memory root has a top-level index and sessions directory, and every
+/// index in it parses.
+fn validate_tree(root: &Path) -> Result<(), AppError> {
+    if !root.join(INDEX_FILE).is_file() || !root.join(SESSIONS_DIR).is_dir() {
+        return Err(invalid(
+            "archive is not a memory tree (no sessions.json and sessions/ at the top)",
+        ));
+    }
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir).map_err(|e| memory_error(&dir, e))? {
+            let path = entry.map_err(|e| memory_error(&dir, e))?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.file_name().is_some_and(|name| name == INDEX_FILE) {
+                let data = fs::read_to_string(&path).map_err(|e| memory_error(&path, e))?;
+                serde_json::from_str::<SessionIndex>(&data).map_err(|e| {
+                    let relative = path.strip_prefix(root).unwrap_or(&path);
+                    invalid(&format!(
+                        "{} is not a session index: {e}",
+                        relative.display()
+                    ))
+                })?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// ── ustar headers ─────────────────────────────────────────────────────────────
+
+struct Entry {
+    path: PathBuf,
+    /// `b'0'` for a file, `b'5'` for a directory.
+    kind: u8,
+    size: u64,
+}
+
+fn header(path: &Path, kind: u8, size: u64, mtime: u64) -> Result<[u8; BLOCK], AppError> {
+    let name = path
+        .to_str()
+        .ok_or_else(|| AppError::Memory(format!("{} is not valid utf-8", path.display())))?
+        .replace('\\', "/");
+    let name = if kind == b'5' {
+        format!("{name}/")
+    } else {
+        name
+    };
+    // Names over 100 bytes go into the 155-byte prefix, split at a `/`.
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name.as_str())
+    } else {
+        name.char_indices()
+            .filter(|&(i, c)| c == '/' && i <= 155 && name.len() - i - 1 <= 100)
+            .map(|(i, _)| (&name[..i], &name[i + 1..]))
+            .find(|(_, rest)| !rest.is_empty())
+            .ok_or_else(|| AppError::Memory(format!("path too long to archive: {name}")))?
+    };
+
+    let mut block = [0u8; BLOCK];
+    block[..name.len()].copy_from_slice(name.as_bytes());
+    octal(
+        &mut block[100..108],
+        if kind == b'5' { 0o755 } else { 0o644 },
+    );
+    octal(&mut block[108..116], 0);
+    octal(&mut block[116..124], 0);
+    octal(&mut block[124..136], size);
+    octal(&mut block[136..148], mtime);
+    block[156] = kind;
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+    block[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+    block[148..156].fill(b' ');
+    let sum: u64 = block.iter().map(|&b| u64::from(b)).sum();
+    octal(&mut block[148..155], sum);
+    Ok(block)
+}
+
+fn parse_header(block: &[u8; BLOCK]) -> Result<Entry, AppError> {
+    if &block[257..262] != b"ustar" {
+        return Err(invalid("not a ustar archive"));
+    }
+    let mut unsummed = *block;
+    unsummed[148..156].fill(b' ');
+    let sum: u64 = unsummed.iter().map(|&b| u64::from(b)).sum();
+    if parse_octal(&block[148..156]) != Some(sum) {
+        return Err(invalid("bad header checksum"));
+    }
+
+    let text = |field: &[u8]| {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        std::str::from_utf8(&field[..end])
+            .map(str::to_string)
+            .map_err(|_| invalid("entry name is not utf-8"))
+    };
+    let name = text(&block[..100])?;
+    let prefix = text(&block[345..500])?;
+    let name = if prefix.is_empty() {
+        name
+    } else {
+        format!("{prefix}/{name}")
+    };
+
+    let kind = match block[156] {
+        b'0' | 0 => b'0',
+        b'5' => b'5',
+        other => {
+            return Err(invalid(&format!(
+                "{name}: unsupported entry type {:?}",
+                other as char
+            )));
+        }
+    };
+    let size = parse_octal(&block[124..136]).ok_or_else(|| invalid("bad entry size"))?;
+
+    let path = PathBuf::from(name.trim_end_matches('/'));
+    let safe = path.components().next().is_some()
+        && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if !safe {
+        return Err(invalid(&format!("{name}: path escapes the memory root")));
+    }
+    Ok(Entry { path, kind, size })
+}
+
+/// Zero-padded octal, NUL-terminated, filling `field`.
+fn octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}", width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    u64::from_str_radix(text, 8).ok()
+}
+
+/// Zero bytes after `size` bytes of content to reach a block boundary.
+fn padding(size: u64) -> usize {
+    (BLOCK - (size % BLOCK as u64) as usize) % BLOCK
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::Memory(format!("invalid memory archive: {message}"))
+}
+
+fn archive_io(e: io::Error) -> AppError {
+    AppError::Memory(format!("memory archive: {e}"))
+}
+
+fn memory_error(path: &Path, e: io::Error) -> AppError {
+    AppError::Memory(format!("{}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tar_gz(entries: &[(&str, u8, &[u8])]) -> Vec<u8> {
+        let mut out = GzEncoder::new(Vec::new(), Compression::default());
+        for (path, kind, content) in entries {
+            out.write_all(&header(Path::new(path), *kind, content.len() as u64, 0).unwrap())
+                .unwrap();
+            out.write_all(content).unwrap();
+            out.write_all(&[0u8; BLOCK][..padding(content.len() as u64)])
+                .unwrap();
+        }
+        out.write_all(&[0u8; BLOCK * 2]).unwrap();
+        out.finish().unwrap()
+    }
+
+    #[test]
+    fn long_paths_round_trip_through_the_prefix_field() {
+        let path = format!(
+            "agents/{}/sessions/{}/kv.json",
+            "a".repeat(60),
+            "b".repeat(60)
+        );
+        let block = header(Path::new(&path), b'0', 3, 0).unwrap();
+        let entry = parse_header(&block).unwrap();
+        assert_eq!(entry.path, PathBuf::from(path));
+        assert_eq!(entry.size, 3);
+    }
+
+    #[test]
+    fn unpack_rejects_escaping_paths_and_non_memory_trees() {
+        let dir = TempDir::new().unwrap();
+
+        let mut block = header(Path::new("sessions.json"), b'0', 0, 0).unwrap();
+        block[..14].copy_from_slice(b"../escape.json");
+        block[148..156].fill(b' ');
+        let sum: u64 = block.iter().map(|&b| u64::from(b)).sum();
+        octal(&mut block[148..155], sum);
+        let err = parse_header(&block).err().unwrap();
+        assert!(err.to_string().contains("escapes"), "{err}");
+
+        let dest = dir.path().join("no-index");
+        let err = unpack_tree(&tar_gz(&[("notes.txt", b'0', b"hi")])[..], &dest).unwrap_err();
+        assert!(err.to_string().contains("not a memory tree"), "{err}");
+        assert!(!dest.exists());
+
+        let dest = dir.path().join("bad-index");
+        let archive = tar_gz(&[
+            ("sessions", b'5', b""),
+            ("sessions.json", b'0', b"not json"),
+        ]);
+        let err = unpack_tree(&archive[..], &dest).unwrap_err();
+        assert!(err.to_string().contains("not a session index"), "{err}");
+        assert!(!dest.exists());
+    }
+}
@@ -6,21 +6,43 @@
 //!
 //! Currently exposes:
 //! - `memory/kg_graph` — knowledge graph JSON for an agent's kgdocstore.
+//! - `memory/export` — whole-tree backup as a `.tar.gz` (see
+//!   [`crate::archive`]), streamed back as a [`BusPayload::ByteStream`], or
+//!   written to a `path` on the bot's host when one is given.
+//! - `memory/import` — restore such an archive from a host `path` into a
+//!   fresh bot; only in maintenance mode, so no agent touches the tree
+//!   while it is swapped.
+//!
+//! Both are protected and normally reached through `manage/memory/*`.
+//! - `memory/check` — round-trip a throwaway diagnostic session (see
+//!   [`MemorySystem::create_diagnostic_session`]); used by the startup
+//!   self-test.
 //! - `memory/status`   — subsystem status (required convention).
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use tokio::sync::oneshot;
+use tokio::sync::{mpsc, oneshot};
 
 use araliya_core::bus::ComponentStatusResponse;
-use araliya_core::bus::{BusError, BusHandler, BusPayload, BusResult, ERR_METHOD_NOT_FOUND};
+use araliya_core::bus::maintenance::Maintenance;
+use araliya_core::bus::{
+    BusError, BusHandler, BusPayload, BusResult, ByteReceiver, ERR_METHOD_NOT_FOUND,
+};
+use araliya_core::error::AppError;
+
+use crate::MemorySystem;
 
 /// Bus handler for the `memory/` prefix.
 pub struct MemoryBusHandler {
     /// agent_id → identity_dir, used to locate each agent's kgdocstore.
     agent_identity_dirs: Arc<HashMap<String, PathBuf>>,
+    /// Backs `memory/export` and `memory/import`; without it they are not found.
+    memory: Option<Arc<MemorySystem>>,
+    /// `memory/import` is refused unless this is on.
+    maintenance: Maintenance,
     reporter: Option<araliya_core::bus::health::HealthReporter>,
 }
 
@@ -28,10 +50,24 @@ impl MemoryBusHandler {
     pub fn new(agent_identity_dirs: Arc<HashMap<String, PathBuf>>) -> Self {
         Self {
             agent_identity_dirs,
+            memory: None,
+            maintenance: Maintenance::default(),
             reporter: None,
         }
     }
 
+    /// The maintenance switch `memory/import` waits for.
+    pub fn with_maintenance(mut self, maintenance: Maintenance) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Serve `memory/export` and `memory/import` for `memory`.
+    pub fn with_memory_system(mut self, memory: Arc<MemorySystem>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
     pub fn with_health_reporter(
         mut self,
         reporter: araliya_core::bus::health::HealthReporter,
@@ -119,6 +155,74 @@ impl BusHandler for MemoryBusHandler {
                 }));
             }
 
+            "memory/export" | "memory/import" if self.memory.is_some() => {
+                let memory = self.memory.clone().unwrap();
+                let export = method == "memory/export";
+                let path = match archive_path(payload) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        let _ = reply_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let path = match path {
+                    Some(path) => path,
+                    None if export => {
+                        let _ = reply_tx.send(Ok(BusPayload::ByteStream {
+                            rx: stream_export(memory),
+                        }));
+                        return;
+                    }
+                    None => {
+                        let _ = reply_tx.send(Err(BusError::new(
+                            -32602,
+                            "expected {\"path\": ...} in payload",
+                        )));
+                        return;
+                    }
+                };
+                if !export && !self.maintenance.is_enabled() {
+                    let _ = reply_tx.send(Err(BusError::new(
+                        -32000,
+                        "memory/import needs maintenance mode (manage/maintenance/on)",
+                    )));
+                    return;
+                }
+                tokio::spawn(async move {
+                    let result = tokio::task::spawn_blocking(move || {
+                        let summary = if export {
+                            export_to(&memory, &path)?
+                        } else {
+                            import_from(&memory, &path)?
+                        };
+                        Ok::<_, AppError>(serde_json::json!({
+                            "path": path,
+                            "files": summary.files,
+                            "bytes": summary.bytes,
+                        }))
+                    })
+                    .await;
+                    let _ = reply_tx.send(match result {
+                        Ok(Ok(body)) => Ok(BusPayload::JsonResponse {
+                            data: body.to_string(),
+                        }),
+                        Ok(Err(e)) => Err(BusError::new(-32000, e.to_string())),
+                        Err(e) => Err(BusError::new(-32000, format!("memory archive task: {e}"))),
+                    });
+                });
+            }
+
+            "memory/check" if self.memory.is_some() => {
+                let memory = self.memory.clone().unwrap();
+                tokio::spawn(async move {
+                    let result = session_round_trip(&memory)
+                        .await
+                        .map(|()| BusPayload::Empty)
+                        .map_err(|e| BusError::new(-32000, format!("memory check: {e}")));
+                    let _ = reply_tx.send(result);
+                });
+            }
+
             "memory/status" => {
                 let data = ComponentStatusResponse::running("memory").to_json();
                 let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data }));
@@ -133,3 +237,173 @@ impl BusHandler for MemoryBusHandler {
         }
     }
 }
+
+/// Write to a diagnostic session and read it back.  The session is gone
+/// once this returns.
+async fn session_round_trip(memory: &MemorySystem) -> Result<(), AppError> {
+    let session = memory.create_diagnostic_session(None)?;
+    session.kv_set("probe", "memory check").await?;
+    if session.kv_get("probe").await?.as_deref() != Some("memory check") {
+        return Err(AppError::Memory(
+            "session data did not read back".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The `path` field of a `JsonRequest` payload, if any.
+fn archive_path(payload: BusPayload) -> Result<Option<PathBuf>, BusError> {
+    let data = match payload {
+        BusPayload::JsonRequest { data } => data,
+        BusPayload::Empty => return Ok(None),
+        _ => return Err(BusError::new(-32600, "expected {\"path\": ...} in payload")),
+    };
+    let value = serde_json::from_str::<serde_json::Value>(&data)
+        .map_err(|_| BusError::new(-32602, "expected {\"path\": ...} in payload"))?;
+    match &value["path"] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(p) if !p.is_empty() => Ok(Some(PathBuf::from(p))),
+        _ => Err(BusError::new(-32602, "expected {\"path\": ...} in payload")),
+    }
+}
+
+/// Chunk size of a streamed export.
+const EXPORT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Export on a blocking thread, handing the archive to the returned
+/// receiver chunk by chunk.  Dropping the receiver aborts the export.
+fn stream_export(memory: Arc<MemorySystem>) -> ByteReceiver {
+    let (tx, rx) = mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let mut out = BufWriter::with_capacity(EXPORT_CHUNK_BYTES, ChunkWriter(tx.clone()));
+        let result = memory
+            .export_archive(&mut out)
+            .and_then(|_| out.flush().map_err(|e| AppError::Memory(e.to_string())));
+        if let Err(e) = result {
+            let _ = tx.blocking_send(Err(e.to_string()));
+        }
+    });
+    ByteReceiver(rx)
+}
+
+/// A [`Write`] that sends each buffer it is given as one chunk.
+struct ChunkWriter(mpsc::Sender<Result<Vec<u8>, String>>);
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(buf.to_vec()))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "export receiver dropped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Export to `path`, via `{path}.partial` so an interrupted export never
+/// looks complete.
+fn export_to(
+    memory: &MemorySystem,
+    path: &Path,
+) -> Result<crate::archive::ArchiveSummary, AppError> {
+    let absolute = |p: &Path| std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    if absolute(path).starts_with(absolute(memory.memory_root())) {
+        return Err(AppError::Memory(
+            "cannot export the memory tree into itself".to_string(),
+        ));
+    }
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let io_error = |e: std::io::Error| AppError::Memory(format!("{}: {e}", path.display()));
+
+    let file = std::fs::File::create(&partial).map_err(io_error)?;
+    let mut out = BufWriter::new(file);
+    let summary = memory.export_archive(&mut out).and_then(|summary| {
+        out.flush().map_err(io_error)?;
+        std::fs::rename(&partial, path).map_err(io_error)?;
+        Ok(summary)
+    });
+    if summary.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    summary
+}
+
+fn import_from(
+    memory: &MemorySystem,
+    path: &Path,
+) -> Result<crate::archive::ArchiveSummary, AppError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| AppError::Memory(format!("{}: {e}", path.display())))?;
+    memory.import_archive(BufReader::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryConfig;
+
+    fn handler() -> (tempfile::TempDir, Arc<MemorySystem>, MemoryBusHandler) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory = Arc::new(MemorySystem::new(dir.path(), MemoryConfig::default()).unwrap());
+        let handler = MemoryBusHandler::new(Arc::new(HashMap::new()))
+            .with_memory_system(memory.clone())
+            .with_maintenance(Maintenance::new(false, "back soon"));
+        (dir, memory, handler)
+    }
+
+    async fn call(handler: &MemoryBusHandler, method: &str, payload: BusPayload) -> BusResult {
+        let (tx, rx) = oneshot::channel();
+        handler.handle_request(method, payload, tx);
+        rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn check_round_trips_a_session_without_indexing_it() {
+        let (_dir, memory, handler) = handler();
+        let result = call(&handler, "memory/check", BusPayload::Empty).await;
+        assert!(matches!(result, Ok(BusPayload::Empty)));
+        assert!(memory.list_sessions().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_streams_back_and_import_needs_maintenance() {
+        let (_src_dir, src, src_handler) = handler();
+        let session = src.create_session(&["basic_session"], None).unwrap();
+        session.transcript_append("user", "hello").await.unwrap();
+        drop(session);
+
+        let Ok(BusPayload::ByteStream {
+            rx: ByteReceiver(mut rx),
+        }) = call(&src_handler, "memory/export", BusPayload::Empty).await
+        else {
+            panic!("expected a byte stream");
+        };
+        let mut archive = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            archive.extend(chunk.unwrap());
+        }
+
+        let (dst_dir, dst, dst_handler) = handler();
+        let path = dst_dir.path().join("backup.tar.gz");
+        std::fs::write(&path, &archive).unwrap();
+        let payload = || BusPayload::JsonRequest {
+            data: serde_json::json!({ "path": path }).to_string(),
+        };
+
+        let err = call(&dst_handler, "memory/import", payload())
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("maintenance"), "{}", err.message);
+        assert!(dst.list_sessions().unwrap().is_empty());
+
+        dst_handler.maintenance.set_enabled(true);
+        call(&dst_handler, "memory/import", payload())
+            .await
+            .unwrap();
+        assert_eq!(dst.list_sessions().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,57 @@
+//! Wall-clock source for time-based session maintenance.
+//!
+//! The idle-session archiver compares `updated_at` against "now"; taking the
+//! clock as a [`Clock`] lets tests move time forward with [`MockClock`]
+//! instead of sleeping.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time in whole seconds since the Unix epoch.
+pub trait Clock: Send + Sync {
+    fn now_unix_secs(&self) -> u64;
+}
+
+/// The system wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_unix_secs(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// The UTC calendar date (`YYYY-MM-DD`) containing `unix_secs`.
+pub fn utc_date(unix_secs: u64) -> String {
+    let (y, mo, d, ..) = crate::handle::epoch_to_ymd_hms(unix_secs);
+    format!("{y:04}-{mo:02}-{d:02}")
+}
+
+/// A clock that only moves when told to.  For tests.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    secs: AtomicU64,
+}
+
+impl MockClock {
+    pub fn new(unix_secs: u64) -> Self {
+        Self {
+            secs: AtomicU64::new(unix_secs),
+        }
+    }
+
+    /// Move the clock forward by `by` (sub-second parts are dropped).
+    pub fn advance(&self, by: Duration) {
+        self.secs.fetch_add(by.as_secs(), Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_unix_secs(&self) -> u64 {
+        self.secs.load(Ordering::Relaxed)
+    }
+}
@@ -3,18 +3,23 @@
 //! `SessionHandle` is intentionally lightweight: it carries identity metadata
 //! (`session_id`) and delegates all data read/write behavior to [`SessionRw`].
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError, Weak};
 
 use araliya_core::error::AppError;
 use araliya_core::types::llm::{LlmUsage, ModelRates};
 
-use crate::SessionSpend;
+use crate::clock::{Clock, SystemClock, utc_date};
 use crate::collections::{Block, Doc};
 pub use crate::rw::SessionFileInfo;
-use crate::rw::SessionRw;
+use crate::rw::{SessionRw, WriteFallback};
 use crate::store::{SessionStore, TranscriptEntry};
 use crate::stores::tmp::TmpStore;
+use crate::{SessionSpend, UNKNOWN_MODEL};
+
+/// KV key holding a session's working memory.
+pub(crate) const WORKING_MEMORY_KEY: &str = "working_memory";
 
 #[derive(Clone)]
 pub struct SessionHandle {
@@ -28,13 +33,42 @@ impl SessionHandle {
         session_dir: PathBuf,
         stores: Vec<Arc<dyn SessionStore>>,
         tmp_store: Option<Arc<TmpStore>>,
+        write_fallback: Option<Arc<WriteFallback>>,
+    ) -> Self {
+        let rw = SessionRw::new(session_dir, stores, tmp_store).with_write_fallback(write_fallback);
+        Self {
+            session_id,
+            rw: Arc::new(rw),
+        }
+    }
+
+    /// A handle to an unindexed `tmp` session whose data `tmp_store`
+    /// releases when the last clone drops.
+    pub(crate) fn diagnostic(
+        session_id: String,
+        session_dir: PathBuf,
+        stores: Vec<Arc<dyn SessionStore>>,
+        tmp_store: Arc<TmpStore>,
     ) -> Self {
+        let rw = SessionRw::new(session_dir, stores, Some(tmp_store)).release_on_drop();
         Self {
             session_id,
-            rw: Arc::new(SessionRw::new(session_dir, stores, tmp_store)),
+            rw: Arc::new(rw),
         }
     }
 
+    /// `true` once a failed disk write has moved this session to memory
+    /// (`[memory] write_fallback`); later writes are not persisted.
+    pub fn is_degraded(&self) -> bool {
+        self.rw.is_degraded()
+    }
+
+    /// Weak reference that stays upgradable while any clone of this handle
+    /// is alive; used to keep open sessions out of LRU eviction.
+    pub(crate) fn downgrade(&self) -> Weak<SessionRw> {
+        Arc::downgrade(&self.rw)
+    }
+
     pub async fn kv_get(&self, key: &str) -> Result<Option<String>, AppError> {
         self.rw.kv_get(key).await
     }
@@ -51,12 +85,32 @@ impl SessionHandle {
         self.rw.transcript_append(role, content).await
     }
 
+    /// Append an entry with per-turn metadata (see [`TranscriptEntry::meta`]).
+    pub async fn transcript_append_with_meta(
+        &self,
+        role: &str,
+        content: &str,
+        meta: serde_json::Value,
+    ) -> Result<(), AppError> {
+        self.rw
+            .transcript_append_with_meta(role, content, meta)
+            .await
+    }
+
     pub async fn transcript_read_last(&self, n: usize) -> Result<Vec<TranscriptEntry>, AppError> {
         self.rw.transcript_read_last(n).await
     }
 
+    pub async fn transcript_since(&self, since: &str) -> Result<Vec<TranscriptEntry>, AppError> {
+        self.rw.transcript_since(since).await
+    }
+
     pub async fn working_memory_read(&self) -> Result<String, AppError> {
-        Ok(self.kv_get("working_memory").await?.unwrap_or_default())
+        Ok(self.kv_get(WORKING_MEMORY_KEY).await?.unwrap_or_default())
+    }
+
+    pub async fn working_memory_write(&self, content: &str) -> Result<(), AppError> {
+        self.kv_set(WORKING_MEMORY_KEY, content).await
     }
 
     pub async fn kv_doc(&self) -> Result<Doc, AppError> {
@@ -91,18 +145,47 @@ impl SessionHandle {
     ///
     /// Reads the current sidecar (or defaults to zero), adds the new counts,
     /// recomputes the incremental cost using `rates`, and writes the file back.
+    /// The turn is also counted under `model` in [`SessionSpend::by_model`].
     /// Returns the updated [`SessionSpend`].
     pub async fn accumulate_spend(
         &self,
         usage: &LlmUsage,
+        model: &str,
         rates: &ModelRates,
+    ) -> Result<SessionSpend, AppError> {
+        self.accumulate_spend_at(usage, model, rates, SystemClock.now_unix_secs())
+            .await
+    }
+
+    /// [`accumulate_spend`](Self::accumulate_spend) at an explicit time.
+    /// The per-day output counter starts over when `now_unix_secs` falls on
+    /// a different UTC date than the last accumulation.
+    pub async fn accumulate_spend_at(
+        &self,
+        usage: &LlmUsage,
+        model: &str,
+        rates: &ModelRates,
+        now_unix_secs: u64,
     ) -> Result<SessionSpend, AppError> {
         let session_dir = self.rw.session_dir().to_path_buf();
         let usage = usage.clone();
+        let model = model.to_string();
         let rates = rates.clone();
-        tokio::task::spawn_blocking(move || accumulate_spend_blocking(&session_dir, &usage, &rates))
+        tokio::task::spawn_blocking(move || {
+            accumulate_spend_blocking(&session_dir, &usage, &model, &rates, now_unix_secs)
+        })
+        .await
+        .map_err(|e| AppError::Memory(format!("spend spawn_blocking: {e}")))?
+    }
+
+    /// Mark the spend alert as raised if the total has reached
+    /// `threshold_usd` and no alert was raised before.  Returns `true` only
+    /// for the call that flips the flag.
+    pub async fn claim_spend_alert(&self, threshold_usd: f64) -> Result<bool, AppError> {
+        let session_dir = self.rw.session_dir().to_path_buf();
+        tokio::task::spawn_blocking(move || claim_spend_alert_blocking(&session_dir, threshold_usd))
             .await
-            .map_err(|e| AppError::Memory(format!("spend spawn_blocking: {e}")))?
+            .map_err(|e| AppError::Memory(format!("spend alert spawn_blocking: {e}")))?
     }
 
     /// Read aggregate spend totals from `spend.json` for this session.
@@ -118,45 +201,88 @@ impl SessionHandle {
 
 // ── Spend helpers ─────────────────────────────────────────────────────────────
 
+/// One lock per session directory, held across each read-modify-write of
+/// its `spend.json`, so turns finishing together on any of the session's
+/// handles do not lose each other's counts.
+static SPEND_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+fn spend_lock(session_dir: &Path) -> Arc<Mutex<()>> {
+    let mut locks = SPEND_LOCKS.lock().unwrap_or_else(PoisonError::into_inner);
+    locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+    locks.entry(session_dir.to_path_buf()).or_default().clone()
+}
+
+/// Write `spend.json` beside and rename it into place, so a crash leaves
+/// the previous totals rather than a torn file.
+fn write_spend_blocking(session_dir: &Path, spend: &SessionSpend) -> Result<(), AppError> {
+    let data = serde_json::to_string_pretty(spend)
+        .map_err(|e| AppError::Memory(format!("serialize spend.json: {e}")))?;
+    let path = session_dir.join("spend.json");
+    let partial = session_dir.join("spend.json.tmp");
+    std::fs::write(&partial, data)
+        .and_then(|()| std::fs::rename(&partial, &path))
+        .map_err(|e| AppError::Memory(format!("write spend.json: {e}")))
+}
+
 fn accumulate_spend_blocking(
-    session_dir: &std::path::Path,
+    session_dir: &Path,
     usage: &LlmUsage,
+    model: &str,
     rates: &ModelRates,
+    now_unix_secs: u64,
 ) -> Result<SessionSpend, AppError> {
-    let spend_path = session_dir.join("spend.json");
-
-    let mut spend: SessionSpend = if spend_path.exists() {
-        let raw = std::fs::read_to_string(&spend_path)
-            .map_err(|e| AppError::Memory(format!("read spend.json: {e}")))?;
-        serde_json::from_str(&raw)
-            .map_err(|e| AppError::Memory(format!("parse spend.json: {e}")))?
-    } else {
-        SessionSpend::default()
-    };
+    let lock = spend_lock(session_dir);
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut spend = read_spend_blocking(session_dir)?.unwrap_or_default();
 
     spend.total_input_tokens += usage.input_tokens;
     spend.total_output_tokens += usage.output_tokens;
     spend.total_cached_tokens += usage.cached_input_tokens;
-    spend.total_cost_usd += usage.cost_usd(rates);
+    let cost = usage.cost_usd(rates);
+    spend.total_cost_usd += cost;
+    let model = if model.is_empty() {
+        UNKNOWN_MODEL
+    } else {
+        model
+    };
+    spend
+        .by_model
+        .entry(model.to_string())
+        .or_default()
+        .add(usage, cost);
+
+    let today = utc_date(now_unix_secs);
+    if spend.day != today {
+        spend.day = today;
+        spend.day_output_tokens = 0;
+    }
+    spend.day_output_tokens += usage.output_tokens;
+
     spend.last_updated = {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let secs = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
-        let (y, mo, d, h, mi, s) = epoch_to_ymd_hms(secs);
+        let (y, mo, d, h, mi, s) = epoch_to_ymd_hms(now_unix_secs);
         format!("{y:04}-{mo:02}-{d:02}T{h:02}:{mi:02}:{s:02}Z")
     };
 
-    let data = serde_json::to_string_pretty(&spend)
-        .map_err(|e| AppError::Memory(format!("serialize spend.json: {e}")))?;
-    std::fs::write(&spend_path, &data)
-        .map_err(|e| AppError::Memory(format!("write spend.json: {e}")))?;
-
+    write_spend_blocking(session_dir, &spend)?;
     Ok(spend)
 }
 
-fn read_spend_blocking(session_dir: &std::path::Path) -> Result<Option<SessionSpend>, AppError> {
+fn claim_spend_alert_blocking(session_dir: &Path, threshold_usd: f64) -> Result<bool, AppError> {
+    let lock = spend_lock(session_dir);
+    let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(mut spend) = read_spend_blocking(session_dir)? else {
+        return Ok(false);
+    };
+    if spend.alerted || spend.total_cost_usd < threshold_usd {
+        return Ok(false);
+    }
+    spend.alerted = true;
+    write_spend_blocking(session_dir, &spend)?;
+    Ok(true)
+}
+
+pub(crate) fn read_spend_blocking(session_dir: &Path) -> Result<Option<SessionSpend>, AppError> {
     let spend_path = session_dir.join("spend.json");
     if !spend_path.exists() {
         return Ok(None);
@@ -171,7 +297,7 @@ fn read_spend_blocking(session_dir: &std::path::Path) -> Result<Option<SessionSp
 
 /// Convert Unix epoch seconds to (year, month, day, hour, min, sec).
 /// Minimal implementation that avoids a `chrono` dependency.
-fn epoch_to_ymd_hms(secs: u64) -> (u32, u32, u32, u32, u32, u32) {
+pub(crate) fn epoch_to_ymd_hms(secs: u64) -> (u32, u32, u32, u32, u32, u32) {
     let s = secs % 60;
     let m = (secs / 60) % 60;
     let h = (secs / 3600) % 24;
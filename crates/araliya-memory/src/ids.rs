@@ -0,0 +1,46 @@
+//! Session id source.
+//!
+//! New sessions are named by UUIDv7 (time-ordered) by default.  Taking the
+//! generator as an [`IdGenerator`] lets tests swap in [`SequentialIds`] and
+//! assert exact session ids, the way [`crate::clock::MockClock`] pins time.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of new session ids.  Ids become directory names, so they must be
+/// unique and safe as a single path component.
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+/// Time-ordered UUIDv7 ids.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7Ids;
+
+impl IdGenerator for UuidV7Ids {
+    fn next_id(&self) -> String {
+        uuid::Uuid::now_v7().to_string()
+    }
+}
+
+/// `{prefix}000001`, `{prefix}000002`, …  For tests.
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: AtomicU64::new(1),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_id(&self) -> String {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        format!("{}{n:06}", self.prefix)
+    }
+}
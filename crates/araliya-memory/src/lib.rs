@@ -12,11 +12,14 @@
 //! | `idocstore`    | Adds `IDocStore` (FTS5 document index) + manager   |
 //! | `ikgdocstore`  | Adds `IKGDocStore` (docstore + knowledge graph)    |
 
+pub mod archive;
 pub mod bus;
+pub mod clock;
 pub mod collections;
 #[cfg(feature = "idocstore")]
 mod docstore_manager;
 pub mod handle;
+pub mod ids;
 pub mod rw;
 pub mod store;
 pub mod stores;
@@ -28,25 +31,42 @@ pub use collections::{Block, Collection, Doc};
 pub use store::Store;
 pub use types::{Obj, PrimaryValue, TextFile, Value};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::time::Duration;
 
-use tracing::info;
+use tracing::{debug, info, warn};
 
+use araliya_core::bus::health::HealthReporter;
+use araliya_core::config::{SessionOverflow, TranscriptFormat};
 use araliya_core::error::AppError;
+use archive::ArchiveSummary;
+use clock::{Clock, SystemClock};
 use handle::SessionHandle;
+use ids::{IdGenerator, UuidV7Ids};
+use rw::SessionRw;
 use store::SessionStore;
 
 /// Directory name under `{memory_root}/` that stores per-agent identities.
 pub const AGENTS_DIRNAME: &str = "agents";
 
+/// How often the idle-session archiver sweeps the indexes.
+const IDLE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Directory under `work_dir` that holds the daily spend snapshots.
+pub const SPEND_SNAPSHOTS_DIRNAME: &str = "spend-snapshots";
+
 /// Metadata for a single session, persisted in `sessions.json`.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SessionInfo {
     pub session_id: String,
     pub created_at: String,
+    /// Last time the session was created or loaded; drives LRU eviction.
+    /// Older indexes lack it, in which case `created_at` is used.
+    #[serde(default)]
+    pub updated_at: Option<String>,
     pub store_types: Vec<String>,
     /// Last agent that accessed this session (informational).
     #[serde(default)]
@@ -54,6 +74,59 @@ pub struct SessionInfo {
     /// Aggregate token spend for this session, mirrored from `spend.json`.
     #[serde(default)]
     pub spend: Option<SessionSpend>,
+    /// Transcript format the session was created with.  Sessions indexed
+    /// before the option existed are Markdown.
+    #[serde(default)]
+    pub transcript_format: TranscriptFormat,
+    /// Set by the idle-session archiver; cleared when the session is loaded
+    /// again.  Archived sessions are left out of [`MemorySystem::list_sessions`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Short human-readable title (`[agents] auto_title`); set once, after
+    /// the first turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl SessionInfo {
+    /// Timestamp used for LRU ordering.
+    fn last_touched(&self) -> &str {
+        self.updated_at.as_deref().unwrap_or(&self.created_at)
+    }
+}
+
+/// Order of a session listing.  Timestamp keys list the newest first; ties
+/// (and [`SessionSort::SessionId`]) fall back to ascending `session_id`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SessionSort {
+    /// Most recently updated first (`updated_at`, else `created_at`).
+    #[default]
+    UpdatedAt,
+    CreatedAt,
+    SessionId,
+}
+
+impl SessionSort {
+    /// Parse a `sort` parameter: `updated_at`, `created_at` or `session_id`.
+    pub fn parse(key: &str) -> Option<Self> {
+        match key {
+            "updated_at" => Some(Self::UpdatedAt),
+            "created_at" => Some(Self::CreatedAt),
+            "session_id" => Some(Self::SessionId),
+            _ => None,
+        }
+    }
+
+    pub fn sort(self, sessions: &mut [SessionInfo]) {
+        sessions.sort_by(|a, b| {
+            let by_key = match self {
+                Self::UpdatedAt => b.last_touched().cmp(a.last_touched()),
+                Self::CreatedAt => b.created_at.cmp(&a.created_at),
+                Self::SessionId => std::cmp::Ordering::Equal,
+            };
+            by_key.then_with(|| a.session_id.cmp(&b.session_id))
+        });
+    }
 }
 
 /// Aggregate token and cost totals for a session.
@@ -67,6 +140,98 @@ pub struct SessionSpend {
     pub total_cost_usd: f64,
     /// ISO-8601 timestamp of the last accumulation.
     pub last_updated: String,
+    /// Set once a spend alert has been raised for this session.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub alerted: bool,
+    /// UTC date (`YYYY-MM-DD`) that `day_output_tokens` counts toward.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub day: String,
+    /// Output tokens accumulated on `day`.  Starts over on the first
+    /// accumulation of a new day.
+    #[serde(default)]
+    pub day_output_tokens: u64,
+    /// The same totals per model name.  Spend recorded before models were
+    /// tracked is not broken down.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub by_model: BTreeMap<String, ModelSpend>,
+}
+
+/// Model name that spend is counted under when the model is not known.
+pub const UNKNOWN_MODEL: &str = "unknown";
+
+/// Token and cost totals for one model.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ModelSpend {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl ModelSpend {
+    fn add(&mut self, usage: &araliya_core::types::llm::LlmUsage, cost_usd: f64) {
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cached_tokens += usage.cached_input_tokens;
+        self.cost_usd += cost_usd;
+    }
+
+    fn merge(&mut self, other: &ModelSpend) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cached_tokens += other.cached_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+}
+
+/// Spend summed over every session, bot-wide and per agent
+/// (see [`MemorySystem::aggregate_spend`]).
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpendTotals {
+    /// Sessions with any recorded spend.
+    pub sessions: usize,
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
+    pub total_cached_tokens: u64,
+    pub total_cost_usd: f64,
+    /// Totals per model; spend from before models were tracked is counted
+    /// under [`UNKNOWN_MODEL`].
+    pub by_model: BTreeMap<String, ModelSpend>,
+}
+
+impl SpendTotals {
+    fn add(&mut self, spend: &SessionSpend) {
+        self.sessions += 1;
+        self.total_input_tokens += spend.total_input_tokens;
+        self.total_output_tokens += spend.total_output_tokens;
+        self.total_cached_tokens += spend.total_cached_tokens;
+        self.total_cost_usd += spend.total_cost_usd;
+        if spend.by_model.is_empty() {
+            self.by_model
+                .entry(UNKNOWN_MODEL.to_string())
+                .or_default()
+                .merge(&ModelSpend {
+                    input_tokens: spend.total_input_tokens,
+                    output_tokens: spend.total_output_tokens,
+                    cached_tokens: spend.total_cached_tokens,
+                    cost_usd: spend.total_cost_usd,
+                });
+        }
+        for (model, m) in &spend.by_model {
+            self.by_model.entry(model.clone()).or_default().merge(m);
+        }
+    }
+}
+
+impl SessionSpend {
+    /// Output tokens counted toward the UTC day containing `now_unix_secs`.
+    pub fn day_output_tokens_at(&self, now_unix_secs: u64) -> u64 {
+        if self.day == clock::utc_date(now_unix_secs) {
+            self.day_output_tokens
+        } else {
+            0
+        }
+    }
 }
 
 /// On-disk shape of `sessions.json`.
@@ -82,6 +247,27 @@ pub struct MemoryConfig {
     pub kv_cap: Option<usize>,
     /// Cap for transcript entries in `basic_session` store.
     pub transcript_cap: Option<usize>,
+    /// Cap on disk-backed sessions per index.  `None` = unlimited.
+    pub max_sessions: Option<usize>,
+    /// What to do when creating a session would exceed `max_sessions`.
+    pub session_overflow: SessionOverflow,
+    /// Root the tree at `{identity_dir}/memory-{namespace}/` instead of
+    /// `{identity_dir}/memory/` (`[supervisor] bot_namespace`).
+    pub namespace: Option<String>,
+    /// Transcript format for new `basic_session` sessions.
+    pub transcript_format: TranscriptFormat,
+    /// Gzip the transcripts of archived sessions during the idle sweep.
+    pub compress_archived_transcripts: bool,
+    /// Keep a session going in memory when its disk writes fail, instead of
+    /// failing the write.
+    pub write_fallback: bool,
+    /// Working memory written into each new session created for an agent:
+    /// agent_id → text (`[agents.<id>] seed_memory`).
+    pub seed_memory: HashMap<String, String>,
+    /// Disk-backed sessions kept per agent: agent_id → limit
+    /// (`[agents.<id>] max_sessions`).  Past it, the agent's oldest
+    /// sessions are pruned as new ones are created.
+    pub agent_max_sessions: HashMap<String, usize>,
 }
 
 /// Central memory system.  Constructed once at startup, shared via `Arc`.
@@ -92,6 +278,26 @@ pub struct MemorySystem {
     /// Typed reference to the shared `TmpStore` instance, used to populate
     /// [`SessionHandle::tmp_store`] for sessions created with store type `"tmp"`.
     tmp_store: Arc<stores::tmp::TmpStore>,
+    max_sessions: Option<usize>,
+    session_overflow: SessionOverflow,
+    transcript_format: TranscriptFormat,
+    compress_archived_transcripts: bool,
+    /// Present when `write_fallback` is on; shared by every session handle.
+    write_fallback: Option<Arc<rw::WriteFallback>>,
+    /// Held from the `max_sessions` check until the new session is indexed,
+    /// so concurrent creates cannot both take the last slot.  Loads and the
+    /// idle sweep hold it too, so a session cannot be opened between the
+    /// sweep's open check and its archiving.
+    create_lock: Mutex<()>,
+    /// Session dir → handles created for it; a session with any live
+    /// handle is open and never evicted.
+    open_sessions: Mutex<HashMap<PathBuf, Vec<Weak<SessionRw>>>>,
+    /// Names new sessions; UUIDv7 unless replaced for tests.
+    ids: Arc<dyn IdGenerator>,
+    /// Initial working memory for new sessions, per agent.
+    seed_memory: HashMap<String, String>,
+    /// Sessions kept per agent; see [`MemoryConfig::agent_max_sessions`].
+    agent_max_sessions: HashMap<String, usize>,
     /// Background maintenance task for all agent-scoped document stores.
     /// Present only when the `idocstore` feature is enabled and
     /// [`MemorySystem::start_docstore_manager`] has been called.
@@ -100,9 +306,17 @@ pub struct MemorySystem {
 }
 
 impl MemorySystem {
-    /// Create or open the memory root at `{identity_dir}/memory/`.
+    /// Create or open the memory root at `{identity_dir}/memory/`, or
+    /// `{identity_dir}/memory-{namespace}/` when a namespace is configured.
+    ///
+    /// Everything the memory system stores — the session index, sessions
+    /// and agent stores — lives under that root, so each bot identity (and
+    /// each namespace within one) sees only its own data.
     pub fn new(identity_dir: &Path, config: MemoryConfig) -> Result<Self, AppError> {
-        let memory_root = identity_dir.join("memory");
+        let memory_root = match &config.namespace {
+            Some(ns) => identity_dir.join(format!("memory-{ns}")),
+            None => identity_dir.join("memory"),
+        };
         let sessions_dir = memory_root.join("sessions");
 
         fs::create_dir_all(&sessions_dir).map_err(|e| {
@@ -122,10 +336,10 @@ impl MemorySystem {
 
         // Register built-in stores.
         let mut stores: HashMap<String, Arc<dyn SessionStore>> = HashMap::new();
-        let basic = Arc::new(stores::basic_session::BasicSessionStore::new(
-            config.kv_cap,
-            config.transcript_cap,
-        ));
+        let basic = Arc::new(
+            stores::basic_session::BasicSessionStore::new(config.kv_cap, config.transcript_cap)
+                .with_transcript_format(config.transcript_format),
+        );
         stores.insert(basic.store_type().to_string(), basic);
         let tmp = Arc::new(stores::tmp::TmpStore::new());
         stores.insert(
@@ -144,11 +358,38 @@ impl MemorySystem {
             sessions_dir,
             stores,
             tmp_store: tmp,
+            max_sessions: config.max_sessions,
+            session_overflow: config.session_overflow,
+            transcript_format: config.transcript_format,
+            compress_archived_transcripts: config.compress_archived_transcripts,
+            write_fallback: config
+                .write_fallback
+                .then(|| Arc::new(rw::WriteFallback::default())),
+            create_lock: Mutex::new(()),
+            open_sessions: Mutex::new(HashMap::new()),
+            ids: Arc::new(UuidV7Ids),
+            seed_memory: config.seed_memory,
+            agent_max_sessions: config.agent_max_sessions,
             #[cfg(feature = "idocstore")]
             docstore_manager: None,
         })
     }
 
+    /// Name new sessions with `ids` instead of UUIDv7, e.g.
+    /// [`ids::SequentialIds`] for predictable ids in tests.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Report write-fallback degradation through `reporter`.  Without
+    /// `write_fallback`, or when already set, this does nothing.
+    pub fn set_health_reporter(&self, reporter: HealthReporter) {
+        if let Some(fallback) = &self.write_fallback {
+            let _ = fallback.reporter.set(reporter);
+        }
+    }
+
     pub fn memory_root(&self) -> &Path {
         &self.memory_root
     }
@@ -184,6 +425,11 @@ impl MemorySystem {
         }
     }
 
+    /// Whether `store_type` names a registered session store.
+    pub fn has_store_type(&self, store_type: &str) -> bool {
+        self.stores.contains_key(store_type)
+    }
+
     /// Create a new session with the given store types.
     ///
     /// Returns a [`SessionHandle`] for reading and writing session data.
@@ -191,6 +437,41 @@ impl MemorySystem {
         &self,
         store_types: &[&str],
         agent_id: Option<&str>,
+    ) -> Result<SessionHandle, AppError> {
+        self.create_indexed_session(store_types, agent_id)
+    }
+
+    /// Create a throwaway `tmp` session for self-tests, replay and other
+    /// internal checks.  It is never indexed, so no listing or lookup by id
+    /// finds it, and its data is released when the last clone of the
+    /// returned handle drops.
+    pub fn create_diagnostic_session(
+        &self,
+        agent_id: Option<&str>,
+    ) -> Result<SessionHandle, AppError> {
+        let session_id = self.ids.next_id();
+        let session_dir = self.sessions_dir.join(&session_id);
+        let store = self
+            .stores
+            .get("tmp")
+            .ok_or_else(|| AppError::Memory("unknown store type: tmp".to_string()))?
+            .clone();
+        store.init(&session_dir)?;
+        let stores = vec![store];
+        self.seed_working_memory(&stores, &session_dir, agent_id)?;
+        debug!(%session_id, agent = ?agent_id, "diagnostic session created");
+        Ok(SessionHandle::diagnostic(
+            session_id,
+            session_dir,
+            stores,
+            self.tmp_store.clone(),
+        ))
+    }
+
+    fn create_indexed_session(
+        &self,
+        store_types: &[&str],
+        agent_id: Option<&str>,
     ) -> Result<SessionHandle, AppError> {
         // Validate that all requested stores are registered.
         let mut session_stores = Vec::new();
@@ -202,13 +483,17 @@ impl MemorySystem {
             session_stores.push(store.clone());
         }
 
-        // Generate UUIDv7 (time-ordered).
-        let session_id = uuid::Uuid::now_v7().to_string();
+        let session_id = self.ids.next_id();
         let session_dir = self.sessions_dir.join(&session_id);
 
         // Skip disk I/O for purely in-memory sessions.
         let all_tmp = store_types.iter().all(|&s| s == "tmp");
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
         if !all_tmp {
+            self.make_room(&self.sessions_dir, &self.index_path())?;
             fs::create_dir_all(&session_dir).map_err(|e| {
                 AppError::Memory(format!(
                     "cannot create session dir {}: {e}",
@@ -221,19 +506,32 @@ impl MemorySystem {
         for store in &session_stores {
             store.init(&session_dir)?;
         }
+        self.seed_working_memory(&session_stores, &session_dir, agent_id)?;
 
         // Update index.
         let now = now_iso8601();
         let info = SessionInfo {
             session_id: session_id.clone(),
-            created_at: now,
+            created_at: now.clone(),
+            updated_at: Some(now),
             store_types: store_types.iter().map(|s| s.to_string()).collect(),
             last_agent: agent_id.map(|s| s.to_string()),
             spend: None,
+            title: None,
+            transcript_format: self.transcript_format,
+            archived: false,
         };
         self.update_index(|idx| {
             idx.sessions.insert(session_id.clone(), info);
         })?;
+        if !all_tmp {
+            self.prune_agent_sessions(
+                &self.sessions_dir,
+                &self.index_path(),
+                agent_id,
+                &session_id,
+            )?;
+        }
 
         // Attach the typed TmpStore reference when the session uses it.
         let tmp_store = store_types.contains(&"tmp").then(|| self.tmp_store.clone());
@@ -245,12 +543,7 @@ impl MemorySystem {
             "session created"
         );
 
-        Ok(SessionHandle::new(
-            session_id,
-            session_dir,
-            session_stores,
-            tmp_store,
-        ))
+        Ok(self.open_handle(session_id, session_dir, session_stores, tmp_store))
     }
 
     /// Load an existing session by ID.
@@ -261,6 +554,10 @@ impl MemorySystem {
         session_id: &str,
         agent_id: Option<&str>,
     ) -> Result<SessionHandle, AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
         // Read index first — the session must be registered regardless of type.
         let idx = self.read_index()?;
         let info = idx
@@ -288,16 +585,18 @@ impl MemorySystem {
             session_stores.push(store.clone());
         }
 
-        // Update last_agent in index.
-        if let Some(agent) = agent_id {
-            let agent = agent.to_string();
-            let sid = session_id.to_string();
-            self.update_index(|idx| {
-                if let Some(info) = idx.sessions.get_mut(&sid) {
-                    info.last_agent = Some(agent);
+        // Refresh updated_at (and last_agent) in index.
+        let agent = agent_id.map(str::to_string);
+        let sid = session_id.to_string();
+        self.update_index(|idx| {
+            if let Some(info) = idx.sessions.get_mut(&sid) {
+                info.updated_at = Some(now_iso8601());
+                info.archived = false;
+                if agent.is_some() {
+                    info.last_agent = agent;
                 }
-            })?;
-        }
+            }
+        })?;
 
         info!(session_id = %session_id, agent = ?agent_id, "session loaded");
 
@@ -307,7 +606,7 @@ impl MemorySystem {
             .any(|s| s == "tmp")
             .then(|| self.tmp_store.clone());
 
-        Ok(SessionHandle::new(
+        Ok(self.open_handle(
             session_id.to_string(),
             session_dir,
             session_stores,
@@ -327,10 +626,21 @@ impl MemorySystem {
         Arc::new(stores::tmp::TmpStore::new())
     }
 
-    /// List all known sessions.
+    /// List known sessions, most recently updated first, leaving out
+    /// archived ones.
     pub fn list_sessions(&self) -> Result<Vec<SessionInfo>, AppError> {
+        let mut sessions = self.list_sessions_including_archived()?;
+        sessions.retain(|info| !info.archived);
+        Ok(sessions)
+    }
+
+    /// List all known sessions, archived ones included, most recently
+    /// updated first.
+    pub fn list_sessions_including_archived(&self) -> Result<Vec<SessionInfo>, AppError> {
         let idx = self.read_index()?;
-        Ok(idx.sessions.into_values().collect())
+        let mut sessions: Vec<SessionInfo> = idx.sessions.into_values().collect();
+        SessionSort::default().sort(&mut sessions);
+        Ok(sessions)
     }
 
     // ── Rooted session helpers ────────────────────────────────────────
@@ -354,11 +664,16 @@ impl MemorySystem {
             session_stores.push(store.clone());
         }
 
-        let session_id = uuid::Uuid::now_v7().to_string();
+        let session_id = self.ids.next_id();
         let session_dir = sessions_root.join(&session_id);
 
         let all_tmp = store_types.iter().all(|&s| s == "tmp");
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
         if !all_tmp {
+            self.make_room(sessions_root, index_path)?;
             fs::create_dir_all(&session_dir).map_err(|e| {
                 AppError::Memory(format!(
                     "cannot create session dir {}: {e}",
@@ -370,18 +685,26 @@ impl MemorySystem {
         for store in &session_stores {
             store.init(&session_dir)?;
         }
+        self.seed_working_memory(&session_stores, &session_dir, agent_id)?;
 
         let now = now_iso8601();
         let info = SessionInfo {
             session_id: session_id.clone(),
-            created_at: now,
+            created_at: now.clone(),
+            updated_at: Some(now),
             store_types: store_types.iter().map(|s| s.to_string()).collect(),
             last_agent: agent_id.map(|s| s.to_string()),
             spend: None,
+            title: None,
+            transcript_format: self.transcript_format,
+            archived: false,
         };
         Self::update_index_at(index_path, |idx| {
             idx.sessions.insert(session_id.clone(), info);
         })?;
+        if !all_tmp {
+            self.prune_agent_sessions(sessions_root, index_path, agent_id, &session_id)?;
+        }
 
         let tmp_store = store_types.contains(&"tmp").then(|| self.tmp_store.clone());
 
@@ -392,12 +715,7 @@ impl MemorySystem {
             "agent-scoped session created"
         );
 
-        Ok(SessionHandle::new(
-            session_id,
-            session_dir,
-            session_stores,
-            tmp_store,
-        ))
+        Ok(self.open_handle(session_id, session_dir, session_stores, tmp_store))
     }
 
     /// Create a session rooted at `sessions_root` using an explicit session ID.
@@ -418,6 +736,10 @@ impl MemorySystem {
             session_stores.push(store.clone());
         }
 
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
         let existing = Self::read_index_at(index_path)?;
         if existing.sessions.contains_key(session_id) {
             return Err(AppError::Memory(format!(
@@ -434,6 +756,7 @@ impl MemorySystem {
                 )));
             }
 
+            self.make_room(sessions_root, index_path)?;
             fs::create_dir_all(&session_dir).map_err(|e| {
                 AppError::Memory(format!(
                     "cannot create session dir {}: {e}",
@@ -445,18 +768,26 @@ impl MemorySystem {
         for store in &session_stores {
             store.init(&session_dir)?;
         }
+        self.seed_working_memory(&session_stores, &session_dir, agent_id)?;
 
         let now = now_iso8601();
         let info = SessionInfo {
             session_id: session_id.to_string(),
-            created_at: now,
+            created_at: now.clone(),
+            updated_at: Some(now),
             store_types: store_types.iter().map(|s| s.to_string()).collect(),
             last_agent: agent_id.map(|s| s.to_string()),
             spend: None,
+            title: None,
+            transcript_format: self.transcript_format,
+            archived: false,
         };
         Self::update_index_at(index_path, |idx| {
             idx.sessions.insert(session_id.to_string(), info);
         })?;
+        if !all_tmp {
+            self.prune_agent_sessions(sessions_root, index_path, agent_id, session_id)?;
+        }
 
         let tmp_store = store_types.contains(&"tmp").then(|| self.tmp_store.clone());
 
@@ -467,7 +798,7 @@ impl MemorySystem {
             "agent-scoped session created with explicit id"
         );
 
-        Ok(SessionHandle::new(
+        Ok(self.open_handle(
             session_id.to_string(),
             session_dir,
             session_stores,
@@ -483,12 +814,59 @@ impl MemorySystem {
         session_id: &str,
         agent_id: Option<&str>,
     ) -> Result<SessionHandle, AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
         let idx = Self::read_index_at(index_path)?;
         let info = idx
             .sessions
             .get(session_id)
             .ok_or_else(|| AppError::Memory(format!("session not found: {session_id}")))?;
+        let handle = self.open_indexed_session(sessions_root, session_id, info)?;
+
+        let agent = agent_id.map(str::to_string);
+        let sid = session_id.to_string();
+        Self::update_index_at(index_path, |idx| {
+            if let Some(info) = idx.sessions.get_mut(&sid) {
+                info.updated_at = Some(now_iso8601());
+                info.archived = false;
+                if agent.is_some() {
+                    info.last_agent = agent;
+                }
+            }
+        })?;
+        Ok(handle)
+    }
 
+    /// Open an existing session for reading, like [`Self::load_session_in`]
+    /// but without marking it used: `updated_at`, `last_agent` and
+    /// `archived` stay as they are, so looking through old sessions does
+    /// not un-archive them.
+    pub fn peek_session_in(
+        &self,
+        sessions_root: &Path,
+        index_path: &Path,
+        session_id: &str,
+    ) -> Result<SessionHandle, AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let idx = Self::read_index_at(index_path)?;
+        let info = idx
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::Memory(format!("session not found: {session_id}")))?;
+        self.open_indexed_session(sessions_root, session_id, info)
+    }
+
+    fn open_indexed_session(
+        &self,
+        sessions_root: &Path,
+        session_id: &str,
+        info: &SessionInfo,
+    ) -> Result<SessionHandle, AppError> {
         let session_dir = sessions_root.join(session_id);
         let all_tmp = info.store_types.iter().all(|s| s == "tmp");
         if !all_tmp && !session_dir.exists() {
@@ -507,23 +885,13 @@ impl MemorySystem {
             session_stores.push(store.clone());
         }
 
-        if let Some(agent) = agent_id {
-            let agent = agent.to_string();
-            let sid = session_id.to_string();
-            Self::update_index_at(index_path, |idx| {
-                if let Some(info) = idx.sessions.get_mut(&sid) {
-                    info.last_agent = Some(agent);
-                }
-            })?;
-        }
-
         let tmp_store = info
             .store_types
             .iter()
             .any(|s| s == "tmp")
             .then(|| self.tmp_store.clone());
 
-        Ok(SessionHandle::new(
+        Ok(self.open_handle(
             session_id.to_string(),
             session_dir,
             session_stores,
@@ -531,14 +899,643 @@ impl MemorySystem {
         ))
     }
 
-    /// List sessions from an arbitrary index file.
-    /// Returns an empty list if the index file does not yet exist.
+    /// Hand session `session_id` from one session root to another (from one
+    /// agent to another).  Its directory and index entry move; its id and
+    /// history stay, and `last_agent` becomes `agent_id`.  Refused while the
+    /// session has a live [`SessionHandle`] or the target already has it.
+    /// The open check and the move happen under `create_lock`, which loads
+    /// take too, so the session cannot be opened in between.
+    pub fn move_session_in(
+        &self,
+        from_root: &Path,
+        from_index: &Path,
+        to_root: &Path,
+        to_index: &Path,
+        session_id: &str,
+        agent_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut info = Self::read_index_at(from_index)?
+            .sessions
+            .remove(session_id)
+            .ok_or_else(|| AppError::Memory(format!("session not found: {session_id}")))?;
+        if Self::read_index_at(to_index)?
+            .sessions
+            .contains_key(session_id)
+        {
+            return Err(AppError::Memory(format!(
+                "session already exists: {session_id}"
+            )));
+        }
+
+        let from_dir = from_root.join(session_id);
+        if self.is_open(&from_dir) {
+            return Err(AppError::Memory(format!("session is busy: {session_id}")));
+        }
+        if from_dir.exists() {
+            let to_dir = to_root.join(session_id);
+            fs::create_dir_all(to_root)
+                .and_then(|()| fs::rename(&from_dir, &to_dir))
+                .map_err(|e| {
+                    AppError::Memory(format!(
+                        "cannot move session dir to {}: {e}",
+                        to_dir.display()
+                    ))
+                })?;
+        }
+
+        info.updated_at = Some(now_iso8601());
+        info.archived = false;
+        if let Some(agent) = agent_id {
+            info.last_agent = Some(agent.to_string());
+        }
+        Self::update_index_at(to_index, |idx| {
+            idx.sessions.insert(session_id.to_string(), info);
+        })?;
+        Self::update_index_at(from_index, |idx| {
+            idx.sessions.remove(session_id);
+        })?;
+
+        info!(session_id = %session_id, agent = ?agent_id, "session moved");
+        Ok(())
+    }
+
+    /// List sessions from an arbitrary index file, most recently updated
+    /// first.  Returns an empty list if the index file does not yet exist.
     pub fn list_sessions_in(index_path: &Path) -> Result<Vec<SessionInfo>, AppError> {
         if !index_path.exists() {
             return Ok(Vec::new());
         }
         let idx = Self::read_index_at(index_path)?;
-        Ok(idx.sessions.into_values().collect())
+        let mut sessions: Vec<SessionInfo> = idx.sessions.into_values().collect();
+        SessionSort::default().sort(&mut sessions);
+        Ok(sessions)
+    }
+
+    /// Forget the `tmp`-only session `session_id` indexed in `index_path`:
+    /// drop its index entry and free its in-memory data.  Sessions with a
+    /// persistent store are refused.
+    pub fn discard_tmp_session_in(
+        &self,
+        sessions_root: &Path,
+        index_path: &Path,
+        session_id: &str,
+    ) -> Result<(), AppError> {
+        let idx = Self::read_index_at(index_path)?;
+        let info = idx
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::Memory(format!("session not found: {session_id}")))?;
+        if !info.store_types.iter().all(|s| s == "tmp") {
+            return Err(AppError::Memory(format!(
+                "session {session_id} is not a tmp session"
+            )));
+        }
+        self.tmp_store.release(&sessions_root.join(session_id))?;
+        Self::delete_sessions(sessions_root, index_path, &[session_id.to_string()])
+    }
+
+    /// Set the title of `session_id` in the index at `index_path`.
+    pub fn set_session_title_in(
+        index_path: &Path,
+        session_id: &str,
+        title: &str,
+    ) -> Result<(), AppError> {
+        let mut found = false;
+        Self::update_index_at(index_path, |idx| {
+            if let Some(info) = idx.sessions.get_mut(session_id) {
+                info.title = Some(title.to_string());
+                found = true;
+            }
+        })?;
+        if found {
+            Ok(())
+        } else {
+            Err(AppError::Memory(format!("session not found: {session_id}")))
+        }
+    }
+
+    // ── Idle archiving ────────────────────────────────────────────────
+
+    /// Mark every disk-backed session not touched for `idle` as archived,
+    /// in the global index and in each agent's `sessions.json`.  Sessions
+    /// with a live [`SessionHandle`] are skipped.  Returns the ids newly
+    /// archived.
+    ///
+    /// With `compress_archived_transcripts`, every archived session's
+    /// transcript is then gzipped in place (see
+    /// [`BasicSessionStore::compress_transcript`](stores::basic_session::BasicSessionStore::compress_transcript)).
+    pub fn archive_idle_sessions(
+        &self,
+        idle: Duration,
+        clock: &dyn Clock,
+    ) -> Result<Vec<String>, AppError> {
+        let cutoff = iso8601_from_secs(clock.now_unix_secs().saturating_sub(idle.as_secs()));
+        let mut archived = self.archive_idle_in(&self.sessions_dir, &self.index_path(), &cutoff)?;
+
+        let agents_root = self.memory_root.join(AGENTS_DIRNAME);
+        let Ok(entries) = fs::read_dir(&agents_root) else {
+            return Ok(archived);
+        };
+        for entry in entries.flatten() {
+            let identity_dir = entry.path();
+            let index_path = identity_dir.join("sessions.json");
+            if !index_path.is_file() {
+                continue;
+            }
+            match self.archive_idle_in(&identity_dir.join("sessions"), &index_path, &cutoff) {
+                Ok(ids) => archived.extend(ids),
+                Err(e) => warn!(index = %index_path.display(), "idle archive sweep failed: {e}"),
+            }
+        }
+        Ok(archived)
+    }
+
+    /// Archive sessions in one index last touched before `cutoff`.
+    ///
+    /// Holds `create_lock` throughout, so no session is opened between its
+    /// open check and its archiving or compression.
+    fn archive_idle_in(
+        &self,
+        sessions_root: &Path,
+        index_path: &Path,
+        cutoff: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let idx = Self::read_index_at(index_path)?;
+        let idle: Vec<String> = idx
+            .sessions
+            .values()
+            .filter(|info| !info.archived && info.last_touched() < cutoff)
+            .filter(|info| !info.store_types.iter().all(|s| s == "tmp"))
+            .filter(|info| !self.is_open(&sessions_root.join(&info.session_id)))
+            .map(|info| info.session_id.clone())
+            .collect();
+        if !idle.is_empty() {
+            Self::update_index_at(index_path, |idx| {
+                for session_id in &idle {
+                    if let Some(info) = idx.sessions.get_mut(session_id) {
+                        info.archived = true;
+                    }
+                }
+            })?;
+            for session_id in &idle {
+                info!(session_id = %session_id, "idle session archived");
+            }
+        }
+        if self.compress_archived_transcripts {
+            let archived = idx
+                .sessions
+                .values()
+                .filter(|info| info.archived || idle.contains(&info.session_id))
+                .filter(|info| info.store_types.iter().any(|s| s == "basic_session"));
+            for info in archived {
+                let session_dir = sessions_root.join(&info.session_id);
+                if self.is_open(&session_dir) {
+                    continue;
+                }
+                match stores::basic_session::BasicSessionStore::compress_transcript(&session_dir) {
+                    Ok(true) => {
+                        debug!(session_id = %info.session_id, "archived transcript compressed")
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!(session_id = %info.session_id, "transcript compression failed: {e}")
+                    }
+                }
+            }
+        }
+        Ok(idle)
+    }
+
+    /// Spawn a task that runs [`archive_idle_sessions`](Self::archive_idle_sessions)
+    /// every minute until `shutdown` is cancelled.
+    pub fn spawn_idle_archiver(
+        memory: Arc<Self>,
+        idle: Duration,
+        clock: Arc<dyn Clock>,
+        shutdown: tokio_util::sync::CancellationToken,
+    ) {
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(IDLE_SWEEP_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tick.tick() => {
+                        if let Err(e) = memory.archive_idle_sessions(idle, clock.as_ref()) {
+                            warn!("idle session sweep failed: {e}");
+                        }
+                    }
+                }
+            }
+        });
+        info!(idle_secs = idle.as_secs(), "idle session archiver started");
+    }
+
+    // ── Spend ─────────────────────────────────────────────────────────
+
+    /// Sum the `spend.json` of every session in the bot-wide index and in
+    /// each agent's own, archived ones included.
+    pub fn aggregate_spend(&self) -> Result<SpendTotals, AppError> {
+        let mut totals = SpendTotals::default();
+        Self::aggregate_spend_in(&self.sessions_dir, &self.index_path(), &mut totals)?;
+
+        let agents_root = self.memory_root.join(AGENTS_DIRNAME);
+        let Ok(entries) = fs::read_dir(&agents_root) else {
+            return Ok(totals);
+        };
+        for entry in entries.flatten() {
+            let identity_dir = entry.path();
+            let index_path = identity_dir.join("sessions.json");
+            if !index_path.is_file() {
+                continue;
+            }
+            if let Err(e) =
+                Self::aggregate_spend_in(&identity_dir.join("sessions"), &index_path, &mut totals)
+            {
+                warn!(index = %index_path.display(), "spend aggregation failed: {e}");
+            }
+        }
+        Ok(totals)
+    }
+
+    fn aggregate_spend_in(
+        sessions_root: &Path,
+        index_path: &Path,
+        totals: &mut SpendTotals,
+    ) -> Result<(), AppError> {
+        for info in Self::list_sessions_in(index_path)? {
+            let session_dir = sessions_root.join(&info.session_id);
+            match handle::read_spend_blocking(&session_dir) {
+                Ok(Some(spend)) => totals.add(&spend),
+                Ok(None) => {}
+                Err(e) => warn!(session_id = %info.session_id, "skipping spend: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Write [`aggregate_spend`](Self::aggregate_spend) to
+    /// `{dir}/{YYYY-MM-DD}.json` for the current UTC date, replacing that
+    /// day's earlier snapshot.  The file is written beside and renamed into
+    /// place, so a crash leaves the previous snapshot intact.
+    pub fn write_spend_snapshot(&self, dir: &Path, clock: &dyn Clock) -> Result<PathBuf, AppError> {
+        let now = clock.now_unix_secs();
+        let totals = self.aggregate_spend()?;
+        let snapshot = serde_json::json!({
+            "taken_at": iso8601_from_secs(now),
+            "totals": totals,
+        });
+        let data = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| AppError::Memory(format!("serialize spend snapshot: {e}")))?;
+
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::Memory(format!("cannot create {}: {e}", dir.display())))?;
+        let path = dir.join(format!("{}.json", clock::utc_date(now)));
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, data)
+            .and_then(|()| fs::rename(&tmp, &path))
+            .map_err(|e| AppError::Memory(format!("write {}: {e}", path.display())))?;
+        Ok(path)
+    }
+
+    /// Spawn a task that runs [`write_spend_snapshot`](Self::write_spend_snapshot)
+    /// every `every` until `shutdown` is cancelled.
+    pub fn spawn_spend_snapshots(
+        memory: Arc<Self>,
+        dir: PathBuf,
+        every: Duration,
+        clock: Arc<dyn Clock>,
+        shutdown: tokio_util::sync::CancellationToken,
+    ) {
+        info!(every_secs = every.as_secs(), dir = %dir.display(), "spend snapshots started");
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(every);
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tick.tick() => {
+                        let (memory, dir, clock) = (memory.clone(), dir.clone(), clock.clone());
+                        let written = tokio::task::spawn_blocking(move || {
+                            memory.write_spend_snapshot(&dir, clock.as_ref())
+                        })
+                        .await;
+                        match written {
+                            Ok(Ok(_)) => {}
+                            Ok(Err(e)) => warn!("spend snapshot failed: {e}"),
+                            Err(e) => warn!("spend snapshot task failed: {e}"),
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    // ── Backup ────────────────────────────────────────────────────────
+
+    /// Stream the whole memory root to `out` as a `.tar.gz` (see
+    /// [`archive`]).  Sessions being written meanwhile may be captured
+    /// mid-turn; pause traffic (maintenance mode) for a clean snapshot.
+    pub fn export_archive(&self, out: impl std::io::Write) -> Result<ArchiveSummary, AppError> {
+        let summary = archive::export_tree(&self.memory_root, out)?;
+        info!(
+            files = summary.files,
+            bytes = summary.bytes,
+            "memory exported"
+        );
+        Ok(summary)
+    }
+
+    /// Replace this (fresh) memory root with the tree in a `.tar.gz` made
+    /// by [`export_archive`](Self::export_archive).  Refused while any
+    /// session is open, and when the root or any agent under it already
+    /// indexes sessions.  The archive is unpacked and validated beside the
+    /// root first, so a bad archive leaves the root untouched; the replaced
+    /// root is kept as `{root}.pre-import-{unix time}`.
+    ///
+    /// Agents must be idle meanwhile (the `memory/import` handler requires
+    /// maintenance mode), and they read their identities at startup:
+    /// restart the bot afterwards.
+    pub fn import_archive(&self, input: impl std::io::Read) -> Result<ArchiveSummary, AppError> {
+        let _creating = self
+            .create_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.any_session_open() {
+            return Err(AppError::Memory(
+                "sessions are open; import needs a fresh bot".to_string(),
+            ));
+        }
+        let existing = self.indexed_session_count()?;
+        if existing > 0 {
+            return Err(AppError::Memory(format!(
+                "memory root already has {existing} session(s); import needs a fresh bot"
+            )));
+        }
+
+        let sibling = |suffix: &str| {
+            let mut name = self.memory_root.file_name().unwrap_or_default().to_owned();
+            name.push(suffix);
+            self.memory_root.with_file_name(name)
+        };
+        let staging = sibling(".import");
+        let backup = sibling(&format!(".pre-import-{}", SystemClock.now_unix_secs()));
+        if backup.exists() {
+            return Err(AppError::Memory(format!(
+                "{} already exists; try again",
+                backup.display()
+            )));
+        }
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(|e| {
+                AppError::Memory(format!("cannot remove {}: {e}", staging.display()))
+            })?;
+        }
+
+        let summary = archive::unpack_tree(input, &staging)?;
+        fs::rename(&self.memory_root, &backup).map_err(|e| {
+            let _ = fs::remove_dir_all(&staging);
+            AppError::Memory(format!(
+                "cannot move {} aside: {e}",
+                self.memory_root.display()
+            ))
+        })?;
+        if let Err(e) = fs::rename(&staging, &self.memory_root) {
+            let _ = fs::rename(&backup, &self.memory_root);
+            let _ = fs::remove_dir_all(&staging);
+            return Err(AppError::Memory(format!(
+                "cannot move imported tree into place: {e}"
+            )));
+        }
+        info!(
+            files = summary.files,
+            bytes = summary.bytes,
+            backup = %backup.display(),
+            "memory imported"
+        );
+        Ok(summary)
+    }
+
+    /// Sessions in the bot-wide index and every agent's own index.
+    fn indexed_session_count(&self) -> Result<usize, AppError> {
+        let mut count = self.read_index()?.sessions.len();
+        let Ok(entries) = fs::read_dir(self.memory_root.join(AGENTS_DIRNAME)) else {
+            return Ok(count);
+        };
+        for entry in entries.flatten() {
+            let index_path = entry.path().join("sessions.json");
+            if index_path.is_file() {
+                count += Self::read_index_at(&index_path)?.sessions.len();
+            }
+        }
+        Ok(count)
+    }
+
+    // ── Session cap ───────────────────────────────────────────────────
+
+    /// Enforce `max_sessions` before a new disk-backed session is created in
+    /// `sessions_root`.  Under [`SessionOverflow::Evict`] the least-recently
+    /// updated disk-backed sessions are deleted until one slot is free; under
+    /// [`SessionOverflow::Reject`] an error is returned instead.
+    ///
+    /// Sessions with a live [`SessionHandle`] are never evicted; if they alone
+    /// fill the cap the create fails.  Callers hold `create_lock`.
+    fn make_room(&self, sessions_root: &Path, index_path: &Path) -> Result<(), AppError> {
+        let Some(max) = self.max_sessions.filter(|&n| n > 0) else {
+            return Ok(());
+        };
+        let idx = Self::read_index_at(index_path)?;
+        let disk_backed: Vec<&SessionInfo> = idx
+            .sessions
+            .values()
+            .filter(|info| !info.store_types.iter().all(|s| s == "tmp"))
+            .collect();
+        if disk_backed.len() < max {
+            return Ok(());
+        }
+        if self.session_overflow == SessionOverflow::Reject {
+            return Err(AppError::Memory(format!(
+                "session limit reached ({max}); not creating a new session"
+            )));
+        }
+
+        let excess = disk_backed.len() + 1 - max;
+        let mut candidates: Vec<&SessionInfo> = disk_backed
+            .into_iter()
+            .filter(|info| !self.is_open(&sessions_root.join(&info.session_id)))
+            .collect();
+        if candidates.len() < excess {
+            return Err(AppError::Memory(format!(
+                "session limit reached ({max}) and all sessions are in use"
+            )));
+        }
+
+        // Oldest first; UUIDv7 ids break timestamp ties in creation order.
+        candidates.sort_by(|a, b| {
+            a.last_touched()
+                .cmp(b.last_touched())
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        let evict: Vec<String> = candidates[..excess]
+            .iter()
+            .map(|info| info.session_id.clone())
+            .collect();
+
+        for session_id in &evict {
+            info!(session_id = %session_id, max_sessions = max, "session evicted");
+        }
+        Self::delete_sessions(sessions_root, index_path, &evict)
+    }
+
+    /// Enforce `agent_id`'s `[agents.<id>] max_sessions` once `new_session`
+    /// is indexed: the agent's oldest disk-backed sessions past the limit
+    /// are deleted, dir and index entry.  Only sessions whose `last_agent`
+    /// is `agent_id` count, and open ones are left alone.  Callers hold
+    /// `create_lock`.
+    fn prune_agent_sessions(
+        &self,
+        sessions_root: &Path,
+        index_path: &Path,
+        agent_id: Option<&str>,
+        new_session: &str,
+    ) -> Result<(), AppError> {
+        let Some((agent, max)) = agent_id.and_then(|agent| {
+            self.agent_max_sessions
+                .get(agent)
+                .filter(|&&n| n > 0)
+                .map(|&max| (agent, max))
+        }) else {
+            return Ok(());
+        };
+        let idx = Self::read_index_at(index_path)?;
+        let mut owned: Vec<&SessionInfo> = idx
+            .sessions
+            .values()
+            .filter(|info| {
+                info.last_agent.as_deref() == Some(agent)
+                    && !info.store_types.iter().all(|s| s == "tmp")
+            })
+            .collect();
+        let excess = owned.len().saturating_sub(max);
+        if excess == 0 {
+            return Ok(());
+        }
+
+        // Oldest first, as for `max_sessions`.
+        owned.sort_by(|a, b| {
+            a.last_touched()
+                .cmp(b.last_touched())
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        let prune: Vec<String> = owned
+            .into_iter()
+            .filter(|info| {
+                info.session_id != new_session
+                    && !self.is_open(&sessions_root.join(&info.session_id))
+            })
+            .take(excess)
+            .map(|info| info.session_id.clone())
+            .collect();
+        for session_id in &prune {
+            info!(session_id = %session_id, agent, max_sessions = max, "agent session pruned");
+        }
+        Self::delete_sessions(sessions_root, index_path, &prune)
+    }
+
+    /// Delete `session_ids` from `sessions_root` and `index_path`.
+    fn delete_sessions(
+        sessions_root: &Path,
+        index_path: &Path,
+        session_ids: &[String],
+    ) -> Result<(), AppError> {
+        if session_ids.is_empty() {
+            return Ok(());
+        }
+        for session_id in session_ids {
+            let dir = sessions_root.join(session_id);
+            if let Err(e) = fs::remove_dir_all(&dir)
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!(session_id = %session_id, "cannot remove deleted session dir: {e}");
+            }
+        }
+        Self::update_index_at(index_path, |idx| {
+            for session_id in session_ids {
+                idx.sessions.remove(session_id);
+            }
+        })
+    }
+
+    /// Write `agent_id`'s configured seed into a new session's working
+    /// memory.  Callers have just initialised `stores` for `session_dir`.
+    fn seed_working_memory(
+        &self,
+        stores: &[Arc<dyn SessionStore>],
+        session_dir: &Path,
+        agent_id: Option<&str>,
+    ) -> Result<(), AppError> {
+        let seed = agent_id.and_then(|agent| self.seed_memory.get(agent));
+        match (seed, stores.first()) {
+            (Some(seed), Some(store)) => {
+                store.kv_set(session_dir, handle::WORKING_MEMORY_KEY, seed)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // ── Open-session tracking ─────────────────────────────────────────
+
+    /// Build a handle and remember it as open until its last clone drops.
+    fn open_handle(
+        &self,
+        session_id: String,
+        session_dir: PathBuf,
+        stores: Vec<Arc<dyn SessionStore>>,
+        tmp_store: Option<Arc<stores::tmp::TmpStore>>,
+    ) -> SessionHandle {
+        let handle = SessionHandle::new(
+            session_id,
+            session_dir.clone(),
+            stores,
+            tmp_store,
+            self.write_fallback.clone(),
+        );
+        let mut open = self
+            .open_sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        open.retain(|_, handles| {
+            handles.retain(|rw| rw.strong_count() > 0);
+            !handles.is_empty()
+        });
+        open.entry(session_dir)
+            .or_default()
+            .push(handle.downgrade());
+        handle
+    }
+
+    /// Whether any session has a live handle.
+    fn any_session_open(&self) -> bool {
+        self.open_sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .flatten()
+            .any(|rw| rw.strong_count() > 0)
+    }
+
+    fn is_open(&self, session_dir: &Path) -> bool {
+        self.open_sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(session_dir)
+            .is_some_and(|handles| handles.iter().any(|rw| rw.strong_count() > 0))
     }
 
     // ── Index helpers ─────────────────────────────────────────────────
@@ -583,10 +1580,11 @@ impl std::fmt::Debug for MemorySystem {
 
 /// ISO-8601 UTC timestamp without external crate.
 fn now_iso8601() -> String {
-    let d = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default();
-    let secs = d.as_secs();
+    iso8601_from_secs(SystemClock.now_unix_secs())
+}
+
+/// Format seconds since the Unix epoch as an ISO-8601 UTC timestamp.
+fn iso8601_from_secs(secs: u64) -> String {
     let s = secs % 60;
     let total_min = secs / 60;
     let m = total_min % 60;
@@ -671,6 +1669,221 @@ mod tests {
         assert_eq!(sessions[0].last_agent, Some("chat".into()));
     }
 
+    #[test]
+    fn id_generator_names_sessions_in_order() {
+        let dir = TempDir::new().unwrap();
+        let mem = MemorySystem::new(dir.path(), MemoryConfig::default())
+            .unwrap()
+            .with_id_generator(Arc::new(ids::SequentialIds::new("s-")));
+
+        let first = mem.create_session(&["basic_session"], None).unwrap();
+        let second = mem.create_session(&["tmp"], None).unwrap();
+        let agent_index = dir.path().join("sessions.json");
+        fs::write(&agent_index, r#"{"sessions":{}}"#).unwrap();
+        let scoped = mem
+            .create_session_in(&dir.path().join("sessions"), &agent_index, &["tmp"], None)
+            .unwrap();
+
+        assert_eq!(first.session_id, "s-000001");
+        assert_eq!(second.session_id, "s-000002");
+        assert_eq!(scoped.session_id, "s-000003");
+        assert!(mem.sessions_dir.join("s-000001").is_dir());
+        assert!(mem.load_session("s-000002", None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn new_sessions_for_a_seeded_agent_start_with_its_working_memory() {
+        let dir = TempDir::new().unwrap();
+        let seed = "- name: Ada\n- timezone: Europe/London";
+        let config = MemoryConfig {
+            seed_memory: HashMap::from([("chat".to_string(), seed.to_string())]),
+            ..MemoryConfig::default()
+        };
+        let mem = MemorySystem::new(dir.path(), config).unwrap();
+
+        let seeded = mem
+            .create_session(&["basic_session"], Some("chat"))
+            .unwrap();
+        assert_eq!(seeded.working_memory_read().await.unwrap(), seed);
+        let tmp = mem.create_session(&["tmp"], Some("chat")).unwrap();
+        assert_eq!(tmp.working_memory_read().await.unwrap(), seed);
+        let other = mem
+            .create_session(&["basic_session"], Some("docs"))
+            .unwrap();
+        assert_eq!(other.working_memory_read().await.unwrap(), "");
+
+        // Loading an existing session does not re-seed it.
+        seeded.working_memory_write("edited").await.unwrap();
+        let reloaded = mem.load_session(&seeded.session_id, Some("chat")).unwrap();
+        assert_eq!(reloaded.working_memory_read().await.unwrap(), "edited");
+    }
+
+    #[tokio::test]
+    async fn diagnostic_sessions_are_unindexed_and_released_on_drop() {
+        let (_dir, mem) = setup();
+        let labels = || mem.tmp_store.inner().labels().unwrap().len();
+        let before = labels();
+        let diag = mem.create_diagnostic_session(Some("selftest")).unwrap();
+        let clone = diag.clone();
+
+        diag.kv_set("probe", "ping").await.unwrap();
+        assert_eq!(
+            clone.kv_get("probe").await.unwrap().as_deref(),
+            Some("ping")
+        );
+        assert!(!mem.sessions_dir.join(&diag.session_id).exists());
+        assert!(
+            !mem.read_index()
+                .unwrap()
+                .sessions
+                .contains_key(&diag.session_id)
+        );
+        assert!(mem.load_session(&diag.session_id, None).is_err());
+
+        drop(diag);
+        assert!(labels() > before, "released only with the last clone");
+        drop(clone);
+        assert_eq!(labels(), before);
+    }
+
+    #[test]
+    fn discarding_a_tmp_session_frees_its_entry_and_data() {
+        let (dir, mem) = setup();
+        let root = dir.path().join("agent_sessions");
+        let index = dir.path().join("agent_sessions.json");
+        fs::write(&index, r#"{"sessions":{}}"#).unwrap();
+        let tmp = mem
+            .create_session_in(&root, &index, &["tmp"], Some("echo"))
+            .unwrap();
+        let kept = mem
+            .create_session_in(&root, &index, &["basic_session"], Some("echo"))
+            .unwrap();
+        let labels = || mem.tmp_store.inner().labels().unwrap().len();
+        let before = labels();
+
+        mem.discard_tmp_session_in(&root, &index, &tmp.session_id)
+            .unwrap();
+        assert_eq!(labels(), before - 2);
+        let listed: Vec<String> = MemorySystem::list_sessions_in(&index)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        assert_eq!(listed, std::slice::from_ref(&kept.session_id));
+
+        let err = mem
+            .discard_tmp_session_in(&root, &index, &kept.session_id)
+            .unwrap_err();
+        assert!(err.to_string().contains("not a tmp session"), "{err}");
+        assert!(root.join(&kept.session_id).is_dir());
+    }
+
+    #[tokio::test]
+    async fn moved_session_keeps_its_transcript_under_the_new_agent() {
+        let (dir, mem) = setup();
+        let root = |agent: &str| {
+            let identity = dir.path().join(agent);
+            fs::create_dir_all(&identity).unwrap();
+            fs::write(identity.join("sessions.json"), r#"{"sessions":{}}"#).unwrap();
+            (identity.join("sessions"), identity.join("sessions.json"))
+        };
+        let (chat_root, chat_index) = root("chat");
+        let (docs_root, docs_index) = root("docs");
+
+        let handle = mem
+            .create_session_in(&chat_root, &chat_index, &["basic_session"], Some("chat"))
+            .unwrap();
+        let sid = handle.session_id.clone();
+        handle.transcript_append("user", "hello").await.unwrap();
+        let move_to_docs = || {
+            mem.move_session_in(
+                &chat_root,
+                &chat_index,
+                &docs_root,
+                &docs_index,
+                &sid,
+                Some("docs"),
+            )
+        };
+        // Not while the session is in use.
+        assert!(move_to_docs().is_err());
+        drop(handle);
+
+        move_to_docs().unwrap();
+        assert!(
+            MemorySystem::list_sessions_in(&chat_index)
+                .unwrap()
+                .is_empty()
+        );
+        let moved = MemorySystem::list_sessions_in(&docs_index).unwrap();
+        assert_eq!(moved[0].last_agent.as_deref(), Some("docs"));
+
+        let loaded = mem
+            .load_session_in(&docs_root, &docs_index, &sid, Some("docs"))
+            .unwrap();
+        loaded.transcript_append("assistant", "hi").await.unwrap();
+        let transcript = loaded.transcript_read_last(10).await.unwrap();
+        assert_eq!(transcript.len(), 2);
+        assert_eq!(transcript[0].content, "hello");
+    }
+
+    #[test]
+    fn a_session_is_never_moved_out_from_under_a_load() {
+        let (dir, mem) = setup();
+        let mem = Arc::new(mem);
+        let root = |agent: &str| {
+            let identity = dir.path().join(agent);
+            fs::create_dir_all(&identity).unwrap();
+            fs::write(identity.join("sessions.json"), r#"{"sessions":{}}"#).unwrap();
+            (identity.join("sessions"), identity.join("sessions.json"))
+        };
+        let (chat_root, chat_index) = root("chat");
+        let (docs_root, docs_index) = root("docs");
+        let sid = mem
+            .create_session_in(&chat_root, &chat_index, &["basic_session"], Some("chat"))
+            .unwrap()
+            .session_id
+            .clone();
+
+        for _ in 0..100 {
+            let mover = {
+                let mem = mem.clone();
+                let (chat_root, chat_index) = (chat_root.clone(), chat_index.clone());
+                let (docs_root, docs_index) = (docs_root.clone(), docs_index.clone());
+                let sid = sid.clone();
+                std::thread::spawn(move || {
+                    mem.move_session_in(
+                        &chat_root,
+                        &chat_index,
+                        &docs_root,
+                        &docs_index,
+                        &sid,
+                        Some("docs"),
+                    )
+                    .is_ok()
+                })
+            };
+            let loaded = mem.load_session_in(&chat_root, &chat_index, &sid, Some("chat"));
+            let moved = mover.join().unwrap();
+            assert!(
+                loaded.is_ok() != moved,
+                "loaded: {loaded:?}, moved: {moved}"
+            );
+            drop(loaded);
+            if moved {
+                mem.move_session_in(
+                    &docs_root,
+                    &docs_index,
+                    &chat_root,
+                    &chat_index,
+                    &sid,
+                    Some("chat"),
+                )
+                .unwrap();
+            }
+        }
+    }
+
     #[test]
     fn load_session_works() {
         let (_dir, mem) = setup();
@@ -683,6 +1896,37 @@ mod tests {
         assert_eq!(loaded.session_id, sid);
     }
 
+    #[test]
+    fn jsonl_transcript_format_is_recorded_per_session() {
+        let dir = TempDir::new().unwrap();
+        let md = MemorySystem::new(dir.path(), MemoryConfig::default()).unwrap();
+        let md_id = closed_session(&md);
+        let jsonl = MemorySystem::new(
+            dir.path(),
+            MemoryConfig {
+                transcript_format: TranscriptFormat::Jsonl,
+                ..MemoryConfig::default()
+            },
+        )
+        .unwrap();
+        let jsonl_id = closed_session(&jsonl);
+
+        let jsonl_dir = jsonl.sessions_dir.join(&jsonl_id);
+        assert!(jsonl_dir.join("transcript.jsonl").exists());
+        assert!(!jsonl_dir.join("transcript.md").exists());
+
+        let format_of = |id: &str| {
+            md.list_sessions()
+                .unwrap()
+                .into_iter()
+                .find(|s| s.session_id == id)
+                .unwrap()
+                .transcript_format
+        };
+        assert_eq!(format_of(&md_id), TranscriptFormat::Md);
+        assert_eq!(format_of(&jsonl_id), TranscriptFormat::Jsonl);
+    }
+
     #[test]
     fn load_nonexistent_session_errors() {
         let (_dir, mem) = setup();
@@ -784,4 +2028,559 @@ mod tests {
             "new standalone store should be empty"
         );
     }
+
+    // ── namespaces ────────────────────────────────────────────────────
+
+    fn namespaced(identity_dir: &Path, namespace: Option<&str>) -> MemorySystem {
+        let config = MemoryConfig {
+            namespace: namespace.map(str::to_string),
+            ..MemoryConfig::default()
+        };
+        MemorySystem::new(identity_dir, config).unwrap()
+    }
+
+    #[test]
+    fn namespaces_do_not_share_sessions() {
+        let dir = TempDir::new().unwrap();
+        let a = namespaced(dir.path(), Some("persona-a"));
+        let b = namespaced(dir.path(), Some("persona-b"));
+        let default = namespaced(dir.path(), None);
+
+        let a_id = closed_session(&a);
+        let b_id = closed_session(&b);
+
+        assert_eq!(a.memory_root(), dir.path().join("memory-persona-a"));
+        assert_eq!(default.memory_root(), dir.path().join("memory"));
+
+        let ids = |mem: &MemorySystem| -> Vec<String> {
+            mem.list_sessions()
+                .unwrap()
+                .into_iter()
+                .map(|s| s.session_id)
+                .collect()
+        };
+        assert_eq!(ids(&a), std::slice::from_ref(&a_id));
+        assert_eq!(ids(&b), std::slice::from_ref(&b_id));
+        assert!(ids(&default).is_empty());
+        assert!(b.load_session(&a_id, None).is_err());
+        assert!(a.load_session(&b_id, None).is_err());
+    }
+
+    // ── max_sessions ──────────────────────────────────────────────────
+
+    fn capped(max: usize, overflow: SessionOverflow) -> (TempDir, MemorySystem) {
+        let dir = TempDir::new().unwrap();
+        let config = MemoryConfig {
+            max_sessions: Some(max),
+            session_overflow: overflow,
+            ..MemoryConfig::default()
+        };
+        let mem = MemorySystem::new(dir.path(), config).unwrap();
+        (dir, mem)
+    }
+
+    /// Create a disk-backed session and drop its handle, so it can be evicted.
+    fn closed_session(mem: &MemorySystem) -> String {
+        mem.create_session(&["basic_session"], None)
+            .unwrap()
+            .session_id
+    }
+
+    fn set_updated_at(mem: &MemorySystem, session_id: &str, ts: &str) {
+        mem.update_index(|idx| {
+            idx.sessions.get_mut(session_id).unwrap().updated_at = Some(ts.to_string());
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn max_sessions_evicts_least_recently_updated() {
+        let (_dir, mem) = capped(3, SessionOverflow::Evict);
+        let a = closed_session(&mem);
+        let b = closed_session(&mem);
+        let c = closed_session(&mem);
+        set_updated_at(&mem, &a, "2000-01-03T00:00:00Z");
+        set_updated_at(&mem, &b, "2000-01-01T00:00:00Z");
+        set_updated_at(&mem, &c, "2000-01-02T00:00:00Z");
+
+        let d = closed_session(&mem);
+        let mut ids: Vec<String> = mem
+            .list_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        ids.sort();
+        let mut expected = vec![a, c.clone(), d];
+        expected.sort();
+        assert_eq!(ids, expected);
+        assert!(!mem.sessions_dir.join(&b).exists());
+
+        // Next eviction takes the next-oldest.
+        closed_session(&mem);
+        assert!(mem.load_session(&c, None).is_err());
+        assert_eq!(mem.list_sessions().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sessions_are_listed_newest_first() {
+        let (_dir, mem) = setup();
+        let a = closed_session(&mem);
+        let b = closed_session(&mem);
+        let c = closed_session(&mem);
+        set_updated_at(&mem, &a, "2000-01-02T00:00:00Z");
+        set_updated_at(&mem, &b, "2000-01-03T00:00:00Z");
+        set_updated_at(&mem, &c, "2000-01-02T00:00:00Z");
+
+        let ids = |sessions: &[SessionInfo]| -> Vec<String> {
+            sessions.iter().map(|s| s.session_id.clone()).collect()
+        };
+        // `a` and `c` tie on `updated_at`; `session_id` orders them.
+        let (first_tied, second_tied) = if a < c { (&a, &c) } else { (&c, &a) };
+        let expected = [b.clone(), first_tied.clone(), second_tied.clone()];
+        for _ in 0..5 {
+            assert_eq!(ids(&mem.list_sessions().unwrap()), expected);
+        }
+
+        let mut sessions = mem.list_sessions().unwrap();
+        SessionSort::parse("session_id")
+            .unwrap()
+            .sort(&mut sessions);
+        let mut by_id = vec![a, b, c];
+        by_id.sort();
+        assert_eq!(ids(&sessions), by_id);
+        assert_eq!(SessionSort::parse("size"), None);
+    }
+
+    #[test]
+    fn load_session_refreshes_lru_position() {
+        let (_dir, mem) = capped(2, SessionOverflow::Evict);
+        let a = closed_session(&mem);
+        let b = closed_session(&mem);
+        set_updated_at(&mem, &a, "2000-01-01T00:00:00Z");
+        set_updated_at(&mem, &b, "2000-01-02T00:00:00Z");
+
+        // Touch `a`, so `b` becomes the eviction candidate.
+        mem.load_session(&a, None).unwrap();
+        closed_session(&mem);
+        assert!(mem.load_session(&a, None).is_ok());
+        assert!(mem.load_session(&b, None).is_err());
+    }
+
+    #[test]
+    fn eviction_skips_sessions_that_are_open() {
+        let (_dir, mem) = capped(2, SessionOverflow::Evict);
+        let open = mem.create_session(&["basic_session"], None).unwrap();
+        let closed = closed_session(&mem);
+        set_updated_at(&mem, &open.session_id, "2000-01-01T00:00:00Z");
+        set_updated_at(&mem, &closed, "2000-01-02T00:00:00Z");
+
+        // `open` is older but still held, so `closed` goes instead.
+        let newer = mem.create_session(&["basic_session"], None).unwrap();
+        assert!(mem.load_session(&open.session_id, None).is_ok());
+        assert!(mem.load_session(&closed, None).is_err());
+
+        // With every session held there is nothing to evict.
+        let err = mem
+            .create_session(&["basic_session"], None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("in use"), "got: {err}");
+        drop((open, newer));
+        assert!(mem.create_session(&["basic_session"], None).is_ok());
+    }
+
+    #[test]
+    fn concurrent_creates_respect_the_cap() {
+        let (_dir, mem) = capped(3, SessionOverflow::Reject);
+        let created = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| mem.create_session(&["basic_session"], None).is_ok()))
+                .collect();
+            workers
+                .into_iter()
+                .map(|w| w.join().unwrap())
+                .filter(|&ok| ok)
+                .count()
+        });
+        assert_eq!(created, 3);
+        assert_eq!(mem.list_sessions().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn max_sessions_reject_policy_errors_and_keeps_existing() {
+        let (_dir, mem) = capped(2, SessionOverflow::Reject);
+        mem.create_session(&["basic_session"], None).unwrap();
+        mem.create_session(&["basic_session"], None).unwrap();
+
+        let err = mem
+            .create_session(&["basic_session"], None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("session limit reached"), "got: {err}");
+        assert_eq!(mem.list_sessions().unwrap().len(), 2);
+
+        // In-memory sessions do not count against the cap.
+        assert!(mem.create_session(&["tmp"], None).is_ok());
+    }
+
+    #[test]
+    fn agent_max_sessions_prunes_only_that_agents_oldest_sessions() {
+        let dir = TempDir::new().unwrap();
+        let config = MemoryConfig {
+            agent_max_sessions: HashMap::from([("news".to_string(), 2)]),
+            ..MemoryConfig::default()
+        };
+        let mem = MemorySystem::new(dir.path(), config)
+            .unwrap()
+            .with_id_generator(Arc::new(ids::SequentialIds::new("s-")));
+        let create = |agent| {
+            mem.create_session(&["basic_session"], Some(agent))
+                .unwrap()
+                .session_id
+        };
+        let first = create("news");
+        let chat = create("chat");
+        let second = create("news");
+        set_updated_at(&mem, &first, "2000-01-01T00:00:00Z");
+        set_updated_at(&mem, &chat, "1999-01-01T00:00:00Z");
+        set_updated_at(&mem, &second, "2000-01-02T00:00:00Z");
+
+        let third = create("news");
+        let fourth = create("news");
+        let mut ids: Vec<String> = mem
+            .list_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec![chat.clone(), third, fourth]);
+        assert!(!mem.sessions_dir.join(&first).exists());
+        assert!(!mem.sessions_dir.join(&second).exists());
+        // The other agent's older session is untouched.
+        assert!(mem.sessions_dir.join(&chat).is_dir());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_spend_on_one_session_loses_no_turns() {
+        use araliya_core::types::llm::{LlmUsage, ModelRates};
+
+        let (_dir, mem) = setup();
+        let session = mem.create_session(&["basic_session"], None).unwrap();
+        let other = mem.load_session(&session.session_id, None).unwrap();
+        let usage = LlmUsage {
+            output_tokens: 1,
+            ..Default::default()
+        };
+
+        let turns: Vec<_> = (0..32)
+            .map(|i| {
+                let handle = if i % 2 == 0 {
+                    session.clone()
+                } else {
+                    other.clone()
+                };
+                let usage = usage.clone();
+                tokio::spawn(async move {
+                    handle
+                        .accumulate_spend_at(&usage, "m", &ModelRates::default(), 0)
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for turn in turns {
+            turn.await.unwrap();
+        }
+
+        let spend = session.read_spend().await.unwrap().unwrap();
+        assert_eq!(spend.total_output_tokens, 32);
+        let session_dir = mem.sessions_dir.join(&session.session_id);
+        assert!(!session_dir.join("spend.json.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn spend_snapshot_task_writes_daily_totals() {
+        use araliya_core::types::llm::{LlmUsage, ModelRates};
+
+        let (dir, mem) = setup();
+        // 2026-01-01T00:00:00Z
+        let clock = Arc::new(clock::MockClock::new(1_767_225_600));
+        let rates = ModelRates {
+            input_per_million_usd: 1.0,
+            output_per_million_usd: 2.0,
+            cached_input_per_million_usd: 0.0,
+        };
+        let usage = |input_tokens, output_tokens| LlmUsage {
+            input_tokens,
+            output_tokens,
+            cached_input_tokens: 0,
+            reasoning_tokens: 0,
+            model: None,
+        };
+
+        let a = mem.create_session(&["basic_session"], None).unwrap();
+        a.accumulate_spend_at(&usage(1_000_000, 500_000), "model-a", &rates, 0)
+            .await
+            .unwrap();
+        a.accumulate_spend_at(&usage(1_000_000, 0), "model-b", &rates, 0)
+            .await
+            .unwrap();
+        let identity_dir = mem.memory_root().join(AGENTS_DIRNAME).join("chat-abc");
+        let agent_index = identity_dir.join("sessions.json");
+        fs::create_dir_all(&identity_dir).unwrap();
+        fs::write(&agent_index, r#"{"sessions":{}}"#).unwrap();
+        let b = mem
+            .create_session_in(
+                &identity_dir.join("sessions"),
+                &agent_index,
+                &["basic_session"],
+                None,
+            )
+            .unwrap();
+        b.accumulate_spend_at(&usage(0, 1_000_000), "model-a", &rates, 0)
+            .await
+            .unwrap();
+        // A session without spend is not counted.
+        mem.create_session(&["basic_session"], None).unwrap();
+
+        clock.advance(Duration::from_secs(24 * 60 * 60));
+        let snapshots = dir.path().join(SPEND_SNAPSHOTS_DIRNAME);
+        let shutdown = tokio_util::sync::CancellationToken::new();
+        MemorySystem::spawn_spend_snapshots(
+            Arc::new(mem),
+            snapshots.clone(),
+            Duration::from_secs(15 * 60),
+            clock,
+            shutdown.clone(),
+        );
+
+        let path = snapshots.join("2026-01-02.json");
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !path.exists() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("no snapshot written");
+        shutdown.cancel();
+
+        let snapshot: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(snapshot["taken_at"], "2026-01-02T00:00:00Z");
+        let totals: SpendTotals = serde_json::from_value(snapshot["totals"].clone()).unwrap();
+        assert_eq!(totals.sessions, 2);
+        assert_eq!(totals.total_input_tokens, 2_000_000);
+        assert_eq!(totals.total_output_tokens, 1_500_000);
+        assert!((totals.total_cost_usd - 5.0).abs() < 1e-9);
+        assert_eq!(
+            totals.by_model["model-a"],
+            ModelSpend {
+                input_tokens: 1_000_000,
+                output_tokens: 1_500_000,
+                cached_tokens: 0,
+                cost_usd: 4.0,
+            }
+        );
+        assert!((totals.by_model["model-b"].cost_usd - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn idle_sessions_are_archived_and_hidden_from_list() {
+        let (_dir, mem) = setup();
+        let clock = clock::MockClock::new(SystemClock.now_unix_secs());
+        let idle = Duration::from_secs(30 * 60);
+
+        let global = mem.create_session(&["basic_session"], None).unwrap();
+        let global_id = global.session_id.clone();
+        drop(global);
+        let identity_dir = mem.memory_root().join(AGENTS_DIRNAME).join("chat-abc");
+        let agent_index = identity_dir.join("sessions.json");
+        fs::create_dir_all(&identity_dir).unwrap();
+        fs::write(&agent_index, r#"{"sessions":{}}"#).unwrap();
+        let agent_sessions = identity_dir.join("sessions");
+        let scoped = mem
+            .create_session_in(&agent_sessions, &agent_index, &["basic_session"], None)
+            .unwrap();
+        let held = mem.create_session(&["basic_session"], None).unwrap();
+
+        assert!(mem.archive_idle_sessions(idle, &clock).unwrap().is_empty());
+
+        // Sessions with a live handle are never archived.
+        clock.advance(idle + Duration::from_secs(60));
+        assert_eq!(
+            mem.archive_idle_sessions(idle, &clock).unwrap(),
+            std::slice::from_ref(&global_id)
+        );
+        let scoped_id = scoped.session_id.clone();
+        drop(scoped);
+        assert_eq!(
+            mem.archive_idle_sessions(idle, &clock).unwrap(),
+            [scoped_id]
+        );
+
+        let listed: Vec<String> = mem
+            .list_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        assert_eq!(listed, std::slice::from_ref(&held.session_id));
+        assert_eq!(mem.list_sessions_including_archived().unwrap().len(), 2);
+        assert!(MemorySystem::list_sessions_in(&agent_index).unwrap()[0].archived);
+
+        // Loading an archived session brings it back.
+        mem.load_session(&global_id, None).unwrap();
+        assert_eq!(mem.list_sessions().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn a_session_loaded_during_a_sweep_is_never_left_archived() {
+        let (_dir, mem) = setup();
+        let mem = Arc::new(mem);
+        let session_id = mem
+            .create_session(&["basic_session"], None)
+            .unwrap()
+            .session_id
+            .clone();
+
+        for _ in 0..100 {
+            let sweeper = {
+                let mem = mem.clone();
+                std::thread::spawn(move || {
+                    mem.archive_idle_sessions(
+                        Duration::ZERO,
+                        &clock::MockClock::new(SystemClock.now_unix_secs() + 3600),
+                    )
+                    .unwrap();
+                })
+            };
+            let held = mem.load_session(&session_id, None).unwrap();
+            sweeper.join().unwrap();
+            assert!(
+                !mem.list_sessions_including_archived().unwrap()[0].archived,
+                "an open session was archived"
+            );
+            drop(held);
+        }
+    }
+
+    #[test]
+    fn archived_transcripts_are_compressed_when_enabled() {
+        let dir = TempDir::new().unwrap();
+        let config = MemoryConfig {
+            compress_archived_transcripts: true,
+            ..MemoryConfig::default()
+        };
+        let mem = MemorySystem::new(dir.path(), config).unwrap();
+        let clock = clock::MockClock::new(SystemClock.now_unix_secs());
+        let idle = Duration::from_secs(60);
+
+        let session_id = closed_session(&mem);
+        let session_dir = mem.sessions_dir.join(&session_id);
+        let store = stores::basic_session::BasicSessionStore::new(None, None);
+        store.transcript_append(&session_dir, "user", "hi").unwrap();
+        let before = store.transcript_read_last(&session_dir, 10).unwrap();
+
+        clock.advance(idle * 2);
+        assert_eq!(
+            mem.archive_idle_sessions(idle, &clock).unwrap(),
+            [session_id]
+        );
+        assert!(!session_dir.join("transcript.md").exists());
+        assert!(session_dir.join("transcript.md.gz").exists());
+        assert_eq!(
+            store.transcript_read_last(&session_dir, 10).unwrap(),
+            before
+        );
+    }
+
+    #[tokio::test]
+    async fn export_then_import_reproduces_sessions_and_transcripts() {
+        let (_src_dir, src) = setup();
+        let chat = src
+            .create_session(&["basic_session"], Some("chat"))
+            .unwrap();
+        chat.transcript_append("user", "hello there").await.unwrap();
+        chat.transcript_append("assistant", "hi!").await.unwrap();
+        let other = closed_session(&src);
+
+        let mut archive = Vec::new();
+        let exported = src.export_archive(&mut archive).unwrap();
+        assert!(exported.files >= 3, "{exported:?}");
+
+        let (_dst_dir, dst) = setup();
+        let imported = dst.import_archive(&archive[..]).unwrap();
+        assert_eq!(imported, exported);
+
+        let ids = |mem: &MemorySystem| {
+            let mut ids: Vec<_> = mem
+                .list_sessions()
+                .unwrap()
+                .into_iter()
+                .map(|s| s.session_id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(ids(&dst), ids(&src));
+        assert!(ids(&dst).contains(&other));
+
+        let restored = dst.load_session(&chat.session_id, Some("chat")).unwrap();
+        let transcript = restored.transcript_read_last(10).await.unwrap();
+        let turns: Vec<_> = transcript
+            .iter()
+            .map(|e| (e.role.as_str(), e.content.as_str()))
+            .collect();
+        assert_eq!(turns, [("user", "hello there"), ("assistant", "hi!")]);
+
+        // A bot that already has sessions is not overwritten.
+        let err = dst.import_archive(&archive[..]).unwrap_err();
+        assert!(err.to_string().contains("fresh bot"), "{err}");
+        // The replaced root was kept.
+        let backups = fs::read_dir(_dst_dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with("memory.pre-import-")
+            })
+            .count();
+        assert_eq!(backups, 1);
+    }
+
+    #[tokio::test]
+    async fn import_is_refused_over_agent_sessions_or_open_sessions() {
+        let (_src_dir, src) = setup();
+        closed_session(&src);
+        let mut archive = Vec::new();
+        src.export_archive(&mut archive).unwrap();
+
+        // Only an agent's own index has a session.
+        let (dir, dst) = setup();
+        let identity = araliya_core::identity::setup_named_identity(
+            &dst.memory_root().join(AGENTS_DIRNAME),
+            "chat",
+        )
+        .unwrap();
+        let store = stores::agent::AgentStore::open(&identity.identity_dir).unwrap();
+        dst.create_session_in(
+            &store.agent_sessions_dir(),
+            &store.agent_sessions_index(),
+            &["basic_session"],
+            Some("chat"),
+        )
+        .unwrap();
+        let err = dst.import_archive(&archive[..]).unwrap_err();
+        assert!(err.to_string().contains("1 session(s)"), "{err}");
+        drop(dir);
+
+        // An open in-memory session holds the bot too.
+        let (_dir, dst) = setup();
+        let _open = dst.create_session(&["tmp"], None).unwrap();
+        let err = dst.import_archive(&archive[..]).unwrap_err();
+        assert!(err.to_string().contains("sessions are open"), "{err}");
+    }
 }
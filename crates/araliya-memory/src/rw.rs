@@ -3,17 +3,44 @@
 //! `SessionRw` centralizes store selection, blocking I/O dispatch, and tmp-store
 //! typed collection access. [`SessionHandle`](crate::handle::SessionHandle)
 //! delegates all data operations to this struct.
-
+//!
+//! With `[memory] write_fallback`, a failed disk write (a full disk, most
+//! often) does not fail the caller: the session switches to an in-memory
+//! overlay for the rest of its life.  Reads still come from disk, merged with
+//! the overlay, so the conversation continues; nothing written after the
+//! failure is persisted.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 use std::time::UNIX_EPOCH;
 
+use araliya_core::bus::health::HealthReporter;
 use araliya_core::error::AppError;
+use tracing::error;
 
 use crate::collections::{Block, Collection, Doc};
 use crate::store::{SessionStore, TranscriptEntry};
+use crate::stores::basic_session::BasicSessionStore;
 use crate::stores::tmp::TmpStore;
 
+/// Shared by every session of one [`MemorySystem`](crate::MemorySystem)
+/// when `[memory] write_fallback` is on.
+#[derive(Default)]
+pub(crate) struct WriteFallback {
+    /// Marked unhealthy when a session falls back.
+    pub(crate) reporter: OnceLock<HealthReporter>,
+}
+
+/// What a degraded session has written since its disk writes failed.
+#[derive(Default)]
+struct Overlay {
+    /// `None` records a deletion.
+    kv: HashMap<String, Option<String>>,
+    transcript: Vec<TranscriptEntry>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionFileInfo {
     pub name: String,
@@ -25,6 +52,11 @@ pub struct SessionRw {
     session_dir: PathBuf,
     stores: Vec<Arc<dyn SessionStore>>,
     tmp_store: Option<Arc<TmpStore>>,
+    write_fallback: Option<Arc<WriteFallback>>,
+    /// Set once a write has failed and the session runs from memory.
+    overlay: Mutex<Option<Overlay>>,
+    /// Release the session's `tmp` data on drop (diagnostic sessions).
+    release_on_drop: bool,
 }
 
 impl SessionRw {
@@ -37,9 +69,27 @@ impl SessionRw {
             session_dir,
             stores,
             tmp_store,
+            write_fallback: None,
+            overlay: Mutex::new(None),
+            release_on_drop: false,
         }
     }
 
+    pub(crate) fn release_on_drop(mut self) -> Self {
+        self.release_on_drop = true;
+        self
+    }
+
+    pub(crate) fn with_write_fallback(mut self, fallback: Option<Arc<WriteFallback>>) -> Self {
+        self.write_fallback = fallback;
+        self
+    }
+
+    /// `true` once a failed write has moved this session to memory.
+    pub fn is_degraded(&self) -> bool {
+        self.overlay().is_some()
+    }
+
     pub fn session_dir(&self) -> &Path {
         &self.session_dir
     }
@@ -63,6 +113,9 @@ impl SessionRw {
     }
 
     pub async fn kv_get(&self, key: &str) -> Result<Option<String>, AppError> {
+        if let Some(value) = self.overlay().as_ref().and_then(|o| o.kv.get(key).cloned()) {
+            return Ok(value);
+        }
         let store = self.default_store()?;
         let dir = self.session_dir.clone();
         let key = key.to_string();
@@ -72,40 +125,112 @@ impl SessionRw {
     }
 
     pub async fn kv_set(&self, key: &str, value: &str) -> Result<(), AppError> {
+        if let Some(overlay) = self.overlay().as_mut() {
+            overlay.kv.insert(key.to_string(), Some(value.to_string()));
+            return Ok(());
+        }
         let store = self.default_store()?;
         let dir = self.session_dir.clone();
-        let key = key.to_string();
-        let value = value.to_string();
-        tokio::task::spawn_blocking(move || store.kv_set(&dir, &key, &value))
+        let (k, v) = (key.to_string(), value.to_string());
+        let result = tokio::task::spawn_blocking(move || store.kv_set(&dir, &k, &v))
             .await
-            .map_err(|e| AppError::Memory(format!("kv_set join: {e}")))?
+            .map_err(|e| AppError::Memory(format!("kv_set join: {e}")))?;
+        self.fall_back_on_failure(result, |overlay| {
+            overlay.kv.insert(key.to_string(), Some(value.to_string()));
+        })
+        .await
     }
 
     pub async fn kv_delete(&self, key: &str) -> Result<bool, AppError> {
+        let existed = self.kv_get(key).await?.is_some();
+        if let Some(overlay) = self.overlay().as_mut() {
+            overlay.kv.insert(key.to_string(), None);
+            return Ok(existed);
+        }
         let store = self.default_store()?;
         let dir = self.session_dir.clone();
-        let key = key.to_string();
-        tokio::task::spawn_blocking(move || store.kv_delete(&dir, &key))
+        let k = key.to_string();
+        let result = tokio::task::spawn_blocking(move || store.kv_delete(&dir, &k))
             .await
-            .map_err(|e| AppError::Memory(format!("kv_delete join: {e}")))?
+            .map_err(|e| AppError::Memory(format!("kv_delete join: {e}")))?;
+        match result {
+            Ok(removed) => Ok(removed),
+            Err(e) => {
+                self.fall_back_on_failure(Err(e), |overlay| {
+                    overlay.kv.insert(key.to_string(), None);
+                })
+                .await?;
+                Ok(existed)
+            }
+        }
     }
 
     pub async fn transcript_append(&self, role: &str, content: &str) -> Result<(), AppError> {
+        self.transcript_append_with_meta(role, content, serde_json::Value::Null)
+            .await
+    }
+
+    pub async fn transcript_append_with_meta(
+        &self,
+        role: &str,
+        content: &str,
+        meta: serde_json::Value,
+    ) -> Result<(), AppError> {
+        let entry = || TranscriptEntry {
+            role: role.to_string(),
+            timestamp: BasicSessionStore::now_iso8601(),
+            content: content.to_string(),
+            tokens: None,
+            meta: meta.clone(),
+        };
+        if let Some(overlay) = self.overlay().as_mut() {
+            overlay.transcript.push(entry());
+            return Ok(());
+        }
         let store = self.default_store()?;
         let dir = self.session_dir.clone();
-        let role = role.to_string();
-        let content = content.to_string();
-        tokio::task::spawn_blocking(move || store.transcript_append(&dir, &role, &content))
+        let (r, c, m) = (role.to_string(), content.to_string(), meta.clone());
+        let result = tokio::task::spawn_blocking(move || {
+            store.transcript_append_with_meta(&dir, &r, &c, &m)
+        })
+        .await
+        .map_err(|e| AppError::Memory(format!("transcript_append join: {e}")))?;
+        self.fall_back_on_failure(result, |overlay| overlay.transcript.push(entry()))
             .await
-            .map_err(|e| AppError::Memory(format!("transcript_append join: {e}")))?
     }
 
     pub async fn transcript_read_last(&self, n: usize) -> Result<Vec<TranscriptEntry>, AppError> {
         let store = self.default_store()?;
         let dir = self.session_dir.clone();
-        tokio::task::spawn_blocking(move || store.transcript_read_last(&dir, n))
+        let mut entries = tokio::task::spawn_blocking(move || store.transcript_read_last(&dir, n))
             .await
-            .map_err(|e| AppError::Memory(format!("transcript_read_last join: {e}")))?
+            .map_err(|e| AppError::Memory(format!("transcript_read_last join: {e}")))??;
+        if let Some(overlay) = self.overlay().as_ref() {
+            entries.extend(overlay.transcript.iter().cloned());
+            let excess = entries.len().saturating_sub(n);
+            entries.drain(..excess);
+        }
+        Ok(entries)
+    }
+
+    pub async fn transcript_since(&self, since: &str) -> Result<Vec<TranscriptEntry>, AppError> {
+        let store = self.default_store()?;
+        let dir = self.session_dir.clone();
+        let owned_since = since.to_string();
+        let mut entries =
+            tokio::task::spawn_blocking(move || store.transcript_since(&dir, &owned_since))
+                .await
+                .map_err(|e| AppError::Memory(format!("transcript_since join: {e}")))??;
+        if let Some(overlay) = self.overlay().as_ref() {
+            entries.extend(
+                overlay
+                    .transcript
+                    .iter()
+                    .filter(|e| e.timestamp.as_str() > since)
+                    .cloned(),
+            );
+        }
+        Ok(entries)
     }
 
     pub async fn kv_doc(&self) -> Result<Doc, AppError> {
@@ -192,6 +317,44 @@ impl SessionRw {
         .map_err(|e| AppError::Memory(format!("list_files join: {e}")))?
     }
 
+    fn overlay(&self) -> std::sync::MutexGuard<'_, Option<Overlay>> {
+        self.overlay.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pass `result` through, unless it is an I/O failure and
+    /// `write_fallback` is on: then move the session to memory, apply the
+    /// write there with `apply`, and report the memory subsystem degraded.
+    async fn fall_back_on_failure(
+        &self,
+        result: Result<(), AppError>,
+        apply: impl FnOnce(&mut Overlay),
+    ) -> Result<(), AppError> {
+        let Err(e) = result else {
+            return Ok(());
+        };
+        let (Some(fallback), Some(reason)) = (&self.write_fallback, write_failure(&e)) else {
+            return Err(e);
+        };
+        error!(
+            session_dir = %self.session_dir.display(),
+            error = %e,
+            "memory write failed — session continues in memory WITHOUT persistence"
+        );
+        apply(self.overlay().get_or_insert_with(Overlay::default));
+        if let Some(reporter) = fallback.reporter.get() {
+            reporter
+                .set_unhealthy_with(
+                    format!("memory degraded: {reason}"),
+                    Some(serde_json::json!({
+                        "session_dir": self.session_dir.display().to_string(),
+                        "error": e.to_string(),
+                    })),
+                )
+                .await;
+        }
+        Ok(())
+    }
+
     fn tmp_store(&self) -> Result<&Arc<TmpStore>, AppError> {
         self.tmp_store.as_ref().ok_or_else(|| {
             AppError::Memory("session has no tmp store (not a 'tmp' session)".into())
@@ -203,6 +366,26 @@ impl SessionRw {
     }
 }
 
+impl Drop for SessionRw {
+    fn drop(&mut self) {
+        if let (true, Some(tmp)) = (self.release_on_drop, &self.tmp_store)
+            && let Err(e) = tmp.release(&self.session_dir)
+        {
+            error!(session_dir = %self.session_dir.display(), "releasing tmp session data: {e}");
+        }
+    }
+}
+
+/// Why a store write failed, if it was the disk (an `io::Error`) rather
+/// than the store refusing the operation.
+fn write_failure(e: &AppError) -> Option<&'static str> {
+    match e {
+        AppError::Io(io) if io.kind() == ErrorKind::StorageFull => Some("disk full"),
+        AppError::Io(_) => Some("write failed"),
+        _ => None,
+    }
+}
+
 fn epoch_to_iso8601(epoch_secs: u64) -> String {
     let s = epoch_secs % 60;
     let total_min = epoch_secs / 60;
@@ -252,3 +435,120 @@ fn epoch_to_iso8601(epoch_secs: u64) -> String {
 
     format!("{yr:04}-{mon:02}-{day:02}T{h:02}:{m:02}:{s:02}Z")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::bus::health::HealthRegistry;
+
+    /// Disk store whose writes fail as on a full disk; reads see an empty
+    /// session.
+    struct FullDisk;
+
+    impl SessionStore for FullDisk {
+        fn store_type(&self) -> &str {
+            "basic_session"
+        }
+
+        fn init(&self, _session_dir: &Path) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn kv_get(&self, _session_dir: &Path, _key: &str) -> Result<Option<String>, AppError> {
+            Ok(None)
+        }
+
+        fn kv_set(&self, dir: &Path, _key: &str, _value: &str) -> Result<(), AppError> {
+            Err(disk_full(format!("cannot write {}/kv.json", dir.display())))
+        }
+
+        fn transcript_append(
+            &self,
+            dir: &Path,
+            _role: &str,
+            _content: &str,
+        ) -> Result<(), AppError> {
+            Err(disk_full(format!(
+                "cannot append {}/transcript.md",
+                dir.display()
+            )))
+        }
+
+        fn transcript_read_last(
+            &self,
+            _session_dir: &Path,
+            _n: usize,
+        ) -> Result<Vec<TranscriptEntry>, AppError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn disk_full(context: String) -> AppError {
+        AppError::Io(std::io::Error::new(
+            ErrorKind::StorageFull,
+            format!("{context}: No space left on device"),
+        ))
+    }
+
+    fn full_disk_session(fallback: Option<Arc<WriteFallback>>) -> SessionRw {
+        SessionRw::new(
+            PathBuf::from("/sessions/s1"),
+            vec![Arc::new(FullDisk)],
+            None,
+        )
+        .with_write_fallback(fallback)
+    }
+
+    #[tokio::test]
+    async fn full_disk_moves_the_session_to_memory() {
+        let registry = HealthRegistry::new();
+        let fallback = Arc::new(WriteFallback::default());
+        let _ = fallback.reporter.set(registry.reporter("memory"));
+        let rw = full_disk_session(Some(fallback));
+
+        rw.transcript_append("user", "hello").await.unwrap();
+        rw.transcript_append("assistant", "hi there").await.unwrap();
+        rw.kv_set("topic", "greetings").await.unwrap();
+        assert!(rw.is_degraded());
+
+        let last = rw.transcript_read_last(10).await.unwrap();
+        let turns: Vec<_> = last
+            .iter()
+            .map(|e| (e.role.as_str(), e.content.as_str()))
+            .collect();
+        assert_eq!(turns, [("user", "hello"), ("assistant", "hi there")]);
+        assert_eq!(
+            rw.transcript_read_last(1).await.unwrap()[0].content,
+            "hi there"
+        );
+        assert_eq!(
+            rw.kv_get("topic").await.unwrap().as_deref(),
+            Some("greetings")
+        );
+
+        let health = registry.reporter("memory").get_current().await.unwrap();
+        assert!(!health.healthy);
+        assert_eq!(health.message, "memory degraded: disk full");
+    }
+
+    #[tokio::test]
+    async fn write_failures_error_without_fallback() {
+        let rw = full_disk_session(None);
+        let err = rw.transcript_append("user", "hello").await.unwrap_err();
+        assert!(err.to_string().contains("No space left"), "got: {err}");
+        assert!(!rw.is_degraded());
+    }
+
+    #[test]
+    fn write_failures_are_classified_by_error_kind() {
+        assert_eq!(
+            write_failure(&disk_full("kv.json".into())),
+            Some("disk full")
+        );
+        let denied = AppError::Io(std::io::Error::new(ErrorKind::PermissionDenied, "kv.json"));
+        assert_eq!(write_failure(&denied), Some("write failed"));
+        // A store's own refusal is not a disk failure, whatever it says.
+        let refused = AppError::Memory("value names (os error 28)".into());
+        assert_eq!(write_failure(&refused), None);
+    }
+}
@@ -14,12 +14,20 @@ use std::sync::RwLock;
 
 use araliya_core::error::AppError;
 
-/// A parsed transcript entry.
-#[derive(Debug, Clone)]
+/// A parsed transcript entry.  Also the line shape of `transcript.jsonl`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct TranscriptEntry {
     pub role: String,
     pub timestamp: String,
     pub content: String,
+    /// Token count, when known.  Only the JSONL format keeps it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u64>,
+    /// Free-form per-turn metadata set by the agent (model, usage,
+    /// latency, …).  `null` when there is none, including for entries
+    /// written before the field existed.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub meta: serde_json::Value,
 }
 
 /// Pluggable session-backed memory store.
@@ -74,6 +82,18 @@ pub trait SessionStore: Send + Sync {
         )))
     }
 
+    /// Append an entry carrying `meta`.  Stores that cannot keep metadata
+    /// append it without.
+    fn transcript_append_with_meta(
+        &self,
+        session_dir: &Path,
+        role: &str,
+        content: &str,
+        _meta: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        self.transcript_append(session_dir, role, content)
+    }
+
     fn transcript_read_last(
         &self,
         _session_dir: &Path,
@@ -85,6 +105,18 @@ pub trait SessionStore: Send + Sync {
         )))
     }
 
+    /// Entries with a timestamp strictly after `since` (ISO-8601, UTC).
+    fn transcript_since(
+        &self,
+        _session_dir: &Path,
+        _since: &str,
+    ) -> Result<Vec<TranscriptEntry>, AppError> {
+        Err(AppError::Memory(format!(
+            "store '{}' does not support transcript_since",
+            self.store_type()
+        )))
+    }
+
     // ── Typed Collection views ────────────────────────────────────────
 
     /// Return the current k-v store as a [`Doc`](crate::collections::Doc) collection.
@@ -1,4 +1,4 @@
-//! `basic_session` store — capped key-value (Doc-backed) + capped Markdown transcript.
+//! `basic_session` store — capped key-value (Doc-backed) + capped transcript.
 //!
 //! ## On-disk format
 //!
@@ -9,20 +9,35 @@
 //!   `order` preserves insertion order for FIFO eviction.
 //!
 //! - `transcript.md` — Markdown with `### {role} — {timestamp}` delimiters.
-//!   Human-readable; also exposed as a [`Block`] collection via
+//!   An entry's metadata, if any, is a `<!-- meta: {json} -->` line right
+//!   under its header.  Human-readable; also exposed as a [`Block`] collection via
 //!   [`read_transcript_block`](BasicSessionStore::read_transcript_block).
 //!   Each entry becomes a `Value::Text(TextFile)` keyed by zero-padded index.
 //!
+//! - `transcript.jsonl` — used instead of `transcript.md` when the session was
+//!   created with `[memory] transcript_format = "jsonl"`.  One serialised
+//!   [`TranscriptEntry`] per line, so roles and timestamps read back exactly.
+//!   A session keeps its format for life.  The format is detected from the
+//!   transcript's content (a JSON object per line, or `###` headers); an
+//!   empty transcript goes by its file extension.
+//!
+//! Either transcript may instead be stored gzip-compressed as
+//! `transcript.md.gz` / `transcript.jsonl.gz` once its session is archived
+//! (see [`compress_transcript`](BasicSessionStore::compress_transcript)).
+//! Reads decompress transparently; the next write stores it uncompressed
+//! again.
+//!
 //! Both stores are capped by entry count (FIFO — oldest entries dropped first).
 
 use std::collections::HashMap;
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 
 use crate::collections::{Block, Doc};
 use crate::store::{SessionStore, TranscriptEntry};
 use crate::types::{PrimaryValue, TextFile, Value};
+use araliya_core::config::TranscriptFormat;
 use araliya_core::error::AppError;
 
 //  TODO: these  should be const  definied top or config.
@@ -33,6 +48,12 @@ const DEFAULT_TRANSCRIPT_CAP: usize = 500;
 
 const KV_FILENAME: &str = "kv.json";
 const TRANSCRIPT_FILENAME: &str = "transcript.md";
+/// Wraps an entry's metadata in `transcript.md`.
+const META_PREFIX: &str = "<!-- meta: ";
+const META_SUFFIX: &str = " -->";
+const TRANSCRIPT_JSONL_FILENAME: &str = "transcript.jsonl";
+/// Suffix of a gzip-compressed transcript.
+const GZ_SUFFIX: &str = ".gz";
 
 // ── On-disk structures ────────────────────────────────────────────────────────
 
@@ -96,6 +117,8 @@ impl KvFile {
 pub struct BasicSessionStore {
     kv_cap: usize,
     transcript_cap: usize,
+    /// Format given to sessions created by [`init`](SessionStore::init).
+    transcript_format: TranscriptFormat,
 }
 
 impl BasicSessionStore {
@@ -103,9 +126,16 @@ impl BasicSessionStore {
         Self {
             kv_cap: kv_cap.unwrap_or(DEFAULT_KV_CAP),
             transcript_cap: transcript_cap.unwrap_or(DEFAULT_TRANSCRIPT_CAP),
+            transcript_format: TranscriptFormat::default(),
         }
     }
 
+    /// Create new sessions with `format` transcripts.
+    pub fn with_transcript_format(mut self, format: TranscriptFormat) -> Self {
+        self.transcript_format = format;
+        self
+    }
+
     // ── K-V helpers ───────────────────────────────────────────────────
 
     fn kv_path(session_dir: &Path) -> std::path::PathBuf {
@@ -115,7 +145,7 @@ impl BasicSessionStore {
     pub(crate) fn read_kv(session_dir: &Path) -> Result<KvFile, AppError> {
         let path = Self::kv_path(session_dir);
         let data = fs::read_to_string(&path)
-            .map_err(|e| AppError::Memory(format!("cannot read {}: {e}", path.display())))?;
+            .map_err(|e| io_error(e, format!("cannot read {}", path.display())))?;
         serde_json::from_str(&data)
             .map_err(|e| AppError::Memory(format!("malformed {}: {e}", path.display())))
     }
@@ -124,8 +154,7 @@ impl BasicSessionStore {
         let path = Self::kv_path(session_dir);
         let data = serde_json::to_string_pretty(kv)
             .map_err(|e| AppError::Memory(format!("serialise kv: {e}")))?;
-        fs::write(&path, data)
-            .map_err(|e| AppError::Memory(format!("cannot write {}: {e}", path.display())))
+        fs::write(&path, data).map_err(|e| io_error(e, format!("cannot write {}", path.display())))
     }
 
     pub(crate) fn now_iso8601() -> String {
@@ -139,40 +168,147 @@ impl BasicSessionStore {
 
     // ── Transcript helpers ────────────────────────────────────────────
 
-    fn transcript_path(session_dir: &Path) -> std::path::PathBuf {
-        session_dir.join(TRANSCRIPT_FILENAME)
+    fn transcript_path(session_dir: &Path, format: TranscriptFormat) -> std::path::PathBuf {
+        match format {
+            TranscriptFormat::Md => session_dir.join(TRANSCRIPT_FILENAME),
+            TranscriptFormat::Jsonl => session_dir.join(TRANSCRIPT_JSONL_FILENAME),
+        }
+    }
+
+    /// Find and read the session's transcript.
+    ///
+    /// Each transcript file present (`transcript.jsonl`, then
+    /// `transcript.md`, either possibly as its `.gz` copy) is read, and the
+    /// first with content decides: its format is the one its content is in
+    /// (see [`detect_format`]), whatever its name.  A session whose
+    /// transcript is still empty goes by the file's extension; one with no
+    /// transcript file predates them and is Markdown.
+    fn load_transcript(session_dir: &Path) -> Result<Transcript, AppError> {
+        let mut empty = None;
+        for format in [TranscriptFormat::Jsonl, TranscriptFormat::Md] {
+            let path = Self::transcript_path(session_dir, format);
+            let Some(text) = Self::read_transcript_text(&path)? else {
+                continue;
+            };
+            match detect_format(&text) {
+                Some(format) => return Ok(Transcript { path, format, text }),
+                None => {
+                    empty.get_or_insert(Transcript { path, format, text });
+                }
+            }
+        }
+        Ok(empty.unwrap_or_else(|| Transcript {
+            path: Self::transcript_path(session_dir, TranscriptFormat::Md),
+            format: TranscriptFormat::Md,
+            text: String::new(),
+        }))
+    }
+
+    /// Read every transcript entry of the session, in order.
+    fn read_transcript(session_dir: &Path) -> Result<Vec<TranscriptEntry>, AppError> {
+        let transcript = Self::load_transcript(session_dir)?;
+        match transcript.format {
+            TranscriptFormat::Md => Ok(Self::parse_transcript(&transcript.text)),
+            TranscriptFormat::Jsonl => Self::parse_transcript_jsonl(&transcript.text),
+        }
+    }
+
+    /// Raw transcript text at `path`, falling back to its `.gz` copy;
+    /// `None` when neither file exists.
+    fn read_transcript_text(path: &Path) -> Result<Option<String>, AppError> {
+        if let Ok(text) = fs::read_to_string(path) {
+            return Ok(Some(text));
+        }
+        let gz = gz_path(path);
+        let Ok(file) = fs::File::open(&gz) else {
+            return Ok(None);
+        };
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(file)
+            .read_to_string(&mut text)
+            .map_err(|e| io_error(e, format!("cannot decompress {}", gz.display())))?;
+        Ok(Some(text))
+    }
+
+    /// Gzip the session's transcript to `<transcript>.gz` and remove the
+    /// original.  Returns `false` when there is no uncompressed transcript.
+    pub fn compress_transcript(session_dir: &Path) -> Result<bool, AppError> {
+        let path = Self::load_transcript(session_dir)?.path;
+        let Ok(data) = fs::read(&path) else {
+            return Ok(false);
+        };
+        let gz = gz_path(&path);
+        let file = fs::File::create(&gz)
+            .map_err(|e| io_error(e, format!("cannot write {}", gz.display())))?;
+        let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        encoder
+            .write_all(&data)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| io_error(e, format!("compress {}", path.display())))?;
+        fs::remove_file(&path)
+            .map_err(|e| io_error(e, format!("cannot remove {}", path.display())))?;
+        Ok(true)
+    }
+
+    /// Replace the session's transcript with `entries`, in its own format
+    /// and file.
+    fn write_transcript(session_dir: &Path, entries: &[TranscriptEntry]) -> Result<(), AppError> {
+        let Transcript { path, format, .. } = Self::load_transcript(session_dir)?;
+        let out = match format {
+            TranscriptFormat::Md => Self::serialise_transcript(entries),
+            TranscriptFormat::Jsonl => Self::serialise_transcript_jsonl(entries)?,
+        };
+        let mut f = fs::File::create(&path)
+            .map_err(|e| io_error(e, format!("cannot write {}", path.display())))?;
+        f.write_all(out.as_bytes())
+            .map_err(|e| io_error(e, format!("write {}", path.display())))?;
+        // A compressed copy left from archiving is now stale.
+        let gz = gz_path(&path);
+        if gz.exists() {
+            fs::remove_file(&gz)
+                .map_err(|e| io_error(e, format!("cannot remove {}", gz.display())))?;
+        }
+        Ok(())
     }
 
     /// Parse `transcript.md` into typed entries.
     fn parse_transcript(text: &str) -> Vec<TranscriptEntry> {
         let mut entries = Vec::new();
-        let mut current: Option<(String, String, Vec<String>)> = None;
+        let mut current: Option<(String, String, serde_json::Value, Vec<String>)> = None;
+        let finish =
+            |(role, timestamp, meta, lines): (String, String, _, Vec<String>)| TranscriptEntry {
+                role,
+                timestamp,
+                content: lines.join("\n").trim().to_string(),
+                tokens: None,
+                meta,
+            };
 
         for line in text.lines() {
             if let Some(header) = line.strip_prefix("### ") {
-                if let Some((role, ts, lines)) = current.take() {
-                    entries.push(TranscriptEntry {
-                        role,
-                        timestamp: ts,
-                        content: lines.join("\n").trim().to_string(),
-                    });
+                if let Some(entry) = current.take() {
+                    entries.push(finish(entry));
                 }
                 let (role, ts) = if let Some((r, t)) = header.split_once(" — ") {
                     (r.trim().to_string(), t.trim().to_string())
                 } else {
                     (header.to_string(), String::new())
                 };
-                current = Some((role, ts, Vec::new()));
-            } else if let Some((_, _, ref mut lines)) = current {
-                lines.push(line.to_string());
+                current = Some((role, ts, serde_json::Value::Null, Vec::new()));
+            } else if let Some((_, _, ref mut meta, ref mut lines)) = current {
+                // The meta comment sits directly under the header.
+                let meta_line = line
+                    .strip_prefix(META_PREFIX)
+                    .and_then(|l| l.strip_suffix(META_SUFFIX))
+                    .filter(|_| lines.is_empty() && meta.is_null());
+                match meta_line.and_then(|json| serde_json::from_str(json).ok()) {
+                    Some(parsed) => *meta = parsed,
+                    None => lines.push(line.to_string()),
+                }
             }
         }
-        if let Some((role, ts, lines)) = current {
-            entries.push(TranscriptEntry {
-                role,
-                timestamp: ts,
-                content: lines.join("\n").trim().to_string(),
-            });
+        if let Some(entry) = current {
+            entries.push(finish(entry));
         }
         entries
     }
@@ -180,14 +316,37 @@ impl BasicSessionStore {
     fn serialise_transcript(entries: &[TranscriptEntry]) -> String {
         let mut out = String::new();
         for e in entries {
-            out.push_str(&format!(
-                "### {} — {}\n\n{}\n\n",
-                e.role, e.timestamp, e.content
-            ));
+            out.push_str(&format!("### {} — {}\n", e.role, e.timestamp));
+            if !e.meta.is_null() {
+                out.push_str(&format!("{META_PREFIX}{}{META_SUFFIX}\n", e.meta));
+            }
+            out.push_str(&format!("\n{}\n\n", e.content));
         }
         out
     }
 
+    /// Parse `transcript.jsonl`; blank lines are skipped.
+    fn parse_transcript_jsonl(text: &str) -> Result<Vec<TranscriptEntry>, AppError> {
+        text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|e| AppError::Memory(format!("malformed transcript line: {e}")))
+            })
+            .collect()
+    }
+
+    fn serialise_transcript_jsonl(entries: &[TranscriptEntry]) -> Result<String, AppError> {
+        let mut out = String::new();
+        for e in entries {
+            let line = serde_json::to_string(e)
+                .map_err(|e| AppError::Memory(format!("serialise transcript entry: {e}")))?;
+            out.push_str(&line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
     // ── Typed Collection views ────────────────────────────────────────
 
     /// Return the current k-v store as a [`Doc`] collection.
@@ -197,9 +356,7 @@ impl BasicSessionStore {
 
     /// Return all transcript entries as a [`Block`] collection.
     pub fn read_transcript_block(&self, session_dir: &Path) -> Result<Block, AppError> {
-        let path = Self::transcript_path(session_dir);
-        let text = fs::read_to_string(&path).unwrap_or_default();
-        let entries = Self::parse_transcript(&text);
+        let entries = Self::read_transcript(session_dir)?;
 
         let mut block = Block::default();
         for (i, entry) in entries.iter().enumerate() {
@@ -223,9 +380,9 @@ impl SessionStore for BasicSessionStore {
 
     fn init(&self, session_dir: &Path) -> Result<(), AppError> {
         Self::write_kv(session_dir, &KvFile::empty(self.kv_cap))?;
-        let path = Self::transcript_path(session_dir);
+        let path = Self::transcript_path(session_dir, self.transcript_format);
         fs::write(&path, "")
-            .map_err(|e| AppError::Memory(format!("cannot create {}: {e}", path.display())))?;
+            .map_err(|e| io_error(e, format!("cannot create {}", path.display())))?;
         Ok(())
     }
 
@@ -256,26 +413,31 @@ impl SessionStore for BasicSessionStore {
         role: &str,
         content: &str,
     ) -> Result<(), AppError> {
-        let path = Self::transcript_path(session_dir);
-        let existing = fs::read_to_string(&path).unwrap_or_default();
-        let mut entries = Self::parse_transcript(&existing);
+        self.transcript_append_with_meta(session_dir, role, content, &serde_json::Value::Null)
+    }
+
+    fn transcript_append_with_meta(
+        &self,
+        session_dir: &Path,
+        role: &str,
+        content: &str,
+        meta: &serde_json::Value,
+    ) -> Result<(), AppError> {
+        let mut entries = Self::read_transcript(session_dir)?;
 
         entries.push(TranscriptEntry {
             role: role.to_string(),
             timestamp: Self::now_iso8601(),
             content: content.to_string(),
+            tokens: None,
+            meta: meta.clone(),
         });
 
         while entries.len() > self.transcript_cap {
             entries.remove(0);
         }
 
-        let out = Self::serialise_transcript(&entries);
-        let mut f = fs::File::create(&path)
-            .map_err(|e| AppError::Memory(format!("cannot write {}: {e}", path.display())))?;
-        f.write_all(out.as_bytes())
-            .map_err(|e| AppError::Memory(format!("write {}: {e}", path.display())))?;
-        Ok(())
+        Self::write_transcript(session_dir, &entries)
     }
 
     fn transcript_read_last(
@@ -283,13 +445,21 @@ impl SessionStore for BasicSessionStore {
         session_dir: &Path,
         n: usize,
     ) -> Result<Vec<TranscriptEntry>, AppError> {
-        let path = Self::transcript_path(session_dir);
-        let text = fs::read_to_string(&path).unwrap_or_default();
-        let entries = Self::parse_transcript(&text);
+        let entries = Self::read_transcript(session_dir)?;
         let start = entries.len().saturating_sub(n);
         Ok(entries[start..].to_vec())
     }
 
+    fn transcript_since(
+        &self,
+        session_dir: &Path,
+        since: &str,
+    ) -> Result<Vec<TranscriptEntry>, AppError> {
+        let mut entries = Self::read_transcript(session_dir)?;
+        entries.retain(|e| e.timestamp.as_str() > since);
+        Ok(entries)
+    }
+
     fn read_kv_doc(&self, session_dir: &Path) -> Result<crate::collections::Doc, AppError> {
         self.read_kv_doc(session_dir)
     }
@@ -302,6 +472,38 @@ impl SessionStore for BasicSessionStore {
     }
 }
 
+/// A failed file operation on `context`, keeping the `io::Error`'s kind so
+/// callers can tell a full disk from other failures.
+fn io_error(e: std::io::Error, context: String) -> AppError {
+    AppError::Io(std::io::Error::new(e.kind(), format!("{context}: {e}")))
+}
+
+/// A session's transcript as found on disk.
+struct Transcript {
+    /// Uncompressed path; the text may have come from its `.gz` copy.
+    path: PathBuf,
+    format: TranscriptFormat,
+    text: String,
+}
+
+/// The format transcript `text` is in, from its first non-blank line: a
+/// JSON object is JSONL, anything else Markdown.  `None` when it is empty.
+fn detect_format(text: &str) -> Option<TranscriptFormat> {
+    let first = text.lines().find(|line| !line.trim().is_empty())?;
+    if serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(first).is_ok() {
+        Some(TranscriptFormat::Jsonl)
+    } else {
+        Some(TranscriptFormat::Md)
+    }
+}
+
+/// `path` with [`GZ_SUFFIX`] appended.
+fn gz_path(path: &Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(GZ_SUFFIX);
+    name.into()
+}
+
 // ── Minimal UTC formatter (avoids chrono/time dependency) ─────────────────────
 
 fn secs_to_utc(epoch_secs: u64) -> (u64, u64, u64, u64, u64, u64) {
@@ -461,6 +663,36 @@ mod tests {
         assert_eq!(entries[1].content, "hi there");
     }
 
+    #[test]
+    fn transcript_meta_round_trips_in_both_formats() {
+        let meta = serde_json::json!({
+            "model": "gpt-x",
+            "usage": { "input_tokens": 12, "output_tokens": 3 },
+        });
+        for (dir, store) in [setup(), jsonl_setup()] {
+            store
+                .transcript_append(dir.path(), "user", "hello")
+                .unwrap();
+            store
+                .transcript_append_with_meta(dir.path(), "assistant", "hi there", &meta)
+                .unwrap();
+
+            let entries = store.transcript_read_last(dir.path(), 10).unwrap();
+            assert_eq!(entries[0].meta, serde_json::Value::Null);
+            assert_eq!(entries[1].content, "hi there");
+            assert_eq!(entries[1].meta, meta);
+        }
+
+        // Transcripts written before metadata existed read it as null.
+        let old = "### user — 2026-01-01T00:00:00Z\n\nhello\n\n";
+        let entries = BasicSessionStore::parse_transcript(old);
+        assert_eq!(entries[0].content, "hello");
+        assert!(entries[0].meta.is_null());
+        let old = r#"{"role":"user","timestamp":"2026-01-01T00:00:00Z","content":"hello"}"#;
+        let entries = BasicSessionStore::parse_transcript_jsonl(old).unwrap();
+        assert!(entries[0].meta.is_null());
+    }
+
     #[test]
     fn transcript_fifo_cap() {
         let (dir, store) = setup(); // cap = 3
@@ -493,6 +725,161 @@ mod tests {
         assert_eq!(entries[1].content, "c");
     }
 
+    fn jsonl_setup() -> (TempDir, BasicSessionStore) {
+        let dir = TempDir::new().unwrap();
+        let store = BasicSessionStore::new(Some(5), Some(10))
+            .with_transcript_format(TranscriptFormat::Jsonl);
+        store.init(dir.path()).unwrap();
+        (dir, store)
+    }
+
+    fn entry(role: &str, timestamp: &str, content: &str, tokens: Option<u64>) -> TranscriptEntry {
+        TranscriptEntry {
+            role: role.to_string(),
+            timestamp: timestamp.to_string(),
+            content: content.to_string(),
+            tokens,
+            meta: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn jsonl_round_trip_preserves_entries() {
+        let (dir, store) = jsonl_setup();
+        assert!(dir.path().join(TRANSCRIPT_JSONL_FILENAME).exists());
+        assert!(!dir.path().join(TRANSCRIPT_FILENAME).exists());
+
+        // Content that the Markdown format would misparse.
+        let entries = vec![
+            entry(
+                "user",
+                "2026-10-16T08:09:05Z",
+                "  padded\n### tool — fake\n",
+                None,
+            ),
+            entry("assistant", "2026-10-16T08:09:07Z", "", Some(42)),
+            entry(
+                "tool — search",
+                "2026-10-16T08:09:07Z",
+                "{\"ok\":true}",
+                Some(0),
+            ),
+        ];
+        BasicSessionStore::write_transcript(dir.path(), &entries).unwrap();
+
+        assert_eq!(store.transcript_read_last(dir.path(), 10).unwrap(), entries);
+        assert_eq!(
+            store.transcript_read_last(dir.path(), 1).unwrap(),
+            entries[2..]
+        );
+    }
+
+    #[test]
+    fn jsonl_append_keeps_format_and_cap() {
+        let (dir, store) = jsonl_setup();
+        for i in 0..12 {
+            store
+                .transcript_append(dir.path(), "user", &format!("msg{i}"))
+                .unwrap();
+        }
+
+        let entries = store.transcript_read_last(dir.path(), 100).unwrap();
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[0].content, "msg2");
+        let text = fs::read_to_string(dir.path().join(TRANSCRIPT_JSONL_FILENAME)).unwrap();
+        assert_eq!(text.lines().count(), 10);
+
+        // A store configured for Markdown still reads the session as JSONL.
+        let md_store = BasicSessionStore::new(None, None);
+        assert_eq!(
+            md_store.transcript_read_last(dir.path(), 100).unwrap(),
+            entries
+        );
+        assert!(!dir.path().join(TRANSCRIPT_FILENAME).exists());
+    }
+
+    #[test]
+    fn transcript_format_is_detected_from_content() {
+        // A Markdown session with a stray, empty `transcript.jsonl`.
+        let (dir, store) = setup();
+        store
+            .transcript_append(dir.path(), "user", "hello")
+            .unwrap();
+        fs::write(dir.path().join(TRANSCRIPT_JSONL_FILENAME), "").unwrap();
+        store
+            .transcript_append(dir.path(), "assistant", "hi")
+            .unwrap();
+        let entries = store.transcript_read_last(dir.path(), 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].content, "hello");
+        let md = fs::read_to_string(dir.path().join(TRANSCRIPT_FILENAME)).unwrap();
+        assert!(md.contains("### assistant"), "{md}");
+
+        // JSONL content is read as JSONL whatever the file is called.
+        let dir = TempDir::new().unwrap();
+        let line = r####"{"role":"user","timestamp":"2026-01-01T00:00:00Z","content":"### not a header"}"####;
+        fs::write(dir.path().join(TRANSCRIPT_FILENAME), format!("{line}\n")).unwrap();
+        let entries = store.transcript_read_last(dir.path(), 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].role, "user");
+        assert_eq!(entries[0].content, "### not a header");
+
+        assert_eq!(detect_format(""), None);
+        assert_eq!(
+            detect_format("\n### user — t\n\nhi\n"),
+            Some(TranscriptFormat::Md)
+        );
+    }
+
+    #[test]
+    fn compressed_transcript_reads_back_identically() {
+        for (dir, store) in [setup(), jsonl_setup()] {
+            store.transcript_append(dir.path(), "user", "a").unwrap();
+            store
+                .transcript_append(dir.path(), "assistant", "b\n\nwith a gap")
+                .unwrap();
+            let before = store.transcript_read_last(dir.path(), 10).unwrap();
+            let path = BasicSessionStore::load_transcript(dir.path()).unwrap().path;
+
+            assert!(BasicSessionStore::compress_transcript(dir.path()).unwrap());
+            assert!(!path.exists());
+            assert!(gz_path(&path).exists());
+            assert!(!BasicSessionStore::compress_transcript(dir.path()).unwrap());
+            assert_eq!(store.transcript_read_last(dir.path(), 10).unwrap(), before);
+
+            // Writing again stores the transcript uncompressed.
+            store.transcript_append(dir.path(), "user", "c").unwrap();
+            assert!(path.exists());
+            assert!(!gz_path(&path).exists());
+            let after = store.transcript_read_last(dir.path(), 10).unwrap();
+            assert_eq!(after[..2], before[..]);
+            assert_eq!(after[2].content, "c");
+        }
+    }
+
+    #[test]
+    fn transcript_since_filters_by_timestamp() {
+        for (dir, store) in [setup(), jsonl_setup()] {
+            let entries = vec![
+                entry("user", "2026-10-16T08:00:00Z", "a", None),
+                entry("assistant", "2026-10-16T08:00:01Z", "b", None),
+                entry("user", "2026-10-16T08:00:02Z", "c", None),
+            ];
+            BasicSessionStore::write_transcript(dir.path(), &entries).unwrap();
+
+            let since = store
+                .transcript_since(dir.path(), "2026-10-16T08:00:00Z")
+                .unwrap();
+            assert_eq!(since, entries[1..]);
+            assert!(
+                store
+                    .transcript_since(dir.path(), "2026-10-16T08:00:02Z")
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
     #[test]
     fn iso8601_format() {
         let ts = BasicSessionStore::now_iso8601();
@@ -77,6 +77,15 @@ impl TmpStore {
         &self.store
     }
 
+    /// Drop the collections [`SessionStore::init`] made for `session_dir`.
+    pub fn release(&self, session_dir: &Path) -> Result<(), AppError> {
+        self.store
+            .remove_collection(&Self::doc_label(session_dir))?;
+        self.store
+            .remove_collection(&Self::block_label(session_dir))?;
+        Ok(())
+    }
+
     // ── Session-namespace helpers ─────────────────────────────────────
 
     fn doc_label(session_dir: &Path) -> String {
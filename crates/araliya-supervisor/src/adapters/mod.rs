@@ -8,27 +8,95 @@ pub mod stdio;
 pub mod uds;
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use tokio_util::sync::CancellationToken;
 
-use crate::control::ControlHandle;
-use araliya_core::bus::BusHandle;
+use crate::control::{ControlError, ControlHandle, ControlResponse, ControlResult};
+use araliya_core::bus::health::HealthRegistry;
+use araliya_core::bus::{BusHandle, BusPayload};
 
-/// Start supervisor-owned transport adapters.
+/// How hard the socket adapter tries to bind
+/// (`[supervisor] socket_bind_attempts` / `socket_bind_backoff_ms`).
+#[derive(Debug, Clone, Copy)]
+pub struct BindRetry {
+    /// Total bind attempts, at least 1.
+    pub attempts: u32,
+    /// Wait before the first retry; doubled after each failure.
+    pub initial_backoff: Duration,
+}
+
+impl BindRetry {
+    pub fn from_config(config: &araliya_core::config::Config) -> Self {
+        Self {
+            attempts: config.socket_bind_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.socket_bind_backoff_ms),
+        }
+    }
+}
+
+/// Start supervisor-owned transport adapters.  `presence` is the health
+/// registry the socket streams to `Subscribe` clients (`None` when
+/// `[supervisor] presence_events` is off); `handshake_bot_id` is the bot id
+/// the socket greets each connection with (`None` when `[supervisor]
+/// control_handshake` is off).
+#[allow(clippy::too_many_arguments)]
 pub fn start(
     control: ControlHandle,
     bus: BusHandle,
     shutdown: CancellationToken,
     interactive_enabled: bool,
     socket_path: PathBuf,
+    bind_retry: BindRetry,
+    presence: Option<HealthRegistry>,
+    handshake_bot_id: Option<String>,
 ) {
-    stdio::start(control.clone(), bus, shutdown.clone(), interactive_enabled);
+    stdio::start(
+        control.clone(),
+        bus.clone(),
+        shutdown.clone(),
+        interactive_enabled,
+    );
 
     #[cfg(unix)]
-    uds::start(control, socket_path, shutdown);
+    uds::start(
+        control,
+        bus,
+        socket_path,
+        bind_retry,
+        presence,
+        handshake_bot_id,
+        shutdown,
+    );
 
     #[cfg(not(unix))]
     {
-        let _ = (control, socket_path, shutdown);
+        let _ = (
+            control,
+            bus,
+            socket_path,
+            bind_retry,
+            presence,
+            handshake_bot_id,
+            shutdown,
+        );
+    }
+}
+
+/// Answer [`ControlCommand::Config`](crate::control::ControlCommand::Config)
+/// from `manage/config`.  Adapters hold an unrestricted bus handle, so the
+/// protected method is reachable here and only here.
+pub(crate) async fn effective_config(bus: &BusHandle) -> ControlResult {
+    match bus.request("manage/config", BusPayload::Empty).await {
+        Ok(Ok(BusPayload::JsonResponse { data })) => {
+            Ok(ControlResponse::Config { config_json: data })
+        }
+        Ok(Ok(other)) => Err(ControlError::Invalid {
+            message: format!("unexpected manage/config reply: {other:?}"),
+        }),
+        Ok(Err(e)) => Err(ControlError::Invalid { message: e.message }),
+        Err(e) => Err(ControlError::Invalid {
+            message: e.to_string(),
+        }),
     }
 }
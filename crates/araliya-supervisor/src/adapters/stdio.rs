@@ -144,6 +144,20 @@ pub fn start(
                                 Err(err) => eprintln!("health refresh transport error: {err}"),
                             }
                         }
+                        Ok(Some(StdioFrame::Maintenance { method })) => {
+                            match bus.request(method, BusPayload::Empty).await {
+                                Ok(Ok(BusPayload::JsonResponse { data })) => println!("{data}"),
+                                Ok(Ok(other)) => println!("{other:?}"),
+                                Ok(Err(err)) => eprintln!("maintenance error: {} ({})", err.message, err.code),
+                                Err(err) => eprintln!("maintenance transport error: {err}"),
+                            }
+                        }
+                        Ok(Some(StdioFrame::Config)) => {
+                            match super::effective_config(&bus).await {
+                                Ok(response) => print_control_response(response),
+                                Err(err) => eprintln!("config error: {err:?}"),
+                            }
+                        }
                         Ok(Some(StdioFrame::Control(command))) => {
                             match control.request(command).await {
                                 Ok(Ok(response)) => print_control_response(response),
@@ -169,6 +183,8 @@ enum StdioFrame {
     Chat { content: String },
     AgentHealth { agent_id: String },
     HealthRefresh,
+    Maintenance { method: &'static str },
+    Config,
     Control(ControlCommand),
     Help,
 }
@@ -219,6 +235,19 @@ fn parse_tty_protocol(line: &str) -> Result<Option<StdioFrame>, String> {
                 }))
             }
         }
+        "maintenance" => match rest {
+            "" => Ok(Some(StdioFrame::Maintenance {
+                method: "manage/maintenance",
+            })),
+            "on" => Ok(Some(StdioFrame::Maintenance {
+                method: "manage/maintenance/on",
+            })),
+            "off" => Ok(Some(StdioFrame::Maintenance {
+                method: "manage/maintenance/off",
+            })),
+            _ => Err("usage: /maintenance [on|off]".to_string()),
+        },
+        "config" => ensure_no_args(rest, StdioFrame::Config),
         "status" => ensure_no_args(rest, StdioFrame::Control(ControlCommand::Status)),
         "subsys" => ensure_no_args(rest, StdioFrame::Control(ControlCommand::SubsystemsList)),
         "tree" => ensure_no_args(rest, StdioFrame::Control(ControlCommand::ComponentTree)),
@@ -241,6 +270,8 @@ fn print_usage() {
     eprintln!("commands:");
     eprintln!("  /chat <message>");
     eprintln!("  /health [agent|refresh]");
+    eprintln!("  /maintenance [on|off]");
+    eprintln!("  /config");
     eprintln!("  /status");
     eprintln!("  /subsys");
     eprintln!("  /tree");
@@ -270,9 +301,32 @@ fn print_control_response(response: ControlResponse) {
                 Err(_) => println!("{tree_json}"),
             }
         }
+        ControlResponse::Config { config_json } => {
+            let pretty = serde_json::from_str::<serde_json::Value>(&config_json)
+                .and_then(|v| serde_json::to_string_pretty(&v));
+            match pretty {
+                Ok(s) => println!("{s}"),
+                Err(_) => println!("{config_json}"),
+            }
+        }
         ControlResponse::Ack { message } => {
             println!("ok: {message}");
         }
+        ControlResponse::HealthChanged {
+            id,
+            healthy,
+            message,
+            ..
+        } => {
+            println!("health {id}: healthy={healthy} ({message})");
+        }
+        ControlResponse::Hello {
+            protocol_version,
+            bot_id,
+            ..
+        } => {
+            println!("hello {bot_id}: protocol v{protocol_version}");
+        }
     }
 }
 
@@ -325,4 +379,24 @@ mod tests {
             other => panic!("unexpected parse result: {other:?}"),
         }
     }
+
+    #[test]
+    fn parse_config_command() {
+        match parse_tty_protocol("/config") {
+            Ok(Some(StdioFrame::Config)) => {}
+            other => panic!("unexpected parse result: {other:?}"),
+        }
+        assert!(parse_tty_protocol("/config all").is_err());
+    }
+
+    #[test]
+    fn parse_maintenance_command() {
+        match parse_tty_protocol("/maintenance on") {
+            Ok(Some(StdioFrame::Maintenance { method })) => {
+                assert_eq!(method, "manage/maintenance/on")
+            }
+            other => panic!("unexpected parse result: {other:?}"),
+        }
+        assert!(parse_tty_protocol("/maintenance soon").is_err());
+    }
 }
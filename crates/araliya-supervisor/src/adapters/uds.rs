@@ -9,35 +9,72 @@
 //!   ← `<WireResponse JSON>\n`
 //!
 //! The connection stays open and can process multiple request/response pairs.
+//! `Config` is answered from the bus (`manage/config`); everything else goes
+//! to the supervisor control plane.
+//!
+//! With `[supervisor] control_handshake` on, each connection first gets a
+//! `Hello` line (protocol version, optional features, bot id) before it is
+//! read from, so clients can check what they are talking to.
+//!
+//! `Subscribe` (with `[supervisor] presence_events` on) turns the connection
+//! into a one-way feed: an `Ack`, the current `HealthChanged` of every
+//! subsystem, then one `HealthChanged` per change in the health registry,
+//! until the client disconnects.  Further requests on it are ignored.
+//!
+//! Binding retries per [`BindRetry`]: a socket file left by an unclean
+//! shutdown (nothing listening) is removed and the bind repeated, other
+//! errors back off exponentially.  A socket with a live listener is never
+//! removed.
 
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::UnixListener;
+use tokio::sync::broadcast::error::RecvError;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
-use crate::control::{ControlCallError, ControlCommand, ControlError, ControlHandle, WireResponse};
+use super::BindRetry;
+use crate::control::{
+    ControlCallError, ControlCommand, ControlError, ControlHandle, ControlResponse, WireResponse,
+    CONTROL_PROTOCOL_VERSION,
+};
+use araliya_core::bus::health::HealthRegistry;
+use araliya_core::bus::BusHandle;
 
-pub fn start(control: ControlHandle, socket_path: PathBuf, shutdown: CancellationToken) {
-    // Remove stale socket file left by a previous run.
-    let _ = std::fs::remove_file(&socket_path);
-
-    let listener = match UnixListener::bind(&socket_path) {
-        Ok(l) => l,
-        Err(e) => {
-            error!(
-                socket = %socket_path.display(),
-                error = %e,
-                "management socket bind failed — araliya-ctl will not work"
-            );
-            return;
-        }
-    };
+/// `presence` serves `Subscribe`; `None` refuses it.  With
+/// `handshake_bot_id`, every connection opens with a [`hello`] for that bot.
+pub fn start(
+    control: ControlHandle,
+    bus: BusHandle,
+    socket_path: PathBuf,
+    retry: BindRetry,
+    presence: Option<HealthRegistry>,
+    handshake_bot_id: Option<String>,
+    shutdown: CancellationToken,
+) {
+    let hello = handshake_bot_id.map(|bot_id| hello(bot_id, presence.is_some()));
+    tokio::spawn(async move {
+        let bound = tokio::select! {
+            _ = shutdown.cancelled() => return,
+            bound = bind_with_retry(&socket_path, retry) => bound,
+        };
+        let listener = match bound {
+            Ok(l) => l,
+            Err(e) => {
+                error!(
+                    socket = %socket_path.display(),
+                    error = %e,
+                    "management socket bind failed — araliya-ctl will not work"
+                );
+                return;
+            }
+        };
 
-    info!(socket = %socket_path.display(), "management socket listening");
+        info!(socket = %socket_path.display(), "management socket listening");
 
-    tokio::spawn(async move {
         loop {
             tokio::select! {
                 biased;
@@ -52,8 +89,13 @@ pub fn start(control: ControlHandle, socket_path: PathBuf, shutdown: Cancellatio
                     match result {
                         Ok((stream, _addr)) => {
                             let ctl = control.clone();
+                            let bus = bus.clone();
+                            let presence = presence.clone();
+                            let hello = hello.clone();
                             let tok = shutdown.clone();
-                            tokio::spawn(handle_connection(stream, ctl, tok));
+                            tokio::spawn(handle_connection(
+                                stream, ctl, bus, presence, hello, tok,
+                            ));
                         }
                         Err(e) => {
                             warn!(error = %e, "management socket accept error");
@@ -65,13 +107,77 @@ pub fn start(control: ControlHandle, socket_path: PathBuf, shutdown: Cancellatio
     });
 }
 
+/// The handshake line: protocol version, the optional commands this socket
+/// serves, and the bot id.
+fn hello(bot_id: String, presence: bool) -> WireResponse {
+    let mut features = vec!["config".to_string()];
+    if presence {
+        features.push("subscribe".to_string());
+    }
+    WireResponse::Ok(ControlResponse::Hello {
+        protocol_version: CONTROL_PROTOCOL_VERSION,
+        features,
+        bot_id,
+    })
+}
+
+/// Bind `path`, removing a stale socket file once and retrying other
+/// failures with doubling backoff, up to `retry.attempts` tries.
+async fn bind_with_retry(path: &Path, retry: BindRetry) -> io::Result<UnixListener> {
+    let mut attempt = 1;
+    let mut backoff = retry.initial_backoff;
+    let mut removed_stale = false;
+    loop {
+        let err = match UnixListener::bind(path) {
+            Ok(listener) => return Ok(listener),
+            Err(e) => e,
+        };
+
+        if err.kind() == io::ErrorKind::AddrInUse && !removed_stale {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another process is listening on the socket",
+                ));
+            }
+            warn!(socket = %path.display(), "removing stale management socket");
+            std::fs::remove_file(path)?;
+            removed_stale = true;
+            continue;
+        }
+
+        if attempt >= retry.attempts {
+            return Err(err);
+        }
+        warn!(
+            socket = %path.display(),
+            error = %err,
+            attempt,
+            max_attempts = retry.attempts,
+            backoff_ms = backoff.as_millis() as u64,
+            "management socket bind failed; retrying"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff = backoff.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
 async fn handle_connection(
     stream: tokio::net::UnixStream,
     control: ControlHandle,
+    bus: BusHandle,
+    presence: Option<HealthRegistry>,
+    hello: Option<WireResponse>,
     shutdown: CancellationToken,
 ) {
     let (reader, mut writer) = stream.into_split();
     let mut lines = BufReader::new(reader).lines();
+    if let Some(hello) = &hello {
+        if write_wire(&mut writer, hello).await.is_err() {
+            return;
+        }
+    }
 
     loop {
         tokio::select! {
@@ -84,16 +190,14 @@ async fn handle_connection(
                     Ok(None) => break, // client closed connection
                     Ok(Some(l)) if l.trim().is_empty() => continue,
                     Ok(Some(l)) => {
-                        let wire = dispatch(&l, &control).await;
-                        let mut json = match serde_json::to_string(&wire) {
-                            Ok(s) => s,
-                            Err(e) => {
-                                warn!(error = %e, "management socket serialise error");
-                                continue;
-                            }
-                        };
-                        json.push('\n');
-                        if writer.write_all(json.as_bytes()).await.is_err() {
+                        if let (Some(registry), Ok(ControlCommand::Subscribe)) =
+                            (&presence, serde_json::from_str(&l))
+                        {
+                            stream_presence(&mut writer, &mut lines, registry, &shutdown).await;
+                            break;
+                        }
+                        let wire = dispatch(&l, &control, &bus).await;
+                        if write_wire(&mut writer, &wire).await.is_err() {
                             break;
                         }
                     }
@@ -107,7 +211,70 @@ async fn handle_connection(
     }
 }
 
-async fn dispatch(line: &str, control: &ControlHandle) -> WireResponse {
+/// Write `wire` as one JSON line.  Serialisation failures are logged and
+/// skipped; only a failed write is an error.
+async fn write_wire(writer: &mut OwnedWriteHalf, wire: &WireResponse) -> io::Result<()> {
+    let mut json = match serde_json::to_string(wire) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!(error = %e, "management socket serialise error");
+            return Ok(());
+        }
+    };
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await
+}
+
+/// Serve `Subscribe`: an `Ack` and every subsystem's current health, then
+/// each change, until the client hangs up or the bot shuts down.
+async fn stream_presence(
+    writer: &mut OwnedWriteHalf,
+    lines: &mut Lines<BufReader<OwnedReadHalf>>,
+    registry: &HealthRegistry,
+    shutdown: &CancellationToken,
+) {
+    // Subscribe before the snapshot, so no change falls between the two.
+    let mut changes = registry.subscribe();
+    let ack = WireResponse::Ok(ControlResponse::Ack {
+        message: "subscribed".to_string(),
+    });
+    if write_wire(writer, &ack).await.is_err() {
+        return;
+    }
+    let mut pending = registry.snapshot().await;
+    loop {
+        for health in pending.drain(..) {
+            if write_wire(writer, &WireResponse::Ok(health.into()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+        tokio::select! {
+            biased;
+
+            _ = shutdown.cancelled() => return,
+
+            line = lines.next_line() => match line {
+                Ok(Some(_)) => continue,
+                Ok(None) | Err(_) => return,
+            },
+
+            change = changes.recv() => match change {
+                Ok(health) => pending.push(health),
+                // Fell behind: the full current state replaces what was missed.
+                Err(RecvError::Lagged(missed)) => {
+                    debug!(missed, "presence subscriber lagged; resending snapshot");
+                    pending = registry.snapshot().await;
+                }
+                Err(RecvError::Closed) => return,
+            },
+        }
+    }
+}
+
+async fn dispatch(line: &str, control: &ControlHandle, bus: &BusHandle) -> WireResponse {
     let cmd: ControlCommand = match serde_json::from_str(line) {
         Ok(c) => c,
         Err(e) => {
@@ -119,6 +286,15 @@ async fn dispatch(line: &str, control: &ControlHandle) -> WireResponse {
 
     debug!(cmd = ?cmd, "management socket dispatching command");
 
+    if matches!(cmd, ControlCommand::Config) {
+        return WireResponse::from(super::effective_config(bus).await);
+    }
+    if matches!(cmd, ControlCommand::Subscribe) {
+        return WireResponse::Err(ControlError::Invalid {
+            message: "presence events are disabled ([supervisor] presence_events)".into(),
+        });
+    }
+
     match control.request(cmd).await {
         Ok(result) => WireResponse::from(result),
         Err(ControlCallError::Send) => WireResponse::Err(ControlError::Invalid {
@@ -129,3 +305,144 @@ async fn dispatch(line: &str, control: &ControlHandle) -> WireResponse {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const RETRY: BindRetry = BindRetry {
+        attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+    };
+
+    #[tokio::test]
+    async fn stale_socket_file_is_removed_and_bind_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("araliya.sock");
+        // A listener that went away without unlinking its file.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let listener = bind_with_retry(&path, RETRY).await.unwrap();
+        let (_client, accepted) =
+            tokio::join!(tokio::net::UnixStream::connect(&path), listener.accept());
+        assert!(accepted.is_ok());
+    }
+
+    #[tokio::test]
+    async fn live_socket_is_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("araliya.sock");
+        let _live = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let err = bind_with_retry(&path, RETRY).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert!(std::os::unix::net::UnixStream::connect(&path).is_ok());
+    }
+
+    async fn next_json(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> serde_json::Value {
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribed_client_receives_health_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("araliya.sock");
+        let registry = HealthRegistry::new();
+        let llm = registry.reporter("llm");
+        llm.set_healthy().await;
+        let shutdown = CancellationToken::new();
+        start(
+            crate::control::SupervisorControl::new(1).handle,
+            araliya_core::bus::SupervisorBus::new(1).handle,
+            path.clone(),
+            RETRY,
+            Some(registry),
+            None,
+            shutdown.clone(),
+        );
+
+        let stream = loop {
+            match tokio::net::UnixStream::connect(&path).await {
+                Ok(s) => break s,
+                Err(_) => tokio::time::sleep(Duration::from_millis(5)).await,
+            }
+        };
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"\"Subscribe\"\n").await.unwrap();
+
+        assert_eq!(
+            next_json(&mut lines).await["ok"]["Ack"]["message"],
+            "subscribed"
+        );
+        let current = next_json(&mut lines).await;
+        assert_eq!(current["ok"]["HealthChanged"]["id"], "llm");
+        assert_eq!(current["ok"]["HealthChanged"]["healthy"], true);
+
+        llm.set_unhealthy("provider unreachable").await;
+        let changed = next_json(&mut lines).await;
+        assert_eq!(changed["ok"]["HealthChanged"]["healthy"], false);
+        assert_eq!(
+            changed["ok"]["HealthChanged"]["message"],
+            "provider unreachable"
+        );
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn new_connection_opens_with_a_handshake() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("araliya.sock");
+        let shutdown = CancellationToken::new();
+        start(
+            crate::control::SupervisorControl::new(1).handle,
+            araliya_core::bus::SupervisorBus::new(1).handle,
+            path.clone(),
+            RETRY,
+            Some(HealthRegistry::new()),
+            Some("5f3a9c".to_string()),
+            shutdown.clone(),
+        );
+
+        let stream = loop {
+            match tokio::net::UnixStream::connect(&path).await {
+                Ok(s) => break s,
+                Err(_) => tokio::time::sleep(Duration::from_millis(5)).await,
+            }
+        };
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        // Sent before the client says anything.
+        let hello = &next_json(&mut lines).await["ok"]["Hello"];
+        assert_eq!(hello["protocol_version"], CONTROL_PROTOCOL_VERSION);
+        assert_eq!(
+            hello["features"],
+            serde_json::json!(["config", "subscribe"])
+        );
+        assert_eq!(hello["bot_id"], "5f3a9c");
+
+        // Requests are answered as usual after it.
+        writer.write_all(b"\"Subscribe\"\n").await.unwrap();
+        assert_eq!(
+            next_json(&mut lines).await["ok"]["Ack"]["message"],
+            "subscribed"
+        );
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn other_bind_errors_give_up_after_max_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("araliya.sock");
+        let err = bind_with_retry(&path, RETRY).await.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
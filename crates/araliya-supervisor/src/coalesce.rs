@@ -0,0 +1,173 @@
+//! Single-flight request coalescing (`[supervisor] coalesce_requests`).
+//!
+//! While a request for one of [`COALESCED_METHODS`] is being handled, a
+//! request with the same method and payload is not dispatched again: its
+//! reply sender is attached to the one in flight and gets a copy of that
+//! reply.  Nothing is cached — once the reply is out, the next identical
+//! request runs afresh.
+//!
+//! Only methods whose replies can be shared go through here: completions,
+//! which are expensive, and the read-only agent session queries.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+
+use araliya_core::bus::{BusPayload, BusResult};
+
+/// Methods eligible for coalescing.  Everything else is dispatched as is.
+pub const COALESCED_METHODS: &[&str] = &[
+    "llm/complete",
+    "agents/sessions",
+    "agents/sessions/detail",
+    "agents/sessions/memory",
+    "agents/sessions/files",
+    "agents/sessions/debug",
+];
+
+/// In-flight requests keyed by method and serialised payload, each with the
+/// callers waiting on it.
+#[derive(Clone, Default)]
+pub struct Coalescer {
+    in_flight: Arc<Mutex<HashMap<String, Vec<oneshot::Sender<BusResult>>>>>,
+}
+
+impl Coalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route `reply_tx` for a request about to be dispatched.
+    ///
+    /// Returns the sender to hand the handler, or `None` when an identical
+    /// request is already in flight and `reply_tx` now waits on it.  If the
+    /// handler drops its sender unanswered, every waiter sees a closed
+    /// channel, as a lone caller would.
+    pub fn join(
+        &self,
+        method: &str,
+        payload: &BusPayload,
+        reply_tx: oneshot::Sender<BusResult>,
+    ) -> Option<oneshot::Sender<BusResult>> {
+        if !COALESCED_METHODS.contains(&method) {
+            return Some(reply_tx);
+        }
+        let Ok(payload) = serde_json::to_string(payload) else {
+            return Some(reply_tx);
+        };
+        let key = format!("{method}\n{payload}");
+
+        {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(waiters) = in_flight.get_mut(&key) {
+                waiters.push(reply_tx);
+                return None;
+            }
+            in_flight.insert(key.clone(), vec![reply_tx]);
+        }
+
+        let (tx, rx) = oneshot::channel::<BusResult>();
+        let in_flight = self.in_flight.clone();
+        tokio::spawn(async move {
+            let result = rx.await;
+            let waiters = in_flight
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&key)
+                .unwrap_or_default();
+            if let Ok(result) = result {
+                for waiter in waiters {
+                    let _ = waiter.send(result.clone());
+                }
+            }
+        });
+        Some(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::SupervisorControl;
+    use araliya_core::bus::{BusHandler, SupervisorBus};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    /// Counts completions and answers each after a short delay, standing in
+    /// for a slow provider.
+    struct SlowLlm(Arc<AtomicUsize>);
+
+    impl BusHandler for SlowLlm {
+        fn prefix(&self) -> &str {
+            "llm"
+        }
+
+        fn handle_request(
+            &self,
+            _method: &str,
+            payload: BusPayload,
+            reply_tx: oneshot::Sender<BusResult>,
+        ) {
+            let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                let BusPayload::LlmRequest { content, .. } = payload else {
+                    return;
+                };
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: format!("{content} #{calls}"),
+                }));
+            });
+        }
+    }
+
+    fn completion(content: &str) -> BusPayload {
+        BusPayload::LlmRequest {
+            channel_id: "test".into(),
+            content: content.into(),
+            system: None,
+            provider_override: None,
+            model_override: None,
+            response_format: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_requests_share_one_call() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let bus = SupervisorBus::new(8);
+        let handle = bus.handle.clone();
+        let shutdown = CancellationToken::new();
+        tokio::spawn(crate::run::run(
+            bus,
+            SupervisorControl::new(8),
+            shutdown.clone(),
+            vec![Box::new(SlowLlm(calls.clone()))],
+            None,
+            Some(Coalescer::new()),
+        ));
+
+        let (a, b, other) = tokio::join!(
+            handle.request("llm/complete", completion("hi")),
+            handle.request("llm/complete", completion("hi")),
+            handle.request("llm/complete", completion("bye")),
+        );
+        let reply = |r: Result<BusResult, _>| match r {
+            Ok(Ok(BusPayload::JsonResponse { data })) => data,
+            other => panic!("unexpected reply: {other:?}"),
+        };
+        let (a, b, other) = (reply(a), reply(b), reply(other));
+        assert_eq!(a, b);
+        assert!(a.starts_with("hi #"));
+        assert!(other.starts_with("bye #"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Once answered, the same request runs again.
+        let again = reply(handle.request("llm/complete", completion("hi")).await);
+        assert_eq!(again, "hi #3");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        shutdown.cancel();
+    }
+}
@@ -9,6 +9,10 @@ use std::{error::Error, fmt};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, oneshot};
 
+/// Version of the management socket wire protocol, sent in
+/// [`ControlResponse::Hello`].  Bump it on changes old clients cannot read.
+pub const CONTROL_PROTOCOL_VERSION: u32 = 1;
+
 /// Control command set owned by the supervisor.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ControlCommand {
@@ -24,6 +28,14 @@ pub enum ControlCommand {
         id: String,
     },
     Shutdown,
+    /// Effective config with secrets redacted (`manage/config`).  Answered by
+    /// the transport adapters over the bus, not by the control loop.
+    Config,
+    /// Turn the socket connection into a live feed: an `Ack`, then a
+    /// `HealthChanged` per subsystem (current state) and per change, until
+    /// the client disconnects.  Served by the socket adapter only
+    /// (`[supervisor] presence_events`).
+    Subscribe,
 }
 
 /// Control plane response payload.
@@ -43,9 +55,39 @@ pub enum ControlResponse {
     ComponentTree {
         tree_json: String,
     },
+    /// JSON of the effective config, secrets redacted.
+    Config {
+        config_json: String,
+    },
     Ack {
         message: String,
     },
+    /// One subsystem's health, pushed to `Subscribe` connections.
+    HealthChanged {
+        id: String,
+        healthy: bool,
+        ready: bool,
+        message: String,
+    },
+    /// First line on a socket connection with `[supervisor]
+    /// control_handshake` on: what the daemon speaks, before any request.
+    Hello {
+        protocol_version: u32,
+        /// Optional commands this daemon serves (`"config"`, `"subscribe"`).
+        features: Vec<String>,
+        bot_id: String,
+    },
+}
+
+impl From<araliya_core::bus::health::SubsystemHealth> for ControlResponse {
+    fn from(h: araliya_core::bus::health::SubsystemHealth) -> Self {
+        ControlResponse::HealthChanged {
+            id: h.id,
+            healthy: h.healthy,
+            ready: h.ready,
+            message: h.message,
+        }
+    }
 }
 
 /// Control plane error payload.
@@ -144,7 +186,7 @@ impl Error for ControlCallError {}
 /// Wire-format response envelope for socket transports.
 ///
 /// Serialises as `{"ok": <response>}` or `{"err": <error>}`.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum WireResponse {
     #[serde(rename = "ok")]
     Ok(ControlResponse),
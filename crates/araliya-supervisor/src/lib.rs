@@ -1,10 +1,15 @@
 //! Supervisor runtime orchestrator.
 //!
 //! Contains the dispatch loop, internal control plane, transport adapters,
-//! and the management bus handler. Depends on `araliya-core` for bus protocol
+//! the management bus handler, the startup self-test, the readiness signal,
+//! handler panic reporting and single-flight request coalescing. Depends on `araliya-core` for bus protocol
 //! types, traits, and shared primitives.
 
 pub mod adapters;
+pub mod coalesce;
 pub mod control;
 pub mod management;
+pub mod panic_hook;
+pub mod readiness;
 pub mod run;
+pub mod self_test;
@@ -6,9 +6,29 @@
 //! - `manage/tree` — same tree for control/CLI consumers.
 //! - `manage/observe/snapshot` — last N observability events from the ring buffer.
 //! - `manage/observe/clear` — drain the ring buffer.
+//! - `manage/config` — effective (merged) config as JSON, secrets redacted.
+//!   Protected: see [`araliya_core::bus::acl`].
+//! - `manage/logs?level=debug&limit=200` — recent log records from the
+//!   in-memory buffer (`[supervisor] log_buffer_capacity`), oldest first.
+//!   `level` is the minimum severity (default `info`).  Protected.
+//! - `manage/maintenance` — current maintenance-mode state.
+//! - `manage/maintenance/on`, `manage/maintenance/off` — toggle maintenance
+//!   mode (agent requests get a fixed reply).  Protected.
+//! - `manage/memory/export`, `manage/memory/import` — back up the whole
+//!   memory tree as a `.tar.gz` (streamed back, or written to a `{"path": ...}`
+//!   on the host), or restore it from one in maintenance mode; forwarded to
+//!   `memory/export` / `memory/import`.  Protected.
+//! - `manage/health/refresh` — ask every subsystem to re-check its health in
+//!   parallel, then return the health body plus each subsystem's refresh
+//!   latency and whether it timed out (`[health] refresh_timeout_ms`).
+//!
+//! Consumes the `manage/spend/alert` notification (sent by the agents
+//! subsystem when a session crosses `agents.spend_alert_usd`): logged at WARN
+//! and recorded in the observability ring.
 
 use std::collections::VecDeque;
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use tokio::sync::{oneshot, RwLock};
 use tracing::{debug, warn};
@@ -16,13 +36,21 @@ use tracing::{debug, warn};
 use crate::control::{ControlCommand, ControlHandle, ControlResponse};
 use araliya_core::bus::{
     BusError, BusHandle, BusHandler, BusPayload, BusResult, ComponentInfo, ComponentStatusResponse,
-    HealthRegistry, ERR_METHOD_NOT_FOUND,
+    HealthRegistry, Maintenance, ERR_METHOD_NOT_FOUND,
 };
-use araliya_core::obs::{ObsBus, ObsEvent};
+use araliya_core::config::Config;
+use araliya_core::logger::LogBuffer;
+use araliya_core::obs::{ObsBus, ObsEvent, ObsLevel, ObservabilityHandle};
 
 /// Default ring buffer capacity (last N events kept in memory).
 const DEFAULT_RING_CAPACITY: usize = 1000;
 
+/// Records returned by `manage/logs` when no `limit` is given.
+const DEFAULT_LOGS_LIMIT: usize = 200;
+
+/// Default per-subsystem timeout for `manage/health/refresh`.
+const DEFAULT_REFRESH_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Static info collected at startup and included in the health response.
 #[derive(Debug, Clone)]
 pub struct ManagementInfo {
@@ -43,6 +71,24 @@ pub struct ManagementSubsystem {
     /// Ring buffer of recent observability events — written by the drain task,
     /// read by `manage/observe/snapshot`.
     ring: Arc<RwLock<VecDeque<ObsEvent>>>,
+    /// Emits management-originated events (spend alerts) into the ring.
+    obs: ObservabilityHandle,
+    /// Effective config serialized at startup, served by `manage/config`.
+    effective_config: Option<String>,
+    /// Maintenance switch shared with the agents subsystem.
+    maintenance: Maintenance,
+    /// Per-subsystem timeout for `manage/health/refresh`.
+    refresh_timeout: Duration,
+    /// Recent log records served by `manage/logs`.
+    logs: Option<LogBuffer>,
+}
+
+/// How one subsystem answered its `{prefix}/health` call during a refresh.
+#[derive(Debug, Clone, serde::Serialize)]
+struct RefreshTiming {
+    id: String,
+    latency_ms: u64,
+    timed_out: bool,
 }
 
 impl ManagementSubsystem {
@@ -57,7 +103,7 @@ impl ManagementSubsystem {
         let ring = Arc::new(RwLock::new(VecDeque::with_capacity(DEFAULT_RING_CAPACITY)));
 
         // Spawn the drain task that subscribes to the obs bus and fills the ring buffer.
-        Self::spawn_obs_drain(obs_bus, ring.clone(), bus.clone());
+        Self::spawn_obs_drain(obs_bus.clone(), ring.clone(), bus.clone());
 
         Self {
             control,
@@ -66,7 +112,41 @@ impl ManagementSubsystem {
             comms_info,
             health,
             ring,
+            obs: obs_bus.handle(),
+            effective_config: None,
+            maintenance: Maintenance::default(),
+            refresh_timeout: DEFAULT_REFRESH_TIMEOUT,
+            logs: None,
+        }
+    }
+
+    /// Snapshot the effective config for `manage/config`.
+    ///
+    /// API keys are redacted by `Config`'s `Serialize` impl.
+    pub fn with_effective_config(mut self, config: &Config) -> Self {
+        match serde_json::to_string(config) {
+            Ok(json) => self.effective_config = Some(json),
+            Err(e) => warn!("manage/config: failed to serialize config: {e}"),
         }
+        self
+    }
+
+    /// Share the maintenance switch toggled by `manage/maintenance/{on,off}`.
+    pub fn with_maintenance(mut self, maintenance: Maintenance) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Per-subsystem timeout for `manage/health/refresh`.
+    pub fn with_refresh_timeout(mut self, timeout: Duration) -> Self {
+        self.refresh_timeout = timeout;
+        self
+    }
+
+    /// Serve `manage/logs` from `buffer`.
+    pub fn with_log_buffer(mut self, buffer: LogBuffer) -> Self {
+        self.logs = Some(buffer);
+        self
     }
 
     /// Background task: subscribes to the obs bus and drains events into
@@ -122,10 +202,74 @@ fn tree_comms_message(content: String, channel_id: &str) -> BusPayload {
     }
 }
 
+/// Parse the `level` / `limit` query of `manage/logs`.
+fn parse_logs_query(query: &str) -> Result<(ObsLevel, usize), String> {
+    let mut level = ObsLevel::Info;
+    let mut limit = DEFAULT_LOGS_LIMIT;
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "level" => {
+                level = match value.to_ascii_lowercase().as_str() {
+                    "trace" => ObsLevel::Trace,
+                    "debug" => ObsLevel::Debug,
+                    "info" => ObsLevel::Info,
+                    "warn" => ObsLevel::Warn,
+                    "error" => ObsLevel::Error,
+                    _ => return Err(format!("unknown log level: {value:?}")),
+                }
+            }
+            "limit" => {
+                limit = value
+                    .parse()
+                    .map_err(|_| format!("limit must be a number, got {value:?}"))?
+            }
+            _ => return Err(format!("unknown query parameter: {key:?}")),
+        }
+    }
+    Ok((level, limit))
+}
+
 fn control_status_error(e: impl std::fmt::Display) -> BusError {
     BusError::new(-32000, format!("{e}"))
 }
 
+/// Send `{prefix}/health` to every subsystem concurrently and wait for all of
+/// them (each bounded by `timeout`).  Results are sorted by subsystem id.
+async fn refresh_health(
+    bus: &BusHandle,
+    prefixes: &[String],
+    timeout: Duration,
+) -> Vec<RefreshTiming> {
+    let mut join_set = tokio::task::JoinSet::new();
+    for prefix in prefixes {
+        let method = format!("{prefix}/health");
+        let id = prefix.clone();
+        let bus = bus.clone();
+        join_set.spawn(async move {
+            let started = Instant::now();
+            let timed_out = tokio::time::timeout(timeout, bus.request(method, BusPayload::Empty))
+                .await
+                .is_err();
+            RefreshTiming {
+                id,
+                latency_ms: started.elapsed().as_millis() as u64,
+                timed_out,
+            }
+        });
+    }
+
+    let mut timings = Vec::with_capacity(prefixes.len());
+    while let Some(joined) = join_set.join_next().await {
+        match joined {
+            Ok(timing) => timings.push(timing),
+            Err(e) => warn!("health refresh task failed: {e}"),
+        }
+    }
+    timings.sort_by(|a, b| a.id.cmp(&b.id));
+    timings
+}
+
 impl BusHandler for ManagementSubsystem {
     fn prefix(&self) -> &str {
         "manage"
@@ -137,6 +281,36 @@ impl BusHandler for ManagementSubsystem {
         ComponentInfo::leaf("manage", "Management")
     }
 
+    fn handle_notification(&self, method: &str, payload: BusPayload) {
+        match (method, payload) {
+            (
+                "manage/spend/alert",
+                BusPayload::SpendAlert {
+                    session_id,
+                    total_cost_usd,
+                },
+            ) => {
+                warn!(
+                    session_id = %session_id,
+                    total_cost_usd,
+                    "session spend crossed the alert threshold"
+                );
+                self.obs.emit(
+                    ObsEvent::now(
+                        ObsLevel::Warn,
+                        "agents",
+                        format!("session spend reached ${total_cost_usd:.4}"),
+                        Some(session_id),
+                        None,
+                        None,
+                    )
+                    .with_fields(serde_json::json!({ "total_cost_usd": total_cost_usd })),
+                );
+            }
+            (method, _) => debug!(method, "management: ignoring notification"),
+        }
+    }
+
     fn handle_request(
         &self,
         method: &str,
@@ -149,6 +323,13 @@ impl BusHandler for ManagementSubsystem {
         const HEALTH_REFRESH: &str = "manage/health/refresh";
         const OBSERVE_SNAPSHOT: &str = "manage/observe/snapshot";
         const OBSERVE_CLEAR: &str = "manage/observe/clear";
+        const CONFIG: &str = "manage/config";
+        const LOGS: &str = "manage/logs";
+        const MAINTENANCE: &str = "manage/maintenance";
+        const MAINTENANCE_ON: &str = "manage/maintenance/on";
+        const MAINTENANCE_OFF: &str = "manage/maintenance/off";
+        const MEMORY_EXPORT: &str = "manage/memory/export";
+        const MEMORY_IMPORT: &str = "manage/memory/import";
 
         // manage/status — management subsystem is always running.
         if method == "manage/status" {
@@ -159,6 +340,70 @@ impl BusHandler for ManagementSubsystem {
             return;
         }
 
+        if method == CONFIG {
+            let _ = reply_tx.send(match &self.effective_config {
+                Some(data) => Ok(BusPayload::JsonResponse { data: data.clone() }),
+                None => Err(BusError::new(
+                    ERR_METHOD_NOT_FOUND,
+                    "effective config not available".to_string(),
+                )),
+            });
+            return;
+        }
+
+        let (base, query) = method.split_once('?').unwrap_or((method, ""));
+        if base == LOGS {
+            let Some(logs) = &self.logs else {
+                let _ = reply_tx.send(Err(BusError::new(
+                    ERR_METHOD_NOT_FOUND,
+                    "log buffer not enabled".to_string(),
+                )));
+                return;
+            };
+            let _ = reply_tx.send(match parse_logs_query(query) {
+                Ok((level, limit)) => {
+                    let records = logs.recent(level, limit);
+                    let data = serde_json::to_string(&records).unwrap_or_else(|_| "[]".into());
+                    Ok(BusPayload::JsonResponse { data })
+                }
+                Err(e) => Err(BusError::new(-32602, e)),
+            });
+            return;
+        }
+
+        if matches!(method, MAINTENANCE | MAINTENANCE_ON | MAINTENANCE_OFF) {
+            // `set_enabled` returns the previous state; log only real changes.
+            match method {
+                MAINTENANCE_ON if !self.maintenance.set_enabled(true) => {
+                    warn!("maintenance mode enabled — agent requests now get a fixed reply");
+                }
+                MAINTENANCE_OFF if self.maintenance.set_enabled(false) => {
+                    warn!("maintenance mode disabled — agent routing resumed");
+                }
+                _ => {}
+            }
+            let data = serde_json::json!({
+                "maintenance": self.maintenance.is_enabled(),
+                "message": self.maintenance.message(),
+            })
+            .to_string();
+            let _ = reply_tx.send(Ok(BusPayload::JsonResponse { data }));
+            return;
+        }
+
+        if matches!(method, MEMORY_EXPORT | MEMORY_IMPORT) {
+            let bus = self.bus.clone();
+            let target = method.trim_start_matches("manage/").to_string();
+            tokio::spawn(async move {
+                let result = match bus.request(target, payload).await {
+                    Ok(result) => result,
+                    Err(e) => Err(BusError::new(-32000, format!("bus error: {e}"))),
+                };
+                let _ = reply_tx.send(result);
+            });
+            return;
+        }
+
         // ── Observability endpoints ─────────────────────────────────────
         if method == OBSERVE_SNAPSHOT {
             let ring = self.ring.clone();
@@ -205,6 +450,7 @@ impl BusHandler for ManagementSubsystem {
         let info = self.info.clone();
         let comms_info = self.comms_info.clone();
         let health = self.health.clone();
+        let refresh_timeout = self.refresh_timeout;
         let channel_id = if method == HTTP_TREE {
             "manage-http-tree"
         } else if method == TREE {
@@ -323,25 +569,17 @@ impl BusHandler for ManagementSubsystem {
             // wait for all (with per-request timeout), then fall through to build
             // the health body with fresh data from the registry.
             let (uptime_ms, handlers) = status;
-            if is_refresh {
-                let mut join_set = tokio::task::JoinSet::new();
-                for prefix in handlers.iter().filter(|h| h.as_str() != "manage") {
-                    let method = format!("{prefix}/health");
-                    let bus2 = bus.clone();
-                    join_set.spawn(async move {
-                        let _ = tokio::time::timeout(
-                            std::time::Duration::from_secs(5),
-                            bus2.request(method, BusPayload::Empty),
-                        )
-                        .await;
-                    });
-                }
-                // Wait for all concurrent checks to finish (or time out).
-                while join_set.join_next().await.is_some() {}
-            }
+            let refresh = if is_refresh {
+                let prefixes: Vec<String> = handlers
+                    .into_iter()
+                    .filter(|h| h.as_str() != "manage")
+                    .collect();
+                Some(refresh_health(&bus, &prefixes, refresh_timeout).await)
+            } else {
+                None
+            };
 
             // manage/http/get / manage/health/refresh: health body
-            let (uptime_ms, _handlers) = (uptime_ms, handlers);
 
             let cron_schedules = match bus.request("cron/list", BusPayload::CronList).await {
                 Ok(Ok(BusPayload::CronListResult { entries })) => entries
@@ -361,7 +599,12 @@ impl BusHandler for ManagementSubsystem {
             // Read live health state from the registry (instant — no fan-out).
             let health_snapshot = health.snapshot().await;
             let all_healthy = health_snapshot.iter().all(|h| h.healthy);
-            let top_status = if all_healthy { "ok" } else { "degraded" };
+            let all_ready = all_healthy && health_snapshot.iter().all(|h| h.ready);
+            let top_status = match (all_healthy, all_ready) {
+                (false, _) => "degraded",
+                (true, false) => "starting",
+                (true, true) => "ok",
+            };
 
             let subsystems_json: Vec<serde_json::Value> = health_snapshot
                 .iter()
@@ -369,6 +612,7 @@ impl BusHandler for ManagementSubsystem {
                     let mut v = serde_json::json!({
                         "id": h.id,
                         "healthy": h.healthy,
+                        "ready": h.ready,
                         "message": h.message,
                     });
                     if let Some(details) = &h.details {
@@ -378,8 +622,9 @@ impl BusHandler for ManagementSubsystem {
                 })
                 .collect();
 
-            let body = serde_json::json!({
+            let mut body = serde_json::json!({
                 "status": top_status,
+                "ready": all_ready,
                 "uptime_ms": uptime_ms,
                 "main_process": {
                     "id": "supervisor",
@@ -400,7 +645,246 @@ impl BusHandler for ManagementSubsystem {
                 "max_tool_rounds": 0,
                 "session_count": 0,
             });
+            if let Some(refresh) = refresh {
+                body["refresh"] = serde_json::to_value(refresh).unwrap_or_default();
+            }
             let _ = reply_tx.send(Ok(tree_comms_message(body.to_string(), channel_id)));
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::SupervisorControl;
+    use araliya_core::bus::{BusMessage, SupervisorBus};
+    use araliya_core::config::{load_from, REDACTED};
+    use std::io::Write;
+
+    fn loaded_config() -> Config {
+        let mut f = tempfile::NamedTempFile::new().unwrap();
+        f.write_all(
+            br#"
+[supervisor]
+bot_name = "cfg-bot"
+work_dir = "/tmp/araliya-test"
+log_level = "info"
+
+[llm]
+default = "dummy"
+"#,
+        )
+        .unwrap();
+        let mut cfg = load_from(f.path(), None, None).unwrap();
+        cfg.openai_api_key = Some("sk-very-secret".to_string());
+        cfg
+    }
+
+    fn management(config: &Config) -> ManagementSubsystem {
+        let bus = SupervisorBus::new(8);
+        let control = SupervisorControl::new(8);
+        ManagementSubsystem::new(
+            control.handle,
+            bus.handle,
+            ManagementInfo {
+                bot_id: "test".to_string(),
+                llm_provider: "dummy".to_string(),
+                llm_model: "dummy".to_string(),
+                llm_timeout_seconds: 60,
+            },
+            Arc::new(OnceLock::new()),
+            HealthRegistry::new(),
+            ObsBus::new(),
+        )
+        .with_effective_config(config)
+    }
+
+    #[tokio::test]
+    async fn manage_config_returns_redacted_effective_config() {
+        let cfg = loaded_config();
+        let mgmt = management(&cfg);
+
+        let (tx, rx) = oneshot::channel();
+        mgmt.handle_request("manage/config", BusPayload::Empty, tx);
+        let data = match rx.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => data,
+            other => panic!("unexpected response: {other:?}"),
+        };
+
+        assert!(!data.contains("sk-very-secret"));
+        let v: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(v["openai_api_key"], REDACTED);
+        assert_eq!(v["bot_name"], "cfg-bot");
+        assert_eq!(v["llm"]["default"], "dummy");
+        assert_eq!(v, serde_json::to_value(&cfg).unwrap());
+    }
+
+    #[tokio::test]
+    async fn health_refresh_reports_latency_and_timeouts() {
+        let bus = SupervisorBus::new(8);
+        let mut rx = bus.rx;
+        tokio::spawn(async move {
+            // Hold the slow subsystem's reply open until the test ends.
+            let mut pending = Vec::new();
+            while let Some(msg) = rx.recv().await {
+                if let BusMessage::Request {
+                    method, reply_tx, ..
+                } = msg
+                {
+                    if method == "fast/health" {
+                        let _ = reply_tx.send(Ok(BusPayload::Empty));
+                    } else {
+                        pending.push(reply_tx);
+                    }
+                }
+            }
+        });
+
+        let prefixes = ["slow".to_string(), "fast".to_string()];
+        let timings = refresh_health(&bus.handle, &prefixes, Duration::from_millis(50)).await;
+
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings[0].id, "fast");
+        assert!(!timings[0].timed_out);
+        assert_eq!(timings[1].id, "slow");
+        assert!(timings[1].timed_out);
+        assert!(timings[1].latency_ms >= 50);
+    }
+
+    #[test]
+    fn manage_config_is_protected() {
+        assert!(araliya_core::bus::acl::is_protected("manage/config"));
+    }
+
+    #[tokio::test]
+    async fn manage_logs_returns_recent_records_filtered_by_level() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let buffer = LogBuffer::new(16);
+        let subscriber = tracing_subscriber::registry().with(buffer.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::trace!("noise");
+            tracing::debug!(subsystem = "cron", "scheduled");
+            tracing::info!("started");
+            tracing::warn!("slow reply");
+        });
+        let mgmt = management(&loaded_config()).with_log_buffer(buffer);
+
+        let query = |method: &str| {
+            let (tx, rx) = oneshot::channel();
+            mgmt.handle_request(method, BusPayload::Empty, tx);
+            rx
+        };
+        let messages = |result: BusResult| match result {
+            Ok(BusPayload::JsonResponse { data }) => serde_json::from_str::<Vec<ObsEvent>>(&data)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.message)
+                .collect::<Vec<_>>(),
+            other => panic!("unexpected response: {other:?}"),
+        };
+
+        assert_eq!(
+            messages(query("manage/logs?level=debug&limit=200").await.unwrap()),
+            ["scheduled", "started", "slow reply"]
+        );
+        assert_eq!(
+            messages(query("manage/logs").await.unwrap()),
+            ["started", "slow reply"]
+        );
+        assert_eq!(
+            messages(query("manage/logs?level=trace&limit=1").await.unwrap()),
+            ["slow reply"]
+        );
+        let err = query("manage/logs?level=loud").await.unwrap().unwrap_err();
+        assert_eq!(err.code, -32602);
+        assert!(araliya_core::bus::acl::is_protected(
+            "manage/logs?level=debug"
+        ));
+    }
+
+    #[tokio::test]
+    async fn manage_maintenance_toggles_shared_switch() {
+        let maintenance = Maintenance::new(false, "back soon");
+        let mgmt = management(&loaded_config()).with_maintenance(maintenance.clone());
+
+        let call = |method: &str| {
+            let (tx, rx) = oneshot::channel();
+            mgmt.handle_request(method, BusPayload::Empty, tx);
+            rx
+        };
+        let state = |result: BusResult| match result {
+            Ok(BusPayload::JsonResponse { data }) => {
+                serde_json::from_str::<serde_json::Value>(&data).unwrap()["maintenance"]
+                    .as_bool()
+                    .unwrap()
+            }
+            other => panic!("unexpected response: {other:?}"),
+        };
+
+        assert!(state(call("manage/maintenance/on").await.unwrap()));
+        assert!(maintenance.is_enabled());
+        assert!(state(call("manage/maintenance").await.unwrap()));
+        assert!(!state(call("manage/maintenance/off").await.unwrap()));
+        assert!(!maintenance.is_enabled());
+
+        assert!(araliya_core::bus::acl::is_protected(
+            "manage/maintenance/on"
+        ));
+        assert!(!araliya_core::bus::acl::is_protected("manage/maintenance"));
+    }
+
+    #[tokio::test]
+    async fn manage_memory_export_is_forwarded_to_the_memory_subsystem() {
+        let bus = SupervisorBus::new(8);
+        let mut rx = bus.rx;
+        tokio::spawn(async move {
+            while let Some(BusMessage::Request {
+                method,
+                payload: BusPayload::JsonRequest { data },
+                reply_tx,
+                ..
+            }) = rx.recv().await
+            {
+                let _ = reply_tx.send(Ok(BusPayload::JsonResponse {
+                    data: format!("{method} {data}"),
+                }));
+            }
+        });
+        let control = SupervisorControl::new(8);
+        let mgmt = ManagementSubsystem::new(
+            control.handle,
+            bus.handle,
+            ManagementInfo {
+                bot_id: "test".to_string(),
+                llm_provider: "dummy".to_string(),
+                llm_model: "dummy".to_string(),
+                llm_timeout_seconds: 60,
+            },
+            Arc::new(OnceLock::new()),
+            HealthRegistry::new(),
+            ObsBus::new(),
+        );
+
+        let (tx, reply) = oneshot::channel();
+        let payload = BusPayload::JsonRequest {
+            data: r#"{"path":"/backups/bot.tar.gz"}"#.to_string(),
+        };
+        mgmt.handle_request("manage/memory/export", payload, tx);
+        match reply.await.unwrap() {
+            Ok(BusPayload::JsonResponse { data }) => {
+                assert_eq!(data, r#"memory/export {"path":"/backups/bot.tar.gz"}"#)
+            }
+            other => panic!("unexpected response: {other:?}"),
+        }
+
+        for method in [
+            "manage/memory/export",
+            "manage/memory/import",
+            "memory/export",
+            "memory/import",
+        ] {
+            assert!(araliya_core::bus::acl::is_protected(method), "{method}");
+        }
+    }
+}
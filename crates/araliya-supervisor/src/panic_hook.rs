@@ -0,0 +1,289 @@
+//! Panic reporting for bus handlers (`[supervisor] panic_hook`).
+//!
+//! Handlers resolve requests on tasks they spawn themselves, so a panic there
+//! is swallowed by tokio: the reply sender is dropped and the subsystem keeps
+//! reporting healthy.  With the hook enabled:
+//!
+//! - [`install`] wraps the process panic hook to log every panic as a
+//!   structured `error!` and remember its message on the panicking thread.
+//! - [`guard_reply`] hands a handler a proxy reply sender.  When the proxy is
+//!   dropped unanswered by a panicking task, the handler's subsystem is
+//!   marked unhealthy with the panic message and the caller gets an error
+//!   instead of a closed channel.
+//!
+//! Panics are matched to requests by task: tokio drops a panicked task's
+//! future while unwinding, on the thread that panicked, and the proxy is
+//! notified of the drop right there.  Replies are forwarded from that
+//! notification too, so no task is spawned per request.
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::PanicHookInfo;
+use std::pin::pin;
+use std::sync::{Arc, Mutex, Once};
+use std::task::{Context, Wake, Waker};
+
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tracing::error;
+
+use araliya_core::bus::{BusError, BusResult, HealthReporter};
+
+/// Error code replied to callers whose handler panicked.
+pub const ERR_HANDLER_PANICKED: i32 = -32603;
+
+thread_local! {
+    /// Message of the last panic on this thread.
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL: Once = Once::new();
+
+/// Install the reporting hook in front of the current panic hook.
+///
+/// Idempotent; the previous hook (normally the default stderr printer)
+/// still runs after the structured log line.
+pub fn install() {
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let message = panic_message(info);
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}", l.file(), l.line()))
+                .unwrap_or_default();
+            error!(panic = %message, %location, "task panicked");
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(message));
+            previous(info);
+        }));
+    });
+}
+
+/// Wrap `reply_tx` so a handler panic is reported to `reporter`.
+///
+/// Returns the sender to pass to the handler.  Replies are forwarded
+/// unchanged; a sender dropped without a reply by a task that is not
+/// panicking closes `reply_tx` exactly as before.
+pub fn guard_reply(
+    method: String,
+    reporter: HealthReporter,
+    reply_tx: oneshot::Sender<BusResult>,
+) -> oneshot::Sender<BusResult> {
+    let (tx, rx) = oneshot::channel();
+    let guard = Arc::new(ReplyGuard(Mutex::new(Some(Pending {
+        rx,
+        reply_tx,
+        method,
+        reporter,
+    }))));
+    guard.register();
+    tx
+}
+
+/// A proxied request still waiting on its handler.
+struct Pending {
+    rx: oneshot::Receiver<BusResult>,
+    reply_tx: oneshot::Sender<BusResult>,
+    method: String,
+    reporter: HealthReporter,
+}
+
+/// The waker registered on a proxy receiver.  It is woken by the handler's
+/// send or drop, on the handler's thread, and settles the request there.
+struct ReplyGuard(Mutex<Option<Pending>>);
+
+impl ReplyGuard {
+    /// Register `self` as the proxy receiver's waker.
+    fn register(self: &Arc<Self>) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(pending) = slot.as_mut() {
+            let waker = Waker::from(self.clone());
+            // Unconstrained, so an exhausted coop budget cannot skip the
+            // registration.
+            let recv = pin!(tokio::task::unconstrained(&mut pending.rx));
+            // Nothing has been sent yet, so this only registers the waker.
+            let _ = recv.poll(&mut Context::from_waker(&waker));
+        }
+    }
+
+    fn settle(self: &Arc<Self>) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(pending) = slot.as_mut() else {
+            return;
+        };
+        let outcome = pending.rx.try_recv();
+        if matches!(outcome, Err(TryRecvError::Empty)) {
+            drop(slot);
+            self.register();
+            return;
+        }
+        let Pending {
+            reply_tx,
+            method,
+            reporter,
+            ..
+        } = slot.take().expect("checked above");
+        drop(slot);
+        match outcome {
+            Ok(result) => {
+                let _ = reply_tx.send(result);
+            }
+            Err(_) => {
+                let panicked = std::thread::panicking()
+                    .then(|| LAST_PANIC.with(|last| last.borrow().clone()))
+                    .flatten();
+                let Some(message) = panicked else {
+                    return;
+                };
+                report_panic(method, reporter, reply_tx, message);
+            }
+        }
+    }
+}
+
+impl Wake for ReplyGuard {
+    fn wake(self: Arc<Self>) {
+        self.settle();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.settle();
+    }
+}
+
+/// Mark the subsystem unhealthy, then answer the caller with the panic.
+fn report_panic(
+    method: String,
+    reporter: HealthReporter,
+    reply_tx: oneshot::Sender<BusResult>,
+    message: String,
+) {
+    error!(subsystem = %reporter.id(), %method, panic = %message, "bus handler panicked");
+    let report = async move {
+        reporter.set_unhealthy(format!("panicked: {message}")).await;
+        let _ = reply_tx.send(Err(BusError::new(
+            ERR_HANDLER_PANICKED,
+            format!("{} handler panicked: {message}", reporter.id()),
+        )));
+    };
+    // Outside a runtime the caller just sees the channel close.
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(report);
+    }
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::SupervisorControl;
+    use araliya_core::bus::{BusHandler, BusPayload, HealthRegistry, SupervisorBus};
+    use tokio_util::sync::CancellationToken;
+
+    /// Panics on its request task for `boom/panic`, drops the reply for
+    /// `boom/drop` after a moment, echoes otherwise.
+    struct PanickyHandler;
+
+    impl BusHandler for PanickyHandler {
+        fn prefix(&self) -> &str {
+            "boom"
+        }
+
+        fn handle_request(
+            &self,
+            method: &str,
+            _payload: BusPayload,
+            reply_tx: oneshot::Sender<BusResult>,
+        ) {
+            let method = method.to_string();
+            tokio::spawn(async move {
+                match method.as_str() {
+                    "boom/panic" => panic!("handler exploded"),
+                    "boom/drop" => {
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                        drop(reply_tx);
+                    }
+                    _ => {
+                        let _ = reply_tx.send(Ok(BusPayload::Empty));
+                    }
+                }
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn handler_task_panic_marks_subsystem_unhealthy() {
+        install();
+        let registry = HealthRegistry::new();
+        let reporter = registry.reporter("boom");
+        reporter.set_healthy().await;
+
+        let bus = SupervisorBus::new(8);
+        let handle = bus.handle.clone();
+        let shutdown = CancellationToken::new();
+        tokio::spawn(crate::run::run(
+            bus,
+            SupervisorControl::new(8),
+            shutdown.clone(),
+            vec![Box::new(PanickyHandler)],
+            Some(registry.clone()),
+            None,
+        ));
+
+        let ok = handle.request("boom/echo", BusPayload::Empty).await;
+        assert!(matches!(ok, Ok(Ok(BusPayload::Empty))));
+
+        let err = handle
+            .request("boom/panic", BusPayload::Empty)
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code, ERR_HANDLER_PANICKED);
+        assert!(err.message.contains("handler exploded"));
+
+        let health = reporter.get_current().await.unwrap();
+        assert!(!health.healthy);
+        assert_eq!(health.message, "panicked: handler exploded");
+
+        // The supervisor keeps routing after the panic.
+        let ok = handle.request("boom/echo", BusPayload::Empty).await;
+        assert!(matches!(ok, Ok(Ok(BusPayload::Empty))));
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn a_panic_is_not_blamed_on_a_concurrent_request() {
+        install();
+        let registry = HealthRegistry::new();
+        registry.reporter("boom").set_healthy().await;
+
+        let bus = SupervisorBus::new(8);
+        let handle = bus.handle.clone();
+        let shutdown = CancellationToken::new();
+        tokio::spawn(crate::run::run(
+            bus,
+            SupervisorControl::new(8),
+            shutdown.clone(),
+            vec![Box::new(PanickyHandler)],
+            Some(registry),
+            None,
+        ));
+
+        let (panicked, dropped) = tokio::join!(
+            handle.request("boom/panic", BusPayload::Empty),
+            handle.request("boom/drop", BusPayload::Empty),
+        );
+        let err = panicked.unwrap().unwrap_err();
+        assert_eq!(err.code, ERR_HANDLER_PANICKED);
+        // The request dropped just after the panic only sees its channel close.
+        assert!(dropped.is_err(), "{dropped:?}");
+        shutdown.cancel();
+    }
+}
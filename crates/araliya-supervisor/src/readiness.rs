@@ -0,0 +1,110 @@
+//! Startup readiness signal for process supervisors.
+//!
+//! Once subsystems are up and the startup self-test has passed, the bot can
+//! announce that it is serving: a `READY` line on stdout
+//! (`[supervisor] ready_line`) for wrappers that watch the output, and
+//! `READY=1` over the systemd notify socket (`[supervisor] sd_notify`) for
+//! `Type=notify` units.
+
+use std::io::{self, Write};
+
+use tracing::{info, warn};
+
+/// Environment variable systemd sets to the notify socket path.
+pub const NOTIFY_SOCKET_ENV: &str = "NOTIFY_SOCKET";
+
+/// The machine-parseable ready line, e.g. `READY pid=4242 version=0.2.0`.
+pub fn ready_line(pid: u32, version: &str) -> String {
+    format!("READY pid={pid} version={version}")
+}
+
+/// Write [`ready_line`] for this process to `out` and flush it.
+pub fn write_ready_line(out: &mut impl Write, version: &str) -> io::Result<()> {
+    writeln!(out, "{}", ready_line(std::process::id(), version))?;
+    out.flush()
+}
+
+/// Send `state` (e.g. `READY=1`) to the socket named by `NOTIFY_SOCKET`.
+///
+/// Returns `Ok(false)` when the variable is unset, i.e. not running under
+/// systemd.  A leading `@` names a Linux abstract socket.
+#[cfg(unix)]
+pub fn sd_notify(state: &str) -> io::Result<bool> {
+    match std::env::var_os(NOTIFY_SOCKET_ENV) {
+        Some(path) if !path.is_empty() => {
+            notify_socket(&path.to_string_lossy(), state)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn sd_notify(_state: &str) -> io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(unix)]
+fn notify_socket(path: &str, state: &str) -> io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let socket = UnixDatagram::unbound()?;
+    #[cfg(target_os = "linux")]
+    if let Some(name) = path.strip_prefix('@') {
+        use std::os::linux::net::SocketAddrExt;
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+        socket.send_to_addr(state.as_bytes(), &addr)?;
+        return Ok(());
+    }
+    socket.send_to(state.as_bytes(), path)?;
+    Ok(())
+}
+
+/// Emit whichever readiness signals are enabled.  Failures are logged; a
+/// missing signal must not take the bot down.
+pub fn signal_ready(ready_line: bool, sd_notify_enabled: bool, version: &str) {
+    if ready_line {
+        if let Err(e) = write_ready_line(&mut io::stdout().lock(), version) {
+            warn!("ready line: {e}");
+        }
+    }
+    if sd_notify_enabled {
+        match sd_notify("READY=1") {
+            Ok(true) => info!("notified systemd: READY=1"),
+            Ok(false) => warn!("sd_notify is on but {NOTIFY_SOCKET_ENV} is not set"),
+            Err(e) => warn!("sd_notify failed: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_line_is_parseable() {
+        let mut out = Vec::new();
+        write_ready_line(&mut out, "1.2.3").unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert_eq!(
+            line,
+            format!("READY pid={} version=1.2.3\n", std::process::id())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn notify_socket_delivers_state() {
+        use std::os::unix::net::UnixDatagram;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let listener = UnixDatagram::bind(&path).unwrap();
+
+        notify_socket(path.to_str().unwrap(), "READY=1").unwrap();
+
+        let mut buf = [0u8; 32];
+        let n = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+    }
+}
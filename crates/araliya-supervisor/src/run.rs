@@ -1,17 +1,20 @@
 //! Supervisor dispatch loop — owns the event bus and routes messages between subsystems.
 
 use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::time::Instant;
 
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, info, trace, warn};
+use tracing::{debug, error, info, info_span, trace, warn};
 
+use crate::coalesce::Coalescer;
 use crate::control::{
     ControlCommand, ControlError, ControlMessage, ControlResponse, SupervisorControl,
 };
+use crate::panic_hook;
 use araliya_core::bus::{
-    BusError, BusHandler, BusMessage, ComponentInfo, ComponentStatus, SupervisorBus,
-    ERR_METHOD_NOT_FOUND,
+    BusError, BusHandler, BusMessage, ComponentInfo, ComponentStatus, HealthRegistry,
+    SupervisorBus, ERR_METHOD_NOT_FOUND,
 };
 
 /// Run the supervisor message loop until `shutdown` is cancelled.
@@ -20,6 +23,16 @@ use araliya_core::bus::{
 /// target subsystem by the first `/`-delimited method segment, and hands
 /// off ownership of `reply_tx` to the matching [`BusHandler`].
 ///
+/// A panic inside `handle_request` itself is caught and logged so it cannot
+/// take the loop down.  With `panic_health`, each reply is also routed
+/// through [`panic_hook::guard_reply`], so a panic in the handler's spawned
+/// task marks its subsystem unhealthy in that registry (the hook itself must
+/// be [installed](panic_hook::install)).
+///
+/// With a `coalescer`, an identical request arriving while one is in flight
+/// is answered with that request's reply instead of being dispatched (see
+/// [`crate::coalesce`]).
+///
 /// # Panics
 ///
 /// Panics on startup if two handlers share the same prefix — a programming
@@ -29,6 +42,8 @@ pub async fn run(
     mut control: SupervisorControl,
     shutdown: CancellationToken,
     handlers: Vec<Box<dyn BusHandler>>,
+    panic_health: Option<HealthRegistry>,
+    coalescer: Option<Coalescer>,
 ) {
     // Build the dispatch table; panic on duplicate prefixes.
     let mut table: HashMap<String, Box<dyn BusHandler>> = HashMap::new();
@@ -96,6 +111,7 @@ pub async fn run(
                                     state: ComponentStatus::On,
                                     uptime_ms: Some(uptime_ms),
                                     children,
+                                    metadata: None,
                                 };
                                 let tree_json = serde_json::to_string(&root).unwrap_or_else(|_| "{}".to_string());
                                 Ok(ControlResponse::ComponentTree { tree_json })
@@ -117,6 +133,12 @@ pub async fn run(
                                     message: format!("subsystem disable not implemented: {id}"),
                                 })
                             }
+                            ControlCommand::Config => Err(ControlError::Invalid {
+                                message: "config is served by the management adapters".to_string(),
+                            }),
+                            ControlCommand::Subscribe => Err(ControlError::Invalid {
+                                message: "subscribe is served by the management socket".to_string(),
+                            }),
                         };
                         let _ = reply_tx.send(result);
                     }
@@ -140,9 +162,37 @@ pub async fn run(
                         let prefix = method.split('/').next().unwrap_or_default();
                         match table.get(prefix) {
                             Some(handler) => {
-                                debug!(%id, %method, %prefix, "routing request");
-                                trace!(%id, %method, payload = ?payload, "request payload");
-                                handler.handle_request(&method, payload, reply_tx);
+                                // Correlation span: carries the request id to
+                                // log lines and exported traces (`otel`).
+                                let _span = info_span!("bus_request", %id, %method).entered();
+                                debug!(%prefix, "routing request");
+                                trace!(payload = ?payload, "request payload");
+                                let reply_tx = match &coalescer {
+                                    Some(coalescer) => {
+                                        match coalescer.join(&method, &payload, reply_tx) {
+                                            Some(reply_tx) => reply_tx,
+                                            None => {
+                                                debug!("joined identical in-flight request");
+                                                continue;
+                                            }
+                                        }
+                                    }
+                                    None => reply_tx,
+                                };
+                                let reply_tx = match &panic_health {
+                                    Some(registry) => panic_hook::guard_reply(
+                                        method.clone(),
+                                        registry.reporter(prefix),
+                                        reply_tx,
+                                    ),
+                                    None => reply_tx,
+                                };
+                                let handled = std::panic::catch_unwind(AssertUnwindSafe(|| {
+                                    handler.handle_request(&method, payload, reply_tx)
+                                }));
+                                if handled.is_err() {
+                                    error!(%prefix, %method, "bus handler panicked while dispatching");
+                                }
                             }
                             None => {
                                 warn!(%id, %method, "unhandled request method — replying with error");
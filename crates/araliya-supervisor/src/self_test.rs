@@ -0,0 +1,254 @@
+//! Startup self-test (`[supervisor] startup_self_test`).
+//!
+//! Once the supervisor is routing, a canary message is sent to the `echo`
+//! agent (`agents/echo`, a full agent round trip with no provider call),
+//! the default agent route is resolved without running it
+//! (`agents/resolve`), the LLM subsystem is pinged (`llm/health`) and
+//! memory round-trips a throwaway session (`memory/check`), so a disabled
+//! default agent, a bad API key or broken session storage shows up in the
+//! startup log instead of on the first user message.
+//!
+//! The default-agent check is critical: if it fails the bot cannot answer
+//! anyone.  The other checks are reported but not critical: `echo` need
+//! not be enabled, the provider may only be down for a moment (the LLM
+//! health checker keeps retrying), and agents without sessions still
+//! answer.
+
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use araliya_core::bus::health::SubsystemHealth;
+use araliya_core::bus::{BusHandle, BusPayload};
+
+/// Channel id the canary message is sent from.
+pub const SELF_TEST_CHANNEL: &str = "selftest";
+
+/// Content of the canary message.
+const CANARY_CONTENT: &str = "self-test canary";
+
+/// How long each check may take before it counts as failed.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Outcome of one self-test check.
+#[derive(Debug, Clone)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    /// A failed critical check can abort startup.
+    pub critical: bool,
+    pub detail: String,
+}
+
+/// Outcome of the whole self-test.
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    /// `true` when any critical check failed.
+    pub fn critical_failure(&self) -> bool {
+        self.checks.iter().any(|c| c.critical && !c.passed)
+    }
+
+    /// One-line summary, e.g. `agent canary: ok; llm health: FAIL (…)`.
+    pub fn summary(&self) -> String {
+        self.checks
+            .iter()
+            .map(|c| {
+                if c.passed {
+                    format!("{}: ok", c.name)
+                } else {
+                    format!("{}: FAIL ({})", c.name, c.detail)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Log the summary: INFO when every check passed, WARN otherwise.
+    pub fn log(&self) {
+        if self.passed() {
+            info!("startup self-test passed — {}", self.summary());
+        } else {
+            warn!("startup self-test failed — {}", self.summary());
+        }
+    }
+}
+
+/// Run the self-test against the subsystems in `handlers` (bus prefixes of
+/// the registered handlers).  Checks for subsystems that are not registered
+/// are skipped.
+pub async fn run(bus: &BusHandle, handlers: &[String], timeout: Duration) -> SelfTestReport {
+    let registered = |prefix: &str| handlers.iter().any(|h| h == prefix);
+    let mut report = SelfTestReport::default();
+    if registered("agents") {
+        report.checks.push(agent_canary(bus, timeout).await);
+        report.checks.push(default_agent(bus, timeout).await);
+    }
+    if registered("llm") {
+        report.checks.push(llm_health(bus, timeout).await);
+    }
+    if registered("memory") {
+        report.checks.push(memory_check(bus, timeout).await);
+    }
+    report
+}
+
+fn canary_message() -> BusPayload {
+    BusPayload::CommsMessage {
+        channel_id: SELF_TEST_CHANNEL.to_string(),
+        content: CANARY_CONTENT.to_string(),
+        session_id: None,
+        usage: None,
+        timing: None,
+        thinking: None,
+    }
+}
+
+/// Send the canary through the `echo` agent and expect its reply.
+async fn agent_canary(bus: &BusHandle, timeout: Duration) -> SelfTestCheck {
+    let result =
+        match tokio::time::timeout(timeout, bus.request("agents/echo", canary_message())).await {
+            Err(_) => Err(format!("no reply within {}ms", timeout.as_millis())),
+            Ok(Err(e)) => Err(e.to_string()),
+            Ok(Ok(Err(e))) => Err(e.message),
+            Ok(Ok(Ok(BusPayload::CommsMessage { .. }))) => Ok(()),
+            Ok(Ok(Ok(_))) => Err("unexpected reply payload".to_string()),
+        };
+    check("agent canary", false, result)
+}
+
+/// Resolve the canary against the default agent route without running it.
+async fn default_agent(bus: &BusHandle, timeout: Duration) -> SelfTestCheck {
+    let result = match tokio::time::timeout(
+        timeout,
+        bus.request("agents/resolve", canary_message()),
+    )
+    .await
+    {
+        Err(_) => Err(format!("no reply within {}ms", timeout.as_millis())),
+        Ok(Err(e)) => Err(e.to_string()),
+        Ok(Ok(Err(e))) => Err(e.message),
+        Ok(Ok(Ok(BusPayload::JsonResponse { .. }))) => Ok(()),
+        Ok(Ok(Ok(_))) => Err("unexpected reply payload".to_string()),
+    };
+    check("default agent", true, result)
+}
+
+async fn llm_health(bus: &BusHandle, timeout: Duration) -> SelfTestCheck {
+    let result =
+        match tokio::time::timeout(timeout, bus.request("llm/health", BusPayload::Empty)).await {
+            Err(_) => Err(format!("no reply within {}ms", timeout.as_millis())),
+            Ok(Err(e)) => Err(e.to_string()),
+            Ok(Ok(Err(e))) => Err(e.message),
+            Ok(Ok(Ok(BusPayload::JsonResponse { data }))) => {
+                match serde_json::from_str::<SubsystemHealth>(&data) {
+                    Ok(h) if h.healthy => Ok(()),
+                    Ok(h) => Err(h.message),
+                    Err(e) => Err(format!("malformed health reply: {e}")),
+                }
+            }
+            Ok(Ok(Ok(_))) => Err("unexpected reply payload".to_string()),
+        };
+    check("llm health", false, result)
+}
+
+async fn memory_check(bus: &BusHandle, timeout: Duration) -> SelfTestCheck {
+    let result =
+        match tokio::time::timeout(timeout, bus.request("memory/check", BusPayload::Empty)).await {
+            Err(_) => Err(format!("no reply within {}ms", timeout.as_millis())),
+            Ok(Err(e)) => Err(e.to_string()),
+            Ok(Ok(Err(e))) => Err(e.message),
+            Ok(Ok(Ok(_))) => Ok(()),
+        };
+    check("memory check", false, result)
+}
+
+fn check(name: &'static str, critical: bool, result: Result<(), String>) -> SelfTestCheck {
+    let (passed, detail) = match result {
+        Ok(()) => (true, String::new()),
+        Err(detail) => (false, detail),
+    };
+    SelfTestCheck {
+        name,
+        passed,
+        critical,
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use araliya_core::bus::{BusError, BusMessage, SupervisorBus, ERR_METHOD_NOT_FOUND};
+
+    /// Answer bus requests the way a bot whose default agent is not enabled
+    /// does, with a healthy LLM.
+    fn broken_default_agent_bus() -> BusHandle {
+        let bus = SupervisorBus::new(8);
+        let mut rx = bus.rx;
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let BusMessage::Request {
+                    method, reply_tx, ..
+                } = msg
+                {
+                    let reply = if method == "agents/resolve" {
+                        Err(BusError::new(
+                            ERR_METHOD_NOT_FOUND,
+                            "default agent 'chat' is not enabled",
+                        ))
+                    } else if method == "agents/echo" {
+                        Ok(BusPayload::CommsMessage {
+                            channel_id: SELF_TEST_CHANNEL.to_string(),
+                            content: CANARY_CONTENT.to_string(),
+                            session_id: None,
+                            usage: None,
+                            timing: None,
+                            thinking: None,
+                        })
+                    } else {
+                        Ok(BusPayload::JsonResponse {
+                            data: serde_json::to_string(&SubsystemHealth::ok("llm")).unwrap(),
+                        })
+                    };
+                    let _ = reply_tx.send(reply);
+                }
+            }
+        });
+        bus.handle
+    }
+
+    #[tokio::test]
+    async fn broken_default_agent_fails_the_self_test() {
+        let bus = broken_default_agent_bus();
+        let handlers = [
+            "agents".to_string(),
+            "llm".to_string(),
+            "memory".to_string(),
+        ];
+        let report = run(&bus, &handlers, Duration::from_secs(1)).await;
+
+        assert!(!report.passed());
+        assert!(report.critical_failure());
+        assert_eq!(
+            report.summary(),
+            "agent canary: ok; default agent: FAIL (default agent 'chat' is not enabled); \
+             llm health: ok; memory check: ok"
+        );
+    }
+
+    #[tokio::test]
+    async fn unregistered_subsystems_are_skipped() {
+        let bus = broken_default_agent_bus();
+        let report = run(&bus, &["llm".to_string()], Duration::from_secs(1)).await;
+        assert!(report.passed());
+        assert_eq!(report.checks.len(), 1);
+    }
+}
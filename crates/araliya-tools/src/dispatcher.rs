@@ -12,6 +12,8 @@ use araliya_core::config::NewsmailAggregatorConfig;
 use crate::gdelt_bigquery;
 #[cfg(feature = "plugin-gmail-tool")]
 use crate::gmail;
+#[cfg(feature = "plugin-memory-tool")]
+use crate::memory::MemoryTool;
 #[cfg(feature = "plugin-gmail-tool")]
 use crate::newsmail_aggregator;
 #[cfg(feature = "plugin-rss-fetch-tool")]
@@ -22,6 +24,8 @@ pub struct ToolsSubsystem {
     #[allow(dead_code)]
     newsmail_defaults: NewsmailAggregatorConfig,
     reporter: Option<HealthReporter>,
+    #[cfg(feature = "plugin-memory-tool")]
+    memory: Option<std::sync::Arc<MemoryTool>>,
 }
 
 impl ToolsSubsystem {
@@ -29,9 +33,18 @@ impl ToolsSubsystem {
         Self {
             newsmail_defaults,
             reporter: None,
+            #[cfg(feature = "plugin-memory-tool")]
+            memory: None,
         }
     }
 
+    /// Serve the `memory` tool from `memory`'s agent session stores.
+    #[cfg(feature = "plugin-memory-tool")]
+    pub fn with_memory(mut self, memory: MemoryTool) -> Self {
+        self.memory = Some(std::sync::Arc::new(memory));
+        self
+    }
+
     /// Attach a health reporter.  Reports healthy at startup; individual tool
     /// failures are surfaced per-execution, not via subsystem health state.
     pub fn with_health_reporter(mut self, reporter: HealthReporter) -> Self {
@@ -115,6 +128,10 @@ impl BusHandler for ToolsSubsystem {
             {
                 available_tools.push("rss_fetch");
             }
+            #[cfg(feature = "plugin-memory-tool")]
+            if self.memory.is_some() {
+                available_tools.push("memory");
+            }
             let available_tools: Vec<String> =
                 available_tools.iter().map(|s| s.to_string()).collect();
             tokio::spawn(async move {
@@ -153,8 +170,37 @@ impl BusHandler for ToolsSubsystem {
                 action,
                 args_json,
                 channel_id: _,
-                session_id: _,
+                session_id,
+                agent_id,
             } => {
+                #[cfg(feature = "plugin-memory-tool")]
+                if tool == "memory" {
+                    if let Some(memory) = self.memory.clone() {
+                        tokio::spawn(async move {
+                            let result = memory
+                                .execute(
+                                    agent_id.as_deref(),
+                                    session_id.as_deref(),
+                                    &action,
+                                    &args_json,
+                                )
+                                .await;
+                            let (ok, data_json, error) = match result {
+                                Ok(data) => (true, Some(data.to_string()), None),
+                                Err(e) => (false, None, Some(e)),
+                            };
+                            let _ = reply_tx.send(Ok(BusPayload::ToolResponse {
+                                tool,
+                                action,
+                                ok,
+                                data_json,
+                                error,
+                            }));
+                        });
+                        return;
+                    }
+                }
+
                 #[cfg(feature = "plugin-gmail-tool")]
                 if tool == "gmail" && action == "read_latest" {
                     tokio::spawn(async move {
@@ -369,7 +415,7 @@ impl BusHandler for ToolsSubsystem {
                 }
 
                 // Suppress unused-variable lint when no tool features are compiled in.
-                let _ = args_json;
+                let _ = (args_json, agent_id);
                 let _ = reply_tx.send(Err(BusError::new(
                     ERR_METHOD_NOT_FOUND,
                     format!("tool/action not found: {tool}/{action}"),
@@ -399,6 +445,10 @@ impl BusHandler for ToolsSubsystem {
         {
             children.push(ComponentInfo::leaf("rss_fetch", "RSS Fetch"));
         }
+        #[cfg(feature = "plugin-memory-tool")]
+        if self.memory.is_some() {
+            children.push(ComponentInfo::leaf("memory", "Memory"));
+        }
         children.sort_by(|a, b| a.id.cmp(&b.id));
         ComponentInfo::running("tools", "Tools", children)
     }
@@ -1,10 +1,13 @@
-//! Tools subsystem — external tool integrations (Gmail, GDELT BigQuery, RSS).
+//! Tools subsystem — external tool integrations (Gmail, GDELT BigQuery, RSS)
+//! and the `memory` tool over an agent's own sessions.
 
 pub mod dispatcher;
 #[cfg(feature = "plugin-gdelt-tool")]
 pub mod gdelt_bigquery;
 #[cfg(feature = "plugin-gmail-tool")]
 pub mod gmail;
+#[cfg(feature = "plugin-memory-tool")]
+pub mod memory;
 #[cfg(feature = "plugin-gmail-tool")]
 pub mod newsmail_aggregator;
 #[cfg(feature = "plugin-rss-fetch-tool")]
@@ -0,0 +1,365 @@
+//! `memory` tool — lets an agent look through its own past sessions.
+//!
+//! | Action          | Params                         | `data_json`                                   |
+//! |-----------------|--------------------------------|-----------------------------------------------|
+//! | `list_sessions` | —                              | `{"sessions": [{session_id, created_at, …}]}` |
+//! | `search`        | `{"query": "..."}`             | `{"query", "hits": [{session_id, role, …}]}`  |
+//! | `get_session`   | `{"session_id": "...", "last"}`| `{"session_id", "transcript": [...]}`         |
+//!
+//! Every call is scoped to the calling session (`ToolRequest.session_id`)
+//! in the calling agent's own session store (`ToolRequest.agent_id`, which
+//! must be a registered agent).  With `[tools.memory] all_sessions`, every
+//! session in that store is in reach instead — sessions of other users of
+//! the same agent included, so only for single-user bots.  Sessions are
+//! opened with [`MemorySystem::peek_session_in`], so looking through them
+//! does not mark them used or un-archive them.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use araliya_core::config::MemoryToolConfig;
+use araliya_memory::stores::agent::AgentStore;
+use araliya_memory::{MemorySystem, SessionInfo};
+
+/// Transcript entries `get_session` returns when `last` is not given.
+const DEFAULT_LAST: usize = 50;
+
+/// Most recent transcript entries `search` reads per session.
+const SEARCH_LAST: usize = 500;
+
+/// Most recently updated sessions `search` looks through.
+const SEARCH_SESSIONS: usize = 50;
+
+#[derive(Debug, Default, Deserialize)]
+struct SearchArgs {
+    #[serde(default)]
+    query: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GetSessionArgs {
+    #[serde(default)]
+    session_id: String,
+    #[serde(default)]
+    last: Option<usize>,
+}
+
+pub struct MemoryTool {
+    memory: Arc<MemorySystem>,
+    /// Identity dirs of the registered agents, by agent id.
+    agent_dirs: Arc<HashMap<String, PathBuf>>,
+    max_results: usize,
+    all_sessions: bool,
+}
+
+impl MemoryTool {
+    pub fn new(
+        memory: Arc<MemorySystem>,
+        agent_dirs: Arc<HashMap<String, PathBuf>>,
+        config: &MemoryToolConfig,
+    ) -> Self {
+        Self {
+            memory,
+            agent_dirs,
+            max_results: config.max_results,
+            all_sessions: config.all_sessions,
+        }
+    }
+
+    /// Run `action` for `agent_id` in `session_id`; the reply's `data_json`
+    /// on success.
+    pub async fn execute(
+        &self,
+        agent_id: Option<&str>,
+        session_id: Option<&str>,
+        action: &str,
+        args_json: &str,
+    ) -> Result<Value, String> {
+        let agent_id = agent_id.ok_or("memory tool needs a calling agent")?;
+        let (sessions_root, index_path) = self.agent_sessions(agent_id)?;
+        let in_scope =
+            |info: &SessionInfo| self.all_sessions || session_id == Some(info.session_id.as_str());
+        if !self.all_sessions && session_id.is_none() {
+            return Err("memory tool needs a calling session".to_string());
+        }
+        match action {
+            "list_sessions" => {
+                let sessions = MemorySystem::list_sessions_in(&index_path)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .filter(|s| in_scope(s))
+                    .take(self.max_results)
+                    .map(|s| {
+                        json!({
+                            "session_id": s.session_id,
+                            "created_at": s.created_at,
+                            "updated_at": s.updated_at,
+                            "archived": s.archived,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({ "sessions": sessions }))
+            }
+            "search" => {
+                let args: SearchArgs = serde_json::from_str(args_json).unwrap_or_default();
+                let query = args.query.trim().to_lowercase();
+                if query.is_empty() {
+                    return Err("search needs a non-empty \"query\"".to_string());
+                }
+                let sessions =
+                    MemorySystem::list_sessions_in(&index_path).map_err(|e| e.to_string())?;
+                let mut hits = Vec::new();
+                let in_reach = sessions
+                    .iter()
+                    .filter(|s| in_scope(s))
+                    .take(SEARCH_SESSIONS);
+                'sessions: for info in in_reach {
+                    let Ok(handle) =
+                        self.memory
+                            .peek_session_in(&sessions_root, &index_path, &info.session_id)
+                    else {
+                        continue;
+                    };
+                    let Ok(entries) = handle.transcript_read_last(SEARCH_LAST).await else {
+                        continue;
+                    };
+                    // Newest entries first, like the sessions.
+                    for entry in entries.into_iter().rev() {
+                        if entry.content.to_lowercase().contains(&query) {
+                            hits.push(json!({
+                                "session_id": info.session_id,
+                                "role": entry.role,
+                                "timestamp": entry.timestamp,
+                                "content": entry.content,
+                            }));
+                            if hits.len() >= self.max_results {
+                                break 'sessions;
+                            }
+                        }
+                    }
+                }
+                Ok(json!({ "query": args.query, "hits": hits }))
+            }
+            "get_session" => {
+                let args: GetSessionArgs = serde_json::from_str(args_json).unwrap_or_default();
+                if !self.all_sessions && session_id != Some(args.session_id.as_str()) {
+                    return Err(format!("session not found: {}", args.session_id));
+                }
+                let handle = self
+                    .memory
+                    .peek_session_in(&sessions_root, &index_path, &args.session_id)
+                    .map_err(|e| e.to_string())?;
+                let transcript = handle
+                    .transcript_read_last(args.last.unwrap_or(DEFAULT_LAST))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|e| {
+                        json!({
+                            "role": e.role,
+                            "timestamp": e.timestamp,
+                            "content": e.content,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({ "session_id": args.session_id, "transcript": transcript }))
+            }
+            other => Err(format!("unknown memory action: {other}")),
+        }
+    }
+
+    /// Session root and index of registered agent `agent_id`'s own
+    /// session store.
+    fn agent_sessions(&self, agent_id: &str) -> Result<(PathBuf, PathBuf), String> {
+        let dir = self
+            .agent_dirs
+            .get(agent_id)
+            .ok_or_else(|| format!("unknown agent: {agent_id}"))?;
+        let store = AgentStore::open(dir).map_err(|e| e.to_string())?;
+        Ok((store.agent_sessions_dir(), store.agent_sessions_index()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolsSubsystem;
+    use araliya_core::bus::{BusHandler, BusPayload};
+    use araliya_core::identity;
+    use araliya_memory::{MemoryConfig, AGENTS_DIRNAME};
+    use tempfile::TempDir;
+    use tokio::sync::oneshot;
+
+    /// A tool over a fresh memory root with agents `chat` and `docs`.
+    fn tool(all_sessions: bool) -> (TempDir, MemoryTool) {
+        let dir = TempDir::new().unwrap();
+        let memory = MemorySystem::new(dir.path(), MemoryConfig::default()).unwrap();
+        let agents_root = memory.memory_root().join(AGENTS_DIRNAME);
+        let agent_dirs = ["chat", "docs"]
+            .into_iter()
+            .map(|id| {
+                let identity = identity::setup_named_identity(&agents_root, id).unwrap();
+                (id.to_string(), identity.identity_dir)
+            })
+            .collect();
+        let config = MemoryToolConfig {
+            max_results: 20,
+            all_sessions,
+        };
+        let tool = MemoryTool::new(Arc::new(memory), Arc::new(agent_dirs), &config);
+        (dir, tool)
+    }
+
+    async fn session_with(tool: &MemoryTool, agent_id: &str, lines: &[&str]) -> String {
+        let (root, index) = tool.agent_sessions(agent_id).unwrap();
+        let handle = tool
+            .memory
+            .create_session_in(&root, &index, &["basic_session"], Some(agent_id))
+            .unwrap();
+        for line in lines {
+            handle.transcript_append("user", line).await.unwrap();
+        }
+        handle.session_id.clone()
+    }
+
+    /// `tools/execute` for `memory/{action}` as `agent_id` in `session_id`:
+    /// the reply's `data_json`, or its error.
+    async fn call(
+        tools: &ToolsSubsystem,
+        agent_id: &str,
+        session_id: &str,
+        action: &str,
+        args: Value,
+    ) -> Result<Value, String> {
+        let (tx, rx) = oneshot::channel();
+        tools.handle_request(
+            "tools/execute",
+            BusPayload::ToolRequest {
+                tool: "memory".to_string(),
+                action: action.to_string(),
+                args_json: args.to_string(),
+                channel_id: "test".to_string(),
+                session_id: Some(session_id.to_string()),
+                agent_id: Some(agent_id.to_string()),
+            },
+            tx,
+        );
+        match rx.await.unwrap() {
+            Ok(BusPayload::ToolResponse {
+                ok: true,
+                data_json: Some(data),
+                ..
+            }) => Ok(serde_json::from_str(&data).unwrap()),
+            Ok(BusPayload::ToolResponse { error: Some(e), .. }) => Err(e),
+            other => panic!("unexpected reply: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn all_sessions_reaches_only_the_calling_agents_sessions() {
+        let (_dir, tool) = tool(true);
+        let trip = session_with(&tool, "chat", &["Planning the Kandy trip", "book a train"]).await;
+        let other = session_with(&tool, "chat", &["unrelated"]).await;
+        let private = session_with(&tool, "docs", &["the kandy notes"]).await;
+        let tools = ToolsSubsystem::default().with_memory(tool);
+
+        let data = call(&tools, "chat", &other, "list_sessions", json!({}))
+            .await
+            .unwrap();
+        let mut listed: Vec<&str> = data["sessions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["session_id"].as_str().unwrap())
+            .collect();
+        listed.sort();
+        let mut expected = vec![trip.as_str(), other.as_str()];
+        expected.sort();
+        assert_eq!(listed, expected);
+
+        let data = call(
+            &tools,
+            "chat",
+            &other,
+            "search",
+            json!({ "query": "KANDY" }),
+        )
+        .await
+        .unwrap();
+        let hits = data["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["session_id"], trip.as_str());
+        assert_eq!(hits[0]["content"], "Planning the Kandy trip");
+
+        let data = call(
+            &tools,
+            "chat",
+            &other,
+            "get_session",
+            json!({ "session_id": trip, "last": 1 }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(data["transcript"][0]["content"], "book a train");
+
+        // Another agent's session is out of reach.
+        let err = call(
+            &tools,
+            "chat",
+            &other,
+            "get_session",
+            json!({ "session_id": private }),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("session not found"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn by_default_only_the_calling_session_is_in_reach() {
+        let (_dir, tool) = tool(false);
+        let mine = session_with(&tool, "chat", &["my kandy trip"]).await;
+        let theirs = session_with(&tool, "chat", &["their kandy trip"]).await;
+        let agents_root = tool.memory.memory_root().join(AGENTS_DIRNAME);
+        let tools = ToolsSubsystem::default().with_memory(tool);
+
+        let data = call(&tools, "chat", &mine, "list_sessions", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(data["sessions"].as_array().unwrap().len(), 1);
+        assert_eq!(data["sessions"][0]["session_id"], mine.as_str());
+
+        let data = call(&tools, "chat", &mine, "search", json!({ "query": "kandy" }))
+            .await
+            .unwrap();
+        let hits = data["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["content"], "my kandy trip");
+
+        let err = call(
+            &tools,
+            "chat",
+            &mine,
+            "get_session",
+            json!({ "session_id": theirs }),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("session not found"), "{err}");
+
+        // An unregistered agent id is refused and gets no identity on disk.
+        let err = call(&tools, "ghost", &mine, "list_sessions", json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err, "unknown agent: ghost");
+        let dirs: Vec<String> = std::fs::read_dir(agents_root)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert!(dirs.iter().all(|d| !d.starts_with("ghost")), "{dirs:?}");
+    }
+}
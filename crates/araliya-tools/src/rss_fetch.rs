@@ -144,7 +144,7 @@ pub async fn fetch(args: RssFetchArgs) -> Result<Vec<RssItem>, String> {
     }
 
     // Sort newest first, then cap
-    all.sort_by(|a, b| b.0.cmp(&a.0));
+    all.sort_by_key(|(dt, _)| std::cmp::Reverse(*dt));
     let items: Vec<RssItem> = all
         .into_iter()
         .take(max_items)
@@ -0,0 +1,119 @@
+//! Lifecycle of the spawned `araliya-gpui` child.
+//!
+//! On beacon exit the child is asked to quit, given a grace period, then
+//! killed; either way it is reaped so it never lingers as an orphan or zombie.
+
+use std::io;
+use std::process::Child;
+use std::time::{Duration, Instant};
+
+/// Grace period between the quit request and a forced kill.
+pub const DEFAULT_QUIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Env var overriding [`DEFAULT_QUIT_TIMEOUT`], in milliseconds.
+pub const QUIT_TIMEOUT_ENV: &str = "ARALIYA_GPUI_QUIT_TIMEOUT_MS";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How [`shutdown`] ended the child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shutdown {
+    /// The child had already exited before the quit request.
+    AlreadyExited,
+    /// The child exited on its own within the grace period.
+    Graceful,
+    /// The grace period elapsed and the child was killed.
+    Killed,
+}
+
+/// Grace period from [`QUIT_TIMEOUT_ENV`], falling back to the default.
+pub fn quit_timeout() -> Duration {
+    std::env::var(QUIT_TIMEOUT_ENV)
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_QUIT_TIMEOUT)
+}
+
+/// Ask the process `pid` to quit.
+///
+/// TODO: replace with the gpui IPC `quit` command once araliya-gpui exposes one.
+pub fn request_quit(pid: u32) {
+    #[cfg(unix)]
+    {
+        let _ = std::process::Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .status();
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Stop `child`: call `quit` with its pid, wait up to `timeout` for it to
+/// exit, then `kill()` it.  The child is always reaped before returning.
+pub fn shutdown(
+    child: &mut Child,
+    quit: impl FnOnce(u32),
+    timeout: Duration,
+) -> io::Result<Shutdown> {
+    if child.try_wait()?.is_some() {
+        return Ok(Shutdown::AlreadyExited);
+    }
+
+    quit(child.id());
+
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if child.try_wait()?.is_some() {
+            return Ok(Shutdown::Graceful);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    // The child may exit between the last poll and the kill; in that case
+    // kill() fails with InvalidInput and wait() still reaps it.
+    let _ = child.kill();
+    child.wait()?;
+    Ok(Shutdown::Killed)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn spawn_sleep() -> Child {
+        Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("spawn sleep")
+    }
+
+    #[test]
+    fn kills_child_that_ignores_quit() {
+        let mut child = spawn_sleep();
+        let outcome = shutdown(&mut child, |_| {}, Duration::from_millis(100)).unwrap();
+        assert_eq!(outcome, Shutdown::Killed);
+        assert!(child.try_wait().unwrap().is_some(), "child must be reaped");
+    }
+
+    #[test]
+    fn quit_request_ends_child_gracefully() {
+        let mut child = spawn_sleep();
+        let outcome = shutdown(&mut child, request_quit, Duration::from_secs(5)).unwrap();
+        assert_eq!(outcome, Shutdown::Graceful);
+    }
+
+    #[test]
+    fn already_exited_child_is_not_signalled() {
+        let mut child = Command::new("true").spawn().expect("spawn true");
+        child.wait().unwrap();
+        let outcome = shutdown(
+            &mut child,
+            |_| panic!("quit must not be requested"),
+            Duration::from_millis(100),
+        )
+        .unwrap();
+        assert_eq!(outcome, Shutdown::AlreadyExited);
+    }
+}
@@ -16,6 +16,7 @@ pub enum Command {
     Health,
     Status,
     SubsystemsList,
+    Subscribe,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -33,6 +34,26 @@ pub enum ControlResponse {
     Ack {
         message: String,
     },
+    HealthChanged {
+        id: String,
+        healthy: bool,
+        ready: bool,
+        message: String,
+    },
+    Hello {
+        protocol_version: u32,
+        features: Vec<String>,
+        bot_id: String,
+    },
+}
+
+/// One subsystem's health, as pushed on a `Subscribe` connection.
+#[derive(Debug, Clone)]
+pub struct Presence {
+    pub id: String,
+    pub healthy: bool,
+    pub ready: bool,
+    pub message: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -91,17 +112,22 @@ pub async fn send_command(cmd: Command) -> Result<String, String> {
             .await
             .map_err(|e| format!("send: {e}"))?;
 
+        // Skip the handshake line, if the daemon sends one.
         let mut lines = BufReader::new(reader).lines();
-        let line = lines
-            .next_line()
-            .await
-            .map_err(|e| format!("recv: {e}"))?
-            .ok_or_else(|| "daemon closed without responding".to_string())?;
+        loop {
+            let line = lines
+                .next_line()
+                .await
+                .map_err(|e| format!("recv: {e}"))?
+                .ok_or_else(|| "daemon closed without responding".to_string())?;
 
-        let resp: WireResponse =
-            serde_json::from_str(&line).map_err(|e| format!("parse: {e}\n  raw: {line}"))?;
+            let resp: WireResponse =
+                serde_json::from_str(&line).map_err(|e| format!("parse: {e}\n  raw: {line}"))?;
 
-        Ok(format_response(resp))
+            if !matches!(resp, WireResponse::Ok(ControlResponse::Hello { .. })) {
+                return Ok(format_response(resp));
+            }
+        }
     }
 
     #[cfg(not(unix))]
@@ -111,6 +137,87 @@ pub async fn send_command(cmd: Command) -> Result<String, String> {
     }
 }
 
+/// Why a presence subscription ended with an error.
+#[derive(Debug)]
+pub enum SubscribeError {
+    /// The daemon answered `Subscribe` with an error: presence events are
+    /// off (`[supervisor] presence_events`); asking again will not help.
+    Refused(String),
+    /// The daemon is unreachable or the connection failed.
+    Failed(String),
+}
+
+/// Subscribe to the daemon's health feed and call `on_change` for every
+/// subsystem state it pushes (the current ones first, then each change).
+/// Returns when the daemon closes the connection.
+pub async fn subscribe(on_change: impl FnMut(Presence)) -> Result<(), SubscribeError> {
+    subscribe_at(&socket_path(), on_change).await
+}
+
+/// [`subscribe`] on the socket at `path`.
+async fn subscribe_at(
+    path: &std::path::Path,
+    mut on_change: impl FnMut(Presence),
+) -> Result<(), SubscribeError> {
+    #[cfg(unix)]
+    {
+        use tokio::net::UnixStream;
+
+        let stream = UnixStream::connect(path).await.map_err(|e| {
+            SubscribeError::Failed(format!(
+                "connect {}: {e}\n  is the daemon running?",
+                path.display()
+            ))
+        })?;
+
+        let (reader, mut writer) = stream.into_split();
+
+        let mut request = serde_json::to_string(&Command::Subscribe)
+            .map_err(|e| SubscribeError::Failed(format!("serialise: {e}")))?;
+        request.push('\n');
+        writer
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| SubscribeError::Failed(format!("send: {e}")))?;
+
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| SubscribeError::Failed(format!("recv: {e}")))?
+        {
+            let resp: WireResponse = serde_json::from_str(&line)
+                .map_err(|e| SubscribeError::Failed(format!("parse: {e}\n  raw: {line}")))?;
+            match resp {
+                WireResponse::Ok(ControlResponse::HealthChanged {
+                    id,
+                    healthy,
+                    ready,
+                    message,
+                }) => on_change(Presence {
+                    id,
+                    healthy,
+                    ready,
+                    message,
+                }),
+                WireResponse::Ok(_) => {}
+                err @ WireResponse::Err(_) => {
+                    return Err(SubscribeError::Refused(format_response(err)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (path, &mut on_change);
+        Err(SubscribeError::Failed(
+            "IPC requires Unix (Unix domain sockets not available on this platform)".to_string(),
+        ))
+    }
+}
+
 fn format_response(resp: WireResponse) -> String {
     match resp {
         WireResponse::Ok(r) => match r {
@@ -131,6 +238,20 @@ fn format_response(resp: WireResponse) -> String {
                 format!("ok  subsystems: {}", handlers.join(", "))
             }
             ControlResponse::Ack { message } => format!("ok  {message}"),
+            ControlResponse::HealthChanged {
+                id,
+                healthy,
+                message,
+                ..
+            } => {
+                let state = if healthy { "ok" } else { "err" };
+                format!("{state}  {id}: {message}")
+            }
+            ControlResponse::Hello {
+                protocol_version,
+                bot_id,
+                ..
+            } => format!("ok  {bot_id} protocol v{protocol_version}"),
         },
         WireResponse::Err(e) => match e {
             ControlError::NotImplemented { message } => format!("err  not implemented: {message}"),
@@ -138,3 +259,70 @@ fn format_response(resp: WireResponse) -> String {
         },
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    /// Serve one `Subscribe` connection on a fresh socket with `lines`,
+    /// then close it; returns what `subscribe_at` delivered and returned.
+    fn subscribe_to(lines: &'static [&'static str]) -> (Vec<Presence>, Result<(), SubscribeError>) {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let path = std::env::temp_dir().join(format!(
+                "beacon-ipc-{}-{}.sock",
+                std::process::id(),
+                lines.len()
+            ));
+            let _ = std::fs::remove_file(&path);
+            let listener = UnixListener::bind(&path).unwrap();
+            let daemon = tokio::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let (reader, mut writer) = stream.into_split();
+                let request = BufReader::new(reader).lines().next_line().await.unwrap();
+                assert_eq!(request.as_deref(), Some("\"Subscribe\""));
+                for line in lines {
+                    writer
+                        .write_all(format!("{line}\n").as_bytes())
+                        .await
+                        .unwrap();
+                }
+            });
+
+            let mut seen = Vec::new();
+            let ended = subscribe_at(&path, |p| seen.push(p)).await;
+            daemon.await.unwrap();
+            let _ = std::fs::remove_file(&path);
+            (seen, ended)
+        })
+    }
+
+    #[test]
+    fn subscription_forwards_pushed_health() {
+        let (seen, ended) = subscribe_to(&[
+            r#"{"ok":{"Ack":{"message":"subscribed"}}}"#,
+            r#"{"ok":{"HealthChanged":{"id":"llm","healthy":true,"ready":true,"message":"ok"}}}"#,
+            r#"{"ok":{"HealthChanged":{"id":"llm","healthy":false,"ready":false,"message":"provider unreachable"}}}"#,
+        ]);
+        assert!(ended.is_ok(), "{ended:?}");
+        let states: Vec<_> = seen.iter().map(|p| (p.id.as_str(), p.healthy)).collect();
+        assert_eq!(states, [("llm", true), ("llm", false)]);
+        assert_eq!(seen[1].message, "provider unreachable");
+    }
+
+    #[test]
+    fn disabled_presence_is_a_refusal() {
+        let (seen, ended) = subscribe_to(&[
+            r#"{"err":{"NotImplemented":{"message":"presence events are disabled ([supervisor] presence_events)"}}}"#,
+        ]);
+        assert!(seen.is_empty());
+        assert!(
+            matches!(ended, Err(SubscribeError::Refused(_))),
+            "{ended:?}"
+        );
+    }
+}
@@ -6,14 +6,18 @@
 //!
 //! Architecture:
 //!   Main thread:    winit event loop + vello/wgpu rendering
-//!   Tokio thread:   IPC socket client
+//!   Tokio thread:   IPC socket client; holds a `Subscribe` connection so
+//!                   the status dot follows daemon health without polling
 //!   Bridge:         EventLoopProxy<UiMessage>
 
+mod child;
 mod ipc;
 mod scene;
 
+use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
+use std::time::Duration;
 
 use pollster::FutureExt as _;
 use vello::peniko::Color;
@@ -30,8 +34,15 @@ use self::scene::Hit;
 #[allow(dead_code)]
 enum UiMessage {
     IpcResult(String),
+    /// A subsystem's health from the daemon's presence feed.
+    Presence(ipc::Presence),
+    /// The presence feed ended or could not be opened.
+    PresenceLost(String),
 }
 
+/// Wait before reconnecting a presence feed that ended.
+const PRESENCE_RETRY: Duration = Duration::from_secs(5);
+
 struct RenderState {
     surface: wgpu::Surface<'static>,
     device: wgpu::Device,
@@ -47,6 +58,8 @@ struct BeaconApp {
     proxy: EventLoopProxy<UiMessage>,
 
     status_text: Option<String>,
+    /// Last health pushed per subsystem by the presence feed.
+    presence: BTreeMap<String, ipc::Presence>,
     cursor: (f64, f64),
 
     // hover_over_main: cursor is over the main hex (used for drag / pin logic).
@@ -73,11 +86,13 @@ impl BeaconApp {
             .thread_name("beacon-ipc")
             .build()
             .expect("tokio runtime");
+        rt.spawn(watch_presence(proxy.clone()));
         Self {
             window: None,
             render: None,
             proxy,
             status_text: None,
+            presence: BTreeMap::new(),
             cursor: (0.0, 0.0),
             hover_over_main: false,
             hover_over_any: false,
@@ -130,6 +145,19 @@ impl BeaconApp {
         }
     }
 
+    /// Ask the gpui child to quit, force-kill it after the grace period, and
+    /// reap it.  Idempotent — called from `exiting` and again from `Drop`.
+    fn stop_gpui(&mut self) {
+        let Some(mut c) = self.gpui_child.take() else {
+            return;
+        };
+        let pid = c.id();
+        match child::shutdown(&mut c, child::request_quit, child::quit_timeout()) {
+            Ok(outcome) => eprintln!("[beacon] gpui pid={pid} stopped ({outcome:?})"),
+            Err(e) => eprintln!("[beacon] gpui pid={pid} shutdown: {e}"),
+        }
+    }
+
     fn foreground_by_pid(pid: u32) {
         // TODO: replace with IPC focus command once araliya-gpui exposes one.
         let ok = std::process::Command::new("wmctrl")
@@ -441,16 +469,77 @@ impl ApplicationHandler<UiMessage> for BeaconApp {
         }
     }
 
+    fn exiting(&mut self, _: &ActiveEventLoop) {
+        self.stop_gpui();
+    }
+
     fn user_event(&mut self, _: &ActiveEventLoop, event: UiMessage) {
         match event {
             UiMessage::IpcResult(text) => {
                 self.status_text = Some(text);
                 self.request_redraw();
             }
+            UiMessage::Presence(p) => {
+                self.presence.insert(p.id.clone(), p);
+                self.status_text = Some(presence_text(&self.presence));
+                self.request_redraw();
+            }
+            UiMessage::PresenceLost(reason) => {
+                self.presence.clear();
+                self.status_text = Some(format!("err  {reason}"));
+                self.request_redraw();
+            }
         }
     }
 }
 
+/// Hold a presence subscription to the daemon, forwarding every pushed
+/// health state to the UI; reconnect after [`PRESENCE_RETRY`] when it ends.
+/// A daemon with presence events off refuses it: the beacon then stops
+/// watching and keeps the status the ping reports.
+async fn watch_presence(proxy: EventLoopProxy<UiMessage>) {
+    loop {
+        let forward = proxy.clone();
+        let ended = ipc::subscribe(move |p| {
+            let _ = forward.send_event(UiMessage::Presence(p));
+        })
+        .await;
+        let reason = match ended {
+            Ok(()) => "daemon closed the presence feed".to_string(),
+            Err(ipc::SubscribeError::Refused(reason)) => {
+                eprintln!("[beacon] presence → {reason}; not watching");
+                return;
+            }
+            Err(ipc::SubscribeError::Failed(reason)) => reason,
+        };
+        eprintln!("[beacon] presence → {reason}");
+        if proxy.send_event(UiMessage::PresenceLost(reason)).is_err() {
+            return; // event loop gone
+        }
+        tokio::time::sleep(PRESENCE_RETRY).await;
+    }
+}
+
+/// Status line for the dot: `err` (red) while any subsystem is unhealthy.
+fn presence_text(presence: &BTreeMap<String, ipc::Presence>) -> String {
+    let down: Vec<&str> = presence
+        .values()
+        .filter(|p| !p.healthy)
+        .map(|p| p.id.as_str())
+        .collect();
+    if down.is_empty() {
+        format!("ok  {} subsystems healthy", presence.len())
+    } else {
+        format!("err  unhealthy: {}", down.join(", "))
+    }
+}
+
+impl Drop for BeaconApp {
+    fn drop(&mut self) {
+        self.stop_gpui();
+    }
+}
+
 pub fn run() {
     let event_loop = EventLoop::<UiMessage>::with_user_event()
         .build()
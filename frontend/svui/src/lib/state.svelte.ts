@@ -53,6 +53,8 @@ let lastTiming = $state<LlmTiming | null>(null);
 /** Live elapsed ms while a streaming request is in-flight; null otherwise. */
 let streamElapsedMs = $state<number | null>(null);
 let workingMemoryUpdated = $state<boolean>(false);
+/** Agent steps (`[agents] emit_steps`) of the in-flight request, oldest first. */
+let liveSteps = $state<string[]>([]);
 let debugExpanded = $state<boolean>(false);
 
 let sessionsRequest: Promise<void> | null = null;
@@ -126,6 +128,10 @@ export function getStreamElapsedMs(): number | null {
 	return streamElapsedMs;
 }
 
+export function getLiveSteps(): string[] {
+	return liveSteps;
+}
+
 export function getWorkingMemoryUpdated(): boolean {
 	return workingMemoryUpdated;
 }
@@ -150,6 +156,52 @@ export function setDebugExpanded(open: boolean) {
 
 // ── Actions ─────────────────────────────────────────────────
 
+/// Collect the agent steps of session `id` into `liveSteps` from
+/// GET /api/events?session_id=…, until the returned function is called.
+/// A new session has no id yet, so its first turn shows no trace.
+function watchSteps(id: string | undefined): () => void {
+	liveSteps = [];
+	if (!id || typeof EventSource === 'undefined') return () => {};
+	const source = new EventSource(`${baseUrl}/api/events?session_id=${encodeURIComponent(id)}`);
+	source.onmessage = (e) => {
+		try {
+			const event = JSON.parse(e.data) as { event?: string; step?: string };
+			if (event.event === 'agent_step' && event.step) {
+				liveSteps = [...liveSteps, event.step];
+			}
+		} catch {
+			// Ignore malformed events.
+		}
+	};
+	return () => {
+		source.close();
+		liveSteps = [];
+	};
+}
+
+/// Open while the bot is up: turns its `shutting_down` event
+/// (`[comms] shutdown_message`) into the health status.
+let shutdownSource: EventSource | null = null;
+
+function watchShutdown() {
+	if (shutdownSource || typeof EventSource === 'undefined') return;
+	const source = new EventSource(`${baseUrl}/api/events`);
+	source.onmessage = (e) => {
+		try {
+			const event = JSON.parse(e.data) as { event?: string; message?: string };
+			if (event.event === 'shutting_down') {
+				healthStatus = 'error';
+				healthMessage = event.message || 'Bot is shutting down';
+				source.close();
+				shutdownSource = null;
+			}
+		} catch {
+			// Ignore malformed events.
+		}
+	};
+	shutdownSource = source;
+}
+
 export async function doCheckHealth() {
 	if (!baseUrl) return;
 	healthStatus = 'checking';
@@ -158,6 +210,7 @@ export async function doCheckHealth() {
 		const res = await api.checkHealth(baseUrl);
 		healthStatus = 'ok';
 		healthMessage = res.status || 'ok';
+		watchShutdown();
 	} catch (e: unknown) {
 		healthStatus = 'error';
 		healthMessage = e instanceof Error ? e.message : 'Connection failed';
@@ -175,9 +228,10 @@ export async function doSendMessage(text: string, agentId?: string) {
 	};
 	messages = [...messages, userMsg];
 	isLoading = true;
+	const outgoingSessionId = sessionId && sessionId !== NO_SESSION_ID ? sessionId : undefined;
+	const stopSteps = watchSteps(outgoingSessionId);
 
 	try {
-		const outgoingSessionId = sessionId && sessionId !== NO_SESSION_ID ? sessionId : undefined;
 		const res = await api.sendMessage(baseUrl, text.trim(), outgoingSessionId, undefined, agentId);
 
 		if (res.session_id && res.session_id !== NO_SESSION_ID) {
@@ -213,6 +267,7 @@ export async function doSendMessage(text: string, agentId?: string) {
 		};
 		messages = [...messages, errorMsg];
 	} finally {
+		stopSteps();
 		isLoading = false;
 	}
 }
@@ -266,9 +321,10 @@ export async function doSendMessageStreaming(text: string, agentId?: string) {
 		}
 	};
 
+	const outgoingSessionId = sessionId && sessionId !== NO_SESSION_ID ? sessionId : undefined;
+	const stopSteps = watchSteps(outgoingSessionId);
+
 	try {
-		const outgoingSessionId =
-			sessionId && sessionId !== NO_SESSION_ID ? sessionId : undefined;
 		const streamPayload: Record<string, string | undefined> = {
 			message: text.trim(),
 			session_id: outgoingSessionId
@@ -334,6 +390,8 @@ export async function doSendMessageStreaming(text: string, agentId?: string) {
 						thinkingBuf += payload.delta;
 						updateAssistant({ thinking: thinkingBuf });
 					} else if (eventName === 'content' && typeof payload.delta === 'string') {
+						// The answer has started; the trace is done.
+						stopSteps();
 						contentBuf += payload.delta;
 						updateAssistant({ content: contentBuf || '…' });
 					} else if (eventName === 'done') {
@@ -373,6 +431,7 @@ export async function doSendMessageStreaming(text: string, agentId?: string) {
 			content: e instanceof Error ? e.message : 'Stream failed'
 		});
 	} finally {
+		stopSteps();
 		isLoading = false;
 	}
 }